@@ -101,7 +101,7 @@ fixturator!(
     ThirtyTwoBytes;
     curve Empty [0; 32];
     curve Unpredictable {
-        rand::thread_rng().gen::<[u8; 32]>()
+        crate::rng().gen::<[u8; 32]>()
     };
     curve Predictable {
         let mut u8_fixturator = U8Fixturator::new_indexed(Predictable, get_fixt_index!());
@@ -124,7 +124,7 @@ fixturator!(
     SixtyFourBytes;
     curve Empty [0; 64];
     curve Unpredictable {
-        let bytes: Vec<u8> = (0..64).map(|_| rand::random::<u8>()).collect();
+        let bytes: Vec<u8> = (0..64).map(|_| crate::rng().gen::<u8>()).collect();
         let mut ret = [0; 64];
         ret.copy_from_slice(&bytes);
         ret
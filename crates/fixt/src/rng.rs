@@ -4,36 +4,85 @@
 //! be able to re-run the test with the same values. This module provides a RNG
 //! whose seed will be set automatically and printed to stdout before each test run.
 //! To use a previous seed, just set the FIXT_SEED environment variable to the value
-//! of a previous run'
+//! of a previous run.
+//!
+//! Each thread gets its own RNG, seeded from FIXT_SEED and the name of the thread.
+//! The test harness runs each test on a thread named after the test, so a test
+//! gets the same values for the same seed no matter which other tests run
+//! alongside it. Threads sharing a name, such as tokio workers, are told apart
+//! by the order they first use the RNG in, so values generated on several
+//! runtime threads may still differ between runs.
 
 use parking_lot::Mutex;
 use rand::rngs::StdRng;
 use rand::RngCore;
 use rand::SeedableRng;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 lazy_static::lazy_static! {
-    /// The singleton global RNG for test randomness
-    static ref FIXT_RNG: FixtRng = {
-        let seed: u64 = match std::env::var("FIXT_SEED") {
+    /// The seed that all thread RNGs are derived from
+    static ref FIXT_SEED: u64 = {
+        match std::env::var("FIXT_SEED") {
             Ok(seed_str) => {
                 seed_str.parse().expect("Expected integer for FIXT_SEED")
             }
             Err(std::env::VarError::NotPresent) => { rand::random() },
             Err(std::env::VarError::NotUnicode(v)) => { panic!("Invalid FIXT_SEED value: {:?}", v) },
-        };
-        println!("Fixturator seed: {}", seed);
-        FixtRng(Arc::new(
-            Mutex::new(StdRng::seed_from_u64(seed))
-        ))
+        }
     };
+
+    /// How many threads with each name have created an RNG so far
+    static ref THREAD_NAMES: Mutex<HashMap<String, u64>> = Mutex::new(HashMap::new());
+}
+
+thread_local! {
+    /// The RNG for test randomness on this thread
+    static FIXT_RNG: FixtRng = FixtRng::for_current_thread();
 }
 
 /// A seedable RNG which uses an Arc and a Mutex to allow easy cloneability and thread safety.
-/// A singleton global instance is created in this module. See module-level docs for more info.
+/// An instance is created for each thread in this module. See module-level docs for more info.
 #[derive(Clone)]
 pub struct FixtRng(Arc<Mutex<StdRng>>);
 
+impl FixtRng {
+    fn for_current_thread() -> Self {
+        let name = std::thread::current()
+            .name()
+            .unwrap_or_default()
+            .to_string();
+        let nth = {
+            let mut names = THREAD_NAMES.lock();
+            let count = names.entry(name.clone()).or_insert(0);
+            *count += 1;
+            *count - 1
+        };
+        // Printed on every thread so the seed is in the captured output of any failing test.
+        println!("Fixturator seed: {}", seed());
+        FixtRng(Arc::new(Mutex::new(StdRng::seed_from_u64(thread_seed(
+            seed(),
+            &name,
+            nth,
+        )))))
+    }
+}
+
+/// Derive the seed for the `nth` thread with a given name.
+/// Uses blake2b so a seed gives the same values with any build of the crate.
+fn thread_seed(seed: u64, name: &str, nth: u64) -> u64 {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(8)
+        .to_state()
+        .update(&seed.to_le_bytes())
+        .update(&nth.to_le_bytes())
+        .update(name.as_bytes())
+        .finalize();
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(hash.as_bytes());
+    u64::from_le_bytes(bytes)
+}
+
 impl RngCore for FixtRng {
     fn next_u32(&mut self) -> u32 {
         self.0.lock().next_u32()
@@ -52,8 +101,51 @@ impl RngCore for FixtRng {
     }
 }
 
-/// Access the seeded random number generator. This should be used in all places where
-/// tests produce random values.
+/// Access the seeded random number generator for this thread. This should be used in all
+/// places where tests produce random values.
 pub fn rng() -> FixtRng {
-    FIXT_RNG.clone()
+    FIXT_RNG.with(|rng| rng.clone())
+}
+
+/// The seed used for this test run, to be passed as FIXT_SEED to reproduce it.
+pub fn seed() -> u64 {
+    *FIXT_SEED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_rng_is_derived_from_seed_and_thread_name() {
+        let name = "fixt_rng_test";
+        let value = || {
+            std::thread::Builder::new()
+                .name(name.to_string())
+                .spawn(|| rng().next_u64())
+                .unwrap()
+                .join()
+                .unwrap()
+        };
+        let first = value();
+        let second = value();
+        assert_eq!(
+            first,
+            StdRng::seed_from_u64(thread_seed(seed(), name, 0)).next_u64()
+        );
+        assert_eq!(
+            second,
+            StdRng::seed_from_u64(thread_seed(seed(), name, 1)).next_u64()
+        );
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn thread_seed_is_stable() {
+        // Pinned so a change to the derivation is noticed.
+        assert_eq!(thread_seed(0, "main", 0), 11981391197917037429);
+        assert_ne!(thread_seed(0, "main", 0), thread_seed(1, "main", 0));
+        assert_ne!(thread_seed(0, "main", 0), thread_seed(0, "main", 1));
+        assert_ne!(thread_seed(0, "main", 0), thread_seed(0, "other", 0));
+    }
 }
@@ -158,6 +158,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash.clone(),
                     dependencies: vec![],
+                    init_after: Default::default(),
                 })
                 .into(),
             ),
@@ -166,6 +167,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash.clone(),
                     dependencies: vec![],
+                    init_after: Default::default(),
                 })
                 .into(),
             ),
@@ -176,6 +178,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash2.clone(),
                     dependencies: vec!["zome1".into()],
+                    init_after: Default::default(),
                 })
                 .into(),
             ),
@@ -184,6 +187,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash2.clone(),
                     dependencies: vec!["zome1".into(), "zome2".into()],
+                    init_after: Default::default(),
                 })
                 .into(),
             ),
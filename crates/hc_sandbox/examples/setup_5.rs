@@ -55,6 +55,7 @@ async fn main() -> anyhow::Result<()> {
             source: AppBundleSource::Bundle(bundle),
             membrane_proofs: Default::default(),
             uid: None,
+            role_overrides: Default::default(),
         };
 
         let r = AdminRequest::InstallAppBundle(Box::new(payload));
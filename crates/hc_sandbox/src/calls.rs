@@ -13,6 +13,7 @@ use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::ConductorStateDump;
 use holochain_conductor_api::InterfaceDriver;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
@@ -28,6 +29,7 @@ use std::convert::TryFrom;
 
 use crate::cmds::Existing;
 use crate::expect_match;
+use crate::ports::get_admin_auth_token;
 use crate::ports::get_admin_ports;
 use crate::run::run_async;
 use crate::CmdRunner;
@@ -41,6 +43,10 @@ pub struct Call {
     /// If this is empty existing sandboxes will be used.
     /// Cannot be combined with existing sandboxes.
     pub running: Vec<u16>,
+    #[structopt(long, requires = "running")]
+    /// The auth token of the admin interfaces of the running conductors.
+    /// The tokens of existing sandboxes are read from their config.
+    pub auth_token: Option<String>,
     #[structopt(flatten)]
     pub existing: Existing,
     #[structopt(subcommand)]
@@ -75,6 +81,8 @@ pub enum AdminRequestCli {
     EnableApp(EnableApp),
     DisableApp(DisableApp),
     DumpState(DumpState),
+    /// Calls AdminRequest::DumpConductorState.
+    DumpConductorState,
     /// Calls AdminRequest::AddAgentInfo.
     /// _Unimplemented_.
     AddAgents,
@@ -87,6 +95,9 @@ pub struct AddAdminWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    #[structopt(long)]
+    /// Optional token which clients must present to connect.
+    pub auth_token: Option<String>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -229,48 +240,86 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
     let Call {
         existing,
         running,
+        auth_token,
         call,
     } = req;
+    let cmds = connect(holochain_path, running, auth_token, existing).await?;
+    for mut cmd in cmds {
+        call_inner(&mut cmd.0, call.clone()).await?;
+    }
+    Ok(())
+}
+
+/// Connect to the admin interfaces of the running conductors on these ports,
+/// or of these existing sandboxes, starting any that aren't already running.
+/// The auth token is only used for the running conductors.
+pub(crate) async fn connect(
+    holochain_path: &Path,
+    running: Vec<u16>,
+    auth_token: Option<String>,
+    existing: Existing,
+) -> anyhow::Result<Vec<(CmdRunner, Option<tokio::process::Child>)>> {
     let cmds = if running.is_empty() {
-        let paths = if existing.is_empty() {
-            crate::save::load(std::env::current_dir()?)?
-        } else {
-            existing.load()?
-        };
-        let ports = get_admin_ports(paths.clone()).await?;
-        let mut cmds = Vec::with_capacity(ports.len());
-        for (port, path) in ports.into_iter().zip(paths.into_iter()) {
-            match CmdRunner::try_new(port).await {
-                Ok(cmd) => cmds.push((cmd, None)),
-                Err(e) => {
-                    if let holochain_websocket::WebsocketError::Io(e) = &e {
-                        if let std::io::ErrorKind::ConnectionRefused
-                        | std::io::ErrorKind::AddrNotAvailable = e.kind()
-                        {
-                            let (port, holochain) = run_async(holochain_path, path, None).await?;
-                            cmds.push((CmdRunner::new(port).await, Some(holochain)));
-                            continue;
-                        }
-                    }
-                    bail!(
-                        "Failed to connect to running conductor or start one {:?}",
-                        e
-                    )
-                }
-            }
-        }
-        cmds
+        connect_sandboxes(holochain_path, existing)
+            .await?
+            .into_iter()
+            .map(|(cmd, holochain, _)| (cmd, holochain))
+            .collect()
     } else {
         let mut cmds = Vec::with_capacity(running.len());
         for port in running {
-            cmds.push((CmdRunner::new(port).await, None));
+            let cmd = CmdRunner::try_new_with_auth_token(port, auth_token.as_deref())
+                .await
+                .map_err(|e| anyhow!("Failed to connect to admin port {}: {:?}", port, e))?;
+            cmds.push((cmd, None));
         }
         cmds
     };
-    for mut cmd in cmds {
-        call_inner(&mut cmd.0, call.clone()).await?;
+    Ok(cmds)
+}
+
+/// Connect to the admin interfaces of these existing sandboxes,
+/// or of all saved sandboxes if none are given, starting any that
+/// aren't already running. Each connection comes with the path of its sandbox.
+pub(crate) async fn connect_sandboxes(
+    holochain_path: &Path,
+    existing: Existing,
+) -> anyhow::Result<Vec<(CmdRunner, Option<tokio::process::Child>, PathBuf)>> {
+    let paths = if existing.is_empty() {
+        crate::save::load(std::env::current_dir()?)?
+    } else {
+        existing.load()?
+    };
+    let ports = get_admin_ports(paths.clone()).await?;
+    let mut cmds = Vec::with_capacity(ports.len());
+    for (port, path) in ports.into_iter().zip(paths.into_iter()) {
+        let auth_token = get_admin_auth_token(path.clone())?;
+        match CmdRunner::try_new_with_auth_token(port, auth_token.as_deref()).await {
+            Ok(cmd) => cmds.push((cmd, None, path)),
+            Err(e) => {
+                if let holochain_websocket::WebsocketError::Io(e) = &e {
+                    if let std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::AddrNotAvailable = e.kind()
+                    {
+                        let (port, holochain) =
+                            run_async(holochain_path, path.clone(), None).await?;
+                        let cmd = CmdRunner::try_new_with_auth_token(port, auth_token.as_deref())
+                            .await
+                            .map_err(|e| {
+                                anyhow!("Failed to connect to admin port {}: {:?}", port, e)
+                            })?;
+                        cmds.push((cmd, Some(holochain), path));
+                        continue;
+                    }
+                }
+                bail!(
+                    "Failed to connect to running conductor or start one {:?}",
+                    e
+                )
+            }
+        }
     }
-    Ok(())
+    Ok(cmds)
 }
 
 async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Result<()> {
@@ -339,6 +388,10 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let state = dump_state(cmd, args).await?;
             msg!("DUMP STATE \n{}", state);
         }
+        AdminRequestCli::DumpConductorState => {
+            let state = dump_conductor_state(cmd).await?;
+            msg!("DUMP CONDUCTOR STATE \n{}", serde_yaml::to_string(&state)?);
+        }
         AdminRequestCli::AddAgents => todo!("Adding agent info via cli is coming soon"),
         AdminRequestCli::ListAgents(args) => {
             use std::fmt::Write;
@@ -399,7 +452,10 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     let resp = cmd
         .command(AdminRequest::AddAdminInterfaces(vec![
             AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
+                driver: InterfaceDriver::Websocket {
+                    port,
+                    auth_token: args.auth_token,
+                },
             },
         ]))
         .await?;
@@ -510,6 +566,7 @@ pub async fn install_app_bundle(
         source: AppBundleSource::Path(path),
         membrane_proofs: Default::default(),
         uid,
+        role_overrides: Default::default(),
     };
 
     let r = AdminRequest::InstallAppBundle(Box::new(payload));
@@ -640,6 +697,13 @@ pub async fn dump_state(cmd: &mut CmdRunner, args: DumpState) -> anyhow::Result<
     Ok(expect_match!(resp => AdminResponse::StateDumped, "Failed to dump state"))
 }
 
+/// Calls [`AdminRequest::DumpConductorState`] and dumps an overview of the conductor.
+pub async fn dump_conductor_state(cmd: &mut CmdRunner) -> anyhow::Result<ConductorStateDump> {
+    let resp = cmd.command(AdminRequest::DumpConductorState).await?;
+    let dump = expect_match!(resp => AdminResponse::ConductorStateDumped, "Failed to dump conductor state");
+    Ok(*dump)
+}
+
 /// Calls [`AdminRequest::AddAgentInfo`] with and adds the list of agent info.
 pub async fn add_agent_info(cmd: &mut CmdRunner, args: Vec<AgentInfoSigned>) -> anyhow::Result<()> {
     let resp = cmd
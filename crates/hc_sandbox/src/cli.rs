@@ -61,6 +61,16 @@ pub enum HcSandboxSubcommand {
 
         /// A hApp bundle to install.
         happ: Option<PathBuf>,
+
+        /// Generate the sandboxes described in a topology YAML file
+        /// instead of copies of a single sandbox.
+        /// The number of sandboxes, network and app id options are ignored.
+        ///
+        /// A manifest of the created sandboxes, their ports and apps
+        /// is written to the `manifest` path given in the topology.
+        /// See the docs of the `topology` module for the file format.
+        #[structopt(long, conflicts_with = "happ")]
+        topology: Option<PathBuf>,
     },
     /// Run conductor(s) from existing sandbox(es).
     Run(Run),
@@ -68,6 +78,16 @@ pub enum HcSandboxSubcommand {
     /// Make a call to a conductor's admin interface.
     Call(crate::calls::Call),
 
+    /// Call a zome function on a conductor's app interface
+    /// and print the result as JSON.
+    CallZome(crate::zome_call::CallZome),
+
+    /// Follow the logs of sandbox conductors, merged into one stream.
+    ///
+    /// Shows the logs of all the sandboxes in `$(pwd)/.hc`
+    /// unless some are chosen.
+    Logs(crate::logs::Logs),
+
     /// List sandboxes found in `$(pwd)/.hc`.
     List {
         /// Show more verbose information.
@@ -107,8 +127,18 @@ impl HcSandbox {
                 create,
                 run,
                 happ,
+                topology,
             } => {
-                let paths = generate(&self.holochain_path, happ, create, app_id).await?;
+                let paths = match topology {
+                    Some(topology) => {
+                        let topology = crate::topology::read_topology(&topology)?;
+                        let paths =
+                            crate::topology::generate(&self.holochain_path, topology).await?;
+                        crate::save::save(std::env::current_dir()?, paths.clone())?;
+                        paths
+                    }
+                    None => generate(&self.holochain_path, happ, create, app_id).await?,
+                };
                 for (port, path) in self
                     .force_admin_ports
                     .clone()
@@ -149,6 +179,10 @@ impl HcSandbox {
             HcSandboxSubcommand::Call(call) => {
                 crate::calls::call(&self.holochain_path, call).await?
             }
+            HcSandboxSubcommand::CallZome(call) => {
+                crate::zome_call::call_zome(&self.holochain_path, call).await?
+            }
+            HcSandboxSubcommand::Logs(logs) => crate::logs::logs(logs).await?,
             // HcSandboxSubcommand::Task => todo!("Running custom tasks is coming soon"),
             HcSandboxSubcommand::List { verbose } => {
                 crate::save::list(std::env::current_dir()?, verbose)?
@@ -73,6 +73,14 @@
 //! ```shell
 //! hc sandbox call list-cells
 //! ```
+//! #### Call Zome
+//! Allows calling a zome function on the app api.
+//! The cell is found from the app id and role id, and the
+//! payload and result are JSON.
+//!
+//! ```shell
+//! hc sandbox call-zome --app-id my-app my_zome my_fn '{"value": 1}'
+//! ```
 //! #### List and Clean
 //! These commands allow you to list the persisted sandboxes
 //! in the current directory (from the`.hcXXX`) file.
@@ -114,8 +122,8 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use holochain_conductor_api::{AdminRequest, AdminResponse};
+use holochain_websocket::TypedWebsocketSender;
 use holochain_websocket::WebsocketResult;
-use holochain_websocket::WebsocketSender;
 use ports::get_admin_api;
 
 pub use ports::force_admin_port;
@@ -137,16 +145,19 @@ pub mod cli;
 pub mod cmds;
 pub mod config;
 pub mod generate;
+pub mod logs;
 pub mod run;
 pub mod sandbox;
 pub mod save;
+pub mod topology;
+pub mod zome_call;
 pub use cli::HcSandbox;
 
 mod ports;
 
 /// An active connection to a running conductor.
 pub struct CmdRunner {
-    client: WebsocketSender,
+    client: TypedWebsocketSender<AdminRequest, AdminResponse>,
 }
 
 impl CmdRunner {
@@ -161,8 +172,19 @@ impl CmdRunner {
 
     /// Create a new connection for calling admin interface commands.
     pub async fn try_new(port: u16) -> WebsocketResult<Self> {
-        let client = get_admin_api(port).await?;
-        Ok(Self { client })
+        Self::try_new_with_auth_token(port, None).await
+    }
+
+    /// Create a new connection for calling admin interface commands
+    /// on an interface which requires an auth token.
+    pub async fn try_new_with_auth_token(
+        port: u16,
+        auth_token: Option<&str>,
+    ) -> WebsocketResult<Self> {
+        let client = get_admin_api(port, auth_token).await?;
+        Ok(Self {
+            client: client.into(),
+        })
     }
 
     /// Create a command runner from a sandbox path.
@@ -179,15 +201,15 @@ impl CmdRunner {
         holochain_bin_path: &Path,
         sandbox_path: PathBuf,
     ) -> anyhow::Result<(Self, tokio::process::Child)> {
+        let auth_token = ports::get_admin_auth_token(sandbox_path.clone())?;
         let conductor = run::run_async(holochain_bin_path, sandbox_path, None).await?;
-        let cmd = CmdRunner::try_new(conductor.0).await?;
+        let cmd = CmdRunner::try_new_with_auth_token(conductor.0, auth_token.as_deref()).await?;
         Ok((cmd, conductor.1))
     }
 
     /// Make an Admin request to this conductor.
     pub async fn command(&mut self, cmd: AdminRequest) -> anyhow::Result<AdminResponse> {
-        let response: Result<AdminResponse, _> = self.client.request(cmd).await;
-        Ok(response?)
+        Ok(self.client.request(cmd).await?)
     }
 }
 
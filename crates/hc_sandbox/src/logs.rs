@@ -0,0 +1,245 @@
+//! Follow the logs of several sandboxes at once.
+//!
+//! Conductors run by the sandbox append their output to a log file in their
+//! sandbox directory (see [`LOG_FILE`]). This module follows those files and
+//! prints new lines from all of them in one stream, each prefixed with
+//! the sandbox it came from.
+//!
+//! Lines can be filtered by level, target and cell. Lines logged as json
+//! (`--structured Json`) are filtered on their `level` and `target` fields,
+//! other lines are matched on their text.
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ansi_term::Color;
+use structopt::StructOpt;
+use tracing::Level;
+
+use crate::cmds::Existing;
+use crate::run::LOG_FILE;
+
+/// How often the log files are checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct Logs {
+    #[structopt(flatten)]
+    pub existing: Existing,
+    #[structopt(short = "L", long)]
+    /// Only show lines at this level or more severe,
+    /// e.g. `warn` shows warnings and errors.
+    pub level: Option<Level>,
+    #[structopt(short = "t", long)]
+    /// Only show lines from targets starting with one of these,
+    /// e.g. `-t holochain::core -t kitsune_p2p`.
+    pub target: Vec<String>,
+    #[structopt(short = "c", long)]
+    /// Only show lines that mention this cell, dna or agent.
+    /// Any part of the hash can be given.
+    pub cell: Option<String>,
+    #[structopt(long)]
+    /// Show the existing logs before following new lines.
+    pub from_start: bool,
+}
+
+/// Which lines to show.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// The least severe level to show.
+    pub level: Option<Level>,
+    /// Target prefixes to show.
+    pub target: Vec<String>,
+    /// Text that must appear in the line.
+    pub cell: Option<String>,
+}
+
+impl LogFilter {
+    /// Check if a line passes this filter.
+    /// Lines without a level or target,
+    /// like the output of a panic, pass those checks.
+    pub fn matches(&self, line: &str) -> bool {
+        let json = serde_json::from_str::<serde_json::Value>(line).ok();
+        let field = |name: &str| {
+            json.as_ref()
+                .and_then(|j| j.get(name))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+        if let Some(level) = self.level {
+            let line_level = match field("level") {
+                Some(l) => l.parse().ok(),
+                None => text_level(line),
+            };
+            if let Some(line_level) = line_level {
+                // More verbose levels compare as greater.
+                if line_level > level {
+                    return false;
+                }
+            }
+        }
+        if !self.target.is_empty() {
+            let matches_target = match field("target") {
+                Some(target) => self.target.iter().any(|t| target.starts_with(t.as_str())),
+                None => self.target.iter().any(|t| line.contains(t.as_str())),
+            };
+            if !matches_target {
+                return false;
+            }
+        }
+        if let Some(cell) = &self.cell {
+            if !line.contains(cell.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Find the level of a line in the human readable log format.
+fn text_level(line: &str) -> Option<Level> {
+    line.split_whitespace()
+        .take(4)
+        .find_map(|word| strip_ansi(word).parse().ok())
+}
+
+fn strip_ansi(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip to the end of the escape sequence.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[doc(hidden)]
+pub async fn logs(args: Logs) -> anyhow::Result<()> {
+    let Logs {
+        existing,
+        level,
+        target,
+        cell,
+        from_start,
+    } = args;
+    let paths = if existing.is_empty() {
+        crate::save::load(std::env::current_dir()?)?
+    } else {
+        existing.load()?
+    };
+    if paths.is_empty() {
+        msg!("There are no sandboxes to show the logs of");
+        return Ok(());
+    }
+    let filter = LogFilter {
+        level,
+        target,
+        cell,
+    };
+    follow(paths, filter, from_start).await
+}
+
+/// Print the lines that match the filter from the log files of these sandboxes
+/// as they are written. Runs until it is stopped.
+pub async fn follow(
+    paths: Vec<PathBuf>,
+    filter: LogFilter,
+    from_start: bool,
+) -> anyhow::Result<()> {
+    let mut logs: Vec<_> = paths
+        .into_iter()
+        .enumerate()
+        .map(|(i, path)| FollowedLog::new(i, path, from_start))
+        .collect();
+    loop {
+        for log in logs.iter_mut() {
+            for line in log.read_lines()? {
+                if filter.matches(&line) {
+                    println!("{} {}", log.prefix, line);
+                }
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+struct FollowedLog {
+    prefix: String,
+    path: PathBuf,
+    from_start: bool,
+    reader: Option<BufReader<std::fs::File>>,
+    partial: String,
+}
+
+impl FollowedLog {
+    fn new(index: usize, sandbox: PathBuf, from_start: bool) -> Self {
+        let name = sandbox
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| sandbox.display().to_string());
+        // Skip the colors that are hard to read on a dark or light background.
+        let color = Color::Fixed(1 + (index % 14) as u8);
+        Self {
+            prefix: color
+                .bold()
+                .paint(format!("[{}:{}]", index, name))
+                .to_string(),
+            path: sandbox.join(LOG_FILE),
+            from_start,
+            reader: None,
+            partial: String::new(),
+        }
+    }
+
+    /// Read the complete lines written since the last read.
+    fn read_lines(&mut self) -> anyhow::Result<Vec<String>> {
+        if self.reader.is_none() {
+            match std::fs::File::open(&self.path) {
+                Ok(mut file) => {
+                    if !self.from_start {
+                        file.seek(SeekFrom::End(0))?;
+                    }
+                    self.reader = Some(BufReader::new(file));
+                }
+                // The conductor hasn't been run yet,
+                // so everything it logs is new.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    self.from_start = true;
+                    return Ok(Vec::new());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let reader = self.reader.as_mut().expect("Opened above");
+        let mut lines = Vec::new();
+        while reader.read_line(&mut self.partial)? > 0 {
+            if self.partial.ends_with('\n') {
+                lines.push(self.partial.trim_end().to_string());
+                self.partial.clear();
+            }
+        }
+        // The log file was rotated if the file at the path is now shorter
+        // than what has been read, so read the new one from its start.
+        let read_to = reader.stream_position()?;
+        if matches!(std::fs::metadata(&self.path), Ok(m) if m.len() < read_to) {
+            self.reader = None;
+            self.from_start = true;
+            if !self.partial.is_empty() {
+                lines.push(std::mem::take(&mut self.partial));
+            }
+        }
+        Ok(lines)
+    }
+}
@@ -32,7 +32,7 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
         if let Some(config) = read_config(p)? {
             if let Some(ai) = config.admin_interfaces {
                 if let Some(AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port },
+                    driver: InterfaceDriver::Websocket { port, .. },
                 }) = ai.get(0)
                 {
                     ports.push(*port)
@@ -43,25 +43,41 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
     Ok(ports)
 }
 
-pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender> {
+/// The auth token of the first admin interface of a sandbox, if it has one.
+pub(crate) fn get_admin_auth_token(path: PathBuf) -> anyhow::Result<Option<String>> {
+    Ok(read_config(path)?
+        .and_then(|config| config.admin_interfaces)
+        .and_then(|ai| ai.into_iter().next())
+        .and_then(|ai| ai.driver.auth_token().map(String::from)))
+}
+
+pub(crate) async fn get_admin_api(
+    port: u16,
+    auth_token: Option<&str>,
+) -> WebsocketResult<WebsocketSender> {
     tracing::debug!(port);
-    websocket_client_by_port(port).await.map(|p| p.0)
+    websocket_client_by_port(port, auth_token)
+        .await
+        .map(|p| p.0)
 }
 
-async fn websocket_client_by_port(
+/// Connect to an interface, presenting the auth token
+/// as the `token` query parameter if there is one.
+pub(crate) async fn websocket_client_by_port(
     port: u16,
+    auth_token: Option<&str>,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
-    ws::connect(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await
+    let mut url = url2!("ws://127.0.0.1:{}", port);
+    if let Some(auth_token) = auth_token {
+        url.query_pairs_mut().append_pair("token", auth_token);
+    }
+    ws::connect(url, Arc::new(WebsocketConfig::default())).await
 }
 
 pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
     match config.admin_interfaces.as_mut().and_then(|i| i.first_mut()) {
         Some(AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::Websocket { port, .. },
         }) => {
             if *port != 0 {
                 *port = 0;
@@ -70,26 +86,35 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
         None => {
             let port = 0;
             config.admin_interfaces = Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
+                driver: InterfaceDriver::Websocket {
+                    port,
+                    auth_token: None,
+                },
             }]);
         }
     }
 }
 
 pub(crate) fn set_admin_port(config: &mut ConductorConfig, port: u16) {
-    let p = port;
-    let port = AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port },
-    };
     match config
         .admin_interfaces
         .as_mut()
         .and_then(|ai| ai.get_mut(0))
     {
-        Some(admin_interface) => {
-            *admin_interface = port;
+        // Keep the auth token of the interface.
+        Some(AdminInterfaceConfig {
+            driver: InterfaceDriver::Websocket { port: p, .. },
+        }) => {
+            *p = port;
+        }
+        None => {
+            config.admin_interfaces = Some(vec![AdminInterfaceConfig {
+                driver: InterfaceDriver::Websocket {
+                    port,
+                    auth_token: None,
+                },
+            }])
         }
-        None => config.admin_interfaces = Some(vec![port]),
     }
-    msg!("Admin port set to: {}", p);
+    msg!("Admin port set to: {}", port);
 }
@@ -1,19 +1,30 @@
 //! Helpers for running the conductor.
 use std::path::Path;
+use std::sync::Arc;
 use std::{path::PathBuf, process::Stdio};
 
 use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::process::{Child, Command};
 use tokio::sync::oneshot;
+use tokio::sync::Mutex;
 
 use crate::calls::attach_app_interface;
 use crate::calls::AddAppWs;
 use crate::config::*;
+use crate::ports::get_admin_auth_token;
 use crate::ports::random_admin_port;
 use crate::ports::set_admin_port;
 use crate::CmdRunner;
 
+/// The file in each sandbox directory that the output of its conductor is appended to.
+pub const LOG_FILE: &str = "holochain.log";
+
+/// The size [`LOG_FILE`] may grow to before it is moved to `holochain.log.1`,
+/// replacing the one before, and a new log file is started.
+pub const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+
 /// Run a conductor and wait for it to finish.
 /// Use [`run_async`] to run in the background.
 /// Requires the holochain binary is available
@@ -32,9 +43,10 @@ pub async fn run(
     let (port, mut holochain) =
         run_async(holochain_path, sandbox_path.clone(), force_admin_port).await?;
     msg!("Running conductor on admin port {}", port);
+    let auth_token = get_admin_auth_token(sandbox_path.clone())?;
     for app_port in app_ports {
         msg!("Attaching app port {}", app_port);
-        let mut cmd = CmdRunner::try_new(port).await?;
+        let mut cmd = CmdRunner::try_new_with_auth_token(port, auth_token.as_deref()).await?;
         let port = attach_app_interface(
             &mut cmd,
             AddAppWs {
@@ -76,7 +88,8 @@ pub async fn run_async(
     }
     let config_path = write_config(sandbox_path.clone(), &config);
     let (tx_config, rx_config) = oneshot::channel();
-    let mut child = start_holochain(holochain_path, config_path, tx_config).await;
+    let log_file = SandboxLog::open(sandbox_path.join(LOG_FILE))?;
+    let mut child = start_holochain(holochain_path, config_path, tx_config, log_file).await;
     check_started(&mut child).await;
     let port = rx_config
         .await
@@ -88,6 +101,7 @@ async fn start_holochain(
     holochain_path: &Path,
     config_path: PathBuf,
     tx_config: oneshot::Sender<u16>,
+    log_file: SandboxLog,
 ) -> Child {
     tracing::info!("\n\n----\nstarting holochain\n----\n\n");
     let mut cmd = Command::new(holochain_path);
@@ -100,8 +114,7 @@ async fn start_holochain(
         .kill_on_drop(true);
 
     let mut holochain = cmd.spawn().expect("Failed to spawn holochain");
-    // TODO: Allow redirecting output per conductor.
-    spawn_output(&mut holochain, tx_config);
+    spawn_output(&mut holochain, tx_config, log_file);
     holochain
 }
 
@@ -114,9 +127,12 @@ async fn check_started(holochain: &mut Child) {
     }
 }
 
-fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>) {
+/// Print the output of the conductor and also append it to the sandbox's log file.
+fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>, log_file: SandboxLog) {
     let stdout = holochain.stdout.take();
     let stderr = holochain.stderr.take();
+    let stdout_log = Arc::new(Mutex::new(log_file));
+    let stderr_log = stdout_log.clone();
     tokio::task::spawn(async move {
         let mut needs_setup = true;
         let mut config = Some(config);
@@ -137,6 +153,7 @@ fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>) {
                         (false, _) => (),
                     }
                 }
+                stdout_log.lock().await.write_line(&line).await;
                 println!("{}", line);
             }
         }
@@ -145,12 +162,72 @@ fn spawn_output(holochain: &mut Child, config: oneshot::Sender<u16>) {
         if let Some(stderr) = stderr {
             let mut reader = BufReader::new(stderr).lines();
             while let Ok(Some(line)) = reader.next_line().await {
+                stderr_log.lock().await.write_line(&line).await;
                 eprintln!("{}", line);
             }
         }
     });
 }
 
+/// The log file of a sandbox, which is rotated once it reaches [`MAX_LOG_SIZE`].
+struct SandboxLog {
+    path: PathBuf,
+    /// Set to none if a write fails, so the output is still printed.
+    file: Option<tokio::fs::File>,
+    size: u64,
+}
+
+impl SandboxLog {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file: Some(tokio::fs::File::from_std(file)),
+            size,
+        })
+    }
+
+    /// Append a line to the log file, rotating it first if the line
+    /// would take it over [`MAX_LOG_SIZE`].
+    async fn write_line(&mut self, line: &str) {
+        let line = format!("{}\n", line);
+        if self.file.is_some()
+            && self.size > 0
+            && self.size + line.len() as u64 > MAX_LOG_SIZE
+            && self.rotate().await.is_err()
+        {
+            self.file = None;
+        }
+        if let Some(file) = &mut self.file {
+            match file.write_all(line.as_bytes()).await {
+                Ok(()) => self.size += line.len() as u64,
+                Err(_) => self.file = None,
+            }
+        }
+    }
+
+    /// Move the log file to `holochain.log.1` and start a new one.
+    async fn rotate(&mut self) -> std::io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+        }
+        tokio::fs::rename(&self.path, self.path.with_extension("log.1")).await?;
+        self.file = Some(
+            tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?,
+        );
+        self.size = 0;
+        Ok(())
+    }
+}
+
 fn check_sandbox(line: &str, needs_setup: &mut bool) -> (bool, Option<u16>) {
     if let Some(line) = line.strip_prefix("###") {
         if let Some(line) = line.strip_suffix("###") {
@@ -0,0 +1,242 @@
+//! Generate a set of sandboxes from a topology file.
+//!
+//! A topology describes every conductor of a test network in one YAML file:
+//! which hApps each one installs, the network settings they share
+//! and whether they should start out knowing about each other.
+//!
+//! ```yaml
+//! network:
+//!   bootstrap_service: https://bootstrap-staging.holo.host
+//!   transport_pool:
+//!     - type: quic
+//!   network_type: quic_bootstrap
+//! exchange_peer_info: true
+//! manifest: network.manifest.yaml
+//! conductors:
+//!   - directory: alice
+//!     admin_port: 9000
+//!     app_ports: [8888]
+//!     happs:
+//!       - path: ./my-app.happ
+//!         app_id: my-app
+//!   - directory: bob
+//!     happs:
+//!       - path: ./my-app.happ
+//!         app_id: my-app
+//! ```
+//!
+//! After the sandboxes are created a manifest is written
+//! listing the directory, ports and installed apps of each conductor.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::ensure;
+use holochain_p2p::kitsune_p2p::KitsuneP2pConfig;
+use holochain_types::prelude::AppRoleId;
+use holochain_types::prelude::InstalledAppId;
+use holochain_types::prelude::Uid;
+
+use crate::calls::add_agent_info;
+use crate::calls::attach_app_interface;
+use crate::calls::install_app_bundle;
+use crate::calls::request_agent_info;
+use crate::calls::AddAppWs;
+use crate::calls::InstallAppBundle;
+use crate::calls::ListAgents;
+use crate::run::run_async;
+use crate::CmdRunner;
+
+/// A description of a set of conductor sandboxes.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Topology {
+    /// The network config shared by all the conductors.
+    /// This has the same format as `network` in a conductor config.
+    #[serde(default)]
+    pub network: Option<KitsuneP2pConfig>,
+    /// The directory to create the sandboxes in.
+    /// Defaults to the system's temp directory.
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+    /// Add the agent info of every conductor to every other conductor,
+    /// so they can reach each other without a bootstrap service.
+    #[serde(default)]
+    pub exchange_peer_info: bool,
+    /// Where to write the manifest of the created sandboxes.
+    #[serde(default = "default_manifest")]
+    pub manifest: PathBuf,
+    /// The conductors to create.
+    pub conductors: Vec<TopologyConductor>,
+}
+
+/// A single conductor in a [`Topology`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TopologyConductor {
+    /// The directory name of the sandbox.
+    /// Defaults to a random name.
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// The admin port of the conductor.
+    /// Defaults to a port chosen each time the conductor is run.
+    #[serde(default)]
+    pub admin_port: Option<u16>,
+    /// App interfaces to attach to the conductor.
+    #[serde(default)]
+    pub app_ports: Vec<u16>,
+    /// The hApps to install on the conductor.
+    #[serde(default)]
+    pub happs: Vec<TopologyHapp>,
+}
+
+/// A hApp to install on a [`TopologyConductor`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TopologyHapp {
+    /// Path to the `.happ` bundle,
+    /// relative to the directory of the topology file.
+    pub path: PathBuf,
+    /// The InstalledAppId of the app.
+    /// Defaults to the name of the app in the bundle.
+    #[serde(default)]
+    pub app_id: Option<InstalledAppId>,
+    /// Optional UID override for every DNA in the app.
+    #[serde(default)]
+    pub uid: Option<Uid>,
+}
+
+/// The sandboxes created from a [`Topology`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    /// The created conductors, in the same order as in the topology.
+    pub conductors: Vec<ManifestConductor>,
+}
+
+/// A conductor created from a [`Topology`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ManifestConductor {
+    /// The sandbox directory.
+    pub path: PathBuf,
+    /// The admin port, if it was set in the topology.
+    pub admin_port: Option<u16>,
+    /// The attached app interface ports.
+    pub app_ports: Vec<u16>,
+    /// The installed apps.
+    pub apps: Vec<ManifestApp>,
+}
+
+/// An app installed from a [`Topology`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ManifestApp {
+    /// The InstalledAppId of the app.
+    pub app_id: InstalledAppId,
+    /// The agent the app was installed for.
+    pub agent: Option<String>,
+    /// The DNA hash of each cell of the app, by role id.
+    pub cells: BTreeMap<AppRoleId, String>,
+}
+
+fn default_manifest() -> PathBuf {
+    PathBuf::from("sandbox-manifest.yaml")
+}
+
+/// Read a topology file.
+pub fn read_topology(path: &Path) -> anyhow::Result<Topology> {
+    let mut topology: Topology = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for happ in topology
+        .conductors
+        .iter_mut()
+        .flat_map(|c| c.happs.iter_mut())
+    {
+        happ.path = dir.join(&happ.path);
+    }
+    Ok(topology)
+}
+
+/// Create the sandboxes of a topology and write its manifest.
+/// The conductors are run while the apps are installed
+/// and stopped again before this returns.
+pub async fn generate(holochain_path: &Path, topology: Topology) -> anyhow::Result<Vec<PathBuf>> {
+    let Topology {
+        network,
+        root,
+        exchange_peer_info,
+        manifest,
+        conductors,
+    } = topology;
+    ensure!(!conductors.is_empty(), "The topology has no conductors");
+    msg!("Creating {} conductor sandboxes", conductors.len());
+
+    let mut running = Vec::with_capacity(conductors.len());
+    let mut manifest_conductors = Vec::with_capacity(conductors.len());
+    for conductor in conductors {
+        let path =
+            crate::generate::generate(network.clone(), root.clone(), conductor.directory.clone())?;
+        let (port, holochain) =
+            run_async(holochain_path, path.clone(), conductor.admin_port).await?;
+        let mut cmd = CmdRunner::new(port).await;
+        for port in &conductor.app_ports {
+            attach_app_interface(&mut cmd, AddAppWs { port: Some(*port) }).await?;
+        }
+        let mut apps = Vec::with_capacity(conductor.happs.len());
+        for happ in conductor.happs {
+            let app = install_app_bundle(
+                &mut cmd,
+                InstallAppBundle {
+                    app_id: happ.app_id,
+                    agent_key: None,
+                    path: happ.path,
+                    uid: happ.uid,
+                },
+            )
+            .await?;
+            apps.push(ManifestApp {
+                agent: app
+                    .cell_data
+                    .first()
+                    .map(|c| c.as_id().agent_pubkey().to_string()),
+                cells: app
+                    .cell_data
+                    .iter()
+                    .map(|c| (c.as_role_id().clone(), c.as_id().dna_hash().to_string()))
+                    .collect(),
+                app_id: app.installed_app_id,
+            });
+        }
+        manifest_conductors.push(ManifestConductor {
+            path,
+            admin_port: conductor.admin_port,
+            app_ports: conductor.app_ports,
+            apps,
+        });
+        running.push((cmd, holochain));
+    }
+
+    if exchange_peer_info {
+        let mut agent_infos = Vec::new();
+        for (cmd, _) in running.iter_mut() {
+            agent_infos.extend(
+                request_agent_info(
+                    cmd,
+                    ListAgents {
+                        agent_key: None,
+                        dna: None,
+                    },
+                )
+                .await?,
+            );
+        }
+        for (cmd, _) in running.iter_mut() {
+            add_agent_info(cmd, agent_infos.clone()).await?;
+        }
+        msg!("Exchanged {} agent infos", agent_infos.len());
+    }
+
+    let paths = manifest_conductors.iter().map(|c| c.path.clone()).collect();
+    let manifest_yaml = serde_yaml::to_string(&Manifest {
+        conductors: manifest_conductors,
+    })?;
+    std::fs::write(&manifest, manifest_yaml)?;
+    msg!("Created {:?}", paths);
+    msg!("Wrote manifest to {}", manifest.display());
+    Ok(paths)
+}
@@ -0,0 +1,200 @@
+//! Helpers for making zome calls to the app interface of a sandbox.
+//!
+//! The cell to call is found through the admin interface from the
+//! installed app id and role id, and the call is made on the first
+//! app interface of the conductor, which is attached if there is none.
+//! The call is signed by the agent of the cell through the sandbox's
+//! lair keystore.
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use holochain_conductor_api::config::conductor::KeystoreConfig;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::SignedZomeCall;
+use holochain_conductor_api::ZomeCall;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::MetaLairClient;
+use holochain_types::prelude::AppRoleId;
+use holochain_types::prelude::CapSecret;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::InstalledAppId;
+use holochain_types::prelude::Timestamp;
+use holochain_websocket::TypedWebsocketSender;
+use std::convert::TryFrom;
+use structopt::StructOpt;
+
+use crate::calls::attach_app_interface;
+use crate::calls::list_app_ws;
+use crate::calls::list_apps;
+use crate::calls::AddAppWs;
+use crate::calls::ListApps;
+use crate::cmds::Existing;
+use crate::config::read_config;
+use crate::ports::websocket_client_by_port;
+use crate::CmdRunner;
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct CallZome {
+    #[structopt(flatten)]
+    pub existing: Existing,
+    #[structopt(long, default_value = "test-app")]
+    /// The InstalledAppId of the app to call.
+    pub app_id: InstalledAppId,
+    #[structopt(long)]
+    /// The AppRoleId of the cell to call.
+    /// Can be left out if the app only has one cell.
+    pub role_id: Option<AppRoleId>,
+    #[structopt(long, parse(try_from_str = parse_cap_secret))]
+    /// The base64 encoded CapSecret to make the call with.
+    /// Not needed for functions with an unrestricted grant
+    /// or when calling as the agent of the cell.
+    pub cap_secret: Option<CapSecret>,
+    /// The zome to call.
+    pub zome: String,
+    /// The zome function to call.
+    pub function: String,
+    #[structopt(default_value = "null")]
+    /// The payload to pass to the function, as JSON.
+    pub payload: String,
+}
+
+#[doc(hidden)]
+pub async fn call_zome(holochain_path: &Path, req: CallZome) -> anyhow::Result<()> {
+    let CallZome {
+        existing,
+        app_id,
+        role_id,
+        cap_secret,
+        zome,
+        function,
+        payload,
+    } = req;
+    let payload: serde_json::Value = serde_json::from_str(&payload)
+        .map_err(|e| anyhow!("The payload must be valid JSON: {}", e))?;
+    let payload = ExternIO::encode(payload)?;
+    let cmds = crate::calls::connect_sandboxes(holochain_path, existing).await?;
+    for (mut cmd, _holochain, path) in cmds {
+        let cell_id = find_cell(&mut cmd, &app_id, role_id.as_ref()).await?;
+        let keystore = sandbox_keystore(path).await?;
+        let mut app = connect_app(&mut cmd).await?;
+        let call = ZomeCall {
+            provenance: cell_id.agent_pubkey().clone(),
+            cell_id,
+            zome_name: zome.clone().into(),
+            fn_name: function.clone().into(),
+            payload: payload.clone(),
+            cap_secret,
+        };
+        let signed = sign_call(&keystore, call).await?;
+        let resp: AppResponse = app
+            .request(AppRequest::SignedZomeCall(Box::new(signed)))
+            .await?;
+        let output = match resp {
+            AppResponse::ZomeCall(output) => *output,
+            AppResponse::Error(e) => bail!("Zome call failed: {:?}", e),
+            _ => bail!("Zome call failed: Expected ZomeCall but got {:?}", resp),
+        };
+        msg!("{}", extern_io_to_json(&output)?);
+    }
+    Ok(())
+}
+
+/// Connect to the lair keystore of a sandbox, which holds the keys of its agents.
+async fn sandbox_keystore(path: PathBuf) -> anyhow::Result<MetaLairClient> {
+    let config = read_config(path.clone())?
+        .ok_or_else(|| anyhow!("No conductor config found in sandbox {}", path.display()))?;
+    match config.keystore {
+        KeystoreConfig::LairServerLegacyDeprecated {
+            keystore_path,
+            danger_passphrase_insecure_from_config,
+        } => {
+            let passphrase =
+                sodoken::BufRead::new_no_lock(danger_passphrase_insecure_from_config.as_bytes());
+            Ok(holochain_keystore::lair_keystore::spawn_lair_keystore(
+                keystore_path.as_deref(),
+                passphrase,
+            )
+            .await?)
+        }
+        keystore => bail!(
+            "Can't sign zome calls for sandbox {}, its keystore must be the lair server in its own directory but is {:?}",
+            path.display(),
+            keystore
+        ),
+    }
+}
+
+/// Sign a zome call with its provenance, with a fresh nonce,
+/// so that it expires a minute from now.
+async fn sign_call(keystore: &MetaLairClient, call: ZomeCall) -> anyhow::Result<SignedZomeCall> {
+    let nonce: [u8; 32] = rand::random();
+    let expires_at = Timestamp::now().saturating_add(&std::time::Duration::from_secs(60));
+    let data = SignedZomeCall::data_to_sign(&call, &nonce, expires_at)?;
+    let signature = call
+        .provenance
+        .sign_raw(keystore, data.into())
+        .await
+        .map_err(|e| anyhow!("Failed to sign the zome call: {:?}", e))?;
+    Ok(SignedZomeCall {
+        call,
+        nonce,
+        expires_at,
+        signature,
+    })
+}
+
+/// Find the cell of an installed app with this role id,
+/// or its only cell if no role id is given.
+async fn find_cell(
+    cmd: &mut CmdRunner,
+    app_id: &InstalledAppId,
+    role_id: Option<&AppRoleId>,
+) -> anyhow::Result<CellId> {
+    let app = list_apps(cmd, ListApps { status: None })
+        .await?
+        .into_iter()
+        .find(|app| &app.installed_app_id == app_id)
+        .ok_or_else(|| anyhow!("App {} is not installed", app_id))?;
+    let mut cells = app.cell_data.into_iter().filter(|cell| match role_id {
+        Some(role_id) => cell.as_role_id() == role_id,
+        None => true,
+    });
+    match (cells.next(), cells.next()) {
+        (Some(cell), None) => Ok(cell.into_id()),
+        (None, _) => bail!("App {} has no cell with role id {:?}", app_id, role_id),
+        (Some(_), Some(_)) => bail!(
+            "App {} has more than one cell, use --role-id to choose one",
+            app_id
+        ),
+    }
+}
+
+/// Connect to the first app interface of a conductor, attaching one if there are none.
+async fn connect_app(
+    cmd: &mut CmdRunner,
+) -> anyhow::Result<TypedWebsocketSender<AppRequest, AppResponse>> {
+    let port = match list_app_ws(cmd).await?.first() {
+        Some(port) => *port,
+        None => attach_app_interface(cmd, AddAppWs { port: None }).await?,
+    };
+    Ok(websocket_client_by_port(port, None).await?.0.into())
+}
+
+/// Decode the msgpack output of a zome call into pretty printed JSON.
+/// Binary data is shown as an array of bytes.
+fn extern_io_to_json(output: &ExternIO) -> anyhow::Result<String> {
+    let value = rmpv::decode::read_value(&mut output.as_bytes())?;
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+fn parse_cap_secret(arg: &str) -> anyhow::Result<CapSecret> {
+    let bytes = base64::decode_config(arg, base64::URL_SAFE_NO_PAD)
+        .or_else(|_| base64::decode(arg))
+        .map_err(|e| anyhow!("{:?}", e))?;
+    CapSecret::try_from(&bytes[..]).map_err(|e| anyhow!("{:?}", e))
+}
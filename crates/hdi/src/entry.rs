@@ -109,6 +109,27 @@ pub fn must_get_valid_record(action_hash: ActionHash) -> ExternResult<Record> {
     })
 }
 
+/// MUST get the [`ValidationPackage`] of an action.
+///
+/// The package holds the records which the entry type of the action
+/// declared it needs for validation, as set by its
+/// [`RequiredValidationType`].
+///
+/// `must_get_validation_package` is available in contexts such as validation where both determinism and network access is desirable.
+/// During validation the package is only ever read from the local
+/// databases, where it is stored before the validation callback runs.
+///
+/// If a `ValidationPackage` fails to be returned:
+///
+/// - Callbacks will return early with `UnresolvedDependencies`
+/// - Zome calls will receive a `WasmError` from the host
+pub fn must_get_validation_package(action_hash: ActionHash) -> ExternResult<ValidationPackage> {
+    HDI.with(|h| {
+        h.borrow()
+            .must_get_validation_package(MustGetValidationPackageInput::new(action_hash))
+    })
+}
+
 /// Helper trait for deserializing [`Entry`]s to the correct type.
 ///
 /// This is implemented by the [`hdk_entry_defs`] proc_macro.
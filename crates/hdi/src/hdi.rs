@@ -5,14 +5,28 @@ pub const HDI_NOT_REGISTERED: &str = "HDI not registered";
 /// This is a cell so it can be set many times.
 /// Every test needs its own mock so each test needs to set it.
 use core::cell::RefCell;
+#[cfg(any(
+    feature = "mock",
+    not(feature = "minimal"),
+    not(target_arch = "wasm32")
+))]
 use std::rc::Rc;
 
 #[cfg(any(feature = "mock", not(target_arch = "wasm32")))]
 thread_local!(pub static HDI: RefCell<Rc<dyn HdiT>> = RefCell::new(Rc::new(ErrHdi)));
 
-#[cfg(all(not(feature = "mock"), target_arch = "wasm32"))]
+#[cfg(all(
+    not(feature = "mock"),
+    not(feature = "minimal"),
+    target_arch = "wasm32"
+))]
 thread_local!(pub static HDI: RefCell<Rc<dyn HdiT>> = RefCell::new(Rc::new(HostHdi)));
 
+// With the `minimal` feature the host is called directly instead of through
+// a trait object so host functions that are never used are left out of the wasm.
+#[cfg(all(not(feature = "mock"), feature = "minimal", target_arch = "wasm32"))]
+thread_local!(pub static HDI: RefCell<HostHdi> = RefCell::new(HostHdi));
+
 /// When mocking is enabled the mockall crate automatically builds a MockHdiT for us.
 /// ```ignore
 /// let mut mock_hdi = MockHdiT::new();
@@ -32,6 +46,10 @@ pub trait HdiT: Send + Sync {
         &self,
         must_get_valid_record_input: MustGetValidRecordInput,
     ) -> ExternResult<Record>;
+    fn must_get_validation_package(
+        &self,
+        must_get_validation_package_input: MustGetValidationPackageInput,
+    ) -> ExternResult<ValidationPackage>;
     // Info
     fn dna_info(&self, dna_info_input: ()) -> ExternResult<DnaInfo>;
     fn zome_info(&self, zome_info_input: ()) -> ExternResult<ZomeInfo>;
@@ -77,6 +95,12 @@ impl HdiT for ErrHdi {
     fn must_get_valid_record(&self, _: MustGetValidRecordInput) -> ExternResult<Record> {
         Self::err()
     }
+    fn must_get_validation_package(
+        &self,
+        _: MustGetValidationPackageInput,
+    ) -> ExternResult<ValidationPackage> {
+        Self::err()
+    }
     fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
         Self::err()
     }
@@ -143,6 +167,15 @@ impl HdiT for HostHdi {
             must_get_valid_record_input,
         )
     }
+    fn must_get_validation_package(
+        &self,
+        must_get_validation_package_input: MustGetValidationPackageInput,
+    ) -> ExternResult<ValidationPackage> {
+        host_call::<MustGetValidationPackageInput, ValidationPackage>(
+            __must_get_validation_package,
+            must_get_validation_package_input,
+        )
+    }
     fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
         host_call::<(), DnaInfo>(__dna_info, ())
     }
@@ -181,6 +214,12 @@ impl HdiT for HostHdi {
 /// At any time the global HDI can be set to a different HDI.
 /// Generally this is only useful during rust unit testing.
 /// When executing wasm without the `mock` feature, the host will be assumed.
+/// Not available in wasm built with the `minimal` feature and without `mock`.
+#[cfg(any(
+    feature = "mock",
+    not(feature = "minimal"),
+    not(target_arch = "wasm32")
+))]
 pub fn set_hdi<H: 'static>(hdi: H) -> Rc<dyn HdiT>
 where
     H: HdiT,
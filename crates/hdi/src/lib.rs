@@ -49,6 +49,14 @@
 //! that can be performed on the data, a validation rule can be specified. Both data types and data
 //! values can be validated. All of these validation rules are written in a central callback
 //! which is called by the Holochain engine for each operation.
+//!
+//! # Smaller WASM with the `minimal` feature
+//!
+//! The `minimal` feature compiles all tracing macros down to nothing, skips registering the
+//! tracing subscriber for each extern, and calls the host directly instead of through the
+//! `HdiT` trait object so host functions that are never called are left out of the WASM.
+//! `set_hdi` isn't available in WASM built with `minimal` unless `mock` is also enabled.
+//! See the HDK crate docs for details.
 
 pub use hdk_derive::hdk_entry_defs;
 pub use hdk_derive::hdk_entry_helper;
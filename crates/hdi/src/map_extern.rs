@@ -1,12 +1,12 @@
 use crate::prelude::*;
 
-#[cfg(feature = "trace")]
+#[cfg(all(feature = "trace", not(feature = "minimal")))]
 #[doc(hidden)]
 pub fn make_subscriber() -> impl Drop {
     crate::prelude::tracing::subscriber::set_default(crate::trace::WasmSubscriber::default())
 }
 
-#[cfg(not(feature = "trace"))]
+#[cfg(any(not(feature = "trace"), feature = "minimal"))]
 #[doc(hidden)]
 /// Needed as a noop for map_extern! when trace is off.
 pub fn make_subscriber() -> impl Drop {
@@ -4,6 +4,7 @@ pub use crate::ed25519::verify_signature_raw;
 pub use crate::entry::must_get_action;
 pub use crate::entry::must_get_entry;
 pub use crate::entry::must_get_valid_record;
+pub use crate::entry::must_get_validation_package;
 pub use crate::entry::EntryTypesHelper;
 pub use crate::entry_defs;
 pub use crate::hash::*;
@@ -77,6 +78,7 @@ macro_rules! holochain_externs {
             __dna_info,
             __must_get_entry,
             __must_get_valid_record,
+            __must_get_validation_package,
             __must_get_action,
             __x_salsa20_poly1305_decrypt,
             __x_25519_x_salsa20_poly1305_decrypt
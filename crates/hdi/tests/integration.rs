@@ -217,16 +217,19 @@ fn entry_defs_overrides() {
                 id: "hey".into(),
                 visibility: Default::default(),
                 required_validations: Default::default(),
+                required_validation_type: Default::default(),
             },
             EntryDef {
                 id: "b".into(),
                 visibility: EntryVisibility::Private,
                 required_validations: Default::default(),
+                required_validation_type: Default::default(),
             },
             EntryDef {
                 id: "c".into(),
                 visibility: Default::default(),
                 required_validations: RequiredValidations(10),
+                required_validation_type: Default::default(),
             },
         ]))
     );
@@ -254,16 +257,19 @@ fn entry_defs_default() {
                 id: "a".into(),
                 visibility: Default::default(),
                 required_validations: Default::default(),
+                required_validation_type: Default::default(),
             },
             EntryDef {
                 id: "b".into(),
                 visibility: Default::default(),
                 required_validations: Default::default(),
+                required_validation_type: Default::default(),
             },
             EntryDef {
                 id: "c".into(),
                 visibility: Default::default(),
                 required_validations: Default::default(),
+                required_validation_type: Default::default(),
             },
         ]))
     );
@@ -434,6 +440,13 @@ fn set_zome_types(entries: &[(u8, u8)], links: &[(u8, u8)]) {
             todo!()
         }
 
+        fn must_get_validation_package(
+            &self,
+            must_get_validation_package_input: MustGetValidationPackageInput,
+        ) -> ExternResult<ValidationPackage> {
+            todo!()
+        }
+
         fn dna_info(&self, dna_info_input: ()) -> ExternResult<DnaInfo> {
             todo!()
         }
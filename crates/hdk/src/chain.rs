@@ -19,9 +19,25 @@ pub fn get_agent_activity(
 
 /// Walks the source chain in ascending order (oldest to latest) filtering by action and/or entry type
 ///
-/// Given an action and entry type, returns an [ `Vec<Record>` ]
+/// Given a [ `ChainQueryFilter` ], returns an [ `Vec<Record>` ].
+/// All the filters can be combined:
+/// - `sequence_range` limits the records to a range of action sequence numbers or action hashes.
+/// - `entry_type`, `action_type` and `entry_hashes` filter on the actions.
+/// - `time_range` filters on the action timestamps, including the start and excluding the end.
+/// - `include_entries` returns the entries along with the actions.
+///
+/// e.g. all the `Create` actions of the last hour with their entries:
+/// ```ignore
+/// let now = sys_time()?;
+/// let hour_ago = Timestamp::from_micros(now.as_micros() - 60 * 60 * 1_000_000);
+/// let records = query(
+///     ChainQueryFilter::new()
+///         .action_type(ActionType::Create)
+///         .time_range(hour_ago..now)
+///         .include_entries(true),
+/// )?;
+/// ```
 ///
-/// @todo document this better with examples after we make query do all the things we want.
 /// @todo implement cap grant/claim usage in terms of query
 /// @todo have ability to hash-bound query other agent's chains based on agent activity
 /// @todo tie query into validation so we track dependencies e.g. validation packages
@@ -229,6 +229,26 @@ where
         .unwrap())
 }
 
+/// The same as [`get`] but says why the record wasn't returned.
+///
+/// [`GetOutcome::NotFound`] means the authorities that answered don't hold the record.
+/// [`GetOutcome::Unavailable`] means no authority could be reached and
+/// [`GetOutcome::TimedOut`] means the authorities asked didn't answer in time,
+/// so in both cases the record may exist and the call could be retried later.
+pub fn get_outcome<H>(hash: H, options: GetOptions) -> ExternResult<GetOutcome<Record>>
+where
+    AnyDhtHash: From<H>,
+{
+    Ok(HDK
+        .with(|h| {
+            h.borrow()
+                .get_outcome(vec![GetInput::new(AnyDhtHash::from(hash), options)])
+        })?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
 /// Get a record and its details for the entry or action hash passed in.
 /// Returns [`None`] if the entry/action does not exist.
 /// The details returned are a contextual mix of records and action hashes.
@@ -53,3 +53,10 @@ pub mod path;
 ///
 /// The tests in the shard module include several examples of the DSL including multibyte characters.
 pub mod shard;
+
+/// An index of links spread across time bucketed or hash prefixed child paths.
+///
+/// Avoids a single hot anchor for feeds and other high fanout indexes by
+/// linking each target from one of many shards under a base path, then fanning
+/// queries out across the shards and merging the results.
+pub mod sharded_index;
@@ -52,6 +52,7 @@ fn root_ensures() {
             base_address: root_hash().unwrap(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("foo").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning(|_| {
             Ok(vec![vec![Link {
@@ -209,6 +210,7 @@ fn paths_exists() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("bar").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning(|_| {
             Ok(vec![vec![Link {
@@ -224,6 +226,7 @@ fn paths_exists() {
             base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("baz").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning(|_| {
             Ok(vec![vec![Link {
@@ -300,6 +303,7 @@ fn children() {
             base_address: root_hash().unwrap(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("foo").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning({
             let foo = foo.clone();
@@ -311,6 +315,7 @@ fn children() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("bar").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning({
             let foo_bar = foo_bar.clone();
@@ -322,6 +327,7 @@ fn children() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("bar2").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning({
             let foo_bar2 = foo_bar2.clone();
@@ -333,6 +339,7 @@ fn children() {
             base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("baz").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning({
             let foo_bar_baz = foo_bar_baz.clone();
@@ -344,6 +351,7 @@ fn children() {
             base_address: Path::from("foo.bar2").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("baz2").make_tag().unwrap()),
+            pagination: None,
         }]))
         .returning({
             let foo_bar2_baz2 = foo_bar2_baz2.clone();
@@ -355,6 +363,7 @@ fn children() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            pagination: None,
         }]))
         .returning(move |_| Ok(vec![vec![foo_bar.clone(), foo_bar2.clone()]]));
     // foo.bar -[]-> foo.bar.baz
@@ -363,6 +372,7 @@ fn children() {
             base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            pagination: None,
         }]))
         .returning(move |_| Ok(vec![vec![foo_bar_baz.clone()]]));
     // foo.bar2 -[]-> foo.bar2.baz2
@@ -371,6 +381,7 @@ fn children() {
             base_address: Path::from("foo.bar2").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            pagination: None,
         }]))
         .returning(move |_| Ok(vec![vec![foo_bar2_baz2.clone()]]));
     // foo.bar.baz -[]-> ()
@@ -379,6 +390,7 @@ fn children() {
             base_address: Path::from("foo.bar.baz").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            pagination: None,
         }]))
         .returning(|_| Ok(vec![vec![]]));
     // foo.bar2.baz2 -[]-> ()
@@ -390,6 +402,7 @@ fn children() {
                 .into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            pagination: None,
         }]))
         .returning(|_| Ok(vec![vec![]]));
     set_hdk(mock);
@@ -0,0 +1,190 @@
+use crate::hash_path::path::Component;
+use crate::hash_path::path::TypedPath;
+use crate::prelude::*;
+
+/// How a [`ShardedIndex`] decides which shard a target is linked from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IndexSharding {
+    /// One shard per time bucket of the given width.
+    /// New targets are indexed under the bucket for the current [`sys_time`].
+    /// Queries for a time range only visit the buckets that overlap it.
+    Time(std::time::Duration),
+    /// One shard per prefix of the target hash, taking the given number of bytes.
+    /// A width of `1` spreads targets across up to 256 shards.
+    HashPrefix(u8),
+}
+
+/// An index of links that are spread across many child [ `Path` ]s
+/// ("shards") of a base path rather than all being linked from the base.
+///
+/// Linking everything in a busy feed from a single anchor makes the agents
+/// holding that anchor a hotspot on the DHT. Sharding the links means each
+/// shard lands on a different part of the DHT and queries fan out across
+/// the shards in a single `get_links` host call.
+///
+/// The base path is linked to each shard with [ `TypedPath::ensure` ] so the
+/// shards can be discovered, and each shard is linked to its targets with
+/// the same link type.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShardedIndex {
+    base: TypedPath,
+    sharding: IndexSharding,
+}
+
+impl ShardedIndex {
+    /// Create an index rooted at `base`.
+    pub fn new(base: TypedPath, sharding: IndexSharding) -> Self {
+        Self { base, sharding }
+    }
+
+    /// The path all shards are children of.
+    pub fn base(&self) -> &TypedPath {
+        &self.base
+    }
+
+    /// The sharding strategy of this index.
+    pub fn sharding(&self) -> IndexSharding {
+        self.sharding
+    }
+
+    /// Link `target` from the shard it belongs in, creating the shard if needed.
+    pub fn add(
+        &self,
+        target: impl Into<AnyLinkableHash>,
+        tag: impl Into<LinkTag>,
+    ) -> ExternResult<ActionHash> {
+        let target = target.into();
+        let component = match self.sharding {
+            IndexSharding::Time(width) => time_component(time_bucket(sys_time()?, width)),
+            IndexSharding::HashPrefix(width) => hash_prefix_component(&target, width),
+        };
+        let shard = self.shard(component);
+        shard.ensure()?;
+        create_link(shard.path_entry_hash()?, target, self.base.link_type, tag)
+    }
+
+    /// Get the links from every shard, newest first.
+    pub fn get(&self, tag: Option<LinkTag>) -> ExternResult<Vec<Link>> {
+        self.get_from_shards(self.shards()?, tag)
+    }
+
+    /// Get the links created in `[from, to)`, newest first.
+    ///
+    /// For [ `IndexSharding::Time` ] only the buckets that overlap the range are queried.
+    pub fn get_between(
+        &self,
+        from: Timestamp,
+        to: Timestamp,
+        tag: Option<LinkTag>,
+    ) -> ExternResult<Vec<Link>> {
+        let mut shards = self.shards()?;
+        if let IndexSharding::Time(width) = self.sharding {
+            let first = time_bucket(from, width);
+            let last = time_bucket(to, width);
+            shards.retain(|shard| match shard.leaf().and_then(bucket_from_component) {
+                Some(bucket) => bucket >= first && bucket <= last,
+                None => false,
+            });
+        }
+        let mut links = self.get_from_shards(shards, tag)?;
+        links.retain(|link| link.timestamp >= from && link.timestamp < to);
+        Ok(links)
+    }
+
+    /// All the shards that have been created for this index.
+    pub fn shards(&self) -> ExternResult<Vec<TypedPath>> {
+        if !self.base.exists()? {
+            return Ok(vec![]);
+        }
+        self.base.children_paths()
+    }
+
+    fn shard(&self, component: Component) -> TypedPath {
+        let mut path = self.base.path.clone();
+        path.append_component(component);
+        path.into_typed(self.base.link_type)
+    }
+
+    fn get_from_shards(
+        &self,
+        shards: Vec<TypedPath>,
+        tag: Option<LinkTag>,
+    ) -> ExternResult<Vec<Link>> {
+        if shards.is_empty() {
+            return Ok(vec![]);
+        }
+        let inputs = shards
+            .iter()
+            .map(|shard| {
+                Ok(GetLinksInput::new(
+                    shard.path_entry_hash()?.into(),
+                    LinkTypeFilter::single_type(
+                        self.base.link_type.zome_id,
+                        self.base.link_type.zome_type,
+                    ),
+                    tag.clone(),
+                ))
+            })
+            .collect::<ExternResult<Vec<_>>>()?;
+        let mut links: Vec<Link> = HDK
+            .with(|h| h.borrow().get_links(inputs))?
+            .into_iter()
+            .flatten()
+            .collect();
+        links.sort_unstable_by_key(|link| std::cmp::Reverse(link.timestamp));
+        Ok(links)
+    }
+}
+
+/// The bucket a timestamp falls in for buckets of `width`.
+fn time_bucket(timestamp: Timestamp, width: std::time::Duration) -> i64 {
+    let width = (width.as_micros() as i64).max(1);
+    timestamp.as_micros().div_euclid(width)
+}
+
+fn time_component(bucket: i64) -> Component {
+    Component::from(bucket.to_be_bytes().to_vec())
+}
+
+fn bucket_from_component(component: &Component) -> Option<i64> {
+    let bytes: [u8; 8] = component.as_ref().try_into().ok()?;
+    Some(i64::from_be_bytes(bytes))
+}
+
+fn hash_prefix_component(target: &AnyLinkableHash, width: u8) -> Component {
+    let core = target.get_raw_32();
+    Component::from(core[..(width as usize).min(core.len())].to_vec())
+}
+
+#[test]
+#[cfg(test)]
+fn time_buckets() {
+    let hour = std::time::Duration::from_secs(60 * 60);
+    let micros_per_hour = 60 * 60 * 1_000_000;
+    assert_eq!(0, time_bucket(Timestamp::from_micros(0), hour));
+    assert_eq!(
+        0,
+        time_bucket(Timestamp::from_micros(micros_per_hour - 1), hour)
+    );
+    assert_eq!(
+        1,
+        time_bucket(Timestamp::from_micros(micros_per_hour), hour)
+    );
+    assert_eq!(-1, time_bucket(Timestamp::from_micros(-1), hour));
+
+    for bucket in [i64::MIN, -1, 0, 1, 1000, i64::MAX] {
+        assert_eq!(Some(bucket), bucket_from_component(&time_component(bucket)));
+    }
+    assert_eq!(None, bucket_from_component(&Component::from("foo")));
+}
+
+#[test]
+#[cfg(test)]
+fn hash_prefixes() {
+    let hash = AnyLinkableHash::from(EntryHash::from_raw_36((0..36).collect()));
+    assert_eq!(Component::from(vec![0, 1]), hash_prefix_component(&hash, 2));
+    assert_eq!(
+        Component::from((0..32).collect::<Vec<u8>>()),
+        hash_prefix_component(&hash, 40)
+    );
+}
@@ -6,14 +6,28 @@ pub const HDK_NOT_REGISTERED: &str = "HDK not registered";
 /// This is a cell so it can be set many times.
 /// Every test needs its own mock so each test needs to set it.
 use core::cell::RefCell;
+#[cfg(any(
+    feature = "mock",
+    not(feature = "minimal"),
+    not(target_arch = "wasm32")
+))]
 use std::rc::Rc;
 
 #[cfg(any(feature = "mock", not(target_arch = "wasm32")))]
 thread_local!(pub static HDK: RefCell<Rc<dyn HdkT>> = RefCell::new(Rc::new(ErrHdk)));
 
-#[cfg(all(not(feature = "mock"), target_arch = "wasm32"))]
+#[cfg(all(
+    not(feature = "mock"),
+    not(feature = "minimal"),
+    target_arch = "wasm32"
+))]
 thread_local!(pub static HDK: RefCell<Rc<dyn HdkT>> = RefCell::new(Rc::new(HostHdk)));
 
+// With the `minimal` feature the host is called directly instead of through
+// a trait object so host functions that are never used are left out of the wasm.
+#[cfg(all(not(feature = "mock"), feature = "minimal", target_arch = "wasm32"))]
+thread_local!(pub static HDK: RefCell<HostHdk> = RefCell::new(HostHdk));
+
 /// When mocking is enabled the mockall crate automatically builds a MockHdkT for us.
 /// ```ignore
 /// let mut mock = MockHdkT::new();
@@ -36,6 +50,7 @@ pub trait HdkT: HdiT {
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+    fn get_outcome(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<GetOutcome<Record>>>;
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -43,6 +58,7 @@ pub trait HdkT: HdiT {
     ) -> ExternResult<PreflightRequestAcceptance>;
     // Info
     fn agent_info(&self, agent_info_input: ()) -> ExternResult<AgentInfo>;
+    fn app_info(&self, app_info_input: ()) -> ExternResult<AppInfo>;
     fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
     // Link
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash>;
@@ -55,6 +71,7 @@ pub trait HdkT: HdiT {
     // P2P
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
+    fn emit_signal_ack_required(&self, app_signal: AppSignal) -> ExternResult<()>;
     fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()>;
     // Random
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
@@ -106,6 +123,7 @@ mockall::mock! {
         fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
         fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
         fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+        fn get_outcome(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<GetOutcome<Record>>>;
         // CounterSigning
         fn accept_countersigning_preflight_request(
             &self,
@@ -113,6 +131,7 @@ mockall::mock! {
         ) -> ExternResult<PreflightRequestAcceptance>;
         // Info
         fn agent_info(&self, agent_info_input: ()) -> ExternResult<AgentInfo>;
+        fn app_info(&self, app_info_input: ()) -> ExternResult<AppInfo>;
         fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
         // Link
         fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash>;
@@ -125,6 +144,7 @@ mockall::mock! {
         // P2P
         fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
         fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
+        fn emit_signal_ack_required(&self, app_signal: AppSignal) -> ExternResult<()>;
         fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()>;
         // Random
         fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
@@ -169,6 +189,10 @@ mockall::mock! {
             &self,
             must_get_valid_record_input: MustGetValidRecordInput,
         ) -> ExternResult<Record>;
+        fn must_get_validation_package(
+            &self,
+            must_get_validation_package_input: MustGetValidationPackageInput,
+        ) -> ExternResult<ValidationPackage>;
         // Info
         fn dna_info(&self, dna_info_input: ()) -> ExternResult<DnaInfo>;
         fn zome_info(&self, zome_info_input: ()) -> ExternResult<ZomeInfo>;
@@ -230,6 +254,13 @@ impl HdiT for ErrHdk {
         Self::err()
     }
 
+    fn must_get_validation_package(
+        &self,
+        _must_get_validation_package_input: MustGetValidationPackageInput,
+    ) -> ExternResult<ValidationPackage> {
+        Self::err()
+    }
+
     fn dna_info(&self, _dna_info_input: ()) -> ExternResult<DnaInfo> {
         Self::err()
     }
@@ -286,6 +317,9 @@ impl HdkT for ErrHdk {
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
+    fn get_outcome(&self, _: Vec<GetInput>) -> ExternResult<Vec<GetOutcome<Record>>> {
+        Self::err()
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -296,6 +330,9 @@ impl HdkT for ErrHdk {
     fn agent_info(&self, _: ()) -> ExternResult<AgentInfo> {
         Self::err()
     }
+    fn app_info(&self, _: ()) -> ExternResult<AppInfo> {
+        Self::err()
+    }
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         Self::err()
     }
@@ -319,6 +356,9 @@ impl HdkT for ErrHdk {
     fn emit_signal(&self, _: AppSignal) -> ExternResult<()> {
         Self::err()
     }
+    fn emit_signal_ack_required(&self, _: AppSignal) -> ExternResult<()> {
+        Self::err()
+    }
     fn remote_signal(&self, _: RemoteSignal) -> ExternResult<()> {
         Self::err()
     }
@@ -409,6 +449,12 @@ impl HdiT for HostHdk {
     ) -> ExternResult<Record> {
         HostHdi::new().must_get_valid_record(must_get_valid_record_input)
     }
+    fn must_get_validation_package(
+        &self,
+        must_get_validation_package_input: MustGetValidationPackageInput,
+    ) -> ExternResult<ValidationPackage> {
+        HostHdi::new().must_get_validation_package(must_get_validation_package_input)
+    }
     fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
         HostHdi::new().dna_info(())
     }
@@ -471,6 +517,9 @@ impl HdkT for HostHdk {
     fn get_details(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         host_call::<Vec<GetInput>, Vec<Option<Details>>>(__get_details, get_inputs)
     }
+    fn get_outcome(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<GetOutcome<Record>>> {
+        host_call::<Vec<GetInput>, Vec<GetOutcome<Record>>>(__get_outcome, get_inputs)
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -484,6 +533,9 @@ impl HdkT for HostHdk {
     fn agent_info(&self, _: ()) -> ExternResult<AgentInfo> {
         host_call::<(), AgentInfo>(__agent_info, ())
     }
+    fn app_info(&self, _: ()) -> ExternResult<AppInfo> {
+        host_call::<(), AppInfo>(__app_info, ())
+    }
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         host_call::<(), CallInfo>(__call_info, ())
     }
@@ -508,6 +560,9 @@ impl HdkT for HostHdk {
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()> {
         host_call::<AppSignal, ()>(__emit_signal, app_signal)
     }
+    fn emit_signal_ack_required(&self, app_signal: AppSignal) -> ExternResult<()> {
+        host_call::<AppSignal, ()>(__emit_signal_ack_required, app_signal)
+    }
     fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()> {
         host_call::<RemoteSignal, ()>(__remote_signal, remote_signal)
     }
@@ -582,6 +637,12 @@ impl HdkT for HostHdk {
 /// At any time the global HDK can be set to a different hdk.
 /// Generally this is only useful during rust unit testing.
 /// When executing wasm without the `mock` feature, the host will be assumed.
+/// Not available in wasm built with the `minimal` feature and without `mock`.
+#[cfg(any(
+    feature = "mock",
+    not(feature = "minimal"),
+    not(target_arch = "wasm32")
+))]
 pub fn set_hdk<H: 'static>(hdk: H)
 where
     H: HdkT,
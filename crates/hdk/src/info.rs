@@ -14,7 +14,22 @@ pub fn agent_info() -> ExternResult<AgentInfo> {
     HDK.with(|h| h.borrow().agent_info(()))
 }
 
-/// @todo Not implemented
+/// Trivial wrapper for `__app_info` host function.
+///
+/// The [ `AppInfo` ] holds the id of the installed app the current cell is part of,
+/// and the role of the cell within that app.
+/// If the cell is part of several apps, the first running one is used.
+/// Not available in validation or in `post_commit`.
+pub fn app_info() -> ExternResult<AppInfo> {
+    HDK.with(|h| h.borrow().app_info(()))
+}
+
+/// Trivial wrapper for `__call_info` host function.
+///
+/// The [ `CallInfo` ] holds the name of the function being called, the agent
+/// who made the call, the capability grant that authorized it and the chain
+/// head as at the start of the call.
+/// Not available in validation.
 pub fn call_info() -> ExternResult<CallInfo> {
     HDK.with(|h| h.borrow().call_info(()))
 }
@@ -240,6 +240,27 @@
 //! The most common internal errors, such as invalid deserialization between WASM and external processes, are traced as `error!` by default.
 //!
 //!
+//! # Smaller WASM with the `minimal` feature 📦
+//!
+//! Production zomes that don't need tracing can enable the `minimal` feature to get a substantially smaller WASM without pruning anything by hand:
+//!
+//! ```toml
+//! hdk = { version = "=x.y.z", features = ["minimal"] }
+//! ```
+//!
+//! This:
+//!
+//! - Compiles all tracing macros, including the ones in your zome, down to nothing so their messages and `Debug` formatting aren't in the WASM.
+//! - Doesn't register the tracing subscriber for each extern.
+//! - Calls the host directly instead of through the `HdkT` trait object, so HDK functions your zome never calls are left out of the WASM.
+//!
+//! Unlike most features this _removes_ functionality, and Cargo enables it for every crate in the build that depends on the HDK.
+//! Only enable it in the crates that build your production WASM.
+//! `set_hdk` isn't available in WASM built with `minimal` unless `mock` is also enabled.
+//!
+//! See `crates/test_utils/wasm/wasm_size` for a benchmark of how much smaller a zome gets.
+//!
+//!
 //! # HDK requires explicit error handling between the guest and host ⚠
 //!
 //! All calls to functions provided by the host can fail to execute cleanly, at the least serialization could always fail.
@@ -266,6 +287,9 @@ fn wasm_getrandom(buf: &mut [u8]) -> Result<(), getrandom::Error> {
         let code = core::num::NonZeroU32::new(ERAND_TOO_LONG).unwrap();
         return Err(getrandom::Error::from(code));
     }
+    #[cfg(all(feature = "minimal", not(feature = "mock")))]
+    use crate::hdk::HdkT;
+
     let number_of_bytes = buf.len() as u32;
     match crate::hdk::HDK.with(|h| h.borrow().random_bytes(number_of_bytes)) {
         Err(_) => {
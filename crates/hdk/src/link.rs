@@ -148,6 +148,31 @@ pub fn get_links(
         .unwrap())
 }
 
+/// Returns one page of the links that reference a base, in timestamp order.
+///
+/// Use this instead of [ `get_links` ] for bases with many links.
+/// Pass the [ `LinkCursor` ] of the last link of a page as `after` to get the next page.
+/// Links which have been deleted are skipped and don't count towards the limit.
+///
+/// See [ `get_links` ].
+pub fn get_links_page(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+    pagination: LinkPagination,
+) -> ExternResult<Vec<Link>> {
+    let link_type = link_type.try_into_filter()?;
+    Ok(HDK
+        .with(|h| {
+            h.borrow()
+                .get_links(vec![GetLinksInput::new(base.into(), link_type, link_tag)
+                    .with_pagination(pagination)])
+        })?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
 /// Get all link creates and deletes that reference a base entry hash, optionally filtered by tag
 ///
 /// Tag filtering is a simple bytes prefix.
@@ -106,6 +106,24 @@ where
     })
 }
 
+/// Emit an app-defined signal which connected clients must acknowledge.
+///
+/// Works like [`emit_signal`], but clients receive the signal with an id
+/// and acknowledge it with `AppRequest::AckSignal`. The conductor counts
+/// signals which no client acknowledged as undelivered, which clients can
+/// query with `AppRequest::UndeliveredSignalCount`.
+/// Use this for signals which must not be silently lost.
+pub fn emit_signal_ack_required<I>(input: I) -> ExternResult<()>
+where
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow().emit_signal_ack_required(AppSignal::new(
+            ExternIO::encode(input).map_err(|e| wasm_error!(e.into()))?,
+        ))
+    })
+}
+
 /// ## Remote Signal
 /// Send a signal to a list of other agents.
 /// This will send the data as an [ `AppSignal` ] to
@@ -141,6 +159,27 @@ where
         h.borrow().remote_signal(RemoteSignal {
             signal: ExternIO::encode(input).map_err(|e| wasm_error!(e.into()))?,
             agents,
+            reliable: false,
+        })
+    })
+}
+
+/// Like [`remote_signal`], but the conductor retries sending to each agent
+/// with a backoff for up to a minute if the send fails.
+///
+/// The outcome for each agent is reported to the clients of the sending
+/// cell as a `SystemSignal::RemoteSignalDelivery` saying whether that
+/// agent's `recv_remote_signal` received the signal.
+/// This is still non-blocking and does not return delivery errors.
+pub fn remote_signal_reliable<I>(input: I, agents: Vec<AgentPubKey>) -> ExternResult<()>
+where
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow().remote_signal(RemoteSignal {
+            signal: ExternIO::encode(input).map_err(|e| wasm_error!(e.into()))?,
+            agents,
+            reliable: true,
         })
     })
 }
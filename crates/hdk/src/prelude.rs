@@ -19,9 +19,11 @@ pub use crate::entry::delete;
 pub use crate::entry::delete_entry;
 pub use crate::entry::get;
 pub use crate::entry::get_details;
+pub use crate::entry::get_outcome;
 pub use crate::entry::must_get_action;
 pub use crate::entry::must_get_entry;
 pub use crate::entry::must_get_valid_record;
+pub use crate::entry::must_get_validation_package;
 pub use crate::entry::update;
 pub use crate::entry::update_entry;
 pub use crate::entry::EntryTypesHelper;
@@ -36,6 +38,7 @@ pub use crate::hash_path::anchor::Anchor;
 pub use crate::hash_path::path::Path;
 pub use crate::hdk::*;
 pub use crate::info::agent_info;
+pub use crate::info::app_info;
 pub use crate::info::call_info;
 pub use crate::info::dna_info;
 pub use crate::info::zome_info;
@@ -43,13 +46,16 @@ pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
 pub use crate::link::get_links;
+pub use crate::link::get_links_page;
 pub use crate::link::LinkTypeFilterExt;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
 pub use crate::p2p::call;
 pub use crate::p2p::call_remote;
 pub use crate::p2p::emit_signal;
+pub use crate::p2p::emit_signal_ack_required;
 pub use crate::p2p::remote_signal;
+pub use crate::p2p::remote_signal_reliable;
 pub use crate::random::*;
 pub use crate::time::schedule;
 pub use crate::time::sleep;
@@ -64,6 +70,7 @@ pub use crate::x_salsa20_poly1305::x_salsa20_poly1305_shared_secret_create_rando
 pub use crate::x_salsa20_poly1305::x_salsa20_poly1305_shared_secret_export;
 pub use crate::x_salsa20_poly1305::x_salsa20_poly1305_shared_secret_ingest;
 pub use hdi;
+pub use hdi::hdi::HdiT;
 pub use hdi::map_extern_infallible;
 pub use hdi::prelude::app_entry;
 pub use hdk_derive;
@@ -120,16 +127,19 @@ macro_rules! holochain_externs {
             __random_bytes,
             __sys_time,
             __agent_info,
+            __app_info,
             __capability_claims,
             __capability_grants,
             __capability_info,
             __get,
             __get_details,
+            __get_outcome,
             __get_links,
             __get_link_details,
             __get_agent_activity,
             __must_get_entry,
             __must_get_valid_record,
+            __must_get_validation_package,
             __must_get_action,
             __accept_countersigning_preflight_request,
             __query,
@@ -137,6 +147,7 @@ macro_rules! holochain_externs {
             __call,
             __create,
             __emit_signal,
+            __emit_signal_ack_required,
             __remote_signal,
             __create_link,
             __delete_link,
@@ -1,4 +1,5 @@
 use holochain_integrity_types::EntryVisibility;
+use holochain_integrity_types::RequiredValidationType;
 use holochain_integrity_types::RequiredValidations;
 use proc_macro::TokenStream;
 
@@ -17,6 +18,8 @@ struct VarOpts {
     visibility: Option<String>,
     #[darling(default)]
     required_validations: Option<u8>,
+    #[darling(default)]
+    required_validation_type: Option<String>,
 }
 
 #[derive(FromDeriveInput)]
@@ -42,17 +45,21 @@ pub fn derive(input: TokenStream) -> TokenStream {
                      name,
                      visibility,
                      required_validations,
+                     required_validation_type,
                      ..
                  }| {
                     let id = crate::util::to_snake_case(name, &v_ident);
                     let visibility = parse_visibility(&v_ident, visibility);
                     let required_validations =
                         required_validations.unwrap_or_else(|| RequiredValidations::default().0);
+                    let required_validation_type =
+                        parse_required_validation_type(&v_ident, required_validation_type);
                     quote::quote! {
                         EntryDef {
                             id: EntryDefId::App(AppEntryDefName::from_str(#id)),
                             visibility: #visibility,
                             required_validations: RequiredValidations(#required_validations),
+                            required_validation_type: #required_validation_type,
                         },
                     }
                 },
@@ -90,3 +97,32 @@ fn default_visibility() -> proc_macro2::TokenStream {
         EntryVisibility::Private => quote::quote! {EntryVisibility::Private},
     }
 }
+
+fn parse_required_validation_type(
+    ident: &syn::Ident,
+    variant: Option<String>,
+) -> proc_macro2::TokenStream {
+    let variant = match variant {
+        Some(v) => v,
+        None => return default_required_validation_type(),
+    };
+    match variant.as_str() {
+        "record" => quote::quote! {RequiredValidationType::Record},
+        "sub_chain" => quote::quote! {RequiredValidationType::SubChain},
+        "full" => quote::quote! {RequiredValidationType::Full},
+        "custom" => quote::quote! {RequiredValidationType::Custom},
+        _ => abort!(
+            ident,
+            "RequiredValidationType can only be `record`, `sub_chain`, `full` or `custom`"
+        ),
+    }
+}
+
+fn default_required_validation_type() -> proc_macro2::TokenStream {
+    match RequiredValidationType::default() {
+        RequiredValidationType::Record => quote::quote! {RequiredValidationType::Record},
+        RequiredValidationType::SubChain => quote::quote! {RequiredValidationType::SubChain},
+        RequiredValidationType::Full => quote::quote! {RequiredValidationType::Full},
+        RequiredValidationType::Custom => quote::quote! {RequiredValidationType::Custom},
+    }
+}
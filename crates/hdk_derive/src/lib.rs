@@ -33,6 +33,8 @@ impl Parse for EntryDef {
         let mut required_validations =
             holochain_integrity_types::entry_def::RequiredValidations::default();
         let mut visibility = holochain_integrity_types::entry_def::EntryVisibility::default();
+        let mut required_validation_type =
+            holochain_integrity_types::validate::RequiredValidationType::default();
 
         let vars = Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated(input)?;
         for var in vars {
@@ -69,6 +71,26 @@ impl Parse for EntryDef {
                             _ => unreachable!(),
                         };
                     }
+                    "required_validation_type" => {
+                        match var.lit {
+                            syn::Lit::Str(s) => required_validation_type = match s.value().as_str() {
+                                "record" => {
+                                    holochain_integrity_types::validate::RequiredValidationType::Record
+                                }
+                                "sub_chain" => {
+                                    holochain_integrity_types::validate::RequiredValidationType::SubChain
+                                }
+                                "full" => {
+                                    holochain_integrity_types::validate::RequiredValidationType::Full
+                                }
+                                "custom" => {
+                                    holochain_integrity_types::validate::RequiredValidationType::Custom
+                                }
+                                _ => unreachable!(),
+                            },
+                            _ => unreachable!(),
+                        };
+                    }
                     _ => {}
                 }
             }
@@ -77,6 +99,7 @@ impl Parse for EntryDef {
             id,
             visibility,
             required_validations,
+            required_validation_type,
         }))
     }
 }
@@ -141,12 +164,14 @@ impl quote::ToTokens for EntryDef {
         let id = EntryDefId(self.0.id.clone());
         let visibility = EntryVisibility(self.0.visibility);
         let required_validations = RequiredValidations(self.0.required_validations);
+        let required_validation_type = RequiredValidationType(self.0.required_validation_type);
 
         tokens.append_all(quote::quote! {
             hdi::prelude::EntryDef {
                 id: #id,
                 visibility: #visibility,
                 required_validations: #required_validations,
+                required_validation_type: #required_validation_type,
             }
         });
     }
@@ -204,13 +229,15 @@ pub fn derive_to_unit_enum(input: TokenStream) -> TokenStream {
 ///
 /// # Attributes
 /// - `unit_enum(TypeName)`: Defines the unit version of this enum.
-/// - `entry_def(name: String, required_validations: u8, visibility: String)`: Defines an entry type.
+/// - `entry_def(name: String, required_validations: u8, visibility: String, required_validation_type: String)`: Defines an entry type.
 ///   - name: The name of the entry definition (optional).
 ///     Defaults to the name of the enum variant.
 ///   - required_validations: The number of validations required before this entry
 ///     will not be published anymore (optional). Defaults to 5.
 ///   - visibility: The visibility of this entry.
 ///     ["public" | "private"] Default is "public".
+///   - required_validation_type: The validation package validators need from the author.
+///     ["record" | "sub_chain" | "full" | "custom"] Default is "record".
 ///
 /// # Examples
 /// ```ignore
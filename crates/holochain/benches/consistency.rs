@@ -184,7 +184,10 @@ async fn setup() -> (Producer, Consumer, Others) {
         ConductorConfig {
             network: Some(network),
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::Websocket {
+                    port: 0,
+                    auth_token: None,
+                },
             }]),
             ..Default::default()
         }
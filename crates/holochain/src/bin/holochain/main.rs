@@ -1,3 +1,5 @@
+use anyhow::Context;
+use holochain::conductor::backup::ConductorBackup;
 use holochain::conductor::config::ConductorConfig;
 use holochain::conductor::interactive;
 use holochain::conductor::manager::handle_shutdown;
@@ -6,6 +8,10 @@ use holochain::conductor::Conductor;
 use holochain::conductor::ConductorHandle;
 use holochain_conductor_api::conductor::ConductorConfigError;
 use holochain_conductor_api::config::conductor::KeystoreConfig;
+use holochain_types::prelude::AgentPubKey;
+use holochain_types::prelude::AppBundleSource;
+use holochain_types::prelude::InstallAppBundlePayload;
+use holochain_types::prelude::InstalledAppId;
 use holochain_util::tokio_helper;
 use kitsune_p2p_types::dependencies::lair_keystore_api::LairResult;
 use observability::Output;
@@ -61,6 +67,30 @@ struct Opt {
         help = "Display version information such as git revision and HDK version"
     )]
     build_info: bool,
+
+    /// Run a throwaway conductor for development.
+    /// Installs the given hApp bundle for a newly generated agent and
+    /// attaches an app interface. The databases and keys are deleted
+    /// when the conductor exits. No config file is needed.
+    #[structopt(long, value_name = "BUNDLE", conflicts_with_all = &["config-path", "interactive"])]
+    dev: Option<PathBuf>,
+
+    /// With `--dev`, the port of the app interface.
+    /// By default a free port is chosen.
+    #[structopt(long, requires = "dev", default_value = "0")]
+    dev_app_port: u16,
+
+    /// With `--dev`, shut the conductor down after this many seconds.
+    /// By default it runs until it is interrupted.
+    #[structopt(long, requires = "dev")]
+    dev_timeout: Option<u64>,
+
+    /// Restore the databases from an archive made with the
+    /// `BackupConductor` admin call before starting the conductor.
+    /// The databases in the archive must not already exist in the
+    /// environment directory from the config.
+    #[structopt(long, value_name = "ARCHIVE", conflicts_with = "dev")]
+    restore: Option<PathBuf>,
 }
 
 fn main() {
@@ -86,6 +116,17 @@ async fn async_main() {
 
     kitsune_p2p_types::metrics::init_sys_info_poll();
 
+    if let Some(bundle_path) = opt.dev.clone() {
+        // The temporary directory has been cleaned up by the time
+        // `run_dev` returns, so it's safe to exit here.
+        if let Err(e) = run_dev(bundle_path, opt.dev_app_port, opt.dev_timeout).await {
+            error!("{:?}", e);
+            eprintln!("Error: {:?}", e);
+            std::process::exit(ERROR_CODE);
+        }
+        return;
+    }
+
     let conductor = conductor_handle_from_config_path(&opt).await;
 
     info!("Conductor successfully initialized.");
@@ -114,6 +155,85 @@ async fn async_main() {
     // conductor.kill().await
 }
 
+/// Run a throwaway conductor with a single app installed, for `--dev`.
+async fn run_dev(bundle_path: PathBuf, app_port: u16, timeout: Option<u64>) -> anyhow::Result<()> {
+    // Everything the conductor writes goes in here and is deleted on exit.
+    let dir = tempfile::tempdir().context("Could not create a temporary directory")?;
+    let config = ConductorConfig {
+        environment_path: dir.path().into(),
+        keystore: KeystoreConfig::DangerTestKeystore,
+        ..Default::default()
+    };
+    let conductor = Conductor::builder()
+        .config(config)
+        .build()
+        .await
+        .context("Could not initialize Conductor")?;
+
+    let (app_id, agent_key, port) =
+        match install_dev_app(conductor.clone(), bundle_path, app_port).await {
+            Ok(installed) => installed,
+            Err(e) => {
+                conductor.shutdown();
+                return Err(e.context("Couldn't install the app"));
+            }
+        };
+    println!("Installed app `{}` for agent {}", app_id, agent_key);
+    println!("App interface listening on port {}", port);
+    println!("{}", MAGIC_CONDUCTOR_READY_STRING);
+
+    let mut shutdown = conductor
+        .take_shutdown_handle()
+        .context("The shutdown handle has already been taken.")?;
+    let stop = async {
+        match timeout {
+            Some(secs) => {
+                tokio::time::sleep(std::time::Duration::from_secs(secs)).await;
+                println!("Dev conductor timed out after {}s", secs);
+                Ok(())
+            }
+            None => tokio::signal::ctrl_c()
+                .await
+                .context("Could not listen for ctrl-c"),
+        }
+    };
+    let result = tokio::select! {
+        result = &mut shutdown => result,
+        stopped = stop => {
+            conductor.shutdown();
+            let result = shutdown.await;
+            stopped?;
+            result
+        }
+    };
+    result.context("Failed to join the main task")??;
+    drop(dir);
+    Ok(())
+}
+
+async fn install_dev_app(
+    conductor: ConductorHandle,
+    bundle_path: PathBuf,
+    app_port: u16,
+) -> anyhow::Result<(InstalledAppId, AgentPubKey, u16)> {
+    let agent_key = conductor.keystore().new_sign_keypair_random().await?;
+    let app = conductor
+        .clone()
+        .install_app_bundle(InstallAppBundlePayload {
+            source: AppBundleSource::Path(bundle_path),
+            agent_key: agent_key.clone(),
+            installed_app_id: None,
+            membrane_proofs: Default::default(),
+            uid: None,
+            role_overrides: Default::default(),
+        })
+        .await?;
+    let app_id = app.id().clone();
+    conductor.clone().enable_app(app_id.clone()).await?;
+    let port = conductor.add_app_interface(app_port).await?;
+    Ok((app_id, agent_key, port))
+}
+
 fn vec_to_locked(mut pass_tmp: Vec<u8>) -> LairResult<sodoken::BufRead> {
     match sodoken::BufWrite::new_mem_locked(pass_tmp.len()) {
         Err(e) => {
@@ -240,6 +360,20 @@ async fn conductor_handle_from_config_path(opt: &Opt) -> ConductorHandle {
         }
     }
 
+    if let Some(archive) = &opt.restore {
+        match ConductorBackup::read(archive).and_then(|backup| backup.restore(&env_path)) {
+            Ok(()) => println!(
+                "Restored {} into {}.",
+                archive.display(),
+                env_path.display()
+            ),
+            Err(e) => {
+                println!("Couldn't restore backup: {}", e);
+                std::process::exit(ERROR_CODE);
+            }
+        }
+    }
+
     // Initialize the Conductor
     Conductor::builder()
         .config(config)
@@ -14,12 +14,14 @@
 // TODO: clean up allow(missing_docs) once parent is fully documented
 
 pub mod api;
+pub mod backup;
 mod cell;
 #[allow(clippy::module_inception)]
 #[allow(missing_docs)]
 pub mod conductor;
 #[allow(missing_docs)]
 pub mod config;
+pub mod dpki;
 pub mod entry_def_store;
 #[allow(missing_docs)]
 pub mod error;
@@ -36,8 +38,9 @@ pub mod space;
 pub mod state;
 
 pub use cell::error::CellError;
+pub use cell::quota::CellQuota;
 pub use cell::Cell;
 pub use conductor::Conductor;
 pub use conductor::ConductorBuilder;
-pub use conductor::{full_integration_dump, integration_dump};
+pub use conductor::{full_integration_dump, integration_dump, rejections_dump};
 pub use handle::ConductorHandle;
@@ -1,5 +1,6 @@
 //! The CellConductorApi allows Cells to talk to their Conductor
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::error::ConductorApiError;
@@ -11,6 +12,7 @@ use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
+use futures::future::BoxFuture;
 use holo_hash::DnaHash;
 use holochain_conductor_api::ZomeCall;
 use holochain_keystore::MetaLairClient;
@@ -43,6 +45,43 @@ impl CellConductorApi {
     }
 }
 
+/// How many reliable remote signals a cell may be sending at once.
+pub const MAX_RELIABLE_SIGNALS_IN_FLIGHT: usize = 256;
+
+/// Counts the reliable remote signals each cell is still sending.
+#[derive(Clone, Debug, Default)]
+pub struct ReliableSignals(Arc<parking_lot::Mutex<HashMap<CellId, usize>>>);
+
+/// A reliable remote signal counted as in flight until this is dropped.
+pub struct ReliableSignalInFlight(ReliableSignals, CellId);
+
+impl ReliableSignals {
+    /// Count a reliable remote signal from this cell as in flight.
+    /// Returns None if the cell already has
+    /// [`MAX_RELIABLE_SIGNALS_IN_FLIGHT`] in flight.
+    pub fn start(&self, cell_id: &CellId) -> Option<ReliableSignalInFlight> {
+        let mut in_flight = self.0.lock();
+        let count = in_flight.entry(cell_id.clone()).or_default();
+        if *count >= MAX_RELIABLE_SIGNALS_IN_FLIGHT {
+            return None;
+        }
+        *count += 1;
+        Some(ReliableSignalInFlight(self.clone(), cell_id.clone()))
+    }
+}
+
+impl Drop for ReliableSignalInFlight {
+    fn drop(&mut self) {
+        let mut in_flight = (self.0).0.lock();
+        if let Some(count) = in_flight.get_mut(&self.1) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(&self.1);
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl CellConductorApiT for CellConductorApi {
     fn cell_id(&self) -> &CellId {
@@ -100,7 +139,6 @@ impl CellConductorApiT for CellConductorApi {
         Ok(self
             .get_dna(dna_hash)
             .ok_or_else(|| ConductorApiError::DnaMissing(dna_hash.clone()))?
-            .dna_def()
             .get_zome(zome_name)?)
     }
 
@@ -192,6 +230,15 @@ pub trait CellConductorReadHandleT: Send + Sync {
         cell_id: &CellId,
         role_id: &AppRoleId,
     ) -> ConductorResult<Option<CellId>>;
+
+    /// Find the first running app this cell is in, along with the role
+    /// of this cell in that app.
+    async fn find_app_and_role(&self) -> ConductorResult<Option<(InstalledAppId, AppRoleId)>>;
+
+    /// Send a reliable remote signal from this cell in the conductor's
+    /// managed task set. Returns false without sending it if the cell
+    /// already has [`MAX_RELIABLE_SIGNALS_IN_FLIGHT`] in flight.
+    async fn spawn_reliable_signal(&self, task: BoxFuture<'static, ()>) -> ConductorResult<bool>;
 }
 
 #[async_trait]
@@ -231,4 +278,16 @@ impl CellConductorReadHandleT for CellConductorApi {
             .find_cell_with_role_alongside_cell(cell_id, role_id)
             .await
     }
+
+    async fn find_app_and_role(&self) -> ConductorResult<Option<(InstalledAppId, AppRoleId)>> {
+        self.conductor_handle
+            .find_app_and_role_of_cell(&self.cell_id)
+            .await
+    }
+
+    async fn spawn_reliable_signal(&self, task: BoxFuture<'static, ()>) -> ConductorResult<bool> {
+        self.conductor_handle
+            .spawn_reliable_signal(&self.cell_id, task)
+            .await
+    }
 }
@@ -130,13 +130,32 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 Ok(AdminResponse::DnaRegistered(hash))
             }
             CreateCloneCell(payload) => {
-                let cell_id = payload.cell_id();
-                self.conductor_handle
+                let cell_id = self
+                    .conductor_handle
                     .clone()
                     .create_clone_cell(*payload)
                     .await?;
                 Ok(AdminResponse::CloneCellCreated(cell_id))
             }
+            DisableCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .disable_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellDisabled)
+            }
+            MigrateCell(payload) => {
+                let cell_id = self.conductor_handle.clone().migrate_cell(*payload).await?;
+                Ok(AdminResponse::CellMigrated(cell_id))
+            }
+            RotateAppAgentKey { installed_app_id } => {
+                let agent_key = self
+                    .conductor_handle
+                    .clone()
+                    .rotate_app_agent_key(installed_app_id)
+                    .await?;
+                Ok(AdminResponse::AppAgentKeyRotated(agent_key))
+            }
             InstallApp(payload) => {
                 trace!(?payload.dnas);
                 let InstallAppPayload {
@@ -272,6 +291,22 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppDisabled)
             }
+            PauseCell { cell_id } => {
+                self.conductor_handle.pause_cell(cell_id).await?;
+                Ok(AdminResponse::CellPaused)
+            }
+            ResumeCell { cell_id } => {
+                let errors = self
+                    .conductor_handle
+                    .clone()
+                    .resume_cell(cell_id.clone())
+                    .await?
+                    .into_iter()
+                    .filter(|(id, _)| *id == cell_id)
+                    .map(|(cell_id, error)| (cell_id, error.to_string()))
+                    .collect();
+                Ok(AdminResponse::CellResumed { errors })
+            }
             StartApp { installed_app_id } => {
                 // TODO: check to see if app was actually started
                 let app = self
@@ -312,6 +347,48 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let dump = self.conductor_handle.dump_network_metrics(dna_hash).await?;
                 Ok(AdminResponse::NetworkMetricsDumped(dump))
             }
+            RequestGossipStatus { dna_hash } => {
+                let status = self.conductor_handle.gossip_status(dna_hash).await?;
+                Ok(AdminResponse::GossipStatusRequested(status))
+            }
+            RequestNetworkStatus => {
+                let statuses = self.conductor_handle.network_status().await?;
+                Ok(AdminResponse::NetworkStatusRequested(statuses))
+            }
+            DumpConductorState => {
+                let dump = self.conductor_handle.dump_conductor_state().await?;
+                Ok(AdminResponse::ConductorStateDumped(Box::new(dump)))
+            }
+            DumpWasmCacheStats => {
+                let stats = self.conductor_handle.wasm_cache_stats().await?;
+                Ok(AdminResponse::WasmCacheStatsDumped(stats))
+            }
+            PurgeWasmCache => {
+                self.conductor_handle.purge_wasm_cache().await?;
+                Ok(AdminResponse::WasmCachePurged)
+            }
+            BackupConductor { path } => {
+                self.conductor_handle.backup(path).await?;
+                Ok(AdminResponse::ConductorBackedUp)
+            }
+            CompactDatabases => {
+                let compactions = self.conductor_handle.compact_databases().await?;
+                Ok(AdminResponse::DatabasesCompacted(compactions))
+            }
+            CountValidationReceipts { cell_id } => {
+                let counts = self
+                    .conductor_handle
+                    .count_validation_receipts(&cell_id)
+                    .await?;
+                Ok(AdminResponse::ValidationReceiptsCounted(counts))
+            }
+            QueryIntegratedDhtOps { dna_hash, query } => {
+                let page = self
+                    .conductor_handle
+                    .query_integrated_dht_ops(&dna_hash, query)
+                    .await?;
+                Ok(AdminResponse::IntegratedDhtOpsQueried(page))
+            }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -320,6 +397,20 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let r = self.conductor_handle.get_agent_infos(cell_id).await?;
                 Ok(AdminResponse::AgentInfoRequested(r))
             }
+            BlockPeer {
+                dna_hash,
+                target,
+                until,
+            } => {
+                self.conductor_handle
+                    .block_peer(dna_hash, target, until)
+                    .await?;
+                Ok(AdminResponse::PeerBlocked)
+            }
+            UnblockPeer { dna_hash, target } => {
+                self.conductor_handle.unblock_peer(dna_hash, target).await?;
+                Ok(AdminResponse::PeerUnblocked)
+            }
 
             // deprecated aliases
             ListActiveApps => {
@@ -348,6 +439,25 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::RecordsAdded)
             }
+            GrantZomeCallCapability(payload) => {
+                let secret = self
+                    .conductor_handle
+                    .grant_zome_call_capability(*payload)
+                    .await?;
+                Ok(AdminResponse::ZomeCallCapabilityGranted(secret))
+            }
+            ListCapGrants { cell_id } => Ok(AdminResponse::CapGrantsListed(
+                self.conductor_handle.list_cap_grants(&cell_id).await?,
+            )),
+            RevokeCapGrant {
+                cell_id,
+                action_hash,
+            } => {
+                self.conductor_handle
+                    .revoke_cap_grant(&cell_id, action_hash)
+                    .await?;
+                Ok(AdminResponse::CapGrantRevoked)
+            }
         }
     }
 }
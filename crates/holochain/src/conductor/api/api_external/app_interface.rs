@@ -2,8 +2,11 @@ use super::InterfaceApi;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::SignalSubscriptions;
+use crate::conductor::interface::ZomeCallSigningError;
 use crate::conductor::ConductorHandle;
 
 use holochain_serialized_bytes::prelude::*;
@@ -12,6 +15,9 @@ use holochain_types::prelude::*;
 
 pub use holochain_conductor_api::*;
 
+/// How many calls of a concurrent [`AppRequest::ZomeCallBatch`] are made at once.
+pub const MAX_CONCURRENT_BATCH_CALLS: usize = 16;
+
 /// The interface that a Conductor exposes to the outside world.
 #[async_trait::async_trait]
 pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
@@ -41,12 +47,98 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+    signal_subscriptions: SignalSubscriptions,
 }
 
 impl RealAppInterfaceApi {
     /// Create a new instance from a shared Conductor reference
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            signal_subscriptions: Default::default(),
+        }
+    }
+
+    /// A copy of this api for a new connection, which starts out
+    /// subscribed to every signal.
+    pub fn for_connection(&self) -> Self {
+        Self::new(self.conductor_handle.clone())
+    }
+
+    /// The signals this connection has subscribed to.
+    pub fn signal_subscriptions(&self) -> &SignalSubscriptions {
+        &self.signal_subscriptions
+    }
+
+    /// Check that a cell is part of an app, so an app request can only
+    /// act on the cells of the app it names.
+    async fn check_app_cell(
+        &self,
+        installed_app_id: &InstalledAppId,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<()> {
+        if self
+            .conductor_handle
+            .app_cell_ids(installed_app_id)
+            .await?
+            .contains(cell_id)
+        {
+            Ok(())
+        } else {
+            Err(ConductorError::CellNotInApp(cell_id.clone(), installed_app_id.clone()).into())
+        }
+    }
+
+    /// Make a zome call which has been allowed through the interface,
+    /// turning its result into a response.
+    async fn call_zome(&self, call: ZomeCall) -> ConductorApiResult<AppResponse> {
+        match self.conductor_handle.call_zome(call.clone()).await? {
+            Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCall(Box::new(output))),
+            Ok(ZomeCallResponse::Unauthorized(_, _, _, _)) => Ok(AppResponse::Error(
+                ExternalApiWireError::ZomeCallUnauthorized(format!(
+                    "No capabilities grant has been committed that allows the CapSecret {:?} to call the function {} in zome {}",
+                    call.cap_secret, call.fn_name, call.zome_name
+                )),
+            )),
+            Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
+                "Interface zome calls should never be routed to the network. This is a bug. Got {}",
+                e
+            ),
+            Ok(ZomeCallResponse::CountersigningSession(e)) => Ok(AppResponse::Error(
+                ExternalApiWireError::CountersigningSessionError(format!(
+                    "A countersigning session has failed to start on this zome call because: {}",
+                    e
+                )),
+            )),
+            Err(e) => Ok(AppResponse::Error(e.into())),
+        }
+    }
+
+    /// Carry out a cap grant request whose signature has been checked,
+    /// as long as its cell is part of the app it names.
+    async fn cap_grant_request(&self, request: CapGrantRequest) -> ConductorApiResult<AppResponse> {
+        self.check_app_cell(request.installed_app_id(), request.cell_id())
+            .await?;
+        match request {
+            CapGrantRequest::Create { payload, .. } => Ok(AppResponse::CapGrantCreated(
+                self.conductor_handle
+                    .grant_zome_call_capability(payload)
+                    .await?,
+            )),
+            CapGrantRequest::List { cell_id, .. } => Ok(AppResponse::CapGrantsListed(
+                self.conductor_handle.list_cap_grants(&cell_id).await?,
+            )),
+            CapGrantRequest::Revoke {
+                cell_id,
+                action_hash,
+                ..
+            } => {
+                self.conductor_handle
+                    .revoke_cap_grant(&cell_id, action_hash)
+                    .await?;
+                Ok(AppResponse::CapGrantRevoked)
+            }
+        }
     }
 }
 
@@ -79,28 +171,96 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     })
             }
             AppRequest::ZomeCall(call) => {
-                match self.conductor_handle.call_zome(*call.clone()).await? {
-                    Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCall(Box::new(output))),
-                    Ok(ZomeCallResponse::Unauthorized(_, _, _, _)) => Ok(AppResponse::Error(
-                        ExternalApiWireError::ZomeCallUnauthorized(format!(
-                            "No capabilities grant has been committed that allows the CapSecret {:?} to call the function {} in zome {}",
-                            call.cap_secret, call.fn_name, call.zome_name
-                        )),
+                if !self.conductor_handle.allows_unsigned_zome_calls() {
+                    return Ok(AppResponse::Error(
+                        ExternalApiWireError::ZomeCallUnauthorized(
+                            ZomeCallSigningError::SignatureRequired.to_string(),
+                        ),
+                    ));
+                }
+                self.call_zome(*call).await
+            }
+            AppRequest::SignedZomeCall(signed) => {
+                match self.conductor_handle.verify_signed_zome_call(&signed).await {
+                    Ok(()) => self.call_zome(signed.call).await,
+                    Err(ZomeCallSigningError::Database(e)) => Err(e.into()),
+                    Err(e) => Ok(AppResponse::Error(
+                        ExternalApiWireError::ZomeCallUnauthorized(e.to_string()),
                     )),
-                    Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
-                        "Interface zome calls should never be routed to the network. This is a bug. Got {}",
-                        e
-                    ),
-                    Ok(ZomeCallResponse::CountersigningSession(e)) => Ok(AppResponse::Error(
-                        ExternalApiWireError::CountersigningSessionError(format!(
-                            "A countersigning session has failed to start on this zome call because: {}",
-                            e
-                        )),
+                }
+            }
+            AppRequest::ZomeCallBatch { calls, concurrent } => {
+                let calls = calls.into_iter().map(|call| {
+                    self.handle_app_request(match call {
+                        BatchZomeCall::Unsigned(call) => AppRequest::ZomeCall(Box::new(call)),
+                        BatchZomeCall::Signed(call) => AppRequest::SignedZomeCall(Box::new(call)),
+                    })
+                });
+                let responses = if concurrent {
+                    use futures::StreamExt;
+                    futures::stream::iter(calls)
+                        .buffered(MAX_CONCURRENT_BATCH_CALLS)
+                        .collect()
+                        .await
+                } else {
+                    let mut responses = Vec::new();
+                    for call in calls {
+                        responses.push(call.await);
+                    }
+                    responses
+                };
+                Ok(AppResponse::ZomeCallBatch(responses))
+            }
+            AppRequest::SignalSubscription(subscription) => {
+                let cells = self
+                    .conductor_handle
+                    .app_cell_ids(subscription.installed_app_id())
+                    .await?
+                    .into_iter()
+                    .filter(|cell_id| subscription.filters().allows(cell_id))
+                    .collect();
+                self.signal_subscriptions
+                    .subscribe(subscription.installed_app_id().clone(), cells);
+                Ok(AppResponse::SignalsSubscribed)
+            }
+            AppRequest::AckSignal { id } => Ok(AppResponse::SignalAcked(
+                self.conductor_handle.ack_signal(id).await,
+            )),
+            AppRequest::UndeliveredSignalCount { cell_id } => {
+                Ok(AppResponse::UndeliveredSignalCount(
+                    self.conductor_handle
+                        .undelivered_signal_count(&cell_id)
+                        .await,
+                ))
+            }
+            AppRequest::CreateCloneCell(payload) => {
+                let cell_id = self
+                    .conductor_handle
+                    .clone()
+                    .create_clone_cell(*payload)
+                    .await?;
+                Ok(AppResponse::CloneCellCreated(cell_id))
+            }
+            AppRequest::DisableCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .disable_clone_cell(*payload)
+                    .await?;
+                Ok(AppResponse::CloneCellDisabled)
+            }
+            AppRequest::SignedCapGrantRequest(signed) => {
+                match self
+                    .conductor_handle
+                    .verify_signed_cap_grant_request(&signed)
+                    .await
+                {
+                    Ok(()) => self.cap_grant_request(signed.request).await,
+                    Err(ZomeCallSigningError::Database(e)) => Err(e.into()),
+                    Err(e) => Ok(AppResponse::Error(
+                        ExternalApiWireError::CapGrantUnauthorized(e.to_string()),
                     )),
-                    Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
             AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
         }
     }
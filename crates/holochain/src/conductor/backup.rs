@@ -0,0 +1,345 @@
+//! Backups of all the databases of a conductor.
+//!
+//! A backup is taken while the conductor is running, using the SQLite online
+//! backup API so each database is copied consistently without stopping
+//! writes. Each copy is streamed into a tar archive file, which can be
+//! restored into the environment directory before the conductor starts.
+//!
+//! Every DNA with databases in the environment directory is backed up,
+//! including those of disabled apps whose spaces aren't loaded.
+//! Only the databases which exist are copied, without loading those spaces.
+//!
+//! Databases encrypted at rest stay encrypted in the archive, so restoring
+//! them needs the same lair keystore.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use super::space::Spaces;
+use holo_hash::DnaHash;
+use holochain_p2p::DnaHashExt;
+use holochain_sqlite::db::DbKindAuthored;
+use holochain_sqlite::db::DbKindCache;
+use holochain_sqlite::db::DbKindDht;
+use holochain_sqlite::db::DbKindP2pAgents;
+use holochain_sqlite::db::DbKindP2pMetrics;
+use holochain_sqlite::db::DbKindT;
+use holochain_sqlite::db::DbRead;
+use std::collections::BTreeSet;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The directory and file name prefix of each kind of per-DNA database.
+const DNA_DB_PREFIXES: [(&str, &str); 5] = [
+    ("authored", "authored-"),
+    ("dht", "dht-"),
+    ("cache", "cache-"),
+    ("p2p", "p2p_agent_store-"),
+    ("p2p", "p2p_metrics-"),
+];
+
+/// A backup archive of the databases of a conductor.
+///
+/// The archive is a tar file with an entry for each database,
+/// at its path relative to the environment directory.
+#[derive(Debug)]
+pub struct ConductorBackup {
+    archive: PathBuf,
+    /// The databases in the archive,
+    /// as paths relative to the environment directory.
+    pub files: Vec<PathBuf>,
+}
+
+impl ConductorBackup {
+    /// Snapshot the conductor, wasm, cell and p2p databases of a conductor
+    /// into an archive file, replacing any file already there.
+    pub async fn take(spaces: &Spaces, archive: &Path) -> ConductorResult<Self> {
+        let tmp = tempfile::tempdir()?;
+        // Write a temporary file first so a failed backup
+        // never leaves a truncated archive in place of a good one.
+        let mut part = archive.as_os_str().to_owned();
+        part.push(".part");
+        let part = PathBuf::from(part);
+        let mut writer = ArchiveWriter {
+            builder: tar::Builder::new(std::fs::File::create(&part)?),
+            files: Vec::new(),
+        };
+
+        writer = writer.add(spaces.conductor_db.as_ref(), tmp.path()).await?;
+        writer = writer.add(spaces.wasm_db.as_ref(), tmp.path()).await?;
+        for dna_hash in dnas_on_disk(spaces.db_dir.as_ref().as_ref())? {
+            // Only the databases already on disk are backed up. The spaces
+            // of disabled apps aren't loaded and missing databases aren't
+            // created just to be copied.
+            let dna_hash = Arc::new(dna_hash);
+            let space = dna_hash.to_kitsune();
+            writer = writer
+                .add_existing(spaces, DbKindAuthored(dna_hash.clone()), tmp.path())
+                .await?;
+            writer = writer
+                .add_existing(spaces, DbKindDht(dna_hash.clone()), tmp.path())
+                .await?;
+            writer = writer
+                .add_existing(spaces, DbKindCache(dna_hash), tmp.path())
+                .await?;
+            writer = writer
+                .add_existing(spaces, DbKindP2pAgents(space.clone()), tmp.path())
+                .await?;
+            writer = writer
+                .add_existing(spaces, DbKindP2pMetrics(space), tmp.path())
+                .await?;
+        }
+
+        let ArchiveWriter { builder, files } = writer;
+        tokio::task::spawn_blocking(move || builder.into_inner()?.sync_all())
+            .await
+            .map_err(ConductorError::other)??;
+        tokio::fs::rename(&part, archive).await?;
+        Ok(Self {
+            archive: archive.to_owned(),
+            files,
+        })
+    }
+
+    /// Open a backup archive file and list the databases in it.
+    pub fn read(archive: &Path) -> ConductorResult<Self> {
+        let mut files = Vec::new();
+        for entry in tar::Archive::new(std::fs::File::open(archive)?).entries()? {
+            files.push(entry?.path()?.into_owned());
+        }
+        Ok(Self {
+            archive: archive.to_owned(),
+            files,
+        })
+    }
+
+    /// Write the databases of this backup into an environment directory.
+    ///
+    /// Nothing is written if any of the databases already exist there,
+    /// so a backup can only be restored before the conductor is first started.
+    pub fn restore(&self, environment_path: &Path) -> ConductorResult<()> {
+        for path in &self.files {
+            // The archive might not come from this conductor,
+            // so don't let it write outside the environment directory.
+            if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+                return Err(ConductorError::BackupError(format!(
+                    "invalid database path {}",
+                    path.display()
+                )));
+            }
+            let path = environment_path.join(path);
+            if path.exists() {
+                return Err(ConductorError::BackupError(format!(
+                    "database {} already exists",
+                    path.display()
+                )));
+            }
+        }
+        for entry in tar::Archive::new(std::fs::File::open(&self.archive)?).entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.into_owned();
+            // The archive may have changed since it was read.
+            if !self.files.contains(&path) {
+                return Err(ConductorError::BackupError(format!(
+                    "unexpected database path {}",
+                    path.display()
+                )));
+            }
+            let path = environment_path.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry.unpack(path)?;
+        }
+        Ok(())
+    }
+}
+
+struct ArchiveWriter {
+    builder: tar::Builder<std::fs::File>,
+    files: Vec<PathBuf>,
+}
+
+impl ArchiveWriter {
+    /// Copy a database to a file in `tmp` and stream it into the archive,
+    /// so no more than one database is ever held in memory or on disk.
+    async fn add<Kind: DbKindT>(self, db: &DbRead<Kind>, tmp: &Path) -> ConductorResult<Self> {
+        let filename = db.kind().filename();
+        let dest = tmp.join(&filename);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        db.backup(dest.clone()).await?;
+
+        let Self {
+            mut builder,
+            mut files,
+        } = self;
+        let name = filename.clone();
+        let builder = tokio::task::spawn_blocking(move || {
+            builder.append_path_with_name(&dest, &name)?;
+            std::fs::remove_file(&dest)?;
+            std::io::Result::Ok(builder)
+        })
+        .await
+        .map_err(ConductorError::other)??;
+        files.push(filename);
+        Ok(Self { builder, files })
+    }
+
+    /// Add a database of a space if it exists.
+    async fn add_existing<Kind: DbKindT>(
+        self,
+        spaces: &Spaces,
+        kind: Kind,
+        tmp: &Path,
+    ) -> ConductorResult<Self> {
+        match spaces.open_existing_db(kind)? {
+            Some(db) => self.add(db.as_ref(), tmp).await,
+            None => Ok(self),
+        }
+    }
+}
+
+/// The DNAs with databases in the environment directory,
+/// whether or not their spaces are loaded.
+fn dnas_on_disk(environment_path: &Path) -> ConductorResult<BTreeSet<DnaHash>> {
+    let mut dnas = BTreeSet::new();
+    for (dir, prefix) in DNA_DB_PREFIXES {
+        let entries = match std::fs::read_dir(environment_path.join(dir)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let name = entry?.file_name();
+            let dna_hash = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(prefix))
+                .and_then(|name| name.strip_suffix(".sqlite3"))
+                .and_then(|hash| DnaHash::try_from(hash.to_string()).ok());
+            dnas.extend(dna_hash);
+        }
+    }
+    Ok(dnas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holochain_conductor_api::conductor::ConductorConfig;
+    use holochain_sqlite::db::DbKindAuthored;
+    use holochain_sqlite::db::ReadAccess;
+    use holochain_sqlite::prelude::DatabaseResult;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn backup_round_trip() {
+        let dna_hash = fixt!(DnaHash);
+        let conductor_env = tempfile::tempdir().unwrap();
+        let spaces = Spaces::new(&ConductorConfig {
+            environment_path: conductor_env.path().to_path_buf().into(),
+            ..Default::default()
+        })
+        .unwrap();
+        let space = spaces.get_or_create_space(&dna_hash).unwrap();
+        space
+            .authored_db
+            .async_commit(|txn| {
+                txn.execute("CREATE TABLE backed_up (x INTEGER)", [])?;
+                txn.execute("INSERT INTO backed_up (x) VALUES (7)", [])?;
+                DatabaseResult::Ok(())
+            })
+            .await
+            .unwrap();
+
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive = archive_dir.path().join("conductor.backup");
+        // A fresh `Spaces` has no spaces loaded, like a conductor
+        // whose app using the DNA is disabled.
+        let unloaded = Spaces::new(&ConductorConfig {
+            environment_path: conductor_env.path().to_path_buf().into(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(unloaded.get_from_spaces(|_| ()).is_empty());
+        let taken = ConductorBackup::take(&unloaded, &archive).await.unwrap();
+        assert!(!archive_dir.path().join("conductor.backup.part").exists());
+
+        let backup = ConductorBackup::read(&archive).unwrap();
+        assert_eq!(backup.files.len(), 7);
+        assert_eq!(backup.files, taken.files);
+
+        let env = tempfile::tempdir().unwrap();
+        backup.restore(env.path()).unwrap();
+        // Restoring over existing databases is refused.
+        assert!(backup.restore(env.path()).is_err());
+
+        let restored =
+            holochain_sqlite::db::DbWrite::test(env.path(), DbKindAuthored(Arc::new(dna_hash)))
+                .unwrap();
+        let x: i64 = restored
+            .async_reader(|txn| {
+                DatabaseResult::Ok(txn.query_row("SELECT x FROM backed_up", [], |row| row.get(0))?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(x, 7);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn backup_only_copies_existing_dbs() {
+        let dna_hash = Arc::new(fixt!(DnaHash));
+        let conductor_env = tempfile::tempdir().unwrap();
+        // Only the authored database of this DNA is on disk.
+        holochain_sqlite::db::DbWrite::test(conductor_env.path(), DbKindAuthored(dna_hash.clone()))
+            .unwrap();
+        let spaces = Spaces::new(&ConductorConfig {
+            environment_path: conductor_env.path().to_path_buf().into(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive = archive_dir.path().join("conductor.backup");
+        let taken = ConductorBackup::take(&spaces, &archive).await.unwrap();
+        assert_eq!(taken.files.len(), 3);
+        assert!(taken
+            .files
+            .contains(&DbKindAuthored(dna_hash.clone()).filename()));
+
+        // The space wasn't loaded and its other databases weren't created.
+        assert!(spaces.get_from_spaces(|_| ()).is_empty());
+        assert!(!conductor_env
+            .path()
+            .join(DbKindDht(dna_hash.clone()).filename())
+            .exists());
+        assert!(!conductor_env
+            .path()
+            .join(DbKindCache(dna_hash).filename())
+            .exists());
+    }
+
+    #[test]
+    fn restore_refuses_paths_outside_the_environment() {
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive = archive_dir.path().join("conductor.backup");
+        // tar::Builder refuses to write such paths itself,
+        // so write the header by hand.
+        let mut builder = tar::Builder::new(std::fs::File::create(&archive).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..17].copy_from_slice(b"../escape.sqlite3");
+        header.set_size(1);
+        header.set_cksum();
+        builder.append(&header, &[0u8][..]).unwrap();
+        builder.into_inner().unwrap();
+
+        let backup = ConductorBackup::read(&archive).unwrap();
+        assert_eq!(backup.files, vec![PathBuf::from("../escape.sqlite3")]);
+        let env = tempfile::tempdir().unwrap();
+        assert!(backup.restore(env.path()).is_err());
+        assert!(!env.path().join("../escape.sqlite3").exists());
+    }
+}
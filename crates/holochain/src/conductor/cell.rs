@@ -11,12 +11,14 @@ use super::space::Space;
 use crate::conductor::api::CellConductorApi;
 use crate::conductor::api::CellConductorApiT;
 use crate::conductor::cell::error::CellResult;
+use crate::conductor::cell::quota::CellQuota;
 use crate::conductor::handle::ConductorHandle;
 use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
 use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::ribosome::weigh_placeholder;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::countersigning_workflow::countersigning_success;
@@ -35,6 +37,7 @@ use hash_type::AnyDht;
 use holo_hash::*;
 use holochain_cascade::authority;
 use holochain_cascade::Cascade;
+use holochain_conductor_api::CapGrantInfo;
 use holochain_p2p::event::CountersigningSessionNegotiationMessage;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_sqlite::prelude::*;
@@ -46,6 +49,7 @@ use holochain_types::db_cache::DhtDbQueryCache;
 use holochain_types::prelude::*;
 use rusqlite::OptionalExtension;
 use rusqlite::Transaction;
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::sync::Arc;
@@ -55,10 +59,18 @@ use tracing_futures::Instrument;
 
 pub const INIT_MUTEX_TIMEOUT_SECS: u64 = 30;
 
+/// The chain lock held on the old chains of an app while its agent key is rotated.
+pub(super) const AGENT_KEY_ROTATION_LOCK: &[u8] = b"agent_key_rotation";
+
+/// How long the chain lock of a rotation is held for if it isn't released,
+/// e.g. because the conductor stopped during the rotation.
+const AGENT_KEY_ROTATION_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
 mod validation_package;
 
 #[allow(missing_docs)]
 pub mod error;
+pub mod quota;
 
 #[cfg(test)]
 mod gossip_test;
@@ -107,6 +119,10 @@ where
     holochain_p2p_cell: P2pCell,
     queue_triggers: QueueTriggers,
     init_mutex: tokio::sync::Mutex<()>,
+    /// Stops the queue consumers which belong only to this Cell
+    stop: sync::broadcast::Sender<()>,
+    /// Tracks this Cell's resource usage against the configured limits
+    quota: Arc<CellQuota>,
 }
 
 impl Cell {
@@ -137,6 +153,25 @@ impl Cell {
         };
 
         if has_genesis {
+            Self::check_source_chain(&id, &space).await?;
+
+            // This Cell's own queue consumers stop when either the Cell is
+            // cleaned up or the whole conductor shuts down.
+            let (stop, _) = sync::broadcast::channel(1);
+            {
+                let mut conductor_stop = managed_task_stop_broadcaster.subscribe();
+                let mut cell_stop = stop.subscribe();
+                let stop = stop.clone();
+                tokio::spawn(async move {
+                    tokio::select! {
+                        _ = conductor_stop.recv() => {
+                            stop.send(()).ok();
+                        }
+                        _ = cell_stop.recv() => (),
+                    }
+                });
+            }
+
             let (queue_triggers, initial_queue_triggers) = spawn_queue_consumer_tasks(
                 id.clone(),
                 holochain_p2p_cell.clone(),
@@ -144,9 +179,20 @@ impl Cell {
                 conductor_handle.clone(),
                 managed_task_add_sender,
                 managed_task_stop_broadcaster,
+                stop.clone(),
             )
             .await;
 
+            let quota = Arc::new(CellQuota::new(
+                id.clone(),
+                conductor_handle
+                    .get_config()
+                    .cell_quotas
+                    .clone()
+                    .unwrap_or_default(),
+                space.authored_db.clone().into(),
+            ));
+
             Ok((
                 Self {
                     id,
@@ -156,6 +202,8 @@ impl Cell {
                     holochain_p2p_cell,
                     queue_triggers,
                     init_mutex: Default::default(),
+                    stop,
+                    quota,
                 },
                 initial_queue_triggers,
             ))
@@ -164,6 +212,39 @@ impl Cell {
         }
     }
 
+    /// Check that the source chain of this Cell survived the last shutdown
+    /// intact. A chain which breaks after genesis is cut back to its last
+    /// consistent action, as long as nothing after that point could have
+    /// been seen by anyone else.
+    async fn check_source_chain(id: &CellId, space: &Space) -> CellResult<()> {
+        let author = id.agent_pubkey().clone();
+        let corruption =
+            match check_chain_integrity(space.authored_db.clone().into(), author.clone()).await? {
+                Some(corruption) => corruption,
+                None => return Ok(()),
+            };
+        let seq = corruption.seq();
+        if seq >= POST_GENESIS_SEQ_THRESHOLD
+            && repair_chain(
+                space.authored_db.clone(),
+                space.dht_db.clone().into(),
+                author,
+                seq,
+            )
+            .await?
+        {
+            warn!(
+                cell_id = ?id,
+                %corruption,
+                "Repaired the source chain by dropping every action from sequence number {}",
+                seq
+            );
+            Ok(())
+        } else {
+            Err(CellError::ChainCorrupted(id.clone(), corruption))
+        }
+    }
+
     /// Performs the Genesis workflow the Cell, ensuring that its initial
     /// records are committed. This is a prerequisite for any other interaction
     /// with the SourceChain
@@ -356,6 +437,7 @@ impl Cell {
             | QueryAgentInfoSignedNearBasis { .. }
             | QueryPeerDensity { .. }
             | Publish { .. }
+            | PublishWarrants { .. }
             | FetchOpData { .. } => {
                 // These events are aggregated over a set of cells, so need to be handled at the conductor level.
                 unreachable!()
@@ -579,7 +661,7 @@ impl Cell {
         if action.author() == self.id.agent_pubkey() {
             validation_package::get_as_author(
                 action,
-                self.space.authored_db.clone().into(),
+                self.space.authored_db.clone(),
                 self.dht_db().clone().into(),
                 self.space.dht_query_cache.clone(),
                 self.space.cache_db.clone(),
@@ -589,7 +671,7 @@ impl Cell {
             )
             .await
         } else {
-            validation_package::get_as_authority(action, db).await
+            validation_package::get_as_authority(action, db, self.space.cache_db.clone()).await
         }
     }
 
@@ -733,30 +815,41 @@ impl Cell {
             _ => None,
         };
 
-        // If no required receipt count was found then fallback to the default.
-        let required_validation_count = required_receipt_count.unwrap_or(
-            crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE,
-        );
+        // If no required receipt count was found then fallback to the
+        // conductor's default.
+        let required_validation_count = required_receipt_count.unwrap_or_else(|| {
+            self.conductor_handle
+                .get_config()
+                .default_required_validations
+                .unwrap_or(
+                    crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE,
+                )
+        });
 
+        // Receipts are kept with the authored ops so the publish workflow
+        // stops republishing an op once it has enough of them.
         self.space
-            .dht_db
+            .authored_db
             .async_commit(move |txn| {
+                let op_hash = receipt.receipt.dht_op_hash.clone();
+
+                // Add to receipts db
+                validation_receipts::add_if_unique(txn, receipt)?;
+
                 // Get the current count for this dhtop.
                 let receipt_count: usize = txn.query_row(
                     "SELECT COUNT(rowid) FROM ValidationReceipt WHERE op_hash = :op_hash",
                     named_params! {
-                        ":op_hash": receipt.receipt.dht_op_hash,
+                        ":op_hash": op_hash,
                     },
                     |row| row.get(0),
                 )?;
 
                 // If we have enough receipts then set receipts to complete.
                 if receipt_count >= required_validation_count as usize {
-                    set_receipts_complete(txn, &receipt.receipt.dht_op_hash, true)?;
+                    set_receipts_complete(txn, &op_hash, true)?;
                 }
-
-                // Add to receipts db
-                validation_receipts::add_if_unique(txn, receipt)
+                StateMutationResult::Ok(())
             })
             .await?;
 
@@ -800,6 +893,13 @@ impl Cell {
         call: ZomeCall,
         workspace_lock: Option<SourceChainWorkspace>,
     ) -> CellResult<ZomeCallResult> {
+        // Concurrent calls are limited for root zome calls only, so that
+        // calls between zomes of this Cell aren't counted twice.
+        let _permit = match workspace_lock {
+            Some(_) => None,
+            None => Some(self.quota.start_zome_call().await?),
+        };
+
         // Only check if init has run if this call is not coming from
         // an already running init call.
         if workspace_lock
@@ -845,6 +945,7 @@ impl Cell {
             signal_tx,
             conductor_handle,
             is_root_zome_call,
+            quota: self.quota.clone(),
         };
         Ok(call_zome_workflow(
             workspace_lock,
@@ -858,6 +959,325 @@ impl Cell {
         .map_err(Box::new)?)
     }
 
+    /// Commit a capability grant to this cell's source chain
+    /// on behalf of the conductor rather than a zome.
+    #[instrument(skip(self, grant))]
+    pub(super) async fn grant_zome_call_capability(
+        &self,
+        grant: ZomeCallCapGrant,
+    ) -> CellResult<ActionHash> {
+        let ribosome = self.get_ribosome()?;
+        let workspace = SourceChainWorkspace::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.cache().clone(),
+            self.conductor_api.keystore().clone(),
+            self.id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await?;
+        let source_chain = workspace.source_chain();
+
+        let entry = Entry::CapGrant(grant);
+        let action_builder = builder::Create {
+            entry_type: EntryType::CapGrant,
+            entry_hash: EntryHash::with_data_sync(&entry),
+        };
+        let action_hash = source_chain
+            .put_weighed(
+                action_builder,
+                Some(entry),
+                ChainTopOrdering::default(),
+                weigh_placeholder(),
+            )
+            .await?;
+
+        // The grant is validated like any other commit before it is written.
+        call_zome_workflow::inline_validation(
+            workspace.clone(),
+            self.holochain_p2p_cell.clone(),
+            self.conductor_handle.clone(),
+            ribosome,
+        )
+        .await
+        .map_err(Box::new)?;
+        workspace
+            .source_chain()
+            .flush(&self.holochain_p2p_cell)
+            .await?;
+
+        self.queue_triggers
+            .publish_dht_ops
+            .trigger(&"grant_zome_call_capability");
+        self.queue_triggers
+            .integrate_dht_ops
+            .trigger(&"grant_zome_call_capability");
+        Ok(action_hash)
+    }
+
+    /// The zome call capability grants on this cell's source chain
+    /// which haven't been updated or deleted, without their secrets.
+    pub(super) async fn list_cap_grants(&self) -> CellResult<Vec<CapGrantInfo>> {
+        Ok(self
+            .live_cap_grants()
+            .await?
+            .into_iter()
+            .map(|(action_hash, created_at, grant)| {
+                CapGrantInfo::new(action_hash, created_at, grant)
+            })
+            .collect())
+    }
+
+    /// The zome call capability grants on this cell's source chain
+    /// which haven't been updated or deleted, with the actions which wrote them.
+    async fn live_cap_grants(&self) -> CellResult<Vec<(ActionHash, Timestamp, ZomeCallCapGrant)>> {
+        let source_chain = SourceChain::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.conductor_api.keystore().clone(),
+            self.id.agent_pubkey().clone(),
+        )
+        .await?;
+
+        let mut replaced = HashSet::new();
+        for record in source_chain.query(ChainQueryFilter::new()).await? {
+            match record.action() {
+                Action::Update(update) => {
+                    replaced.insert(update.original_action_address.clone());
+                }
+                Action::Delete(delete) => {
+                    replaced.insert(delete.deletes_address.clone());
+                }
+                _ => (),
+            }
+        }
+        let grants = source_chain
+            .query(
+                ChainQueryFilter::new()
+                    .entry_type(EntryType::CapGrant)
+                    .include_entries(true),
+            )
+            .await?
+            .into_iter()
+            .filter(|record| !replaced.contains(record.action_address()))
+            .filter_map(|record| {
+                let action_hash = record.action_address().clone();
+                let created_at = record.action().timestamp();
+                match record.into_inner().1.into_option() {
+                    Some(Entry::CapGrant(grant)) => Some((action_hash, created_at, grant)),
+                    _ => None,
+                }
+            })
+            .collect();
+        Ok(grants)
+    }
+
+    /// Revoke a zome call capability grant by deleting it from this cell's source chain.
+    pub(super) async fn revoke_cap_grant(&self, action_hash: ActionHash) -> CellResult<ActionHash> {
+        let (grant_hash, _, grant) = self
+            .live_cap_grants()
+            .await?
+            .into_iter()
+            .find(|(grant_hash, _, _)| *grant_hash == action_hash)
+            .ok_or_else(|| CellError::CapGrantMissing(action_hash.clone()))?;
+
+        let ribosome = self.get_ribosome()?;
+        let workspace = SourceChainWorkspace::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.cache().clone(),
+            self.conductor_api.keystore().clone(),
+            self.id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await?;
+
+        let action_builder = builder::Delete {
+            deletes_address: grant_hash,
+            deletes_entry_address: EntryHash::with_data_sync(&Entry::CapGrant(grant)),
+        };
+        let action_hash = workspace
+            .source_chain()
+            .put_weightless(action_builder, None, ChainTopOrdering::default())
+            .await?;
+
+        // The revocation is validated like any other commit before it is written.
+        call_zome_workflow::inline_validation(
+            workspace.clone(),
+            self.holochain_p2p_cell.clone(),
+            self.conductor_handle.clone(),
+            ribosome,
+        )
+        .await
+        .map_err(Box::new)?;
+        workspace
+            .source_chain()
+            .flush(&self.holochain_p2p_cell)
+            .await?;
+
+        self.queue_triggers
+            .publish_dht_ops
+            .trigger(&"revoke_cap_grant");
+        self.queue_triggers
+            .integrate_dht_ops
+            .trigger(&"revoke_cap_grant");
+        Ok(action_hash)
+    }
+
+    /// Stage the record on this cell's source chain that its agent has moved
+    /// to a new key, by updating the agent key entry written at genesis to the
+    /// new key, and validate it. Nothing is written until the returned workspace
+    /// is passed to [`Cell::commit_agent_key_update`].
+    pub(super) async fn stage_agent_key_update(
+        &self,
+        new_key: AgentPubKey,
+    ) -> CellResult<SourceChainWorkspace> {
+        let ribosome = self.get_ribosome()?;
+        let workspace = SourceChainWorkspace::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.cache().clone(),
+            self.conductor_api.keystore().clone(),
+            self.id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await?;
+        let source_chain = workspace.source_chain();
+
+        let agent_record = source_chain
+            .query(ChainQueryFilter::new().entry_type(EntryType::AgentPubKey))
+            .await?
+            .into_iter()
+            .find(|record| {
+                record.action().entry_hash()
+                    == Some(&EntryHash::from(self.id.agent_pubkey().clone()))
+            })
+            .ok_or_else(|| CellError::AgentKeyEntryMissing(self.id.clone()))?;
+
+        let entry = Entry::Agent(new_key);
+        let action_builder = builder::Update {
+            original_entry_address: self.id.agent_pubkey().clone().into(),
+            original_action_address: agent_record.action_address().clone(),
+            entry_type: EntryType::AgentPubKey,
+            entry_hash: EntryHash::with_data_sync(&entry),
+        };
+        source_chain
+            .put_weighed(
+                action_builder,
+                Some(entry),
+                ChainTopOrdering::Strict,
+                weigh_placeholder(),
+            )
+            .await?;
+
+        call_zome_workflow::inline_validation(
+            workspace.clone(),
+            self.holochain_p2p_cell.clone(),
+            self.conductor_handle.clone(),
+            ribosome,
+        )
+        .await
+        .map_err(Box::new)?;
+        Ok(workspace)
+    }
+
+    /// Lock this cell's source chain for an agent key rotation, so no zome
+    /// call can write to it until [`Cell::unlock_after_agent_key_rotation`].
+    pub(super) async fn lock_for_agent_key_rotation(&self) -> CellResult<()> {
+        let expires_at =
+            (Timestamp::now() + AGENT_KEY_ROTATION_LOCK_TIMEOUT).map_err(SourceChainError::from)?;
+        self.source_chain()
+            .await?
+            .lock_chain(AGENT_KEY_ROTATION_LOCK.to_vec(), expires_at)
+            .await?;
+        Ok(())
+    }
+
+    /// Release the lock taken by [`Cell::lock_for_agent_key_rotation`].
+    pub(super) async fn unlock_after_agent_key_rotation(&self) -> CellResult<()> {
+        self.source_chain().await?.unlock_chain().await?;
+        Ok(())
+    }
+
+    /// Write an agent key update staged by [`Cell::stage_agent_key_update`].
+    /// It isn't published or held in the DHT database until
+    /// [`Cell::publish_agent_key_update`] is called, so it can still be taken
+    /// back with [`Cell::revert_agent_key_update`].
+    /// Returns the sequence number of the update on the chain.
+    pub(super) async fn commit_agent_key_update(
+        &self,
+        workspace: SourceChainWorkspace,
+    ) -> CellResult<u32> {
+        let seq = workspace.source_chain().chain_head()?.1;
+        workspace
+            .source_chain()
+            .flush_withheld(&self.holochain_p2p_cell, AGENT_KEY_ROTATION_LOCK.to_vec())
+            .await?;
+        Ok(seq)
+    }
+
+    /// Drop an unpublished agent key update at this sequence number from
+    /// this cell's source chain. Returns whether it was dropped.
+    pub(super) async fn revert_agent_key_update(&self, seq: u32) -> CellResult<bool> {
+        Ok(repair_chain(
+            self.authored_db().clone(),
+            self.dht_db().clone().into(),
+            self.id.agent_pubkey().clone(),
+            seq,
+        )
+        .await?)
+    }
+
+    /// Publish and integrate the agent key update committed at this
+    /// sequence number.
+    pub(super) async fn publish_agent_key_update(&self, seq: u32) -> CellResult<()> {
+        release_withheld_action(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            &self.space.dht_query_cache,
+            &self.holochain_p2p_cell,
+            self.id.agent_pubkey().clone(),
+            seq,
+        )
+        .await?;
+        self.queue_triggers
+            .publish_dht_ops
+            .trigger(&"publish_agent_key_update");
+        self.queue_triggers
+            .integrate_dht_ops
+            .trigger(&"publish_agent_key_update");
+        Ok(())
+    }
+
+    async fn source_chain(&self) -> CellResult<SourceChain> {
+        Ok(SourceChain::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.conductor_api.keystore().clone(),
+            self.id.agent_pubkey().clone(),
+        )
+        .await?)
+    }
+
+    /// The membrane proof this cell's agent joined the network with at genesis.
+    pub(super) async fn membrane_proof(&self) -> CellResult<Option<MembraneProof>> {
+        Ok(self
+            .source_chain()
+            .await?
+            .query(ChainQueryFilter::new().action_type(ActionType::AgentValidationPkg))
+            .await?
+            .into_iter()
+            .find_map(|record| match record.action() {
+                Action::AgentValidationPkg(pkg) => pkg.membrane_proof.clone(),
+                _ => None,
+            }))
+    }
+
     /// Check if each Zome's init callback has been run, and if not, run it.
     #[tracing::instrument(skip(self))]
     async fn check_or_run_zome_init(&self) -> CellResult<()> {
@@ -918,23 +1338,18 @@ impl Cell {
         Ok(())
     }
 
-    /// Clean up long-running managed tasks.
-    //
-    // FIXME: this should ensure that the long-running managed tasks,
-    //        i.e. the queue consumers, are stopped. Currently, they
-    //        will continue running because we have no way to target a specific
-    //        Cell's tasks for shutdown.
-    //
-    //        Consider using a separate TaskManager for each Cell, so that all
-    //        of a Cell's tasks can be shut down at once. Perhaps the Conductor
-    //        TaskManager can have these Cell TaskManagers as children.
-    //        [ B-04176 ]
+    /// Stop the queue consumers of this Cell and leave the network.
+    ///
+    /// The consumers and databases shared with the other cells of the dna
+    /// are left alone, see [`Spaces::remove_space`](super::space::Spaces::remove_space).
     pub async fn cleanup(&self) -> CellResult<()> {
         use holochain_p2p::HolochainP2pDnaT;
+        // There may be no consumers left to stop.
+        self.stop.send(()).ok();
         self.holochain_p2p_dna()
             .leave(self.id.agent_pubkey().clone())
             .await?;
-        tracing::info!("Cell removed, but cleanup is not yet fully implemented.");
+        tracing::debug!(cell_id = ?self.id, "Cell cleaned up");
         Ok(())
     }
 
@@ -1,3 +1,4 @@
+use super::quota::CellQuotaKind;
 use super::INIT_MUTEX_TIMEOUT_SECS;
 use crate::conductor::entry_def_store::error::EntryDefStoreError;
 use crate::conductor::{api::error::ConductorApiError, error::ConductorError};
@@ -8,6 +9,7 @@ use crate::core::SourceChainError;
 use holochain_cascade::error::CascadeError;
 use holochain_p2p::HolochainP2pError;
 use holochain_sqlite::error::DatabaseError;
+use holochain_state::source_chain::ChainCorruption;
 use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 
@@ -75,6 +77,14 @@ pub enum CellError {
     StateQueryError(#[from] holochain_state::query::StateQueryError),
     #[error(transparent)]
     StateMutationError(#[from] holochain_state::mutations::StateMutationError),
+    #[error("The cell {0:?} has exceeded its {1:?} quota")]
+    QuotaExceeded(CellId, CellQuotaKind),
+    #[error("The source chain of the cell {0:?} is corrupted and can't be repaired: {1}")]
+    ChainCorrupted(CellId, ChainCorruption),
+    #[error("The source chain of the cell {0:?} has no agent key entry to update")]
+    AgentKeyEntryMissing(CellId),
+    #[error("There is no capability grant written by the action {0:?} which hasn't been revoked")]
+    CapGrantMissing(ActionHash),
 }
 
 pub type CellResult<T> = Result<T, CellError>;
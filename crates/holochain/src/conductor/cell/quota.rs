@@ -0,0 +1,354 @@
+//! Enforcement of the per-cell resource limits set in
+//! [`CellQuotaConfig`].
+
+use super::error::CellError;
+use super::error::CellResult;
+use crate::conductor::config::CellQuotaConfig;
+use holochain_sqlite::prelude::*;
+use holochain_types::prelude::*;
+use rusqlite::named_params;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+/// The window over which zome call time is limited.
+const WASM_TIME_WINDOW: Duration = Duration::from_secs(60);
+
+/// The quota a cell has run over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellQuotaKind {
+    /// The cell has authored more than `max_storage_bytes`.
+    Storage,
+    /// The cell is already running `max_concurrent_zome_calls`.
+    ConcurrentZomeCalls,
+    /// The cell has used up `max_wasm_time_per_minute_ms`.
+    WasmTime,
+}
+
+/// Tracks the resources used by a single cell against its limits.
+pub struct CellQuota {
+    cell_id: CellId,
+    config: CellQuotaConfig,
+    authored_db: DbRead<DbKindAuthored>,
+    in_flight: Arc<AtomicUsize>,
+    wasm_time: parking_lot::Mutex<WasmTime>,
+    /// The bytes this cell has authored. Loaded from the database the first
+    /// time it's needed, then kept up to date as zome calls are flushed.
+    storage_bytes: tokio::sync::OnceCell<AtomicU64>,
+}
+
+struct WasmTime {
+    window_start: Instant,
+    used: Duration,
+}
+
+/// Holds one of a cell's concurrent zome call slots until dropped.
+#[derive(Debug)]
+pub struct ZomeCallPermit(Arc<AtomicUsize>);
+
+impl Drop for ZomeCallPermit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl CellQuota {
+    /// Create the quota tracker for a cell.
+    pub fn new(
+        cell_id: CellId,
+        config: CellQuotaConfig,
+        authored_db: DbRead<DbKindAuthored>,
+    ) -> Self {
+        Self {
+            cell_id,
+            config,
+            authored_db,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            wasm_time: parking_lot::Mutex::new(WasmTime {
+                window_start: Instant::now(),
+                used: Duration::ZERO,
+            }),
+            storage_bytes: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// Check that the cell is within its limits and take a slot for a new
+    /// zome call. The slot is released when the permit is dropped.
+    pub async fn start_zome_call(&self) -> CellResult<ZomeCallPermit> {
+        self.check_wasm_time(Instant::now())?;
+        if let Some(max) = self.config.max_storage_bytes {
+            if self.storage_bytes().await? >= max {
+                return Err(self.exceeded(CellQuotaKind::Storage));
+            }
+        }
+        self.take_zome_call_slot()
+    }
+
+    /// Add the time a zome call spent running wasm to the cell's usage.
+    pub fn record_wasm_time(&self, elapsed: Duration) {
+        self.record_wasm_time_at(Instant::now(), elapsed)
+    }
+
+    /// Check that writing these records keeps the cell within its limits
+    /// and reserve the storage they need.
+    /// Call this before a zome call is flushed so that a single call can't
+    /// take the cell over its limits. If the flush fails, give the storage
+    /// back with [`CellQuota::release_storage`].
+    /// Returns the number of bytes reserved.
+    pub async fn reserve_flush(&self, records: &[Record]) -> CellResult<u64> {
+        self.check_wasm_time(Instant::now())?;
+        let bytes = records_bytes(records)?;
+        self.storage_counter()
+            .await?
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                let total = used.saturating_add(bytes);
+                match self.config.max_storage_bytes {
+                    Some(max) if total > max => None,
+                    _ => Some(total),
+                }
+            })
+            .map_err(|_| self.exceeded(CellQuotaKind::Storage))?;
+        Ok(bytes)
+    }
+
+    /// Give back storage reserved for a flush that didn't happen.
+    pub fn release_storage(&self, bytes: u64) {
+        if let Some(counter) = self.storage_bytes.get() {
+            counter.fetch_sub(bytes, Ordering::AcqRel);
+        }
+    }
+
+    /// The number of zome calls currently running in this cell.
+    pub fn in_flight_zome_calls(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// The number of bytes of actions and entries this cell has authored.
+    pub async fn storage_bytes(&self) -> CellResult<u64> {
+        Ok(self.storage_counter().await?.load(Ordering::Acquire))
+    }
+
+    async fn storage_counter(&self) -> CellResult<&AtomicU64> {
+        self.storage_bytes
+            .get_or_try_init(|| async {
+                let author = self.cell_id.agent_pubkey().clone();
+                let bytes: i64 = self
+                    .authored_db
+                    .async_reader(move |txn| {
+                        txn.query_row(
+                            holochain_sqlite::sql::sql_cell::AUTHORED_BYTES,
+                            named_params! { ":author": author },
+                            |row| row.get(0),
+                        )
+                        .map_err(DatabaseError::from)
+                    })
+                    .await?;
+                CellResult::Ok(AtomicU64::new(bytes as u64))
+            })
+            .await
+    }
+
+    fn take_zome_call_slot(&self) -> CellResult<ZomeCallPermit> {
+        let max = self.config.max_concurrent_zome_calls.unwrap_or(usize::MAX);
+        self.in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then(|| n + 1)
+            })
+            .map_err(|_| self.exceeded(CellQuotaKind::ConcurrentZomeCalls))?;
+        Ok(ZomeCallPermit(self.in_flight.clone()))
+    }
+
+    fn check_wasm_time(&self, now: Instant) -> CellResult<()> {
+        if let Some(max) = self.config.max_wasm_time_per_minute_ms {
+            let mut wasm_time = self.wasm_time.lock();
+            wasm_time.roll_window(now);
+            if wasm_time.used >= Duration::from_millis(max) {
+                return Err(self.exceeded(CellQuotaKind::WasmTime));
+            }
+        }
+        Ok(())
+    }
+
+    fn record_wasm_time_at(&self, now: Instant, elapsed: Duration) {
+        let mut wasm_time = self.wasm_time.lock();
+        wasm_time.roll_window(now);
+        wasm_time.used += elapsed;
+    }
+
+    fn exceeded(&self, kind: CellQuotaKind) -> CellError {
+        CellError::QuotaExceeded(self.cell_id.clone(), kind)
+    }
+}
+
+/// The bytes these records take up in the database,
+/// counted the same way as the authored bytes query.
+fn records_bytes(records: &[Record]) -> CellResult<u64> {
+    let mut bytes = 0;
+    for record in records {
+        let action = SignedAction(record.action().clone(), record.signature().clone());
+        bytes += holochain_serialized_bytes::encode(&action)?.len() as u64;
+        if let Some(entry) = record.entry().as_option() {
+            bytes += holochain_serialized_bytes::encode(entry)?.len() as u64;
+        }
+    }
+    Ok(bytes)
+}
+
+impl WasmTime {
+    fn roll_window(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.window_start) >= WASM_TIME_WINDOW {
+            self.window_start = now;
+            self.used = Duration::ZERO;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holochain_state::test_utils::test_authored_db;
+    use holochain_state::test_utils::TestDb;
+    use holochain_types::test_utils::fake_cell_id;
+    use matches::assert_matches;
+
+    fn quota(config: CellQuotaConfig) -> (CellQuota, TestDb<DbKindAuthored>) {
+        let db = test_authored_db();
+        (
+            CellQuota::new(fake_cell_id(1), config, db.to_db().into()),
+            db,
+        )
+    }
+
+    fn record(author: &AgentPubKey) -> Record {
+        let mut create = fixt!(Create);
+        create.author = author.clone();
+        let action = SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(create.into()),
+            fixt!(Signature),
+        );
+        Record::new(action, Some(Entry::App(fixt!(AppEntryBytes))))
+    }
+
+    #[test]
+    fn concurrent_zome_calls_are_limited() {
+        let (quota, _db) = quota(CellQuotaConfig {
+            max_concurrent_zome_calls: Some(2),
+            ..Default::default()
+        });
+        let a = quota.take_zome_call_slot().unwrap();
+        let _b = quota.take_zome_call_slot().unwrap();
+        assert_matches!(
+            quota.take_zome_call_slot(),
+            Err(CellError::QuotaExceeded(
+                _,
+                CellQuotaKind::ConcurrentZomeCalls
+            ))
+        );
+        assert_eq!(quota.in_flight_zome_calls(), 2);
+
+        drop(a);
+        assert_eq!(quota.in_flight_zome_calls(), 1);
+        assert!(quota.take_zome_call_slot().is_ok());
+    }
+
+    #[test]
+    fn wasm_time_is_limited_per_window() {
+        let (quota, _db) = quota(CellQuotaConfig {
+            max_wasm_time_per_minute_ms: Some(100),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        quota.record_wasm_time_at(start, Duration::from_millis(60));
+        assert!(quota.check_wasm_time(start).is_ok());
+
+        quota.record_wasm_time_at(start, Duration::from_millis(60));
+        assert_matches!(
+            quota.check_wasm_time(start),
+            Err(CellError::QuotaExceeded(_, CellQuotaKind::WasmTime))
+        );
+
+        // - The usage resets once the window has passed
+        assert!(quota.check_wasm_time(start + WASM_TIME_WINDOW).is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wasm_time_is_enforced_at_flush() {
+        let (quota, _db) = quota(CellQuotaConfig {
+            max_wasm_time_per_minute_ms: Some(100),
+            ..Default::default()
+        });
+        assert!(quota.start_zome_call().await.is_ok());
+
+        // - The call ran over the limit so its writes are refused.
+        quota.record_wasm_time(Duration::from_millis(150));
+        assert_matches!(
+            quota.reserve_flush(&[]).await,
+            Err(CellError::QuotaExceeded(_, CellQuotaKind::WasmTime))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn storage_is_counted_from_the_database() {
+        let (quota, db) = quota(CellQuotaConfig::default());
+        let mut create = fixt!(Create);
+        create.author = quota.cell_id.agent_pubkey().clone();
+        let action = SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(create.into()),
+            fixt!(Signature),
+        );
+        db.to_db()
+            .async_commit(move |txn| holochain_state::mutations::insert_action(txn, &action))
+            .await
+            .unwrap();
+
+        assert!(quota.storage_bytes().await.unwrap() > 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn storage_is_enforced_at_flush() {
+        let author = fake_cell_id(1).agent_pubkey().clone();
+        let one = records_bytes(&[record(&author)]).unwrap();
+        let (quota, _db) = quota(CellQuotaConfig {
+            max_storage_bytes: Some(one * 2),
+            ..Default::default()
+        });
+        assert_eq!(quota.storage_bytes().await.unwrap(), 0);
+
+        // - A flush that fits is reserved and counted.
+        assert_eq!(quota.reserve_flush(&[record(&author)]).await.unwrap(), one);
+        assert_eq!(quota.storage_bytes().await.unwrap(), one);
+
+        // - A single flush can't take the cell over the limit.
+        assert_matches!(
+            quota
+                .reserve_flush(&[record(&author), record(&author)])
+                .await,
+            Err(CellError::QuotaExceeded(_, CellQuotaKind::Storage))
+        );
+        assert_eq!(quota.storage_bytes().await.unwrap(), one);
+
+        // - Storage for a flush that failed is given back.
+        let reserved = quota.reserve_flush(&[record(&author)]).await.unwrap();
+        assert_matches!(
+            quota.start_zome_call().await,
+            Err(CellError::QuotaExceeded(_, CellQuotaKind::Storage))
+        );
+        quota.release_storage(reserved);
+        assert_eq!(quota.storage_bytes().await.unwrap(), one);
+        assert!(quota.start_zome_call().await.is_ok());
+    }
+
+    #[test]
+    fn no_limits_by_default() {
+        let (quota, _db) = quota(CellQuotaConfig::default());
+        quota.record_wasm_time(Duration::from_secs(3600));
+        assert!(quota.check_wasm_time(Instant::now()).is_ok());
+        let _permits: Vec<_> = (0..100)
+            .map(|_| quota.take_zome_call_slot().unwrap())
+            .collect();
+    }
+}
@@ -63,7 +63,7 @@ async fn test_cell_handle_publish() {
     .await
     .unwrap();
 
-    let (add_task_sender, shutdown) = spawn_task_manager(mock_handle.clone());
+    let (add_task_sender, shutdown) = spawn_task_manager(mock_handle.clone(), Default::default());
     let (stop_tx, _) = sync::broadcast::channel(1);
 
     let (_cell, _) = super::Cell::create(
@@ -5,6 +5,7 @@ use crate::core::ribosome::RibosomeT;
 use crate::core::workflow::app_validation_workflow::validation_package::get_as_author_custom;
 use crate::core::workflow::app_validation_workflow::validation_package::get_as_author_full;
 use crate::core::workflow::app_validation_workflow::validation_package::get_as_author_sub_chain;
+use crate::core::workflow::app_validation_workflow::validation_package::required_validation_type;
 use holochain_cascade::Cascade;
 use holochain_p2p::HolochainP2pDna;
 use holochain_types::db_cache::DhtDbQueryCache;
@@ -23,7 +24,7 @@ use holochain_zome_types::ActionHashed;
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn get_as_author(
     action_hashed: ActionHashed,
-    authored_db: DbRead<DbKindAuthored>,
+    authored_db: DbWrite<DbKindAuthored>,
     dht_db: DbRead<DbKindDht>,
     dht_db_cache: DhtDbQueryCache,
     cache: DbWrite<DbKindCache>,
@@ -37,7 +38,7 @@ pub(super) async fn get_as_author(
     // TODO: evaluate if we even need to use a source chain here
     // vs directly querying the database.
     let mut source_chain = SourceChainRead::new(
-        authored_db.clone(),
+        authored_db.clone().into(),
         dht_db.clone(),
         dht_db_cache.clone(),
         conductor_handle.keystore().clone(),
@@ -57,15 +58,13 @@ pub(super) async fn get_as_author(
     };
 
     // Get the required validation package
-    // FIXME: Remove this completely.
-    let required_validation_type = RequiredValidationType::default();
+    let required_validation_type =
+        required_validation_type(action, ribosome.dna_def().as_hash(), conductor_handle).await;
 
     // Gather the package
     match required_validation_type {
-        RequiredValidationType::Record => {
-            // TODO: I'm not sure if we should handle this case, it seems like they should already have the record
-            Ok(None.into())
-        }
+        // Validators already have the record
+        RequiredValidationType::Record => Ok(None.into()),
         RequiredValidationType::SubChain => Ok(Some(
             get_as_author_sub_chain(action_seq, app_entry_type, &source_chain).await?,
         )
@@ -74,14 +73,17 @@ pub(super) async fn get_as_author(
             Ok(Some(get_as_author_full(action_seq, &source_chain).await?).into())
         }
         RequiredValidationType::Custom => {
-            let cascade = Cascade::empty().with_authored(authored_db.clone());
+            let cascade = Cascade::empty().with_authored(authored_db.clone().into());
 
-            if let Some(records) = cascade.get_validation_package_local(action_hashed.as_hash())? {
-                return Ok(Some(ValidationPackage::new(records)).into());
+            if let Some(package) = cascade
+                .get_validation_package_local(action_hashed.as_hash())
+                .await?
+            {
+                return Ok(Some(package).into());
             }
 
             let workspace_lock = HostFnWorkspace::new(
-                authored_db.clone(),
+                authored_db.clone().into(),
                 dht_db,
                 dht_db_cache,
                 cache,
@@ -97,8 +99,15 @@ pub(super) async fn get_as_author(
                 };
             match result {
                 ValidationPackageResult::Success(validation_package) => {
-                    // TODO: Cache the package for future calls
-
+                    // The callback may not be deterministic so keep
+                    // the package to give every validator the same one.
+                    let action_hash = action_hashed.as_hash().clone();
+                    let package = validation_package.clone();
+                    authored_db
+                        .async_commit(move |txn| {
+                            insert_validation_package(txn, &action_hash, &package)
+                        })
+                        .await?;
                     Ok(Some(validation_package).into())
                 }
                 ValidationPackageResult::Fail(reason) => {
@@ -129,75 +138,16 @@ pub(super) async fn get_as_author(
     }
 }
 
+/// Authorities can only give out the packages they
+/// have already received from the author.
 pub(super) async fn get_as_authority(
     action: ActionHashed,
     env: DbRead<DbKindDht>,
+    cache: DbWrite<DbKindCache>,
 ) -> CellResult<ValidationPackageResponse> {
-    // Get author and hash
-    let (action, action_hash) = action.into_inner();
-
-    // Get the action data
-    let (app_entry_type, action_seq) = match action
-        .entry_type()
-        .cloned()
-        .map(|et| (et, action.action_seq()))
-    {
-        Some((EntryType::App(aet), action_seq)) => (aet, action_seq),
-        _ => return Ok(None.into()),
-    };
-
-    // Get the required validation package
-    // FIXME: Remove this completely.
-    let required_validation_type = RequiredValidationType::default();
-
-    let cascade = Cascade::empty().with_dht(env);
-
-    // Gather the package
-    match required_validation_type {
-        RequiredValidationType::Record => {
-            // TODO: I'm not sure if we should handle this case, it seems like they should already have the record
-            Ok(None.into())
-        }
-        RequiredValidationType::SubChain => {
-            let query = ChainQueryFilter::default()
-                .include_entries(true)
-                .entry_type(EntryType::App(app_entry_type))
-                .sequence_range(ChainQueryFilterRange::ActionSeqRange(
-                    0,
-                    action_seq.saturating_sub(1),
-                ));
-
-            // Collect and return the sub chain
-            let records = match cascade.get_validation_package_local(&action_hash)? {
-                Some(records) => records,
-                None => return Ok(None.into()),
-            };
-
-            Ok(Some(ValidationPackage::new(query.filter_records(records))).into())
-        }
-        RequiredValidationType::Full => {
-            let query = &ChainQueryFilter::default()
-                .include_entries(true)
-                .sequence_range(ChainQueryFilterRange::ActionSeqRange(
-                    0,
-                    action_seq.saturating_sub(1),
-                ));
-
-            // Collect and return the sub chain
-            let records = match cascade.get_validation_package_local(&action_hash)? {
-                Some(records) => records,
-                None => return Ok(None.into()),
-            };
-
-            Ok(Some(ValidationPackage::new(query.filter_records(records))).into())
-        }
-        RequiredValidationType::Custom => {
-            let records = match cascade.get_validation_package_local(&action_hash)? {
-                Some(records) => records,
-                None => return Ok(None.into()),
-            };
-
-            Ok(Some(ValidationPackage::new(records)).into())
-        }
-    }
+    let cascade = Cascade::empty().with_dht(env).with_cache(cache);
+    Ok(cascade
+        .get_validation_package_local(action.as_hash())
+        .await?
+        .into())
 }
@@ -11,8 +11,11 @@
 
 pub use self::share::RwShare;
 use super::api::RealAppInterfaceApi;
+use super::api::ReliableSignals;
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
+use super::dpki::DpkiService;
+use super::dpki::NoDpki;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::handle::ConductorHandleImpl;
@@ -21,10 +24,13 @@ use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
+use super::interface::SignalAcks;
 use super::interface::SignalBroadcaster;
+use super::interface::ZomeCallNonces;
 use super::manager::keep_alive_task;
 use super::manager::ManagedTaskAdd;
 use super::manager::ManagedTaskHandle;
+use super::manager::ManagedTasks;
 use super::manager::TaskManagerRunHandle;
 use super::paths::DatabaseRootPath;
 use super::ribosome_store::RibosomeStore;
@@ -45,6 +51,7 @@ use crate::core::queue_consumer::QueueConsumerMap;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CHANNEL_BOUND;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CONCURRENT_LIMIT;
+use crate::core::ribosome::real_ribosome::WasmCallLimits;
 use crate::core::ribosome::RibosomeT;
 use crate::{
     conductor::api::error::ConductorApiResult, core::ribosome::real_ribosome::RealRibosome,
@@ -75,6 +82,7 @@ pub use holochain_types::share;
 use rusqlite::{OptionalExtension, Transaction};
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicIsize;
 use std::sync::Arc;
 use tokio::sync::mpsc::error::SendError;
 use tracing::*;
@@ -98,6 +106,16 @@ pub enum CellStatus {
     Joining,
 }
 
+impl From<CellStatus> for holochain_conductor_api::ConductorCellStatus {
+    fn from(status: CellStatus) -> Self {
+        match status {
+            CellStatus::Joined => Self::Joined,
+            CellStatus::PendingJoin => Self::PendingJoin,
+            CellStatus::Joining => Self::Joining,
+        }
+    }
+}
+
 /// Declarative filter for CellStatus
 pub type CellStatusFilter = CellStatus;
 
@@ -150,13 +168,29 @@ where
     /// the dynamically allocated port later.
     admin_websocket_ports: RwShare<Vec<u16>>,
 
+    /// The number of clients connected to each admin and app interface,
+    /// keyed by port.
+    interface_connections: RwShare<HashMap<u16, Arc<AtomicIsize>>>,
+
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
+    /// Delivery tracking for signals which must be acknowledged by clients
+    signal_acks: SignalAcks,
+
+    /// The reliable remote signals each cell is still sending
+    pub(super) reliable_signals: ReliableSignals,
+
+    /// The nonces of signed zome calls which haven't expired yet
+    pub(super) zome_call_nonces: ZomeCallNonces,
+
     /// The channels and handles needed to interact with the task_manager task.
     /// If this is None, then the task manager has not yet been initialized.
     pub(super) task_manager: RwShare<Option<TaskManagerClient>>,
 
+    /// The tasks currently held by the task manager.
+    pub(super) managed_tasks: ManagedTasks,
+
     /// Placeholder for what will be the real DNA/Wasm cache
     ribosome_store: RwShare<RibosomeStore>,
 
@@ -167,6 +201,9 @@ where
     holochain_p2p: holochain_p2p::HolochainP2pRef,
 
     post_commit: tokio::sync::mpsc::Sender<PostCommitArgs>,
+
+    /// Decides which agent key rotations are allowed.
+    pub(super) dpki: Arc<dyn DpkiService>,
 }
 
 impl Conductor {
@@ -303,16 +340,20 @@ impl Conductor {
             let stop_tx = stop_tx.clone();
             async move {
                 match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
+                    InterfaceDriver::Websocket { port, .. } => {
+                        let (listener_handle, listener) =
+                            spawn_websocket_listener(port, driver.auth_token().map(String::from))
+                                .await?;
                         let port = listener_handle.local_addr().port().unwrap_or(port);
+                        let connections = Arc::new(AtomicIsize::new(0));
                         let handle: ManagedTaskHandle = spawn_admin_interface_task(
                             listener_handle,
                             listener,
                             admin_api.clone(),
+                            connections.clone(),
                             stop_tx.subscribe(),
                         )?;
-                        InterfaceResult::Ok((port, handle))
+                        InterfaceResult::Ok((port, handle, connections))
                     }
                 }
             }
@@ -340,8 +381,9 @@ impl Conductor {
             .await?;
 
             // Now that tasks are spawned, register them with the TaskManager
-            for (port, handle) in handles {
+            for (port, handle, connections) in handles {
                 ports.push(port);
+                self.add_interface_connections(port, connections);
                 self.manage_task(ManagedTaskAdd::ignore(
                     handle,
                     &format!("admin interface, port {}", port),
@@ -376,9 +418,17 @@ impl Conductor {
                 .task_stop_broadcaster()
                 .subscribe()
         });
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
+        let connections = Arc::new(AtomicIsize::new(0));
+        let (port, task) = spawn_app_interface_task(
+            port,
+            app_api,
+            signal_tx.clone(),
+            connections.clone(),
+            stop_rx,
+        )
+        .await
+        .map_err(Box::new)?;
+        self.add_interface_connections(port, connections);
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
         self.manage_task(ManagedTaskAdd::ignore(
             task,
@@ -454,7 +504,14 @@ impl Conductor {
         &self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
+            // App interfaces don't check auth tokens, so don't pretend to.
+            if config.driver.auth_token().is_some() {
+                return Err(ConductorError::ConfigError(format!(
+                    "App interface {:?} has an auth_token, which only admin interfaces support",
+                    id
+                )));
+            }
             tracing::debug!("Starting up app interface: {:?}", id);
             let _ = self
                 .add_app_interface_via_handle(either::Right(id), handle.clone())
@@ -467,7 +524,17 @@ impl Conductor {
         let senders = self
             .app_interfaces
             .share_ref(|ai| ai.values().map(|i| i.signal_tx()).cloned().collect());
-        SignalBroadcaster::new(senders)
+        SignalBroadcaster::new(senders).with_acks(self.signal_acks.clone())
+    }
+
+    /// Acknowledge delivery of an ack-required signal
+    pub(super) fn ack_signal(&self, id: u64) -> bool {
+        self.signal_acks.ack(id)
+    }
+
+    /// The number of ack-required signals from a cell which were never acknowledged
+    pub(super) fn undelivered_signal_count(&self, cell_id: &CellId) -> usize {
+        self.signal_acks.undelivered_count(cell_id)
     }
 
     /// Instantiate a Ribosome for use with a DNA
@@ -505,6 +572,8 @@ impl Conductor {
     ///     then set it to Running
     /// - If an app is Running but at least one of its (required) Cells are off,
     ///     then set it to Paused
+    /// - Cells which have been paused individually count as on, so that
+    ///     pausing a Cell does not pause its app.
     pub(super) async fn reconcile_app_status_with_cell_status<S>(
         &self,
         app_ids: Option<S>,
@@ -519,6 +588,8 @@ impl Conductor {
         let running_cells: HashSet<CellId> = self.running_cell_ids();
         let (_, delta) = self
             .update_state_prime(move |mut state| {
+                let mut running_cells = running_cells;
+                running_cells.extend(state.paused_cells().iter().cloned());
                 let apps = state.installed_apps_mut().iter_mut().filter(|(id, _)| {
                     app_ids
                         .as_ref()
@@ -566,13 +637,15 @@ impl Conductor {
         Ok(delta)
     }
 
-    /// Remove all Cells which are not referenced by any Enabled app.
+    /// Remove all Cells which are not referenced by any Enabled app,
+    /// as well as all Cells which have been paused individually.
     /// (Cells belonging to Paused apps are not considered "dangling" and will not be removed)
     pub(super) async fn remove_dangling_cells(&self) -> ConductorResult<()> {
         let state = self.get_state().await?;
         let keepers: HashSet<CellId> = state
             .enabled_apps()
             .flat_map(|(_, app)| app.all_cells().cloned().collect::<HashSet<_>>())
+            .filter(|cell_id| !state.paused_cells().contains(cell_id))
             .collect();
 
         // Clean up all cells that will be dropped (leave network, etc.)
@@ -598,7 +671,8 @@ impl Conductor {
 
     /// Attempt to create all necessary Cells which have not already been created
     /// and added to the conductor, namely the cells which are referenced by
-    /// Running apps. If there are no cells to create, this function does nothing.
+    /// Running apps and have not been paused individually.
+    /// If there are no cells to create, this function does nothing.
     ///
     /// Returns a Result for each attempt so that successful creations can be
     /// handled alongside the failures.
@@ -625,6 +699,7 @@ impl Conductor {
             .iter()
             .filter(|(_, app)| app.status().is_running())
             .flat_map(|(_id, app)| app.all_cells().collect::<Vec<&CellId>>())
+            .filter(|cell_id| !state.paused_cells().contains(cell_id))
             .cloned()
             .collect();
 
@@ -745,53 +820,76 @@ impl Conductor {
         }
     }
 
-    /// Associate a Cell with an existing App
+    /// Associate a cloned Cell with a role of an existing App
     pub(super) async fn add_clone_cell_to_app(
         &self,
         app_id: InstalledAppId,
         role_id: AppRoleId,
-        properties: YamlProperties,
+        cell_id: CellId,
+    ) -> ConductorResult<()> {
+        self.update_state_prime(move |mut state| {
+            state
+                .get_app_mut(&app_id)?
+                .add_clone(&role_id, cell_id)
+                .map_err(ConductorError::from)?;
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Point a role of an existing App at the cell its agent was migrated to.
+    /// Returns the previous cell of the role.
+    pub(super) async fn migrate_role_of_app(
+        &self,
+        app_id: InstalledAppId,
+        role_id: AppRoleId,
+        cell_id: CellId,
     ) -> ConductorResult<CellId> {
-        let ribosome_store = &self.ribosome_store;
-        let (_, parent_dna_hash) = self
-            .update_state_prime({
-                let app_id = app_id.clone();
-                let role_id = role_id.clone();
-                move |mut state| {
-                    if let Some(app) = state.installed_apps_mut().get_mut(&app_id) {
-                        let role = app
-                            .roles()
-                            .get(&role_id)
-                            .ok_or_else(|| AppError::AppRoleIdMissing(role_id.to_owned()))?;
-                        let parent_dna_hash = role.dna_hash().clone();
-                        Ok((state, parent_dna_hash))
-                    } else {
-                        Err(ConductorError::AppNotRunning(app_id.clone()))
-                    }
-                }
+        let (_, old_cell_id) = self
+            .update_state_prime(move |mut state| {
+                let old_cell_id = state
+                    .get_app_mut(&app_id)?
+                    .migrate_role(&role_id, cell_id)?;
+                Ok((state, old_cell_id))
             })
             .await?;
-        let child_dna = ribosome_store.share_ref(|ds| {
-            ds.get_dna_file(&parent_dna_hash)
-                .ok_or(DnaError::DnaMissing(parent_dna_hash))?
-                .modify_phenotype(random_uid(), properties)
-        })?;
-        let child_dna_hash = child_dna.dna_hash().to_owned();
-        let child_ribosome = RealRibosome::new(child_dna)?;
-        self.register_phenotype(child_ribosome);
-        let (_, cell_id) = self
+        Ok(old_cell_id)
+    }
+
+    /// Move every cell of an existing App over to a new agent key.
+    /// Returns the previous and new id of each cell.
+    pub(super) async fn rotate_agent_key_of_app(
+        &self,
+        app_id: InstalledAppId,
+        new_key: AgentPubKey,
+    ) -> ConductorResult<Vec<(CellId, CellId)>> {
+        let (_, rotated) = self
             .update_state_prime(move |mut state| {
-                if let Some(app) = state.installed_apps_mut().get_mut(&app_id) {
-                    let agent_key = app.role(&role_id)?.agent_key().to_owned();
-                    let cell_id = CellId::new(child_dna_hash, agent_key);
-                    app.add_clone(&role_id, cell_id.clone())?;
-                    Ok((state, cell_id))
-                } else {
-                    Err(ConductorError::AppNotRunning(app_id.clone()))
-                }
+                let rotated = state.get_app_mut(&app_id)?.rotate_agent_key(new_key);
+                Ok((state, rotated))
             })
             .await?;
-        Ok(cell_id)
+        Ok(rotated)
+    }
+
+    /// Dissociate a cloned Cell from a role of an existing App.
+    /// The Cell's data is left in place.
+    pub(super) async fn remove_clone_cell_from_app(
+        &self,
+        app_id: InstalledAppId,
+        role_id: AppRoleId,
+        cell_id: CellId,
+    ) -> ConductorResult<()> {
+        self.update_state_prime(move |mut state| {
+            let app = state.get_app_mut(&app_id)?;
+            if !app.remove_clone(&role_id, &cell_id)? {
+                return Err(AppError::CloneCellMissing(role_id, cell_id).into());
+            }
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
     }
 
     pub(super) async fn load_wasms_into_dna_files(
@@ -853,10 +951,31 @@ impl Conductor {
             })
             .await?;
         // try to join all the tasks and return the list of dna files
-        let dnas = futures::future::try_join_all(wasm_tasks).await?;
+        let wasm_call_limits = self.wasm_call_limits();
+        let dnas = futures::future::try_join_all(wasm_tasks)
+            .await?
+            .into_iter()
+            .map(move |(dna_hash, ribosome)| {
+                (dna_hash, ribosome.with_wasm_call_limits(wasm_call_limits))
+            });
         Ok((dnas, defs))
     }
 
+    /// The budget of each zome call or callback, from the config.
+    pub(super) fn wasm_call_limits(&self) -> WasmCallLimits {
+        let default = WasmCallLimits::default();
+        WasmCallLimits {
+            ops_per_call: self
+                .config
+                .max_wasm_ops_per_call
+                .unwrap_or(default.ops_per_call),
+            time_per_call: self
+                .config
+                .max_wasm_time_per_call_ms
+                .map(std::time::Duration::from_millis),
+        }
+    }
+
     /// Get the root environment directory.
     pub fn root_db_dir(&self) -> &DatabaseRootPath {
         &self.spaces.db_dir
@@ -872,6 +991,35 @@ impl Conductor {
         &self.holochain_p2p
     }
 
+    /// Pause a single Cell: persist its paused status and remove it from the
+    /// Conductor, which stops its zome calls and its own queue consumers.
+    pub(super) async fn pause_cell(&self, cell_id: CellId) -> ConductorResult<()> {
+        self.update_state({
+            let cell_id = cell_id.clone();
+            move |mut state| {
+                state.pause_cell(cell_id)?;
+                Ok(state)
+            }
+        })
+        .await?;
+        self.remove_cells(vec![cell_id]).await;
+        Ok(())
+    }
+
+    /// Clear the paused status of a Cell.
+    /// The Cell still needs to be created again if its app is running.
+    pub(super) async fn resume_cell(&self, cell_id: CellId) -> ConductorResult<()> {
+        self.update_state(move |mut state| {
+            if state.resume_cell(&cell_id) {
+                Ok(state)
+            } else {
+                Err(ConductorError::CellNotPaused(cell_id))
+            }
+        })
+        .await?;
+        Ok(())
+    }
+
     /// Remove cells from the cell map in the Conductor
     pub(super) async fn remove_cells(&self, cell_ids: Vec<CellId>) {
         let to_cleanup: Vec<_> = self.cells.share_mut(|cells| {
@@ -881,6 +1029,9 @@ impl Conductor {
                 .collect()
         });
         for (cell_id, item) in to_cleanup {
+            self.spaces
+                .queue_consumer_map
+                .remove_publish_trigger(&cell_id);
             if let Err(err) = item.cell.cleanup().await {
                 tracing::error!("Error cleaning up Cell: {:?}\nCellId: {}", err, cell_id);
             }
@@ -982,6 +1133,28 @@ impl Conductor {
         })
     }
 
+    pub(super) fn list_cells_with_status(&self) -> Vec<(CellId, CellStatus)> {
+        self.cells.share_ref(|cells| {
+            cells
+                .iter()
+                .map(|(id, cell)| (id.clone(), cell.status.clone()))
+                .collect()
+        })
+    }
+
+    pub(super) fn list_admin_interfaces(&self) -> Vec<u16> {
+        self.admin_websocket_ports.share_ref(|p| p.clone())
+    }
+
+    /// The number of clients connected to the interface on this port.
+    pub(super) fn interface_connections(&self, port: u16) -> usize {
+        self.interface_connections.share_ref(|c| {
+            c.get(&port)
+                .map(|n| n.load(std::sync::atomic::Ordering::Relaxed).max(0) as usize)
+                .unwrap_or(0)
+        })
+    }
+
     pub(super) async fn list_running_apps(&self) -> ConductorResult<Vec<InstalledAppId>> {
         let state = self.get_state().await?;
         Ok(state.running_apps().map(|(id, _)| id).cloned().collect())
@@ -1045,6 +1218,25 @@ impl Conductor {
             }))
     }
 
+    pub(super) async fn find_app_and_role_of_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<(InstalledAppId, AppRoleId)>> {
+        Ok(self
+            .get_state()
+            .await?
+            .running_apps()
+            .find_map(|(app_id, running_app)| {
+                running_app
+                    .roles()
+                    .iter()
+                    .find(|(_, role)| {
+                        role.provisioned_cell() == Some(cell_id) || role.clones().contains(cell_id)
+                    })
+                    .map(|(role_id, _)| (app_id.clone(), role_id.clone()))
+            }))
+    }
+
     pub(super) async fn list_running_apps_for_dna_hash(
         &self,
         dna_hash: &DnaHash,
@@ -1204,6 +1396,15 @@ pub async fn integration_dump(
         .await
 }
 
+/// Dump why each rejected op was rejected.
+pub async fn rejections_dump(
+    vault: &DbRead<DbKindDht>,
+) -> ConductorApiResult<Vec<(DhtOpHash, ValidationRejection)>> {
+    vault
+        .async_reader(move |txn| query_rejections(&txn, None))
+        .await
+}
+
 /// Dump the full integration json state.
 /// Careful! This will return a lot of data.
 pub async fn full_integration_dump(
@@ -1227,12 +1428,15 @@ pub async fn full_integration_dump(
                 dht_ops_cursor,
             )?;
 
+            let rejections = query_rejections(&txn, dht_ops_cursor)?;
+
             let dht_ops_cursor = txn.query_row(state_dump::DHT_OPS_ROW_ID, [], |row| row.get(0))?;
 
             ConductorApiResult::Ok(FullIntegrationStateDump {
                 validation_limbo,
                 integration_limbo,
                 integrated,
+                rejections,
                 dht_ops_cursor,
             })
         })
@@ -1271,6 +1475,31 @@ fn query_dht_ops_from_statement(
     Ok(r)
 }
 
+fn query_rejections(
+    txn: &Transaction,
+    dht_ops_cursor: Option<u64>,
+) -> ConductorApiResult<Vec<(DhtOpHash, ValidationRejection)>> {
+    let final_stmt_str = match dht_ops_cursor {
+        Some(cursor) => format!(
+            "{} AND DhtOp.rowid > {}",
+            state_dump::DHT_OPS_REJECTIONS,
+            cursor
+        ),
+        None => state_dump::DHT_OPS_REJECTIONS.into(),
+    };
+
+    let mut stmt = txn.prepare(final_stmt_str.as_str())?;
+
+    let r = stmt
+        .query_and_then([], |row| {
+            let hash: DhtOpHash = row.get("dht_hash")?;
+            let rejection = from_blob::<ValidationRejection>(row.get("rejection_blob")?)?;
+            StateQueryResult::Ok((hash, rejection))
+        })?
+        .collect::<StateQueryResult<Vec<_>>>()?;
+    Ok(r)
+}
+
 //-----------------------------------------------------------------------------
 // Private methods
 //-----------------------------------------------------------------------------
@@ -1284,19 +1513,27 @@ impl Conductor {
         holochain_p2p: holochain_p2p::HolochainP2pRef,
         spaces: Spaces,
         post_commit: tokio::sync::mpsc::Sender<PostCommitArgs>,
+        dpki: Arc<dyn DpkiService>,
     ) -> ConductorResult<Self> {
+        let zome_call_nonces = ZomeCallNonces::new(spaces.conductor_db.clone());
         Ok(Self {
             spaces,
             cells: RwShare::new(HashMap::new()),
             config,
             shutting_down: Arc::new(AtomicBool::new(false)),
             app_interfaces: RwShare::new(HashMap::new()),
+            signal_acks: SignalAcks::default(),
+            reliable_signals: ReliableSignals::default(),
+            zome_call_nonces,
             task_manager: RwShare::new(None),
+            managed_tasks: ManagedTasks::default(),
             admin_websocket_ports: RwShare::new(Vec::new()),
+            interface_connections: RwShare::new(HashMap::new()),
             ribosome_store,
             keystore,
             holochain_p2p,
             post_commit,
+            dpki,
         })
     }
 
@@ -1383,8 +1620,13 @@ impl Conductor {
         self.admin_websocket_ports.share_mut(|p| p.push(port));
     }
 
+    fn add_interface_connections(&self, port: u16, connections: Arc<AtomicIsize>) {
+        self.interface_connections
+            .share_mut(|c| c.insert(port, connections));
+    }
+
     /// Sends a JoinHandle to the TaskManager task to be managed
-    async fn manage_task(&self, handle: ManagedTaskAdd) -> ConductorResult<()> {
+    pub(super) async fn manage_task(&self, handle: ManagedTaskAdd) -> ConductorResult<()> {
         self.task_manager
             .share_ref(|tm| {
                 tm.as_ref()
@@ -1418,6 +1660,8 @@ mod builder {
         pub passphrase: Option<sodoken::BufRead>,
         /// Optional keystore override
         pub keystore: Option<MetaLairClient>,
+        /// Optional DPKI service, which defaults to [`NoDpki`]
+        pub dpki: Option<Arc<dyn DpkiService>>,
         #[cfg(any(test, feature = "test_utils"))]
         /// Optional state override (for testing)
         pub state: Option<ConductorState>,
@@ -1446,6 +1690,12 @@ mod builder {
             self
         }
 
+        /// Set the DPKI service which decides which agent key rotations are allowed
+        pub fn with_dpki(mut self, dpki: Arc<dyn DpkiService>) -> Self {
+            self.dpki = Some(dpki);
+            self
+        }
+
         /// Initialize a "production" Conductor
         pub async fn build(self) -> ConductorResult<ConductorHandle> {
             cfg_if::cfg_if! {
@@ -1499,6 +1749,7 @@ mod builder {
             let Self {
                 ribosome_store,
                 config,
+                dpki,
                 ..
             } = self;
 
@@ -1516,7 +1767,8 @@ mod builder {
             let strat =
                 ArqStrat::from_params(network_config.tuning_params.gossip_redundancy_target);
 
-            let spaces = Spaces::new(&config)?;
+            let db_key = db_key(&config, &keystore).await?;
+            let spaces = Spaces::new(&config)?.with_db_key(db_key);
             let host = KitsuneHostImpl::new(
                 spaces.clone(),
                 ribosome_store.clone(),
@@ -1537,6 +1789,7 @@ mod builder {
                 holochain_p2p,
                 spaces,
                 post_commit_sender,
+                dpki.unwrap_or_else(|| Arc::new(NoDpki)),
             )
             .await?;
 
@@ -1618,8 +1871,22 @@ mod builder {
                 .clone()
                 .start_scheduler(holochain_zome_types::schedule::SCHEDULER_INTERVAL);
 
+            // An interval of zero hours would compact the databases constantly.
+            if let Some(hours) = conductor_config
+                .db_compaction_interval_hours
+                .filter(|hours| *hours > 0)
+            {
+                handle
+                    .clone()
+                    .start_db_compaction(std::time::Duration::from_secs(hours * 60 * 60));
+            }
+
             let _ = Self::spawn_post_commit(handle.clone(), post_commit_receiver);
 
+            if let Some(path) = conductor_config.wasm_cache_path {
+                crate::core::ribosome::wasm_cache::set_disk_cache_path(path)?;
+            }
+
             let configs = conductor_config.admin_interfaces.unwrap_or_default();
             let cell_startup_errors = handle.clone().initialize_conductor(configs).await?;
 
@@ -1679,7 +1946,8 @@ mod builder {
             let keystore = self.keystore.unwrap_or_else(test_keystore);
             self.config.environment_path = env_path.to_path_buf().into();
 
-            let spaces = Spaces::new(&self.config)?;
+            let db_key = db_key(&self.config, &keystore).await?;
+            let spaces = Spaces::new(&self.config)?.with_db_key(db_key);
 
             let network_config = self.config.network.clone().unwrap_or_default();
             let tuning_params = network_config.tuning_params.clone();
@@ -1703,6 +1971,7 @@ mod builder {
                 holochain_p2p,
                 spaces,
                 post_commit_sender,
+                self.dpki.unwrap_or_else(|| Arc::new(NoDpki)),
             )
             .await?;
 
@@ -1730,6 +1999,22 @@ mod builder {
             Self::finish(handle, self.config, p2p_evt, post_commit_receiver).await
         }
     }
+
+    /// Get the key from lair to encrypt the cell databases with,
+    /// if the config turns on database encryption.
+    async fn db_key(
+        config: &ConductorConfig,
+        keystore: &MetaLairClient,
+    ) -> ConductorResult<Option<holochain_sqlite::conn::DbKey>> {
+        if !config.db_encryption {
+            return Ok(None);
+        }
+        if !cfg!(feature = "db-encryption") {
+            return Err(DatabaseError::EncryptionUnavailable.into());
+        }
+        let key = keystore.get_or_create_db_key().await?;
+        Ok(Some(holochain_sqlite::conn::DbKey::new(key)))
+    }
 }
 
 #[instrument(skip(p2p_evt, handle))]
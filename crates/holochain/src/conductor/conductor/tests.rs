@@ -5,7 +5,7 @@ use super::Conductor;
 use super::ConductorState;
 use super::*;
 use crate::conductor::api::error::ConductorApiError;
-use crate::core::ribosome::guest_callback::validate::ValidateResult;
+use crate::conductor::dpki::*;
 use crate::sweettest::*;
 use crate::test_utils::fake_valid_dna_file;
 use crate::{
@@ -46,6 +46,7 @@ async fn can_update_state() {
         holochain_p2p,
         spaces,
         post_commit_sender,
+        Arc::new(NoDpki),
     )
     .await
     .unwrap();
@@ -99,6 +100,7 @@ async fn can_add_clone_cell_to_app() {
         holochain_p2p,
         spaces,
         post_commit_sender,
+        Arc::new(NoDpki),
     )
     .await
     .unwrap();
@@ -130,17 +132,30 @@ async fn can_add_clone_cell_to_app() {
         .await
         .unwrap();
 
+    let new_clone = || CellId::new(fixt!(DnaHash), cell_id.agent_pubkey().clone());
+
     matches::assert_matches!(
         conductor
-            .add_clone_cell_to_app("no clone".to_string(), "role_id".to_string(), ().into())
+            .add_clone_cell_to_app("no clone".to_string(), "role_id".to_string(), new_clone())
             .await,
         Err(ConductorError::AppError(AppError::CloneLimitExceeded(0, _)))
     );
 
-    let cloned_cell_id = conductor
-        .add_clone_cell_to_app("yes clone".to_string(), "role_id".to_string(), ().into())
+    let cloned_cell_id = new_clone();
+    conductor
+        .add_clone_cell_to_app(
+            "yes clone".to_string(),
+            "role_id".to_string(),
+            cloned_cell_id.clone(),
+        )
         .await
         .unwrap();
+    matches::assert_matches!(
+        conductor
+            .add_clone_cell_to_app("yes clone".to_string(), "role_id".to_string(), new_clone())
+            .await,
+        Err(ConductorError::AppError(AppError::CloneLimitExceeded(1, _)))
+    );
 
     let state = conductor.get_state().await.unwrap();
     assert_eq!(
@@ -152,8 +167,28 @@ async fn can_add_clone_cell_to_app() {
             .cloned_cells()
             .cloned()
             .collect::<Vec<CellId>>(),
-        vec![cloned_cell_id]
+        vec![cloned_cell_id.clone()]
+    );
+
+    // Disabling the clone frees up its place
+    matches::assert_matches!(
+        conductor
+            .remove_clone_cell_from_app("yes clone".to_string(), "role_id".to_string(), new_clone())
+            .await,
+        Err(ConductorError::AppError(AppError::CloneCellMissing(_, _)))
     );
+    conductor
+        .remove_clone_cell_from_app(
+            "yes clone".to_string(),
+            "role_id".to_string(),
+            cloned_cell_id,
+        )
+        .await
+        .unwrap();
+    conductor
+        .add_clone_cell_to_app("yes clone".to_string(), "role_id".to_string(), new_clone())
+        .await
+        .unwrap();
 }
 
 /// App can't be installed if another app is already installed under the
@@ -177,6 +212,7 @@ async fn app_ids_are_unique() {
         holochain_p2p,
         spaces,
         post_commit_sender,
+        Arc::new(NoDpki),
     )
     .await
     .unwrap();
@@ -362,6 +398,63 @@ async fn test_uninstall_app() {
     );
 }
 
+/// The total size of the files in a directory and its subdirectories.
+fn dir_size(path: &std::path::Path) -> u64 {
+    std::fs::read_dir(path)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let metadata = entry.metadata().unwrap();
+            if metadata.is_dir() {
+                dir_size(&entry.path())
+            } else {
+                metadata.len()
+            }
+        })
+        .sum()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_uninstall_app_frees_disk_space() {
+    use crate::conductor::api::AdminInterfaceApi;
+    use crate::conductor::api::RealAdminInterfaceApi;
+
+    observability::test_run().ok();
+    let zome = InlineIntegrityZome::new_unique(Vec::new(), 0);
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = common_genesis_test_app(&mut conductor, ("custom", zome))
+        .await
+        .unwrap();
+    let db_files: Vec<_> = app
+        .cells()
+        .iter()
+        .flat_map(|cell| {
+            let dna_hash = cell.cell_id().dna_hash();
+            let handle = conductor.inner_handle();
+            vec![
+                handle.get_authored_db(dna_hash).unwrap().path().clone(),
+                handle.get_dht_db(dna_hash).unwrap().path().clone(),
+            ]
+        })
+        .collect();
+    assert!(db_files.iter().all(|path| path.exists()));
+    // The cells hold handles to the databases.
+    drop(app);
+    let size_before = dir_size(conductor.db_path());
+
+    let admin_api = RealAdminInterfaceApi::new(conductor.inner_handle());
+    let response = admin_api
+        .handle_admin_request(AdminRequest::UninstallApp {
+            installed_app_id: "app".to_string(),
+        })
+        .await;
+    assert_matches!(response, AdminResponse::AppUninstalled);
+
+    // - The databases of the app's dnas are deleted
+    assert!(db_files.iter().all(|path| !path.exists()));
+    assert!(dir_size(conductor.db_path()) < size_before);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_reconciliation_idempotency() {
     observability::test_run().ok();
@@ -440,6 +533,234 @@ async fn make_signing_call(client: &mut WebsocketSender, cell: &SweetCell) -> Ap
         .unwrap()
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_signed_zome_calls() {
+    use crate::conductor::api::AppInterfaceApi;
+    use holochain_conductor_api::BatchZomeCall;
+    use holochain_conductor_api::ExternalApiWireError;
+    use holochain_conductor_api::SignedZomeCall;
+    use holochain_keystore::AgentPubKeyExt;
+
+    observability::test_run().ok();
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_config(standard_config()).await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let api = RealAppInterfaceApi::new(conductor.inner_handle());
+    let keystore = conductor.keystore().clone();
+
+    let call = ZomeCall {
+        cell_id: cell.cell_id().clone(),
+        zome_name: TestWasm::Foo.into(),
+        fn_name: "foo".into(),
+        payload: ExternIO::encode(()).unwrap(),
+        cap_secret: None,
+        provenance: cell.agent_pubkey().clone(),
+    };
+    let sign = |signer: AgentPubKey, nonce, expires_at| {
+        let call = call.clone();
+        let keystore = keystore.clone();
+        async move {
+            let data = SignedZomeCall::data_to_sign(&call, &nonce, expires_at).unwrap();
+            let signature = signer.sign_raw(&keystore, data.into()).await.unwrap();
+            SignedZomeCall {
+                call,
+                nonce,
+                expires_at,
+                signature,
+            }
+        }
+    };
+    let request = |signed: SignedZomeCall| AppRequest::SignedZomeCall(Box::new(signed));
+    let expires_at = Timestamp::now().saturating_add(&std::time::Duration::from_secs(60));
+
+    // - Unsigned calls are refused
+    assert_matches!(
+        api.handle_app_request(AppRequest::ZomeCall(Box::new(call.clone())))
+            .await,
+        AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_))
+    );
+
+    // - A call signed by its provenance is made, but only once
+    let signed = request(sign(cell.agent_pubkey().clone(), [1; 32], expires_at).await);
+    assert_matches!(
+        api.handle_app_request(signed.clone()).await,
+        AppResponse::ZomeCall(_)
+    );
+    assert_matches!(
+        api.handle_app_request(signed).await,
+        AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_))
+    );
+
+    // - A call signed by another key is refused
+    let other_key = keystore.new_sign_keypair_random().await.unwrap();
+    assert_matches!(
+        api.handle_app_request(request(sign(other_key.clone(), [2; 32], expires_at).await))
+            .await,
+        AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_))
+    );
+
+    // - An expired call is refused
+    assert_matches!(
+        api.handle_app_request(request(
+            sign(cell.agent_pubkey().clone(), [3; 32], Timestamp::now()).await
+        ))
+        .await,
+        AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_))
+    );
+
+    // - Each call in a batch is checked like a single call
+    let batch = AppRequest::ZomeCallBatch {
+        calls: vec![
+            BatchZomeCall::Signed(sign(cell.agent_pubkey().clone(), [4; 32], expires_at).await),
+            BatchZomeCall::Signed(sign(other_key, [5; 32], expires_at).await),
+            BatchZomeCall::Signed(sign(cell.agent_pubkey().clone(), [4; 32], expires_at).await),
+            BatchZomeCall::Unsigned(call.clone()),
+        ],
+        concurrent: false,
+    };
+    assert_matches!(
+        api.handle_app_request(batch).await,
+        AppResponse::ZomeCallBatch(responses) if matches!(
+            responses.as_slice(),
+            [
+                AppResponse::ZomeCall(_),
+                AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_)),
+                AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_)),
+                AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_)),
+            ]
+        )
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_scoped_cap_grants() {
+    use crate::conductor::api::AppInterfaceApi;
+    use holochain_conductor_api::CapGrantRequest;
+    use holochain_conductor_api::ExternalApiWireError;
+    use holochain_conductor_api::SignedCapGrantRequest;
+    use holochain_keystore::AgentPubKeyExt;
+
+    observability::test_run().ok();
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (cell,) = conductor
+        .setup_app("app", [&dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    conductor.setup_app("other", [&dna]).await.unwrap();
+    let api = RealAppInterfaceApi::new(conductor.inner_handle());
+    let keystore = conductor.keystore().clone();
+
+    let expires_at = Timestamp::now().saturating_add(&std::time::Duration::from_secs(60));
+    let mut nonce = 0;
+    let mut sign = |signer: AgentPubKey, request: CapGrantRequest| {
+        nonce += 1;
+        let nonce = [nonce; 32];
+        let keystore = keystore.clone();
+        async move {
+            let data = SignedCapGrantRequest::data_to_sign(&request, &nonce, expires_at).unwrap();
+            let signature = signer.sign_raw(&keystore, data.into()).await.unwrap();
+            AppRequest::SignedCapGrantRequest(Box::new(SignedCapGrantRequest {
+                request,
+                nonce,
+                expires_at,
+                signature,
+            }))
+        }
+    };
+    let agent = cell.agent_pubkey().clone();
+    let create = CapGrantRequest::Create {
+        installed_app_id: "app".into(),
+        payload: GrantZomeCallCapabilityPayload {
+            cell_id: cell.cell_id().clone(),
+            signing_key: fixt!(AgentPubKey),
+            tag: "ui".into(),
+            functions: [(TestWasm::Foo.coordinator_zome_name(), "foo".into())].into(),
+            access: ZomeCallCapAccess::Transferable,
+        },
+    };
+    let list = |installed_app_id: &str| CapGrantRequest::List {
+        installed_app_id: installed_app_id.into(),
+        cell_id: cell.cell_id().clone(),
+    };
+
+    // - Requests not signed by the agent of the cell are refused
+    let other_key = keystore.new_sign_keypair_random().await.unwrap();
+    assert_matches!(
+        api.handle_app_request(sign(other_key.clone(), create.clone()).await)
+            .await,
+        AppResponse::Error(ExternalApiWireError::CapGrantUnauthorized(_))
+    );
+    assert_matches!(
+        api.handle_app_request(sign(other_key.clone(), list("app")).await)
+            .await,
+        AppResponse::Error(ExternalApiWireError::CapGrantUnauthorized(_))
+    );
+
+    // - The agent of the cell can create a grant, but only once per signature
+    let signed = sign(agent.clone(), create).await;
+    assert_matches!(
+        api.handle_app_request(signed.clone()).await,
+        AppResponse::CapGrantCreated(Some(_))
+    );
+    assert_matches!(
+        api.handle_app_request(signed).await,
+        AppResponse::Error(ExternalApiWireError::CapGrantUnauthorized(_))
+    );
+
+    // - Another app can neither list nor revoke the grants of this app's cell
+    assert_matches!(
+        api.handle_app_request(sign(agent.clone(), list("other")).await)
+            .await,
+        AppResponse::Error(_)
+    );
+    let grants = match api
+        .handle_app_request(sign(agent.clone(), list("app")).await)
+        .await
+    {
+        AppResponse::CapGrantsListed(grants) => grants,
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert_eq!(grants.len(), 1);
+    let revoke = |installed_app_id: &str| CapGrantRequest::Revoke {
+        installed_app_id: installed_app_id.into(),
+        cell_id: cell.cell_id().clone(),
+        action_hash: grants[0].action_hash.clone(),
+    };
+    assert_matches!(
+        api.handle_app_request(sign(agent.clone(), revoke("other")).await)
+            .await,
+        AppResponse::Error(_)
+    );
+    assert_matches!(
+        api.handle_app_request(sign(other_key, revoke("app")).await)
+            .await,
+        AppResponse::Error(ExternalApiWireError::CapGrantUnauthorized(_))
+    );
+    assert_matches!(
+        api.handle_app_request(sign(agent.clone(), list("app")).await)
+            .await,
+        AppResponse::CapGrantsListed(grants) if grants.len() == 1
+    );
+
+    // - The app itself can revoke them
+    assert_matches!(
+        api.handle_app_request(sign(agent.clone(), revoke("app")).await)
+            .await,
+        AppResponse::CapGrantRevoked
+    );
+    assert_matches!(
+        api.handle_app_request(sign(agent, list("app")).await).await,
+        AppResponse::CapGrantsListed(grants) if grants.is_empty()
+    );
+}
+
 /// A test which simulates Keystore errors with a test keystore which is designed
 /// to fail.
 ///
@@ -459,7 +780,8 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
         .unwrap();
 
     let db_dir = test_db_dir();
-    let config = standard_config();
+    let mut config = standard_config();
+    config.danger_allow_unsigned_zome_calls = true;
     let mut conductor = SweetConductor::new(
         SweetConductor::handle_from_existing(db_dir.path(), keystore.clone(), &config, &[]).await,
         db_dir,
@@ -654,11 +976,11 @@ async fn test_bad_entry_validation_after_genesis_returns_zome_call_error() {
                 Op::StoreEntry { action, .. }
                     if action.hashed.content.app_entry_type().is_some() =>
                 {
-                    Ok(ValidateResult::Invalid(
+                    Ok(ValidateCallbackResult::Invalid(
                         "intentional invalid result for testing".into(),
                     ))
                 }
-                _ => Ok(ValidateResult::Valid),
+                _ => Ok(ValidateCallbackResult::Valid),
             })
             .callback("custom", "create", move |api, ()| {
                 let entry = Entry::app(().try_into().unwrap()).unwrap();
@@ -718,9 +1040,9 @@ async fn test_apps_disable_on_panic_after_genesis() {
                     {
                         // Trigger a deserialization error
                         let _: Entry = SerializedBytes::try_from(())?.try_into()?;
-                        Ok(ValidateResult::Valid)
+                        Ok(ValidateCallbackResult::Valid)
                     }
-                    _ => Ok(ValidateResult::Valid),
+                    _ => Ok(ValidateCallbackResult::Valid),
                 }
             })
             .callback("custom", "create", move |api, ()| {
@@ -966,6 +1288,440 @@ async fn test_app_status_filters() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pause_resume_cell() {
+    observability::test_run().ok();
+    let mk_zome = || ("zome", InlineIntegrityZome::new_unique(Vec::new(), 0));
+    let dnas = [
+        mk_dna(mk_zome()).await.unwrap().0,
+        mk_dna(mk_zome()).await.unwrap().0,
+    ];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    conductor.setup_app("app", &dnas).await.unwrap();
+
+    let cell_ids = conductor.list_cell_ids(None);
+    assert_eq!(cell_ids.len(), 2);
+    let paused = cell_ids[0].clone();
+
+    // - Pausing a cell stops it without affecting the app
+    conductor.pause_cell(paused.clone()).await.unwrap();
+    assert_eq!(conductor.list_cell_ids(None), vec![cell_ids[1].clone()]);
+    assert_eq!(conductor.list_running_apps().await.unwrap().len(), 1);
+    assert_matches!(
+        conductor
+            .inner_handle()
+            .resume_cell(cell_ids[1].clone())
+            .await,
+        Err(ConductorError::CellNotPaused(_))
+    );
+
+    // - The cell stays paused across a restart
+    conductor.shutdown().await;
+    conductor.startup().await;
+    assert_eq!(conductor.list_cell_ids(None), vec![cell_ids[1].clone()]);
+    assert_eq!(conductor.list_running_apps().await.unwrap().len(), 1);
+
+    // - Resuming starts the cell again
+    let errors = conductor
+        .inner_handle()
+        .resume_cell(paused.clone())
+        .await
+        .unwrap();
+    assert!(errors.is_empty());
+    assert_eq!(
+        conductor
+            .list_cell_ids(None)
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        cell_ids.into_iter().collect::<HashSet<_>>()
+    );
+    assert!(conductor
+        .get_state_from_handle()
+        .await
+        .unwrap()
+        .paused_cells()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_cell() {
+    observability::test_run().ok();
+    let (old_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::MigrateAgentPass])
+        .await
+        .unwrap();
+    let (new_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::MigrateAgentPass])
+        .await
+        .unwrap();
+    let (refusing_dna, _, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::MigrateAgentFail])
+            .await
+            .unwrap();
+    // agrees to the migration, but its validation rejects the OpenChain
+    let (invalid_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![
+        TestWasm::MigrateAgentPass,
+        TestWasm::ValidateInvalid,
+    ])
+    .await
+    .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&old_dna]).await.unwrap();
+    let old_cell_id = app.cells()[0].cell_id().clone();
+    let handle = conductor.inner_handle();
+    handle.register_dna(new_dna.clone()).await.unwrap();
+    handle.register_dna(refusing_dna.clone()).await.unwrap();
+    handle.register_dna(invalid_dna.clone()).await.unwrap();
+
+    let migrate = |dna: &DnaFile| MigrateCellPayload {
+        installed_app_id: "app".into(),
+        role_id: old_dna.dna_hash().to_string(),
+        new_dna_hash: dna.dna_hash().clone(),
+        membrane_proof: None,
+        carry_forward: true,
+    };
+    let chain_actions = |cell_id: &CellId, action_type: ActionType| {
+        let handle = handle.clone();
+        let cell_id = cell_id.clone();
+        async move {
+            let dna_hash = cell_id.dna_hash();
+            SourceChain::new(
+                handle.get_authored_db(dna_hash).unwrap(),
+                handle.get_dht_db(dna_hash).unwrap(),
+                handle.get_dht_db_cache(dna_hash).unwrap(),
+                handle.keystore().clone(),
+                cell_id.agent_pubkey().clone(),
+            )
+            .await
+            .unwrap()
+            .query(ChainQueryFilter::new().action_type(action_type))
+            .await
+            .unwrap()
+        }
+    };
+
+    // - The migration can't target the DNA already in use
+    assert_matches!(
+        handle.clone().migrate_cell(migrate(&old_dna)).await,
+        Err(ConductorError::MigrationToSameDna(_))
+    );
+
+    // - A failing callback leaves the old chain open and the old cell in place
+    assert_matches!(
+        handle.clone().migrate_cell(migrate(&refusing_dna)).await,
+        Err(ConductorError::MigrateAgentFailed(_, _))
+    );
+    assert!(chain_actions(&old_cell_id, ActionType::CloseChain)
+        .await
+        .is_empty());
+    assert_eq!(conductor.list_cell_ids(None), vec![old_cell_id.clone()]);
+
+    // - If the new chain's OpenChain is invalid, the old chain is not closed
+    //   and the new chain is not opened
+    assert_matches!(
+        handle.clone().migrate_cell(migrate(&invalid_dna)).await,
+        Err(ConductorError::WorkflowError(_))
+    );
+    assert!(chain_actions(&old_cell_id, ActionType::CloseChain)
+        .await
+        .is_empty());
+    let invalid_cell_id = CellId::new(
+        invalid_dna.dna_hash().clone(),
+        old_cell_id.agent_pubkey().clone(),
+    );
+    assert!(chain_actions(&invalid_cell_id, ActionType::OpenChain)
+        .await
+        .is_empty());
+    assert_eq!(conductor.list_cell_ids(None), vec![old_cell_id.clone()]);
+
+    // - A successful migration closes the old chain, opens the new one,
+    //   and replaces the old cell in the app
+    let new_cell_id = handle
+        .clone()
+        .migrate_cell(migrate(&new_dna))
+        .await
+        .unwrap();
+    assert_eq!(
+        new_cell_id,
+        CellId::new(
+            new_dna.dna_hash().clone(),
+            old_cell_id.agent_pubkey().clone()
+        )
+    );
+    assert_eq!(conductor.list_cell_ids(None), vec![new_cell_id.clone()]);
+    assert_matches!(
+        chain_actions(&old_cell_id, ActionType::CloseChain).await[..],
+        [ref r] if matches!(r.action(), Action::CloseChain(c) if &c.new_dna_hash == new_dna.dna_hash())
+    );
+    assert_matches!(
+        chain_actions(&new_cell_id, ActionType::OpenChain).await[..],
+        [ref r] if matches!(r.action(), Action::OpenChain(o) if &o.prev_dna_hash == old_dna.dna_hash())
+    );
+}
+
+/// A DPKI service which refuses every key rotation.
+#[derive(Debug)]
+struct RefuseRotations;
+
+#[async_trait::async_trait]
+impl DpkiService for RefuseRotations {
+    async fn validate_key_rotation(
+        &self,
+        _installed_app_id: &InstalledAppId,
+        old_key: &AgentPubKey,
+        new_key: &AgentPubKey,
+    ) -> DpkiResult<()> {
+        Err(DpkiError::RotationRefused {
+            old_key: old_key.clone(),
+            new_key: new_key.clone(),
+            reason: "rotations are not allowed".into(),
+        })
+    }
+}
+
+/// A DPKI service which allows every key rotation.
+#[derive(Debug)]
+struct AllowRotations;
+
+#[async_trait::async_trait]
+impl DpkiService for AllowRotations {
+    async fn validate_key_rotation(
+        &self,
+        _installed_app_id: &InstalledAppId,
+        _old_key: &AgentPubKey,
+        _new_key: &AgentPubKey,
+    ) -> DpkiResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rotate_app_agent_key() {
+    observability::test_run().ok();
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create])
+        .await
+        .unwrap();
+    let chain_updates = |handle: &ConductorHandle, cell_id: &CellId| {
+        let handle = handle.clone();
+        let cell_id = cell_id.clone();
+        async move {
+            let dna_hash = cell_id.dna_hash();
+            SourceChain::new(
+                handle.get_authored_db(dna_hash).unwrap(),
+                handle.get_dht_db(dna_hash).unwrap(),
+                handle.get_dht_db_cache(dna_hash).unwrap(),
+                handle.keystore().clone(),
+                cell_id.agent_pubkey().clone(),
+            )
+            .await
+            .unwrap()
+            .query(ChainQueryFilter::new().action_type(ActionType::Update))
+            .await
+            .unwrap()
+        }
+    };
+
+    // - A rotation refused by the DPKI service leaves the app as it was
+    let mut conductor =
+        SweetConductor::from_builder(Conductor::builder().with_dpki(Arc::new(RefuseRotations)))
+            .await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let old_cell_id = app.cells()[0].cell_id().clone();
+    let handle = conductor.inner_handle();
+    assert_matches!(
+        handle.clone().rotate_app_agent_key("app".into()).await,
+        Err(ConductorError::DpkiError(DpkiError::RotationRefused { .. }))
+    );
+    assert_eq!(conductor.list_cell_ids(None), vec![old_cell_id.clone()]);
+    assert!(chain_updates(&handle, &old_cell_id).await.is_empty());
+
+    // - Without a DPKI service rotations are refused
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let old_cell_id = app.cells()[0].cell_id().clone();
+    let handle = conductor.inner_handle();
+    assert_matches!(
+        handle.clone().rotate_app_agent_key("app".into()).await,
+        Err(ConductorError::DpkiError(DpkiError::NotConfigured(_)))
+    );
+    assert!(chain_updates(&handle, &old_cell_id).await.is_empty());
+
+    // - If the update is invalid on any chain, no chain records it
+    let (invalid_dna, _, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create, TestWasm::ValidateInvalid])
+            .await
+            .unwrap();
+    let mut conductor =
+        SweetConductor::from_builder(Conductor::builder().with_dpki(Arc::new(AllowRotations)))
+            .await;
+    let app = conductor
+        .setup_app("app", [&dna, &invalid_dna])
+        .await
+        .unwrap();
+    let old_cell_ids: Vec<_> = app.cells().iter().map(|c| c.cell_id().clone()).collect();
+    let handle = conductor.inner_handle();
+    assert!(handle
+        .clone()
+        .rotate_app_agent_key("app".into())
+        .await
+        .is_err());
+    for cell_id in &old_cell_ids {
+        assert!(chain_updates(&handle, cell_id).await.is_empty());
+    }
+    let mut cell_ids = conductor.list_cell_ids(None);
+    cell_ids.sort();
+    let mut expected = old_cell_ids.clone();
+    expected.sort();
+    assert_eq!(cell_ids, expected);
+
+    // - If any chain can't be locked for the rotation, here because another
+    //   lock is held on it, no chain records the update, nothing is held in
+    //   the DHT databases and the other chains are unlocked again
+    let (other_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create])
+        .await
+        .unwrap();
+    let mut conductor =
+        SweetConductor::from_builder(Conductor::builder().with_dpki(Arc::new(AllowRotations)))
+            .await;
+    let app = conductor
+        .setup_app("app", [&dna, &other_dna])
+        .await
+        .unwrap();
+    let mut old_cell_ids: Vec<_> = app.cells().iter().map(|c| c.cell_id().clone()).collect();
+    old_cell_ids.sort();
+    let handle = conductor.inner_handle();
+    // The chains are locked in the order of their cell ids,
+    // so lock the last one to make the rotation fail after the first.
+    let locked = old_cell_ids.last().unwrap().clone();
+    handle
+        .get_authored_db(locked.dna_hash())
+        .unwrap()
+        .async_commit(move |txn| {
+            lock_chain(
+                txn,
+                &[1, 2, 3],
+                locked.agent_pubkey(),
+                &(Timestamp::now() + std::time::Duration::from_secs(600)).unwrap(),
+            )
+        })
+        .await
+        .unwrap();
+    assert!(handle
+        .clone()
+        .rotate_app_agent_key("app".into())
+        .await
+        .is_err());
+    for cell_id in &old_cell_ids {
+        assert!(chain_updates(&handle, cell_id).await.is_empty());
+        let held_in_dht: bool = handle
+            .get_dht_db(cell_id.dna_hash())
+            .unwrap()
+            .async_reader(|txn| {
+                DatabaseResult::Ok(txn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM Action WHERE type = :type)",
+                    rusqlite::named_params! { ":type": ActionType::Update.to_string() },
+                    |row| row.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert!(!held_in_dht);
+    }
+    let first = old_cell_ids.first().unwrap().clone();
+    let first_locked = handle
+        .get_authored_db(first.dna_hash())
+        .unwrap()
+        .async_reader(move |txn| {
+            holochain_state::chain_lock::is_chain_locked(&txn, &[], first.agent_pubkey())
+        })
+        .await
+        .unwrap();
+    assert!(!first_locked);
+    let mut cell_ids = conductor.list_cell_ids(None);
+    cell_ids.sort();
+    assert_eq!(cell_ids, old_cell_ids);
+
+    // - While a chain is locked for a rotation zome calls can't write to it
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+    let cell_id = app.cells()[0].cell_id();
+    let handle = conductor.inner_handle();
+    let chain = SourceChain::new(
+        handle.get_authored_db(cell_id.dna_hash()).unwrap(),
+        handle.get_dht_db(cell_id.dna_hash()).unwrap(),
+        handle.get_dht_db_cache(cell_id.dna_hash()).unwrap(),
+        handle.keystore().clone(),
+        cell_id.agent_pubkey().clone(),
+    )
+    .await
+    .unwrap();
+    chain
+        .lock_chain(
+            crate::conductor::cell::AGENT_KEY_ROTATION_LOCK.to_vec(),
+            (Timestamp::now() + std::time::Duration::from_secs(600)).unwrap(),
+        )
+        .await
+        .unwrap();
+    let r: Result<ActionHash, _> = conductor.call_fallible(&zome, "create_entry", ()).await;
+    assert!(r.is_err());
+    chain.unlock_chain().await.unwrap();
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+
+    // - A successful rotation records the new key on the old chain
+    //   and replaces the old cell in the app with one that joined
+    //   with the same membrane proof
+    let conductor =
+        SweetConductor::from_builder(Conductor::builder().with_dpki(Arc::new(AllowRotations)))
+            .await;
+    let handle = conductor.inner_handle();
+    handle.register_dna(dna.clone()).await.unwrap();
+    let old_cell_id = CellId::new(
+        dna.dna_hash().clone(),
+        handle.keystore().new_sign_keypair_random().await.unwrap(),
+    );
+    let proof: MembraneProof = Arc::new(SerializedBytes::from(UnsafeBytes::from(vec![1, 2, 3])));
+    handle
+        .clone()
+        .install_app(
+            "app".into(),
+            vec![(
+                InstalledCell::new(old_cell_id.clone(), "role".into()),
+                Some(proof.clone()),
+            )],
+        )
+        .await
+        .unwrap();
+    handle.clone().enable_app("app".into()).await.unwrap();
+    let new_key = handle
+        .clone()
+        .rotate_app_agent_key("app".into())
+        .await
+        .unwrap();
+    assert_ne!(&new_key, old_cell_id.agent_pubkey());
+    let new_cell_id = CellId::new(dna.dna_hash().clone(), new_key.clone());
+    assert_eq!(conductor.list_cell_ids(None), vec![new_cell_id.clone()]);
+    assert_matches!(
+        chain_updates(&handle, &old_cell_id).await[..],
+        [ref r] if r.entry().as_option() == Some(&Entry::Agent(new_key.clone()))
+    );
+    assert!(chain_updates(&handle, &new_cell_id).await.is_empty());
+    let new_chain = SourceChain::new(
+        handle.get_authored_db(dna.dna_hash()).unwrap(),
+        handle.get_dht_db(dna.dna_hash()).unwrap(),
+        handle.get_dht_db_cache(dna.dna_hash()).unwrap(),
+        handle.keystore().clone(),
+        new_key.clone(),
+    )
+    .await
+    .unwrap();
+    assert_matches!(
+        new_chain
+            .query(ChainQueryFilter::new().action_type(ActionType::AgentValidationPkg))
+            .await
+            .unwrap()[..],
+        [ref r] if matches!(r.action(), Action::AgentValidationPkg(p) if p.membrane_proof == Some(proof.clone()))
+    );
+}
+
 /// Check that the init() callback is only ever called once, even under many
 /// concurrent initial zome function calls
 #[tokio::test(flavor = "multi_thread")]
@@ -1009,3 +1765,88 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_network_goes_offline_and_rejoins() {
+    use holochain_p2p::DnaHashExt;
+    use kitsune_p2p::actor::NetworkStatus;
+
+    observability::test_run().ok();
+    let (driver, addr, shutdown) = kitsune_p2p_bootstrap::run(([127, 0, 0, 1], 0), vec![])
+        .await
+        .unwrap();
+    tokio::spawn(driver);
+    let bootstrap = url2::url2!("http://{}", addr);
+
+    let mut config = standard_config();
+    let network = config.network.as_mut().unwrap();
+    network.bootstrap_service = Some(bootstrap.clone());
+    let mut tuning_params = (*network.tuning_params).clone();
+    tuning_params.bootstrap_max_delay_ms = 500;
+    network.tuning_params = Arc::new(tuning_params);
+    let mut conductor = SweetConductor::from_config(config).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    conductor.setup_app("app", [&dna]).await.unwrap();
+    let dna_hash = dna.dna_hash().clone();
+
+    let wait_for_status = |expected: NetworkStatus| {
+        let conductor = &conductor;
+        let dna_hash = &dna_hash;
+        async move {
+            tokio::time::timeout(std::time::Duration::from_secs(30), async {
+                loop {
+                    let statuses = conductor.network_status().await.unwrap();
+                    if statuses.contains(&(dna_hash.clone(), expected)) {
+                        break;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                }
+            })
+            .await
+            .unwrap_or_else(|_| panic!("network never became {:?}", expected))
+        }
+    };
+    wait_for_status(NetworkStatus::Online).await;
+
+    // - Losing the bootstrap service takes the space offline
+    shutdown();
+    wait_for_status(NetworkStatus::Offline).await;
+
+    // - Once the bootstrap service is back the space is online again
+    let (driver, _, _shutdown) = kitsune_p2p_bootstrap::run(addr, vec![]).await.unwrap();
+    tokio::spawn(driver);
+    wait_for_status(NetworkStatus::Online).await;
+
+    // - and rejoining puts the agent info to the new bootstrap service,
+    //   which started out empty
+    let query = kitsune_p2p_types::bootstrap::RandomQuery {
+        space: dna_hash.to_kitsune(),
+        limit: 8.into(),
+    };
+    let client = reqwest::Client::new();
+    tokio::time::timeout(std::time::Duration::from_secs(30), async {
+        loop {
+            let mut body = Vec::new();
+            kitsune_p2p_types::codec::rmp_encode(&mut body, &query).unwrap();
+            let res = client
+                .post(bootstrap.as_str())
+                .body(body)
+                .header("X-Op", "random")
+                .header(reqwest::header::CONTENT_TYPE, "application/octet")
+                .send()
+                .await
+                .unwrap();
+            let agents: Vec<serde::de::IgnoredAny> =
+                kitsune_p2p_types::codec::rmp_decode(&mut res.bytes().await.unwrap().as_ref())
+                    .unwrap();
+            if agents.len() == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("the agent info was never put to the bootstrap service again");
+}
@@ -0,0 +1,67 @@
+//! The hook through which a DPKI (distributed public key infrastructure)
+//! service takes part in agent key management.
+//!
+//! When the agent key of an app is rotated, the conductor asks its
+//! [`DpkiService`] whether the rotation is allowed before anything is
+//! generated on the source chains. A conductor without a DPKI service
+//! uses [`NoDpki`], which refuses every rotation.
+
+use holochain_types::prelude::*;
+use thiserror::Error;
+
+/// The result of a request to a [`DpkiService`].
+pub type DpkiResult<T> = Result<T, DpkiError>;
+
+/// Why a [`DpkiService`] refused or failed a request.
+#[derive(Error, Debug)]
+pub enum DpkiError {
+    /// The service does not allow the agent to rotate to the new key.
+    #[error("The DPKI service refused to rotate agent key {old_key} to {new_key}: {reason}")]
+    RotationRefused {
+        /// The key being rotated away from.
+        old_key: AgentPubKey,
+        /// The key being rotated to.
+        new_key: AgentPubKey,
+        /// Why the rotation was refused.
+        reason: String,
+    },
+
+    /// The conductor has no DPKI service to approve the rotation.
+    #[error("No DPKI service is configured, so agent key {0} can't be rotated")]
+    NotConfigured(AgentPubKey),
+
+    /// The service could not be reached or failed to answer.
+    #[error("The DPKI service failed: {0}")]
+    Other(String),
+}
+
+/// A service which decides which keys an agent may use,
+/// for example a DPKI app running on the conductor.
+#[async_trait::async_trait]
+pub trait DpkiService: Send + Sync + std::fmt::Debug {
+    /// Check that the agent of an app may replace its current key with a new one.
+    /// An error stops the rotation.
+    async fn validate_key_rotation(
+        &self,
+        installed_app_id: &InstalledAppId,
+        old_key: &AgentPubKey,
+        new_key: &AgentPubKey,
+    ) -> DpkiResult<()>;
+}
+
+/// The [`DpkiService`] of a conductor which isn't using DPKI.
+/// Without a service to vouch for the new key, every key rotation is refused.
+#[derive(Debug, Default, Clone)]
+pub struct NoDpki;
+
+#[async_trait::async_trait]
+impl DpkiService for NoDpki {
+    async fn validate_key_rotation(
+        &self,
+        _installed_app_id: &InstalledAppId,
+        old_key: &AgentPubKey,
+        _new_key: &AgentPubKey,
+    ) -> DpkiResult<()> {
+        Err(DpkiError::NotConfigured(old_key.clone()))
+    }
+}
@@ -1,6 +1,7 @@
 use super::interface::error::InterfaceError;
 use super::{entry_def_store::error::EntryDefStoreError, state::AppInterfaceId};
 use crate::conductor::cell::error::CellError;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::workflow::error::WorkflowError;
 use holochain_conductor_api::conductor::ConductorConfigError;
 use holochain_sqlite::error::DatabaseError;
@@ -37,12 +38,21 @@ pub enum ConductorError {
     #[error("Cell was referenced, but is missing from the conductor. CellId: {0:?}")]
     CellMissing(CellId),
 
+    #[error("Cell is not paused. CellId: {0:?}")]
+    CellNotPaused(CellId),
+
+    #[error("Cell {0:?} is not part of the app {1}")]
+    CellNotInApp(CellId, InstalledAppId),
+
     #[error(transparent)]
     ConductorConfigError(#[from] ConductorConfigError),
 
     #[error("Configuration consistency error: {0}")]
     ConfigError(String),
 
+    #[error("Backup error: {0}")]
+    BackupError(String),
+
     #[error("Config deserialization error: {0}")]
     SerializationError(#[from] serde_yaml::Error),
 
@@ -89,6 +99,15 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error("Tried to migrate the cell {0:?} to the DNA it already uses")]
+    MigrationToSameDna(CellId),
+
+    #[error("The migrate_agent callbacks refused the migration of cell {0:?}: {1:?}")]
+    MigrateAgentFailed(CellId, MigrateAgentResult),
+
+    #[error(transparent)]
+    DpkiError(#[from] super::dpki::DpkiError),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -37,11 +37,15 @@
 
 use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
+use super::backup::ConductorBackup;
 use super::conductor::CellStatus;
 use super::config::AdminInterfaceConfig;
+use super::error::ConductorError;
 use super::error::ConductorResult;
 use super::interface::SignalBroadcaster;
+use super::interface::ZomeCallSigningError;
 use super::manager::spawn_task_manager;
+use super::manager::ManagedTaskAdd;
 use super::manager::TaskManagerClient;
 use super::manager::TaskManagerRunHandle;
 use super::p2p_agent_store;
@@ -53,39 +57,65 @@ use super::space::Spaces;
 use super::Cell;
 use super::CellError;
 use super::Conductor;
-use super::{full_integration_dump, integration_dump};
+use super::{full_integration_dump, integration_dump, rejections_dump};
 use crate::conductor::p2p_agent_store::get_single_agent_info;
 use crate::conductor::p2p_agent_store::query_peer_density;
 use crate::conductor::p2p_agent_store::P2pBatch;
 use crate::core::queue_consumer::QueueConsumerMap;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::RibosomeT;
+use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentChain;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentWorkflowArgs;
 use crate::core::workflow::ZomeCallResult;
 use derive_more::From;
 use futures::future::FutureExt;
 use futures::StreamExt;
 use holochain_conductor_api::conductor::ConductorConfig;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CapGrantInfo;
+use holochain_conductor_api::ConductorBindingsDump;
+use holochain_conductor_api::ConductorCellDump;
+use holochain_conductor_api::ConductorCellStatus;
+use holochain_conductor_api::ConductorDiskUsageDump;
+use holochain_conductor_api::ConductorDnaDump;
+use holochain_conductor_api::ConductorInterfaceDump;
+use holochain_conductor_api::ConductorStateDump;
+use holochain_conductor_api::DatabaseCompaction;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::SignedCapGrantRequest;
+use holochain_conductor_api::SignedZomeCall;
+use holochain_conductor_api::WasmCacheStats;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::actor::HolochainP2pRefToDna;
+use holochain_p2p::actor::PeerBlockTarget;
 use holochain_p2p::event::HolochainP2pEvent;
 use holochain_p2p::event::HolochainP2pEvent::*;
 use holochain_p2p::DnaHashExt;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_state::integrated_ops;
+use holochain_state::integrated_ops::IntegratedDhtOpsPage;
+use holochain_state::integrated_ops::IntegratedDhtOpsQuery;
 use holochain_state::prelude::SourceChainError;
 use holochain_state::prelude::SourceChainResult;
 use holochain_state::prelude::StateMutationError;
 use holochain_state::prelude::StateMutationResult;
 use holochain_state::source_chain;
+use holochain_state::validation_receipts;
+use holochain_state::validation_receipts::ValidationReceiptCount;
 use holochain_types::prelude::*;
+use kitsune_p2p::actor::NetworkStatus;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::SpaceGossipStatus;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::{collections::HashSet, sync::Arc};
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::OwnedPermit;
@@ -208,14 +238,32 @@ pub trait ConductorHandleT: Send + Sync {
     /// Prune expired agent_infos from the p2p agents database
     async fn prune_p2p_agents_db(&self) -> ConductorResult<()>;
 
-    /// Create a new Cell in an existing App based on an existing DNA
+    /// Create a new Cell in an existing App by cloning the DNA of one of its
+    /// roles with a new UID and properties. Returns the new Cell's id.
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId>;
 
-    /// Destroy a cloned Cell
-    async fn destroy_clone_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<()>;
+    /// Remove a cloned Cell from its App and stop it. The Cell's data is kept.
+    async fn disable_clone_cell(
+        self: Arc<Self>,
+        payload: DisableCloneCellPayload,
+    ) -> ConductorResult<()>;
+
+    /// Migrate the agent of an app role to a new version of the role's DNA,
+    /// closing the old source chain and opening a new one.
+    /// Returns the id of the new Cell, which replaces the old one in the app.
+    async fn migrate_cell(self: Arc<Self>, payload: MigrateCellPayload) -> ConductorResult<CellId>;
+
+    /// Move the agent of an app to a newly generated key, if the DPKI service allows it.
+    /// Each cell records the new key on its source chain and is replaced in the
+    /// app by a cell with the same DNA and the new key.
+    /// Returns the new key.
+    async fn rotate_app_agent_key(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+    ) -> ConductorResult<AgentPubKey>;
 
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
@@ -265,6 +313,15 @@ pub trait ConductorHandleT: Send + Sync {
     /// Start an enabled but stopped (paused) app
     async fn start_app(self: Arc<Self>, app_id: InstalledAppId) -> ConductorResult<InstalledApp>;
 
+    /// Pause a single Cell of an installed app. A paused Cell handles no zome
+    /// calls and its own queue consumers are stopped, but its app keeps running.
+    /// The Cell stays paused across restarts until it is resumed.
+    async fn pause_cell(&self, cell_id: CellId) -> ConductorResult<()>;
+
+    /// Resume a Cell which was paused with `pause_cell`, starting it again
+    /// if its app is running.
+    async fn resume_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<CellStartupErrors>;
+
     /// Start the scheduler. All ephemeral tasks are deleted.
     async fn start_scheduler(self: Arc<Self>, interval_period: std::time::Duration);
 
@@ -307,6 +364,17 @@ pub trait ConductorHandleT: Send + Sync {
         role_id: &AppRoleId,
     ) -> ConductorResult<Option<CellId>>;
 
+    /// The ids of all cells of an installed app, including its clones.
+    async fn app_cell_ids(&self, installed_app_id: &InstalledAppId)
+        -> ConductorResult<Vec<CellId>>;
+
+    /// Find the first running app the given cell is in, along with the role
+    /// of the cell in that app.
+    async fn find_app_and_role_of_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<(InstalledAppId, AppRoleId)>>;
+
     /// Get the IDs of all active installed Apps which use this Dna
     async fn list_running_apps_for_required_dna_hash(
         &self,
@@ -326,10 +394,97 @@ pub trait ConductorHandleT: Send + Sync {
     /// Dump the network metrics
     async fn dump_network_metrics(&self, dna_hash: Option<DnaHash>) -> ConductorApiResult<String>;
 
+    /// Get the progress of gossip for a DNA
+    async fn gossip_status(&self, dna_hash: DnaHash) -> ConductorApiResult<SpaceGossipStatus>;
+
+    /// Get whether the network of each running DNA can be reached
+    async fn network_status(&self) -> ConductorApiResult<Vec<(DnaHash, NetworkStatus)>>;
+
+    /// Block a peer in the network of a DNA
+    async fn block_peer(
+        &self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+        until: Option<Timestamp>,
+    ) -> ConductorApiResult<()>;
+
+    /// Remove the block on a peer in the network of a DNA
+    async fn unblock_peer(
+        &self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+    ) -> ConductorApiResult<()>;
+
+    /// Dump an overview of the apps, cells, workflow queues and network bindings
+    async fn dump_conductor_state(&self) -> ConductorApiResult<ConductorStateDump>;
+
+    /// Report on the conductor-wide wasm module caches
+    async fn wasm_cache_stats(&self) -> ConductorApiResult<WasmCacheStats>;
+
+    /// Empty the wasm module caches, in memory and on disk
+    async fn purge_wasm_cache(&self) -> ConductorApiResult<()>;
+
+    /// Snapshot all the databases into an archive file while running
+    async fn backup(&self, archive: PathBuf) -> ConductorApiResult<()>;
+
+    /// Vacuum all the databases, reporting the bytes reclaimed from each
+    async fn compact_databases(&self) -> ConductorApiResult<Vec<DatabaseCompaction>>;
+
+    /// Compact all the databases every `interval_period`.
+    fn start_db_compaction(self: Arc<Self>, interval_period: std::time::Duration);
+
+    /// Count the validation receipts of each op authored by a cell
+    async fn count_validation_receipts(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Vec<ValidationReceiptCount>>;
+
+    /// List one page of the ops integrated for a DNA that match a query
+    async fn query_integrated_dht_ops(
+        &self,
+        dna_hash: &DnaHash,
+        query: IntegratedDhtOpsQuery,
+    ) -> ConductorApiResult<IntegratedDhtOpsPage>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
 
+    /// Acknowledge delivery of a signal sent with `emit_signal_ack_required`.
+    /// Returns false if the signal is unknown or was already counted as undelivered.
+    async fn ack_signal(&self, id: u64) -> bool;
+
+    /// The number of ack-required signals from a cell which were never acknowledged
+    async fn undelivered_signal_count(&self, cell_id: &CellId) -> usize;
+
+    /// Send a reliable remote signal from a cell in the conductor's managed
+    /// task set, so it is stopped when the conductor shuts down.
+    /// Returns false without sending it if the cell already has
+    /// [`MAX_RELIABLE_SIGNALS_IN_FLIGHT`](super::api::MAX_RELIABLE_SIGNALS_IN_FLIGHT) in flight.
+    async fn spawn_reliable_signal(
+        &self,
+        cell_id: &CellId,
+        task: futures::future::BoxFuture<'static, ()>,
+    ) -> ConductorResult<bool>;
+
+    /// Whether app interfaces also accept zome calls which aren't signed by
+    /// their provenance.
+    fn allows_unsigned_zome_calls(&self) -> bool;
+
+    /// Check that a signed zome call from an app interface is signed by its
+    /// provenance with an unused nonce, and hasn't expired.
+    async fn verify_signed_zome_call(
+        &self,
+        signed: &SignedZomeCall,
+    ) -> Result<(), ZomeCallSigningError>;
+
+    /// Check that a cap grant request from an app interface is signed by the
+    /// agent of its cell with an unused nonce, and hasn't expired.
+    async fn verify_signed_cap_grant_request(
+        &self,
+        signed: &SignedCapGrantRequest,
+    ) -> Result<(), ZomeCallSigningError>;
+
     /// Get info about an installed App, whether active or inactive
     async fn get_app_info(
         &self,
@@ -361,6 +516,25 @@ pub trait ConductorHandleT: Send + Sync {
         records: Vec<Record>,
     ) -> ConductorApiResult<()>;
 
+    /// Write a zome call capability grant for a signing key onto the
+    /// source chain of a running cell, returning the secret if one is needed.
+    async fn grant_zome_call_capability(
+        &self,
+        payload: GrantZomeCallCapabilityPayload,
+    ) -> ConductorApiResult<Option<CapSecret>>;
+
+    /// List the zome call capability grants on the source chain of a running cell
+    /// which haven't been revoked.
+    async fn list_cap_grants(&self, cell_id: &CellId) -> ConductorApiResult<Vec<CapGrantInfo>>;
+
+    /// Revoke a zome call capability grant by deleting it from the source chain
+    /// of a running cell.
+    async fn revoke_cap_grant(
+        &self,
+        cell_id: &CellId,
+        action_hash: ActionHash,
+    ) -> ConductorApiResult<()>;
+
     /// Retrieve the authored environment for this dna. FOR TESTING ONLY.
     #[cfg(any(test, feature = "test_utils"))]
     fn get_authored_db(&self, cell_id: &DnaHash) -> ConductorApiResult<DbWrite<DbKindAuthored>>;
@@ -509,6 +683,73 @@ pub struct ConductorHandleImpl {
     pub dev_settings: parking_lot::RwLock<DevSettings>,
 }
 
+impl ConductorHandleImpl {
+    /// Record a new agent key on the locked chains of an app and save it as
+    /// the app's key, then publish the updates. Nothing is published unless
+    /// every chain and the app were updated, and the chains are put back as
+    /// they were if any of that fails.
+    async fn write_agent_key_update(
+        &self,
+        installed_app_id: InstalledAppId,
+        old_cells: &[Arc<Cell>],
+        new_key: AgentPubKey,
+    ) -> ConductorResult<Vec<(CellId, CellId)>> {
+        // Validate the key update on every old chain before any is written.
+        let mut staged = Vec::with_capacity(old_cells.len());
+        for cell in old_cells {
+            staged.push(cell.stage_agent_key_update(new_key.clone()).await?);
+        }
+
+        // The updates are neither published nor held in the DHT databases
+        // until all are written and the app uses the new key, so the ones
+        // already written can be taken back if anything fails.
+        let mut committed = Vec::with_capacity(old_cells.len());
+        let mut result = Ok(());
+        for (cell, workspace) in old_cells.iter().zip(staged) {
+            match cell.commit_agent_key_update(workspace).await {
+                Ok(seq) => committed.push((cell, seq)),
+                Err(e) => {
+                    result = Err(e.into());
+                    break;
+                }
+            }
+        }
+        let result = match result {
+            Ok(()) => {
+                self.conductor
+                    .rotate_agent_key_of_app(installed_app_id, new_key)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        let rotated = match result {
+            Ok(rotated) => rotated,
+            Err(e) => {
+                for (cell, seq) in committed {
+                    if !matches!(cell.revert_agent_key_update(seq).await, Ok(true)) {
+                        error!(
+                            cell_id = ?cell.id(),
+                            "Failed to take back the agent key update of a cell after a failed rotation"
+                        );
+                    }
+                }
+                return Err(e);
+            }
+        };
+
+        for (cell, seq) in committed {
+            if let Err(e) = cell.publish_agent_key_update(seq).await {
+                error!(
+                    cell_id = ?cell.id(),
+                    ?e,
+                    "Failed to publish the agent key update of a cell"
+                );
+            }
+        }
+        Ok(rotated)
+    }
+}
+
 #[async_trait::async_trait]
 impl ConductorHandleT for ConductorHandleImpl {
     /// Check that shutdown has not been called
@@ -532,7 +773,8 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.load_dnas().await?;
 
         // Start the task manager
-        let (task_add_sender, run_handle) = spawn_task_manager(self.clone());
+        let (task_add_sender, run_handle) =
+            spawn_task_manager(self.clone(), self.conductor.managed_tasks.clone());
         let (task_stop_broadcaster, _) = tokio::sync::broadcast::channel::<()>(1);
         self.conductor.task_manager.share_mut(|tm| {
             if tm.is_some() {
@@ -571,7 +813,8 @@ impl ConductorHandleT for ConductorHandleImpl {
     }
 
     async fn register_dna(&self, dna: DnaFile) -> ConductorResult<()> {
-        let ribosome = RealRibosome::new(dna)?;
+        let ribosome =
+            RealRibosome::new(dna)?.with_wasm_call_limits(self.conductor.wasm_call_limits());
         self.register_genotype(ribosome.clone()).await?;
         self.conductor.register_phenotype(ribosome);
         Ok(())
@@ -806,6 +1049,41 @@ impl ConductorHandleT for ConductorHandleImpl {
                 .instrument(debug_span!("handle_publish"))
                 .await;
             }
+            PublishWarrants {
+                dna_hash,
+                respond,
+                warrants,
+                ..
+            } => {
+                async {
+                    use holochain_p2p::HolochainP2pSender;
+                    let res = async {
+                        // Only hold warrants against data we are an authority for.
+                        let mut held = Vec::with_capacity(warrants.len());
+                        for warrant in warrants {
+                            if self
+                                .holochain_p2p()
+                                .authority_for_hash(
+                                    dna_hash.clone(),
+                                    warrant.warrant.warrant.basis.clone(),
+                                )
+                                .await?
+                            {
+                                held.push(warrant);
+                            }
+                        }
+                        self.conductor
+                            .spaces
+                            .handle_publish_warrants(&dna_hash, held)
+                            .await
+                            .map_err(holochain_p2p::HolochainP2pError::other)
+                    }
+                    .await;
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("handle_publish_warrants"))
+                .await;
+            }
             FetchOpData {
                 respond,
                 query,
@@ -895,29 +1173,250 @@ impl ConductorHandleT for ConductorHandleImpl {
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId> {
         let CreateCloneCellPayload {
-            properties,
-            dna_hash,
             installed_app_id,
-            agent_key,
             role_id,
+            uid,
+            properties,
             membrane_proof,
         } = payload;
-        let cell_id = CellId::new(dna_hash, agent_key);
-        let cells = vec![(cell_id.clone(), membrane_proof)];
 
-        // Run genesis on cells.
-        crate::conductor::conductor::genesis_cells(&self.conductor, cells, self.clone()).await?;
+        // Check the clone limit up front, so that no genesis is run for a
+        // clone which can't be added.
+        let role = {
+            let state = self.conductor.get_state().await?;
+            let app = state.get_app(&installed_app_id)?;
+            app.check_can_add_clone(&role_id)?;
+            app.role(&role_id)?.clone()
+        };
 
-        let properties = properties.unwrap_or_else(|| ().into());
-        let cell_id = self
-            .conductor
-            .add_clone_cell_to_app(installed_app_id, role_id, properties)
+        let child_dna = self
+            .get_dna_file(role.dna_hash())
+            .ok_or_else(|| DnaError::DnaMissing(role.dna_hash().clone()))?
+            .modify_phenotype(
+                uid.unwrap_or_else(random_uid),
+                properties.unwrap_or_else(|| ().into()),
+            )?;
+        let cell_id = CellId::new(child_dna.dna_hash().clone(), role.agent_key().clone());
+        if !self.list_dnas().contains(cell_id.dna_hash()) {
+            self.register_dna(child_dna).await?;
+        }
+
+        // Run genesis on the new cell.
+        crate::conductor::conductor::genesis_cells(
+            &self.conductor,
+            vec![(cell_id.clone(), membrane_proof)],
+            self.clone(),
+        )
+        .await?;
+
+        self.conductor
+            .add_clone_cell_to_app(installed_app_id, role_id, cell_id.clone())
             .await?;
+
+        // Start the clone if its app is running
+        let errors = self.clone().reconcile_cell_status_with_app_status().await?;
+        if !errors.is_empty() {
+            error!(msg = "Errors when trying to start clone cell", ?errors);
+        }
+
         Ok(cell_id)
     }
 
-    async fn destroy_clone_cell(self: Arc<Self>, _cell_id: CellId) -> ConductorResult<()> {
-        todo!()
+    async fn disable_clone_cell(
+        self: Arc<Self>,
+        payload: DisableCloneCellPayload,
+    ) -> ConductorResult<()> {
+        let DisableCloneCellPayload {
+            installed_app_id,
+            role_id,
+            clone_cell_id,
+        } = payload;
+        self.conductor
+            .remove_clone_cell_from_app(installed_app_id, role_id, clone_cell_id)
+            .await?;
+
+        // Stop the clone, which leaves its data in place
+        self.clone().reconcile_cell_status_with_app_status().await?;
+        Ok(())
+    }
+
+    async fn migrate_cell(self: Arc<Self>, payload: MigrateCellPayload) -> ConductorResult<CellId> {
+        let MigrateCellPayload {
+            installed_app_id,
+            role_id,
+            new_dna_hash,
+            membrane_proof,
+            carry_forward,
+        } = payload;
+
+        let old_cell_id = self
+            .conductor
+            .get_state()
+            .await?
+            .get_app(&installed_app_id)?
+            .role(&role_id)?
+            .cell_id()
+            .clone();
+        if *old_cell_id.dna_hash() == new_dna_hash {
+            return Err(ConductorError::MigrationToSameDna(old_cell_id));
+        }
+        let agent_key = old_cell_id.agent_pubkey().clone();
+        let new_cell_id = CellId::new(new_dna_hash, agent_key.clone());
+
+        // The old cell must be running to be closed.
+        let old_cell = self.conductor.cell_by_id(&old_cell_id)?;
+        let old_ribosome = self.get_ribosome(old_cell_id.dna_hash())?;
+        let new_ribosome = self.get_ribosome(new_cell_id.dna_hash())?;
+
+        // Run genesis on the new chain, which is a no-op if it already exists.
+        crate::conductor::conductor::genesis_cells(
+            &self.conductor,
+            vec![(new_cell_id.clone(), membrane_proof)],
+            self.clone(),
+        )
+        .await?;
+
+        let chain = |cell_id: &CellId, ribosome: RealRibosome, network| {
+            let space = self.conductor.get_or_create_space(cell_id.dna_hash());
+            let keystore = self.keystore().clone();
+            let agent_key = agent_key.clone();
+            async move {
+                let space = space?;
+                let workspace = SourceChainWorkspace::new(
+                    space.authored_db.clone(),
+                    space.dht_db.clone(),
+                    space.dht_query_cache.clone(),
+                    space.cache_db.clone(),
+                    keystore,
+                    agent_key,
+                    Arc::new(ribosome.dna_def().as_content().clone()),
+                )
+                .await
+                .map_err(CellError::from)?;
+                ConductorResult::Ok(MigrateAgentChain::new(workspace, network, ribosome))
+            }
+        };
+        let old = chain(
+            &old_cell_id,
+            old_ribosome,
+            old_cell.holochain_p2p_dna().clone(),
+        )
+        .await?;
+        let new = chain(
+            &new_cell_id,
+            new_ribosome,
+            self.holochain_p2p().to_dna(new_cell_id.dna_hash().clone()),
+        )
+        .await?;
+
+        let result = migrate_agent_workflow(MigrateAgentWorkflowArgs::new(
+            old,
+            new,
+            carry_forward,
+            self.clone(),
+        ))
+        .await?;
+        if result != MigrateAgentResult::Pass {
+            return Err(ConductorError::MigrateAgentFailed(old_cell_id, result));
+        }
+
+        self.conductor
+            .migrate_role_of_app(installed_app_id, role_id, new_cell_id.clone())
+            .await?;
+
+        // Stop the closed cell and start the new one
+        let errors = self.clone().reconcile_cell_status_with_app_status().await?;
+        if !errors.is_empty() {
+            error!(msg = "Errors when trying to start migrated cell", ?errors);
+        }
+
+        Ok(new_cell_id)
+    }
+
+    async fn rotate_app_agent_key(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+    ) -> ConductorResult<AgentPubKey> {
+        let app = self
+            .conductor
+            .get_state()
+            .await?
+            .get_app(&installed_app_id)?
+            .clone();
+        let old_key = app._agent_key().clone();
+
+        // The old cells must be running to record the new key.
+        // They are written in the order of their ids.
+        let mut old_cell_ids: Vec<_> = app.all_cells().collect();
+        old_cell_ids.sort();
+        let old_cells = old_cell_ids
+            .into_iter()
+            .map(|cell_id| self.conductor.cell_by_id(cell_id))
+            .collect::<ConductorResult<Vec<_>>>()?;
+
+        let new_key = self.keystore().new_sign_keypair_random().await?;
+        self.conductor
+            .dpki
+            .validate_key_rotation(&installed_app_id, &old_key, &new_key)
+            .await?;
+
+        // Run genesis for the new key with the membrane proofs the old
+        // cells joined with, before the old chains are changed,
+        // so a failure leaves the app as it was.
+        let mut new_cells = Vec::with_capacity(old_cells.len());
+        for cell in &old_cells {
+            new_cells.push((
+                CellId::new(cell.id().dna_hash().clone(), new_key.clone()),
+                cell.membrane_proof().await?,
+            ));
+        }
+        crate::conductor::conductor::genesis_cells(&self.conductor, new_cells, self.clone())
+            .await?;
+
+        // Hold the old chains locked for the whole rotation, so no zome call
+        // can write on top of an update which may still be taken back.
+        let mut locked = Vec::with_capacity(old_cells.len());
+        let mut result = Ok(());
+        for cell in &old_cells {
+            match cell.lock_for_agent_key_rotation().await {
+                Ok(()) => locked.push(cell),
+                Err(e) => {
+                    result = Err(e.into());
+                    break;
+                }
+            }
+        }
+        let result = match result {
+            Ok(()) => {
+                self.write_agent_key_update(installed_app_id, &old_cells, new_key.clone())
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        for cell in locked {
+            if let Err(e) = cell.unlock_after_agent_key_rotation().await {
+                error!(
+                    cell_id = ?cell.id(),
+                    ?e,
+                    "Failed to unlock the chain of a cell after rotating its agent key"
+                );
+            }
+        }
+        let rotated = result?;
+        tracing::info!(
+            ?old_key,
+            ?new_key,
+            ?rotated,
+            "Rotated the agent key of an app"
+        );
+
+        // Stop the old cells and start the new ones
+        let errors = self.clone().reconcile_cell_status_with_app_status().await?;
+        if !errors.is_empty() {
+            error!(msg = "Errors when trying to start rotated cells", ?errors);
+        }
+
+        Ok(new_key)
     }
 
     async fn install_app(
@@ -954,13 +1453,21 @@ impl ConductorHandleT for ConductorHandleImpl {
             installed_app_id,
             membrane_proofs,
             uid,
+            role_overrides,
         } = payload;
 
         let bundle: AppBundle = {
             let original_bundle = source.resolve().await?;
-            if let Some(uid) = uid {
+            if uid.is_some() || !role_overrides.is_empty() {
                 let mut manifest = original_bundle.manifest().to_owned();
-                manifest.set_uid(uid);
+                if let Some(uid) = uid {
+                    manifest.set_uid(uid);
+                }
+                for (role_id, DnaPhenotypeOverrides { uid, properties }) in role_overrides {
+                    manifest
+                        .override_role(&role_id, uid, properties)
+                        .map_err(AppBundleError::from)?;
+                }
                 AppBundle::from(original_bundle.into_inner().update_manifest(manifest)?)
             } else {
                 original_bundle
@@ -1107,6 +1614,18 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(app)
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn pause_cell(&self, cell_id: CellId) -> ConductorResult<()> {
+        self.conductor.pause_cell(cell_id).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn resume_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<CellStartupErrors> {
+        self.conductor.resume_cell(cell_id).await?;
+        self.create_and_add_initialized_cells_for_running_apps(self.clone())
+            .await
+    }
+
     #[tracing::instrument(skip(self))]
     #[cfg(any(test, feature = "test_utils"))]
     async fn pause_app(
@@ -1136,6 +1655,23 @@ impl ConductorHandleT for ConductorHandleImpl {
         self_clone
             .process_app_status_fx(AppStatusFx::SpinDown, None)
             .await?;
+
+        // Reclaim the disk used by the dnas which no other app uses.
+        let state = self.conductor.get_state().await?;
+        let dnas_in_use: HashSet<&DnaHash> = state
+            .installed_apps()
+            .values()
+            .flat_map(|app| app.all_cells())
+            .map(CellId::dna_hash)
+            .collect();
+        let unused_dnas: HashSet<&DnaHash> = app
+            .all_cells()
+            .map(CellId::dna_hash)
+            .filter(|dna_hash| !dnas_in_use.contains(dna_hash))
+            .collect();
+        for dna_hash in unused_dnas {
+            self.conductor.spaces.remove_space(dna_hash).await?;
+        }
         Ok(())
     }
 
@@ -1171,6 +1707,27 @@ impl ConductorHandleT for ConductorHandleImpl {
             .await
     }
 
+    async fn app_cell_ids(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<Vec<CellId>> {
+        Ok(self
+            .conductor
+            .get_state()
+            .await?
+            .get_app(installed_app_id)?
+            .all_cells()
+            .cloned()
+            .collect())
+    }
+
+    async fn find_app_and_role_of_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<(InstalledAppId, AppRoleId)>> {
+        self.conductor.find_app_and_role_of_cell(cell_id).await
+    }
+
     async fn list_running_apps_for_required_dna_hash(
         &self,
         dna_hash: &DnaHash,
@@ -1197,6 +1754,7 @@ impl ConductorHandleT for ConductorHandleImpl {
             peer_dump,
             source_chain_dump,
             integration_dump: integration_dump(&dht_db.clone().into()).await?,
+            rejections: rejections_dump(&dht_db.clone().into()).await?,
         };
         // Add summary
         let summary = out.to_string();
@@ -1237,10 +1795,317 @@ impl ConductorHandleT for ConductorHandleImpl {
             .map_err(super::api::error::ConductorApiError::other)
     }
 
+    async fn gossip_status(&self, dna_hash: DnaHash) -> ConductorApiResult<SpaceGossipStatus> {
+        use holochain_p2p::HolochainP2pSender;
+        self.holochain_p2p()
+            .gossip_status(dna_hash)
+            .await
+            .map_err(super::api::error::ConductorApiError::other)
+    }
+
+    async fn network_status(&self) -> ConductorApiResult<Vec<(DnaHash, NetworkStatus)>> {
+        use holochain_p2p::HolochainP2pSender;
+        let dna_hashes: HashSet<DnaHash> = self
+            .conductor
+            .running_cell_ids()
+            .into_iter()
+            .map(|cell_id| cell_id.dna_hash().clone())
+            .collect();
+        let mut statuses = Vec::with_capacity(dna_hashes.len());
+        for dna_hash in dna_hashes {
+            let status = self
+                .holochain_p2p()
+                .network_status(dna_hash.clone())
+                .await
+                .map_err(super::api::error::ConductorApiError::other)?;
+            statuses.push((dna_hash, status));
+        }
+        Ok(statuses)
+    }
+
+    async fn block_peer(
+        &self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+        until: Option<Timestamp>,
+    ) -> ConductorApiResult<()> {
+        use holochain_p2p::HolochainP2pSender;
+        self.holochain_p2p()
+            .block_peer(dna_hash, target, until)
+            .await
+            .map_err(super::api::error::ConductorApiError::other)
+    }
+
+    async fn unblock_peer(
+        &self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+    ) -> ConductorApiResult<()> {
+        use holochain_p2p::HolochainP2pSender;
+        self.holochain_p2p()
+            .unblock_peer(dna_hash, target)
+            .await
+            .map_err(super::api::error::ConductorApiError::other)
+    }
+
+    async fn dump_conductor_state(&self) -> ConductorApiResult<ConductorStateDump> {
+        use holochain_p2p::HolochainP2pSender;
+        let apps = self.conductor.list_apps(None).await?;
+        let app_roles: HashMap<CellId, (InstalledAppId, AppRoleId)> = apps
+            .iter()
+            .flat_map(|app| {
+                app.cell_data.iter().map(move |cell| {
+                    (
+                        cell.as_id().clone(),
+                        (app.installed_app_id.clone(), cell.as_role_id().clone()),
+                    )
+                })
+            })
+            .collect();
+
+        let cells = self.conductor.list_cells_with_status();
+        let paused_cells = self.conductor.get_state().await?.paused_cells().clone();
+        let dna_hashes: HashSet<DnaHash> = cells
+            .iter()
+            .map(|(cell_id, _)| cell_id)
+            .chain(paused_cells.iter())
+            .map(|cell_id| cell_id.dna_hash().clone())
+            .collect();
+        let mut dnas = Vec::with_capacity(dna_hashes.len());
+        for dna_hash in dna_hashes {
+            let space = self.conductor.get_or_create_space(&dna_hash)?;
+            let db_bytes = space.authored_db.size().await?
+                + space.dht_db.size().await?
+                + space.cache_db.size().await?;
+            let mut roles: Vec<_> = app_roles
+                .iter()
+                .filter(|(cell_id, _)| cell_id.dna_hash() == &dna_hash)
+                .map(|(_, app_role)| app_role.clone())
+                .collect();
+            roles.sort();
+            roles.dedup();
+            dnas.push(ConductorDnaDump {
+                dna_hash,
+                roles,
+                db_bytes,
+                integration_dump: integration_dump(&space.dht_db.into()).await?,
+            });
+        }
+        let cells = cells
+            .into_iter()
+            .map(|(cell_id, status)| (cell_id, status.into()))
+            .chain(
+                paused_cells
+                    .into_iter()
+                    .map(|cell_id| (cell_id, ConductorCellStatus::Paused)),
+            )
+            .map(|(cell_id, status)| ConductorCellDump {
+                app_role: app_roles.get(&cell_id).cloned(),
+                cell_id,
+                status,
+            })
+            .collect();
+
+        let interface_dump = |port| ConductorInterfaceDump {
+            port,
+            connections: self.conductor.interface_connections(port),
+        };
+        let bindings = ConductorBindingsDump {
+            admin_interfaces: self
+                .conductor
+                .list_admin_interfaces()
+                .into_iter()
+                .map(interface_dump)
+                .collect(),
+            app_interfaces: self
+                .conductor
+                .list_app_interfaces()
+                .await?
+                .into_iter()
+                .map(interface_dump)
+                .collect(),
+            transport: self
+                .holochain_p2p()
+                .list_transport_bindings()
+                .await
+                .map_err(super::api::error::ConductorApiError::other)?,
+        };
+
+        let environment_path = self.conductor.config.environment_path.clone().into();
+        let disk_usage =
+            tokio::task::spawn_blocking(move || ConductorDiskUsageDump::measure(environment_path))
+                .await
+                .map_err(ConductorError::from)??;
+
+        Ok(ConductorStateDump {
+            apps,
+            cells,
+            dnas,
+            bindings,
+            tasks: self.conductor.managed_tasks.list(),
+            disk_usage,
+        })
+    }
+
+    async fn wasm_cache_stats(&self) -> ConductorApiResult<WasmCacheStats> {
+        Ok(
+            tokio::task::spawn_blocking(crate::core::ribosome::wasm_cache::stats)
+                .await
+                .map_err(ConductorError::from)?,
+        )
+    }
+
+    async fn purge_wasm_cache(&self) -> ConductorApiResult<()> {
+        Ok(
+            tokio::task::spawn_blocking(crate::core::ribosome::wasm_cache::purge)
+                .await
+                .map_err(ConductorError::from)??,
+        )
+    }
+
+    async fn backup(&self, archive: PathBuf) -> ConductorApiResult<()> {
+        let backup = ConductorBackup::take(&self.conductor.spaces, &archive).await?;
+        tracing::info!(
+            ?archive,
+            files = backup.files.len(),
+            "Backed up the conductor"
+        );
+        Ok(())
+    }
+
+    async fn compact_databases(&self) -> ConductorApiResult<Vec<DatabaseCompaction>> {
+        Ok(self.conductor.spaces.compact_databases().await?)
+    }
+
+    fn start_db_compaction(self: Arc<Self>, interval_period: std::time::Duration) {
+        tokio::task::spawn(async move {
+            let mut interval = tokio::time::interval(interval_period);
+            // The first tick completes immediately, and there's
+            // no point compacting the databases as the conductor starts.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match self.compact_databases().await {
+                    Ok(compactions) => tracing::info!(
+                        reclaimed_bytes =
+                            compactions.iter().map(|c| c.reclaimed_bytes).sum::<u64>(),
+                        "Compacted the databases"
+                    ),
+                    Err(e) => tracing::error!(?e, "Failed to compact the databases"),
+                }
+            }
+        });
+    }
+
+    async fn count_validation_receipts(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Vec<ValidationReceiptCount>> {
+        let authored_db = self
+            .conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?;
+        let author = cell_id.agent_pubkey().clone();
+        Ok(authored_db
+            .async_reader(move |txn| validation_receipts::count_authored(&txn, &author))
+            .await?)
+    }
+
+    async fn query_integrated_dht_ops(
+        &self,
+        dna_hash: &DnaHash,
+        query: IntegratedDhtOpsQuery,
+    ) -> ConductorApiResult<IntegratedDhtOpsPage> {
+        let dht_db = self.conductor.get_or_create_dht_db(dna_hash)?;
+        Ok(dht_db
+            .async_reader(move |txn| integrated_ops::query_integrated(&txn, &query))
+            .await?)
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.signal_broadcaster()
     }
 
+    async fn ack_signal(&self, id: u64) -> bool {
+        self.conductor.ack_signal(id)
+    }
+
+    async fn undelivered_signal_count(&self, cell_id: &CellId) -> usize {
+        self.conductor.undelivered_signal_count(cell_id)
+    }
+
+    async fn spawn_reliable_signal(
+        &self,
+        cell_id: &CellId,
+        task: futures::future::BoxFuture<'static, ()>,
+    ) -> ConductorResult<bool> {
+        let in_flight = match self.conductor.reliable_signals.start(cell_id) {
+            Some(in_flight) => in_flight,
+            None => return Ok(false),
+        };
+        let mut stop_rx = self.conductor.task_manager.share_ref(|tm| {
+            tm.as_ref()
+                .expect("Task manager not initialized")
+                .task_stop_broadcaster()
+                .subscribe()
+        });
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = task => (),
+                _ = stop_rx.recv() => (),
+            }
+            drop(in_flight);
+            Ok(())
+        });
+        self.conductor
+            .manage_task(ManagedTaskAdd::ignore(handle, "reliable remote signal"))
+            .await?;
+        Ok(true)
+    }
+
+    fn allows_unsigned_zome_calls(&self) -> bool {
+        self.conductor.config.danger_allow_unsigned_zome_calls
+    }
+
+    async fn verify_signed_zome_call(
+        &self,
+        signed: &SignedZomeCall,
+    ) -> Result<(), ZomeCallSigningError> {
+        let data = SignedZomeCall::data_to_sign(&signed.call, &signed.nonce, signed.expires_at)?;
+        if !signed
+            .call
+            .provenance
+            .verify_signature_raw(&signed.signature, data.into())
+            .await
+        {
+            return Err(ZomeCallSigningError::InvalidSignature);
+        }
+        // The nonce is only used up once the signature is known to be good,
+        // so nobody else can burn it.
+        self.conductor
+            .zome_call_nonces
+            .check(&signed.call.provenance, signed.nonce, signed.expires_at)
+            .await
+    }
+
+    async fn verify_signed_cap_grant_request(
+        &self,
+        signed: &SignedCapGrantRequest,
+    ) -> Result<(), ZomeCallSigningError> {
+        let data =
+            SignedCapGrantRequest::data_to_sign(&signed.request, &signed.nonce, signed.expires_at)?;
+        let agent = signed.request.cell_id().agent_pubkey();
+        if !agent
+            .verify_signature_raw(&signed.signature, data.into())
+            .await
+        {
+            return Err(ZomeCallSigningError::InvalidSignature);
+        }
+        self.conductor
+            .zome_call_nonces
+            .check(agent, signed.nonce, signed.expires_at)
+            .await
+    }
+
     async fn get_app_info(
         &self,
         installed_app_id: &InstalledAppId,
@@ -1466,6 +2331,38 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(())
     }
 
+    async fn grant_zome_call_capability(
+        &self,
+        payload: GrantZomeCallCapabilityPayload,
+    ) -> ConductorApiResult<Option<CapSecret>> {
+        let cell = self.conductor.cell_by_id(&payload.cell_id)?;
+
+        let mut secret = [0; CAP_SECRET_BYTES];
+        getrandom::getrandom(&mut secret)
+            .map_err(|e| super::api::error::ConductorApiError::other(e.to_string()))?;
+        let secret = CapSecret::from(secret);
+
+        let access = payload.access;
+        cell.grant_zome_call_capability(payload.into_cap_grant(secret))
+            .await?;
+        Ok((access != ZomeCallCapAccess::Unrestricted).then_some(secret))
+    }
+
+    async fn list_cap_grants(&self, cell_id: &CellId) -> ConductorApiResult<Vec<CapGrantInfo>> {
+        let cell = self.conductor.cell_by_id(cell_id)?;
+        Ok(cell.list_cap_grants().await?)
+    }
+
+    async fn revoke_cap_grant(
+        &self,
+        cell_id: &CellId,
+        action_hash: ActionHash,
+    ) -> ConductorApiResult<()> {
+        let cell = self.conductor.cell_by_id(cell_id)?;
+        cell.revoke_cap_grant(action_hash).await?;
+        Ok(())
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     fn get_authored_db(&self, dna_hash: &DnaHash) -> ConductorApiResult<DbWrite<DbKindAuthored>> {
         Ok(self.conductor.get_or_create_authored_db(dna_hash)?)
@@ -8,8 +8,20 @@
 use crate::conductor::api::*;
 use error::InterfaceError;
 use error::InterfaceResult;
+use holo_hash::AgentPubKey;
+use holochain_sqlite::prelude::*;
+use holochain_types::prelude::AppSignal;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::InstalledAppId;
+use holochain_types::prelude::Timestamp;
 use holochain_types::signal::Signal;
+use rusqlite::named_params;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 use tokio::sync::broadcast;
 
 #[allow(missing_docs)]
@@ -50,6 +62,7 @@ impl AppInterfaceRuntime {
 #[derive(Clone, Debug)]
 pub struct SignalBroadcaster {
     senders: Vec<broadcast::Sender<Signal>>,
+    acks: SignalAcks,
 }
 
 impl SignalBroadcaster {
@@ -63,17 +76,45 @@ impl SignalBroadcaster {
         Ok(())
     }
 
+    /// Send an app signal which the client must acknowledge with
+    /// `AppRequest::AckSignal`.
+    ///
+    /// The signal is tracked until it is acknowledged, and is counted as
+    /// undelivered if no client is connected to receive it or if no
+    /// acknowledgement arrives within [`SIGNAL_ACK_TIMEOUT`].
+    /// Undelivered signals are not an error for the emitting zome.
+    pub fn send_ack_required(&mut self, cell_id: CellId, sig: AppSignal) {
+        let receivers: usize = self.senders.iter().map(|tx| tx.receiver_count()).sum();
+        if receivers == 0 {
+            self.acks.undelivered(cell_id);
+            return;
+        }
+        let id = self.acks.track(cell_id.clone());
+        for tx in self.senders.iter() {
+            // An interface without clients can't deliver the signal,
+            // which is what the ack is tracking.
+            let _ = tx.send(Signal::AppAckRequired(id, cell_id.clone(), sig.clone()));
+        }
+    }
+
     /// internal constructor
     pub fn new(senders: Vec<broadcast::Sender<Signal>>) -> Self {
-        Self { senders }
+        Self {
+            senders,
+            acks: SignalAcks::default(),
+        }
+    }
+
+    /// Track ack-required signals with the given [`SignalAcks`]
+    pub fn with_acks(mut self, acks: SignalAcks) -> Self {
+        self.acks = acks;
+        self
     }
 
     #[cfg(test)]
     /// A sender with nothing to send to. A placeholder for tests
     pub fn noop() -> Self {
-        Self {
-            senders: Vec::new(),
-        }
+        Self::new(Vec::new())
     }
 
     #[cfg(any(test, feature = "test_utils"))]
@@ -98,4 +139,372 @@ impl SignalBroadcaster {
     }
 }
 
+/// How long a client has to acknowledge an ack-required signal before
+/// it is counted as undelivered.
+pub const SIGNAL_ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracks the signals sent with [`SignalBroadcaster::send_ack_required`]
+/// until a client acknowledges them, and counts the ones which were never
+/// acknowledged, per cell.
+#[derive(Clone, Debug, Default)]
+pub struct SignalAcks(Arc<parking_lot::Mutex<SignalAcksInner>>);
+
+#[derive(Debug, Default)]
+struct SignalAcksInner {
+    next_id: u64,
+    pending: HashMap<u64, (CellId, Instant)>,
+    undelivered: HashMap<CellId, usize>,
+}
+
+impl SignalAcksInner {
+    /// Count the pending signals which have timed out as undelivered.
+    fn expire(&mut self, now: Instant) {
+        let undelivered = &mut self.undelivered;
+        self.pending.retain(|_, (cell_id, sent)| {
+            if now.saturating_duration_since(*sent) < SIGNAL_ACK_TIMEOUT {
+                true
+            } else {
+                *undelivered.entry(cell_id.clone()).or_default() += 1;
+                false
+            }
+        });
+    }
+}
+
+impl SignalAcks {
+    /// Start tracking a signal sent from this cell and get its id.
+    pub fn track(&self, cell_id: CellId) -> u64 {
+        self.track_at(cell_id, Instant::now())
+    }
+
+    fn track_at(&self, cell_id: CellId, now: Instant) -> u64 {
+        let mut inner = self.0.lock();
+        inner.expire(now);
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.pending.insert(id, (cell_id, now));
+        id
+    }
+
+    /// Count a signal from this cell as undelivered without tracking it.
+    pub fn undelivered(&self, cell_id: CellId) {
+        *self.0.lock().undelivered.entry(cell_id).or_default() += 1;
+    }
+
+    /// Acknowledge a signal.
+    /// Returns false if the id is unknown, has already been acknowledged
+    /// or was acknowledged too late and already counted as undelivered.
+    pub fn ack(&self, id: u64) -> bool {
+        self.ack_at(id, Instant::now())
+    }
+
+    fn ack_at(&self, id: u64, now: Instant) -> bool {
+        let mut inner = self.0.lock();
+        inner.expire(now);
+        inner.pending.remove(&id).is_some()
+    }
+
+    /// The number of ack-required signals from this cell which were
+    /// never acknowledged.
+    pub fn undelivered_count(&self, cell_id: &CellId) -> usize {
+        self.undelivered_count_at(cell_id, Instant::now())
+    }
+
+    fn undelivered_count_at(&self, cell_id: &CellId, now: Instant) -> usize {
+        let mut inner = self.0.lock();
+        inner.expire(now);
+        inner.undelivered.get(cell_id).copied().unwrap_or_default()
+    }
+}
+
+/// The cells a single app interface connection wants signals from,
+/// for each app it has subscribed to with `AppRequest::SignalSubscription`.
+///
+/// A connection which hasn't subscribed to any app receives every signal.
+#[derive(Clone, Debug, Default)]
+pub struct SignalSubscriptions(Arc<parking_lot::RwLock<HashMap<InstalledAppId, HashSet<CellId>>>>);
+
+impl SignalSubscriptions {
+    /// Replace the subscription to an app with the cells given.
+    pub fn subscribe(&self, installed_app_id: InstalledAppId, cells: HashSet<CellId>) {
+        self.0.write().insert(installed_app_id, cells);
+    }
+
+    /// Whether this connection should be sent the signal.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::App(cell_id, _) | Signal::AppAckRequired(_, cell_id, _) => {
+                self.allows_cell(Some(cell_id))
+            }
+            Signal::System(_) => true,
+        }
+    }
+
+    /// Whether this connection should be sent a signal from this cell.
+    /// Signals that aren't from a cell are always sent.
+    pub fn allows_cell(&self, cell_id: Option<&CellId>) -> bool {
+        let cell_id = match cell_id {
+            Some(cell_id) => cell_id,
+            None => return true,
+        };
+        let subscriptions = self.0.read();
+        subscriptions.is_empty() || subscriptions.values().any(|cells| cells.contains(cell_id))
+    }
+}
+
+/// How far in the future the expiry of a signed zome call may be.
+/// Nonces are remembered until their call expires, so this bounds
+/// how many are kept.
+pub const MAX_ZOME_CALL_SIGNATURE_VALIDITY: Duration = Duration::from_secs(5 * 60);
+
+/// Why a zome call made over an app interface wasn't accepted.
+#[derive(Debug, thiserror::Error)]
+pub enum ZomeCallSigningError {
+    /// The conductor only accepts signed zome calls.
+    #[error("This conductor only accepts signed zome calls, use AppRequest::SignedZomeCall")]
+    SignatureRequired,
+    /// The signature isn't by the provenance of the call.
+    #[error("The zome call is not signed by its provenance")]
+    InvalidSignature,
+    /// The expiry of the call has passed.
+    #[error("The signed zome call expired at {0}")]
+    Expired(Timestamp),
+    /// The expiry of the call is further away than [`MAX_ZOME_CALL_SIGNATURE_VALIDITY`].
+    #[error("The signed zome call expires at {0}, which is too far in the future")]
+    ExpiryTooFar(Timestamp),
+    /// The provenance has already made a call with this nonce.
+    #[error("The nonce of the signed zome call has already been used")]
+    NonceReused,
+    /// The call couldn't be encoded to check the signature.
+    #[error(transparent)]
+    Serialization(#[from] holochain_serialized_bytes::SerializedBytesError),
+    /// The used nonces couldn't be read or written.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+/// The nonces of the signed zome calls which haven't expired yet,
+/// so that each signed call is only accepted once.
+/// They are kept in the conductor database, so a captured call
+/// can't be replayed after the conductor restarts either.
+#[derive(Clone)]
+pub struct ZomeCallNonces(DbWrite<DbKindConductor>);
+
+impl ZomeCallNonces {
+    /// Keep the nonces in this conductor database.
+    pub fn new(db: DbWrite<DbKindConductor>) -> Self {
+        Self(db)
+    }
+
+    /// Check the expiry of a signed zome call and use up its nonce.
+    pub async fn check(
+        &self,
+        provenance: &AgentPubKey,
+        nonce: ZomeCallNonce,
+        expires_at: Timestamp,
+    ) -> Result<(), ZomeCallSigningError> {
+        self.check_at(provenance, nonce, expires_at, Timestamp::now())
+            .await
+    }
+
+    async fn check_at(
+        &self,
+        provenance: &AgentPubKey,
+        nonce: ZomeCallNonce,
+        expires_at: Timestamp,
+        now: Timestamp,
+    ) -> Result<(), ZomeCallSigningError> {
+        if expires_at <= now {
+            return Err(ZomeCallSigningError::Expired(expires_at));
+        }
+        if expires_at > now.saturating_add(&MAX_ZOME_CALL_SIGNATURE_VALIDITY) {
+            return Err(ZomeCallSigningError::ExpiryTooFar(expires_at));
+        }
+        let provenance = provenance.clone();
+        self.0
+            .async_commit(move |txn| {
+                txn.execute(
+                    "DELETE FROM ZomeCallNonce WHERE expires_at <= :now",
+                    named_params! { ":now": now },
+                )
+                .map_err(DatabaseError::from)?;
+                let inserted = txn
+                    .execute(
+                        "
+                        INSERT INTO ZomeCallNonce (agent, nonce, expires_at)
+                        VALUES (:agent, :nonce, :expires_at)
+                        ",
+                        named_params! {
+                            ":agent": provenance,
+                            ":nonce": &nonce[..],
+                            ":expires_at": expires_at,
+                        },
+                    )
+                    .map_err(DatabaseError::from)?;
+                if inserted == 0 {
+                    Err(ZomeCallSigningError::NonceReused)
+                } else {
+                    Ok(())
+                }
+            })
+            .await
+    }
+}
+
 pub use holochain_conductor_api::config::InterfaceDriver;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::prelude::*;
+    use holochain_types::test_utils::fake_agent_pubkey_1;
+    use holochain_types::test_utils::fake_agent_pubkey_2;
+    use holochain_types::test_utils::fake_cell_id;
+    use matches::assert_matches;
+
+    fn app_signal() -> AppSignal {
+        AppSignal::new(ExternIO::encode(()).unwrap())
+    }
+
+    #[test]
+    fn signal_acks_count_timed_out_signals() {
+        let acks = SignalAcks::default();
+        let cell_id = fake_cell_id(1);
+        let now = Instant::now();
+
+        let acked = acks.track_at(cell_id.clone(), now);
+        let missed = acks.track_at(cell_id.clone(), now);
+        assert_ne!(acked, missed);
+
+        assert!(acks.ack_at(acked, now));
+        // can't ack twice
+        assert!(!acks.ack_at(acked, now));
+        assert_eq!(acks.undelivered_count_at(&cell_id, now), 0);
+
+        let later = now + SIGNAL_ACK_TIMEOUT;
+        assert_eq!(acks.undelivered_count_at(&cell_id, later), 1);
+        // too late
+        assert!(!acks.ack_at(missed, later));
+        assert_eq!(acks.undelivered_count_at(&fake_cell_id(2), later), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ack_required_signals_without_clients_are_undelivered() {
+        let (tx, _) = broadcast::channel(10);
+        let acks = SignalAcks::default();
+        let mut broadcaster = SignalBroadcaster::new(vec![tx.clone()]).with_acks(acks.clone());
+        let cell_id = fake_cell_id(1);
+
+        broadcaster.send_ack_required(cell_id.clone(), app_signal());
+        assert_eq!(acks.undelivered_count(&cell_id), 1);
+
+        let mut rx = tx.subscribe();
+        broadcaster.send_ack_required(cell_id.clone(), app_signal());
+        let id = match rx.recv().await.unwrap() {
+            Signal::AppAckRequired(id, c, s) if c == cell_id && s == app_signal() => id,
+            s => panic!("unexpected signal {:?}", s),
+        };
+        assert!(acks.ack(id));
+        assert_eq!(acks.undelivered_count(&cell_id), 1);
+    }
+
+    fn num_nonces(
+        db: &DbWrite<DbKindConductor>,
+        agent: &AgentPubKey,
+        nonce: ZomeCallNonce,
+    ) -> usize {
+        db.test_commit(|txn| {
+            txn.query_row(
+                "SELECT COUNT(*) FROM ZomeCallNonce WHERE agent = :agent AND nonce = :nonce",
+                named_params! { ":agent": agent, ":nonce": &nonce[..] },
+                |row| row.get(0),
+            )
+            .unwrap()
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn zome_call_nonces_can_only_be_used_once() {
+        let db = DbWrite::test_in_mem(DbKindConductor).unwrap();
+        let nonces = ZomeCallNonces::new(db.clone());
+        let alice = fake_agent_pubkey_1();
+        let bob = fake_agent_pubkey_2();
+        let now = Timestamp::now();
+        let expires_at = now.saturating_add(&Duration::from_secs(60));
+
+        nonces
+            .check_at(&alice, [1; 32], expires_at, now)
+            .await
+            .unwrap();
+        assert_matches!(
+            nonces.check_at(&alice, [1; 32], expires_at, now).await,
+            Err(ZomeCallSigningError::NonceReused)
+        );
+        // Nonces are per provenance.
+        nonces
+            .check_at(&bob, [1; 32], expires_at, now)
+            .await
+            .unwrap();
+
+        // Nonces are still used up after a restart.
+        let restarted = ZomeCallNonces::new(db.clone());
+        assert_matches!(
+            restarted.check_at(&alice, [1; 32], expires_at, now).await,
+            Err(ZomeCallSigningError::NonceReused)
+        );
+
+        assert_matches!(
+            nonces.check_at(&alice, [2; 32], now, now).await,
+            Err(ZomeCallSigningError::Expired(_))
+        );
+        assert_matches!(
+            nonces
+                .check_at(
+                    &alice,
+                    [2; 32],
+                    now.saturating_add(&(MAX_ZOME_CALL_SIGNATURE_VALIDITY * 2)),
+                    now
+                )
+                .await,
+            Err(ZomeCallSigningError::ExpiryTooFar(_))
+        );
+
+        // Expired nonces are forgotten.
+        let later = expires_at.saturating_add(&Duration::from_secs(1));
+        assert_eq!(num_nonces(&db, &alice, [1; 32]), 1);
+        nonces
+            .check_at(
+                &alice,
+                [3; 32],
+                later.saturating_add(&Duration::from_secs(60)),
+                later,
+            )
+            .await
+            .unwrap();
+        assert_eq!(num_nonces(&db, &alice, [1; 32]), 0);
+    }
+
+    #[test]
+    fn signal_subscriptions_filter_app_signals() {
+        let subscriptions = SignalSubscriptions::default();
+        let alice = fake_cell_id(1);
+        let bob = fake_cell_id(2);
+        let from = |cell_id: &CellId| Signal::App(cell_id.clone(), app_signal());
+        let system = Signal::System(SystemSignal::Test("test".into()));
+
+        // Everything gets through until something is subscribed to.
+        assert!(subscriptions.allows(&from(&alice)));
+        assert!(subscriptions.allows(&from(&bob)));
+
+        subscriptions.subscribe("app".into(), [alice.clone()].into_iter().collect());
+        assert!(subscriptions.allows(&from(&alice)));
+        assert!(!subscriptions.allows(&from(&bob)));
+        assert!(!subscriptions.allows(&Signal::AppAckRequired(0, bob.clone(), app_signal())));
+        assert!(subscriptions.allows(&system));
+
+        // Resubscribing replaces the app's cells.
+        subscriptions.subscribe("app".into(), HashSet::new());
+        assert!(!subscriptions.allows(&from(&alice)));
+        assert!(subscriptions.allows(&system));
+    }
+}
@@ -3,15 +3,21 @@
 
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
+use crate::conductor::api::RealAppInterfaceApi;
 use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
 use holochain_serialized_bytes::SerializedBytes;
+use holochain_serialized_bytes::UnsafeBytes;
 use holochain_types::signal::Signal;
+use holochain_websocket::Bytes;
+use holochain_websocket::HandshakeInfo;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::Rejection;
 use holochain_websocket::WebsocketConfig;
+use holochain_websocket::WebsocketError;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
@@ -32,29 +38,72 @@ use url2::url2;
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 const MAX_CONNECTIONS: isize = 400;
 
-/// Create a WebsocketListener to be used in interfaces
+/// Create a WebsocketListener to be used in interfaces.
+/// If `auth_token` is set, connections which don't present it are refused.
 pub async fn spawn_websocket_listener(
     port: u16,
+    auth_token: Option<String>,
 ) -> InterfaceResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
     trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let mut config = WebsocketConfig::default();
+    if let Some(auth_token) = auth_token {
+        let auth_token = Arc::new(auth_token);
+        config = config.on_connect(move |info| {
+            let auth_token = auth_token.clone();
+            async move { check_auth_token(&info, &auth_token) }
+        });
+    }
+    let listener =
+        WebsocketListener::bind_with_handle(url2!("ws://127.0.0.1:{}", port), Arc::new(config))
+            .await?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
     Ok(listener)
 }
 
+/// Refuse a connection which doesn't present the token, either as the
+/// percent-encoded `token` query parameter or as an `Authorization: Bearer` header.
+fn check_auth_token(info: &HandshakeInfo, auth_token: &str) -> Result<(), Rejection> {
+    let query = info.uri.query().unwrap_or_default();
+    let from_query = url::form_urlencoded::parse(query.as_bytes())
+        .filter(|(key, _)| key == "token")
+        .map(|(_, token)| token);
+    let from_header = info
+        .headers
+        .get_all("authorization")
+        .into_iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.strip_prefix("Bearer "))
+        .map(std::borrow::Cow::from);
+    if from_query
+        .chain(from_header)
+        .any(|token| tokens_match(&token, auth_token))
+    {
+        Ok(())
+    } else {
+        Err(Rejection::unauthorized("missing or invalid auth token"))
+    }
+}
+
+/// Compare tokens in time which doesn't depend on where they differ.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
+/// from the external client.
+/// `num_connections` is kept up to date with the number of connected clients.
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
+    num_connections: Arc<AtomicIsize>,
     mut stop_rx: StopReceiver,
 ) -> InterfaceResult<ManagedTaskHandle> {
     Ok(tokio::task::spawn(async move {
@@ -63,7 +112,6 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
             handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
         );
 
-        let num_connections = Arc::new(AtomicIsize::new(0));
         futures::pin_mut!(listener);
         // establish a new connection to a client
         while let Some(connection) = listener.next().await {
@@ -72,6 +120,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
                     if num_connections.fetch_add(1, Ordering::Relaxed) > MAX_CONNECTIONS {
                         // Max connections so drop this connection
                         // which will close it.
+                        num_connections.fetch_sub(1, Ordering::Relaxed);
                         continue;
                     };
                     tokio::task::spawn(recv_incoming_admin_msgs(
@@ -90,11 +139,13 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
+/// from Cells via a broadcast channel.
+/// `num_connections` is kept up to date with the number of connected clients.
+pub async fn spawn_app_interface_task(
     port: u16,
-    api: A,
+    api: RealAppInterfaceApi,
     signal_broadcaster: broadcast::Sender<Signal>,
+    num_connections: Arc<AtomicIsize>,
     mut stop_rx: StopReceiver,
 ) -> InterfaceResult<(u16, ManagedTaskHandle)> {
     trace!("Initializing App interface");
@@ -112,17 +163,23 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
     tokio::task::spawn(
         handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
     );
+    let signal_encoder = SignalEncoder::new(signal_broadcaster);
     let task = tokio::task::spawn(async move {
         // establish a new connection to a client
         while let Some(connection) = listener.next().await {
             match connection {
                 Ok((tx_to_iface, rx_from_iface)) => {
-                    let rx_from_cell = signal_broadcaster.subscribe();
+                    let rx_from_cell = signal_encoder.subscribe();
+                    num_connections.fetch_add(1, Ordering::Relaxed);
+                    let api = api.for_connection();
+                    let signal_subscriptions = api.signal_subscriptions().clone();
                     spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
+                        api,
+                        signal_subscriptions,
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
+                        num_connections.clone(),
                     );
                 }
                 Err(err) => {
@@ -136,6 +193,98 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
     Ok((port, task))
 }
 
+/// A signal that has been encoded for sending over an app interface.
+#[derive(Clone)]
+struct EncodedSignal {
+    /// The cell that emitted the signal, checked against each
+    /// connection's subscriptions.
+    cell_id: Option<CellId>,
+    bytes: Bytes,
+}
+
+type SharedEncodedSender = Arc<parking_lot::Mutex<Option<broadcast::Sender<EncodedSignal>>>>;
+
+/// Encodes each signal once and shares the bytes with every connection
+/// on an app interface, instead of every connection cloning and
+/// encoding the signal itself.
+///
+/// The encoding task only runs while the interface has connections, so
+/// a signal emitted with no client connected still has no receiver.
+struct SignalEncoder {
+    signal_broadcaster: broadcast::Sender<Signal>,
+    tx_encoded: SharedEncodedSender,
+}
+
+impl SignalEncoder {
+    fn new(signal_broadcaster: broadcast::Sender<Signal>) -> Self {
+        Self {
+            signal_broadcaster,
+            tx_encoded: Arc::new(parking_lot::Mutex::new(None)),
+        }
+    }
+
+    /// Receive encoded signals for a new connection.
+    fn subscribe(&self) -> broadcast::Receiver<EncodedSignal> {
+        let mut tx_encoded = self.tx_encoded.lock();
+        match &*tx_encoded {
+            Some(tx) => tx.subscribe(),
+            None => {
+                let (tx, rx) = broadcast::channel(SIGNAL_BUFFER_SIZE);
+                *tx_encoded = Some(tx);
+                tokio::task::spawn(encode_signals(
+                    self.signal_broadcaster.subscribe(),
+                    self.tx_encoded.clone(),
+                ));
+                rx
+            }
+        }
+    }
+}
+
+/// Encode signals until the last connection has gone.
+async fn encode_signals(
+    mut rx_from_cell: broadcast::Receiver<Signal>,
+    tx_encoded: SharedEncodedSender,
+) {
+    loop {
+        let signal = match rx_from_cell.recv().await {
+            Ok(signal) => signal,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                warn!(missed = n, "App interface fell behind and dropped signals");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                *tx_encoded.lock() = None;
+                return;
+            }
+        };
+        let cell_id = match &signal {
+            Signal::App(cell_id, _) | Signal::AppAckRequired(_, cell_id, _) => {
+                Some(cell_id.clone())
+            }
+            Signal::System(_) => None,
+        };
+        let bytes = match SerializedBytes::try_from(signal) {
+            Ok(bytes) => Bytes::from(Vec::from(UnsafeBytes::from(bytes))),
+            Err(err) => {
+                error!(?err, "error encoding signal");
+                continue;
+            }
+        };
+        let mut tx = tx_encoded.lock();
+        if let Some(tx_to_connections) = &*tx {
+            if tx_to_connections
+                .send(EncodedSignal { cell_id, bytes })
+                .is_err()
+            {
+                // Every connection has closed.
+                *tx = None;
+                return;
+            }
+        }
+    }
+}
+
 /// Polls for messages coming in from the external client.
 /// Used by Admin interface.
 async fn recv_incoming_admin_msgs<A: InterfaceApi>(
@@ -163,46 +312,53 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
 /// App interface.
 fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
     api: A,
+    signal_subscriptions: SignalSubscriptions,
     rx_from_iface: WebsocketReceiver,
-    rx_from_cell: broadcast::Receiver<Signal>,
-    tx_to_iface: WebsocketSender,
+    mut rx_from_cell: broadcast::Receiver<EncodedSignal>,
+    mut tx_to_iface: WebsocketSender,
+    num_connections: Arc<AtomicIsize>,
 ) {
     use futures::stream::StreamExt;
 
     trace!("CONNECTION: {}", rx_from_iface.remote_addr());
 
-    let rx_from_cell = futures::stream::unfold(rx_from_cell, |mut rx_from_cell| async move {
-        if let Ok(item) = rx_from_cell.recv().await {
-            Some((item, rx_from_cell))
-        } else {
-            None
-        }
-    });
-
-    tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
-        let mut tx_to_iface = tx_to_iface.clone();
-        async move {
-            trace!(msg = "Sending signal!", ?signal);
-            if let Err(err) = async move {
-                let bytes = SerializedBytes::try_from(signal)?;
-                tx_to_iface.signal(bytes).await?;
-                InterfaceResult::Ok(())
+    tokio::task::spawn(async move {
+        loop {
+            let signal = match rx_from_cell.recv().await {
+                Ok(signal) => signal,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(missed = n, "App connection fell behind and dropped signals");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            // Drop the signals of cells this connection hasn't subscribed to.
+            if !signal_subscriptions.allows_cell(signal.cell_id.as_ref()) {
+                continue;
             }
-            .await
-            {
-                error!(?err, "error emitting signal");
+            trace!(msg = "Sending signal!", len = signal.bytes.len());
+            // The bytes are shared with the other connections, not copied.
+            match tx_to_iface.signal_bytes(signal.bytes).await {
+                Ok(()) => (),
+                Err(WebsocketError::Shutdown) => break,
+                Err(err) => error!(?err, "error emitting signal"),
             }
         }
-    }));
+    });
 
-    tokio::task::spawn(rx_from_iface.for_each_concurrent(4096, move |msg| {
-        let api = api.clone();
-        async move {
-            if let Err(err) = handle_incoming_message(msg, api).await {
-                error!(?err, "error handling websocket message");
-            }
-        }
-    }));
+    tokio::task::spawn(async move {
+        rx_from_iface
+            .for_each_concurrent(4096, move |msg| {
+                let api = api.clone();
+                async move {
+                    if let Err(err) = handle_incoming_message(msg, api).await {
+                        error!(?err, "error handling websocket message");
+                    }
+                }
+            })
+            .await;
+        num_connections.fetch_sub(1, Ordering::SeqCst);
+    });
 }
 
 /// Handles messages on all interfaces
@@ -238,6 +394,7 @@ pub mod test {
     use holochain_p2p::{AgentPubKeyExt, DnaHashExt};
     use holochain_serialized_bytes::prelude::*;
     use holochain_sqlite::prelude::*;
+    use holochain_state::integrated_ops::IntegratedDhtOpsQuery;
     use holochain_state::prelude::test_db_dir;
     use holochain_types::prelude::*;
     use holochain_types::test_utils::fake_agent_pubkey_1;
@@ -319,6 +476,31 @@ pub mod test {
         conductor_handle
     }
 
+    #[test]
+    fn auth_token_is_checked() {
+        let handshake = |uri: &str, authorization: Option<&str>| {
+            let mut info = HandshakeInfo {
+                remote_addr: url2!("ws://127.0.0.1:1234"),
+                uri: uri.parse().unwrap(),
+                headers: Default::default(),
+            };
+            if let Some(authorization) = authorization {
+                info.headers
+                    .insert("authorization", authorization.parse().unwrap());
+            }
+            info
+        };
+        assert!(check_auth_token(&handshake("/?token=s3cret", None), "s3cret").is_ok());
+        assert!(check_auth_token(&handshake("/?a=b&token=s3cret", None), "s3cret").is_ok());
+        assert!(check_auth_token(&handshake("/", Some("Bearer s3cret")), "s3cret").is_ok());
+        assert!(check_auth_token(&handshake("/", None), "s3cret").is_err());
+        assert!(check_auth_token(&handshake("/?token=s3cre", None), "s3cret").is_err());
+        assert!(check_auth_token(&handshake("/", Some("Bearer wrong!")), "s3cret").is_err());
+        // - Query values are percent-decoded
+        assert!(check_auth_token(&handshake("/?token=a%2Fb%3D", None), "a/b=").is_ok());
+        assert!(check_auth_token(&handshake("/?token=a%2Fb%3D", None), "a%2Fb%3D").is_err());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn serialization_failure() {
         let (_tmpdir, conductor_handle) = setup_admin().await;
@@ -414,6 +596,69 @@ pub mod test {
         shutdown.await.unwrap().unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn websocket_call_zome_batch() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![dna], vec![(installed_cell, None)]).await;
+        let mut call: ZomeCall =
+            crate::fixt::ZomeCallInvocationFixturator::new(crate::fixt::NamedInvocation(
+                cell_id.clone(),
+                TestWasm::Foo.into(),
+                "foo".into(),
+                ExternIO::encode(()).unwrap(),
+            ))
+            .next()
+            .unwrap()
+            .into();
+        call.cell_id = cell_id;
+        let mut missing_zome = call.clone();
+        missing_zome.zome_name = "missing".into();
+
+        for concurrent in [false, true] {
+            let msg = AppRequest::ZomeCallBatch {
+                calls: vec![
+                    BatchZomeCall::Unsigned(call.clone()),
+                    BatchZomeCall::Unsigned(missing_zome.clone()),
+                    BatchZomeCall::Unsigned(call.clone()),
+                ],
+                concurrent,
+            };
+            let msg = msg.try_into().unwrap();
+            let respond = |bytes: SerializedBytes| {
+                let response: AppResponse = bytes.try_into().unwrap();
+                // the failing call doesn't stop the one after it
+                assert_matches!(
+                    response,
+                    AppResponse::ZomeCallBatch(responses) if matches!(
+                        responses.as_slice(),
+                        [
+                            AppResponse::ZomeCall(_),
+                            AppResponse::Error(_),
+                            AppResponse::ZomeCall(_),
+                        ]
+                    )
+                );
+                async { Ok(()) }.boxed().into()
+            };
+            let respond = Respond::Request(Box::new(respond));
+            let msg = (msg, respond);
+            handle_incoming_message(msg, app_api.clone()).await.unwrap();
+        }
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn enable_disable_app() {
         observability::test_run().ok();
@@ -597,6 +842,207 @@ pub mod test {
         shutdown.await.unwrap().unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dump_conductor_state() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id.clone(), None)]).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::DumpConductorState;
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            let dump = unwrap_to::unwrap_to!(response => AdminResponse::ConductorStateDumped);
+            assert_eq!(dump.cells.len(), 1);
+            assert_eq!(dump.cells[0].cell_id, cell_id);
+            assert_eq!(dump.dnas.len(), 1);
+            assert_eq!(&dump.dnas[0].dna_hash, cell_id.dna_hash());
+            assert_eq!(dump.dnas[0].roles.len(), 1);
+            assert_eq!(dump.dnas[0].roles[0].0, "test app");
+            assert!(dump.dnas[0].db_bytes > 0);
+            assert_eq!(
+                dump.cells[0].app_role.as_ref(),
+                Some(&dump.dnas[0].roles[0])
+            );
+            assert!(!dump.tasks.is_empty());
+            assert!(dump.disk_usage.total_bytes > 0);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message(msg, admin_api).await.unwrap();
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wasm_cache_stats_and_purge() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id.clone(), None)]).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::DumpWasmCacheStats;
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            let stats = unwrap_to::unwrap_to!(response => AdminResponse::WasmCacheStatsDumped);
+            // The foo wasm was loaded to install the dna.
+            assert!(stats.memory_hits + stats.disk_hits + stats.compilations > 0);
+            assert_eq!(stats.modules_on_disk, None);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        handle_incoming_message((msg, respond), admin_api.clone())
+            .await
+            .unwrap();
+
+        let msg = AdminRequest::PurgeWasmCache;
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::WasmCachePurged);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        handle_incoming_message((msg, respond), admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn compact_databases() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id.clone(), None)]).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::CompactDatabases;
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            let compactions = unwrap_to::unwrap_to!(response => AdminResponse::DatabasesCompacted);
+            // The conductor and wasm databases and the five databases of the cell's space.
+            assert_eq!(compactions.len(), 7);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        handle_incoming_message((msg, respond), admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn count_validation_receipts() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id.clone(), None)]).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::CountValidationReceipts {
+            cell_id: Box::new(cell_id),
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            let counts =
+                unwrap_to::unwrap_to!(response => AdminResponse::ValidationReceiptsCounted);
+            // - The genesis ops are authored but nobody has validated them.
+            assert!(!counts.is_empty());
+            assert!(counts.iter().all(|c| c.receipts == 0));
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message(msg, admin_api).await.unwrap();
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn query_integrated_dht_ops() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id, None)]).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::QueryIntegratedDhtOps {
+            dna_hash,
+            query: IntegratedDhtOpsQuery {
+                op_type: Some(DhtOpType::RegisterAgentActivity),
+                limit: Some(1),
+                ..Default::default()
+            },
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            let page = unwrap_to::unwrap_to!(response => AdminResponse::IntegratedDhtOpsQueried);
+            assert!(page.ops.len() <= 1);
+            assert!(page
+                .ops
+                .iter()
+                .all(|op| op.op.get_type() == DhtOpType::RegisterAgentActivity));
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message(msg, admin_api).await.unwrap();
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
     async fn make_dna(uid: &str, zomes: Vec<TestWasm>) -> DnaFile {
         DnaFile::new(
             DnaDef {
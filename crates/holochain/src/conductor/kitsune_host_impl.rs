@@ -14,7 +14,10 @@ use holochain_p2p::{
 };
 use holochain_types::{db::PermittedConn, prelude::DnaError, share::RwShare};
 use kitsune_p2p::{
-    agent_store::AgentInfoSigned, event::GetAgentInfoSignedEvt, KitsuneHost, KitsuneHostResult,
+    agent_store::AgentInfoSigned,
+    block::{Block, BlockTarget},
+    event::GetAgentInfoSignedEvt,
+    KitsuneHost, KitsuneHostResult,
 };
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 
@@ -85,6 +88,26 @@ impl KitsuneHost for KitsuneHostImpl {
         .into()
     }
 
+    fn query_metrics(
+        &self,
+        space: std::sync::Arc<kitsune_p2p::KitsuneSpace>,
+        query: kitsune_p2p::event::MetricQuery,
+    ) -> KitsuneHostResult<kitsune_p2p::event::MetricQueryAnswer> {
+        async move {
+            let db = self.spaces.p2p_metrics_db(&DnaHash::from_kitsune(&space))?;
+            use holochain_sqlite::db::AsP2pMetricStoreConExt;
+            let permit = db.conn_permit().await;
+            let task = tokio::task::spawn_blocking(move || {
+                let mut conn = db.with_permit(permit)?;
+                conn.p2p_query_metrics(query)
+            })
+            .await;
+            Ok(task??)
+        }
+        .boxed()
+        .into()
+    }
+
     fn get_agent_info_signed(
         &self,
         GetAgentInfoSignedEvt { space, agent }: GetAgentInfoSignedEvt,
@@ -143,4 +166,56 @@ impl KitsuneHost for KitsuneHostImpl {
             .boxed()
             .into()
     }
+
+    fn get_blocks(&self, space: Arc<kitsune_p2p::KitsuneSpace>) -> KitsuneHostResult<Vec<Block>> {
+        async move {
+            let db = self.spaces.p2p_agents_db(&DnaHash::from_kitsune(&space))?;
+            use holochain_sqlite::db::AsP2pAgentStoreConExt;
+            let permit = db.conn_permit().await;
+            let task = tokio::task::spawn_blocking(move || {
+                let mut conn = db.with_permit(permit)?;
+                conn.p2p_list_blocks()
+            })
+            .await;
+            Ok(task??)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn put_block(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        block: Block,
+    ) -> KitsuneHostResult<()> {
+        async move {
+            let db = self.spaces.p2p_agents_db(&DnaHash::from_kitsune(&space))?;
+            Ok(holochain_sqlite::db::p2p_put_block(&db, block).await?)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn delete_block(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        target: BlockTarget,
+    ) -> KitsuneHostResult<()> {
+        async move {
+            let db = self.spaces.p2p_agents_db(&DnaHash::from_kitsune(&space))?;
+            Ok(holochain_sqlite::db::p2p_delete_block(&db, &target).await?)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn network_rejoined(&self, space: Arc<kitsune_p2p::KitsuneSpace>) -> KitsuneHostResult<()> {
+        // Publishing is held back while offline, so publish the
+        // ops which were authored in the meantime straight away.
+        let dna_hash = DnaHash::from_kitsune(&space);
+        for trigger in self.spaces.queue_consumer_map.publish_triggers(&dna_hash) {
+            trigger.trigger(&"network_rejoined");
+        }
+        async move { Ok(()) }.boxed().into()
+    }
 }
@@ -9,7 +9,9 @@ mod error;
 pub use error::*;
 
 use futures::stream::FuturesUnordered;
+use holochain_conductor_api::ConductorTaskDump;
 use holochain_types::prelude::*;
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -51,11 +53,26 @@ pub enum TaskKind {
     Generic(OnDeath),
 }
 
+impl TaskKind {
+    /// Describe what happens when a task of this kind fails.
+    fn on_failure(&self) -> String {
+        match self {
+            TaskKind::Ignore => "log".to_string(),
+            TaskKind::Unrecoverable => "shut down the conductor".to_string(),
+            TaskKind::CellCritical(cell_id) => format!("stop apps using cell {}", cell_id),
+            TaskKind::DnaCritical(dna_hash) => format!("stop apps using dna {}", dna_hash),
+            TaskKind::Generic(_) => "custom".to_string(),
+        }
+    }
+}
+
 /// A message sent to the TaskManager, registering an ManagedTask of a given kind.
 pub struct ManagedTaskAdd {
     handle: ManagedTaskHandle,
     kind: TaskKind,
     name: String,
+    /// Lists this task in [`ManagedTasks`] until it is dropped.
+    registration: Option<ManagedTaskRegistration>,
 }
 
 impl ManagedTaskAdd {
@@ -64,6 +81,7 @@ impl ManagedTaskAdd {
             handle,
             kind,
             name: name.to_string(),
+            registration: None,
         }
     }
 
@@ -134,22 +152,80 @@ pub enum TaskOutcome {
     StopAppsWithDna(Arc<DnaHash>, Box<ManagedTaskError>, String),
 }
 
+/// The tasks currently held by the task manager, so they can be listed
+/// for diagnostics.
+#[derive(Clone, Default)]
+pub struct ManagedTasks(Arc<parking_lot::Mutex<ManagedTasksInner>>);
+
+#[derive(Default)]
+struct ManagedTasksInner {
+    next_id: u64,
+    tasks: HashMap<u64, ConductorTaskDump>,
+}
+
+/// Removes a task from [`ManagedTasks`] when dropped.
+struct ManagedTaskRegistration {
+    tasks: ManagedTasks,
+    id: u64,
+}
+
+impl ManagedTasks {
+    fn register(&self, task: &ManagedTaskAdd) -> ManagedTaskRegistration {
+        let mut inner = self.0.lock();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.tasks.insert(
+            id,
+            ConductorTaskDump {
+                name: task.name.clone(),
+                on_failure: task.kind.on_failure(),
+                started_at: Timestamp::now(),
+            },
+        );
+        ManagedTaskRegistration {
+            tasks: self.clone(),
+            id,
+        }
+    }
+
+    /// List the tasks which are still running, oldest first.
+    pub fn list(&self) -> Vec<ConductorTaskDump> {
+        let inner = self.0.lock();
+        let mut tasks: Vec<_> = inner.tasks.iter().collect();
+        tasks.sort_unstable_by_key(|(id, _)| **id);
+        tasks.into_iter().map(|(_, task)| task.clone()).collect()
+    }
+}
+
+impl Drop for ManagedTaskRegistration {
+    fn drop(&mut self) {
+        self.tasks.0.lock().tasks.remove(&self.id);
+    }
+}
+
 struct TaskManager {
     stream: FuturesUnordered<ManagedTaskAdd>,
+    tasks: ManagedTasks,
 }
 
 impl TaskManager {
-    fn new() -> Self {
+    fn new(tasks: ManagedTasks) -> Self {
         let stream = FuturesUnordered::new();
-        TaskManager { stream }
+        TaskManager { stream, tasks }
+    }
+
+    fn push(&mut self, mut task: ManagedTaskAdd) {
+        task.registration = Some(self.tasks.register(&task));
+        self.stream.push(task);
     }
 }
 
 pub(crate) fn spawn_task_manager(
     handle: ConductorHandle,
+    tasks: ManagedTasks,
 ) -> (mpsc::Sender<ManagedTaskAdd>, TaskManagerRunHandle) {
     let (send, recv) = mpsc::channel(CHANNEL_SIZE);
-    (send, tokio::spawn(run(handle, recv)))
+    (send, tokio::spawn(run(handle, recv, tasks)))
 }
 
 /// A super pessimistic task that is just waiting to die
@@ -163,11 +239,12 @@ pub(crate) async fn keep_alive_task(mut die: broadcast::Receiver<()>) -> Managed
 async fn run(
     conductor: ConductorHandle,
     mut new_task_channel: mpsc::Receiver<ManagedTaskAdd>,
+    tasks: ManagedTasks,
 ) -> TaskManagerResult {
-    let mut task_manager = TaskManager::new();
+    let mut task_manager = TaskManager::new(tasks);
     // Need to have at least one item in the stream or it will exit early
     if let Some(new_task) = new_task_channel.recv().await {
-        task_manager.stream.push(new_task);
+        task_manager.push(new_task);
     } else {
         error!("All senders to task manager were dropped before starting");
         return Err(TaskManagerError::TaskManagerFailedToStart);
@@ -175,13 +252,13 @@ async fn run(
     loop {
         tokio::select! {
             Some(new_task) = new_task_channel.recv() => {
-                task_manager.stream.push(new_task);
+                task_manager.push(new_task);
                 tracing::debug!("Task added. Total tasks: {}", task_manager.stream.len());
             }
             result = task_manager.stream.next() => {
                 tracing::debug!("Task completed. Total tasks: {}", task_manager.stream.len());
                 match result {
-                Some(TaskOutcome::NewTask(new_task)) => task_manager.stream.push(new_task),
+                Some(TaskOutcome::NewTask(new_task)) => task_manager.push(new_task),
                 Some(TaskOutcome::LogInfo(context)) => {
                     debug!("Managed task completed: {}", context)
                 }
@@ -393,7 +470,8 @@ mod test {
     async fn spawn_and_handle_dying_task() -> Result<()> {
         observability::test_run().ok();
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), Default::default());
         let handle = tokio::spawn(async {
             Err(Box::new(ConductorError::Other(
                 anyhow::anyhow!("This task gotta die").into(),
@@ -431,7 +509,8 @@ mod test {
         observability::test_run().ok();
         let (_tx, rx) = tokio::sync::broadcast::channel(1);
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), Default::default());
         send_task_handle
             .send(ManagedTaskAdd::ignore(
                 tokio::spawn(keep_alive_task(rx)),
@@ -460,13 +539,43 @@ mod test {
             .expect_err("The main task should return an error");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tasks_are_listed_until_they_finish() {
+        observability::test_run().ok();
+        let (tx, rx) = tokio::sync::broadcast::channel(1);
+        let mock_handle = MockConductorHandleT::new();
+        let tasks = ManagedTasks::default();
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), tasks.clone());
+        let main_task = tokio::spawn(main_task);
+        send_task_handle
+            .send(ManagedTaskAdd::ignore(
+                tokio::spawn(keep_alive_task(rx)),
+                "keep alive",
+            ))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let listed = tasks.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "keep alive");
+
+        // - Once the task finishes it is no longer listed
+        tx.send(()).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(tasks.list().is_empty());
+        main_task.abort();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[ignore = "panics in tokio break other tests, this test is here to confirm behavior but cannot be run on ci"]
     async fn unrecoverable_panic() {
         observability::test_run().ok();
         let (_tx, rx) = tokio::sync::broadcast::channel(1);
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), Default::default());
         send_task_handle
             .send(ManagedTaskAdd::ignore(
                 tokio::spawn(keep_alive_task(rx)),
@@ -45,16 +45,52 @@ pub async fn inject_agent_infos<'iter, I: IntoIterator<Item = &'iter AgentInfoSi
     Ok(p2p_put_all(&env, iter.into_iter()).await?)
 }
 
+/// Why an agent info was refused by the peer store.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum AgentInfoRejected {
+    #[error("Agent info expired at {expires_at_ms}ms")]
+    Expired { expires_at_ms: u64 },
+    #[error("Agent info expires at {expires_at_ms}ms, which is more than {max_expires_after_ms}ms from now")]
+    ExpiresTooLate {
+        expires_at_ms: u64,
+        max_expires_after_ms: u64,
+    },
+}
+
+/// Check that an agent info has not expired, and that it does not
+/// expire more than `max_expires_after_ms` after `now_ms`.
+pub fn check_agent_info_expiry(
+    info: &AgentInfoSigned,
+    now_ms: u64,
+    max_expires_after_ms: u64,
+) -> Result<(), AgentInfoRejected> {
+    let expires_at_ms = info.expires_at_ms;
+    if is_expired(now_ms, info) {
+        Err(AgentInfoRejected::Expired { expires_at_ms })
+    } else if expires_at_ms > now_ms.saturating_add(max_expires_after_ms) {
+        Err(AgentInfoRejected::ExpiresTooLate {
+            expires_at_ms,
+            max_expires_after_ms,
+        })
+    } else {
+        Ok(())
+    }
+}
+
 /// Inject multiple agent info entries into the peer store in batches.
+/// Agent infos that fail [`check_agent_info_expiry`] are dropped.
 pub async fn p2p_put_all_batch(
     env: DbWrite<DbKindP2pAgents>,
     rx: tokio::sync::mpsc::Receiver<P2pBatch>,
+    max_expires_after_ms: u64,
 ) {
     let stream = tokio_stream::wrappers::ReceiverStream::new(rx);
     let mut stream = stream.ready_chunks(100);
     while let Some(batch) = stream.next().await {
         let mut responses = Vec::with_capacity(batch.len());
         let (tx, rx) = tokio::sync::oneshot::channel();
+        let now = now();
         let result = env
             .async_commit(move |txn| {
                 'batch: for P2pBatch {
@@ -63,6 +99,11 @@ pub async fn p2p_put_all_batch(
                 } in batch
                 {
                     for info in batch {
+                        if let Err(err) = check_agent_info_expiry(&info, now, max_expires_after_ms)
+                        {
+                            tracing::debug!(agent = ?info.agent, ?err, "dropping agent info");
+                            continue;
+                        }
                         match p2p_put_single(txn, &info) {
                             Ok(_) => (),
                             Err(e) => {
@@ -174,8 +215,13 @@ pub async fn get_agent_info_signed(
     _kitsune_space: Arc<kitsune_p2p::KitsuneSpace>,
     kitsune_agent: Arc<kitsune_p2p::KitsuneAgent>,
 ) -> ConductorResult<Option<AgentInfoSigned>> {
+    // Expired agent info may not have been pruned yet,
+    // but we shouldn't hand it out to other peers.
+    let now = now();
     run_query(environ, move |mut conn| {
-        Ok(conn.p2p_get_agent(&kitsune_agent)?)
+        Ok(conn
+            .p2p_get_agent(&kitsune_agent)?
+            .filter(|info| !is_expired(now, info)))
     })
     .await
 }
@@ -371,4 +417,67 @@ mod tests {
 
         assert_eq!(expect, agents);
     }
+
+    async fn agent_info_expiring_at(expires_at_ms: u64) -> AgentInfoSigned {
+        use kitsune_p2p::fixt::*;
+        AgentInfoSigned::sign(
+            Arc::new(fixt!(KitsuneSpace)),
+            Arc::new(fixt!(KitsuneAgent)),
+            u32::MAX / 4,
+            fixt!(UrlList, Empty),
+            0,
+            expires_at_ms,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature))) },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn agent_info_expiry_is_checked() {
+        let max = 1000 * 60 * 60;
+        let now = now();
+        let expired = agent_info_expiring_at(now - 1).await;
+        let too_late = agent_info_expiring_at(now + max + 1000).await;
+        let fresh = agent_info_expiring_at(now + 1000 * 60).await;
+
+        assert_eq!(
+            check_agent_info_expiry(&expired, now, max),
+            Err(AgentInfoRejected::Expired {
+                expires_at_ms: now - 1
+            })
+        );
+        assert!(matches!(
+            check_agent_info_expiry(&too_late, now, max),
+            Err(AgentInfoRejected::ExpiresTooLate { .. })
+        ));
+        assert_eq!(check_agent_info_expiry(&fresh, now, max), Ok(()));
+
+        // - Only the fresh agent info makes it into the store
+        let t_db = test_p2p_agents_db();
+        let db = t_db.to_db();
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(p2p_put_all_batch(db.clone(), rx, max));
+        let (result_sender, response) = tokio::sync::oneshot::channel();
+        let sent = tx
+            .send(P2pBatch {
+                peer_data: vec![expired, too_late, fresh.clone()],
+                result_sender,
+            })
+            .await;
+        assert!(sent.is_ok());
+        response.await.unwrap().unwrap();
+        assert_eq!(
+            all_agent_infos(db.clone().into()).await.unwrap(),
+            vec![fresh]
+        );
+
+        // - Expired agent info that hasn't been pruned yet is not handed out
+        let expired = agent_info_expiring_at(now - 1).await;
+        p2p_put(&db, &expired).await.unwrap();
+        let got = get_agent_info_signed(db.into(), expired.space.clone(), expired.agent.clone())
+            .await
+            .unwrap();
+        assert_eq!(got, None);
+    }
 }
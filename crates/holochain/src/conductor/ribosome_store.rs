@@ -3,7 +3,11 @@ use holochain_zome_types::entry_def::EntryDef;
 use std::collections::HashMap;
 use tracing::*;
 
-use crate::core::ribosome::{error::RibosomeResult, real_ribosome::RealRibosome, RibosomeT};
+use crate::core::ribosome::{
+    error::RibosomeResult,
+    real_ribosome::{RealRibosome, WasmCallLimits},
+    RibosomeT,
+};
 
 #[derive(Default, Debug)]
 pub struct RibosomeStore {
@@ -20,9 +24,9 @@ impl RibosomeStore {
     }
 
     #[instrument]
-    pub fn add_dna(&mut self, dna: DnaFile) -> RibosomeResult<()> {
+    pub fn add_dna(&mut self, dna: DnaFile, limits: WasmCallLimits) -> RibosomeResult<()> {
         let hash = dna.dna_hash().clone();
-        let ribosome = RealRibosome::new(dna)?;
+        let ribosome = RealRibosome::new(dna)?.with_wasm_call_limits(limits);
         self.ribosomes.insert(hash, ribosome);
         Ok(())
     }
@@ -3,8 +3,9 @@
 //! Multiple [`Cell`](crate::conductor::Cell)'s could share the same space.
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use holo_hash::{DhtOpHash, DnaHash};
+use holo_hash::{ActionHash, DhtOpHash, DnaHash};
 use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath};
+use holochain_conductor_api::DatabaseCompaction;
 use holochain_p2p::{
     dht::{
         arq::{power_and_count_from_length, ArqBoundsSet},
@@ -19,22 +20,29 @@ use holochain_p2p::{
     event::FetchOpDataQuery,
 };
 use holochain_sqlite::{
-    conn::{DbSyncLevel, DbSyncStrategy},
+    conn::{DbKey, DbSyncLevel, DbSyncStrategy},
     db::{
-        DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents, DbKindP2pMetrics,
-        DbKindWasm, DbWrite, ReadAccess,
+        DbKind, DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents,
+        DbKindP2pMetrics, DbKindT, DbKindWasm, DbWrite, ReadAccess,
     },
     prelude::{DatabaseError, DatabaseResult},
 };
 use holochain_state::{
-    prelude::{from_blob, StateQueryResult},
+    mutations::insert_pending_warrant,
+    prelude::{from_blob, StateMutationResult, StateQueryResult},
     query::{map_sql_dht_op_common, StateQueryError},
+    warrants::{
+        count_warrants_by_warrantor, get_warrants_for_op, resolve_pending_warrants, verify_warrant,
+        MAX_WARRANTS_PER_WARRANTOR,
+    },
 };
 use holochain_types::{
     db_cache::DhtDbQueryCache,
-    dht_op::{DhtOp, DhtOpType},
+    dht_op::{DhtOp, DhtOpType, WarrantedOp},
+};
+use holochain_zome_types::{
+    warrant::SignedWarrant, Entry, EntryVisibility, SignedAction, Timestamp,
 };
-use holochain_zome_types::{Entry, EntryVisibility, SignedAction, Timestamp};
 use kitsune_p2p::{
     event::{TimeWindow, TimeWindowInclusive},
     KitsuneP2pConfig,
@@ -75,6 +83,8 @@ pub struct Spaces {
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
     pub(crate) wasm_db: DbWrite<DbKindWasm>,
     network_config: KitsuneP2pConfig,
+    /// The key the databases of each space are encrypted with, if any.
+    db_key: Option<DbKey>,
 }
 
 #[derive(Clone)]
@@ -118,6 +128,9 @@ pub struct Space {
 
     /// Incoming ops batch for this space.
     pub incoming_ops_batch: IncomingOpsBatch,
+
+    /// Stops the queue consumers which are shared by the cells of this space.
+    pub stop: tokio::sync::broadcast::Sender<()>,
 }
 
 #[cfg(test)]
@@ -153,9 +166,16 @@ impl Spaces {
             conductor_db,
             wasm_db,
             network_config: config.network.clone().unwrap_or_default(),
+            db_key: None,
         })
     }
 
+    /// Encrypt the databases of every space created from now on with this key.
+    pub fn with_db_key(mut self, db_key: Option<DbKey>) -> Self {
+        self.db_key = db_key;
+        self
+    }
+
     /// Get something from every space
     pub fn get_from_spaces<R, F: FnMut(&Space) -> R>(&self, f: F) -> Vec<R> {
         self.map
@@ -167,6 +187,30 @@ impl Spaces {
         self.get_or_create_space_ref(dna_hash, Space::clone)
     }
 
+    /// Open a database of a space, whether or not the space is loaded,
+    /// without loading the space or creating the database.
+    /// Returns None if the database doesn't exist.
+    pub fn open_existing_db<Kind: DbKindT>(
+        &self,
+        kind: Kind,
+    ) -> ConductorResult<Option<DbWrite<Kind>>> {
+        let root_db_dir: &std::path::Path = self.db_dir.as_ref().as_ref();
+        if !root_db_dir.join(kind.filename()).exists() {
+            return Ok(None);
+        }
+        // Open it the same way the space does, because the handle is shared.
+        let db_sync_level = match (kind.kind(), self.db_sync_strategy) {
+            (DbKind::Authored(_), _) | (_, DbSyncStrategy::Resilient) => DbSyncLevel::Normal,
+            (_, DbSyncStrategy::Fast) => DbSyncLevel::Off,
+        };
+        Ok(Some(DbWrite::open_with_key(
+            root_db_dir,
+            kind,
+            db_sync_level,
+            self.db_key.clone(),
+        )?))
+    }
+
     fn get_or_create_space_ref<F, R>(&self, dna_hash: &DnaHash, f: F) -> ConductorResult<R>
     where
         F: Fn(&Space) -> R,
@@ -182,6 +226,10 @@ impl Spaces {
                             Arc::new(dna_hash.clone()),
                             &self.db_dir,
                             self.db_sync_strategy,
+                            self.network_config
+                                .tuning_params
+                                .agent_info_max_expires_after_ms as u64,
+                            self.db_key.clone(),
                         )?;
 
                         let r = f(&space);
@@ -192,6 +240,50 @@ impl Spaces {
         }
     }
 
+    /// Stop the queue consumers of this space and delete its databases.
+    ///
+    /// The cells of the space should already have been removed.
+    /// Each database is deleted once the stopped consumers have
+    /// dropped their handles to it.
+    /// The space is created again with empty databases if it's used afterwards.
+    pub async fn remove_space(&self, dna_hash: &DnaHash) -> ConductorResult<()> {
+        let space = match self.map.share_mut(|spaces| spaces.remove(dna_hash)) {
+            Some(space) => space,
+            None => return Ok(()),
+        };
+        self.queue_consumer_map.remove(dna_hash);
+        space.stop.send(()).ok();
+        let cache_db = space.cache_db.clone();
+        let authored_db = space.authored_db.clone();
+        let dht_db = space.dht_db.clone();
+        let p2p_agents_db = space.p2p_agents_db.clone();
+        let p2p_metrics_db = space.p2p_metrics_db.clone();
+        // Release the handles held by the space itself.
+        drop(space);
+        cache_db.remove().await?;
+        authored_db.remove().await?;
+        dht_db.remove().await?;
+        p2p_agents_db.remove().await?;
+        p2p_metrics_db.remove().await?;
+        Ok(())
+    }
+
+    /// Vacuum the conductor and wasm databases and the databases of every space.
+    pub async fn compact_databases(&self) -> ConductorResult<Vec<DatabaseCompaction>> {
+        let mut compactions = vec![
+            compact_database(&self.conductor_db).await?,
+            compact_database(&self.wasm_db).await?,
+        ];
+        for space in self.get_from_spaces(Space::clone) {
+            compactions.push(compact_database(&space.authored_db).await?);
+            compactions.push(compact_database(&space.dht_db).await?);
+            compactions.push(compact_database(&space.cache_db).await?);
+            compactions.push(compact_database(&space.p2p_agents_db).await?);
+            compactions.push(compact_database(&space.p2p_metrics_db).await?);
+        }
+        Ok(compactions)
+    }
+
     /// Get the cache database (this will create the space if it doesn't already exist).
     pub fn cache(&self, dna_hash: &DnaHash) -> ConductorResult<DbWrite<DbKindCache>> {
         self.get_or_create_space_ref(dna_hash, |space| space.cache_db.clone())
@@ -384,17 +476,36 @@ impl Spaces {
         &self,
         dna_hash: &DnaHash,
         query: FetchOpDataQuery,
-    ) -> ConductorResult<Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>> {
-        match query {
+    ) -> ConductorResult<
+        Vec<(
+            holo_hash::DhtOpHash,
+            holochain_types::dht_op::DhtOp,
+            Vec<SignedWarrant>,
+        )>,
+    > {
+        let ops = match query {
             FetchOpDataQuery::Hashes(op_hashes) => {
                 self.handle_fetch_op_data_by_hashes(dna_hash, op_hashes)
-                    .await
+                    .await?
             }
             FetchOpDataQuery::Regions(regions) => {
                 self.handle_fetch_op_data_by_regions(dna_hash, regions)
-                    .await
+                    .await?
             }
-        }
+        };
+        // Warrants are gossiped along with the ops they are about.
+        Ok(self
+            .dht_db(dna_hash)?
+            .async_reader(move |txn| {
+                ops.into_iter()
+                    .map(|(hash, op)| {
+                        let action_hash = ActionHash::with_data_sync(&op.action());
+                        let warrants = get_warrants_for_op(&txn, &action_hash, &op.dht_basis())?;
+                        StateQueryResult::Ok((hash, op, warrants))
+                    })
+                    .collect::<StateQueryResult<Vec<_>>>()
+            })
+            .await?)
     }
 
     #[instrument(skip(self, regions))]
@@ -567,6 +678,60 @@ impl Spaces {
         Ok(())
     }
 
+    #[instrument(skip(self, warrants))]
+    /// Hold warrants against data this node is an authority for.
+    ///
+    /// A warrant must be signed by its warrantor and come with the op it is
+    /// about. It is held as pending until this node has validated the op
+    /// itself, and is only kept if the op is rejected.
+    pub async fn handle_publish_warrants(
+        &self,
+        dna_hash: &DnaHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> ConductorResult<()> {
+        let mut verified = Vec::with_capacity(warrants.len());
+        for warranted in warrants {
+            if !warranted.op_matches_warrant() {
+                tracing::warn!(warrant = ?warranted.warrant, "Ignoring a warrant which is not about its op");
+            } else if !verify_warrant(&warranted.warrant).await {
+                tracing::warn!(warrant = ?warranted.warrant, "Ignoring a warrant with an invalid signature");
+            } else {
+                verified.push(warranted);
+            }
+        }
+        if verified.is_empty() {
+            return Ok(());
+        }
+        let space = self.get_or_create_space(dna_hash)?;
+        let ops = space
+            .dht_db
+            .async_commit(move |txn| {
+                let mut ops = Vec::new();
+                for WarrantedOp { warrant, op } in verified {
+                    if count_warrants_by_warrantor(txn, &warrant.warrant.warrantor)?
+                        >= MAX_WARRANTS_PER_WARRANTOR
+                    {
+                        tracing::warn!(warrantor = ?warrant.warrant.warrantor, "Ignoring a warrant from a warrantor with too many warrants held");
+                        continue;
+                    }
+                    insert_pending_warrant(txn, &warrant)?;
+                    if !ops.contains(&op) {
+                        ops.push(op);
+                    }
+                }
+                // Settle the warrants for ops which were already validated.
+                resolve_pending_warrants(txn)?;
+                StateMutationResult::Ok(ops)
+            })
+            .await?;
+        if !ops.is_empty() {
+            // The ops are validated as if they had been published to us.
+            // Any already held are ignored by the incoming ops workflow.
+            self.handle_publish(dna_hash, false, false, ops).await?;
+        }
+        Ok(())
+    }
+
     /// Get the recent_threshold based on the kitsune network config
     pub fn recent_threshold(&self) -> Duration {
         self.network_config
@@ -575,11 +740,22 @@ impl Spaces {
     }
 }
 
+async fn compact_database<Kind: DbKindT + Send + Sync + 'static>(
+    db: &DbWrite<Kind>,
+) -> ConductorResult<DatabaseCompaction> {
+    Ok(DatabaseCompaction {
+        database: db.kind().filename(),
+        reclaimed_bytes: db.vacuum().await?,
+    })
+}
+
 impl Space {
     fn new(
         dna_hash: Arc<DnaHash>,
         root_db_dir: &DatabaseRootPath,
         db_sync_strategy: DbSyncStrategy,
+        max_agent_info_expires_after_ms: u64,
+        db_key: Option<DbKey>,
     ) -> ConductorResult<Self> {
         use holochain_p2p::DnaHashExt;
         let space = dna_hash.to_kitsune();
@@ -587,36 +763,42 @@ impl Space {
             DbSyncStrategy::Fast => DbSyncLevel::Off,
             DbSyncStrategy::Resilient => DbSyncLevel::Normal,
         };
-        let cache = DbWrite::open_with_sync_level(
+        let cache = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindCache(dna_hash.clone()),
             db_sync_level,
+            db_key.clone(),
         )?;
-        let authored_db = DbWrite::open_with_sync_level(
+        let authored_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindAuthored(dna_hash.clone()),
             DbSyncLevel::Normal,
+            db_key.clone(),
         )?;
-        let dht_db = DbWrite::open_with_sync_level(
+        let dht_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindDht(dna_hash.clone()),
             db_sync_level,
+            db_key.clone(),
         )?;
-        let p2p_agents_db = DbWrite::open_with_sync_level(
+        let p2p_agents_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindP2pAgents(space.clone()),
             db_sync_level,
+            db_key.clone(),
         )?;
-        let p2p_metrics_db = DbWrite::open_with_sync_level(
+        let p2p_metrics_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindP2pMetrics(space),
             db_sync_level,
+            db_key.clone(),
         )?;
 
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         tokio::spawn(p2p_agent_store::p2p_put_all_batch(
             p2p_agents_db.clone(),
             rx,
+            max_agent_info_expires_after_ms,
         ));
         let p2p_batch_sender = tx;
 
@@ -624,6 +806,7 @@ impl Space {
         let incoming_op_hashes = IncomingOpHashes::default();
         let incoming_ops_batch = IncomingOpsBatch::default();
         let dht_query_cache = DhtDbQueryCache::new(dht_db.clone().into());
+        let (stop, _) = tokio::sync::broadcast::channel(1);
         let r = Self {
             dna_hash,
             cache_db: cache,
@@ -636,6 +819,7 @@ impl Space {
             incoming_op_hashes,
             incoming_ops_batch,
             dht_query_cache,
+            stop,
         };
         Ok(r)
     }
@@ -693,6 +877,10 @@ impl TestSpace {
                 Arc::new(dna_hash),
                 &temp_dir.path().to_path_buf().into(),
                 Default::default(),
+                KitsuneP2pConfig::default()
+                    .tuning_params
+                    .agent_info_max_expires_after_ms as u64,
+                None,
             )
             .unwrap(),
             _temp_dir: temp_dir,
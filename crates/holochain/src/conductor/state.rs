@@ -7,6 +7,7 @@ use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 use super::error::{ConductorError, ConductorResult};
 
@@ -24,6 +25,10 @@ pub struct ConductorState {
     /// List of interfaces any UI can use to access zome functions.
     #[serde(default)]
     pub(crate) app_interfaces: HashMap<AppInterfaceId, AppInterfaceConfig>,
+    /// Cells which have been paused individually. These are not run,
+    /// even if the apps they belong to are running.
+    #[serde(default)]
+    paused_cells: HashSet<CellId>,
 }
 
 /// A unique identifier used to refer to an App Interface internally.
@@ -122,11 +127,51 @@ impl ConductorState {
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
     }
 
+    /// Mutable getter for a single app. Returns error if app missing.
+    pub fn get_app_mut(&mut self, id: &InstalledAppId) -> ConductorResult<&mut InstalledApp> {
+        self.installed_apps
+            .get_mut(id)
+            .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
+    }
+
     /// Getter for a single app. Returns error if app missing.
     pub fn remove_app(&mut self, id: &InstalledAppId) -> ConductorResult<InstalledApp> {
-        self.installed_apps
+        let app = self
+            .installed_apps
             .remove(id)
-            .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
+            .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))?;
+        // Forget paused cells which no longer belong to any app.
+        let installed_apps = &self.installed_apps;
+        self.paused_cells.retain(|cell_id| {
+            installed_apps
+                .values()
+                .any(|app| app.all_cells().any(|id| id == cell_id))
+        });
+        Ok(app)
+    }
+
+    /// The cells which have been paused individually
+    pub fn paused_cells(&self) -> &HashSet<CellId> {
+        &self.paused_cells
+    }
+
+    /// Pause a single cell of an installed app.
+    /// Returns false if the cell was already paused.
+    pub fn pause_cell(&mut self, cell_id: CellId) -> ConductorResult<bool> {
+        if !self
+            .installed_apps
+            .values()
+            .any(|app| app.all_cells().any(|id| *id == cell_id))
+        {
+            return Err(ConductorError::CellMissing(cell_id));
+        }
+        Ok(self.paused_cells.insert(cell_id))
+    }
+
+    /// Resume a cell which was paused with [`ConductorState::pause_cell`].
+    /// Returns false if the cell was not paused.
+    pub fn resume_cell(&mut self, cell_id: &CellId) -> bool {
+        self.paused_cells.remove(cell_id)
     }
 
     /// Add an app in the Deactivated state. Returns an error if an app is already
@@ -194,7 +239,10 @@ impl AppInterfaceConfig {
     pub fn websocket(port: u16) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::Websocket {
+                port,
+                auth_token: None,
+            },
         }
     }
 }
@@ -72,6 +72,10 @@ use super::workflow::sys_validation_workflow::SysValidationWorkspace;
 ///
 /// Waits for the initial loop to complete before returning, to prevent causing
 /// a race condition by trying to run a workflow too soon after cell creation.
+///
+/// The consumers which are shared by all cells of a dna space stop with `stop`
+/// or when the space is removed, while those which belong only to this cell
+/// stop with `cell_stop`.
 #[allow(clippy::too_many_arguments)]
 pub async fn spawn_queue_consumer_tasks(
     cell_id: CellId,
@@ -80,6 +84,7 @@ pub async fn spawn_queue_consumer_tasks(
     conductor_handle: ConductorHandle,
     task_sender: sync::mpsc::Sender<ManagedTaskAdd>,
     stop: sync::broadcast::Sender<()>,
+    cell_stop: sync::broadcast::Sender<()>,
 ) -> (QueueTriggers, InitialQueueTriggers) {
     let Space {
         authored_db,
@@ -92,15 +97,17 @@ pub async fn spawn_queue_consumer_tasks(
     let keystore = conductor_handle.keystore().clone();
     let dna_hash = Arc::new(cell_id.dna_hash().clone());
     let queue_consumer_map = conductor_handle.get_queue_consumer_workflows();
+    let space_stop = || stop_either(&stop, &space.stop);
 
     // Publish
     let (tx_publish, handle) = spawn_publish_dht_ops_consumer(
         cell_id.agent_pubkey().clone(),
         authored_db.clone(),
         conductor_handle.clone(),
-        stop.subscribe(),
+        cell_stop.subscribe(),
         Box::new(network.clone()),
     );
+    queue_consumer_map.insert_publish_trigger(&cell_id, tx_publish.clone());
     task_sender
         .send(ManagedTaskAdd::cell_critical(
             handle,
@@ -118,7 +125,7 @@ pub async fn spawn_queue_consumer_tasks(
                 dna_hash.clone(),
                 dht_db.clone(),
                 conductor_handle.clone(),
-                stop.subscribe(),
+                space_stop(),
                 network.clone(),
             )
         });
@@ -142,7 +149,7 @@ pub async fn spawn_queue_consumer_tasks(
                 dna_hash.clone(),
                 dht_db.clone(),
                 dht_query_cache.clone(),
-                stop.subscribe(),
+                space_stop(),
                 tx_receipt.clone(),
                 network.clone(),
             )
@@ -177,7 +184,7 @@ pub async fn spawn_queue_consumer_tasks(
                 Arc::new(dna_def),
             ),
             conductor_handle.clone(),
-            stop.subscribe(),
+            space_stop(),
             tx_integration.clone(),
             network.clone(),
             dht_query_cache.clone(),
@@ -211,7 +218,7 @@ pub async fn spawn_queue_consumer_tasks(
             ),
             space.clone(),
             conductor_handle.clone(),
-            stop.subscribe(),
+            space_stop(),
             tx_app.clone(),
             network.clone(),
         )
@@ -229,12 +236,7 @@ pub async fn spawn_queue_consumer_tasks(
     }
 
     let (tx_cs, handle) = queue_consumer_map.spawn_once_countersigning(dna_hash.clone(), || {
-        spawn_countersigning_consumer(
-            space.clone(),
-            stop.subscribe(),
-            network.clone(),
-            tx_sys.clone(),
-        )
+        spawn_countersigning_consumer(space.clone(), space_stop(), network.clone(), tx_sys.clone())
     });
     if let Some(handle) = handle {
         task_sender
@@ -358,6 +360,44 @@ impl QueueConsumerMap {
         self.get_trigger(&QueueEntry(dna_hash, QueueType::Countersigning))
     }
 
+    /// Keep the publish trigger of a cell, so every cell of a dna
+    /// can be told to publish when its network is rejoined.
+    pub fn insert_publish_trigger(&self, cell_id: &CellId, trigger: TriggerSender) {
+        let key = QueueEntry(
+            Arc::new(cell_id.dna_hash().clone()),
+            QueueType::Publish(cell_id.agent_pubkey().clone()),
+        );
+        self.map.share_mut(|map| map.insert(key, trigger));
+    }
+
+    /// Forget the publish trigger of a cell which was removed.
+    pub fn remove_publish_trigger(&self, cell_id: &CellId) {
+        let key = QueueEntry(
+            Arc::new(cell_id.dna_hash().clone()),
+            QueueType::Publish(cell_id.agent_pubkey().clone()),
+        );
+        self.map.share_mut(|map| map.remove(&key));
+    }
+
+    /// Get the publish triggers of the cells of this dna hash.
+    pub fn publish_triggers(&self, dna_hash: &DnaHash) -> Vec<TriggerSender> {
+        self.map.share_ref(|map| {
+            map.iter()
+                .filter(|(QueueEntry(hash, queue_type), _)| {
+                    **hash == *dna_hash && matches!(queue_type, QueueType::Publish(_))
+                })
+                .map(|(_, trigger)| trigger.clone())
+                .collect()
+        })
+    }
+
+    /// Forget the consumers of this dna hash, so they are spawned again
+    /// the next time a cell of the dna is created.
+    pub fn remove(&self, dna_hash: &DnaHash) {
+        self.map
+            .share_mut(|map| map.retain(|QueueEntry(hash, _), _| **hash != *dna_hash));
+    }
+
     fn get_trigger(&self, key: &QueueEntry) -> Option<TriggerSender> {
         self.map.share_ref(|map| map.get(key).cloned())
     }
@@ -380,6 +420,21 @@ impl QueueConsumerMap {
     }
 }
 
+/// A stop receiver which fires when either sender does.
+fn stop_either(a: &broadcast::Sender<()>, b: &broadcast::Sender<()>) -> broadcast::Receiver<()> {
+    let (tx, rx) = broadcast::channel(1);
+    let mut a = a.subscribe();
+    let mut b = b.subscribe();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = a.recv() => (),
+            _ = b.recv() => (),
+        }
+        tx.send(()).ok();
+    });
+    rx
+}
+
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 struct QueueEntry(Arc<DnaHash>, QueueType);
 
@@ -390,6 +445,7 @@ enum QueueType {
     AppValidation,
     SysValidation,
     Countersigning,
+    Publish(AgentPubKey),
 }
 
 /// The entry points for kicking off a chain reaction of queue activity
@@ -235,6 +235,9 @@ async fn publish_loop() {
         })
         .unwrap();
     let mut dna_network = MockHolochainP2pDnaT::new();
+    dna_network
+        .expect_network_status()
+        .returning(|| Ok(holochain_p2p::kitsune_p2p::actor::NetworkStatus::Online));
     let (tx, mut op_published) = tokio::sync::mpsc::channel(100);
     dna_network
         .expect_publish()
@@ -1,8 +1,11 @@
-//! The workflow and queue consumer for validation receipt
+//! The workflow and queue consumer for validation receipt.
+//! Warrants for rejected ops are issued by this consumer too, as both
+//! follow the integration of validated ops.
 
 use super::*;
 use crate::conductor::manager::ManagedTaskResult;
 use crate::core::workflow::validation_receipt_workflow::validation_receipt_workflow;
+use crate::core::workflow::warrant_workflow::warrant_workflow;
 use tokio::task::JoinHandle;
 use tracing::*;
 
@@ -28,8 +31,8 @@ pub fn spawn_validation_receipt_consumer(
                 break;
             }
 
-            // Run the workflow
-            match validation_receipt_workflow(
+            // Run the workflows
+            let result = match warrant_workflow(
                 dna_hash.clone(),
                 env.clone(),
                 &network,
@@ -38,6 +41,21 @@ pub fn spawn_validation_receipt_consumer(
             )
             .await
             {
+                Ok(warrants_complete) => validation_receipt_workflow(
+                    dna_hash.clone(),
+                    env.clone(),
+                    &network,
+                    keystore.clone(),
+                    conductor_handle.clone(),
+                )
+                .await
+                .map(|receipts_complete| match warrants_complete {
+                    WorkComplete::Complete => receipts_complete,
+                    WorkComplete::Incomplete => WorkComplete::Incomplete,
+                }),
+                Err(err) => Err(err),
+            };
+            match result {
                 Ok(WorkComplete::Incomplete) => {
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
@@ -12,6 +12,7 @@ pub mod error;
 pub mod guest_callback;
 pub mod host_fn;
 pub mod real_ribosome;
+pub mod wasm_cache;
 
 use crate::conductor::api::CellConductorApi;
 use crate::conductor::api::CellConductorReadHandle;
@@ -190,6 +191,27 @@ impl HostContext {
         }
     }
 
+    /// Get the guest time of a zome call if this context is for one
+    pub fn guest_time(&self) -> Option<&GuestTime> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { guest_time, .. }) => Some(guest_time),
+            _ => None,
+        }
+    }
+
+    /// Get the call zome handle if this context has one
+    pub fn maybe_call_zome_handle(&self) -> Option<&CellConductorReadHandle> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess {
+                call_zome_handle, ..
+            })
+            | Self::Init(InitHostAccess {
+                call_zome_handle, ..
+            }) => Some(call_zome_handle),
+            _ => None,
+        }
+    }
+
     /// Get the call zome handle, panics if none was provided
     pub fn call_zome_handle(&self) -> &CellConductorReadHandle {
         match self {
@@ -249,6 +271,9 @@ pub enum ZomesToInvoke {
     AllIntegrity,
     /// All integrity and coordinator zomes.
     All,
+    /// All integrity and coordinator zomes in the
+    /// order set by their init dependencies.
+    AllInInitOrder,
     /// A single zome of unknown type.
     One(Zome),
     /// A single integrity zome.
@@ -443,6 +468,26 @@ pub struct ZomeCallHostAccess {
     pub network: HolochainP2pDna,
     pub signal_tx: SignalBroadcaster,
     pub call_zome_handle: CellConductorReadHandle,
+    pub guest_time: GuestTime,
+}
+
+/// The time a zome call spent running wasm.
+/// Time spent in host functions, such as waiting on the network,
+/// is not counted.
+#[derive(Clone, Debug, Default)]
+pub struct GuestTime(Arc<std::sync::atomic::AtomicU64>);
+
+impl GuestTime {
+    /// Add to the time spent running wasm.
+    pub fn add(&self, elapsed: std::time::Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.0.fetch_add(nanos, std::sync::atomic::Ordering::AcqRel);
+    }
+
+    /// The total time spent running wasm.
+    pub fn get(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.0.load(std::sync::atomic::Ordering::Acquire))
+    }
 }
 
 impl From<ZomeCallHostAccess> for HostContext {
@@ -482,6 +527,17 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
                 .all_zomes()
                 .map(|(n, d)| (n.clone(), d.clone()).into())
                 .collect(),
+            ZomesToInvoke::AllInInitOrder => match self.dna_def().init_order() {
+                Ok(zomes) => zomes
+                    .into_iter()
+                    .map(|(n, d)| (n.clone(), d.clone()).into())
+                    .collect(),
+                Err(e) => {
+                    // This is checked when the dna is registered so should never happen.
+                    tracing::error!(?e, "Invalid init order, using the zome definition order");
+                    self.zomes_to_invoke(ZomesToInvoke::All)
+                }
+            },
             ZomesToInvoke::One(zome) => vec![zome],
             ZomesToInvoke::OneIntegrity(zome) => vec![zome.erase_type()],
             ZomesToInvoke::OneCoordinator(zome) => vec![zome.erase_type()],
@@ -489,11 +545,7 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     }
 
     fn zome_name_to_id(&self, zome_name: &ZomeName) -> RibosomeResult<ZomeId> {
-        match self
-            .dna_def()
-            .all_zomes()
-            .position(|(name, _)| name == zome_name)
-        {
+        match self.dna_file().zome_index().zome_position(zome_name) {
             Some(index) => Ok(holochain_zome_types::action::ZomeId::from(index as u8)),
             None => Err(RibosomeError::ZomeNotExists(zome_name.to_owned())),
         }
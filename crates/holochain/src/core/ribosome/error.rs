@@ -86,6 +86,14 @@ pub enum RibosomeError {
     #[error(transparent)]
     SecurePrimitive(#[from] holochain_zome_types::SecurePrimitiveError),
 
+    /// A zome function or callback ran more wasm operations than its budget.
+    #[error("Zome function {1} in zome {0} ran out of its budget of {2} wasm operations")]
+    ComputeBudgetExceeded(ZomeName, FunctionName, u64),
+
+    /// A zome function or callback ran for longer than its time budget.
+    #[error("Zome function {1} in zome {0} ran for longer than its time budget of {2:?}")]
+    ComputeTimeExceeded(ZomeName, FunctionName, std::time::Duration),
+
     /// Zome function doesn't have permissions to call a Host function.
     #[error("Host function {2} cannot be called from zome function {1} in zome {0}")]
     HostFnPermissions(ZomeName, FunctionName, String),
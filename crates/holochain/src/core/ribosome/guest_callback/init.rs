@@ -46,8 +46,11 @@ impl From<&InitHostAccess> for HostFnAccess {
 }
 
 impl Invocation for InitInvocation {
+    /// Zomes are initialized one at a time because every init writes to
+    /// the same source chain. Running them in a fixed order keeps the
+    /// actions they commit in the same order on every run.
     fn zomes(&self) -> ZomesToInvoke {
-        ZomesToInvoke::All
+        ZomesToInvoke::AllInInitOrder
     }
     fn fn_components(&self) -> FnComponents {
         vec!["init".into()].into()
@@ -190,7 +193,7 @@ mod test {
         let init_invocation = InitInvocationFixturator::new(::fixt::Unpredictable)
             .next()
             .unwrap();
-        assert_eq!(ZomesToInvoke::All, init_invocation.zomes(),);
+        assert_eq!(ZomesToInvoke::AllInInitOrder, init_invocation.zomes(),);
     }
 
     #[test]
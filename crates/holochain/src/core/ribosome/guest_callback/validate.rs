@@ -73,7 +73,8 @@ impl Invocation for ValidateInvocation {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SerializedBytes)]
 pub enum ValidateResult {
     Valid,
-    Invalid(String),
+    /// Carries the zome which rejected the op, if known, and its reason.
+    Invalid(ValidationRejection),
     /// subconscious needs to map this to either pending or abandoned based on context that the
     /// wasm can't possibly have
     UnresolvedDependencies(Vec<AnyDhtHash>),
@@ -84,10 +85,20 @@ impl From<Vec<(ZomeName, ValidateCallbackResult)>> for ValidateResult {
     /// have been run by a Ribosome and it is necessary to return one
     /// decisive result to the host, even if that "decisive" result
     /// is the UnresolvedDependencies variant.
-    /// It drops the irrelevant zome names and falls back to the conversion from
-    /// a Vec<ValidateCallbackResults> -> ValidateResult
+    /// The zome name of the last invalid result is kept as the provenance of
+    /// the rejection, the same result the fold over a
+    /// Vec<ValidateCallbackResults> -> ValidateResult reports.
     fn from(a: Vec<(ZomeName, ValidateCallbackResult)>) -> Self {
-        a.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into()
+        match a
+            .iter()
+            .rev()
+            .find(|(_, v)| matches!(v, ValidateCallbackResult::Invalid(_)))
+        {
+            Some((zome_name, ValidateCallbackResult::Invalid(reason))) => Self::Invalid(
+                ValidationRejection::app(Some(zome_name.clone()), reason.clone()),
+            ),
+            _ => a.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into(),
+        }
     }
 }
 
@@ -99,7 +110,9 @@ impl From<Vec<ValidateCallbackResult>> for ValidateResult {
         callback_results
             .into_iter()
             .fold(Self::Valid, |acc, x| match x {
-                ValidateCallbackResult::Invalid(i) => Self::Invalid(i),
+                ValidateCallbackResult::Invalid(i) => {
+                    Self::Invalid(ValidationRejection::app(None, i))
+                }
                 ValidateCallbackResult::UnresolvedDependencies(ud) => match acc {
                     Self::Invalid(_) => acc,
                     _ => Self::UnresolvedDependencies(ud),
@@ -138,7 +151,7 @@ mod test {
 
         let result_valid = || ValidateResult::Valid;
         let result_ud = || ValidateResult::UnresolvedDependencies(vec![]);
-        let result_invalid = || ValidateResult::Invalid("".into());
+        let result_invalid = || ValidateResult::Invalid(ValidationRejection::app(None, ""));
 
         let cb_valid = || ValidateCallbackResult::Valid;
         let cb_ud = || ValidateCallbackResult::UnresolvedDependencies(vec![]);
@@ -171,6 +184,30 @@ mod test {
         }
     }
 
+    #[test]
+    fn validate_callback_result_keeps_last_rejection() {
+        let results = vec![
+            (
+                ZomeName::from("a"),
+                ValidateCallbackResult::Invalid("a".into()),
+            ),
+            (ZomeName::from("b"), ValidateCallbackResult::Valid),
+            (
+                ZomeName::from("c"),
+                ValidateCallbackResult::Invalid("c".into()),
+            ),
+        ];
+        assert_eq!(
+            ValidateResult::from(results.clone()),
+            ValidateResult::Invalid(ValidationRejection::app(Some("c".into()), "c"))
+        );
+        // the zome name is the only difference to the fold without names
+        assert_eq!(
+            ValidateResult::from(results.into_iter().map(|(_, r)| r).collect::<Vec<_>>()),
+            ValidateResult::Invalid(ValidationRejection::app(None, "c"))
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn validate_invocation_allow_side_effects() {
         let validate_host_access = ValidateHostAccessFixturator::new(::fixt::Unpredictable)
@@ -295,7 +332,13 @@ mod slow_tests {
         let result = ribosome
             .run_validate(fixt!(ValidateHostAccess), validate_invocation)
             .unwrap();
-        assert_eq!(result, ValidateResult::Invalid("esoteric edge case".into()));
+        assert_eq!(
+            result,
+            ValidateResult::Invalid(ValidationRejection::app(
+                Some(TestWasm::ValidateInvalid.into()),
+                "esoteric edge case"
+            ))
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
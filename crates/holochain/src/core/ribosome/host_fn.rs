@@ -66,10 +66,13 @@ host_fn_api_impls! {
     // Info about the calling agent.
     fn agent_info (()) -> zt::info::AgentInfo;
 
-    // @todo
+    // Info about the installed app of the calling cell.
+    fn app_info (()) -> zt::info::AppInfo;
+
+    // Info about the current DNA.
     fn dna_info (()) -> zt::info::DnaInfo;
 
-    // @todo
+    // Info about the current call.
     fn call_info (()) -> zt::info::CallInfo;
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
@@ -130,6 +133,9 @@ host_fn_api_impls! {
     // Emit a Signal::App to subscribers on the interface
     fn emit_signal (zt::signal::AppSignal) -> ();
 
+    // Emit a Signal::AppAckRequired which clients must acknowledge.
+    fn emit_signal_ack_required (zt::signal::AppSignal) -> ();
+
     // The trace host import takes a TraceMsg to output wherever the host wants to display it.
     // TraceMsg includes line numbers. so the wasm tells the host about it's own code structure.
     fn trace (zt::trace::TraceMsg) -> ();
@@ -141,6 +147,9 @@ host_fn_api_impls! {
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
 
+    // Attempt to get a live entry from the cascade and say why it is missing.
+    fn get_outcome (Vec<zt::entry::GetInput>) -> Vec<zt::entry::GetOutcome<zt::record::Record>>;
+
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
 
@@ -158,6 +167,9 @@ host_fn_api_impls! {
     // Retrieve an action from the DHT or short circuit.
     fn must_get_action (zt::entry::MustGetActionInput) -> SignedActionHashed;
 
+    // Retrieve the validation package of an action or short circuit.
+    fn must_get_validation_package (zt::validate::MustGetValidationPackageInput) -> zt::validate::ValidationPackage;
+
     // Attempt to accept a preflight request.
     fn accept_countersigning_preflight_request(zt::countersigning::PreflightRequest) -> zt::countersigning::PreflightRequestAcceptance;
 
@@ -0,0 +1,74 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use holochain_zome_types::info::AppInfo;
+use std::sync::Arc;
+
+pub fn app_info(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    _input: (),
+) -> Result<AppInfo, RuntimeError> {
+    let call_zome_handle = match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            bindings: Permission::Allow,
+            ..
+        } => call_context
+            .host_context()
+            .maybe_call_zome_handle()
+            .cloned(),
+        _ => None,
+    };
+    match call_zome_handle {
+        Some(call_zome_handle) => {
+            let (installed_app_id, role_id) = tokio_helper::block_forever_on(async move {
+                call_zome_handle
+                    .find_app_and_role()
+                    .await
+                    .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })
+            })?
+            .ok_or_else(|| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(
+                    "The cell is not part of a running app.".to_string()
+                ))
+                .into()
+            })?;
+            Ok(AppInfo {
+                installed_app_id,
+                role_id,
+            })
+        }
+        None => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "app_info".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn app_info_test() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::ZomeInfo).await;
+
+        let app_info: AppInfo = conductor.call(&alice, "app_info", ()).await;
+        assert!(!app_info.installed_app_id.is_empty());
+        assert!(!app_info.role_id.is_empty());
+    }
+}
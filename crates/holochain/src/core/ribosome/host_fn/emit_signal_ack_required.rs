@@ -0,0 +1,29 @@
+use crate::core::ribosome::RibosomeT;
+use crate::core::ribosome::CallContext;
+use holochain_types::prelude::*;
+use std::sync::Arc;
+use holochain_wasmer_host::prelude::*;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+
+pub fn emit_signal_ack_required(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: AppSignal,
+) -> Result<(), RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess{ write_workspace: Permission::Allow, .. } => {
+            let cell_id = CellId::new(
+                ribosome.dna_def().as_hash().clone(),
+                call_context.host_context.workspace().source_chain().as_ref().expect("Must have a source chain to emit signals").agent_pubkey().clone(),
+            );
+            call_context.host_context().signal_tx().send_ack_required(cell_id, input);
+            Ok(())
+        },
+        _ => Err(wasm_error!(WasmErrorInner::Host(RibosomeError::HostFnPermissions(
+            call_context.zome.zome_name().clone(),
+            call_context.function_name().clone(),
+            "emit_signal_ack_required".into()
+        ).to_string())).into())
+    }
+}
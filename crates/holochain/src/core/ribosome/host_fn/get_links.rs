@@ -30,6 +30,7 @@ pub fn get_links<'a>(
                             base_address,
                             link_type,
                             tag_prefix,
+                            pagination,
                         } = input;
 
                         let key = WireLinkKey {
@@ -41,7 +42,13 @@ pub fn get_links<'a>(
                             &call_context.host_context.workspace(),
                             call_context.host_context.network().to_owned(),
                         )
-                        .dht_get_links(key, GetLinksOptions::default())
+                        .dht_get_links(
+                            key,
+                            GetLinksOptions {
+                                pagination,
+                                ..Default::default()
+                            },
+                        )
                         .await?)
                     }))
                     // Limit concurrent calls to 10 as each call
@@ -0,0 +1,91 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::StreamExt;
+use holochain_cascade::Cascade;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn get_outcome<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<GetInput>,
+) -> Result<Vec<GetOutcome<Record>>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let results: Vec<Result<GetOutcome<Record>, _>> =
+                tokio_helper::block_forever_on(async move {
+                    futures::stream::iter(inputs.into_iter().map(|input| async {
+                        let GetInput {
+                            any_dht_hash,
+                            get_options,
+                        } = input;
+                        Cascade::from_workspace_network(
+                            &call_context.host_context.workspace(),
+                            call_context.host_context.network().clone(),
+                        )
+                        .dht_get_outcome(any_dht_hash, get_options)
+                        .await
+                    }))
+                    // Limit concurrent calls to 10 as each call
+                    // can spawn multiple connections.
+                    .buffered(10)
+                    .collect()
+                    .await
+                });
+            results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(v) => Ok(v),
+                    Err(cascade_error) => {
+                        Err(wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into())
+                    }
+                })
+                .collect()
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_outcome".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_get_outcome_test() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Create).await;
+
+        // Alice is an authority for everything so a missing entry is
+        // not found rather than unavailable.
+        let missing: GetOutcome<Record> = conductor.call(&alice, "get_entry_outcome", ()).await;
+        assert_eq!(missing, GetOutcome::NotFound);
+
+        let _: ActionHash = conductor.call(&alice, "create_entry", ()).await;
+
+        let found: GetOutcome<Record> = conductor.call(&alice, "get_entry_outcome", ()).await;
+        let record: Option<Record> = conductor.call(&alice, "get_entry", ()).await;
+        assert!(record.is_some());
+        assert_eq!(found.into_option(), record);
+    }
+}
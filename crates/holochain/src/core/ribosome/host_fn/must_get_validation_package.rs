@@ -0,0 +1,137 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use crate::core::workflow::app_validation_workflow::validation_package::required_validation_type_from_ribosome;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetOptions as NetworkGetOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn must_get_validation_package<'a>(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: MustGetValidationPackageInput,
+) -> Result<ValidationPackage, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace_deterministic: Permission::Allow,
+            ..
+        } => {
+            let action_hash = input.into_inner();
+
+            // timeouts must be handled by the network
+            tokio_helper::block_forever_on(async move {
+                let workspace = call_context.host_context.workspace();
+                let package = match call_context.host_context {
+                    // Validation only ever sees the packages which
+                    // app validation fetched before running the callback.
+                    HostContext::Validate(_) => {
+                        Cascade::from_workspace(workspace.stores(), None)
+                            .get_validation_package_local(&action_hash)
+                            .await
+                    }
+                    _ => {
+                        let mut cascade = Cascade::from_workspace_network(
+                            &workspace,
+                            call_context.host_context.network().clone(),
+                        );
+                        match cascade
+                            .retrieve_action(
+                                action_hash.clone(),
+                                NetworkGetOptions::must_get_options(),
+                            )
+                            .await
+                        {
+                            Ok(Some(action)) => {
+                                let (action, _) = action.into_inner();
+                                let required = required_validation_type_from_ribosome(
+                                    action.as_content(),
+                                    ribosome.as_ref(),
+                                );
+                                cascade
+                                    .get_validation_package(
+                                        action.author().clone(),
+                                        &action,
+                                        required,
+                                    )
+                                    .await
+                            }
+                            Ok(None) => Ok(None),
+                            Err(e) => Err(e),
+                        }
+                    }
+                }
+                .map_err(|cascade_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+                })?;
+                match package {
+                    Some(package) => Ok(package),
+                    None => match call_context.host_context {
+                        HostContext::EntryDefs(_)
+                        | HostContext::GenesisSelfCheck(_)
+                        | HostContext::MigrateAgent(_)
+                        | HostContext::PostCommit(_)
+                        | HostContext::ZomeCall(_) => Err(wasm_error!(WasmErrorInner::Host(
+                            format!("Failed to get ValidationPackage {}", action_hash)
+                        ))
+                        .into()),
+                        HostContext::Init(_) => Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                            holochain_serialized_bytes::encode(
+                                &ExternIO::encode(InitCallbackResult::UnresolvedDependencies(
+                                    vec![action_hash.into()],
+                                ))
+                                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?,
+                            )
+                            .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?
+                        ))
+                        .into()),
+                        HostContext::Validate(_) => {
+                            Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                                holochain_serialized_bytes::encode(
+                                    &ExternIO::encode(
+                                        ValidateCallbackResult::UnresolvedDependencies(vec![
+                                            action_hash.into()
+                                        ],)
+                                    )
+                                    .map_err(
+                                        |e| -> RuntimeError { wasm_error!(e.into()).into() }
+                                    )?,
+                                )
+                                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?
+                            ))
+                            .into())
+                        }
+                        HostContext::ValidationPackage(_) => {
+                            Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                                holochain_serialized_bytes::encode(
+                                    &ExternIO::encode(
+                                        ValidationPackageCallbackResult::UnresolvedDependencies(
+                                            vec![action_hash.into(),]
+                                        ),
+                                    )
+                                    .map_err(
+                                        |e| -> RuntimeError { wasm_error!(e.into()).into() }
+                                    )?
+                                )
+                                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?,
+                            ))
+                            .into())
+                        }
+                    },
+                }
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "must_get_validation_package".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -1,15 +1,26 @@
+use crate::conductor::interface::SignalBroadcaster;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
+use futures::FutureExt;
+use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
-use holochain_types::access::Permission;
+use holochain_types::prelude::*;
+use holochain_types::signal::SystemSignal;
 use holochain_wasmer_host::prelude::*;
-use holochain_zome_types::signal::RemoteSignal;
-use holochain_zome_types::zome::FunctionName;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 use tracing::Instrument;
 
+/// How long a reliable remote signal keeps retrying an agent before giving up.
+const RELIABLE_RETRY_PERIOD: Duration = Duration::from_secs(60);
+
+/// The wait before the first retry of a reliable remote signal.
+/// It doubles after every failed attempt.
+const RELIABLE_FIRST_BACKOFF: Duration = Duration::from_secs(1);
+
 #[tracing::instrument(skip(_ribosome, call_context, input))]
 pub fn remote_signal(
     _ribosome: Arc<impl RibosomeT>,
@@ -25,12 +36,55 @@ pub fn remote_signal(
             const FN_NAME: &str = "recv_remote_signal";
             let from_agent = super::agent_info::agent_info(_ribosome, call_context.clone(), ())?
                 .agent_latest_pubkey;
-            // Timeouts and errors are ignored,
-            // this is a send and forget operation.
             let network = call_context.host_context().network().clone();
-            let RemoteSignal { agents, signal } = input;
+            let RemoteSignal {
+                agents,
+                signal,
+                reliable,
+            } = input;
             let zome_name = call_context.zome().zome_name().clone();
             let fn_name: FunctionName = FN_NAME.into();
+            if reliable {
+                let cell_id = CellId::new(network.dna_hash(), from_agent.clone());
+                let mut signal_tx = call_context.host_context().signal_tx().clone();
+                let handle = call_context.host_context().call_zome_handle().clone();
+                for to_agent in agents {
+                    let task = send_reliably(
+                        network.clone(),
+                        signal_tx.clone(),
+                        cell_id.clone(),
+                        to_agent.clone(),
+                        zome_name.clone(),
+                        fn_name.clone(),
+                        signal.clone(),
+                    )
+                    .in_current_span()
+                    .boxed();
+                    let spawned =
+                        tokio_helper::block_forever_on(handle.spawn_reliable_signal(task))
+                            .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?;
+                    if !spawned {
+                        tracing::warn!(
+                            ?to_agent,
+                            "Too many reliable remote signals in flight, not sending"
+                        );
+                        let outcome = SystemSignal::RemoteSignalDelivery {
+                            cell_id: cell_id.clone(),
+                            to_agent,
+                            delivered: false,
+                        };
+                        if let Err(e) = signal_tx.send(outcome.into()) {
+                            tracing::info!(
+                                "Failed to report remote signal delivery because of {:?}",
+                                e
+                            );
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            // Timeouts and errors are ignored,
+            // this is a send and forget operation.
             tokio::task::spawn(
                 async move {
                     if let Err(e) = network
@@ -51,7 +105,57 @@ pub fn remote_signal(
                 "remote_signal".into(),
             )
             .to_string(),
-        )).into()),
+        ))
+        .into()),
+    }
+}
+
+/// Call `recv_remote_signal` on one agent until it succeeds or the retry
+/// period runs out, then tell the sender's clients whether it arrived.
+async fn send_reliably(
+    network: HolochainP2pDna,
+    mut signal_tx: SignalBroadcaster,
+    cell_id: CellId,
+    to_agent: AgentPubKey,
+    zome_name: ZomeName,
+    fn_name: FunctionName,
+    signal: ExternIO,
+) {
+    let give_up_at = Instant::now() + RELIABLE_RETRY_PERIOD;
+    let mut backoff = RELIABLE_FIRST_BACKOFF;
+    let delivered = loop {
+        let response = network
+            .call_remote(
+                cell_id.agent_pubkey().clone(),
+                to_agent.clone(),
+                zome_name.clone(),
+                fn_name.clone(),
+                None,
+                signal.clone(),
+            )
+            .await
+            .map(ZomeCallResponse::try_from);
+        match response {
+            Ok(Ok(ZomeCallResponse::Ok(_))) => break true,
+            // Retrying won't get the agent to grant access.
+            Ok(Ok(ZomeCallResponse::Unauthorized(..))) => break false,
+            response => {
+                tracing::debug!(?to_agent, ?response, "Failed to send remote signal")
+            }
+        }
+        if Instant::now() + backoff > give_up_at {
+            break false;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    };
+    let outcome = SystemSignal::RemoteSignalDelivery {
+        cell_id,
+        to_agent,
+        delivered,
+    };
+    if let Err(e) = signal_tx.send(outcome.into()) {
+        tracing::info!("Failed to report remote signal delivery because of {:?}", e);
     }
 }
 
@@ -66,7 +170,11 @@ mod tests {
     use hdk::prelude::*;
     use holochain_types::inline_zome::InlineZomeSet;
 
-    fn zome(agents: Vec<AgentPubKey>, num_signals: Arc<AtomicUsize>) -> InlineZomeSet {
+    fn zome(
+        agents: Vec<AgentPubKey>,
+        num_signals: Arc<AtomicUsize>,
+        reliable: bool,
+    ) -> InlineZomeSet {
         let entry_def = EntryDef::default_with_id("entrydef");
 
         SweetEasyInline::new(vec![entry_def.clone()], 0)
@@ -75,6 +183,7 @@ mod tests {
                 let signal = RemoteSignal {
                     agents: agents.clone(),
                     signal,
+                    reliable,
                 };
                 tracing::debug!("sending signal to {:?}", agents);
                 api.remote_signal(signal)?;
@@ -120,10 +229,13 @@ mod tests {
         let agents =
             future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
 
-        let (dna_file, _, _) =
-            SweetDnaFile::unique_from_inline_zomes(zome(agents.clone(), num_signals.clone()))
-                .await
-                .unwrap();
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zome(
+            agents.clone(),
+            num_signals.clone(),
+            false,
+        ))
+        .await
+        .unwrap();
 
         let apps = conductors
             .setup_app_for_zipped_agents("app", &agents, &[dna_file.clone().into()])
@@ -156,4 +268,59 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "test_utils")]
+    async fn reliable_remote_signal_reports_delivery() -> anyhow::Result<()> {
+        use futures::StreamExt;
+        use holochain_types::signal::SystemSignal;
+        observability::test_run().ok();
+
+        let num_signals = Arc::new(AtomicUsize::new(0));
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let agents =
+            future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
+        let (dna_file, _, _) =
+            SweetDnaFile::unique_from_inline_zomes(zome(agents.clone(), num_signals.clone(), true))
+                .await
+                .unwrap();
+        let apps = conductors
+            .setup_app_for_zipped_agents("app", &agents, &[dna_file.clone().into()])
+            .await
+            .unwrap();
+        conductors.exchange_peer_info().await;
+        let cells: Vec<_> = apps.cells_flattened();
+
+        let signals = conductors[0].signal_broadcaster().await.subscribe_merged();
+        let _: () = conductors[0]
+            .call(
+                &cells[0].zome(SweetEasyInline::COORDINATOR),
+                "signal_others",
+                (),
+            )
+            .await;
+
+        // Each agent's delivery is reported back to the sender.
+        let mut delivered: Vec<_> = signals
+            .filter_map(|signal| async move {
+                match signal {
+                    Signal::System(SystemSignal::RemoteSignalDelivery {
+                        to_agent,
+                        delivered,
+                        ..
+                    }) => Some((to_agent, delivered)),
+                    _ => None,
+                }
+            })
+            .take(2)
+            .collect()
+            .await;
+        delivered.sort();
+        let mut expected: Vec<_> = agents.into_iter().map(|agent| (agent, true)).collect();
+        expected.sort();
+        assert_eq!(delivered, expected);
+        assert_eq!(num_signals.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
 }
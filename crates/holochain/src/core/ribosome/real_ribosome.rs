@@ -27,6 +27,7 @@ use crate::core::ribosome::guest_callback::validation_package::ValidationPackage
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::ribosome::host_fn::accept_countersigning_preflight_request::accept_countersigning_preflight_request;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
+use crate::core::ribosome::host_fn::app_info::app_info;
 use crate::core::ribosome::host_fn::call::call;
 use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
@@ -39,14 +40,17 @@ use crate::core::ribosome::host_fn::delete::delete;
 use crate::core::ribosome::host_fn::delete_link::delete_link;
 use crate::core::ribosome::host_fn::dna_info::dna_info;
 use crate::core::ribosome::host_fn::emit_signal::emit_signal;
+use crate::core::ribosome::host_fn::emit_signal_ack_required::emit_signal_ack_required;
 use crate::core::ribosome::host_fn::get::get;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
+use crate::core::ribosome::host_fn::get_outcome::get_outcome;
 use crate::core::ribosome::host_fn::hash::hash;
 use crate::core::ribosome::host_fn::must_get_action::must_get_action;
 use crate::core::ribosome::host_fn::must_get_entry::must_get_entry;
 use crate::core::ribosome::host_fn::must_get_valid_record::must_get_valid_record;
+use crate::core::ribosome::host_fn::must_get_validation_package::must_get_validation_package;
 use crate::core::ribosome::host_fn::query::query;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
 use crate::core::ribosome::host_fn::remote_signal::remote_signal;
@@ -74,17 +78,19 @@ use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallInvocation;
 use fallible_iterator::FallibleIterator;
 use holochain_types::prelude::*;
-use holochain_wasmer_host::module::SerializedModuleCache;
+use wasmer_middlewares::metering::get_remaining_points;
+use wasmer_middlewares::metering::set_remaining_points;
+use wasmer_middlewares::metering::MeteringPoints;
 use wasmer_middlewares::Metering;
 // This is here because there were errors about different crate versions
 // without it.
-use kitsune_p2p_types::dependencies::lair_keystore_api::dependencies::parking_lot::lock_api::RwLock;
 
 use holochain_types::zome_types::GlobalZomeTypes;
 use holochain_types::zome_types::ZomeTypesError;
 use holochain_wasmer_host::prelude::*;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 
@@ -103,6 +109,38 @@ pub struct RealRibosome {
 
     /// Dependencies for every zome.
     pub zome_dependencies: Arc<HashMap<ZomeName, Vec<ZomeId>>>,
+
+    /// The zome types in scope for every zome, from its dependencies.
+    pub scoped_zome_types: Arc<HashMap<ZomeName, ScopedZomeTypesSet>>,
+
+    /// How much work each zome call or callback may do before it is stopped.
+    pub wasm_call_limits: WasmCallLimits,
+}
+
+/// The number of wasm operations a call may run if the conductor config
+/// doesn't set `max_wasm_ops_per_call`.
+pub const DEFAULT_WASM_OPS_PER_CALL: u64 = 10_000_000_000;
+
+/// The budget of each zome call or callback run by a [`RealRibosome`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WasmCallLimits {
+    /// The number of wasm operations a call may run before it fails
+    /// with [`RibosomeError::ComputeBudgetExceeded`].
+    pub ops_per_call: u64,
+    /// How long a call may run before it fails with
+    /// [`RibosomeError::ComputeTimeExceeded`]. Time spent in host
+    /// functions counts too, but a call is only stopped while it is
+    /// running wasm. Unlimited if `None`.
+    pub time_per_call: Option<std::time::Duration>,
+}
+
+impl Default for WasmCallLimits {
+    fn default() -> Self {
+        Self {
+            ops_per_call: DEFAULT_WASM_OPS_PER_CALL,
+            time_per_call: None,
+        }
+    }
 }
 
 struct HostFnBuilder {
@@ -167,10 +205,12 @@ impl HostFnBuilder {
                             })
                             .clone()
                     };
+                    let started = std::time::Instant::now();
                     let result = match db.consume_bytes_from_guest(guest_ptr, len) {
                         Ok(input) => host_function(Arc::clone(&ribosome_arc), context_arc, input),
                         Err(runtime_error) => Result::<_, RuntimeError>::Err(runtime_error),
                     };
+                    HOST_FN_TIME.with(|t| t.set(t.get() + started.elapsed()));
                     Ok(vec![Value::I64(i64::from_le_bytes(
                         db.move_data_to_guest(match result {
                             Err(runtime_error) => match runtime_error.downcast::<WasmError>() {
@@ -200,6 +240,13 @@ static CONTEXT_MAP: ContextMap = Lazy::new(Default::default);
 
 static CONTEXT_KEY: AtomicU64 = AtomicU64::new(0);
 
+thread_local! {
+    /// Time spent in host functions by the guest call running on this thread.
+    /// Host functions run on the same thread as the guest that called them.
+    static HOST_FN_TIME: std::cell::Cell<std::time::Duration> =
+        std::cell::Cell::new(std::time::Duration::ZERO);
+}
+
 /// Create a key for the instance cache.
 /// It will be [WasmHash..DnaHash..context_key] all as bytes.
 fn instance_cache_key(wasm_hash: &WasmHash, dna_hash: &DnaHash, context_key: u64) -> [u8; 32] {
@@ -233,11 +280,16 @@ fn context_key_from_key(key: &[u8; 32]) -> u64 {
 impl RealRibosome {
     /// Create a new instance
     pub fn new(dna_file: DnaFile) -> RibosomeResult<Self> {
+        // Reject zomes whose init dependencies are missing or form a cycle.
+        dna_file.dna_def().init_order().map_err(DnaError::from)?;
+
         // Create an empty ribosome.
         let ribosome = Self {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            scoped_zome_types: Default::default(),
+            wasm_call_limits: WasmCallLimits::default(),
         };
 
         // Collect the number of entry and link types
@@ -299,7 +351,7 @@ impl RealRibosome {
                     dependencies.push(ZomeId(0));
                 } else {
                     // Integrity zomes need to have themselves as a dependency.
-                    if ribosome.dna_def().is_integrity_zome(zome_name) {
+                    if ribosome.dna_file.is_integrity_zome(zome_name) {
                         // Get the ZomeId for this zome.
                         let id = integrity_zomes.get(zome_name).copied().ok_or_else(|| {
                             ZomeTypesError::MissingDependenciesForZome(zome_name.clone())
@@ -319,43 +371,46 @@ impl RealRibosome {
             })
             .collect::<RibosomeResult<HashMap<_, _>>>()?;
 
+        // Scope the zome types for each zome once, rather than on every call.
+        let scoped_zome_types = zome_dependencies
+            .iter()
+            .map(|(zome_name, dependencies)| {
+                (zome_name.clone(), zome_types.in_scope_subset(dependencies))
+            })
+            .collect();
+
         Ok(Self {
             dna_file: ribosome.dna_file,
             zome_types,
             zome_dependencies: Arc::new(zome_dependencies),
+            scoped_zome_types: Arc::new(scoped_zome_types),
+            wasm_call_limits: ribosome.wasm_call_limits,
         })
     }
 
+    /// Set the budget of each call this ribosome runs.
+    pub fn with_wasm_call_limits(mut self, wasm_call_limits: WasmCallLimits) -> Self {
+        self.wasm_call_limits = wasm_call_limits;
+        self
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     pub fn empty(dna_file: DnaFile) -> Self {
         Self {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            scoped_zome_types: Default::default(),
+            wasm_call_limits: WasmCallLimits::default(),
         }
     }
 
     pub fn module(&self, zome_name: &ZomeName) -> RibosomeResult<Arc<Module>> {
-        if holochain_wasmer_host::module::SERIALIZED_MODULE_CACHE
-            .get()
-            .is_none()
-        {
-            holochain_wasmer_host::module::SERIALIZED_MODULE_CACHE
-                .set(RwLock::new(SerializedModuleCache::default_with_cranelift(
-                    Self::cranelift,
-                )))
-                // An error here means the cell is full when we tried to set it, so
-                // some other thread must have done something in between the get
-                // above and the set here. In this case we don't care as we don't
-                // have any competing code paths that could set it to something
-                // unexpected.
-                .ok();
-        }
-
-        Ok(holochain_wasmer_host::module::MODULE_CACHE.write().get(
+        super::wasm_cache::module(
             self.wasm_cache_key(zome_name)?,
             &*self.dna_file.get_wasm_for_zome(zome_name)?.code(),
-        )?)
+            Self::cranelift,
+        )
     }
 
     pub fn wasm_cache_key(&self, zome_name: &ZomeName) -> Result<[u8; 32], DnaError> {
@@ -365,7 +420,6 @@ impl RealRibosome {
         let mut key = [0; 32];
         let bytes = self
             .dna_file
-            .dna()
             .get_wasm_zome(zome_name)?
             .wasm_hash
             .get_raw_32();
@@ -387,7 +441,6 @@ impl RealRibosome {
         let key = instance_cache_key(
             &self
                 .dna_file
-                .dna()
                 .get_wasm_zome(zome_name)
                 .map_err(DnaError::from)?
                 .wasm_hash,
@@ -422,7 +475,6 @@ impl RealRibosome {
         let key_start = instance_cache_key(
             &self
                 .dna_file
-                .dna()
                 .get_wasm_zome(&zome_name)
                 .map_err(DnaError::from)?
                 .wasm_hash,
@@ -433,7 +485,6 @@ impl RealRibosome {
         let key_end = instance_cache_key(
             &self
                 .dna_file
-                .dna()
                 .get_wasm_zome(&zome_name)
                 .map_err(DnaError::from)?
                 .wasm_hash,
@@ -477,11 +528,16 @@ impl RealRibosome {
         Ok((instance, context_key))
     }
 
+    /// The compiler settings of [`RealRibosome::cranelift`]. Modules in the
+    /// wasm disk cache are kept apart by a hash of this and the wasmer version,
+    /// so it must change whenever the compiler or its middleware change.
+    pub const ENGINE_CONFIG: &'static str = "cranelift;canonicalize_nans;metering:1";
+
     pub fn cranelift() -> Cranelift {
         let cost_function = |_operator: &WasmOperator| -> u64 { 1 };
-        // @todo 10 giga-ops is totally arbitrary cutoff so we probably
-        // want to make the limit configurable somehow.
-        let metering = Arc::new(Metering::new(10_000_000_000, cost_function));
+        // The limit is reset to the ribosome's `ops_per_call` before
+        // every call, as instances are cached and reused between calls.
+        let metering = Arc::new(Metering::new(DEFAULT_WASM_OPS_PER_CALL, cost_function));
         let mut cranelift = Cranelift::default();
         cranelift.canonicalize_nans(true).push_middleware(metering);
         cranelift
@@ -552,17 +608,24 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__sys_time", sys_time)
             .with_host_function(&mut ns, "__sleep", sleep)
             .with_host_function(&mut ns, "__agent_info", agent_info)
+            .with_host_function(&mut ns, "__app_info", app_info)
             .with_host_function(&mut ns, "__capability_claims", capability_claims)
             .with_host_function(&mut ns, "__capability_grants", capability_grants)
             .with_host_function(&mut ns, "__capability_info", capability_info)
             .with_host_function(&mut ns, "__get", get)
             .with_host_function(&mut ns, "__get_details", get_details)
+            .with_host_function(&mut ns, "__get_outcome", get_outcome)
             .with_host_function(&mut ns, "__get_links", get_links)
             .with_host_function(&mut ns, "__get_link_details", get_link_details)
             .with_host_function(&mut ns, "__get_agent_activity", get_agent_activity)
             .with_host_function(&mut ns, "__must_get_entry", must_get_entry)
             .with_host_function(&mut ns, "__must_get_action", must_get_action)
             .with_host_function(&mut ns, "__must_get_valid_record", must_get_valid_record)
+            .with_host_function(
+                &mut ns,
+                "__must_get_validation_package",
+                must_get_validation_package,
+            )
             .with_host_function(
                 &mut ns,
                 "__accept_countersigning_preflight_request",
@@ -573,6 +636,11 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__call", call)
             .with_host_function(&mut ns, "__create", create)
             .with_host_function(&mut ns, "__emit_signal", emit_signal)
+            .with_host_function(
+                &mut ns,
+                "__emit_signal_ack_required",
+                emit_signal_ack_required,
+            )
             .with_host_function(&mut ns, "__create_link", create_link)
             .with_host_function(&mut ns, "__delete_link", delete_link)
             .with_host_function(&mut ns, "__update", update)
@@ -590,6 +658,16 @@ impl RealRibosome {
             .get(zome_name)
             .ok_or_else(|| ZomeTypesError::MissingDependenciesForZome(zome_name.clone()))?)
     }
+
+    pub fn get_scoped_zome_types(
+        &self,
+        zome_name: &ZomeName,
+    ) -> RibosomeResult<&ScopedZomeTypesSet> {
+        Ok(self
+            .scoped_zome_types
+            .get(zome_name)
+            .ok_or_else(|| ZomeTypesError::MissingDependenciesForZome(zome_name.clone()))?)
+    }
 }
 
 /// General purpose macro which relies heavily on various impls of the form:
@@ -636,8 +714,8 @@ impl RibosomeT for RealRibosome {
     fn zome_info(&self, zome: Zome) -> RibosomeResult<ZomeInfo> {
         // Get the dependencies for this zome.
         let zome_dependencies = self.get_zome_dependencies(zome.zome_name())?;
-        // Scope the zome types to these dependencies.
-        let zome_types = self.zome_types.in_scope_subset(zome_dependencies);
+        // Get the zome types scoped to these dependencies.
+        let zome_types = self.get_scoped_zome_types(zome.zome_name())?.clone();
 
         Ok(ZomeInfo {
             name: zome.zome_name().clone(),
@@ -723,20 +801,76 @@ impl RibosomeT for RealRibosome {
                     // there is a callback to_call and it is implemented in the wasm
                     // it is important to fully instantiate this (e.g. don't try to use the module above)
                     // because it builds guards against memory leaks and handles imports correctly
+                    let guest_time = call_context.host_context.guest_time().cloned();
                     let (instance, context_key) = self.instance(call_context)?;
 
+                    // Give this call its full budget of operations.
+                    set_remaining_points(&instance.lock(), self.wasm_call_limits.ops_per_call);
+                    // Stop the call once it runs out of time by taking
+                    // away the rest of its operations.
+                    let timed_out = Arc::new(AtomicBool::new(false));
+                    let watchdog = self.wasm_call_limits.time_per_call.map(|time_per_call| {
+                        let remaining_points = instance
+                            .lock()
+                            .exports
+                            .get_global("wasmer_metering_remaining_points")
+                            .cloned();
+                        let timed_out = timed_out.clone();
+                        tokio_helper::TOKIO.spawn(async move {
+                            tokio::time::sleep(time_per_call).await;
+                            timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                            if let Ok(remaining_points) = remaining_points {
+                                let _ = remaining_points.set(0u64.into());
+                            }
+                        })
+                    });
+                    let input = invocation.to_owned().host_input()?;
+                    // A host function may call back into another zome on this
+                    // thread, so keep the caller's host function time aside.
+                    let caller_host_fn_time =
+                        HOST_FN_TIME.with(|t| t.replace(std::time::Duration::ZERO));
+                    let started = std::time::Instant::now();
                     let result: Result<ExternIO, RuntimeError> = holochain_wasmer_host::guest::call(
                         instance.clone(),
                         to_call.as_ref(),
                         // be aware of this clone!
                         // the whole invocation is cloned!
                         // @todo - is this a problem for large payloads like entries?
-                        invocation.to_owned().host_input()?,
+                        input,
                     );
+                    let elapsed = started.elapsed();
+                    if let Some(watchdog) = watchdog {
+                        watchdog.abort();
+                    }
+                    let host_fn_time = HOST_FN_TIME.with(|t| t.replace(caller_host_fn_time));
+                    if let Some(guest_time) = guest_time {
+                        guest_time.add(elapsed.saturating_sub(host_fn_time));
+                    }
+
+                    let exhausted = result.is_err()
+                        && get_remaining_points(&instance.lock()) == MeteringPoints::Exhausted;
 
                     // Cache this instance.
                     self.cache_instance(context_key, instance, zome.zome_name())?;
 
+                    if exhausted {
+                        return Err(match self.wasm_call_limits.time_per_call {
+                            Some(time_per_call)
+                                if timed_out.load(std::sync::atomic::Ordering::Relaxed) =>
+                            {
+                                RibosomeError::ComputeTimeExceeded(
+                                    zome.zome_name().clone(),
+                                    to_call.clone(),
+                                    time_per_call,
+                                )
+                            }
+                            _ => RibosomeError::ComputeBudgetExceeded(
+                                zome.zome_name().clone(),
+                                to_call.clone(),
+                                self.wasm_call_limits.ops_per_call,
+                            ),
+                        });
+                    }
                     Ok(Some(result?))
                 } else {
                     // the func doesn't exist
@@ -1000,7 +1134,8 @@ pub mod wasm_test {
             conductor.call_fallible(&alice, "smash", ()),
         )
         .await;
-        assert!(result.unwrap().is_err());
+        let err = result.unwrap().unwrap_err();
+        assert!(format!("{:?}", err).contains("ComputeBudgetExceeded"));
 
         // The same thing will happen when we commit an entry due to a loop in
         // the validation logic.
@@ -1011,4 +1146,28 @@ pub mod wasm_test {
         .await;
         assert!(create_result.unwrap().is_err());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wasm_calls_are_stopped_when_out_of_time() {
+        observability::test_run().ok();
+        let mut config = crate::sweettest::standard_config();
+        config.max_wasm_ops_per_call = Some(u64::MAX);
+        config.max_wasm_time_per_call_ms = Some(500);
+        let mut conductor = SweetConductor::from_config(config).await;
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::TheIncredibleHalt])
+            .await
+            .unwrap();
+        let app = conductor.setup_app("app", &[dna]).await.unwrap();
+        let (cell,) = app.into_tuple();
+
+        // The loop never runs out of operations, so only the time budget
+        // can stop it.
+        let result: Result<Result<(), _>, _> = tokio::time::timeout(
+            std::time::Duration::from_millis(10000),
+            conductor.call_fallible(&cell.zome(TestWasm::TheIncredibleHalt), "smash", ()),
+        )
+        .await;
+        let err = result.unwrap().unwrap_err();
+        assert!(format!("{:?}", err).contains("ComputeTimeExceeded"));
+    }
 }
@@ -0,0 +1,224 @@
+//! The conductor-wide cache of compiled wasm modules.
+//!
+//! Compiled modules are kept in memory by the caches of
+//! `holochain_wasmer_host`, keyed by the `WasmHash` of their wasm, so every
+//! ribosome running the same wasm shares one compilation. When the conductor
+//! config sets a `wasm_cache_path` the serialized modules are also written to
+//! that directory, so that a restarted conductor can skip compiling them again.
+//!
+//! Modules on disk are kept in a subdirectory named by a hash of the engine
+//! they were compiled with, and each is stored with the hash of its bytes,
+//! which is checked before it is deserialized. Deserializing a module is only
+//! safe for bytes written by this cache, so the directory must not be writable
+//! by anyone who is not trusted to run code in the conductor.
+
+use super::error::RibosomeResult;
+use holo_hash::encode::blake2b_256;
+use holochain_conductor_api::WasmCacheStats;
+use holochain_wasmer_host::module::CacheKey;
+use holochain_wasmer_host::module::PlruCache;
+use holochain_wasmer_host::module::SerializedModuleCache;
+use holochain_wasmer_host::module::INSTANCE_CACHE;
+use holochain_wasmer_host::module::MODULE_CACHE;
+use holochain_wasmer_host::module::SERIALIZED_MODULE_CACHE;
+use holochain_wasmer_host::prelude::*;
+use kitsune_p2p_types::dependencies::lair_keystore_api::dependencies::parking_lot::lock_api::RwLock;
+use once_cell::sync::OnceCell;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// The directory serialized modules are written to, if any.
+static DISK_CACHE: OnceCell<DiskCache> = OnceCell::new();
+
+/// The length of the hash written in front of each serialized module.
+const CHECKSUM_LEN: usize = 32;
+
+#[derive(Debug, PartialEq)]
+struct DiskCache {
+    /// The directory from the conductor config.
+    root: PathBuf,
+    /// The subdirectory for modules compiled by this build's engine.
+    engine_dir: PathBuf,
+}
+
+static MEMORY_HITS: AtomicU64 = AtomicU64::new(0);
+static DISK_HITS: AtomicU64 = AtomicU64::new(0);
+static COMPILATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Keep serialized modules in this directory as well as in memory.
+/// The caches are shared by every conductor in the process, so only the
+/// first path set is used.
+pub fn set_disk_cache_path(path: PathBuf) -> std::io::Result<()> {
+    let cache = DiskCache {
+        engine_dir: path.join(engine_fingerprint()),
+        root: path,
+    };
+    std::fs::create_dir_all(&cache.engine_dir)?;
+    if let Err(cache) = DISK_CACHE.set(cache) {
+        if DISK_CACHE.get() != Some(&cache) {
+            tracing::warn!(
+                path = ?cache.root,
+                "A wasm disk cache is already in use by this process, ignoring this one"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A hash of everything besides the wasm which the compiled code depends on,
+/// so modules compiled by another wasmer version, compiler configuration or
+/// platform are never loaded.
+fn engine_fingerprint() -> String {
+    let engine = format!(
+        "wasmer:{};{};{}-{}",
+        holochain_wasmer_host::prelude::VERSION,
+        super::real_ribosome::RealRibosome::ENGINE_CONFIG,
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+    );
+    hex(&blake2b_256(engine.as_bytes()))
+}
+
+/// Get the compiled module for this wasm, compiling it only if it is neither
+/// in memory nor in the disk cache.
+pub fn module(
+    key: CacheKey,
+    wasm: &[u8],
+    cranelift: fn() -> Cranelift,
+) -> RibosomeResult<Arc<Module>> {
+    if let Some(module) = MODULE_CACHE.write().get_item(&key) {
+        MEMORY_HITS.fetch_add(1, Ordering::Relaxed);
+        return Ok(module);
+    }
+    let serialized_cache = SERIALIZED_MODULE_CACHE
+        .get_or_init(|| RwLock::new(SerializedModuleCache::default_with_cranelift(cranelift)));
+    let disk_path = DISK_CACHE
+        .get()
+        .map(|cache| cache.engine_dir.join(hex(&key)));
+
+    if serialized_cache.read().cache().contains_key(&key) {
+        MEMORY_HITS.fetch_add(1, Ordering::Relaxed);
+    } else if let Some(serialized) = disk_path.as_deref().and_then(read_serialized) {
+        // The checksum has been verified, but still compile over the file
+        // if it doesn't deserialize.
+        let store = Store::new(&Universal::new(cranelift()).engine());
+        match unsafe { Module::deserialize(&store, &serialized) } {
+            Ok(module) => {
+                DISK_HITS.fetch_add(1, Ordering::Relaxed);
+                serialized_cache.write().put_item(key, Arc::new(serialized));
+                return Ok(MODULE_CACHE.write().put_item(key, Arc::new(module)));
+            }
+            Err(e) => {
+                tracing::warn!(?disk_path, ?e, "Ignoring unreadable cached wasm module");
+                COMPILATIONS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    } else {
+        COMPILATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let module = MODULE_CACHE.write().get(key, wasm)?;
+    if let Some(disk_path) = disk_path {
+        if !disk_path.exists() {
+            if let Some(serialized) = serialized_cache.read().cache().get(&key) {
+                write_serialized(&disk_path, serialized);
+            }
+        }
+    }
+    Ok(module)
+}
+
+/// How much the caches hold and how often they were used since the
+/// process started.
+pub fn stats() -> WasmCacheStats {
+    WasmCacheStats {
+        modules_in_memory: MODULE_CACHE.read().cache().len(),
+        serialized_modules_in_memory: SERIALIZED_MODULE_CACHE
+            .get()
+            .map(|cache| cache.read().cache().len())
+            .unwrap_or_default(),
+        instances_in_memory: INSTANCE_CACHE.read().cache().len(),
+        modules_on_disk: DISK_CACHE.get().map(|cache| {
+            std::fs::read_dir(&cache.engine_dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.path().extension().is_none())
+                        .count()
+                })
+                .unwrap_or_default()
+        }),
+        memory_hits: MEMORY_HITS.load(Ordering::Relaxed),
+        disk_hits: DISK_HITS.load(Ordering::Relaxed),
+        compilations: COMPILATIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// Drop every cached module and idle instance, and empty the disk cache.
+/// Instances which are running a call are not affected.
+pub fn purge() -> std::io::Result<()> {
+    fn clear<C: PlruCache>(cache: &mut C) {
+        let keys: Vec<CacheKey> = cache.cache().keys().cloned().collect();
+        for key in keys {
+            cache.remove_item(&key);
+        }
+    }
+    clear(&mut *INSTANCE_CACHE.write());
+    clear(&mut *MODULE_CACHE.write());
+    if let Some(cache) = SERIALIZED_MODULE_CACHE.get() {
+        clear(&mut *cache.write());
+    }
+    if let Some(cache) = DISK_CACHE.get() {
+        // Also remove the modules of other engines left by earlier versions.
+        for entry in std::fs::read_dir(&cache.root)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                std::fs::remove_dir_all(path)?;
+            } else {
+                std::fs::remove_file(path)?;
+            }
+        }
+        std::fs::create_dir_all(&cache.engine_dir)?;
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Read a serialized module, if it is there and its checksum matches.
+fn read_serialized(path: &Path) -> Option<Vec<u8>> {
+    match std::fs::read(path) {
+        Ok(mut bytes) => {
+            if bytes.len() < CHECKSUM_LEN
+                || blake2b_256(&bytes[CHECKSUM_LEN..]) != bytes[..CHECKSUM_LEN]
+            {
+                tracing::warn!(?path, "Ignoring cached wasm module with a bad checksum");
+                return None;
+            }
+            bytes.drain(..CHECKSUM_LEN);
+            Some(bytes)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            tracing::warn!(?path, ?e, "Failed to read cached wasm module");
+            None
+        }
+    }
+}
+
+fn write_serialized(path: &Path, serialized: &[u8]) {
+    // Write to a temporary file first so a crash never leaves half a module
+    // behind for the next run to load.
+    let tmp_path = path.with_extension("tmp");
+    let mut bytes = blake2b_256(serialized);
+    bytes.extend_from_slice(serialized);
+    let result = std::fs::write(&tmp_path, bytes).and_then(|_| std::fs::rename(&tmp_path, path));
+    if let Err(e) = result {
+        tracing::warn!(?path, ?e, "Failed to write wasm module to the disk cache");
+    }
+}
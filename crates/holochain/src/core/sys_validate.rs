@@ -249,6 +249,19 @@ pub fn check_prev_seq(action: &Action, prev_action: &Action) -> SysValidationRes
     }
 }
 
+/// Check the previous action was written by the same author
+pub fn check_prev_author(action: &Action, prev_action: &Action) -> SysValidationResult<()> {
+    if action.author() == prev_action.author() {
+        Ok(())
+    } else {
+        Err(PrevActionError::Author(
+            action.author().clone(),
+            prev_action.author().clone(),
+        ))
+        .map_err(|e| ValidationOutcome::from(e).into())
+    }
+}
+
 /// Check the entry variant matches the variant in the actions entry type
 pub fn check_entry_type(entry_type: &EntryType, entry: &Entry) -> SysValidationResult<()> {
     match (entry_type, entry) {
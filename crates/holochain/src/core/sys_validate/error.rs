@@ -154,6 +154,8 @@ impl ValidationOutcome {
 
 #[derive(Error, Debug)]
 pub enum PrevActionError {
+    #[error("The previous action was authored by {1} instead of {0}")]
+    Author(AgentPubKey, AgentPubKey),
     #[error("The previous action in the source chain doesn't match the next action")]
     HashMismatch,
     #[error("Root of source chain must be Dna")]
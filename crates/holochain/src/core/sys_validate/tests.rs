@@ -192,6 +192,26 @@ async fn check_previous_seq() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn check_previous_author() {
+    let action = fixt!(CreateLink);
+    let mut prev_action = fixt!(CreateLink);
+
+    prev_action.author = action.author.clone();
+    assert_matches!(
+        check_prev_author(&action.clone().into(), &prev_action.clone().into()),
+        Ok(())
+    );
+
+    prev_action.author = fixt!(AgentPubKey);
+    assert_matches!(
+        check_prev_author(&action.clone().into(), &prev_action.clone().into()),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevActionError(PrevActionError::Author(_, _)),
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_entry_type_test() {
     let entry_fixt = EntryFixturator::new(Predictable);
@@ -30,9 +30,11 @@ pub mod genesis_workflow;
 pub mod incoming_dht_ops_workflow;
 pub mod initialize_zomes_workflow;
 pub mod integrate_dht_ops_workflow;
+pub mod migrate_agent_workflow;
 pub mod publish_dht_ops_workflow;
 pub mod sys_validation_workflow;
 pub mod validation_receipt_workflow;
+pub mod warrant_workflow;
 
 // MAYBE: either remove wildcards or add wildcards for all above child modules
 pub use call_zome_workflow::*;
@@ -120,10 +120,29 @@ async fn app_validation_workflow_inner(
 
                 // Validate this op
                 let mut cascade = workspace.full_cascade(network.clone());
+                let action = ActionHashed::from_content_sync(action);
                 let r = match dhtop_to_op(op, &mut cascade).await {
                     Ok(op) => {
-                        validate_op_outer(dna_hash, &op, &conductor_handle, &(*workspace), &network)
-                            .await
+                        match validation_package::hold_validation_package(
+                            &action,
+                            &dna_hash,
+                            conductor_handle.as_ref(),
+                            &mut cascade,
+                        )
+                        .await
+                        {
+                            Ok(()) => {
+                                validate_op_outer(
+                                    dna_hash,
+                                    &op,
+                                    &conductor_handle,
+                                    &(*workspace),
+                                    &network,
+                                )
+                                .await
+                            }
+                            Err(e) => Err(e),
+                        }
                     }
                     Err(e) => Err(e),
                 };
@@ -206,9 +225,10 @@ async fn app_validation_workflow_inner(
                             let status = ValidationLimboStatus::AwaitingAppDeps(deps);
                             put_validation_limbo(txn, &op_hash, status)?;
                         }
-                        Outcome::Rejected(_) => {
+                        Outcome::Rejected(rejection) => {
                             rejected += 1;
-                            tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op_light);
+                            tracing::warn!("Received invalid op! Its author will be warranted once it is integrated.\nOp: {:?}", op_light);
+                            insert_validation_rejection(txn, &op_hash, rejection)?;
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
@@ -538,7 +558,7 @@ where
     )?;
     match validate_result {
         ValidateResult::Valid => Ok(Outcome::Accepted),
-        ValidateResult::Invalid(reason) => Ok(Outcome::Rejected(reason)),
+        ValidateResult::Invalid(rejection) => Ok(Outcome::Rejected(rejection)),
         ValidateResult::UnresolvedDependencies(hashes) => {
             // This is the base case where we've been recursing and start seeing
             // all the same hashes unresolved that we already tried to fetch.
@@ -9,11 +9,13 @@ use crate::test_utils::wait_for_integration;
 use holo_hash::ActionHash;
 use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
+use holochain_conductor_api::JsonDump;
 use holochain_state::prelude::fresh_reader_test;
 use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateQueryResult;
 use holochain_types::prelude::*;
 use holochain_wasm_test_utils::TestWasm;
+use holochain_wasm_test_utils::TestWasmPair;
 
 use holochain_zome_types::Entry;
 use holochain_zome_types::ValidationStatus;
@@ -95,6 +97,28 @@ fn expected_invalid_entry(
     count == 1
 }
 
+// The rejection of the invalid entry records the zome that rejected it
+fn invalid_entry_rejection(
+    txn: &Transaction,
+    invalid_action_hash: &ActionHash,
+) -> ValidationRejection {
+    let blob: Vec<u8> = txn
+        .query_row(
+            "
+            SELECT ValidationRejection.blob FROM ValidationRejection
+            JOIN DhtOp ON DhtOp.hash = ValidationRejection.op_hash
+            WHERE DhtOp.type = :store_entry AND DhtOp.action_hash = :invalid_action_hash
+            ",
+            named_params! {
+                ":invalid_action_hash": invalid_action_hash,
+                ":store_entry": DhtOpType::StoreEntry,
+            },
+            |row| row.get(0),
+        )
+        .unwrap();
+    from_blob(blob).unwrap()
+}
+
 // Now we expect an invalid link
 fn expected_invalid_link(txn: &Transaction, invalid_link_hash: &ActionHash) -> bool {
     let sql = format!(
@@ -232,7 +256,7 @@ async fn run_test(
     let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
     wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
 
-    fresh_reader_test(alice_db, |txn| {
+    let rejection = fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
         let limbo = show_limbo(&txn);
         assert!(limbo_is_empty(&txn), "{:?}", limbo);
@@ -242,10 +266,22 @@ async fn run_test(
             &invalid_action_hash,
             &invalid_entry_hash
         ));
+        let rejection = invalid_entry_rejection(&txn, &invalid_action_hash);
+        assert_eq!(
+            rejection.zome_name,
+            Some(TestWasmPair::<ZomeName>::from(TestWasm::Validate).integrity)
+        );
+        assert_eq!(rejection.callback.as_deref(), Some("validate"));
         // Expect having one invalid op for the store entry.
         assert_eq!(num_valid(&txn), expected_count - 1);
+        rejection
     });
 
+    // The rejection is also in the cell state dump
+    let dump = conductors[0].dump_cell_state(&alice_cell_id).await.unwrap();
+    let (dump, _summary): (JsonDump, String) = serde_json::from_str(&dump).unwrap();
+    assert!(dump.rejections.iter().any(|(_, r)| *r == rejection));
+
     let invocation =
         new_zome_call(&bob_cell_id, "add_valid_link", (), TestWasm::ValidateLink).unwrap();
     conductors[1].call_zome(invocation).await.unwrap().unwrap();
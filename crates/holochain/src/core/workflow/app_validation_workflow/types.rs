@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
 
 use holo_hash::AnyDhtHash;
+use holochain_types::prelude::ValidationRejection;
 
 use crate::core::validation::OutcomeOrError;
 
@@ -16,7 +17,7 @@ pub enum Outcome {
     /// and could not be found
     AwaitingDeps(Vec<AnyDhtHash>),
     /// Moves to integration with status rejected
-    Rejected(String),
+    Rejected(ValidationRejection),
 }
 
 impl Outcome {
@@ -27,7 +28,7 @@ impl Outcome {
     }
     /// Helper function for creating rejected outcomes
     pub fn rejected<E, I: Into<String>>(s: I) -> OutcomeOrError<Self, E> {
-        OutcomeOrError::Outcome(Outcome::Rejected(s.into()))
+        OutcomeOrError::Outcome(Outcome::Rejected(ValidationRejection::new(s)))
     }
     /// Exit early with an awaiting outcome
     pub fn exit_with_awaiting<T, I: Into<AnyDhtHash>, It: IntoIterator<Item = I>>(
@@ -43,7 +44,9 @@ impl Outcome {
     }
     /// Exit early with a rejected outcome
     pub fn exit_with_rejected<T, I: Into<String>>(reason: I) -> AppValidationOutcome<T> {
-        Err(OutcomeOrError::Outcome(Outcome::Rejected(reason.into())))
+        Err(OutcomeOrError::Outcome(Outcome::Rejected(
+            ValidationRejection::new(reason),
+        )))
     }
 }
 
@@ -1,14 +1,22 @@
+use holochain_cascade::Cascade;
 use holochain_p2p::HolochainP2pDna;
 use holochain_state::host_fn_workspace::HostFnWorkspaceRead;
 use holochain_state::prelude::SourceChainRead;
 use holochain_types::prelude::*;
 use holochain_zome_types::ActionHashed;
 
+use super::AppValidationOutcome;
+use super::Outcome;
+use crate::conductor::handle::ConductorHandleT;
 use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsHostAccess;
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageHostAccess;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
 use crate::core::ribosome::RibosomeT;
+use crate::core::sys_validate::check_app_entry_type;
 use crate::core::SourceChainResult;
 use tracing::*;
 
@@ -77,3 +85,92 @@ pub fn get_as_author_custom(
 
     Ok(Some(ribosome.run_validation_package(access, invocation)?))
 }
+
+/// The validation package required by the entry type of this action.
+/// Actions without an app entry type only need their record.
+pub async fn required_validation_type(
+    action: &Action,
+    dna_hash: &DnaHash,
+    conductor_handle: &dyn ConductorHandleT,
+) -> RequiredValidationType {
+    match action.entry_type() {
+        Some(EntryType::App(app_entry_type)) => {
+            match check_app_entry_type(dna_hash, app_entry_type, conductor_handle).await {
+                Ok(entry_def) => entry_def.required_validation_type,
+                Err(e) => {
+                    warn!(
+                        msg = "Couldn't find the entry def of an action",
+                        ?e,
+                        ?action
+                    );
+                    RequiredValidationType::default()
+                }
+            }
+        }
+        _ => RequiredValidationType::default(),
+    }
+}
+
+/// The validation package required by the entry type of this action,
+/// found by running the entry defs callback of the ribosome directly.
+/// For callers which have a ribosome but no conductor handle.
+pub fn required_validation_type_from_ribosome(
+    action: &Action,
+    ribosome: &impl RibosomeT,
+) -> RequiredValidationType {
+    let app_entry_type = match action.entry_type() {
+        Some(EntryType::App(app_entry_type)) => app_entry_type,
+        _ => return RequiredValidationType::default(),
+    };
+    let zome = match ribosome.get_integrity_zome(&app_entry_type.zome_id()) {
+        Some(zome) => zome,
+        None => return RequiredValidationType::default(),
+    };
+    match ribosome.run_entry_defs(EntryDefsHostAccess, EntryDefsInvocation) {
+        Ok(EntryDefsResult::Defs(defs)) => defs
+            .get(zome.zome_name())
+            .and_then(|entry_defs| entry_defs.0.get(app_entry_type.id().0 as usize))
+            .map(|entry_def| entry_def.required_validation_type)
+            .unwrap_or_default(),
+        Ok(EntryDefsResult::Err(_, e)) => {
+            warn!(
+                msg = "Couldn't find the entry def of an action",
+                ?e,
+                ?action
+            );
+            RequiredValidationType::default()
+        }
+        Err(e) => {
+            warn!(
+                msg = "Couldn't find the entry def of an action",
+                ?e,
+                ?action
+            );
+            RequiredValidationType::default()
+        }
+    }
+}
+
+/// Make sure the validation package required by this action is held,
+/// fetching it from the author if it isn't, so the validation callbacks
+/// can get it with `must_get_validation_package`.
+/// Awaits the action as a dependency if the author can't provide the package.
+pub async fn hold_validation_package(
+    action: &ActionHashed,
+    dna_hash: &DnaHash,
+    conductor_handle: &dyn ConductorHandleT,
+    cascade: &mut Cascade,
+) -> AppValidationOutcome<()> {
+    let required = required_validation_type(action.as_content(), dna_hash, conductor_handle).await;
+    if required == RequiredValidationType::Record {
+        return Ok(());
+    }
+    let author = action.author().clone();
+    match cascade
+        .get_validation_package(author, action, required)
+        .await?
+    {
+        Some(_) => Ok(()),
+        None => Outcome::exit_with_awaiting([action.as_hash().clone()]),
+    }
+}
@@ -6,10 +6,12 @@ use super::sys_validation_workflow::sys_validate_record;
 use crate::conductor::api::CellConductorApi;
 use crate::conductor::api::CellConductorApiT;
 use crate::conductor::interface::SignalBroadcaster;
+use crate::conductor::CellQuota;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::post_commit::send_post_commit;
+use crate::core::ribosome::GuestTime;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
@@ -22,6 +24,7 @@ use holochain_state::source_chain::SourceChainError;
 use holochain_zome_types::record::Record;
 
 use holochain_types::prelude::*;
+use std::sync::Arc;
 use tracing::instrument;
 
 #[cfg(test)]
@@ -37,6 +40,7 @@ pub struct CallZomeWorkflowArgs<RibosomeT> {
     pub conductor_handle: ConductorHandle,
     pub is_root_zome_call: bool,
     pub cell_id: CellId,
+    pub quota: Arc<CellQuota>,
 }
 
 #[instrument(skip(
@@ -60,24 +64,45 @@ where
 {
     let coordinator_zome = args
         .ribosome
-        .dna_def()
+        .dna_file()
         .get_coordinator_zome(args.invocation.zome.zome_name())
         .ok();
     let should_write = args.is_root_zome_call;
     let conductor_handle = args.conductor_handle.clone();
+    let quota = args.quota.clone();
+    // Nested calls share the workspace of the root call, so the writes of a
+    // failed call have to be reverted even if the caller carries on.
+    let checkpoint = workspace.source_chain().checkpoint()?;
     let result =
-        call_zome_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args)
-            .await?;
+        call_zome_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args).await;
+    let succeeded = matches!(result, Ok(Ok(_)));
+    if !succeeded {
+        workspace.source_chain().revert_to(&checkpoint)?;
+    }
+    let result = result?;
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    if should_write {
+    if should_write && succeeded {
         let is_empty = workspace.source_chain().is_empty()?;
         let countersigning_op = workspace.source_chain().countersigning_op()?;
-        let flushed_actions: Vec<SignedActionHashed> = HostFnWorkspace::from(workspace.clone())
-            .flush(&network)
+        // Check the cell's limits against what this call has used and is
+        // about to write, so one call can't take the cell over them.
+        let reserved = quota
+            .reserve_flush(&workspace.source_chain().scratch_records()?)
             .await?;
+        let flushed_actions: Vec<SignedActionHashed> =
+            match HostFnWorkspace::from(workspace.clone())
+                .flush(&network)
+                .await
+            {
+                Ok(flushed_actions) => flushed_actions,
+                Err(e) => {
+                    quota.release_storage(reserved);
+                    return Err(e.into());
+                }
+            };
         if !is_empty {
             match countersigning_op {
                 Some(op) => {
@@ -126,6 +151,7 @@ where
         signal_tx,
         conductor_handle,
         cell_id,
+        quota,
         ..
     } = args;
 
@@ -133,15 +159,20 @@ where
         CellConductorApi::new(conductor_handle.clone(), cell_id).into_call_zome_handle();
 
     tracing::trace!("Before zome call");
+    let guest_time = GuestTime::default();
     let host_access = ZomeCallHostAccess::new(
         workspace.clone().into(),
         keystore,
         network.clone(),
         signal_tx,
         call_zome_handle,
+        guest_time.clone(),
     );
-    let (ribosome, result) =
-        call_zome_function_authorized(ribosome, host_access, invocation).await?;
+    let result = call_zome_function_authorized(ribosome, host_access, invocation).await;
+    // Calls into other zomes of this cell run while the caller is in a host
+    // function, so each call only counts its own time.
+    quota.record_wasm_time(guest_time.get());
+    let (ribosome, result) = result?;
     tracing::trace!("After zome call");
 
     let validation_result =
@@ -265,8 +296,8 @@ fn map_outcome(
 ) -> WorkflowResult<()> {
     match outcome.map_err(SourceChainError::other)? {
         app_validation_workflow::Outcome::Accepted => {}
-        app_validation_workflow::Outcome::Rejected(reason) => {
-            return Err(SourceChainError::InvalidCommit(reason).into());
+        app_validation_workflow::Outcome::Rejected(rejection) => {
+            return Err(SourceChainError::InvalidCommit(rejection.reason).into());
         }
         // when the wasm is being called directly in a zome invocation any
         // state other than valid is not allowed for new entries
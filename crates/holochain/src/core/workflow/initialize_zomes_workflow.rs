@@ -139,7 +139,7 @@ pub mod tests {
     use crate::sweettest::*;
     use crate::test_utils::fake_genesis;
     use ::fixt::prelude::*;
-    use fixt::Unpredictable;
+    use ::fixt::Unpredictable;
     use holochain_p2p::HolochainP2pDnaFixturator;
     use holochain_state::prelude::test_authored_db;
     use holochain_state::prelude::test_cache_db;
@@ -0,0 +1,188 @@
+//! Migrate Agent Workflow: Move an agent from the source chain of one DNA
+//! to the source chain of a new version of that DNA.
+//!
+//! - The `migrate_agent` `close` callbacks of the old DNA and the `open`
+//!   callbacks of the new DNA are run. If any of them fail nothing is written.
+//! - A `CloseChain` action pointing at the new DNA is committed to the old chain.
+//! - An `OpenChain` action pointing at the old DNA is committed to the new chain,
+//!   which must already have run genesis.
+//! - Optionally, the capability grants and claims which are still live on the
+//!   old chain are carried forward onto the new chain.
+//! - Both chains are validated before either is written. The new chain is
+//!   flushed first, so if anything fails the old chain is left open and the
+//!   app can keep using it.
+
+use super::error::WorkflowResult;
+use crate::conductor::ConductorHandle;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::weigh_placeholder;
+use crate::core::ribosome::RibosomeT;
+use derive_more::Constructor;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_state::source_chain::SourceChain;
+use holochain_types::prelude::*;
+use holochain_zome_types::action::builder;
+use std::collections::HashSet;
+use tracing::*;
+
+/// One side of a migration: the chain of the agent for one DNA.
+#[derive(Constructor)]
+pub struct MigrateAgentChain<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    pub workspace: SourceChainWorkspace,
+    pub network: HolochainP2pDna,
+    pub ribosome: Ribosome,
+}
+
+#[derive(Constructor)]
+pub struct MigrateAgentWorkflowArgs<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    /// The chain being closed
+    pub old: MigrateAgentChain<Ribosome>,
+    /// The chain being opened
+    pub new: MigrateAgentChain<Ribosome>,
+    /// Whether to copy live capability grants and claims to the new chain
+    pub carry_forward: bool,
+    pub conductor_handle: ConductorHandle,
+}
+
+#[instrument(skip(args))]
+pub async fn migrate_agent_workflow<Ribosome>(
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + Clone + 'static,
+{
+    let MigrateAgentWorkflowArgs {
+        old,
+        new,
+        carry_forward,
+        conductor_handle,
+    } = args;
+    let old_dna_def = old.ribosome.dna_def().clone();
+    let new_dna_def = new.ribosome.dna_def().clone();
+
+    // Both sides must agree to the migration before anything is written.
+    let result = old.ribosome.run_migrate_agent(
+        MigrateAgentHostAccess::new(old.workspace.clone().into()),
+        MigrateAgentInvocation::new(new_dna_def.as_content().clone(), MigrateAgent::Close),
+    )?;
+    if result != MigrateAgentResult::Pass {
+        return Ok(result);
+    }
+    let result = new.ribosome.run_migrate_agent(
+        MigrateAgentHostAccess::new(new.workspace.clone().into()),
+        MigrateAgentInvocation::new(old_dna_def.as_content().clone(), MigrateAgent::Open),
+    )?;
+    if result != MigrateAgentResult::Pass {
+        return Ok(result);
+    }
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    old.workspace
+        .source_chain()
+        .put(
+            builder::CloseChain {
+                new_dna_hash: new_dna_def.as_hash().clone(),
+            },
+            None,
+            ChainTopOrdering::Strict,
+        )
+        .await?;
+    new.workspace
+        .source_chain()
+        .put(
+            builder::OpenChain {
+                prev_dna_hash: old_dna_def.as_hash().clone(),
+            },
+            None,
+            ChainTopOrdering::Strict,
+        )
+        .await?;
+    if carry_forward {
+        for entry in live_cap_entries(old.workspace.source_chain()).await? {
+            let entry_type = match &entry {
+                Entry::CapGrant(_) => EntryType::CapGrant,
+                _ => EntryType::CapClaim,
+            };
+            new.workspace
+                .source_chain()
+                .put_weighed(
+                    builder::Create {
+                        entry_type,
+                        entry_hash: EntryHash::with_data_sync(&entry),
+                    },
+                    Some(entry),
+                    ChainTopOrdering::Strict,
+                    weigh_placeholder(),
+                )
+                .await?;
+        }
+    }
+
+    // Validate both sides before anything is written.
+    super::inline_validation(
+        old.workspace.clone(),
+        old.network.clone(),
+        conductor_handle.clone(),
+        old.ribosome,
+    )
+    .await?;
+    super::inline_validation(
+        new.workspace.clone(),
+        new.network.clone(),
+        conductor_handle,
+        new.ribosome,
+    )
+    .await?;
+
+    // Open the new chain before closing the old one, so a failure
+    // never leaves the app pointing at a closed chain.
+    HostFnWorkspace::from(new.workspace.clone())
+        .flush(&new.network)
+        .await?;
+    HostFnWorkspace::from(old.workspace.clone())
+        .flush(&old.network)
+        .await?;
+
+    Ok(MigrateAgentResult::Pass)
+}
+
+/// The capability grant and claim entries of a chain which have not been deleted.
+async fn live_cap_entries(source_chain: &SourceChain) -> WorkflowResult<Vec<Entry>> {
+    let deleted: HashSet<ActionHash> = source_chain
+        .query(ChainQueryFilter::new().action_type(ActionType::Delete))
+        .await?
+        .into_iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+    let mut entries = Vec::new();
+    for entry_type in [EntryType::CapGrant, EntryType::CapClaim] {
+        let records = source_chain
+            .query(
+                ChainQueryFilter::new()
+                    .entry_type(entry_type)
+                    .include_entries(true),
+            )
+            .await?;
+        entries.extend(
+            records
+                .into_iter()
+                .filter(|record| !deleted.contains(record.action_address()))
+                .filter_map(|record| record.into_inner().1.into_option()),
+        );
+    }
+    Ok(entries)
+}
@@ -14,6 +14,7 @@ use super::error::WorkflowResult;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
 use holo_hash::*;
+use holochain_p2p::kitsune_p2p::actor::NetworkStatus;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_state::prelude::*;
 use holochain_types::prelude::*;
@@ -39,6 +40,15 @@ pub async fn publish_dht_ops_workflow(
     agent: AgentPubKey,
 ) -> WorkflowResult<WorkComplete> {
     let mut complete = WorkComplete::Complete;
+
+    // While offline the ops stay queued, and the workflow is
+    // triggered again when the network is rejoined.
+    if let Ok(NetworkStatus::Offline) = network.network_status().await {
+        tracing::info!("not publishing while the network is offline");
+        trigger_self.pause_loop();
+        return Ok(complete);
+    }
+
     let to_publish = publish_dht_ops_workflow_inner(db.clone().into(), agent).await?;
 
     // Commit to the network
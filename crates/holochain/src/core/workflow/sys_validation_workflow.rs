@@ -1,7 +1,7 @@
 //! The workflow and queue consumer for sys validation
 #![allow(deprecated)]
 
-use super::*;
+use super::error::WorkflowResult;
 use crate::conductor::handle::ConductorHandleT;
 use crate::conductor::space::Space;
 use crate::conductor::ConductorHandle;
@@ -10,7 +10,6 @@ use crate::core::queue_consumer::WorkComplete;
 use crate::core::sys_validate::check_and_hold_store_record;
 use crate::core::sys_validate::*;
 use crate::core::validation::*;
-use error::WorkflowResult;
 use holo_hash::DhtOpHash;
 use holochain_cascade::Cascade;
 use holochain_p2p::HolochainP2pDna;
@@ -154,13 +153,14 @@ async fn sys_validation_workflow_inner(
     while let Some(chunk) = iter.next().await {
         let num_ops: usize = chunk.iter().map(|c| c.len()).sum();
         tracing::debug!("Committing {} ops", num_ops);
-        let (t, a, m, r) = space
+        let (t, a, m, r, c) = space
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut missing = 0;
                 let mut rejected = 0;
+                let mut counterfeit = 0;
                 for outcome in chunk.into_iter().flatten() {
                     let (op_hash, outcome, dependency) = outcome?;
                     match outcome {
@@ -186,22 +186,29 @@ async fn sys_validation_workflow_inner(
                             let status = ValidationLimboStatus::AwaitingSysDeps(missing_dep);
                             put_validation_limbo(txn, &op_hash, status)?;
                         }
-                        Outcome::MissingDhtDep => {
+                        Outcome::MissingDhtDep(missing_dep) => {
                             missing += 1;
-                            // TODO: Not sure what missing dht dep is. Check if we need this.
-                            put_validation_limbo(txn, &op_hash, ValidationLimboStatus::Pending)?;
+                            // The cascade couldn't fetch this dependency so park the op
+                            // until it arrives or it's time to try fetching it again.
+                            let status = ValidationLimboStatus::AwaitingSysDeps(missing_dep);
+                            put_validation_limbo(txn, &op_hash, status)?;
                         }
-                        Outcome::Rejected => {
+                        Outcome::Rejected(rejection) => {
                             rejected += 1;
+                            insert_validation_rejection(txn, &op_hash, rejection)?;
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
                                 put_integration_limbo(txn, &op_hash, ValidationStatus::Rejected)?;
                             }
                         }
+                        Outcome::Counterfeit => {
+                            counterfeit += 1;
+                            delete_dht_op(txn, &op_hash)?;
+                        }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, missing, rejected))
+                WorkflowResult::Ok((total, awaiting, missing, rejected, counterfeit))
             })
             .await?;
 
@@ -218,7 +225,7 @@ async fn sys_validation_workflow_inner(
                 ops_ps
             );
         }
-        tracing::debug!("{} committed, {} awaiting sys dep, {} missing dht dep, {} rejected. {} committed this round", t, a, m, r, total);
+        tracing::debug!("{} committed, {} awaiting sys dep, {} missing dht dep, {} rejected, {} counterfeit dropped. {} committed this round", t, a, m, r, c, total);
     }
     jh.await?;
     tracing::debug!("Accepted {} ops", total);
@@ -256,12 +263,19 @@ async fn validate_op(
                 error_msg = %e
             );
             let outcome = handle_failed(e);
-            if let Outcome::Rejected = outcome {
-                warn!(
+            match &outcome {
+                Outcome::Rejected(rejection) => warn!(
                     dna = %workspace.dna_hash(),
                     msg = "DhtOp was rejected during system validation.",
                     ?op,
-                )
+                    %rejection,
+                ),
+                Outcome::Counterfeit => warn!(
+                    dna = %workspace.dna_hash(),
+                    msg = "Counterfeit DhtOp was dropped during system validation.",
+                    ?op,
+                ),
+                _ => (),
             }
             Ok(outcome)
         }
@@ -269,38 +283,39 @@ async fn validate_op(
     }
 }
 
-/// For now errors result in an outcome but in the future
-/// we might find it useful to include the reason something
-/// was rejected etc.
-/// This is why the errors contain data but is currently unread.
+/// Map a failed validation to its outcome.
+/// Rejections record which rule failed and why so the
+/// reason can be stored alongside the op.
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
-    match error {
-        ValidationOutcome::Counterfeit(_, _) => {
-            unreachable!("Counterfeit ops are dropped before sys validation")
-        }
-        ValidationOutcome::ActionNotInCounterSigningSession(_, _) => Rejected,
-        ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
-        ValidationOutcome::EntryDefId(_) => Rejected,
-        ValidationOutcome::EntryHash => Rejected,
-        ValidationOutcome::EntryTooLarge(_, _) => Rejected,
-        ValidationOutcome::EntryType => Rejected,
-        ValidationOutcome::EntryVisibility(_) => Rejected,
-        ValidationOutcome::TagTooLarge(_, _) => Rejected,
-        ValidationOutcome::NotCreateLink(_) => Rejected,
-        ValidationOutcome::NotNewEntry(_) => Rejected,
-        ValidationOutcome::NotHoldingDep(dep) => AwaitingOpDep(dep),
+    let rule = match &error {
+        // Anyone could have made this op so it is no evidence against the author.
+        ValidationOutcome::Counterfeit(_, _) => return Counterfeit,
+        ValidationOutcome::DepMissingFromDht(dep) => return MissingDhtDep(dep.clone()),
+        ValidationOutcome::NotHoldingDep(dep) => return AwaitingOpDep(dep.clone()),
         ValidationOutcome::PrevActionError(PrevActionError::MissingMeta(dep)) => {
-            AwaitingOpDep(dep.into())
+            return AwaitingOpDep(dep.clone().into())
         }
-        ValidationOutcome::PrevActionError(_) => Rejected,
-        ValidationOutcome::PrivateEntry => Rejected,
-        ValidationOutcome::PreflightResponseSignature(_) => Rejected,
-        ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
-        ValidationOutcome::VerifySignature(_, _) => Rejected,
-        ValidationOutcome::ZomeId(_) => Rejected,
-        ValidationOutcome::CounterSigningError(_) => Rejected,
-    }
+        ValidationOutcome::ActionNotInCounterSigningSession(_, _) => {
+            "ActionNotInCounterSigningSession"
+        }
+        ValidationOutcome::EntryDefId(_) => "EntryDefId",
+        ValidationOutcome::EntryHash => "EntryHash",
+        ValidationOutcome::EntryTooLarge(_, _) => "EntryTooLarge",
+        ValidationOutcome::EntryType => "EntryType",
+        ValidationOutcome::EntryVisibility(_) => "EntryVisibility",
+        ValidationOutcome::TagTooLarge(_, _) => "TagTooLarge",
+        ValidationOutcome::NotCreateLink(_) => "NotCreateLink",
+        ValidationOutcome::NotNewEntry(_) => "NotNewEntry",
+        ValidationOutcome::PrevActionError(_) => "PrevActionError",
+        ValidationOutcome::PrivateEntry => "PrivateEntry",
+        ValidationOutcome::PreflightResponseSignature(_) => "PreflightResponseSignature",
+        ValidationOutcome::UpdateTypeMismatch(_, _) => "UpdateTypeMismatch",
+        ValidationOutcome::VerifySignature(_, _) => "VerifySignature",
+        ValidationOutcome::ZomeId(_) => "ZomeId",
+        ValidationOutcome::CounterSigningError(_) => "CounterSigningError",
+    };
+    Rejected(ValidationRejection::sys(rule, error.to_string()))
 }
 
 async fn validate_op_inner(
@@ -310,6 +325,13 @@ async fn validate_op_inner(
     conductor_handle: &dyn ConductorHandleT,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
+    // Ops are checked for counterfeits when they arrive but they may have
+    // sat in the database since, so check again before trusting their contents.
+    counterfeit_check(op.signature(), &op.action()).await?;
+    // Every op must at least claim a valid position in its author's chain.
+    // The ops held by the authority for the chain also check it against
+    // the previous action.
+    check_prev_action(&op.action())?;
     match op {
         DhtOp::StoreRecord(_, action, entry) => {
             store_record(action, workspace, network.clone()).await?;
@@ -320,14 +342,17 @@ async fn validate_op_inner(
                     let weight = action
                         .entry_rate_data()
                         .ok_or_else(|| SysValidationError::NonEntryAction(action.clone()))?;
-                    for action in session_data.build_action_set(entry_hash, weight)? {
-                        let hh = ActionHash::with_data_sync(&action);
-                        if workspace
-                            .full_cascade(network.clone())
-                            .retrieve_action(hh.clone(), Default::default())
-                            .await?
-                            .is_none()
-                        {
+                    let hashes: Vec<ActionHash> = session_data
+                        .build_action_set(entry_hash, weight)?
+                        .iter()
+                        .map(ActionHash::with_data_sync)
+                        .collect();
+                    let actions = workspace
+                        .full_cascade(network.clone())
+                        .retrieve_actions(hashes.clone(), Default::default())
+                        .await?;
+                    for (hh, action) in hashes.into_iter().zip(actions) {
+                        if action.is_none() {
                             return Err(SysValidationError::ValidationOutcome(
                                 ValidationOutcome::DepMissingFromDht(hh.into()),
                             ));
@@ -598,6 +623,7 @@ async fn store_record(
             .retrieve_action(prev_action_hash.clone(), Default::default())
             .await?
             .ok_or_else(|| ValidationOutcome::DepMissingFromDht(prev_action_hash.clone().into()))?;
+        check_prev_author(action, prev_action.action())?;
         check_prev_timestamp(action, prev_action.action())?;
         check_prev_seq(action, prev_action.action())?;
     }
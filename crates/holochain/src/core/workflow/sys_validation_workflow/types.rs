@@ -11,7 +11,9 @@ pub(super) enum Outcome {
     /// Stays in limbo because a dependency could not
     /// be found currently on the DHT.
     /// Note this is not proof it doesn't exist.
-    MissingDhtDep,
+    MissingDhtDep(AnyDhtHash),
     /// Moves to integration with status rejected
-    Rejected,
+    Rejected(ValidationRejection),
+    /// Is dropped because the signature is not the author's
+    Counterfeit,
 }
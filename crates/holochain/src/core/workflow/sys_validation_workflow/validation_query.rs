@@ -1,5 +1,6 @@
 use holo_hash::DhtOpHash;
 use holochain_sqlite::db::DbKindDht;
+use holochain_sqlite::rusqlite::ToSql;
 use holochain_state::query::prelude::*;
 use holochain_types::db::DbRead;
 use holochain_types::dht_op::DhtOp;
@@ -7,10 +8,16 @@ use holochain_types::dht_op::DhtOpHashed;
 use holochain_types::dht_op::DhtOpType;
 use holochain_zome_types::Entry;
 use holochain_zome_types::SignedAction;
+use holochain_zome_types::Timestamp;
 
 pub use crate::core::validation::DhtOpOrder;
 use crate::core::workflow::error::WorkflowResult;
 
+/// How long an op waits for a sys validation dependency to arrive
+/// before it is validated again, so the dependency is fetched again.
+pub const SYS_VALIDATION_DEPENDENCY_RETRY: std::time::Duration =
+    std::time::Duration::from_secs(60 * 5);
+
 /// Get all ops that need to sys or app validated in order.
 /// - Sys validated or awaiting app dependencies.
 /// - Ordered by type then timestamp (See [`DhtOpOrder`])
@@ -20,6 +27,8 @@ pub async fn get_ops_to_app_validate(db: &DbRead<DbKindDht>) -> WorkflowResult<V
 
 /// Get all ops that need to sys or app validated in order.
 /// - Pending or awaiting sys dependencies.
+/// - Ops awaiting a sys dependency are skipped until the dependency is held
+///   or [`SYS_VALIDATION_DEPENDENCY_RETRY`] has passed since the last attempt.
 /// - Ordered by type then timestamp (See [`DhtOpOrder`])
 pub async fn get_ops_to_sys_validate(db: &DbRead<DbKindDht>) -> WorkflowResult<Vec<DhtOpHashed>> {
     get_ops_to_validate(db, true).await
@@ -52,6 +61,19 @@ async fn get_ops_to_validate(
                 DhtOp.validation_stage IS NULL
                 OR DhtOp.validation_stage = 0
             )
+            AND (
+                DhtOp.sys_validation_dependency IS NULL
+                OR DhtOp.last_validation_attempt IS NULL
+                OR DhtOp.last_validation_attempt < :retry_before
+                OR EXISTS (
+                    SELECT 1 FROM Action
+                    WHERE Action.hash = DhtOp.sys_validation_dependency
+                )
+                OR EXISTS (
+                    SELECT 1 FROM Entry
+                    WHERE Entry.hash = DhtOp.sys_validation_dependency
+                )
+            )
             ",
         );
     } else {
@@ -79,9 +101,15 @@ async fn get_ops_to_validate(
         LIMIT 10000
         ",
     );
+    let retry_before = Timestamp::now().saturating_sub(&SYS_VALIDATION_DEPENDENCY_RETRY);
     db.async_reader(move |txn| {
         let mut stmt = txn.prepare(&sql)?;
-        let r = stmt.query_and_then([], |row| {
+        let params: &[(&str, &dyn ToSql)] = if system {
+            &[(":retry_before", &retry_before)]
+        } else {
+            &[]
+        };
+        let r = stmt.query_and_then(params, |row| {
             let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
             let op_type: DhtOpType = row.get("dht_type")?;
             let hash: DhtOpHash = row.get("dht_hash")?;
@@ -106,10 +134,13 @@ mod tests {
     use arbitrary::Arbitrary;
     use arbitrary::Unstructured;
     use fixt::prelude::*;
+    use holo_hash::ActionHash;
     use holo_hash::HasHash;
     use holo_hash::HashableContentExtSync;
     use holochain_sqlite::db::WriteManager;
     use holochain_sqlite::prelude::DatabaseResult;
+    use holochain_sqlite::rusqlite::named_params;
+    use holochain_sqlite::rusqlite::Transaction;
     use holochain_state::prelude::*;
     use holochain_state::validation_db::ValidationLimboStatus;
     use holochain_types::dht_op::DhtOpHashed;
@@ -262,4 +293,77 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    /// Ops parked on a dependency are skipped until the
+    /// dependency is held or it is time to fetch it again.
+    async fn parked_ops_wait_for_their_dependency() {
+        observability::test_run().ok();
+        let db = test_dht_db();
+        let db = db.to_db();
+        let read: DbRead<_> = db.clone().into();
+        let is_returned = |read: DbRead<DbKindDht>, hash: DhtOpHash| async move {
+            get_ops_to_sys_validate(&read)
+                .await
+                .unwrap()
+                .iter()
+                .any(|op| *op.as_hash() == hash)
+        };
+
+        let dep = fixt!(Action);
+        let dep_hash = ActionHash::with_data_sync(&dep);
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Action),
+        ));
+        let op_hash = op.as_hash().clone();
+        let park = {
+            let op_hash = op_hash.clone();
+            let dep_hash = dep_hash.clone();
+            move |txn: &mut Transaction| {
+                set_validation_stage(
+                    txn,
+                    &op_hash,
+                    ValidationLimboStatus::AwaitingSysDeps(dep_hash.clone().into()),
+                )
+            }
+        };
+
+        db.async_commit({
+            let park = park.clone();
+            move |txn| {
+                insert_op(txn, &op)?;
+                park(txn)
+            }
+        })
+        .await
+        .unwrap();
+        assert!(!is_returned(read.clone(), op_hash.clone()).await);
+
+        // Waiting long enough means it's time to try fetching the dependency.
+        db.async_commit(move |txn| {
+            txn.execute(
+                "UPDATE DhtOp SET last_validation_attempt = :attempt",
+                named_params! {
+                    ":attempt": Timestamp::now()
+                        .saturating_sub(&(SYS_VALIDATION_DEPENDENCY_RETRY * 2)),
+                },
+            )?;
+            StateMutationResult::Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(is_returned(read.clone(), op_hash.clone()).await);
+
+        // Holding the dependency means the op can be validated straight away.
+        db.async_commit(move |txn| {
+            park(txn)?;
+            let dep_op =
+                DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(fixt!(Signature), dep));
+            insert_op(txn, &dep_op)
+        })
+        .await
+        .unwrap();
+        assert!(is_returned(read.clone(), op_hash.clone()).await);
+    }
 }
@@ -92,26 +92,36 @@ pub async fn validation_receipt_workflow(
         })
         .await?;
 
-    // Send the validation receipts
-    for (receipt, author) in receipts {
-        // Don't send receipt to self.
-        if validators.iter().any(|validator| *validator == author) {
-            continue;
-        }
+    // Don't send receipts to self.
+    let receipts: Vec<_> = receipts
+        .into_iter()
+        .filter(|(_, author)| !validators.iter().any(|validator| validator == author))
+        .collect();
 
-        let op_hash = receipt.dht_op_hash.clone();
+    if receipts.is_empty() {
+        return Ok(WorkComplete::Complete);
+    }
 
-        // Sign on the dotted line.
-        let receipt = match ValidationReceipt::sign(receipt, &keystore).await {
-            Ok(Some(r)) => r,
-            Ok(None) => {
-                return Ok(WorkComplete::Incomplete);
-            }
-            Err(e) => {
-                info!(failed_to_sign_receipt = ?e);
-                return Ok(WorkComplete::Incomplete);
-            }
-        };
+    // There's no one to sign the receipts.
+    if validators.is_empty() {
+        return Ok(WorkComplete::Incomplete);
+    }
+
+    // Sign on the dotted line.
+    // All the receipts are signed in one batch to save
+    // a round-trip to the keystore for each receipt.
+    let (receipts, authors): (Vec<_>, Vec<_>) = receipts.into_iter().unzip();
+    let receipts = match ValidationReceipt::sign_batch(receipts, &keystore).await {
+        Ok(r) => r,
+        Err(e) => {
+            info!(failed_to_sign_receipt = ?e);
+            return Ok(WorkComplete::Incomplete);
+        }
+    };
+
+    // Send the validation receipts
+    for (receipt, author) in receipts.into_iter().zip(authors) {
+        let op_hash = receipt.receipt.dht_op_hash.clone();
 
         // Send it and don't wait for response.
         // TODO: When networking has a send without response we can use that
@@ -34,7 +34,7 @@ async fn test_validation_receipt() {
     consistency_10s(&[&alice, &bobbo, &carol]).await;
 
     // Get op hashes
-    let vault = alice.dht_db().clone().into();
+    let vault = alice.authored_db().clone().into();
     let record = fresh_store_test(&vault, |store| {
         store.get_record(&hash.clone().into()).unwrap().unwrap()
     });
@@ -0,0 +1,187 @@
+//! Warrant Workflow: Issue warrants against the authors of ops which this
+//! node has rejected, and publish them to the authorities of the ops' bases.
+//!
+//! - Only integrated ops with a rejected validation status are warranted.
+//! - Ops authored by the agents running on this conductor are never warranted.
+//! - Each op is warranted once by the first validator for this DNA, so
+//!   warrants survive restarts without being issued again.
+//! - Warrants are published with their op, so the authorities can validate
+//!   it for themselves. Warrants received this way are held as pending until
+//!   this node has integrated the op, and are settled here.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use holochain_keystore::MetaLairClient;
+use holochain_p2p::HolochainP2pDna;
+use holochain_p2p::HolochainP2pDnaT;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use tracing::*;
+
+use crate::conductor::conductor::CellStatus;
+use crate::conductor::ConductorHandle;
+use crate::core::queue_consumer::WorkComplete;
+
+use super::error::WorkflowResult;
+
+#[cfg(test)]
+mod tests;
+
+#[instrument(skip(vault, network, keystore, conductor))]
+/// Warrant the authors of rejected ops and publish the warrants.
+pub async fn warrant_workflow(
+    dna_hash: Arc<DnaHash>,
+    vault: DbWrite<DbKindDht>,
+    network: &HolochainP2pDna,
+    keystore: MetaLairClient,
+    conductor: ConductorHandle,
+) -> WorkflowResult<WorkComplete> {
+    vault
+        .async_commit(|txn| resolve_pending_warrants(txn))
+        .await?;
+
+    let validators = conductor
+        .list_cell_ids(Some(CellStatus::Joined))
+        .into_iter()
+        .filter_map(|id| {
+            let (d, a) = id.into_dna_and_agent();
+            if d == *dna_hash {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // There's no one to sign the warrants.
+    let warrantor = match validators.first() {
+        Some(warrantor) => warrantor.clone(),
+        None => return Ok(WorkComplete::Complete),
+    };
+
+    let unwarranted = vault
+        .async_reader({
+            let warrantor = warrantor.clone();
+            move |txn| get_unwarranted_ops(&txn, &warrantor)
+        })
+        .await?;
+
+    // Don't warrant ourselves.
+    let unwarranted: Vec<_> = unwarranted
+        .into_iter()
+        .filter(|(author, _, _, _)| !validators.contains(author))
+        .collect();
+
+    if unwarranted.is_empty() {
+        return Ok(WorkComplete::Complete);
+    }
+
+    let mut warrants = Vec::with_capacity(unwarranted.len());
+    for (author, action_hash, basis, op) in unwarranted {
+        let warrant = Warrant {
+            author,
+            action_hash,
+            basis,
+            warrantor: warrantor.clone(),
+            timestamp: Timestamp::now(),
+        };
+        match sign_warrant(&keystore, warrant).await {
+            Ok(warrant) => warrants.push(WarrantedOp { warrant, op }),
+            Err(e) => {
+                info!(failed_to_sign_warrant = ?e);
+                return Ok(WorkComplete::Incomplete);
+            }
+        }
+    }
+
+    vault
+        .async_commit({
+            let warrants = warrants.clone();
+            move |txn| {
+                for warranted in &warrants {
+                    insert_warrant(txn, &warranted.warrant)?;
+                }
+                StateMutationResult::Ok(())
+            }
+        })
+        .await?;
+
+    let mut by_basis: HashMap<AnyDhtHash, Vec<WarrantedOp>> = HashMap::new();
+    for warrant in warrants {
+        by_basis
+            .entry(warrant.warrant.warrant.basis.clone())
+            .or_default()
+            .push(warrant);
+    }
+    for (basis, warrants) in by_basis {
+        // The warrants are also gossiped with their op,
+        // so a failed publish is not retried.
+        if let Err(e) = network.publish_warrants(basis, warrants).await {
+            info!(failed_to_publish_warrants = ?e);
+        }
+    }
+
+    Ok(WorkComplete::Complete)
+}
+
+/// The author, action, basis and op of every rejected op which
+/// has not yet been warranted by this warrantor.
+fn get_unwarranted_ops(
+    txn: &Transaction,
+    warrantor: &AgentPubKey,
+) -> StateQueryResult<Vec<(AgentPubKey, ActionHash, AnyDhtHash, DhtOp)>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT Action.author, DhtOp.action_hash, DhtOp.basis_hash,
+        DhtOp.type AS dht_type, Action.blob AS action_blob, Entry.blob AS entry_blob
+        FROM DhtOp
+        JOIN Action ON DhtOp.action_hash = Action.hash
+        LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+        WHERE
+        DhtOp.when_integrated IS NOT NULL
+        AND
+        DhtOp.validation_status = :rejected
+        AND
+        NOT EXISTS (
+            SELECT 1 FROM Warrant
+            WHERE Warrant.action_hash = DhtOp.action_hash
+            AND Warrant.basis_hash = DhtOp.basis_hash
+            AND Warrant.warrantor = :warrantor
+        )
+        ",
+    )?;
+    let ops = stmt
+        .query_and_then(
+            named_params! {
+                ":rejected": ValidationStatus::Rejected,
+                ":warrantor": warrantor,
+            },
+            |r| {
+                let action = from_blob::<SignedAction>(r.get("action_blob")?)?;
+                // Private entries never leave this node.
+                let entry = if action
+                    .0
+                    .entry_type()
+                    .filter(|et| *et.visibility() == EntryVisibility::Public)
+                    .is_some()
+                {
+                    let entry: Option<Vec<u8>> = r.get("entry_blob")?;
+                    entry.map(from_blob::<Entry>).transpose()?
+                } else {
+                    None
+                };
+                let op = DhtOp::from_type(r.get("dht_type")?, action, entry)?;
+                StateQueryResult::Ok((
+                    r.get("author")?,
+                    r.get("action_hash")?,
+                    r.get("basis_hash")?,
+                    op,
+                ))
+            },
+        )?
+        .collect::<StateQueryResult<Vec<_>>>()?;
+    Ok(ops)
+}
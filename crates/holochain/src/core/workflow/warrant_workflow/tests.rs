@@ -0,0 +1,140 @@
+use super::*;
+use ::fixt::prelude::*;
+use holochain_state::test_utils::test_dht_db;
+use holochain_state::test_utils::test_keystore;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejected_ops_are_warranted_once() {
+    let db = test_dht_db();
+    let db = db.to_db();
+    let keystore = test_keystore();
+    let warrantor = keystore.new_sign_keypair_random().await.unwrap();
+
+    let op = |status| {
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Action),
+        ));
+        db.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                insert_op(txn, &op)?;
+                set_validation_status(txn, op.as_hash(), status)?;
+                set_when_integrated(txn, op.as_hash(), Timestamp::now())
+            })
+            .unwrap();
+        op
+    };
+    let rejected = op(ValidationStatus::Rejected);
+    op(ValidationStatus::Valid);
+
+    let unwarranted = db
+        .conn()
+        .unwrap()
+        .with_reader_test(|txn| get_unwarranted_ops(&txn, &warrantor).unwrap());
+    let action = rejected.action();
+    assert_eq!(
+        unwarranted,
+        vec![(
+            action.author().clone(),
+            ActionHash::with_data_sync(&action),
+            rejected.dht_basis(),
+            rejected.as_content().clone(),
+        )]
+    );
+
+    let warrant = sign_warrant(
+        &keystore,
+        Warrant {
+            author: action.author().clone(),
+            action_hash: ActionHash::with_data_sync(&action),
+            basis: rejected.dht_basis(),
+            warrantor: warrantor.clone(),
+            timestamp: Timestamp::now(),
+        },
+    )
+    .await
+    .unwrap();
+    db.conn()
+        .unwrap()
+        .with_commit_sync(|txn| insert_warrant(txn, &warrant))
+        .unwrap();
+
+    let unwarranted = db
+        .conn()
+        .unwrap()
+        .with_reader_test(|txn| get_unwarranted_ops(&txn, &warrantor).unwrap());
+    assert!(unwarranted.is_empty());
+
+    // Another validator on this node hasn't warranted the op yet.
+    let other = keystore.new_sign_keypair_random().await.unwrap();
+    let unwarranted = db
+        .conn()
+        .unwrap()
+        .with_reader_test(|txn| get_unwarranted_ops(&txn, &other).unwrap());
+    assert_eq!(unwarranted.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pending_warrants_are_only_held_for_rejected_ops() {
+    let db = test_dht_db();
+    let db = db.to_db();
+    let keystore = test_keystore();
+    let warrantor = keystore.new_sign_keypair_random().await.unwrap();
+
+    let pending_warrant = |status: Option<ValidationStatus>| {
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Action),
+        ));
+        let action = op.action();
+        let warrant = Warrant {
+            author: action.author().clone(),
+            action_hash: ActionHash::with_data_sync(&action),
+            basis: op.dht_basis(),
+            warrantor: warrantor.clone(),
+            timestamp: Timestamp::now(),
+        };
+        (op, status, warrant)
+    };
+    let rejected = pending_warrant(Some(ValidationStatus::Rejected));
+    let valid = pending_warrant(Some(ValidationStatus::Valid));
+    let unvalidated = pending_warrant(None);
+
+    for (op, status, warrant) in [&rejected, &valid, &unvalidated] {
+        let warrant = sign_warrant(&keystore, warrant.clone()).await.unwrap();
+        db.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                insert_op(txn, op)?;
+                if let Some(status) = status {
+                    set_validation_status(txn, op.as_hash(), *status)?;
+                    set_when_integrated(txn, op.as_hash(), Timestamp::now())?;
+                }
+                insert_pending_warrant(txn, &warrant)
+            })
+            .unwrap();
+    }
+    db.conn().unwrap().with_reader_test(|txn| {
+        assert_eq!(count_warrants_by_warrantor(&txn, &warrantor).unwrap(), 3);
+    });
+
+    db.conn()
+        .unwrap()
+        .with_commit_sync(|txn| resolve_pending_warrants(txn))
+        .unwrap();
+
+    db.conn().unwrap().with_reader_test(|txn| {
+        let held = get_warrants_for_basis(&txn, &rejected.2.basis).unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].warrant, rejected.2);
+        assert!(get_warrants_for_basis(&txn, &valid.2.basis)
+            .unwrap()
+            .is_empty());
+        assert!(get_warrants_for_basis(&txn, &unvalidated.2.basis)
+            .unwrap()
+            .is_empty());
+        // The warrant for the op which is yet to be validated is still pending.
+        assert_eq!(count_warrants_by_warrantor(&txn, &warrantor).unwrap(), 2);
+    });
+}
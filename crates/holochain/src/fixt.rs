@@ -18,6 +18,7 @@ use crate::core::ribosome::guest_callback::validation_package::ValidationPackage
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::GuestTime;
 use crate::core::ribosome::HostContext;
 use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::ZomeCallHostAccess;
@@ -356,9 +357,16 @@ fixturator!(
     vanilla fn make_call_zome_handle(CellId);
 );
 
+fixturator!(
+    GuestTime;
+    curve Empty GuestTime::default();
+    curve Unpredictable GuestTime::default();
+    curve Predictable GuestTime::default();
+);
+
 fixturator!(
     ZomeCallHostAccess;
-    constructor fn new(HostFnWorkspace, MetaLairClient, HolochainP2pDna, SignalBroadcaster, CellConductorReadHandle);
+    constructor fn new(HostFnWorkspace, MetaLairClient, HolochainP2pDna, SignalBroadcaster, CellConductorReadHandle, GuestTime);
 );
 
 fixturator!(
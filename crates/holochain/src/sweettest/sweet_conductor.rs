@@ -53,7 +53,10 @@ pub fn standard_config() -> ConductorConfig {
         override_port: None,
     }];
     let admin_interface = AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port: 0 },
+        driver: InterfaceDriver::Websocket {
+            port: 0,
+            auth_token: None,
+        },
     };
     ConductorConfig {
         network: Some(network),
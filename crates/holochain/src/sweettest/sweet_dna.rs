@@ -1,5 +1,6 @@
 use holochain_types::inline_zome::InlineZomeSet;
 use holochain_types::prelude::*;
+use holochain_wasm_test_utils::TestWasmGroup;
 use holochain_wasm_test_utils::TestWasmPair;
 use std::path::Path;
 
@@ -149,6 +150,19 @@ impl SweetDnaFile {
         Ok((dna, integrity_zomes, coordinator_zomes))
     }
 
+    /// Create a DnaFile from a group of test wasms
+    /// with a random UID
+    pub async fn unique_from_test_wasm_group(
+        group: TestWasmGroup,
+    ) -> DnaResult<(DnaFile, Vec<IntegrityZome>, Vec<CoordinatorZome>)> {
+        let TestWasmGroup {
+            integrity,
+            coordinator,
+            wasms,
+        } = group;
+        Self::unique_from_zomes(integrity, coordinator, wasms).await
+    }
+
     /// Create a DnaFile from a collection of InlineZomes (no Wasm)
     pub async fn from_inline_zomes(
         uid: String,
@@ -372,9 +372,14 @@ pub async fn setup_app_inner(
     let conductor_handle = ConductorBuilder::new()
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::Websocket {
+                    port: 0,
+                    auth_token: None,
+                },
             }]),
             network,
+            // The apps set up here are called with unsigned zome calls.
+            danger_allow_unsigned_zome_calls: true,
             ..Default::default()
         })
         .test(db_dir, &[])
@@ -2,8 +2,9 @@
 
 use ::fixt::prelude::*;
 use hdk::prelude::*;
+use holochain::test_utils::wait_for_integration_1m;
 use holochain::{
-    conductor::api::error::ConductorApiResult,
+    conductor::api::{error::ConductorApiResult, ZomeCall},
     sweettest::{SweetAgents, SweetConductor, SweetDnaFile, SweetEasyInline},
 };
 use holochain::{
@@ -11,9 +12,6 @@ use holochain::{
     core::workflow::error::WorkflowError,
     test_utils::WaitOps,
 };
-use holochain::{
-    core::ribosome::guest_callback::validate::ValidateResult, test_utils::wait_for_integration_1m,
-};
 use holochain::{core::SourceChainError, test_utils::display_agent_infos};
 use holochain_keystore::MetaLairClient;
 use holochain_state::prelude::{fresh_reader_test, StateMutationError, Store, Txn};
@@ -394,12 +392,14 @@ fn simple_validation_zome() -> InlineZomeSet {
                     entry: Entry::App(bytes),
                     ..
                 } => AppString::try_from(bytes.into_sb()).unwrap(),
-                _ => return Ok(ValidateResult::Valid),
+                _ => return Ok(ValidateCallbackResult::Valid),
             };
             if &s.0 == "" {
-                Ok(ValidateResult::Invalid("No empty strings allowed".into()))
+                Ok(ValidateCallbackResult::Invalid(
+                    "No empty strings allowed".into(),
+                ))
             } else {
-                Ok(ValidateResult::Valid)
+                Ok(ValidateCallbackResult::Valid)
             }
         })
         .0
@@ -712,6 +712,181 @@ async fn insert_source_chain() {
     assert_eq!(chain.last().unwrap().1, 3);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+/// Test that the conductor can grant a signing key access to zome functions.
+async fn grant_zome_call_capability() {
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome())
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let apps = conductor.setup_app("app", &[dna_file]).await.unwrap();
+    let (alice,) = apps.into_tuple();
+    let zome = alice.zome(SweetEasyInline::COORDINATOR);
+
+    let signing_key = fixt!(AgentPubKey);
+    let call = |fn_name: &str, cap_secret| ZomeCall {
+        cell_id: alice.cell_id().clone(),
+        zome_name: zome.name().clone(),
+        fn_name: fn_name.into(),
+        cap_secret,
+        provenance: signing_key.clone(),
+        payload: ExternIO::encode(()).unwrap(),
+    };
+
+    // The signing key has no access yet.
+    let response = conductor
+        .call_zome(call("create_unit", None))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(response, ZomeCallResponse::Unauthorized(..));
+
+    let grant = |fn_name: &str, access| GrantZomeCallCapabilityPayload {
+        cell_id: alice.cell_id().clone(),
+        signing_key: signing_key.clone(),
+        tag: "ui".into(),
+        functions: [(zome.name().clone(), fn_name.into())].into(),
+        access,
+    };
+
+    let secret = conductor
+        .grant_zome_call_capability(grant("create_unit", ZomeCallCapAccess::Assigned))
+        .await
+        .unwrap();
+    assert!(secret.is_some());
+
+    // The secret is still required.
+    let response = conductor
+        .call_zome(call("create_unit", None))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(response, ZomeCallResponse::Unauthorized(..));
+
+    let response = conductor
+        .call_zome(call("create_unit", secret))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(response, ZomeCallResponse::Ok(_));
+
+    // Only the granted functions can be called.
+    let response = conductor
+        .call_zome(call("delete", secret))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(response, ZomeCallResponse::Unauthorized(..));
+
+    // Unrestricted grants have no secret.
+    let secret = conductor
+        .grant_zome_call_capability(grant("create_string", ZomeCallCapAccess::Unrestricted))
+        .await
+        .unwrap();
+    assert!(secret.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that grants made by the conductor are validated before they are written.
+async fn grant_zome_call_capability_is_validated() {
+    let zomes = SweetEasyInline::new(vec![], 0)
+        .callback("noop", |_api, ()| Ok(()))
+        .integrity_callback("validate", |_api, op: Op| match op {
+            Op::StoreEntry {
+                entry: Entry::CapGrant(_),
+                ..
+            } => Ok(ValidateCallbackResult::Invalid("No grants allowed".into())),
+            _ => Ok(ValidateCallbackResult::Valid),
+        })
+        .0;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes).await.unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let apps = conductor.setup_app("app", &[dna_file]).await.unwrap();
+    let (alice,) = apps.into_tuple();
+    let zome = alice.zome(SweetEasyInline::COORDINATOR);
+
+    let result = conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id: alice.cell_id().clone(),
+            signing_key: fixt!(AgentPubKey),
+            tag: "ui".into(),
+            functions: [(zome.name().clone(), "noop".into())].into(),
+            access: ZomeCallCapAccess::Unrestricted,
+        })
+        .await;
+    assert!(result.is_err());
+    assert!(conductor
+        .list_cap_grants(alice.cell_id())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that capability grants can be listed and revoked outside of wasm.
+async fn list_and_revoke_cap_grants() {
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome())
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let apps = conductor.setup_app("app", &[dna_file]).await.unwrap();
+    let (alice,) = apps.into_tuple();
+    let zome = alice.zome(SweetEasyInline::COORDINATOR);
+    let signing_key = fixt!(AgentPubKey);
+
+    assert!(conductor
+        .list_cap_grants(alice.cell_id())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let secret = conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id: alice.cell_id().clone(),
+            signing_key: signing_key.clone(),
+            tag: "ui".into(),
+            functions: [(zome.name().clone(), "create_unit".into())].into(),
+            access: ZomeCallCapAccess::Transferable,
+        })
+        .await
+        .unwrap();
+    let grants = conductor.list_cap_grants(alice.cell_id()).await.unwrap();
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].tag, "ui");
+    assert_eq!(grants[0].access, ZomeCallCapAccess::Transferable);
+
+    let call = ZomeCall {
+        cell_id: alice.cell_id().clone(),
+        zome_name: zome.name().clone(),
+        fn_name: "create_unit".into(),
+        cap_secret: secret,
+        provenance: signing_key.clone(),
+        payload: ExternIO::encode(()).unwrap(),
+    };
+    let response = conductor.call_zome(call.clone()).await.unwrap().unwrap();
+    assert_matches!(response, ZomeCallResponse::Ok(_));
+
+    conductor
+        .revoke_cap_grant(alice.cell_id(), grants[0].action_hash.clone())
+        .await
+        .unwrap();
+    assert!(conductor
+        .list_cap_grants(alice.cell_id())
+        .await
+        .unwrap()
+        .is_empty());
+
+    // The revoked secret no longer works.
+    let response = conductor.call_zome(call).await.unwrap().unwrap();
+    assert_matches!(response, ZomeCallResponse::Unauthorized(..));
+
+    // A grant can only be revoked once.
+    assert!(conductor
+        .revoke_cap_grant(alice.cell_id(), grants[0].action_hash.clone())
+        .await
+        .is_err());
+}
+
 async fn make_record(keystore: &MetaLairClient, action: Action) -> Record {
     let shh = SignedActionHashed::sign(
         keystore,
@@ -4,6 +4,7 @@ use holochain::sweettest::*;
 use holochain_types::prelude::DnaWasm;
 use holochain_wasm_test_utils::TestCoordinatorWasm;
 use holochain_wasm_test_utils::TestIntegrityWasm;
+use holochain_wasm_test_utils::TestWasmGroup;
 use holochain_zome_types::CoordinatorZome;
 use holochain_zome_types::CoordinatorZomeDef;
 use holochain_zome_types::IntegrityZome;
@@ -15,13 +16,10 @@ use holochain_zome_types::ZomeDef;
 #[tokio::test(flavor = "multi_thread")]
 async fn test_coordinator_zome_hot_swap() {
     let mut conductor = SweetConductor::from_config(Default::default()).await;
-    let (dna, _, _) = SweetDnaFile::unique_from_zomes(
-        vec![TestIntegrityWasm::IntegrityZome],
-        vec![TestCoordinatorWasm::CoordinatorZome],
-        vec![
-            DnaWasm::from(TestIntegrityWasm::IntegrityZome),
-            DnaWasm::from(TestCoordinatorWasm::CoordinatorZome),
-        ],
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasm_group(
+        TestWasmGroup::default()
+            .with_integrity(TestIntegrityWasm::IntegrityZome)
+            .with_coordinator(TestCoordinatorWasm::CoordinatorZome),
     )
     .await
     .unwrap();
@@ -89,6 +87,7 @@ async fn test_coordinator_zome_hot_swap_multi_integrity() {
         ZomeDef::Wasm(WasmZome {
             wasm_hash,
             mut dependencies,
+            init_after,
         }) => {
             dependencies.clear();
             dependencies.push("2".into());
@@ -98,6 +97,7 @@ async fn test_coordinator_zome_hot_swap_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash,
                     dependencies,
+                    init_after,
                 })
                 .into(),
             )
@@ -180,6 +180,7 @@ async fn test_coordinator_zome_hot_swap_multi_integrity() {
     let new_coordinator: CoordinatorZomeDef = ZomeDef::Wasm(WasmZome {
         wasm_hash,
         dependencies: vec!["2".into()],
+        init_after: Default::default(),
     })
     .into();
 
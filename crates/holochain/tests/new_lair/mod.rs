@@ -40,7 +40,10 @@ async fn test_new_lair_conductor_integration() {
     // set up conductor config to use the started keystore
     let mut conductor_config = ConductorConfig::default();
     conductor_config.admin_interfaces = Some(vec![AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port: ADMIN_PORT },
+        driver: InterfaceDriver::Websocket {
+            port: ADMIN_PORT,
+            auth_token: None,
+        },
     }]);
     conductor_config.environment_path = tmp.path().to_owned().into();
     conductor_config.keystore = KeystoreConfig::LairServer {
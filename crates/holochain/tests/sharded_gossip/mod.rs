@@ -259,13 +259,12 @@ async fn mock_network_sharded_gossip() {
     use holochain_p2p::{
         dht_arc::DhtArcSet,
         mock_network::{
-            AddressedHolochainP2pMockMsg, HolochainP2pMockChannel, HolochainP2pMockMsg,
+            mock_network_config, AddressedHolochainP2pMockMsg, HolochainP2pMockChannel,
+            HolochainP2pMockMsg,
         },
     };
     use kitsune_p2p::gossip::sharded_gossip::test_utils::*;
-    use kitsune_p2p::TransportConfig;
     use kitsune_p2p::*;
-    use kitsune_p2p_types::tx2::tx2_adapter::AdapterFactory;
 
     // Get the env var settings for number of simulated agents and
     // the minimum number of ops that should be held by each agent
@@ -315,6 +314,8 @@ async fn mock_network_sharded_gossip() {
             // Simulated agents will send messages from within 50 to 100 ms.
             outbound_delay_range: std::time::Duration::from_millis(50)
                 ..std::time::Duration::from_millis(150),
+            // Use a different random seed each run.
+            seed: None,
         },
     );
 
@@ -358,6 +359,9 @@ async fn mock_network_sharded_gossip() {
                 match msg {
                     HolochainP2pMockMsg::Wire { msg, .. } => match msg {
                         holochain_p2p::WireMessage::CallRemote { .. } => debug!("CallRemote"),
+                        holochain_p2p::WireMessage::PublishWarrants { .. } => {
+                            debug!("PublishWarrants")
+                        }
                         holochain_p2p::WireMessage::Publish { ops, .. } => {
                             if bad_publish.is_some() {
                                 let arc = data.agent_to_arc[&agent];
@@ -541,6 +545,7 @@ async fn mock_network_sharded_gossip() {
                                                 kitsune_p2p::KitsuneOpData::new(
                                                     holochain_p2p::WireDhtOpData {
                                                         op_data: op.into_content(),
+                                                        warrants: vec![],
                                                     }
                                                     .encode()
                                                     .unwrap(),
@@ -635,22 +640,14 @@ async fn mock_network_sharded_gossip() {
         }
     });
 
-    // Create the mock network.
-    let mock_network =
-        kitsune_p2p::test_util::mock_network::mock_network(from_kitsune_tx, to_kitsune_rx);
-    let mock_network: AdapterFactory = Arc::new(mock_network);
-
     // Setup the network.
     let mut tuning =
         kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
     tuning.gossip_strategy = "sharded-gossip".to_string();
     tuning.gossip_dynamic_arcs = true;
 
-    let mut network = KitsuneP2pConfig::default();
-    network.transport_pool = vec![TransportConfig::Mock {
-        mock_network: mock_network.into(),
-    }];
-    network.tuning_params = Arc::new(tuning);
+    // Select the simulated network for the conductor.
+    let network = mock_network_config(from_kitsune_tx, to_kitsune_rx, Arc::new(tuning));
     let mut config = ConductorConfig::default();
     config.network = Some(network);
 
@@ -786,7 +783,8 @@ async fn mock_network_sharding() {
 
     use hdk::prelude::*;
     use holochain_p2p::mock_network::{
-        AddressedHolochainP2pMockMsg, HolochainP2pMockChannel, HolochainP2pMockMsg,
+        mock_network_config, AddressedHolochainP2pMockMsg, HolochainP2pMockChannel,
+        HolochainP2pMockMsg,
     };
     use holochain_p2p::mock_network::{GossipProtocol, MockScenario};
     use holochain_p2p::AgentPubKeyExt;
@@ -794,8 +792,6 @@ async fn mock_network_sharding() {
     use holochain_types::dht_op::WireOps;
     use holochain_types::record::WireRecordOps;
     use kitsune_p2p::gossip::sharded_gossip::test_utils::check_agent_boom;
-    use kitsune_p2p::TransportConfig;
-    use kitsune_p2p_types::tx2::tx2_adapter::AdapterFactory;
 
     // Get the env var settings for number of simulated agents and
     // the minimum number of ops that should be held by each agent
@@ -844,6 +840,8 @@ async fn mock_network_sharding() {
             // Simulated agents will send messages from within 50 to 100 ms.
             outbound_delay_range: std::time::Duration::from_millis(50)
                 ..std::time::Duration::from_millis(150),
+            // Use a different random seed each run.
+            seed: None,
         },
     );
 
@@ -870,6 +868,9 @@ async fn mock_network_sharding() {
                 match msg {
                     HolochainP2pMockMsg::Wire { msg, .. } => match msg {
                         holochain_p2p::WireMessage::CallRemote { .. } => debug!("CallRemote"),
+                        holochain_p2p::WireMessage::PublishWarrants { .. } => {
+                            debug!("PublishWarrants")
+                        }
                         holochain_p2p::WireMessage::Publish { .. } => {}
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
@@ -1074,6 +1075,7 @@ async fn mock_network_sharding() {
                                                 kitsune_p2p::KitsuneOpData::new(
                                                     holochain_p2p::WireDhtOpData {
                                                         op_data: op.into_content(),
+                                                        warrants: vec![],
                                                     }
                                                     .encode()
                                                     .unwrap(),
@@ -1137,11 +1139,6 @@ async fn mock_network_sharding() {
         }
     });
 
-    // Create the mock network.
-    let mock_network =
-        kitsune_p2p::test_util::mock_network::mock_network(from_kitsune_tx, to_kitsune_rx);
-    let mock_network: AdapterFactory = Arc::new(mock_network);
-
     // Setup the bootstrap.
     let (bootstrap, _shutdown) = run_bootstrap(data.agent_to_info.values().cloned()).await;
     // Setup the network.
@@ -1150,12 +1147,9 @@ async fn mock_network_sharding() {
     tuning.gossip_strategy = "sharded-gossip".to_string();
     tuning.gossip_dynamic_arcs = true;
 
-    let mut network = KitsuneP2pConfig::default();
+    // Select the simulated network for the conductor.
+    let mut network = mock_network_config(from_kitsune_tx, to_kitsune_rx, Arc::new(tuning));
     network.bootstrap_service = Some(bootstrap);
-    network.transport_pool = vec![TransportConfig::Mock {
-        mock_network: mock_network.into(),
-    }];
-    network.tuning_params = Arc::new(tuning);
     let mut config = ConductorConfig::default();
     config.network = Some(network);
 
@@ -271,13 +271,24 @@ pub async fn check_started(holochain: &mut Child) {
 pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::Websocket {
+                port,
+                auth_token: None,
+            },
         }]),
         environment_path: environment_path.into(),
         network: None,
         dpki: None,
         keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
         db_sync_strategy: DbSyncStrategy::default(),
+        cell_quotas: None,
+        default_required_validations: None,
+        wasm_cache_path: None,
+        max_wasm_ops_per_call: None,
+        max_wasm_time_per_call_ms: None,
+        db_encryption: false,
+        db_compaction_interval_hours: None,
+        danger_allow_unsigned_zome_calls: true,
     }
 }
 
@@ -1,6 +1,5 @@
 use ::fixt::prelude::*;
 use anyhow::Result;
-use futures::future;
 use hdk::prelude::RemoteSignal;
 use holochain::sweettest::SweetAgents;
 use holochain::sweettest::SweetConductor;
@@ -213,23 +212,17 @@ async fn remote_signals() -> anyhow::Result<()> {
 
     let mut conductors = SweetConductorBatch::from_standard_config(NUM_CONDUCTORS).await;
 
-    // MAYBE: write helper for agents across conductors
-    let all_agents: Vec<HoloHash<hash_type::Agent>> =
-        future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
-
     let dna_file = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::EmitSignal])
         .await
         .unwrap()
         .0;
 
-    let apps = conductors
-        .setup_app_for_zipped_agents("app", &all_agents, &[dna_file])
-        .await
-        .unwrap();
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
 
     conductors.exchange_peer_info().await;
 
     let cells = apps.cells_flattened();
+    let all_agents: Vec<AgentPubKey> = cells.iter().map(|c| c.agent_pubkey().clone()).collect();
 
     let mut rxs = Vec::new();
     for h in conductors.iter().map(|c| c) {
@@ -246,6 +239,7 @@ async fn remote_signals() -> anyhow::Result<()> {
             RemoteSignal {
                 signal: signal.clone(),
                 agents: all_agents,
+                reliable: false,
             },
         )
         .await;
@@ -22,17 +22,25 @@ fn merge_hashes(
     let mut valid = HashSet::new();
     let mut rejected = HashSet::new();
     let mut merged_highest_observed = None;
+    let mut reported_status = None;
+    let mut merged_warrants = HashSet::new();
     for result in results {
         let AgentActivityResponse {
             agent: the_agent,
             highest_observed,
             valid_activity,
             rejected_activity,
-            ..
+            status,
+            warrants,
         } = result;
         if the_agent != agent {
             continue;
         }
+        merge_warrants(&agent, &mut merged_warrants, warrants);
+        reported_status = Some(match reported_status.take() {
+            Some(last) => merge_status(status, last),
+            None => status,
+        });
 
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
@@ -68,6 +76,12 @@ fn merge_hashes(
     }
 
     let (status, valid, rejected) = compute_chain_status(valid, rejected);
+    // The hashes may be filtered by the query so the authorities can have
+    // seen forks or invalid actions which are not among them.
+    let status = match reported_status {
+        Some(reported) => merge_status(status, reported),
+        None => status,
+    };
     let valid_activity = if options.include_valid_activity {
         ChainItems::Hashes(valid)
     } else {
@@ -84,6 +98,7 @@ fn merge_hashes(
         valid_activity,
         rejected_activity,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
 
@@ -147,16 +162,19 @@ fn merge_status_only(
 ) -> AgentActivityResponse<ActionHash> {
     let mut merged_status = None;
     let mut merged_highest_observed = None;
+    let mut merged_warrants = HashSet::new();
     for result in results {
         let AgentActivityResponse {
             status,
             agent: the_agent,
             highest_observed,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merge_warrants(&agent, &mut merged_warrants, warrants);
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -167,66 +185,10 @@ fn merge_status_only(
                 merged_highest_observed = Some(c);
             }
         }
-        match merged_status.take() {
-            Some(last) => match (status, last) {
-                (ChainStatus::Empty, ChainStatus::Empty) => {
-                    merged_status = Some(ChainStatus::Empty);
-                }
-                (ChainStatus::Empty, ChainStatus::Valid(c))
-                | (ChainStatus::Valid(c), ChainStatus::Empty) => {
-                    merged_status = Some(ChainStatus::Valid(c));
-                }
-                (ChainStatus::Empty, ChainStatus::Forked(c))
-                | (ChainStatus::Forked(c), ChainStatus::Empty) => {
-                    merged_status = Some(ChainStatus::Forked(c));
-                }
-                (ChainStatus::Empty, ChainStatus::Invalid(c))
-                | (ChainStatus::Invalid(c), ChainStatus::Empty) => {
-                    merged_status = Some(ChainStatus::Invalid(c));
-                }
-                (ChainStatus::Valid(a), ChainStatus::Valid(b)) => {
-                    let c = if a.action_seq > b.action_seq { a } else { b };
-                    merged_status = Some(ChainStatus::Valid(c));
-                }
-                (ChainStatus::Valid(_), ChainStatus::Forked(c))
-                | (ChainStatus::Forked(c), ChainStatus::Valid(_)) => {
-                    // If the valid and forked chain heads are the same then they are in conflict here.
-                    // TODO: BACKLOG: When we handle conflicts this should count as a conflict.
-                    merged_status = Some(ChainStatus::Forked(c));
-                }
-                (ChainStatus::Invalid(c), ChainStatus::Valid(_))
-                | (ChainStatus::Valid(_), ChainStatus::Invalid(c)) => {
-                    // If the valid and invalid chain heads are the same then they are in conflict here.
-                    // TODO: BACKLOG: When we handle conflicts this should count as a conflict.
-                    merged_status = Some(ChainStatus::Invalid(c));
-                }
-                (ChainStatus::Forked(a), ChainStatus::Forked(b)) => {
-                    let c = if a.fork_seq < b.fork_seq { a } else { b };
-                    merged_status = Some(ChainStatus::Forked(c));
-                }
-                (ChainStatus::Invalid(a), ChainStatus::Invalid(b)) => {
-                    let c = if a.action_seq < b.action_seq { a } else { b };
-                    merged_status = Some(ChainStatus::Invalid(c));
-                }
-                (ChainStatus::Forked(a), ChainStatus::Invalid(b)) => {
-                    if a.fork_seq < b.action_seq {
-                        merged_status = Some(ChainStatus::Forked(a));
-                    } else {
-                        merged_status = Some(ChainStatus::Invalid(b));
-                    };
-                }
-                (ChainStatus::Invalid(a), ChainStatus::Forked(b)) => {
-                    if a.action_seq < b.fork_seq {
-                        merged_status = Some(ChainStatus::Invalid(a));
-                    } else {
-                        merged_status = Some(ChainStatus::Forked(b));
-                    };
-                }
-            },
-            None => {
-                merged_status = Some(status);
-            }
-        }
+        merged_status = Some(match merged_status.take() {
+            Some(last) => merge_status(status, last),
+            None => status,
+        });
     }
     AgentActivityResponse {
         status: merged_status.unwrap_or(ChainStatus::Empty),
@@ -234,5 +196,72 @@ fn merge_status_only(
         valid_activity: ChainItems::NotRequested,
         rejected_activity: ChainItems::NotRequested,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
+    }
+}
+
+/// Keep each warrant against this agent once, whichever
+/// authorities reported it.
+fn merge_warrants(
+    agent: &AgentPubKey,
+    merged: &mut HashSet<SignedWarrant>,
+    warrants: Vec<SignedWarrant>,
+) {
+    merged.extend(
+        warrants
+            .into_iter()
+            .filter(|warrant| warrant.warrant.author == *agent),
+    );
+}
+
+/// Merge the chain status from two sources.
+/// The earliest fork or invalid action wins over a valid chain.
+fn merge_status(status: ChainStatus, last: ChainStatus) -> ChainStatus {
+    match (status, last) {
+        (ChainStatus::Empty, ChainStatus::Empty) => ChainStatus::Empty,
+        (ChainStatus::Empty, ChainStatus::Valid(c))
+        | (ChainStatus::Valid(c), ChainStatus::Empty) => ChainStatus::Valid(c),
+        (ChainStatus::Empty, ChainStatus::Forked(c))
+        | (ChainStatus::Forked(c), ChainStatus::Empty) => ChainStatus::Forked(c),
+        (ChainStatus::Empty, ChainStatus::Invalid(c))
+        | (ChainStatus::Invalid(c), ChainStatus::Empty) => ChainStatus::Invalid(c),
+        (ChainStatus::Valid(a), ChainStatus::Valid(b)) => {
+            let c = if a.action_seq > b.action_seq { a } else { b };
+            ChainStatus::Valid(c)
+        }
+        (ChainStatus::Valid(_), ChainStatus::Forked(c))
+        | (ChainStatus::Forked(c), ChainStatus::Valid(_)) => {
+            // If the valid and forked chain heads are the same then they are in conflict here.
+            // TODO: BACKLOG: When we handle conflicts this should count as a conflict.
+            ChainStatus::Forked(c)
+        }
+        (ChainStatus::Invalid(c), ChainStatus::Valid(_))
+        | (ChainStatus::Valid(_), ChainStatus::Invalid(c)) => {
+            // If the valid and invalid chain heads are the same then they are in conflict here.
+            // TODO: BACKLOG: When we handle conflicts this should count as a conflict.
+            ChainStatus::Invalid(c)
+        }
+        (ChainStatus::Forked(a), ChainStatus::Forked(b)) => {
+            let c = if a.fork_seq < b.fork_seq { a } else { b };
+            ChainStatus::Forked(c)
+        }
+        (ChainStatus::Invalid(a), ChainStatus::Invalid(b)) => {
+            let c = if a.action_seq < b.action_seq { a } else { b };
+            ChainStatus::Invalid(c)
+        }
+        (ChainStatus::Forked(a), ChainStatus::Invalid(b)) => {
+            if a.fork_seq < b.action_seq {
+                ChainStatus::Forked(a)
+            } else {
+                ChainStatus::Invalid(b)
+            }
+        }
+        (ChainStatus::Invalid(a), ChainStatus::Forked(b)) => {
+            if a.action_seq < b.fork_seq {
+                ChainStatus::Invalid(a)
+            } else {
+                ChainStatus::Forked(b)
+            }
+        }
     }
 }
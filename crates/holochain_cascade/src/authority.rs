@@ -10,7 +10,10 @@ use super::error::CascadeResult;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
 use holochain_state::query::Query;
+use holochain_state::query::StateQueryResult;
 use holochain_state::query::Txn;
+use holochain_state::warrants::get_warrants_for_author;
+use holochain_state::warrants::get_warrants_for_basis;
 use holochain_types::prelude::*;
 use holochain_zome_types::agent_activity::DeterministicGetAgentActivityFilter;
 use tracing::*;
@@ -29,9 +32,14 @@ pub async fn handle_get_entry(
     hash: EntryHash,
     _options: holochain_p2p::event::GetOptions,
 ) -> CascadeResult<WireEntryOps> {
+    let basis = hash.clone().into();
     let query = GetEntryOpsQuery::new(hash);
     let results = db
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut results = query.run(Txn::from(&txn))?;
+            results.warrants = get_warrants_for_basis(&txn, &basis)?;
+            StateQueryResult::Ok(results)
+        })
         .await?;
     Ok(results)
 }
@@ -42,9 +50,14 @@ pub async fn handle_get_record(
     hash: ActionHash,
     options: holochain_p2p::event::GetOptions,
 ) -> CascadeResult<WireRecordOps> {
+    let basis = hash.clone().into();
     let query = GetRecordOpsQuery::new(hash, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut results = query.run(Txn::from(&txn))?;
+            results.warrants = get_warrants_for_basis(&txn, &basis)?;
+            StateQueryResult::Ok(results)
+        })
         .await?;
     Ok(results)
 }
@@ -56,9 +69,13 @@ pub async fn handle_get_agent_activity(
     query: ChainQueryFilter,
     options: holochain_p2p::event::GetActivityOptions,
 ) -> CascadeResult<AgentActivityResponse<ActionHash>> {
-    let query = GetAgentActivityQuery::new(agent, query, options);
+    let query = GetAgentActivityQuery::new(agent.clone(), query, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut results = query.run(Txn::from(&txn))?;
+            results.warrants = get_warrants_for_author(&txn, &agent)?;
+            StateQueryResult::Ok(results)
+        })
         .await?;
     Ok(results)
 }
@@ -77,15 +94,18 @@ pub async fn handle_get_agent_activity_deterministic(
     Ok(results)
 }
 
-#[instrument(skip(env, _options))]
+#[instrument(skip(env, options))]
 pub async fn handle_get_links(
     env: DbRead<DbKindDht>,
     link_key: WireLinkKey,
-    _options: holochain_p2p::event::GetLinksOptions,
+    options: holochain_p2p::event::GetLinksOptions,
 ) -> CascadeResult<WireLinkOps> {
-    let query = GetLinksOpsQuery::new(link_key);
+    let query = GetLinksOpsQuery::new(link_key.clone());
     let results = env
         .async_reader(move |txn| query.run(Txn::from(&txn)))
         .await?;
-    Ok(results)
+    Ok(match options.pagination {
+        Some(pagination) => results.paginate(&link_key, &pagination),
+        None => results,
+    })
 }
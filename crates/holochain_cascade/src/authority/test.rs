@@ -4,7 +4,9 @@ use crate::test_utils::*;
 use ghost_actor::dependencies::observability;
 use holochain_p2p::actor;
 use holochain_p2p::event::GetRequest;
+use holochain_state::mutations::insert_warrant;
 use holochain_state::prelude::test_dht_db;
+use holochain_state::warrants::sign_warrant;
 use holochain_types::activity::ChainItems;
 
 fn options() -> holochain_p2p::event::GetOptions {
@@ -33,6 +35,7 @@ async fn get_entry() {
         deletes: vec![],
         updates: vec![],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
@@ -46,6 +49,7 @@ async fn get_entry() {
         deletes: vec![td.wire_delete.clone()],
         updates: vec![],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
@@ -59,6 +63,7 @@ async fn get_entry() {
         deletes: vec![td.wire_delete.clone()],
         updates: vec![td.wire_update.clone()],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 }
@@ -82,6 +87,7 @@ async fn get_record() {
         deletes: vec![],
         updates: vec![],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
@@ -95,6 +101,7 @@ async fn get_record() {
         deletes: vec![td.wire_delete.clone()],
         updates: vec![],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
@@ -108,6 +115,7 @@ async fn get_record() {
         deletes: vec![td.wire_delete.clone()],
         updates: vec![td.wire_update.clone()],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
@@ -125,6 +133,7 @@ async fn get_record() {
         deletes: vec![],
         updates: vec![],
         entry: td.any_entry.clone(),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 }
@@ -149,10 +158,51 @@ async fn retrieve_record() {
         deletes: vec![],
         updates: vec![],
         entry: Some(td.entry.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_record_with_warrants() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+    let keystore = holochain_state::prelude::test_keystore();
+    let warrantor = keystore.new_sign_keypair_random().await.unwrap();
+
+    let td = RecordTestData::create();
+    fill_db(&db.to_db(), td.store_record_op.clone());
+
+    let warrant = sign_warrant(
+        &keystore,
+        Warrant {
+            author: td.wire_create.data.0.author().clone(),
+            action_hash: td.create_hash.clone(),
+            basis: td.create_hash.clone().into(),
+            warrantor,
+            timestamp: Timestamp::now(),
+        },
+    )
+    .await
+    .unwrap();
+    db.to_db()
+        .conn()
+        .unwrap()
+        .with_commit_sync(|txn| insert_warrant(txn, &warrant))
+        .unwrap();
+
+    let result = handle_get_record(db.to_db().into(), td.create_hash.clone(), options())
+        .await
+        .unwrap();
+    assert_eq!(result.warrants, vec![warrant.clone()]);
+
+    // Warrants are only served with the basis they were issued for.
+    let result = handle_get_record(db.to_db().into(), td.any_action_hash.clone(), options())
+        .await
+        .unwrap();
+    assert!(result.warrants.is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_links() {
     observability::test_run().ok();
@@ -189,6 +239,46 @@ async fn get_links() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_links_paginated() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.create_link_op.clone());
+    fill_db(&db.to_db(), td.delete_link_op.clone());
+    let mut options = actor::GetLinksOptions {
+        pagination: Some(LinkPagination::new(LinkOrder::TimestampDesc, Some(1), None)),
+        ..Default::default()
+    };
+
+    // The deleted link doesn't fill the page so its delete comes with it.
+    let result = handle_get_links(db.to_db().into(), td.link_key.clone(), (&options).into())
+        .await
+        .unwrap();
+    let expected = WireLinkOps {
+        creates: vec![td.wire_create_link.clone()],
+        deletes: vec![td.wire_delete_link.clone()],
+    };
+    assert_eq!(result, expected);
+
+    // Nothing comes after the only link.
+    options.pagination = Some(LinkPagination::new(
+        LinkOrder::TimestampDesc,
+        Some(1),
+        Some(LinkCursor {
+            timestamp: td.create_link_action.action().timestamp(),
+            create_link_hash: td.create_link_action.as_hash().clone(),
+        }),
+    ));
+    let result = handle_get_links(db.to_db().into(), td.link_key.clone(), (&options).into())
+        .await
+        .unwrap();
+    assert_eq!(result, WireLinkOps::new());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_agent_activity() {
     observability::test_run().ok();
@@ -224,6 +314,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
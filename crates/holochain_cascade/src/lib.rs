@@ -36,6 +36,7 @@ use holochain_types::prelude::*;
 use mutations::insert_action;
 use mutations::insert_entry;
 use mutations::insert_op_lite;
+use mutations::insert_warrant;
 use tracing::*;
 
 pub mod authority;
@@ -67,6 +68,29 @@ macro_rules! ok_or_return {
     };
 }
 
+/// What came back from asking the authorities for data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthorityResponses {
+    /// This agent didn't need to ask the network because it is
+    /// authoring the data or is an authority for it.
+    NotAsked,
+    /// This many authorities answered.
+    Answered(usize),
+    /// Authorities were asked but none answered in time.
+    TimedOut,
+}
+
+/// Combine the result of searching the stores with how the
+/// authorities answered the network request.
+fn outcome_from_search<T>(result: Option<T>, responses: AuthorityResponses) -> GetOutcome<T> {
+    match (result, responses) {
+        (Some(t), _) => GetOutcome::Found(t),
+        (None, AuthorityResponses::Answered(0)) => GetOutcome::Unavailable,
+        (None, AuthorityResponses::TimedOut) => GetOutcome::TimedOut,
+        (None, _) => GetOutcome::NotFound,
+    }
+}
+
 #[derive(Clone)]
 pub struct Cascade<Network = HolochainP2pDna> {
     authored: Option<DbRead<DbKindAuthored>>,
@@ -218,22 +242,34 @@ where
     }
 
     fn insert_rendered_ops(txn: &mut Transaction, ops: &RenderedOps) -> CascadeResult<()> {
-        let RenderedOps { ops, entry } = ops;
+        let RenderedOps {
+            ops,
+            entry,
+            warrants,
+        } = ops;
         if let Some(entry) = entry {
             insert_entry(txn, entry.as_hash(), entry.as_content())?;
         }
         for op in ops {
             Self::insert_rendered_op(txn, op)?;
         }
+        for warrant in warrants {
+            insert_warrant(txn, warrant)?;
+        }
         Ok(())
     }
 
     async fn merge_ops_into_cache(&mut self, responses: Vec<WireOps>) -> CascadeResult<()> {
         let cache = ok_or_return!(self.cache.as_mut());
+        let mut rendered = Vec::with_capacity(responses.len());
+        for response in responses {
+            let mut ops = response.render()?;
+            ops.warrants = verified_warrants(std::mem::take(&mut ops.warrants)).await;
+            rendered.push(ops);
+        }
         cache
-            .async_commit(|txn| {
-                for response in responses {
-                    let ops = response.render()?;
+            .async_commit(move |txn| {
+                for ops in rendered {
                     Self::insert_rendered_ops(txn, &ops)?;
                 }
                 CascadeResult::Ok(())
@@ -266,14 +302,30 @@ where
         hash: AnyDhtHash,
         options: NetworkGetOptions,
     ) -> CascadeResult<()> {
-        let network = ok_or_return!(self.network.as_mut());
-        let results = network
+        self.fetch_record_from_authorities(hash, options).await?;
+        Ok(())
+    }
+
+    /// Fetch a record into the cache and return how the authorities
+    /// responded. Without a network no authority can respond.
+    async fn fetch_record_from_authorities(
+        &mut self,
+        hash: AnyDhtHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<AuthorityResponses> {
+        let network = ok_or_return!(self.network.as_mut(), AuthorityResponses::Answered(0));
+        let results = match network
             .get(hash, options.clone())
             .instrument(debug_span!("fetch_record::network_get"))
-            .await?;
+            .await
+        {
+            Err(e) if e.is_timeout() => return Ok(AuthorityResponses::TimedOut),
+            results => results?,
+        };
+        let responses = AuthorityResponses::Answered(results.len());
 
         self.merge_ops_into_cache(results).await?;
-        Ok(())
+        Ok(responses)
     }
 
     #[instrument(skip(self, options))]
@@ -389,6 +441,69 @@ where
         Ok(None)
     }
 
+    /// Search through the stores for each key and return the first
+    /// non-none result for each, in the same order as the keys.
+    /// Each store is searched for all the keys still missing in one read.
+    async fn find_map_multi<K, F, T>(&mut self, keys: Vec<K>, f: F) -> CascadeResult<Vec<Option<T>>>
+    where
+        K: Send + Sync + 'static,
+        T: Send + 'static,
+        F: Fn(&dyn Store, &K) -> CascadeResult<Option<T>> + Send + Sync + 'static,
+    {
+        let keys = Arc::new(keys);
+        let f = Arc::new(f);
+        let mut results: Vec<Option<T>> = keys.iter().map(|_| None).collect();
+        let find = |permit,
+                    conn: Box<dyn PermittedConn + Send>,
+                    mut results: Vec<Option<T>>,
+                    keys: Arc<Vec<K>>,
+                    f: Arc<F>| async move {
+            tokio::task::spawn_blocking(move || {
+                let mut conn = conn.with_permit(permit)?;
+                let txn = conn.transaction().map_err(StateQueryError::from)?;
+                let txn = Txn::from(&txn);
+                for (key, result) in keys.iter().zip(results.iter_mut()) {
+                    if result.is_none() {
+                        *result = f(&txn, key)?;
+                    }
+                }
+                CascadeResult::Ok(results)
+            })
+            .await?
+        };
+        let all_found = |results: &[Option<T>]| results.iter().all(Option::is_some);
+        if let Some(cache) = self.cache.clone() {
+            let permit = cache.conn_permit().await;
+            results = find(permit, Box::new(cache), results, keys.clone(), f.clone()).await?;
+        }
+        if let Some(dht) = self.dht.clone() {
+            if !all_found(&results) {
+                let permit = dht.conn_permit().await;
+                results = find(permit, Box::new(dht), results, keys.clone(), f.clone()).await?;
+            }
+        }
+        if let Some(authored) = self.authored.clone() {
+            if !all_found(&results) {
+                let permit = authored.conn_permit().await;
+                results =
+                    find(permit, Box::new(authored), results, keys.clone(), f.clone()).await?;
+            }
+        }
+        if let Some(scratch) = &self.scratch {
+            if !all_found(&results) {
+                scratch.apply_and_then(|scratch| {
+                    for (key, result) in keys.iter().zip(results.iter_mut()) {
+                        if result.is_none() {
+                            *result = f(scratch, key)?;
+                        }
+                    }
+                    CascadeResult::Ok(())
+                })?;
+            }
+        }
+        Ok(results)
+    }
+
     /// Retrieve [`Entry`] from either locally or from an authority.
     /// Data might not have been validated yet by the authority.
     pub async fn retrieve_entry(
@@ -456,6 +571,35 @@ where
         Ok(result)
     }
 
+    /// Retrieve many [`SignedActionHashed`]s from either locally or from
+    /// authorities, in the same order as the hashes.
+    /// The local stores are searched for all of the actions at once,
+    /// and only the actions which aren't held locally are fetched.
+    /// Data might not have been validated yet by the authorities.
+    pub async fn retrieve_actions(
+        &mut self,
+        hashes: Vec<ActionHash>,
+        mut options: NetworkGetOptions,
+    ) -> CascadeResult<Vec<Option<SignedActionHashed>>> {
+        let mut results = self
+            .find_map_multi(hashes.clone(), |store, hash| Ok(store.get_action(hash)?))
+            .await?;
+        options.request_type = holochain_p2p::event::GetRequest::Pending;
+        for (hash, result) in hashes.into_iter().zip(results.iter_mut()) {
+            if result.is_some() {
+                continue;
+            }
+            self.fetch_record(hash.clone().into(), options.clone())
+                .await?;
+
+            // Check if we have the data now after the network call.
+            *result = self
+                .find_map(move |store| Ok(store.get_action(&hash)?))
+                .await?;
+        }
+        Ok(results)
+    }
+
     /// Retrieve data from either locally or from an authority.
     /// Data might not have been validated yet by the authority.
     pub async fn retrieve(
@@ -580,6 +724,17 @@ where
         action_hash: ActionHash,
         options: GetOptions,
     ) -> CascadeResult<Option<Record>> {
+        Ok(self
+            .dht_get_action_outcome(action_hash, options)
+            .await?
+            .into_option())
+    }
+
+    async fn dht_get_action_outcome(
+        &mut self,
+        action_hash: ActionHash,
+        options: GetOptions,
+    ) -> CascadeResult<GetOutcome<Record>> {
         let authoring = self.am_i_authoring(&action_hash.clone().into())?;
         let authority = self.am_i_an_authority(action_hash.clone().into()).await?;
         let query: GetLiveRecordQuery = self.construct_query_with_data_access(action_hash.clone());
@@ -593,25 +748,27 @@ where
         if let GetStrategy::Content = options.strategy {
             let results = self.cascading(query.clone()).await?;
             // We got a result so can short circuit.
-            if results.is_some() {
-                return Ok(results);
+            if let Some(record) = results {
+                return Ok(GetOutcome::Found(record));
             // We didn't get a result so if we are either authoring
             // or the authority there's nothing left to do.
             } else if authoring || authority {
-                return Ok(None);
+                return Ok(GetOutcome::NotFound);
             }
         }
 
         // If we are not in the process of authoring this hash or its
         // authority we need a network call.
-        if !(authoring || authority) {
-            self.fetch_record(action_hash.into(), options.into())
-                .await?;
-        }
+        let responses = if !(authoring || authority) {
+            self.fetch_record_from_authorities(action_hash.into(), options.into())
+                .await?
+        } else {
+            AuthorityResponses::NotAsked
+        };
 
         // Check if we have the data now after the network call.
         let results = self.cascading(query).await?;
-        Ok(results)
+        Ok(outcome_from_search(results, responses))
     }
 
     #[instrument(skip(self, options))]
@@ -622,6 +779,17 @@ where
         entry_hash: EntryHash,
         options: GetOptions,
     ) -> CascadeResult<Option<Record>> {
+        Ok(self
+            .dht_get_entry_outcome(entry_hash, options)
+            .await?
+            .into_option())
+    }
+
+    async fn dht_get_entry_outcome(
+        &mut self,
+        entry_hash: EntryHash,
+        options: GetOptions,
+    ) -> CascadeResult<GetOutcome<Record>> {
         let authoring = self.am_i_authoring(&entry_hash.clone().into())?;
         let authority = self.am_i_an_authority(entry_hash.clone().into()).await?;
         let query: GetLiveEntryQuery = self.construct_query_with_data_access(entry_hash.clone());
@@ -631,24 +799,27 @@ where
         if let GetStrategy::Content = options.strategy {
             let results = self.cascading(query.clone()).await?;
             // We got a result so can short circuit.
-            if results.is_some() {
-                return Ok(results);
+            if let Some(record) = results {
+                return Ok(GetOutcome::Found(record));
             // We didn't get a result so if we are either authoring
             // or the authority there's nothing left to do.
             } else if authoring || authority {
-                return Ok(None);
+                return Ok(GetOutcome::NotFound);
             }
         }
 
         // If we are not in the process of authoring this hash or its
         // authority we need a network call.
-        if !(authoring || authority) {
-            self.fetch_record(entry_hash.into(), options.into()).await?;
-        }
+        let responses = if !(authoring || authority) {
+            self.fetch_record_from_authorities(entry_hash.into(), options.into())
+                .await?
+        } else {
+            AuthorityResponses::NotAsked
+        };
 
         // Check if we have the data now after the network call.
         let results = self.cascading(query).await?;
-        Ok(results)
+        Ok(outcome_from_search(results, responses))
     }
 
     pub async fn get_concurrent<I: IntoIterator<Item = AnyDhtHash>>(
@@ -687,6 +858,20 @@ where
         }
     }
 
+    #[instrument(skip(self))]
+    /// The same as [`Cascade::dht_get`] but says why the record
+    /// wasn't found.
+    pub async fn dht_get_outcome(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetOptions,
+    ) -> CascadeResult<GetOutcome<Record>> {
+        match *hash.hash_type() {
+            AnyDht::Entry => self.dht_get_entry_outcome(hash.into(), options).await,
+            AnyDht::Action => self.dht_get_action_outcome(hash.into(), options).await,
+        }
+    }
+
     #[instrument(skip(self))]
     pub async fn get_details(
         &mut self,
@@ -713,13 +898,18 @@ where
         key: WireLinkKey,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<Link>> {
+        let pagination = options.pagination.clone();
         let authority = self.am_i_an_authority(key.base.clone().into()).await?;
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
         let query = GetLinksQuery::new(key.base, key.type_query, key.tag);
         let results = self.cascading(query).await?;
-        Ok(results)
+        // The cache may hold links from other pages as well as the one fetched.
+        Ok(match pagination {
+            Some(pagination) => pagination.apply(results),
+            None => results,
+        })
     }
 
     #[instrument(skip(self, key, options))]
@@ -739,6 +929,32 @@ where
         Ok(results)
     }
 
+    /// Fetch the actions which show that an agent's chain is forked or invalid
+    /// into the cache, so the evidence is still at hand once warrants can be issued.
+    async fn cache_chain_status_evidence(&mut self, agent: &AgentPubKey, status: &ChainStatus) {
+        let hashes: Vec<AnyDhtHash> = match status {
+            ChainStatus::Forked(fork) => {
+                warn!(?agent, ?fork, "Observed a forked source chain");
+                vec![
+                    fork.first_action.clone().into(),
+                    fork.second_action.clone().into(),
+                ]
+            }
+            ChainStatus::Invalid(head) => {
+                warn!(?agent, ?head, "Observed an invalid source chain");
+                vec![head.hash.clone().into()]
+            }
+            ChainStatus::Empty | ChainStatus::Valid(_) => return,
+        };
+        if let Err(e) = self.get_concurrent(hashes, GetOptions::content()).await {
+            warn!(
+                ?agent,
+                ?e,
+                "Failed to fetch the evidence of a bad source chain"
+            );
+        }
+    }
+
     #[instrument(skip(self, agent, query, options))]
     /// Get agent activity from agent activity authorities.
     /// Hashes are requested from the authority and cache for valid chains.
@@ -763,8 +979,12 @@ where
             let results = self
                 .fetch_agent_activity(agent.clone(), query.clone(), options.clone())
                 .await?;
-            let merged_response: AgentActivityResponse<ActionHash> =
+            let mut merged_response: AgentActivityResponse<ActionHash> =
                 agent_activity::merge_activities(agent.clone(), &options, results)?;
+            merged_response.warrants =
+                verified_warrants(std::mem::take(&mut merged_response.warrants)).await;
+            self.cache_chain_status_evidence(&agent, &merged_response.status)
+                .await;
             merged_response
         } else {
             match self.dht.clone() {
@@ -807,6 +1027,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -861,24 +1082,84 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
 
-    /// Get the validation package if it is cached without going to the network
-    pub fn get_validation_package_local(
+    /// Get the validation package for this action if it is held locally.
+    pub async fn get_validation_package_local(
         &self,
-        _hash: &ActionHash,
-    ) -> CascadeResult<Option<Vec<Record>>> {
+        hash: &ActionHash,
+    ) -> CascadeResult<Option<ValidationPackage>> {
+        if let Some(cache) = &self.cache {
+            let hash = hash.clone();
+            let package = cache
+                .async_reader(move |txn| get_validation_package(&txn, &hash))
+                .await?;
+            if package.is_some() {
+                return Ok(package);
+            }
+        }
+        if let Some(dht) = &self.dht {
+            let hash = hash.clone();
+            let package = dht
+                .async_reader(move |txn| get_validation_package(&txn, &hash))
+                .await?;
+            if package.is_some() {
+                return Ok(package);
+            }
+        }
+        if let Some(authored) = &self.authored {
+            let hash = hash.clone();
+            let package = authored
+                .async_reader(move |txn| get_validation_package(&txn, &hash))
+                .await?;
+            if package.is_some() {
+                return Ok(package);
+            }
+        }
         Ok(None)
     }
 
+    /// Get the validation package for this action, asking the agent
+    /// for it if it isn't held locally.
+    /// Packages received from the network are checked against the
+    /// action and the validation type it requires, and cached.
     pub async fn get_validation_package(
         &mut self,
-        _agent: AgentPubKey,
-        _action: &ActionHashed,
+        agent: AgentPubKey,
+        action: &ActionHashed,
+        required_validation_type: RequiredValidationType,
     ) -> CascadeResult<Option<ValidationPackage>> {
-        Ok(None)
+        if let Some(package) = self.get_validation_package_local(action.as_hash()).await? {
+            return Ok(Some(package));
+        }
+        let network = ok_or_return!(self.network.as_mut(), None);
+        let package = match network
+            .get_validation_package(agent, action.as_hash().clone())
+            .await?
+            .0
+        {
+            Some(package) => package,
+            None => return Ok(None),
+        };
+        if !verify_validation_package(&package, action.as_content(), required_validation_type).await
+        {
+            warn!(?action, "Ignoring an invalid validation package");
+            return Ok(None);
+        }
+        if let Some(cache) = &self.cache {
+            let hash = action.as_hash().clone();
+            let package = package.clone();
+            cache
+                .async_commit(move |txn| {
+                    insert_validation_package(txn, &hash, &package)?;
+                    CascadeResult::Ok(())
+                })
+                .await?;
+        }
+        Ok(Some(package))
     }
 
     fn am_i_authoring(&mut self, hash: &AnyDhtHash) -> CascadeResult<bool> {
@@ -901,3 +1182,49 @@ where
         }
     }
 }
+
+/// Drop any warrants from the network which were not signed by their warrantor.
+async fn verified_warrants(warrants: Vec<SignedWarrant>) -> Vec<SignedWarrant> {
+    let mut verified = Vec::with_capacity(warrants.len());
+    for warrant in warrants {
+        if verify_warrant(&warrant).await {
+            verified.push(warrant);
+        } else {
+            warn!(?warrant, "Ignoring a warrant with an invalid signature");
+        }
+    }
+    verified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::outcome_from_search;
+    use super::AuthorityResponses::*;
+    use super::GetOutcome;
+
+    #[test]
+    fn get_outcome_from_search() {
+        assert_eq!(GetOutcome::Found(1), outcome_from_search(Some(1), NotAsked));
+        assert_eq!(
+            GetOutcome::Found(1),
+            outcome_from_search(Some(1), Answered(0))
+        );
+        assert_eq!(GetOutcome::Found(1), outcome_from_search(Some(1), TimedOut));
+        assert_eq!(
+            GetOutcome::<u8>::NotFound,
+            outcome_from_search(None, NotAsked)
+        );
+        assert_eq!(
+            GetOutcome::<u8>::NotFound,
+            outcome_from_search(None, Answered(2))
+        );
+        assert_eq!(
+            GetOutcome::<u8>::Unavailable,
+            outcome_from_search(None, Answered(0))
+        );
+        assert_eq!(
+            GetOutcome::<u8>::TimedOut,
+            outcome_from_search(None, TimedOut)
+        );
+    }
+}
@@ -25,10 +25,12 @@ use holochain_state::mutations::set_validation_status;
 use holochain_state::mutations::set_when_integrated;
 use holochain_state::prelude::Query;
 use holochain_state::prelude::Txn;
+use holochain_state::warrants::get_warrants_for_basis;
 use holochain_types::activity::AgentActivityResponse;
 use holochain_types::db::DbRead;
 use holochain_types::db::DbWrite;
 use holochain_types::dht_op::DhtOpHashed;
+use holochain_types::dht_op::WarrantedOp;
 use holochain_types::dht_op::WireOps;
 use holochain_types::link::WireLinkKey;
 use holochain_types::link::WireLinkOps;
@@ -203,6 +205,14 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         todo!()
     }
 
+    async fn publish_warrants(
+        &self,
+        _basis: holo_hash::AnyDhtHash,
+        _warrants: Vec<WarrantedOp>,
+    ) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn send_validation_receipt(
         &self,
         _to_agent: AgentPubKey,
@@ -223,6 +233,10 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         todo!()
     }
 
+    async fn network_status(&self) -> actor::HolochainP2pResult<kitsune_p2p::actor::NetworkStatus> {
+        todo!()
+    }
+
     async fn join(
         &self,
         _agent: AgentPubKey,
@@ -381,6 +395,14 @@ impl HolochainP2pDnaT for MockNetwork {
         todo!()
     }
 
+    async fn publish_warrants(
+        &self,
+        _basis: holo_hash::AnyDhtHash,
+        _warrants: Vec<WarrantedOp>,
+    ) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn send_validation_receipt(
         &self,
         _to_agent: AgentPubKey,
@@ -401,6 +423,10 @@ impl HolochainP2pDnaT for MockNetwork {
         todo!()
     }
 
+    async fn network_status(&self) -> actor::HolochainP2pResult<kitsune_p2p::actor::NetworkStatus> {
+        todo!()
+    }
+
     async fn join(
         &self,
         _agent: AgentPubKey,
@@ -442,8 +468,11 @@ pub fn handle_get_entry_txn(
     hash: EntryHash,
     _options: holochain_p2p::event::GetOptions,
 ) -> WireEntryOps {
+    let basis = hash.clone().into();
     let query = GetEntryOpsQuery::new(hash);
-    query.run(Txn::from(txn)).unwrap()
+    let mut ops = query.run(Txn::from(txn)).unwrap();
+    ops.warrants = get_warrants_for_basis(txn, &basis).unwrap();
+    ops
 }
 
 /// Utility for network simulation response to get record.
@@ -452,8 +481,11 @@ pub fn handle_get_record_txn(
     hash: ActionHash,
     options: holochain_p2p::event::GetOptions,
 ) -> WireRecordOps {
+    let basis = hash.clone().into();
     let query = GetRecordOpsQuery::new(hash, options);
-    query.run(Txn::from(txn)).unwrap()
+    let mut ops = query.run(Txn::from(txn)).unwrap();
+    ops.warrants = get_warrants_for_basis(txn, &basis).unwrap();
+    ops
 }
 
 /// Utility for network simulation response to get.
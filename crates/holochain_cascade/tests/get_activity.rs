@@ -1,10 +1,16 @@
+use ::fixt::prelude::*;
 use ghost_actor::dependencies::observability;
+use holo_hash::ActionHash;
+use holo_hash::EntryHash;
 use holochain_cascade::test_utils::*;
 use holochain_cascade::Cascade;
 use holochain_state::prelude::test_cache_db;
 use holochain_state::prelude::test_dht_db;
 use holochain_types::activity::*;
-use holochain_zome_types::ChainStatus;
+use holochain_types::dht_op::DhtOp;
+use holochain_types::dht_op::DhtOpHashed;
+use holochain_zome_types::fixt::*;
+use holochain_zome_types::*;
 use pretty_assertions::assert_eq;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -52,6 +58,89 @@ async fn get_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: vec![],
     };
     assert_eq!(r, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_activity_reports_fork_outside_of_query() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&authority.to_db(), hash_op);
+    }
+    for hash_op in td.store_ops.iter().cloned() {
+        fill_db(&authority.to_db(), hash_op);
+    }
+
+    // A second action at seq 5 forks the chain.
+    let valid_hashes = match &td.valid_hashes {
+        ChainItems::Hashes(hashes) => hashes.clone(),
+        _ => unreachable!(),
+    };
+    let entry = Entry::App(fixt!(AppEntryBytes));
+    let mut fork = fixt!(Create);
+    fork.author = td.agent.clone();
+    fork.action_seq = 5;
+    fork.prev_action = valid_hashes[4].1.clone();
+    fork.entry_hash = EntryHash::with_data_sync(&entry);
+    let fork = Action::Create(fork);
+    let fork_hash = ActionHash::with_data_sync(&fork);
+    fill_db(
+        &authority.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fork.clone(),
+        )),
+    );
+    fill_db(
+        &authority.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::StoreRecord(
+            fixt!(Signature),
+            fork,
+            Some(Box::new(entry)),
+        )),
+    );
+
+    let options = holochain_p2p::actor::GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    // The fork is before the range of the query.
+    let query =
+        ChainQueryFilter::new().sequence_range(ChainQueryFilterRange::ActionSeqRange(10, 20));
+    let r = cascade
+        .get_agent_activity(td.agent.clone(), query, options)
+        .await
+        .unwrap();
+
+    match r.status {
+        ChainStatus::Forked(ChainFork { fork_seq, .. }) => assert_eq!(fork_seq, 5),
+        status => panic!("Expected a forked chain but got {:?}", status),
+    }
+
+    // The forked actions were fetched into the cache.
+    let mut cascade = Cascade::empty().with_cache(cache.to_db());
+    let record = cascade
+        .dht_get(fork_hash.into(), GetOptions::content())
+        .await
+        .unwrap();
+    assert!(record.is_some());
+}
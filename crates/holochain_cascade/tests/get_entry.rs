@@ -14,6 +14,7 @@ use holochain_zome_types::Details;
 use holochain_zome_types::EntryDetails;
 use holochain_zome_types::EntryDhtStatus;
 use holochain_zome_types::GetOptions;
+use holochain_zome_types::GetOutcome;
 use holochain_zome_types::RecordDetails;
 use holochain_zome_types::ValidationStatus;
 
@@ -221,6 +222,18 @@ async fn assert_can_retrieve<N: HolochainP2pDnaT + Clone + Send + 'static>(
         .expect("Failed to retrieve action");
 
     assert_eq!(*r.as_hash(), td_entry.create_hash);
+
+    // - Retrieve many actions at once
+    let r = cascade
+        .retrieve_actions(vec![td_entry.create_hash.clone(); 2], options.into())
+        .await
+        .unwrap();
+
+    assert_eq!(r.len(), 2);
+    for r in r {
+        let r = r.expect("Failed to retrieve actions");
+        assert_eq!(*r.as_hash(), td_entry.create_hash);
+    }
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -246,6 +259,77 @@ async fn entry_not_authority_or_authoring() {
     assert_can_get(&td_entry, &td_record, &mut cascade, GetOptions::latest()).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn outcome_not_found_unavailable_or_timed_out() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td_entry = EntryTestData::create();
+    let td_record = RecordTestData::create();
+    fill_db(&authority.to_db(), td_record.any_store_record_op.clone());
+
+    // - An authority answers but doesn't hold the entry.
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let r = cascade
+        .dht_get_outcome(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(r, GetOutcome::NotFound);
+
+    let r = cascade
+        .dht_get_outcome(
+            td_record.any_action_hash.clone().into(),
+            GetOptions::latest(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(r, GetOutcome::Found(_)));
+
+    // - No authority answers.
+    let network = PassThroughNetwork::authority_for_nothing(vec![]);
+    let mut cascade = Cascade::empty().with_network(network, test_cache_db().to_db());
+
+    let r = cascade
+        .dht_get_outcome(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(r, GetOutcome::Unavailable);
+
+    let r = cascade
+        .dht_get_outcome(
+            td_record.any_action_hash.clone().into(),
+            GetOptions::latest(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(r, GetOutcome::Unavailable);
+
+    // - The authorities asked don't answer in time.
+    let mut mock = MockHolochainP2pDnaT::new();
+    mock.expect_authority_for_hash().returning(|_| Ok(false));
+    mock.expect_get().returning(|_, _| {
+        use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::{
+            KitsuneError, KitsuneErrorKind,
+        };
+        use holochain_p2p::kitsune_p2p::KitsuneP2pError;
+        Err(KitsuneP2pError::from(KitsuneError::from(KitsuneErrorKind::TimedOut)).into())
+    });
+    let mock = MockNetwork::new(mock);
+    let mut cascade = Cascade::empty().with_network(mock, test_cache_db().to_db());
+
+    let r = cascade
+        .dht_get_outcome(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(r, GetOutcome::TimedOut);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn entry_authoring() {
     observability::test_run().ok();
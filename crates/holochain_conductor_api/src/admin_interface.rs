@@ -1,9 +1,18 @@
 use holo_hash::*;
+use holochain_p2p::actor::PeerBlockTarget;
+use holochain_state::integrated_ops::IntegratedDhtOpsPage;
+use holochain_state::integrated_ops::IntegratedDhtOpsQuery;
+use holochain_state::validation_receipts::ValidationReceiptCount;
 use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
+use kitsune_p2p::actor::NetworkStatus;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::SpaceGossipStatus;
+use std::collections::BTreeSet;
 
-use crate::{FullStateDump, InstalledAppInfo};
+use crate::{
+    ConductorStateDump, DatabaseCompaction, FullStateDump, InstalledAppInfo, WasmCacheStats,
+};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -38,15 +47,56 @@ pub enum AdminRequest {
 
     /// Clone a DNA (in the biological sense), thus creating a new `Cell`.
     ///
-    /// Using the provided, already-registered DNA, create a new DNA with a unique
-    /// ID and the specified properties, create a new cell from this cloned DNA,
-    /// and add the cell to the specified app.
+    /// Using the DNA of the given role of an installed app, create a new DNA
+    /// with the specified (or a random) unique ID and properties, create a new
+    /// cell from this cloned DNA for the app's agent, and add the cell to the
+    /// role's clones. Fails if the role's clone limit has been reached.
     ///
     /// # Returns
     ///
     /// [`AdminResponse::CloneCellCreated`]
     CreateCloneCell(Box<CreateCloneCellPayload>),
 
+    /// Disable a cloned `Cell` of an app.
+    ///
+    /// The cell is removed from its role and stops running, freeing up a
+    /// place under the role's clone limit. Its data is kept.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CloneCellDisabled`]
+    DisableCloneCell(Box<DisableCloneCellPayload>),
+
+    /// Migrate the agent of an app role to a new version of the role's DNA.
+    ///
+    /// The new DNA must already be registered with [`AdminRequest::RegisterDna`].
+    /// The `migrate_agent` callbacks of both DNAs are run, and if they all pass,
+    /// the old source chain is closed, a new one is opened for the new DNA and
+    /// the new cell replaces the old one in the app. The data of the old cell is kept.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellMigrated`]
+    MigrateCell(Box<MigrateCellPayload>),
+
+    /// Move the agent of an app to a new agent key, for when the old key
+    /// is lost or compromised.
+    ///
+    /// A new key is generated in the keystore and checked with the conductor's
+    /// DPKI service, and is refused if the conductor has none. Each cell of the
+    /// app then records the new key on its source chain, and is replaced in the
+    /// app by a cell with the same DNA, the same membrane proof and the new key.
+    /// Either every cell records the new key or none does.
+    /// The data of the old cells is kept.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppAgentKeyRotated`]
+    RotateAppAgentKey {
+        /// The app whose agent key to rotate
+        installed_app_id: InstalledAppId,
+    },
+
     /// Install an app from a list of DNA paths.
     ///
     /// Triggers genesis to be run on all cells and to be stored.
@@ -71,6 +121,8 @@ pub enum AdminRequest {
     /// An app is intended for use by
     /// one and only one Agent and for that reason it takes an `AgentPubKey` and
     /// installs all the DNAs with that `AgentPubKey`, forming new cells.
+    /// The UID and properties of each role's DNA can be overridden, so that
+    /// no separate [`RegisterDna`] calls are needed.
     /// See [`InstallAppBundlePayload`] for full details on the configuration.
     ///
     /// Note that the new app will not be enabled automatically after installation
@@ -81,6 +133,7 @@ pub enum AdminRequest {
     /// [`AdminResponse::AppInstalled`]
     ///
     /// [`EnableApp`]: AdminRequest::EnableApp
+    /// [`RegisterDna`]: AdminRequest::RegisterDna
     InstallAppBundle(Box<InstallAppBundlePayload>),
 
     /// Uninstalls the app specified by argument `installed_app_id` from the conductor.
@@ -174,6 +227,31 @@ pub enum AdminRequest {
     #[deprecated = "alias for DisableApp"]
     DeactivateApp { installed_app_id: InstalledAppId },
 
+    /// Pauses a single cell of an installed app.
+    ///
+    /// A paused cell can no longer be called and stops its own background work,
+    /// but the rest of its app keeps running. The cell stays paused across
+    /// reboots of the conductor until it is resumed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellPaused`]
+    PauseCell {
+        /// The cell to pause
+        cell_id: CellId,
+    },
+
+    /// Resumes a cell paused with [`AdminRequest::PauseCell`].
+    /// The cell is started again if its app is running.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellResumed`]
+    ResumeCell {
+        /// The cell to resume
+        cell_id: CellId,
+    },
+
     StartApp {
         /// The app ID to (re)start
         installed_app_id: InstalledAppId,
@@ -246,6 +324,9 @@ pub enum AdminRequest {
 
     /// Dump the network metrics tracked by kitsune.
     ///
+    /// This includes the redundancy (how many agents hold the same ops)
+    /// at each basis of the local storage arc.
+    ///
     /// # Returns
     ///
     /// [`AdminResponse::NetworkMetricsDumped`]
@@ -254,6 +335,116 @@ pub enum AdminRequest {
         dna_hash: Option<DnaHash>,
     },
 
+    /// Report how far gossip has got in the space of a DNA.
+    ///
+    /// This lists the gossip rounds in progress, how many rounds have
+    /// completed and how many ops have been exchanged, along with when
+    /// each peer whose arc overlaps ours last finished a round with us.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::GossipStatusRequested`]
+    RequestGossipStatus {
+        /// The DNA hash of the space to report on.
+        dna_hash: DnaHash,
+    },
+
+    /// Request whether the network of each running DNA can be reached.
+    ///
+    /// A DNA is `offline` when neither its peers nor its bootstrap service
+    /// can be reached, and `partial` when only one of them can. Ops authored
+    /// while offline are published once the network is reachable again.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::NetworkStatusRequested`]
+    RequestNetworkStatus,
+
+    /// Dump an overview of the whole conductor: the installed apps,
+    /// the status of every cell, how many ops are waiting in the validation
+    /// and integration queues of each DNA, and the network bindings.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ConductorStateDumped`]
+    DumpConductorState,
+
+    /// Report how many compiled wasm modules and instances the conductor
+    /// has cached, and how often modules were found in the caches rather
+    /// than compiled.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::WasmCacheStatsDumped`]
+    DumpWasmCacheStats,
+
+    /// Empty the conductor's wasm caches, in memory and on disk.
+    /// Every zome will be compiled again the next time it is called.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::WasmCachePurged`]
+    PurgeWasmCache,
+
+    /// Snapshot all of the conductor's databases into a single archive
+    /// file at `path` on the conductor's host, replacing any file there.
+    ///
+    /// The snapshot is taken while the conductor keeps running. Each
+    /// database is copied consistently, but databases are copied one after
+    /// another. Restore the archive by starting holochain with `--restore`.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ConductorBackedUp`]
+    BackupConductor {
+        /// Where to write the archive.
+        path: std::path::PathBuf,
+    },
+
+    /// Compact all of the conductor's databases, giving the space left
+    /// by deleted data back to the file system.
+    /// Writes to each database wait while it is being compacted.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DatabasesCompacted`]
+    CompactDatabases,
+
+    /// Count the validation receipts collected for each op authored by
+    /// the cell specified by argument `cell_id`.
+    ///
+    /// Authorities send a receipt back to the author once they have
+    /// validated an op. Once an op has enough receipts it is no longer
+    /// published.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ValidationReceiptsCounted`]
+    CountValidationReceipts {
+        /// The cell ID whose authored ops to count receipts for
+        cell_id: Box<CellId>,
+    },
+
+    /// List the ops this conductor has integrated for the DNA specified by
+    /// argument `dna_hash`, to inspect what an authority actually holds.
+    ///
+    /// The ops can be filtered by type, basis hash and when they were
+    /// integrated. They are returned a page at a time; pass the cursor
+    /// from one page in the query to get the next.
+    ///
+    /// **Warning**: this API call is subject to change, and will not be available to hApps.
+    /// This is meant to be used by introspection tooling.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::IntegratedDhtOpsQueried`]
+    QueryIntegratedDhtOps {
+        /// The DNA whose integrated ops to list
+        dna_hash: DnaHash,
+        /// Which ops to list
+        query: IntegratedDhtOpsQuery,
+    },
+
     /// Add a list of agents to this conductor's peer store.
     ///
     /// This is a way of shortcutting peer discovery and is useful for testing.
@@ -288,6 +479,40 @@ pub enum AdminRequest {
         cell_id: Option<CellId>,
     },
 
+    /// Block a misbehaving peer in the network of the DNA specified by
+    /// argument `dna_hash`.
+    ///
+    /// Blocking an agent stops this conductor storing its agent info,
+    /// calling it and publishing to it, and drops calls, publishes and
+    /// gossip for the DNA from the nodes it has been seen on. Blocking a node by its tls cert
+    /// closes connections with it and drops everything it sends, for
+    /// every DNA. Blocks are persisted, so they survive restarts.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::PeerBlocked`]
+    BlockPeer {
+        /// The DNA whose network to block the peer in.
+        dna_hash: DnaHash,
+        /// The agent or node to block.
+        target: PeerBlockTarget,
+        /// When the block ends. If `None`, the peer stays
+        /// blocked until [`AdminRequest::UnblockPeer`] is called.
+        until: Option<Timestamp>,
+    },
+
+    /// Remove a block added with [`AdminRequest::BlockPeer`].
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::PeerUnblocked`]
+    UnblockPeer {
+        /// The DNA whose network the peer was blocked in.
+        dna_hash: DnaHash,
+        /// The blocked agent or node.
+        target: PeerBlockTarget,
+    },
+
     /// Insert [`Record`]s into the source chain of the [`CellId`].
     ///
     /// All records must be authored and signed by the same agent.
@@ -333,6 +558,43 @@ pub enum AdminRequest {
         /// The records to be inserted into the source chain.
         records: Vec<Record>,
     },
+
+    /// Write a [`ZomeCallCapGrant`] onto the source chain of a cell,
+    /// granting a signing key access to some of its zome functions.
+    ///
+    /// This is how a UI which holds its own signing key is given
+    /// permission to call into a cell. The cell must be running.
+    ///
+    /// See [`GrantZomeCallCapabilityPayload`] for the access options.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ZomeCallCapabilityGranted`]
+    GrantZomeCallCapability(Box<GrantZomeCallCapabilityPayload>),
+
+    /// List the zome call capability grants on the source chain of a cell
+    /// which have not been revoked. The secrets of the grants are not included.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CapGrantsListed`]
+    ListCapGrants {
+        /// The cell whose grants to list
+        cell_id: CellId,
+    },
+
+    /// Revoke a zome call capability grant by deleting it from the
+    /// source chain of its cell. The cell must be running.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CapGrantRevoked`]
+    RevokeCapGrant {
+        /// The cell which wrote the grant
+        cell_id: CellId,
+        /// The action which wrote the grant, as found in a [`CapGrantInfo`]
+        action_hash: ActionHash,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -378,6 +640,21 @@ pub enum AdminResponse {
     /// The response contains the [`CellId`] of the newly created clone.
     CloneCellCreated(CellId),
 
+    /// The successful response to an [`AdminRequest::DisableCloneCell`].
+    ///
+    /// It means the clone was removed from its app and stopped.
+    CloneCellDisabled,
+
+    /// The successful response to an [`AdminRequest::MigrateCell`].
+    ///
+    /// The response contains the [`CellId`] of the cell for the new DNA.
+    CellMigrated(CellId),
+
+    /// The successful response to an [`AdminRequest::RotateAppAgentKey`].
+    ///
+    /// Contains the new [`AgentPubKey`] of the app.
+    AppAgentKeyRotated(AgentPubKey),
+
     /// The successful response to an [`AdminRequest::AddAdminInterfaces`].
     ///
     /// It means the `AdminInterface`s have successfully been added.
@@ -455,6 +732,14 @@ pub enum AdminResponse {
     #[deprecated = "alias for AppDisabled"]
     AppDeactivated,
 
+    /// The successful response to an [`AdminRequest::PauseCell`].
+    CellPaused,
+
+    /// The successful response to an [`AdminRequest::ResumeCell`].
+    ///
+    /// Contains the error if the cell was resumed but could not be started.
+    CellResumed { errors: Vec<(CellId, String)> },
+
     /// The successful response to an [`AdminRequest::DumpState`].
     ///
     /// The result contains a string of serialized JSON data which can be deserialized to access the
@@ -474,6 +759,41 @@ pub enum AdminResponse {
     /// The string is a JSON blob of the metrics results.
     NetworkMetricsDumped(String),
 
+    /// The successful response to an [`AdminRequest::RequestGossipStatus`].
+    GossipStatusRequested(SpaceGossipStatus),
+
+    /// The successful response to an [`AdminRequest::RequestNetworkStatus`].
+    ///
+    /// Contains the network status of each running DNA.
+    NetworkStatusRequested(Vec<(DnaHash, NetworkStatus)>),
+
+    /// The successful response to an [`AdminRequest::DumpConductorState`].
+    ConductorStateDumped(Box<ConductorStateDump>),
+
+    /// The successful response to an [`AdminRequest::DumpWasmCacheStats`].
+    WasmCacheStatsDumped(WasmCacheStats),
+
+    /// The successful response to an [`AdminRequest::PurgeWasmCache`].
+    WasmCachePurged,
+
+    /// The successful response to an [`AdminRequest::BackupConductor`].
+    ConductorBackedUp,
+
+    /// The successful response to an [`AdminRequest::CompactDatabases`].
+    ///
+    /// Contains how many bytes were reclaimed from each database.
+    DatabasesCompacted(Vec<DatabaseCompaction>),
+
+    /// The successful response to an [`AdminRequest::CountValidationReceipts`].
+    ///
+    /// Contains the number of receipts for each op authored by the cell.
+    ValidationReceiptsCounted(Vec<ValidationReceiptCount>),
+
+    /// The successful response to an [`AdminRequest::QueryIntegratedDhtOps`].
+    ///
+    /// Contains one page of the matching ops.
+    IntegratedDhtOpsQueried(IntegratedDhtOpsPage),
+
     /// The successful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -484,8 +804,63 @@ pub enum AdminResponse {
     /// This is all the agent info that was found for the request.
     AgentInfoRequested(Vec<AgentInfoSigned>),
 
+    /// The successful response to an [`AdminRequest::BlockPeer`].
+    PeerBlocked,
+
+    /// The successful response to an [`AdminRequest::UnblockPeer`].
+    PeerUnblocked,
+
     /// The successful response to an [`AdminRequest::AddRecords`].
     RecordsAdded,
+
+    /// The successful response to an [`AdminRequest::GrantZomeCallCapability`].
+    ///
+    /// Contains the secret the signing key must send with its zome calls,
+    /// or `None` if the grant is unrestricted.
+    ZomeCallCapabilityGranted(Option<CapSecret>),
+
+    /// The successful response to an [`AdminRequest::ListCapGrants`].
+    CapGrantsListed(Vec<CapGrantInfo>),
+
+    /// The successful response to an [`AdminRequest::RevokeCapGrant`].
+    CapGrantRevoked,
+}
+
+/// A zome call capability grant on the source chain of a cell,
+/// without its secret.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CapGrantInfo {
+    /// The action which wrote the grant, used to revoke it
+    pub action_hash: ActionHash,
+    /// When the grant was written
+    pub created_at: Timestamp,
+    /// The tag of the grant
+    pub tag: String,
+    /// The zome functions the grant gives access to
+    pub functions: GrantedFunctions,
+    /// How the grant is restricted
+    pub access: ZomeCallCapAccess,
+    /// The agents an assigned grant is assigned to
+    pub assignees: BTreeSet<AgentPubKey>,
+}
+
+impl CapGrantInfo {
+    /// The info about a grant written by this action.
+    pub fn new(action_hash: ActionHash, created_at: Timestamp, grant: ZomeCallCapGrant) -> Self {
+        let (access, assignees) = match grant.access {
+            CapAccess::Unrestricted => (ZomeCallCapAccess::Unrestricted, BTreeSet::new()),
+            CapAccess::Transferable { .. } => (ZomeCallCapAccess::Transferable, BTreeSet::new()),
+            CapAccess::Assigned { assignees, .. } => (ZomeCallCapAccess::Assigned, assignees),
+        };
+        Self {
+            action_hash,
+            created_at,
+            tag: grant.tag,
+            functions: grant.functions,
+            access,
+            assignees,
+        }
+    }
 }
 
 /// Error type that goes over the websocket wire.
@@ -509,6 +884,8 @@ pub enum ExternalApiWireError {
     ActivateApp(String),
     /// The zome call is unauthorized.
     ZomeCallUnauthorized(String),
+    /// The request about capability grants is unauthorized.
+    CapGrantUnauthorized(String),
     /// A countersigning session has failed.
     CountersigningSessionError(String),
 }
@@ -1,4 +1,4 @@
-use crate::{signal_subscription::SignalSubscription, ExternalApiWireError};
+use crate::{signal_subscription::SignalSubscription, CapGrantInfo, ExternalApiWireError};
 use holo_hash::AgentPubKey;
 use holochain_types::prelude::*;
 
@@ -39,9 +39,101 @@ pub enum AppRequest {
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`].
+    /// Call a zome function with a call signed by its provenance.
+    /// See [`SignedZomeCall`] for what must be signed.
+    ///
+    /// The conductor checks the signature, the expiry and that the nonce
+    /// hasn't been used before by the same provenance, before making the call.
+    /// Unless `danger_allow_unsigned_zome_calls` is set in its config,
+    /// a conductor only accepts zome calls made this way.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCall`]
+    SignedZomeCall(Box<SignedZomeCall>),
+
+    /// Call several zome functions in a single request.
+    /// Each call is checked like the single call request of its kind,
+    /// see [`BatchZomeCall`].
+    ///
+    /// By default the calls are made one after the other, in order, so each
+    /// call sees the source chain writes of the calls before it.
+    /// If `concurrent` is true they are all made at once instead.
+    /// A failing call does not stop the other calls.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCallBatch`]
+    ZomeCallBatch {
+        /// The zome calls to make
+        calls: Vec<BatchZomeCall>,
+        /// Make the calls concurrently rather than in order.
+        /// The conductor limits how many of them run at once.
+        #[serde(default)]
+        concurrent: bool,
+    },
+
+    /// Choose which cells of an app this connection receives signals from.
+    ///
+    /// A connection which has not subscribed to any app receives the signals
+    /// of every cell. Once it has subscribed, it only receives the signals of
+    /// the cells its subscriptions allow, plus system signals. Subscribing
+    /// again to the same app replaces that app's filters.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalsSubscribed`]
     SignalSubscription(SignalSubscription),
+
+    /// Acknowledge a signal which was emitted with `emit_signal_ack_required`,
+    /// using the id it was sent with.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalAcked`]
+    AckSignal {
+        /// The id of the signal
+        id: u64,
+    },
+
+    /// Get the number of ack-required signals from a cell which were never
+    /// acknowledged, either because no client was connected or because
+    /// no acknowledgement arrived in time.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::UndeliveredSignalCount`]
+    UndeliveredSignalCount {
+        /// The cell which emitted the signals
+        cell_id: CellId,
+    },
+
+    /// Clone the DNA of a role of an app into a new `Cell`.
+    /// See [`CreateCloneCellPayload`].
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::CloneCellCreated`]
+    CreateCloneCell(Box<CreateCloneCellPayload>),
+
+    /// Disable a cloned `Cell` of an app, keeping its data.
+    /// See [`DisableCloneCellPayload`].
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::CloneCellDisabled`]
+    DisableCloneCell(Box<DisableCloneCellPayload>),
+
+    /// Create, list or revoke the zome call capability grants on the source
+    /// chain of a cell of an app. The request must be signed by the agent
+    /// of the cell, and fails if the cell is not part of the app.
+    /// See [`SignedCapGrantRequest`].
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::CapGrantCreated`], [`AppResponse::CapGrantsListed`]
+    /// or [`AppResponse::CapGrantRevoked`], depending on the request
+    SignedCapGrantRequest(Box<SignedCapGrantRequest>),
 }
 
 /// Represents the possible responses to an [`AppRequest`].
@@ -72,6 +164,43 @@ pub enum AppResponse {
 
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::ZomeCallBatch`].
+    ///
+    /// Contains one response per call, in the same order as the calls.
+    /// Each is either an [`AppResponse::ZomeCall`] or an [`AppResponse::Error`].
+    ZomeCallBatch(Vec<AppResponse>),
+
+    /// The successful response to an [`AppRequest::AckSignal`].
+    ///
+    /// `false` if the signal is unknown, was already acknowledged or
+    /// was acknowledged too late and has been counted as undelivered.
+    SignalAcked(bool),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    SignalsSubscribed,
+
+    /// The successful response to an [`AppRequest::UndeliveredSignalCount`].
+    UndeliveredSignalCount(usize),
+
+    /// The successful response to an [`AppRequest::CreateCloneCell`].
+    ///
+    /// The response contains the [`CellId`] of the newly created clone.
+    CloneCellCreated(CellId),
+
+    /// The successful response to an [`AppRequest::DisableCloneCell`].
+    CloneCellDisabled,
+
+    /// The successful response to a [`CapGrantRequest::Create`].
+    ///
+    /// The response contains the secret of the new grant, unless it is unrestricted.
+    CapGrantCreated(Option<CapSecret>),
+
+    /// The successful response to a [`CapGrantRequest::List`].
+    CapGrantsListed(Vec<CapGrantInfo>),
+
+    /// The successful response to a [`CapGrantRequest::Revoke`].
+    CapGrantRevoked,
 }
 
 /// The data provided over an app interface in order to make a zome call
@@ -99,6 +228,134 @@ pub struct ZomeCall {
     pub provenance: AgentPubKey,
 }
 
+/// A random value which a provenance may only use once to sign a zome call.
+pub type ZomeCallNonce = [u8; 32];
+
+/// A [`ZomeCall`] signed by its provenance, which proves that the call was
+/// made by the holder of the provenance's private key.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SignedZomeCall {
+    /// The zome call
+    pub call: ZomeCall,
+    /// A random value which makes the signature unique,
+    /// so that a signed call can't be replayed
+    pub nonce: ZomeCallNonce,
+    /// When the signature stops being accepted
+    pub expires_at: Timestamp,
+    /// The signature by the provenance of the bytes from [`SignedZomeCall::data_to_sign`]
+    pub signature: Signature,
+}
+
+/// A zome call in an [`AppRequest::ZomeCallBatch`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum BatchZomeCall {
+    /// Made like an [`AppRequest::ZomeCall`], so it is refused by a
+    /// conductor without `danger_allow_unsigned_zome_calls` set.
+    Unsigned(ZomeCall),
+    /// Made like an [`AppRequest::SignedZomeCall`].
+    Signed(SignedZomeCall),
+}
+
+impl SignedZomeCall {
+    /// The bytes the provenance signs: the msgpack encoding
+    /// of the call, the nonce and the expiry, in that order.
+    pub fn data_to_sign(
+        call: &ZomeCall,
+        nonce: &ZomeCallNonce,
+        expires_at: Timestamp,
+    ) -> Result<Vec<u8>, SerializedBytesError> {
+        holochain_serialized_bytes::encode(&(call, nonce, expires_at))
+    }
+}
+
+/// A change to, or a look at, the zome call capability grants of a cell of an app,
+/// made in an [`AppRequest::SignedCapGrantRequest`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum CapGrantRequest {
+    /// Write a zome call capability grant for a signing key onto the source
+    /// chain of a running cell of the app.
+    Create {
+        /// The app the cell belongs to
+        installed_app_id: InstalledAppId,
+        /// The grant to write
+        payload: GrantZomeCallCapabilityPayload,
+    },
+    /// List the grants on the source chain of the cell which have not been
+    /// revoked. The secrets of the grants are not included.
+    List {
+        /// The app the cell belongs to
+        installed_app_id: InstalledAppId,
+        /// The cell whose grants to list
+        cell_id: CellId,
+    },
+    /// Revoke a grant by deleting it from the source chain of the cell.
+    /// The cell must be running.
+    Revoke {
+        /// The app the cell belongs to
+        installed_app_id: InstalledAppId,
+        /// The cell which wrote the grant
+        cell_id: CellId,
+        /// The action which wrote the grant, as found in a [`CapGrantInfo`]
+        action_hash: ActionHash,
+    },
+}
+
+impl CapGrantRequest {
+    /// The app the request is made for.
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        match self {
+            Self::Create {
+                installed_app_id, ..
+            }
+            | Self::List {
+                installed_app_id, ..
+            }
+            | Self::Revoke {
+                installed_app_id, ..
+            } => installed_app_id,
+        }
+    }
+
+    /// The cell whose grants the request is about.
+    pub fn cell_id(&self) -> &CellId {
+        match self {
+            Self::Create { payload, .. } => &payload.cell_id,
+            Self::List { cell_id, .. } | Self::Revoke { cell_id, .. } => cell_id,
+        }
+    }
+}
+
+/// A [`CapGrantRequest`] signed by the agent of its cell, which proves that
+/// the request was made by the holder of the agent's private key.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SignedCapGrantRequest {
+    /// The request
+    pub request: CapGrantRequest,
+    /// A random value which makes the signature unique,
+    /// so that a signed request can't be replayed.
+    /// It shares its uses with the nonces of signed zome calls.
+    pub nonce: ZomeCallNonce,
+    /// When the signature stops being accepted
+    pub expires_at: Timestamp,
+    /// The signature by the agent of the cell of the bytes from
+    /// [`SignedCapGrantRequest::data_to_sign`]
+    pub signature: Signature,
+}
+
+impl SignedCapGrantRequest {
+    /// The bytes the agent signs: the msgpack encoding
+    /// of the request, the nonce and the expiry, in that order.
+    pub fn data_to_sign(
+        request: &CapGrantRequest,
+        nonce: &ZomeCallNonce,
+        expires_at: Timestamp,
+    ) -> Result<Vec<u8>, SerializedBytesError> {
+        holochain_serialized_bytes::encode(&(request, nonce, expires_at))
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
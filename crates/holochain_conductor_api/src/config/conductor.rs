@@ -7,6 +7,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 mod admin_interface_config;
+mod cell_quota_config;
 mod dpki_config;
 #[allow(missing_docs)]
 mod error;
@@ -17,12 +18,14 @@ pub mod paths;
 pub use paths::DatabaseRootPath;
 
 pub use super::*;
+pub use cell_quota_config::CellQuotaConfig;
 pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
 pub use keystore_config::KeystoreConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
+use std::path::PathBuf;
 
 // TODO change types from "stringly typed" to Url2
 /// All the config information for the conductor
@@ -56,6 +59,58 @@ pub struct ConductorConfig {
     ///
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// Optional limits on the resources each cell may use.
+    #[serde(default)]
+    pub cell_quotas: Option<CellQuotaConfig>,
+
+    /// The number of validation receipts an authored op needs before it
+    /// stops being republished, when its entry def doesn't set
+    /// `required_validations`. Defaults to 5.
+    #[serde(default)]
+    pub default_required_validations: Option<u8>,
+
+    /// Optional directory to keep compiled wasm modules in, named by the hash
+    /// of their wasm, so that they don't need compiling again after a restart.
+    /// If omitted, compiled modules are only cached in memory.
+    ///
+    /// The modules in this directory are loaded as native code without being
+    /// validated, so it must be trusted: only the conductor's user should be
+    /// able to write to it.
+    #[serde(default)]
+    pub wasm_cache_path: Option<PathBuf>,
+
+    /// The number of wasm operations a single zome call or callback may run
+    /// before it is stopped with an error, so that a zome stuck in a loop
+    /// can't hold a conductor thread forever. Defaults to 10 billion.
+    #[serde(default)]
+    pub max_wasm_ops_per_call: Option<u64>,
+
+    /// How many milliseconds a single zome call or callback may run
+    /// before it is stopped with an error. Unlimited by default.
+    #[serde(default)]
+    pub max_wasm_time_per_call_ms: Option<u64>,
+
+    /// Encrypt the databases of each cell at rest with a key kept in lair.
+    /// The conductor must be built with the `db-encryption` feature and use
+    /// a lair keystore, and this can't be changed for existing databases.
+    /// Defaults to false.
+    #[serde(default)]
+    pub db_encryption: bool,
+
+    /// How many hours to wait between compacting all of the databases,
+    /// which gives the space left by deleted data back to the file system.
+    /// If omitted, databases are only compacted through the admin API.
+    #[serde(default)]
+    pub db_compaction_interval_hours: Option<u64>,
+
+    /// Also accept unsigned zome calls over app interfaces, made with
+    /// `AppRequest::ZomeCall`. By default only calls signed by their provenance,
+    /// with `AppRequest::SignedZomeCall`, are accepted. With this set, any process
+    /// which can reach an app interface can call any zome as any installed agent,
+    /// so it should only be used in tests. Defaults to false.
+    #[serde(default)]
+    pub danger_allow_unsigned_zome_calls: bool,
     //
     //
     // Which signals to emit
@@ -127,6 +182,14 @@ pub mod tests {
                 keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
+                cell_quotas: None,
+                default_required_validations: None,
+                wasm_cache_path: None,
+                max_wasm_ops_per_call: None,
+                max_wasm_time_per_call_ms: None,
+                db_encryption: false,
+                db_compaction_interval_hours: None,
+                danger_allow_unsigned_zome_calls: false,
             }
         );
     }
@@ -153,6 +216,7 @@ pub mod tests {
       - driver:
           type: websocket
           port: 1234
+          auth_token: s3cret
 
     network:
       bootstrap_service: https://bootstrap-staging.holo.host
@@ -177,6 +241,24 @@ pub mod tests {
       network_type: quic_bootstrap
 
     db_sync_strategy: Fast
+
+    cell_quotas:
+      max_storage_bytes: 1000000
+      max_concurrent_zome_calls: 8
+
+    default_required_validations: 3
+
+    wasm_cache_path: /path/to/wasm_cache
+
+    max_wasm_ops_per_call: 1000000
+
+    max_wasm_time_per_call_ms: 30000
+
+    db_encryption: true
+
+    db_compaction_interval_hours: 24
+
+    danger_allow_unsigned_zome_calls: true
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -217,10 +299,25 @@ pub mod tests {
                     danger_passphrase_insecure_from_config: "test-passphrase".to_string(),
                 },
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::Websocket {
+                        port: 1234,
+                        auth_token: Some("s3cret".to_string()),
+                    }
                 }]),
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
+                cell_quotas: Some(CellQuotaConfig {
+                    max_storage_bytes: Some(1_000_000),
+                    max_concurrent_zome_calls: Some(8),
+                    max_wasm_time_per_minute_ms: None,
+                }),
+                default_required_validations: Some(3),
+                wasm_cache_path: Some(PathBuf::from("/path/to/wasm_cache")),
+                max_wasm_ops_per_call: Some(1_000_000),
+                max_wasm_time_per_call_ms: Some(30_000),
+                db_encryption: true,
+                db_compaction_interval_hours: Some(24),
+                danger_allow_unsigned_zome_calls: true,
             }
         );
     }
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Limits on the resources each cell may use.
+///
+/// Every limit is optional and is not enforced when omitted.
+/// A zome call made while a cell is over any limit fails with a quota error,
+/// as does a zome call whose writes would take the cell over a limit.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq, Eq)]
+pub struct CellQuotaConfig {
+    /// The maximum number of bytes of actions and entries a cell may author.
+    #[serde(default)]
+    pub max_storage_bytes: Option<u64>,
+
+    /// The maximum number of zome calls a cell may be running at once.
+    #[serde(default)]
+    pub max_concurrent_zome_calls: Option<usize>,
+
+    /// The maximum time in milliseconds a cell may spend running zome calls
+    /// in any one minute. Only time spent running wasm counts, not time
+    /// spent waiting on host functions such as network gets.
+    #[serde(default)]
+    pub max_wasm_time_per_minute_ms: Option<u64>,
+}
@@ -28,6 +28,14 @@ pub enum InterfaceDriver {
     Websocket {
         /// The port on which to establish the WebsocketListener
         port: u16,
+        /// If set, clients must present this token to connect, either as
+        /// the `token` query parameter of the url they connect to or as an
+        /// `Authorization: Bearer <token>` header.
+        /// Connections without it are refused with `401 Unauthorized`.
+        /// Only admin interfaces check it; a conductor refuses to start
+        /// app interfaces which set it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        auth_token: Option<String>,
     },
 }
 
@@ -35,7 +43,14 @@ impl InterfaceDriver {
     /// Get the port for this driver.
     pub fn port(&self) -> u16 {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port, .. } => *port,
+        }
+    }
+
+    /// Get the token clients must present to connect, if any.
+    pub fn auth_token(&self) -> Option<&str> {
+        match self {
+            InterfaceDriver::Websocket { auth_token, .. } => auth_token.as_deref(),
         }
     }
 }
@@ -1,16 +1,29 @@
 use holo_hash::AgentPubKey;
+use holo_hash::DhtOpHash;
 use holo_hash::DnaHash;
+use holochain_serialized_bytes::prelude::*;
 use holochain_state::source_chain::SourceChainJsonDump;
+use holochain_types::app::InstalledAppId;
 use holochain_types::dht_op::DhtOp;
+use holochain_types::validate::ValidationRejection;
+use holochain_zome_types::call::AppRoleId;
+use holochain_zome_types::cell::CellId;
+use holochain_zome_types::Timestamp;
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::InstalledAppInfo;
+
 #[derive(Serialize, Deserialize)]
 pub struct JsonDump {
     pub peer_dump: P2pAgentsDump,
     pub source_chain_dump: SourceChainJsonDump,
     pub integration_dump: IntegrationStateDump,
+    /// Why each rejected op was rejected,
+    /// keyed by the hash of the op.
+    pub rejections: Vec<(DhtOpHash, ValidationRejection)>,
 }
 
 #[derive(Serialize, Clone, Debug, Deserialize)]
@@ -20,6 +33,166 @@ pub struct FullStateDump {
     pub integration_dump: FullIntegrationStateDump,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, SerializedBytes)]
+/// An overview of everything a conductor is running,
+/// for debugging conductors with many cells.
+pub struct ConductorStateDump {
+    /// Every installed app.
+    pub apps: Vec<InstalledAppInfo>,
+    /// Every cell the conductor is tracking.
+    pub cells: Vec<ConductorCellDump>,
+    /// The workflow queues of each DNA.
+    /// Cells with the same DNA share a DHT shard, so they share these queues.
+    pub dnas: Vec<ConductorDnaDump>,
+    /// Where the conductor can be reached.
+    pub bindings: ConductorBindingsDump,
+    /// The long running tasks held by the conductor's task manager.
+    pub tasks: Vec<ConductorTaskDump>,
+    /// How much disk space the conductor's databases use.
+    pub disk_usage: ConductorDiskUsageDump,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A cell in a [`ConductorStateDump`].
+pub struct ConductorCellDump {
+    /// The id of the cell.
+    pub cell_id: CellId,
+    /// Whether the cell has joined the network.
+    pub status: ConductorCellStatus,
+    /// The app and role the cell was installed for.
+    pub app_role: Option<(InstalledAppId, AppRoleId)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Where a cell is in the process of joining the network.
+pub enum ConductorCellStatus {
+    /// The cell has joined the network.
+    Joined,
+    /// The cell failed to join the network and is waiting to try again.
+    PendingJoin,
+    /// The cell is trying to join the network.
+    Joining,
+    /// The cell has been paused by an admin and is not running.
+    Paused,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A DNA in a [`ConductorStateDump`].
+pub struct ConductorDnaDump {
+    /// The hash of the DNA.
+    pub dna_hash: DnaHash,
+    /// The apps and roles the DNA is installed for.
+    pub roles: Vec<(InstalledAppId, AppRoleId)>,
+    /// The size in bytes of the authored, DHT and cache databases of the DNA.
+    /// These are shared by every cell of the DNA.
+    pub db_bytes: u64,
+    /// How many ops are waiting for validation and integration.
+    pub integration_dump: IntegrationStateDump,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The network bindings in a [`ConductorStateDump`].
+pub struct ConductorBindingsDump {
+    /// The admin interfaces.
+    pub admin_interfaces: Vec<ConductorInterfaceDump>,
+    /// The app interfaces.
+    pub app_interfaces: Vec<ConductorInterfaceDump>,
+    /// The urls the p2p network transport is bound to.
+    pub transport: Vec<url2::Url2>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// An interface in a [`ConductorBindingsDump`].
+pub struct ConductorInterfaceDump {
+    /// The port the interface is listening on.
+    pub port: u16,
+    /// How many clients are connected to the interface.
+    pub connections: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+/// How much the conductor-wide wasm caches hold and how often they were used.
+pub struct WasmCacheStats {
+    /// The number of compiled modules held in memory.
+    pub modules_in_memory: usize,
+    /// The number of serialized modules held in memory.
+    pub serialized_modules_in_memory: usize,
+    /// The number of idle instances held in memory.
+    pub instances_in_memory: usize,
+    /// The number of serialized modules in the disk cache,
+    /// or `None` if there is no disk cache.
+    pub modules_on_disk: Option<usize>,
+    /// How many times a module was found in memory.
+    pub memory_hits: u64,
+    /// How many times a module was loaded from the disk cache.
+    pub disk_hits: u64,
+    /// How many times a module had to be compiled from its wasm.
+    pub compilations: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// How much space compacting one of the conductor's databases gave back.
+pub struct DatabaseCompaction {
+    /// The database file, relative to the environment path.
+    pub database: PathBuf,
+    /// The number of bytes the database shrank by.
+    pub reclaimed_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// A task in a [`ConductorStateDump`].
+pub struct ConductorTaskDump {
+    /// What the task does.
+    pub name: String,
+    /// What the conductor does if the task fails.
+    pub on_failure: String,
+    /// When the task was handed to the task manager.
+    pub started_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// The disk usage in a [`ConductorStateDump`].
+pub struct ConductorDiskUsageDump {
+    /// The directory holding all of the conductor's databases.
+    pub environment_path: PathBuf,
+    /// The total size in bytes of everything in the environment path.
+    pub total_bytes: u64,
+    /// The size in bytes of each file or directory directly
+    /// inside the environment path.
+    pub entries: Vec<(PathBuf, u64)>,
+}
+
+impl ConductorDiskUsageDump {
+    /// Walk the environment path and add up the size of everything in it.
+    /// This touches every file so it should not be run on an async thread.
+    pub fn measure(environment_path: PathBuf) -> std::io::Result<Self> {
+        fn size_of(path: &std::path::Path) -> std::io::Result<u64> {
+            let metadata = std::fs::symlink_metadata(path)?;
+            if !metadata.is_dir() {
+                return Ok(metadata.len());
+            }
+            let mut total = 0;
+            for entry in std::fs::read_dir(path)? {
+                total += size_of(&entry?.path())?;
+            }
+            Ok(total)
+        }
+
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&environment_path)? {
+            let path = entry?.path();
+            let bytes = size_of(&path)?;
+            entries.push((path, bytes));
+        }
+        entries.sort();
+        Ok(Self {
+            environment_path,
+            total_bytes: entries.iter().map(|(_, bytes)| bytes).sum(),
+            entries,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// A collection of many cells dumps for easy viewing.
 /// Use display to see a nice printout.
@@ -57,6 +230,10 @@ pub struct FullIntegrationStateDump {
     /// This includes rejected.
     pub integrated: Vec<DhtOp>,
 
+    /// Why each rejected op was rejected,
+    /// keyed by the hash of the op.
+    pub rejections: Vec<(DhtOpHash, ValidationRejection)>,
+
     /// RowId for the latest DhtOp that we have seen
     /// Useful for subsequent calls to `FullStateDump`
     /// to return only what they haven't seen
@@ -101,7 +278,8 @@ impl std::fmt::Display for JsonDump {
             "Records authored: {}, Ops published: {}",
             s.records.len(),
             s.published_ops_count
-        )
+        )?;
+        writeln!(f, "Ops rejected: {}", self.rejections.len())
     }
 }
 
@@ -3,6 +3,8 @@ use std::borrow::Cow;
 
 use holochain_serialized_bytes::prelude::*;
 
+use crate::validate::RequiredValidationType;
+
 const DEFAULT_REQUIRED_VALIDATIONS: u8 = 5;
 
 #[derive(
@@ -50,6 +52,10 @@ pub struct EntryDef {
     pub visibility: EntryVisibility,
     /// how many validations to receive before considered "network saturated" (MAX value of 50?)
     pub required_validations: RequiredValidations,
+    /// The validation package validators need from the author
+    /// to validate this entry type.
+    #[serde(default)]
+    pub required_validation_type: RequiredValidationType,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -132,9 +138,19 @@ impl EntryDef {
             id,
             visibility,
             required_validations,
+            required_validation_type: Default::default(),
         }
     }
 
+    /// Require this validation package for validating this entry type.
+    pub fn with_required_validation_type(
+        mut self,
+        required_validation_type: RequiredValidationType,
+    ) -> Self {
+        self.required_validation_type = required_validation_type;
+        self
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     pub fn default_with_id<I: Into<EntryDefId>>(id: I) -> Self {
         EntryDef::new(id.into(), Default::default(), Default::default())
@@ -217,6 +233,7 @@ impl Default for EntryDef {
             id: EntryDefId::App(AppEntryDefName(Default::default())),
             visibility: Default::default(),
             required_validations: Default::default(),
+            required_validation_type: Default::default(),
         }
     }
 }
@@ -1,3 +1,5 @@
+use crate::record::Record;
+use holo_hash::ActionHash;
 use holo_hash::AnyDhtHash;
 use holochain_serialized_bytes::prelude::*;
 
@@ -31,3 +33,31 @@ impl Default for RequiredValidationType {
         Self::Record
     }
 }
+
+/// The records of its author's chain which are needed
+/// to validate an action, as set by the [`RequiredValidationType`]
+/// of its entry type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct ValidationPackage(pub Vec<Record>);
+
+impl ValidationPackage {
+    pub fn new(records: Vec<Record>) -> Self {
+        Self(records)
+    }
+}
+
+/// Zome input for must_get_validation_package.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct MustGetValidationPackageInput(pub ActionHash);
+
+impl MustGetValidationPackageInput {
+    /// Constructor.
+    pub fn new(action_hash: ActionHash) -> Self {
+        Self(action_hash)
+    }
+
+    /// Consumes self for inner.
+    pub fn into_inner(self) -> ActionHash {
+        self.0
+    }
+}
@@ -73,31 +73,67 @@ impl MetaLairClient {
     ) -> impl Future<Output = LairResult<Signature>> + 'static + Send {
         let this = self.clone();
         async move {
-            tokio::time::timeout(std::time::Duration::from_secs(30), async move {
-                match this {
-                    Self::Legacy(client) => {
-                        let pk = pub_key.get_raw_32();
-                        let sig = client
-                            .sign_ed25519_sign_by_pub_key(pk.to_vec().into(), data.to_vec().into())
-                            .await
-                            .map_err(one_err::OneErr::new)?;
-                        let sig = Signature::try_from(sig.to_vec().as_ref())
-                            .map_err(one_err::OneErr::new)?;
-                        Ok(sig)
-                    }
-                    Self::NewLair(client) => {
-                        let mut pub_key_2 = [0; 32];
-                        pub_key_2.copy_from_slice(pub_key.get_raw_32());
-                        let sig = client.sign_by_pub_key(pub_key_2.into(), None, data).await?;
-                        Ok(Signature(*sig.0))
-                    }
-                }
-            })
+            tokio::time::timeout(
+                std::time::Duration::from_secs(30),
+                this.sign_inner(pub_key, data),
+            )
             .await
             .map_err(one_err::OneErr::new)?
         }
     }
 
+    /// Generate signatures for many keypair / data pairs.
+    /// The requests are all sent to the keystore before waiting on
+    /// any responses so the batch costs about one round-trip instead
+    /// of one per signature.
+    /// Signatures are returned in the same order as the batch.
+    /// If any signature fails the whole batch fails.
+    pub fn sign_batch(
+        &self,
+        batch: Vec<(holo_hash::AgentPubKey, Arc<[u8]>)>,
+    ) -> impl Future<Output = LairResult<Vec<Signature>>> + 'static + Send {
+        use ghost_actor::dependencies::futures::future::try_join_all;
+
+        let this = self.clone();
+        async move {
+            tokio::time::timeout(
+                std::time::Duration::from_secs(30),
+                try_join_all(
+                    batch
+                        .into_iter()
+                        .map(|(pub_key, data)| this.sign_inner(pub_key, data)),
+                ),
+            )
+            .await
+            .map_err(one_err::OneErr::new)?
+        }
+    }
+
+    async fn sign_inner(
+        &self,
+        pub_key: holo_hash::AgentPubKey,
+        data: Arc<[u8]>,
+    ) -> LairResult<Signature> {
+        match self {
+            Self::Legacy(client) => {
+                let pk = pub_key.get_raw_32();
+                let sig = client
+                    .sign_ed25519_sign_by_pub_key(pk.to_vec().into(), data.to_vec().into())
+                    .await
+                    .map_err(one_err::OneErr::new)?;
+                let sig =
+                    Signature::try_from(sig.to_vec().as_ref()).map_err(one_err::OneErr::new)?;
+                Ok(sig)
+            }
+            Self::NewLair(client) => {
+                let mut pub_key_2 = [0; 32];
+                pub_key_2.copy_from_slice(pub_key.get_raw_32());
+                let sig = client.sign_by_pub_key(pub_key_2.into(), None, data).await?;
+                Ok(Signature(*sig.0))
+            }
+        }
+    }
+
     /// Construct a new randomized shared secret, associated with given tag
     pub fn new_shared_secret(
         &self,
@@ -353,4 +389,82 @@ impl MetaLairClient {
             }
         }
     }
+
+    /// Get the 32 byte key this conductor uses to encrypt its databases.
+    /// The key is derived from a non-exportable seed kept in lair,
+    /// so it is the same every time the conductor starts with the same lair.
+    pub fn get_or_create_db_key(
+        &self,
+    ) -> impl Future<Output = LairResult<[u8; 32]>> + 'static + Send {
+        let this = self.clone();
+        async move {
+            match this {
+                Self::Legacy(_) => Err("LegacyLairDoesNotSupportDbEncryption".into()),
+                Self::NewLair(client) => {
+                    const DB_KEY_SEED: &str = "HcDbEncryptionKeySeed";
+                    const DB_KEY_CONTEXT: &[u8] = b"holochain-db-encryption-key";
+                    let info = match client.get_entry(DB_KEY_SEED.into()).await {
+                        Ok(info) => match info {
+                            LairEntryInfo::Seed { seed_info, .. } => seed_info,
+                            oth => {
+                                return Err(format!(
+                                    "invalid entry type, expecting seed: {:?}",
+                                    oth
+                                )
+                                .into())
+                            }
+                        },
+                        Err(_) => client.new_seed(DB_KEY_SEED.into(), None, false).await?,
+                    };
+
+                    // ed25519 signatures are deterministic so signing a
+                    // fixed context always gives the same secret material.
+                    let sig = client
+                        .sign_by_pub_key(info.ed25519_pub_key, None, DB_KEY_CONTEXT.into())
+                        .await?;
+                    let hash = sodoken::BufWriteSized::<32>::new_no_lock();
+                    sodoken::hash::blake2b::hash(hash.clone(), sig.0.to_vec())
+                        .await
+                        .map_err(one_err::OneErr::new)?;
+                    let mut key = [0; 32];
+                    key.copy_from_slice(&hash.read_lock()[..]);
+                    Ok(key)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sign_batch_matches_sign() {
+        let keystore = crate::test_keystore::spawn_test_keystore().await.unwrap();
+        let agent_1 = keystore.new_sign_keypair_random().await.unwrap();
+        let agent_2 = keystore.new_sign_keypair_random().await.unwrap();
+        let batch: Vec<(holo_hash::AgentPubKey, Arc<[u8]>)> = vec![
+            (agent_1.clone(), b"one".to_vec().into()),
+            (agent_2.clone(), b"two".to_vec().into()),
+            (agent_1.clone(), b"three".to_vec().into()),
+        ];
+
+        let signatures = keystore.sign_batch(batch.clone()).await.unwrap();
+        assert_eq!(signatures.len(), batch.len());
+        for ((agent, data), signature) in batch.into_iter().zip(signatures) {
+            assert!(agent.verify_signature_raw(&signature, data.clone()).await);
+            assert_eq!(keystore.sign(agent, data).await.unwrap(), signature);
+        }
+
+        assert!(keystore.sign_batch(Vec::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn db_key_is_stable() {
+        let keystore = crate::test_keystore::spawn_test_keystore().await.unwrap();
+        let key = keystore.get_or_create_db_key().await.unwrap();
+        assert_eq!(keystore.get_or_create_db_key().await.unwrap(), key);
+        assert_ne!(key, [0; 32]);
+    }
 }
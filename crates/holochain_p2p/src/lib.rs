@@ -75,6 +75,13 @@ pub trait HolochainP2pDnaT {
         timeout_ms: Option<u64>,
     ) -> actor::HolochainP2pResult<usize>;
 
+    /// Publish warrants against invalid data to the authorities of its basis.
+    async fn publish_warrants(
+        &self,
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> actor::HolochainP2pResult<()>;
+
     /// Request a validation package.
     async fn get_validation_package(
         &self,
@@ -83,6 +90,7 @@ pub trait HolochainP2pDnaT {
     ) -> actor::HolochainP2pResult<ValidationPackageResponse>;
 
     /// Get an entry from the DHT.
+    /// Fails with a timeout error if no authority responded in time.
     async fn get(
         &self,
         dht_hash: holo_hash::AnyDhtHash,
@@ -133,6 +141,9 @@ pub trait HolochainP2pDnaT {
 
     /// New data has been integrated and is ready for gossiping.
     async fn new_integrated_data(&self) -> actor::HolochainP2pResult<()>;
+
+    /// Get how well the network of this dna is connected.
+    async fn network_status(&self) -> actor::HolochainP2pResult<kitsune_p2p::actor::NetworkStatus>;
 }
 
 /// A wrapper around HolochainP2pSender that partially applies the dna_hash / agent_pub_key.
@@ -236,6 +247,17 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Publish warrants against invalid data to the authorities of its basis.
+    async fn publish_warrants(
+        &self,
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .publish_warrants((*self.dna_hash).clone(), basis, warrants)
+            .await
+    }
+
     /// Request a validation package.
     async fn get_validation_package(
         &self,
@@ -333,6 +355,10 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .new_integrated_data((*self.dna_hash).clone())
             .await
     }
+
+    async fn network_status(&self) -> actor::HolochainP2pResult<kitsune_p2p::actor::NetworkStatus> {
+        self.sender.network_status((*self.dna_hash).clone()).await
+    }
 }
 
 pub use kitsune_p2p::dht;
@@ -153,6 +153,17 @@ impl WrapEvtSender {
         }, %op_count, "(hp2p:handle) publish")
     }
 
+    fn publish_warrants(
+        &self,
+        dna_hash: DnaHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> impl Future<Output = HolochainP2pResult<()>> + 'static + Send {
+        let warrant_count = warrants.len();
+        timing_trace!({
+            self.0.publish_warrants(dna_hash, warrants)
+        }, %warrant_count, "(hp2p:handle) publish_warrants")
+    }
+
     fn get_validation_package(
         &self,
         dna_hash: DnaHash,
@@ -266,7 +277,13 @@ impl WrapEvtSender {
         dna_hash: DnaHash,
         query: FetchOpDataQuery,
     ) -> impl Future<
-        Output = HolochainP2pResult<Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>>,
+        Output = HolochainP2pResult<
+            Vec<(
+                holo_hash::DhtOpHash,
+                holochain_types::dht_op::DhtOp,
+                Vec<SignedWarrant>,
+            )>,
+        >,
     >
            + 'static
            + Send {
@@ -477,6 +494,36 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving incoming warrants from a remote node
+    fn handle_incoming_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        // The warrants were sent to the authorities of this basis,
+        // so any against data at another basis are dropped.
+        let warrants: Vec<_> = warrants
+            .into_iter()
+            .filter(|w| {
+                let matches = w.warrant.warrant.basis == basis;
+                if !matches {
+                    tracing::warn!(warrant = ?w.warrant, "Ignoring a warrant for another basis");
+                }
+                matches
+            })
+            .collect();
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            if !warrants.is_empty() {
+                evt_sender.publish_warrants(dna_hash, warrants).await?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming validation receipt from a remote node
     fn handle_incoming_validation_receipt(
         &mut self,
@@ -676,7 +723,8 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             } => self.handle_incoming_get_agent_activity(space, to_agent, agent, query, options),
             // holochain_p2p never publishes via request
             // these only occur on broadcasts
-            crate::wire::WireMessage::Publish { .. } => {
+            crate::wire::WireMessage::Publish { .. }
+            | crate::wire::WireMessage::PublishWarrants { .. } => {
                 Err(HolochainP2pError::invalid_p2p_message(
                     "invalid: publish is a broadcast type, not a request".to_string(),
                 )
@@ -753,6 +801,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 countersigning_session,
                 ops,
             ),
+            crate::wire::WireMessage::PublishWarrants { basis, warrants } => {
+                self.handle_incoming_publish_warrants(space, basis, warrants)
+            }
             crate::wire::WireMessage::CountersigningSessionNegotiation { message } => {
                 self.handle_incoming_countersigning_session_negotiation(space, to_agent, message)
             }
@@ -766,16 +817,31 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         ops: Vec<KOp>,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
         let space = DnaHash::from_kitsune(&space);
+        let mut warranted = Vec::new();
         let ops = ops
             .into_iter()
             .map(|op_data| {
-                let op = crate::wire::WireDhtOpData::decode(op_data.0.clone())
-                    .map_err(HolochainP2pError::from)?
-                    .op_data;
-                Ok(op)
+                let crate::wire::WireDhtOpData { op_data, warrants } =
+                    crate::wire::WireDhtOpData::decode(op_data.0.clone())
+                        .map_err(HolochainP2pError::from)?;
+                // Warrants are gossiped along with their op.
+                warranted.extend(warrants.into_iter().map(|warrant| WarrantedOp {
+                    warrant,
+                    op: op_data.clone(),
+                }));
+                Ok(op_data)
             })
             .collect::<Result<_, HolochainP2pError>>()?;
-        self.handle_incoming_publish(space, false, false, ops)
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            evt_sender.publish(space.clone(), false, false, ops).await?;
+            if !warranted.is_empty() {
+                evt_sender.publish_warrants(space, warranted).await?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
@@ -819,13 +885,18 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             let mut out = vec![];
-            for (op_hash, dht_op) in evt_sender.fetch_op_data(space.clone(), query).await? {
+            for (op_hash, dht_op, warrants) in
+                evt_sender.fetch_op_data(space.clone(), query).await?
+            {
                 out.push((
                     op_hash.into_kitsune(),
                     KitsuneOpData::new(
-                        crate::wire::WireDhtOpData { op_data: dht_op }
-                            .encode()
-                            .map_err(kitsune_p2p::KitsuneP2pError::other)?,
+                        crate::wire::WireDhtOpData {
+                            op_data: dht_op,
+                            warrants,
+                        }
+                        .encode()
+                        .map_err(kitsune_p2p::KitsuneP2pError::other)?,
                     ),
                 ));
             }
@@ -991,6 +1062,35 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let timeout = self.tuning_params.implicit_timeout();
+        let payload =
+            crate::wire::WireMessage::publish_warrants(basis.clone(), warrants).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            kitsune_p2p
+                .broadcast(
+                    space,
+                    basis.to_kitsune(),
+                    timeout,
+                    BroadcastTo::Notify,
+                    payload,
+                )
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_get_validation_package(
         &mut self,
@@ -1062,7 +1162,11 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
             let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
-            let result = kitsune_p2p.rpc_multi(input).await?;
+            // Only gets tell a timeout apart from no responses.
+            let result = match kitsune_p2p.rpc_multi(input).await {
+                Err(e) if e.is_timeout() => Vec::new(),
+                result => result?,
+            };
 
             let mut out = Vec::new();
             for item in result {
@@ -1098,7 +1202,11 @@ impl HolochainP2pHandler for HolochainP2pActor {
             //        without doing any pagination / etc...
             //        Setting up RpcMulti to act like RpcSingle
             input.max_remote_agent_count = 1;
-            let result = kitsune_p2p.rpc_multi(input).await?;
+            // Only gets tell a timeout apart from no responses.
+            let result = match kitsune_p2p.rpc_multi(input).await {
+                Err(e) if e.is_timeout() => Vec::new(),
+                result => result?,
+            };
 
             let mut out = Vec::new();
             for item in result {
@@ -1139,7 +1247,11 @@ impl HolochainP2pHandler for HolochainP2pActor {
             //        without doing any pagination / etc...
             //        Setting up RpcMulti to act like RpcSingle
             input.max_remote_agent_count = 1;
-            let result = kitsune_p2p.rpc_multi(input).await?;
+            // Only gets tell a timeout apart from no responses.
+            let result = match kitsune_p2p.rpc_multi(input).await {
+                Err(e) if e.is_timeout() => Vec::new(),
+                result => result?,
+            };
 
             let mut out = Vec::new();
             for item in result {
@@ -1242,4 +1354,68 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_status(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::SpaceGossipStatus> {
+        let space = dna_hash.into_kitsune();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.gossip_status(space).await?) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_network_status(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::actor::NetworkStatus> {
+        let space = dna_hash.into_kitsune();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.network_status(space).await?) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_list_transport_bindings(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<Vec<kitsune_p2p_types::dependencies::url2::Url2>> {
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.list_transport_bindings().await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_block_peer(
+        &mut self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+        until: Option<Timestamp>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let block = kitsune_p2p::block::Block {
+            target: target.into_kitsune(),
+            until,
+        };
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.block(space, block).await?) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_unblock_peer(
+        &mut self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.unblock(space, target.into_kitsune()).await?) }
+                .boxed()
+                .into(),
+        )
+    }
 }
@@ -63,6 +63,14 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<usize> {
         Err("stub".into())
     }
+    fn handle_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
     fn handle_get_validation_package(
         &mut self,
         input: actor::GetValidationPackage,
@@ -134,6 +142,38 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<String> {
         Err("stub".into())
     }
+    fn handle_gossip_status(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::SpaceGossipStatus> {
+        Err("stub".into())
+    }
+    fn handle_network_status(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::actor::NetworkStatus> {
+        Err("stub".into())
+    }
+    fn handle_list_transport_bindings(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<Vec<kitsune_p2p_types::dependencies::url2::Url2>> {
+        Err("stub".into())
+    }
+    fn handle_block_peer(
+        &mut self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+        until: Option<Timestamp>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+    fn handle_unblock_peer(
+        &mut self,
+        dna_hash: DnaHash,
+        target: PeerBlockTarget,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -180,6 +220,7 @@ mod tests {
     use kitsune_p2p::dht::prelude::Topology;
     use kitsune_p2p::dht::{ArqStrat, PeerView, PeerViewQ};
 
+    use crate::actor::PeerBlockTarget;
     use crate::HolochainP2pSender;
     use holochain_zome_types::ValidationStatus;
     use kitsune_p2p::dependencies::kitsune_p2p_types::tls::TlsConfig;
@@ -268,6 +309,78 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_call_remote_blocked_agent() {
+        let (dna, a1, a2, _) = test_setup();
+
+        let (p2p, mut evt) = spawn_holochain_p2p(
+            KitsuneP2pConfig::default(),
+            TlsConfig::new_ephemeral().await.unwrap(),
+            kitsune_p2p::HostStub::new(),
+        )
+        .await
+        .unwrap();
+
+        let r_task = tokio::task::spawn(async move {
+            use tokio_stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    CallRemote { respond, .. } => {
+                        respond.r(Ok(
+                            async move { Ok(UnsafeBytes::from(b"yada".to_vec()).into()) }
+                                .boxed()
+                                .into(),
+                        ));
+                    }
+                    SignNetworkData { respond, .. } => {
+                        respond.r(Ok(async move { Ok([0; 64].into()) }.boxed().into()));
+                    }
+                    PutAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    QueryPeerDensity { respond, .. } => {
+                        let view = test_peer_view();
+                        respond.r(Ok(async move { Ok(view) }.boxed().into()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone(), None).await.unwrap();
+        p2p.join(dna.clone(), a2.clone(), None).await.unwrap();
+
+        let call = |p2p: HolochainP2pRef| {
+            let (dna, a1, a2) = (dna.clone(), a1.clone(), a2.clone());
+            async move {
+                p2p.call_remote(
+                    dna,
+                    a1,
+                    a2,
+                    "".into(),
+                    "".into(),
+                    None,
+                    ExternIO::encode(b"yippo").unwrap(),
+                )
+                .await
+            }
+        };
+
+        let target = PeerBlockTarget::Agent(a2.clone());
+        p2p.block_peer(dna.clone(), target.clone(), None)
+            .await
+            .unwrap();
+        assert!(call(p2p.clone()).await.is_err());
+
+        p2p.unblock_peer(dna.clone(), target).await.unwrap();
+        let res: Vec<u8> = UnsafeBytes::from(call(p2p.clone()).await.unwrap()).into();
+        assert_eq!(b"yada".to_vec(), res);
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_send_validation_receipt_workflow() {
         let (dna, a1, a2, _) = test_setup();
@@ -381,6 +494,62 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[test]
+    fn test_private_entries_are_not_encoded() {
+        let mk_op = |visibility| {
+            let mut create = fixt!(Create);
+            create.entry_type = EntryType::App(AppEntryType::new(0.into(), 0.into(), visibility));
+            holochain_types::dht_op::DhtOp::StoreRecord(
+                fixt!(Signature),
+                create.into(),
+                Some(Box::new(fixt!(Entry))),
+            )
+        };
+        let publish = |op| WireMessage::publish(true, false, fixt!(AnyDhtHash), vec![op]).encode();
+        let publish_warrants = |op: holochain_types::dht_op::DhtOp| {
+            let warrant = Warrant {
+                author: op.action().author().clone(),
+                action_hash: ActionHash::with_data_sync(&op.action()),
+                basis: op.dht_basis(),
+                warrantor: fixt!(AgentPubKey),
+                timestamp: Timestamp::now(),
+            };
+            let warranted = holochain_types::dht_op::WarrantedOp {
+                warrant: SignedWarrant::new(warrant, fixt!(Signature)),
+                op,
+            };
+            WireMessage::publish_warrants(fixt!(AnyDhtHash), vec![warranted]).encode()
+        };
+
+        let public = mk_op(EntryVisibility::Public);
+        assert!(WireDhtOpData {
+            op_data: public.clone(),
+            warrants: vec![],
+        }
+        .encode()
+        .is_ok());
+        assert!(publish(public.clone()).is_ok());
+        assert!(publish_warrants(public).is_ok());
+
+        let private = mk_op(EntryVisibility::Private);
+        assert!(matches!(
+            WireDhtOpData {
+                op_data: private.clone(),
+                warrants: vec![],
+            }
+            .encode(),
+            Err(HolochainP2pError::PrivateEntryOnWire(_))
+        ));
+        assert!(matches!(
+            publish(private.clone()),
+            Err(HolochainP2pError::PrivateEntryOnWire(_))
+        ));
+        assert!(matches!(
+            publish_warrants(private),
+            Err(HolochainP2pError::PrivateEntryOnWire(_))
+        ));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_get_workflow() {
         observability::test_run().ok();
@@ -404,12 +573,14 @@ mod tests {
             deletes: vec![],
             updates: vec![],
             entry: None,
+            warrants: vec![],
         });
         let test_2 = WireOps::Record(WireRecordOps {
             action: Some(Judged::valid(SignedAction(fixt!(Action), fixt!(Signature)))),
             deletes: vec![],
             updates: vec![],
             entry: None,
+            warrants: vec![],
         });
 
         let mut respond_queue = vec![test_1.clone(), test_2.clone()];
@@ -26,6 +26,10 @@ pub enum HolochainP2pError {
     #[error("InvalidP2pMessage: {0}")]
     InvalidP2pMessage(String),
 
+    /// An op carrying a private entry was about to be sent on the network
+    #[error("Refused to send private entry {0} on the network")]
+    PrivateEntryOnWire(holo_hash::EntryHash),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -41,6 +45,11 @@ impl HolochainP2pError {
     pub fn invalid_p2p_message(s: String) -> Self {
         Self::InvalidP2pMessage(s)
     }
+
+    /// Did a network request time out before any remote agent responded?
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::OtherKitsuneP2pError(e) if e.is_timeout())
+    }
 }
 
 // do some manual type translation so we get better error displays
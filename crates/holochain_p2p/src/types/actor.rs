@@ -158,6 +158,9 @@ pub struct GetLinksOptions {
     /// Note - if all requests time-out you will receive an empty result,
     /// not a timeout error.
     pub timeout_ms: Option<u64>,
+    /// ```[Remote]```
+    /// Order the links and only return one page of them.
+    pub pagination: Option<LinkPagination>,
 }
 
 #[derive(Debug, Clone)]
@@ -203,6 +206,27 @@ impl Default for GetActivityOptions {
     }
 }
 
+/// A peer that can be blocked in a DNA's network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerBlockTarget {
+    /// Stop storing the agent's info, calling it and publishing to it.
+    Agent(AgentPubKey),
+    /// Close connections with the node holding this tls cert and drop
+    /// anything it sends. Blocking a node blocks it for all DNAs.
+    Cert(kitsune_p2p_types::Tx2Cert),
+}
+
+impl PeerBlockTarget {
+    /// Convert into the kitsune block target.
+    pub fn into_kitsune(self) -> kitsune_p2p::block::BlockTarget {
+        match self {
+            Self::Agent(agent) => kitsune_p2p::block::BlockTarget::Agent(agent.into_kitsune()),
+            Self::Cert(cert) => kitsune_p2p::block::BlockTarget::Cert(cert),
+        }
+    }
+}
+
 ghost_actor::ghost_chan! {
     /// The HolochainP2pSender struct allows controlling the HolochainP2p
     /// actor instance.
@@ -248,10 +272,18 @@ ghost_actor::ghost_chan! {
             timeout_ms: Option<u64>,
         ) -> usize;
 
+        /// Publish warrants against invalid data to the authorities of its basis.
+        fn publish_warrants(
+            dna_hash: DnaHash,
+            basis: holo_hash::AnyDhtHash,
+            warrants: Vec<WarrantedOp>,
+        ) -> ();
+
         /// Request a validation package.
         fn get_validation_package(input: GetValidationPackage) -> ValidationPackageResponse;
 
         /// Get an entry from the DHT.
+        /// Fails with a timeout error if no authority responded in time.
         fn get(
             dna_hash: DnaHash,
             dht_hash: holo_hash::AnyDhtHash,
@@ -300,6 +332,21 @@ ghost_actor::ghost_chan! {
         fn dump_network_metrics(
             dna_hash: Option<DnaHash>,
         ) -> String;
+
+        /// Get the progress of gossip for a DNA.
+        fn gossip_status(dna_hash: DnaHash) -> kitsune_p2p::SpaceGossipStatus;
+
+        /// Get how well the network of a DNA is connected.
+        fn network_status(dna_hash: DnaHash) -> kitsune_p2p::actor::NetworkStatus;
+
+        /// List the urls the network transport is bound to.
+        fn list_transport_bindings() -> Vec<kitsune_p2p_types::dependencies::url2::Url2>;
+
+        /// Block a peer in a DNA's network until `until`, or until unblocked.
+        fn block_peer(dna_hash: DnaHash, target: PeerBlockTarget, until: Option<Timestamp>) -> ();
+
+        /// Remove the block on a peer in a DNA's network.
+        fn unblock_peer(dna_hash: DnaHash, target: PeerBlockTarget) -> ();
     }
 }
 
@@ -60,11 +60,17 @@ impl From<&actor::GetMetaOptions> for GetMetaOptions {
 
 /// GetLinks options help control how the get is processed at various levels.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetLinksOptions {}
+pub struct GetLinksOptions {
+    /// Order the links and only return one page of them.
+    #[serde(default)]
+    pub pagination: Option<LinkPagination>,
+}
 
 impl From<&actor::GetLinksOptions> for GetLinksOptions {
-    fn from(_a: &actor::GetLinksOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetLinksOptions) -> Self {
+        Self {
+            pagination: a.pagination.clone(),
+        }
     }
 }
 
@@ -181,6 +187,13 @@ ghost_actor::ghost_chan! {
             ops: Vec<holochain_types::dht_op::DhtOp>,
         ) -> ();
 
+        /// A remote node is publishing or gossiping warrants against
+        /// invalid data in a range we claim to be holding.
+        fn publish_warrants(
+            dna_hash: DnaHash,
+            warrants: Vec<WarrantedOp>,
+        ) -> ();
+
         /// A remote node is requesting a validation package.
         fn get_validation_package(
             // The dna_hash / space_hash context.
@@ -242,11 +255,16 @@ ghost_actor::ghost_chan! {
             include_limbo: bool,
         ) -> Option<(Vec<holo_hash::DhtOpHash>, TimeWindowInclusive)>;
 
-        /// The p2p module needs access to the content for a given set of DhtOpHashes.
+        /// The p2p module needs access to the content for a given set of DhtOpHashes,
+        /// along with any warrants held against their authors.
         fn fetch_op_data(
             dna_hash: DnaHash,
             query: FetchOpDataQuery,
-        ) -> Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>;
+        ) -> Vec<(
+            holo_hash::DhtOpHash,
+            holochain_types::dht_op::DhtOp,
+            Vec<SignedWarrant>,
+        )>;
 
         /// P2p operations require cryptographic signatures and validation.
         fn sign_network_data(
@@ -290,6 +308,7 @@ impl HolochainP2pEvent {
     pub fn dna_hash(&self) -> &DnaHash {
         match_p2p_evt!(self => |dna_hash| { dna_hash }, {
             HolochainP2pEvent::Publish { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::PublishWarrants { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::FetchOpData { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryOpHashes { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryAgentInfoSigned { dna_hash, .. } => { dna_hash }
@@ -304,6 +323,7 @@ impl HolochainP2pEvent {
     pub fn target_agents(&self) -> &AgentPubKey {
         match_p2p_evt!(self => |to_agent| { to_agent }, {
             HolochainP2pEvent::Publish { .. } => { unimplemented!("There is no single agent target for Publish") }
+            HolochainP2pEvent::PublishWarrants { .. } => { unimplemented!("There is no single agent target for PublishWarrants") }
             HolochainP2pEvent::FetchOpData { .. } => { unimplemented!("There is no single agent target for FetchOpData") }
             HolochainP2pEvent::QueryOpHashes { .. } => { unimplemented!("There is no single agent target for QueryOpHashes") }
             HolochainP2pEvent::QueryAgentInfoSigned { .. } => { unimplemented!("There is no single agent target for QueryAgentInfoSigned") },
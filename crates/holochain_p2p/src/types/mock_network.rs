@@ -4,7 +4,7 @@
 use fixt::prelude::Distribution;
 use futures::stream::Stream;
 use kitsune_p2p::actor::BroadcastTo;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::ops::Range;
 use std::pin::Pin;
@@ -27,6 +27,8 @@ use kitsune_p2p::GossipModuleType;
 use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
 use kitsune_p2p_types::tx2::MsgId;
 use kitsune_p2p_types::Tx2Cert;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 
 static MSG_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
 
@@ -37,7 +39,9 @@ fn next_msg_id() -> MsgId {
 /// A channel between the simulated network and the set of real
 /// holochain nodes.
 pub struct HolochainP2pMockChannel {
-    address_map: HashMap<AgentPubKey, (Tx2Cert, TxUrl)>,
+    address_map: Arc<BTreeMap<AgentPubKey, (Tx2Cert, TxUrl)>>,
+    offline_nodes: Arc<HashSet<Tx2Cert>>,
+    partition: MockPartition,
     from_kitsune: Pin<Box<dyn Stream<Item = KitsuneMock> + Send + Sync + 'static>>,
     to_kitsune: ToKitsuneMockChannelTx,
 }
@@ -53,6 +57,11 @@ pub struct MockScenario {
     pub inbound_delay_range: Range<Duration>,
     /// The range of time outbound messages will be delayed.
     pub outbound_delay_range: Range<Duration>,
+    /// Seed for the choice of offline nodes, dropped messages and delays.
+    /// Runs with the same seed make the same choices for messages
+    /// that arrive in the same order.
+    /// If not set, a random seed is used.
+    pub seed: Option<u64>,
 }
 
 impl Default for MockScenario {
@@ -62,10 +71,44 @@ impl Default for MockScenario {
             percent_offline: 0.0,
             inbound_delay_range: Duration::from_millis(0)..Duration::from_millis(0),
             outbound_delay_range: Duration::from_millis(0)..Duration::from_millis(0),
+            seed: None,
         }
     }
 }
 
+#[derive(Clone)]
+/// A handle for cutting simulated agents off from the real nodes
+/// while the simulated network is running and for joining them back.
+/// Messages to and from a cut off agent are dropped in both directions.
+pub struct MockPartition {
+    address_map: Arc<BTreeMap<AgentPubKey, (Tx2Cert, TxUrl)>>,
+    cut_off: Arc<std::sync::RwLock<HashSet<Tx2Cert>>>,
+}
+
+impl MockPartition {
+    /// Cut these simulated agents off from the real nodes.
+    /// Agents that are not part of the simulated network are ignored.
+    pub fn partition(&self, agents: impl IntoIterator<Item = AgentPubKey>) {
+        let mut cut_off = self.cut_off.write().unwrap();
+        cut_off.extend(
+            agents
+                .into_iter()
+                .filter_map(|agent| self.address_map.get(&agent))
+                .map(|(cert, _)| cert.clone()),
+        );
+    }
+
+    /// Join all cut off simulated agents back to the real nodes.
+    pub fn heal(&self) {
+        self.cut_off.write().unwrap().clear();
+    }
+
+    /// Is this simulated node currently cut off from the real nodes?
+    fn is_cut_off(&self, cert: &Tx2Cert) -> bool {
+        self.cut_off.read().unwrap().contains(cert)
+    }
+}
+
 #[derive(Debug)]
 /// When a message is received or sent it needs to be
 /// associated with a simulated connection.
@@ -168,7 +211,7 @@ impl HolochainP2pMockChannel {
         ToKitsuneMockChannelRx,
         HolochainP2pMockChannel,
     ) {
-        let address_map: HashMap<_, _> = peer_data
+        let address_map: BTreeMap<_, _> = peer_data
             .into_iter()
             .map(|info| {
                 let agent = holo_hash::AgentPubKey::from_kitsune(&info.agent);
@@ -177,18 +220,20 @@ impl HolochainP2pMockChannel {
                 (agent, (cert, url))
             })
             .collect();
-        let offline_nodes: HashSet<_> = {
-            let mut rng = rand::thread_rng();
-            address_map
-                .values()
-                .filter(|_| {
-                    let offline = rand::distributions::Uniform::from(0.0..1.0);
-                    offline.sample(&mut rng) <= scenario.percent_offline
-                })
-                .map(|(cert, _)| cert.clone())
-                .collect()
+        let address_map = Arc::new(address_map);
+        let rng = Arc::new(std::sync::Mutex::new(match scenario.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }));
+        let offline_nodes = Arc::new(choose_offline(
+            address_map.values().map(|(cert, _)| cert.clone()),
+            scenario.percent_offline,
+            &mut *rng.lock().unwrap(),
+        ));
+        let partition = MockPartition {
+            address_map: address_map.clone(),
+            cut_off: Default::default(),
         };
-        let offline_nodes = Arc::new(offline_nodes);
         let (from_kitsune_tx, from_kitsune_rx) = tokio::sync::mpsc::channel(buffer);
         let (to_kitsune_tx, to_kitsune_rx) = to_kitsune_channel(buffer);
         let (tx, rx) = tokio::sync::mpsc::channel(buffer);
@@ -196,27 +241,33 @@ impl HolochainP2pMockChannel {
         let stream = tokio_stream::wrappers::ReceiverStream::new(from_kitsune_rx)
             .map({
                 let scenario = scenario.clone();
+                let offline_nodes = offline_nodes.clone();
+                let partition = partition.clone();
+                let rng = rng.clone();
                 move |t: KitsuneMock| {
                     let scenario = scenario.clone();
                     let offline_nodes = offline_nodes.clone();
+                    let partition = partition.clone();
+                    let rng = rng.clone();
                     async move {
                         let (delay, keep) = {
-                            let mut rng = rand::thread_rng();
+                            let mut rng = rng.lock().unwrap();
                             let delay = if scenario.inbound_delay_range.is_empty() {
                                 Duration::from_millis(0)
                             } else {
                                 let delay = rand::distributions::Uniform::from(
                                     scenario.inbound_delay_range,
                                 );
-                                delay.sample(&mut rng)
+                                delay.sample(&mut *rng)
                             };
                             let drop = rand::distributions::Uniform::from(0.0..1.0);
-                            let keep = drop.sample(&mut rng) > scenario.percent_drop_msg;
+                            let keep = drop.sample(&mut *rng) > scenario.percent_drop_msg;
                             (delay, keep)
                         };
                         tokio::time::sleep(delay).await;
-                        keep.then(|| t)
-                            .filter(|m| !offline_nodes.contains(m.cert()))
+                        keep.then(|| t).filter(|m| {
+                            !offline_nodes.contains(m.cert()) && !partition.is_cut_off(m.cert())
+                        })
                     }
                 }
             })
@@ -224,26 +275,29 @@ impl HolochainP2pMockChannel {
             .filter_map(|t| async move { t });
         let from_kitsune = Box::pin(stream);
         let stream = tokio_stream::wrappers::ReceiverStream::new(rx).for_each_concurrent(10, {
-            move |msg| {
+            let partition = partition.clone();
+            move |msg: KitsuneMock| {
                 let scenario = scenario.clone();
                 let to_kitsune_tx = to_kitsune_tx.clone();
+                let partition = partition.clone();
+                let rng = rng.clone();
                 async move {
                     let scenario = scenario.clone();
                     let (delay, keep) = {
-                        let mut rng = rand::thread_rng();
+                        let mut rng = rng.lock().unwrap();
                         let delay = if scenario.outbound_delay_range.is_empty() {
                             Duration::from_millis(0)
                         } else {
                             let delay =
                                 rand::distributions::Uniform::from(scenario.outbound_delay_range);
-                            delay.sample(&mut rng)
+                            delay.sample(&mut *rng)
                         };
                         let drop = rand::distributions::Uniform::from(0.0..1.0);
-                        let keep = drop.sample(&mut rng) > scenario.percent_drop_msg;
+                        let keep = drop.sample(&mut *rng) > scenario.percent_drop_msg;
                         (delay, keep)
                     };
                     tokio::time::sleep(delay).await;
-                    if keep {
+                    if keep && !partition.is_cut_off(msg.cert()) {
                         to_kitsune_tx.send(msg).await.unwrap();
                     }
                 }
@@ -258,12 +312,30 @@ impl HolochainP2pMockChannel {
             to_kitsune_rx,
             Self {
                 address_map,
+                offline_nodes,
+                partition,
                 from_kitsune,
                 to_kitsune: tx,
             },
         )
     }
 
+    /// The simulated agents that were chosen to be offline for the whole test.
+    pub fn offline_agents(&self) -> Vec<AgentPubKey> {
+        self.address_map
+            .iter()
+            .filter(|(_, (cert, _))| self.offline_nodes.contains(cert))
+            .map(|(agent, _)| agent.clone())
+            .collect()
+    }
+
+    /// Get a handle for partitioning simulated agents from the real nodes.
+    /// The handle can be kept after this channel is moved into the task
+    /// that drives the simulated agents.
+    pub fn partition(&self) -> MockPartition {
+        self.partition.clone()
+    }
+
     /// Await the next message from the real nodes.
     /// Note that all messages are routed through this call so blocking
     /// on the loop that calls next will slow down all simulated node response times.
@@ -329,6 +401,42 @@ impl HolochainP2pMockChannel {
     }
 }
 
+/// Build a network config that routes all of a conductor's network traffic
+/// through the simulated network. Set it as the `network` of a
+/// `ConductorConfig` to run a conductor against the simulated agents.
+pub fn mock_network_config(
+    from_kitsune_tx: FromKitsuneMockChannelTx,
+    to_kitsune_rx: ToKitsuneMockChannelRx,
+    tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+) -> kitsune_p2p::KitsuneP2pConfig {
+    let mock_network =
+        kitsune_p2p::test_util::mock_network::mock_network(from_kitsune_tx, to_kitsune_rx);
+    let mock_network: kitsune_p2p_types::tx2::tx2_adapter::AdapterFactory = Arc::new(mock_network);
+    let mut network = kitsune_p2p::KitsuneP2pConfig::default();
+    network.transport_pool = vec![kitsune_p2p::TransportConfig::Mock {
+        mock_network: mock_network.into(),
+    }];
+    network.tuning_params = tuning_params;
+    network
+}
+
+/// Choose which of these nodes are offline.
+/// The nodes are sorted first so the same seed always makes the same
+/// choice, whatever order the nodes are given in.
+fn choose_offline(
+    nodes: impl Iterator<Item = Tx2Cert>,
+    percent_offline: f32,
+    rng: &mut StdRng,
+) -> HashSet<Tx2Cert> {
+    let mut nodes: Vec<_> = nodes.collect();
+    nodes.sort();
+    let offline = rand::distributions::Uniform::from(0.0..1.0);
+    nodes
+        .into_iter()
+        .filter(|_| offline.sample(&mut *rng) <= percent_offline)
+        .collect()
+}
+
 impl HolochainP2pMockMsg {
     /// Associate a message with the simulated agent that is sending or receiving
     /// this message. From holochain's point of view this is the remote node that
@@ -349,6 +457,7 @@ impl HolochainP2pMockMsg {
                 | crate::wire::WireMessage::GetAgentActivity { .. }
                 | crate::wire::WireMessage::GetValidationPackage { .. } => next_msg_id().as_req(),
                 crate::wire::WireMessage::Publish { .. }
+                | crate::wire::WireMessage::PublishWarrants { .. }
                 | crate::wire::WireMessage::CountersigningSessionNegotiation { .. } => {
                     MsgId::new_notify()
                 }
@@ -376,6 +485,7 @@ impl HolochainP2pMockMsg {
                     | crate::wire::WireMessage::GetAgentActivity { .. }
                     | crate::wire::WireMessage::GetValidationPackage { .. } => true,
                     crate::wire::WireMessage::Publish { .. }
+                    | crate::wire::WireMessage::PublishWarrants { .. }
                     | crate::wire::WireMessage::CountersigningSessionNegotiation { .. } => false,
                 };
                 let to_agent = to_agent.to_kitsune();
@@ -513,3 +623,26 @@ impl HolochainP2pMockMsg {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn same_seed_chooses_same_offline_nodes() {
+        let certs: Vec<Tx2Cert> = (0..100u8).map(|i| Tx2Cert::from(vec![i; 32])).collect();
+        let choose = |seed: u64| {
+            // Each map has its own random iteration order,
+            // like the address maps of separate runs.
+            let nodes: HashMap<_, _> = certs.iter().cloned().map(|c| (c, ())).collect();
+            choose_offline(nodes.into_keys(), 0.3, &mut StdRng::seed_from_u64(seed))
+        };
+        let first = choose(42);
+        assert!(!first.is_empty());
+        assert!(first.len() < certs.len());
+        for _ in 0..10 {
+            assert_eq!(choose(42), first);
+        }
+    }
+}
@@ -6,11 +6,20 @@ use holochain_zome_types::zome::FunctionName;
 pub struct WireDhtOpData {
     /// The dht op.
     pub op_data: holochain_types::dht_op::DhtOp,
+    /// Warrants held against the author of the op, which are
+    /// gossiped along with it.
+    #[serde(default)]
+    pub warrants: Vec<SignedWarrant>,
 }
 
 impl WireDhtOpData {
     /// Encode as bytes.
-    pub fn encode(self) -> Result<Vec<u8>, SerializedBytesError> {
+    ///
+    /// Fails with [`HolochainP2pError::PrivateEntryOnWire`] if the op
+    /// carries a private entry.
+    #[track_caller]
+    pub fn encode(self) -> actor::HolochainP2pResult<Vec<u8>> {
+        check_no_private_entry(&self.op_data)?;
         Ok(UnsafeBytes::from(SerializedBytes::try_from(self)?).into())
     }
 
@@ -43,6 +52,10 @@ pub enum WireMessage {
         #[serde(with = "serde_bytes")]
         receipt: Vec<u8>,
     },
+    PublishWarrants {
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    },
     Get {
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetOptions,
@@ -68,10 +81,47 @@ pub enum WireMessage {
     },
 }
 
+/// Private entries must never be sent to other agents.
+/// Ops are filtered long before they reach the network, so this is only
+/// a last check that a bug upstream can't leak private entry content.
+#[track_caller]
+fn check_no_private_entry(op: &holochain_types::dht_op::DhtOp) -> actor::HolochainP2pResult<()> {
+    if op.entry().is_none() {
+        return Ok(());
+    }
+    let action = op.action();
+    match action.entry_data() {
+        Some((entry_hash, entry_type)) if *entry_type.visibility() == EntryVisibility::Private => {
+            tracing::error!(
+                caller = %std::panic::Location::caller(),
+                op = %op.get_type(),
+                ?entry_hash,
+                "Refusing to encode an op with a private entry for the network"
+            );
+            Err(HolochainP2pError::PrivateEntryOnWire(entry_hash.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
 #[allow(missing_docs)]
 impl WireMessage {
-    pub fn encode(&self) -> Result<Vec<u8>, SerializedBytesError> {
-        holochain_serialized_bytes::encode(&self)
+    #[track_caller]
+    pub fn encode(&self) -> actor::HolochainP2pResult<Vec<u8>> {
+        match self {
+            Self::Publish { ops, .. } => {
+                for op in ops {
+                    check_no_private_entry(op)?;
+                }
+            }
+            Self::PublishWarrants { warrants, .. } => {
+                for warranted in warrants {
+                    check_no_private_entry(&warranted.op)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(holochain_serialized_bytes::encode(&self)?)
     }
 
     pub fn decode(data: &[u8]) -> Result<Self, SerializedBytesError> {
@@ -114,6 +164,13 @@ impl WireMessage {
         }
     }
 
+    pub fn publish_warrants(
+        basis: holo_hash::AnyDhtHash,
+        warrants: Vec<WarrantedOp>,
+    ) -> WireMessage {
+        Self::PublishWarrants { basis, warrants }
+    }
+
     pub fn get(dht_hash: holo_hash::AnyDhtHash, options: event::GetOptions) -> WireMessage {
         Self::Get { dht_hash, options }
     }
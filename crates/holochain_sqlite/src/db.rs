@@ -1,7 +1,7 @@
 //! Functions dealing with obtaining and referencing singleton databases
 
 use crate::{
-    conn::{new_connection_pool, ConnectionPool, DbSyncLevel, PConn, DATABASE_HANDLES},
+    conn::{new_connection_pool, ConnectionPool, DbKey, DbSyncLevel, PConn, DATABASE_HANDLES},
     prelude::*,
 };
 use derive_more::Into;
@@ -25,6 +25,9 @@ pub use p2p_agent_store::*;
 mod p2p_metrics;
 pub use p2p_metrics::*;
 
+mod savepoint;
+pub use savepoint::*;
+
 #[async_trait::async_trait]
 /// A trait for being generic over [`DbWrite`] and [`DbRead`] that
 /// both implement read access.
@@ -95,6 +98,62 @@ impl<Kind: DbKindT> ReadAccess<Kind> for DbRead<Kind> {
         &self.kind
     }
 }
+/// The size in bytes of the pages of a database, including free pages.
+fn db_size(conn: &Connection) -> DatabaseResult<u64> {
+    let page_count: u64 = conn.pragma_query_value(None, "page_count", |row| row.get(0))?;
+    let page_size: u64 = conn.pragma_query_value(None, "page_size", |row| row.get(0))?;
+    Ok(page_count * page_size)
+}
+
+/// How long to wait before retrying a backup which couldn't take its locks.
+const BACKUP_RETRY_PAUSE: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// How many times to try deleting a db file which is still in use.
+const REMOVE_ATTEMPTS: u32 = 10;
+
+/// How long removing a db waits for its other handles to be dropped.
+const REMOVE_HANDLES_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Whether deleting a file failed because it is still open somewhere.
+fn is_file_in_use(e: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    #[cfg(windows)]
+    const BUSY: &[i32] = &[32, 33];
+    // EBUSY
+    #[cfg(not(windows))]
+    const BUSY: &[i32] = &[16];
+    e.kind() == std::io::ErrorKind::PermissionDenied
+        || matches!(e.raw_os_error(), Some(code) if BUSY.contains(&code))
+}
+
+/// Delete a db file along with its write ahead log and shared memory files.
+///
+/// Deleting a file which another process or a connection which
+/// hasn't closed yet still has open fails on Windows, so a file
+/// which is in use is retried for a while before giving up.
+fn remove_db_files(path: &Path) -> DatabaseResult<()> {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    let mut shm = path.as_os_str().to_owned();
+    shm.push("-shm");
+    for file in [path.to_owned(), wal.into(), shm.into()] {
+        let mut attempt = 1;
+        loop {
+            match std::fs::remove_file(&file) {
+                Ok(()) => break,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+                Err(e) if is_file_in_use(&e) && attempt < REMOVE_ATTEMPTS => {
+                    tracing::debug!(?file, ?e, attempt, "Retrying removing db file");
+                    std::thread::sleep(std::time::Duration::from_millis(100 * attempt as u64));
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    Ok(())
+}
+
 /// A read-only version of [DbWrite].
 /// This environment can only generate read-only transactions, never read-write.
 #[derive(Clone)]
@@ -102,10 +161,13 @@ pub struct DbRead<Kind: DbKindT> {
     kind: Kind,
     path: PathBuf,
     connection_pool: ConnectionPool,
+    key: Option<DbKey>,
     write_semaphore: Arc<Semaphore>,
     read_semaphore: Arc<Semaphore>,
     max_readers: usize,
     num_readers: Arc<AtomicUsize>,
+    /// Shared by every handle to this db, to count them.
+    handles: Arc<()>,
 }
 
 #[derive(Shrinkwrap)]
@@ -162,6 +224,31 @@ impl<Kind: DbKindT> DbRead<Kind> {
         r
     }
 
+    /// Copy a consistent snapshot of this database to a new file at `dest`
+    /// with the SQLite online backup API, without blocking writers.
+    /// The copy is encrypted with the same key as this database.
+    pub async fn backup(&self, dest: PathBuf) -> DatabaseResult<()> {
+        let _g = self.acquire_reader_permit().await;
+        let conn = self.conn()?;
+        let key = self.key.clone();
+        task::spawn_blocking(move || {
+            let mut dest = Connection::open(dest)?;
+            crate::conn::initialize_connection(&mut dest, DbSyncLevel::Normal, key.as_ref())?;
+            let backup = rusqlite::backup::Backup::new(&conn, &mut dest)?;
+            // Copying every page in one step holds a single read
+            // transaction, so writes made meanwhile can't restart the copy.
+            loop {
+                match backup.step(-1)? {
+                    rusqlite::backup::StepResult::Done => break,
+                    // The locks couldn't be taken, so try again shortly.
+                    _ => std::thread::sleep(BACKUP_RETRY_PAUSE),
+                }
+            }
+            DatabaseResult::Ok(())
+        })
+        .await?
+    }
+
     pub async fn async_reader<E, R, F>(&self, f: F) -> Result<R, E>
     where
         E: From<DatabaseError> + Send + 'static,
@@ -220,9 +307,23 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         path_prefix: &Path,
         kind: Kind,
         sync_level: DbSyncLevel,
+    ) -> DatabaseResult<Self> {
+        Self::open_with_key(path_prefix, kind, sync_level, None)
+    }
+
+    /// Create or open an existing database reference,
+    /// encrypted at rest with this key if one is given.
+    ///
+    /// The key is only applied when the db is first opened
+    /// by this process, later calls return the existing handle.
+    pub fn open_with_key(
+        path_prefix: &Path,
+        kind: Kind,
+        sync_level: DbSyncLevel,
+        key: Option<DbKey>,
     ) -> DatabaseResult<Self> {
         DATABASE_HANDLES.get_or_insert(&kind, path_prefix, |kind| {
-            Self::new(Some(path_prefix), kind, sync_level)
+            Self::new(Some(path_prefix), kind, sync_level, key)
         })
     }
 
@@ -230,7 +331,11 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         path_prefix: Option<&Path>,
         kind: Kind,
         sync_level: DbSyncLevel,
+        key: Option<DbKey>,
     ) -> DatabaseResult<Self> {
+        if key.is_some() && !cfg!(feature = "db-encryption") {
+            return Err(DatabaseError::EncryptionUnavailable);
+        }
         let path = match path_prefix {
             Some(path_prefix) => {
                 let path = path_prefix.join(kind.filename());
@@ -246,32 +351,30 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                 match Connection::open(&path)
                     // For some reason calling pragma_update is necessary to prove the database file is valid.
                     .and_then(|mut c| {
-                        crate::conn::initialize_connection(&mut c, sync_level)?;
+                        crate::conn::initialize_connection(&mut c, sync_level, key.as_ref())?;
                         c.pragma_update(None, "synchronous", &"0".to_string())
                     }) {
                     Ok(_) => (),
                     // These are the two errors that can
                     // occur if the database is not valid.
-                    err @ Err(Error::SqliteFailure(
-                        rusqlite::ffi::Error {
-                            code: ErrorCode::DatabaseCorrupt,
-                            ..
-                        },
-                        ..,
-                    ))
-                    | err @ Err(Error::SqliteFailure(
-                        rusqlite::ffi::Error {
-                            code: ErrorCode::NotADatabase,
+                    Err(Error::SqliteFailure(
+                        e @ rusqlite::ffi::Error {
+                            code: ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase,
                             ..
                         },
-                        ..,
+                        msg,
                     )) => {
+                        // A key mismatch looks the same, but the data is
+                        // fine, so it must never be wiped.
+                        if is_key_mismatch(&path, key.as_ref(), e.code) {
+                            return Err(DatabaseError::KeyMismatch(path));
+                        }
                         // Check if this database kind requires wiping.
                         if kind.if_corrupt_wipe() {
                             std::fs::remove_file(&path)?;
                         } else {
                             // If we don't wipe we need to return an error.
-                            err?;
+                            return Err(Error::SqliteFailure(e, msg).into());
                         }
                     }
                     // Another error has occurred when trying to open the db.
@@ -283,7 +386,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         };
 
         // Now we know the database file is valid we can open a connection pool.
-        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level);
+        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level, key.clone());
         let mut conn = pool.get()?;
         // set to faster write-ahead-log mode
         conn.pragma_update(None, "journal_mode", &"WAL".to_string())?;
@@ -294,12 +397,38 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
             read_semaphore: Self::get_read_semaphore(kind.kind()),
             max_readers: num_read_threads(),
             num_readers: Arc::new(AtomicUsize::new(0)),
+            handles: Arc::new(()),
             kind,
             path: path.unwrap_or_default(),
             connection_pool: pool,
+            key,
         }))
     }
 
+    /// The size in bytes of the database, including the space left by deleted data.
+    pub async fn size(&self) -> DatabaseResult<u64> {
+        let conn = self.conn()?;
+        task::spawn_blocking(move || db_size(&conn)).await?
+    }
+
+    /// Rebuild the database file so the space left by deleted data is given
+    /// back to the file system, returning how many bytes were reclaimed.
+    /// Writes wait until the vacuum is done.
+    pub async fn vacuum(&self) -> DatabaseResult<u64> {
+        let _g = self.acquire_writer_permit().await;
+        let conn = self.conn()?;
+        task::spawn_blocking(move || {
+            let before = db_size(&conn)?;
+            conn.execute_batch("VACUUM")?;
+            // The vacuum goes through the write ahead log,
+            // so move it into the db file and truncate the log.
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            let after = db_size(&conn)?;
+            DatabaseResult::Ok(before.saturating_sub(after))
+        })
+        .await?
+    }
+
     fn get_write_semaphore(kind: DbKind) -> Arc<Semaphore> {
         static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
             once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
@@ -322,21 +451,42 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     /// connection pool, useful for testing.
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test(path: &Path, kind: Kind) -> DatabaseResult<Self> {
-        Self::new(Some(path), kind, DbSyncLevel::default())
+        Self::new(Some(path), kind, DbSyncLevel::default(), None)
     }
 
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test_in_mem(kind: Kind) -> DatabaseResult<Self> {
-        Self::new(None, kind, DbSyncLevel::default())
+        Self::new(None, kind, DbSyncLevel::default(), None)
     }
 
-    /// Remove the db and directory
-    #[deprecated = "is this used?"]
+    /// Close the db and delete its files.
+    ///
+    /// The db is forgotten so opening it again creates a new one.
+    /// Waits for the other handles to the db to be dropped first,
+    /// because on some platforms the files can't be deleted while
+    /// connections are open.
     pub async fn remove(self) -> DatabaseResult<()> {
-        if let Some(parent) = self.0.path.parent() {
-            std::fs::remove_dir_all(parent)?;
+        let path = self.0.path.clone();
+        DATABASE_HANDLES.remove(&path);
+        let waiting = std::time::Instant::now();
+        while Arc::strong_count(&self.0.handles) > 1 {
+            if waiting.elapsed() > REMOVE_HANDLES_TIMEOUT {
+                tracing::warn!(
+                    ?path,
+                    handles = Arc::strong_count(&self.0.handles) - 1,
+                    "Removing a db which still has open handles"
+                );
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
         }
-        Ok(())
+        // Dropping the last handle to the pool closes its connections.
+        drop(self);
+        // In memory dbs have no files.
+        if path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        task::spawn_blocking(move || remove_db_files(&path)).await?
     }
 
     pub async fn async_commit<E, R, F>(&self, f: F) -> Result<R, E>
@@ -722,3 +872,283 @@ where
         tokio::time::sleep(RETRY_INTERVAL).await;
     }
 }
+
+/// Whether a db file sqlite refused to open was written with a different
+/// key than `key`, or with none, rather than being corrupt.
+///
+/// Plaintext files start with the sqlite header and encrypted ones don't.
+/// With a key, an unreadable first page means either a wrong key or a
+/// corrupt first page, which can't be told apart, so it counts as a mismatch.
+fn is_key_mismatch(path: &Path, key: Option<&DbKey>, code: ErrorCode) -> bool {
+    const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+    let mut header = [0; 16];
+    let plaintext = match std::fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header))
+    {
+        Ok(()) => &header == SQLITE_HEADER,
+        // too short to be an encrypted db
+        Err(_) => return false,
+    };
+    match key {
+        Some(_) => plaintext || code == ErrorCode::NotADatabase,
+        None => !plaintext,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn removed_db_is_deleted_and_forgotten() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![0; 36])));
+        let db = DbWrite::open(dir.path(), kind.clone()).unwrap();
+        let path = db.path().clone();
+        db.async_commit(|txn| {
+            txn.execute("CREATE TABLE t (x INTEGER)", [])?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(path.exists());
+
+        db.remove().await.unwrap();
+        assert!(!path.exists());
+
+        // Opening the db again starts from scratch.
+        let db = DbWrite::open(dir.path(), kind).unwrap();
+        let exists: bool = db
+            .async_reader(|txn| {
+                DatabaseResult::Ok(txn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 't')",
+                    [],
+                    |row| row.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert!(!exists);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remove_waits_for_other_handles() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![1; 36])));
+        let db = DbWrite::open(dir.path(), kind).unwrap();
+        let path = db.path().clone();
+        let other = db.clone();
+        let released = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        tokio::spawn({
+            let released = released.clone();
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                released.store(true, std::sync::atomic::Ordering::SeqCst);
+                drop(other);
+            }
+        });
+
+        db.remove().await.unwrap();
+        // The other handle was dropped before the files were deleted.
+        assert!(released.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(!path.exists());
+    }
+
+    #[cfg(not(feature = "db-encryption"))]
+    #[test]
+    fn key_requires_encryption_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![0; 36])));
+        let r = DbWrite::new(
+            Some(dir.path()),
+            kind,
+            DbSyncLevel::default(),
+            Some(DbKey::new([1; 32])),
+        );
+        assert!(matches!(r, Err(DatabaseError::EncryptionUnavailable)));
+    }
+
+    #[cfg(feature = "db-encryption")]
+    #[test]
+    fn encrypted_db_needs_its_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindAuthored(Arc::new(DnaHash::from_raw_36(vec![0; 36])));
+        let key = DbKey::new([1; 32]);
+        let db = DbWrite::new(
+            Some(dir.path()),
+            kind.clone(),
+            DbSyncLevel::default(),
+            Some(key.clone()),
+        )
+        .unwrap();
+        let path = db.path().clone();
+        drop(db);
+
+        // The file can't be read without the key.
+        let err = Connection::open(&path)
+            .and_then(|c| {
+                c.query_row("SELECT count(*) FROM sqlite_master", [], |r| {
+                    r.get::<_, i64>(0)
+                })
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SqliteFailure(
+                rusqlite::ffi::Error {
+                    code: ErrorCode::NotADatabase,
+                    ..
+                },
+                ..
+            )
+        ));
+
+        // A wrong key is refused for dbs that can't be wiped.
+        assert!(DbWrite::new(
+            Some(dir.path()),
+            kind.clone(),
+            DbSyncLevel::default(),
+            Some(DbKey::new([2; 32])),
+        )
+        .is_err());
+        assert!(DbWrite::new(Some(dir.path()), kind, DbSyncLevel::default(), Some(key)).is_ok());
+    }
+
+    #[cfg(feature = "db-encryption")]
+    #[test]
+    fn key_mismatch_never_wipes() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![0; 36])));
+        let key = DbKey::new([1; 32]);
+        let marked = |db: &DbWrite<DbKindCache>| {
+            db.conn()
+                .unwrap()
+                .execute_batch("CREATE TABLE IF NOT EXISTS marker (x INTEGER)")
+                .unwrap();
+        };
+        let db = DbWrite::new(
+            Some(dir.path()),
+            kind.clone(),
+            DbSyncLevel::default(),
+            Some(key.clone()),
+        )
+        .unwrap();
+        marked(&db);
+        let path = db.path().clone();
+        drop(db);
+
+        // - A wrong key or no key is an error, even for dbs that are
+        //   wiped when corrupt, and the file is left alone.
+        for wrong_key in [Some(DbKey::new([2; 32])), None] {
+            assert!(matches!(
+                DbWrite::new(
+                    Some(dir.path()),
+                    kind.clone(),
+                    DbSyncLevel::default(),
+                    wrong_key
+                ),
+                Err(DatabaseError::KeyMismatch(_))
+            ));
+            assert!(path.exists());
+        }
+        let db = DbWrite::new(
+            Some(dir.path()),
+            kind.clone(),
+            DbSyncLevel::default(),
+            Some(key),
+        )
+        .unwrap();
+        let kept: bool = db
+            .conn()
+            .unwrap()
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'marker')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(kept);
+    }
+
+    #[test]
+    fn corrupt_plaintext_db_is_wiped() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![0; 36])));
+        let db =
+            DbWrite::new(Some(dir.path()), kind.clone(), DbSyncLevel::default(), None).unwrap();
+        let path = db.path().clone();
+        drop(db);
+
+        // - Keep the sqlite header but trash the rest of the first page.
+        let mut bytes = std::fs::read(&path).unwrap();
+        for b in bytes.iter_mut().skip(16).take(84) {
+            *b = 0xff;
+        }
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(Connection::open(&path)
+            .and_then(|c| c.pragma_update(None, "synchronous", "0"))
+            .is_err());
+        assert!(!is_key_mismatch(&path, None, ErrorCode::NotADatabase));
+        assert!(is_key_mismatch(
+            &path,
+            Some(&DbKey::new([1; 32])),
+            ErrorCode::NotADatabase
+        ));
+        assert!(DbWrite::new(Some(dir.path()), kind, DbSyncLevel::default(), None).is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn backup_copies_the_db() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![1; 36])));
+        let db = DbWrite::test(dir.path(), kind.clone()).unwrap();
+        db.async_commit(|txn| {
+            txn.execute("CREATE TABLE t (x INTEGER)", [])?;
+            txn.execute("INSERT INTO t (x) VALUES (42)", [])?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+
+        let backup_dir = tempfile::tempdir().unwrap();
+        let dest = backup_dir.path().join(kind.filename());
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        db.backup(dest).await.unwrap();
+
+        let copy = DbWrite::test(backup_dir.path(), kind).unwrap();
+        let x: i64 = copy
+            .async_reader(|txn| {
+                DatabaseResult::Ok(txn.query_row("SELECT x FROM t", [], |row| row.get(0))?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(x, 42);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn vacuum_reclaims_deleted_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = DbKindCache(Arc::new(DnaHash::from_raw_36(vec![2; 36])));
+        let db = DbWrite::test(dir.path(), kind).unwrap();
+        db.async_commit(|txn| {
+            txn.execute("CREATE TABLE t (x BLOB)", [])?;
+            for _ in 0..100 {
+                txn.execute("INSERT INTO t (x) VALUES (zeroblob(10000))", [])?;
+            }
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+        db.async_commit(|txn| {
+            txn.execute("DELETE FROM t", [])?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert!(db.vacuum().await.unwrap() >= 100 * 10000);
+        // There is nothing left to reclaim.
+        assert_eq!(db.vacuum().await.unwrap(), 0);
+    }
+}
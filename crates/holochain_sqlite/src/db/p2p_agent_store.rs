@@ -3,6 +3,8 @@
 use crate::prelude::*;
 use crate::sql::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::block::{Block, BlockTarget};
+use kitsune_p2p::dependencies::kitsune_p2p_timestamp::Timestamp;
 use kitsune_p2p::dht_arc::DhtArcRange;
 use kitsune_p2p::dht_arc::DhtArcSet;
 use kitsune_p2p::KitsuneAgent;
@@ -35,6 +37,9 @@ pub trait AsP2pAgentStoreConExt {
 
     /// Extrapolate coverage from agents within our own storage arc
     fn p2p_extrapolated_coverage(&mut self, dht_arc_set: DhtArcSet) -> DatabaseResult<Vec<f64>>;
+
+    /// List all blocks within a space, including expired ones
+    fn p2p_list_blocks(&mut self) -> DatabaseResult<Vec<Block>>;
 }
 
 /// Extension trait to treat transaction instances
@@ -59,6 +64,9 @@ pub trait AsP2pStateTxExt {
 
     /// Extrapolate coverage from agents within our own storage arc
     fn p2p_extrapolated_coverage(&self, dht_arc_set: DhtArcSet) -> DatabaseResult<Vec<f64>>;
+
+    /// List all blocks within a space, including expired ones
+    fn p2p_list_blocks(&self) -> DatabaseResult<Vec<Block>>;
 }
 
 impl AsP2pAgentStoreConExt for crate::db::PConnGuard {
@@ -90,6 +98,10 @@ impl AsP2pAgentStoreConExt for crate::db::PConnGuard {
     fn p2p_extrapolated_coverage(&mut self, dht_arc_set: DhtArcSet) -> DatabaseResult<Vec<f64>> {
         self.with_reader(move |reader| reader.p2p_extrapolated_coverage(dht_arc_set))
     }
+
+    fn p2p_list_blocks(&mut self) -> DatabaseResult<Vec<Block>> {
+        self.with_reader(move |reader| reader.p2p_list_blocks())
+    }
 }
 
 /// Put an AgentInfoSigned record into the p2p_store
@@ -180,6 +192,43 @@ pub async fn p2p_prune(
     Ok(())
 }
 
+/// Put a block into the p2p_store, replacing any block on the same target
+pub async fn p2p_put_block(db: &DbWrite<DbKindP2pAgents>, block: Block) -> DatabaseResult<()> {
+    let target = encode_block_target(&block.target)?;
+    let until_micros = block.until.map(|until| until.as_micros());
+    db.async_commit(move |txn| {
+        txn.execute(
+            sql_p2p_agent_store::INSERT_BLOCK,
+            named_params! {
+                ":target": target,
+                ":until_micros": until_micros,
+            },
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+}
+
+/// Remove the block on a target from the p2p_store
+pub async fn p2p_delete_block(
+    db: &DbWrite<DbKindP2pAgents>,
+    target: &BlockTarget,
+) -> DatabaseResult<()> {
+    let target = encode_block_target(target)?;
+    db.async_commit(move |txn| {
+        txn.execute(
+            sql_p2p_agent_store::DELETE_BLOCK,
+            named_params! { ":target": target },
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+}
+
+fn encode_block_target(target: &BlockTarget) -> DatabaseResult<Vec<u8>> {
+    Ok(rmp_serde::to_vec_named(target).map_err(|e| anyhow::anyhow!(e))?)
+}
+
 impl AsP2pStateTxExt for Transaction<'_> {
     fn p2p_get_agent(&self, agent: &KitsuneAgent) -> DatabaseResult<Option<AgentInfoSigned>> {
         let mut stmt = self
@@ -312,6 +361,27 @@ impl AsP2pStateTxExt for Transaction<'_> {
 
         Ok(out)
     }
+
+    fn p2p_list_blocks(&self) -> DatabaseResult<Vec<Block>> {
+        let mut stmt = self
+            .prepare(sql_p2p_agent_store::SELECT_BLOCKS)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        let mut out = Vec::new();
+        for r in stmt.query_map([], |r| {
+            let target = r.get_ref(0)?;
+            let target: BlockTarget = rmp_serde::from_slice(target.as_blob()?)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+            let until_micros: Option<i64> = r.get(1)?;
+
+            Ok(Block {
+                target,
+                until: until_micros.map(Timestamp::from_micros),
+            })
+        })? {
+            out.push(r?);
+        }
+        Ok(out)
+    }
 }
 
 /// Owned data dealing with a full p2p_agent_store record.
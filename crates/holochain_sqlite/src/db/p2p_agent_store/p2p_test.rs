@@ -252,3 +252,50 @@ async fn test_p2p_agent_store_gossip_query_sanity() {
     // clean up temp dir
     tmp_dir.close().unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_agent_store_blocks() {
+    use kitsune_p2p::block::{Block, BlockTarget};
+    use kitsune_p2p::dependencies::kitsune_p2p_timestamp::Timestamp;
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("p2p_agent_store_blocks")
+        .tempdir()
+        .unwrap();
+
+    let space = rand_space();
+
+    let db = DbWrite::test(tmp_dir.path(), DbKindP2pAgents(space.clone())).unwrap();
+
+    let agent_block = Block {
+        target: BlockTarget::Agent(rand_agent()),
+        until: None,
+    };
+    let cert_block = Block {
+        target: BlockTarget::Cert(vec![1; 32].into()),
+        until: Some(Timestamp::from_micros(1000)),
+    };
+
+    p2p_put_block(&db, agent_block.clone()).await.unwrap();
+    p2p_put_block(&db, cert_block.clone()).await.unwrap();
+
+    let permit = db.conn_permit().await;
+    let mut con = db.with_permit(permit).unwrap();
+
+    let mut blocks = con.p2p_list_blocks().unwrap();
+    blocks.sort_by_key(|b| b.until);
+    assert_eq!(blocks, vec![agent_block.clone(), cert_block.clone()]);
+
+    // putting a block on the same target replaces it
+    let agent_block = Block {
+        until: Some(Timestamp::from_micros(2000)),
+        ..agent_block
+    };
+    p2p_put_block(&db, agent_block.clone()).await.unwrap();
+    p2p_delete_block(&db, &cert_block.target).await.unwrap();
+
+    assert_eq!(con.p2p_list_blocks().unwrap(), vec![agent_block]);
+
+    // clean up temp dir
+    tmp_dir.close().unwrap();
+}
@@ -1,10 +1,12 @@
 use crate::prelude::{DatabaseError, DatabaseResult};
 use crate::sql::*;
 use holochain_zome_types::prelude::*;
-use kitsune_p2p::event::MetricRecord;
+use kitsune_p2p::event::{MetricQuery, MetricQueryAnswer, MetricRecord, MetricRecordKind};
+use kitsune_p2p::KitsuneAgent;
 use rusqlite::*;
 use std::{
     num::TryFromIntError,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
@@ -33,11 +35,13 @@ pub fn time_from_micros(micros: i64) -> DatabaseResult<SystemTime> {
 pub trait AsP2pMetricStoreConExt {
     fn p2p_log_metrics(&mut self, metrics: Vec<MetricRecord>) -> DatabaseResult<()>;
     fn p2p_prune_metrics(&mut self) -> DatabaseResult<()>;
+    fn p2p_query_metrics(&mut self, query: MetricQuery) -> DatabaseResult<MetricQueryAnswer>;
 }
 
 pub trait AsP2pMetricStoreTxExt {
     fn p2p_log_metrics(&self, metrics: Vec<MetricRecord>) -> DatabaseResult<()>;
     fn p2p_prune_metrics(&self) -> DatabaseResult<()>;
+    fn p2p_query_metrics(&self, query: MetricQuery) -> DatabaseResult<MetricQueryAnswer>;
 }
 
 impl AsP2pMetricStoreConExt for crate::db::PConnGuard {
@@ -50,6 +54,10 @@ impl AsP2pMetricStoreConExt for crate::db::PConnGuard {
         use crate::db::WriteManager;
         self.with_commit_sync(move |writer| writer.p2p_prune_metrics())
     }
+
+    fn p2p_query_metrics(&mut self, query: MetricQuery) -> DatabaseResult<MetricQueryAnswer> {
+        self.with_reader(move |reader| reader.p2p_query_metrics(query))
+    }
 }
 
 impl AsP2pMetricStoreTxExt for Transaction<'_> {
@@ -84,4 +92,57 @@ impl AsP2pMetricStoreTxExt for Transaction<'_> {
         )?;
         Ok(())
     }
+
+    fn p2p_query_metrics(&self, query: MetricQuery) -> DatabaseResult<MetricQueryAnswer> {
+        Ok(match query {
+            MetricQuery::LatencyMicros { since } => {
+                MetricQueryAnswer::LatencyMicros(query_by_agent(
+                    self,
+                    sql_p2p_metrics::QUERY_MEAN,
+                    MetricRecordKind::LatencyMicros,
+                    since,
+                )?)
+            }
+            MetricQuery::ErrorRate { since } => MetricQueryAnswer::ErrorRate(query_by_agent(
+                self,
+                sql_p2p_metrics::QUERY_MEAN,
+                MetricRecordKind::ErrorRate,
+                since,
+            )?),
+            MetricQuery::LastSeen => MetricQueryAnswer::LastSeen(
+                query_by_agent(
+                    self,
+                    sql_p2p_metrics::QUERY_MAX,
+                    MetricRecordKind::LastSeen,
+                    Timestamp::MIN,
+                )?
+                .into_iter()
+                .map(|(agent, micros): (_, i64)| (agent, Timestamp::from_micros(micros)))
+                .collect(),
+            ),
+        })
+    }
+}
+
+/// Run an aggregate query over the records of one kind made
+/// at or after `since`, returning a value per agent.
+fn query_by_agent<T: rusqlite::types::FromSql>(
+    txn: &Transaction<'_>,
+    sql: &str,
+    kind: MetricRecordKind,
+    since: Timestamp,
+) -> DatabaseResult<Vec<(Arc<KitsuneAgent>, T)>> {
+    let mut stmt = txn.prepare(sql)?;
+    let mut out = Vec::new();
+    for r in stmt.query_map(
+        named_params! {
+            ":kind": kind.to_db(),
+            ":since_micros": since.as_micros(),
+        },
+        |r| Ok((r.get::<_, Vec<u8>>(0)?, r.get(1)?)),
+    )? {
+        let (agent, value) = r?;
+        out.push((Arc::new(KitsuneAgent(agent)), value));
+    }
+    Ok(out)
 }
@@ -66,3 +66,65 @@ async fn test_p2p_metric_store_sanity() {
     // clean up temp dir
     tmp_dir.close().unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_metric_store_queries() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("p2p_metric_store_queries")
+        .tempdir()
+        .unwrap();
+
+    let space = rand_space();
+    let agent = rand_agent();
+
+    let db = DbWrite::test(tmp_dir.path(), DbKindP2pMetrics(space.clone())).unwrap();
+
+    let permit = db.conn_permit().await;
+    let mut con = db.with_permit(permit).unwrap();
+
+    let record = |kind, micros, data| MetricRecord {
+        kind,
+        agent: Some(agent.clone()),
+        recorded_at_utc: Timestamp::from_micros(micros),
+        expires_at_utc: Timestamp::MAX,
+        data,
+    };
+
+    con.p2p_log_metrics(vec![
+        // -- too old to be counted -- //
+        record(
+            MetricRecordKind::LatencyMicros,
+            10,
+            serde_json::json!(1000.0),
+        ),
+        record(MetricRecordKind::ErrorRate, 10, serde_json::json!(1.0)),
+        // -- recent -- //
+        record(MetricRecordKind::LatencyMicros, 20, serde_json::json!(10.0)),
+        record(MetricRecordKind::LatencyMicros, 30, serde_json::json!(30.0)),
+        record(MetricRecordKind::ErrorRate, 20, serde_json::json!(0.5)),
+        record(MetricRecordKind::ErrorRate, 30, serde_json::json!(0.0)),
+        record(MetricRecordKind::LastSeen, 20, serde_json::json!(15)),
+        record(MetricRecordKind::LastSeen, 30, serde_json::json!(25)),
+    ])
+    .unwrap();
+
+    let since = Timestamp::from_micros(20);
+
+    assert_eq!(
+        MetricQueryAnswer::LatencyMicros(vec![(agent.clone(), 20.0)]),
+        con.p2p_query_metrics(MetricQuery::LatencyMicros { since })
+            .unwrap(),
+    );
+    assert_eq!(
+        MetricQueryAnswer::ErrorRate(vec![(agent.clone(), 0.25)]),
+        con.p2p_query_metrics(MetricQuery::ErrorRate { since })
+            .unwrap(),
+    );
+    assert_eq!(
+        MetricQueryAnswer::LastSeen(vec![(agent.clone(), Timestamp::from_micros(25))]),
+        con.p2p_query_metrics(MetricQuery::LastSeen).unwrap(),
+    );
+
+    // clean up temp dir
+    tmp_dir.close().unwrap();
+}
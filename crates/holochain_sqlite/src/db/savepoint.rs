@@ -0,0 +1,110 @@
+//! Nested savepoints within a single database transaction.
+
+use crate::error::DatabaseError;
+use rusqlite::Savepoint;
+use rusqlite::Transaction;
+
+/// Anything a savepoint can be opened on: a transaction, or another
+/// savepoint to nest within it.
+pub trait OpenSavepoint {
+    /// Open a new savepoint, which rolls back when dropped.
+    fn open_savepoint(&mut self) -> rusqlite::Result<Savepoint<'_>>;
+}
+
+impl OpenSavepoint for Transaction<'_> {
+    fn open_savepoint(&mut self) -> rusqlite::Result<Savepoint<'_>> {
+        self.savepoint()
+    }
+}
+
+impl OpenSavepoint for Savepoint<'_> {
+    fn open_savepoint(&mut self) -> rusqlite::Result<Savepoint<'_>> {
+        self.savepoint()
+    }
+}
+
+/// Run a closure inside a savepoint of an open transaction.
+///
+/// If the closure returns an error, only the writes it made are rolled back
+/// and the rest of the transaction is kept. Savepoints can be nested by
+/// calling this again from within the closure.
+pub fn with_savepoint<T, E, R, F>(txn: &mut T, f: F) -> Result<R, E>
+where
+    T: OpenSavepoint,
+    E: From<DatabaseError>,
+    F: FnOnce(&mut Savepoint) -> Result<R, E>,
+{
+    let mut savepoint = txn.open_savepoint().map_err(DatabaseError::from)?;
+    let r = f(&mut savepoint)?;
+    savepoint.commit().map_err(DatabaseError::from)?;
+    Ok(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DatabaseResult;
+    use rusqlite::Connection;
+
+    fn insert(txn: &Connection, x: u32) {
+        txn.execute("INSERT INTO t (x) VALUES (?)", [x]).unwrap();
+    }
+
+    fn select(txn: &Connection) -> Vec<u32> {
+        txn.prepare("SELECT x FROM t ORDER BY x")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn fail() -> DatabaseResult<()> {
+        Err(DatabaseError::Other(anyhow::anyhow!("fail")))
+    }
+
+    #[test]
+    fn nested_savepoints_roll_back_independently() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE t (x INTEGER PRIMARY KEY)", [])
+            .unwrap();
+        let mut txn = conn.transaction().unwrap();
+        insert(&txn, 1);
+
+        with_savepoint(&mut txn, |txn| {
+            insert(txn, 2);
+
+            // - A failed inner savepoint only discards its own writes
+            with_savepoint(txn, |txn| {
+                insert(txn, 3);
+                fail()
+            })
+            .unwrap_err();
+            assert_eq!(select(txn), vec![1, 2]);
+
+            // - A successful inner savepoint keeps its writes
+            with_savepoint(txn, |txn| {
+                insert(txn, 4);
+                DatabaseResult::Ok(())
+            })
+        })
+        .unwrap();
+        assert_eq!(select(&txn), vec![1, 2, 4]);
+
+        // - A failed outer savepoint discards the inner savepoints it released
+        with_savepoint(&mut txn, |txn| {
+            insert(txn, 5);
+            with_savepoint(txn, |txn| {
+                insert(txn, 6);
+                DatabaseResult::Ok(())
+            })?;
+            fail()
+        })
+        .unwrap_err();
+        assert_eq!(select(&txn), vec![1, 2, 4]);
+
+        txn.commit().unwrap();
+        let txn = conn.transaction().unwrap();
+        assert_eq!(select(&txn), vec![1, 2, 4]);
+    }
+}
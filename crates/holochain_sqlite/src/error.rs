@@ -50,6 +50,12 @@ pub enum DatabaseError {
     #[error("Unable to construct a value key")]
     KeyConstruction,
 
+    #[error("Database encryption was requested but holochain_sqlite was built without the db-encryption feature")]
+    EncryptionUnavailable,
+
+    #[error("The database at {0} was written with a different encryption key, or without one")]
+    KeyMismatch(PathBuf),
+
     #[error("transparent")]
     FailedToJoinBlocking(#[from] tokio::task::JoinError),
 }
@@ -25,6 +25,8 @@ pub mod sql_cell {
 
     pub const FETCH_OP: &str = include_str!("sql/cell/fetch_op.sql");
 
+    pub const AUTHORED_BYTES: &str = include_str!("sql/cell/authored_bytes.sql");
+
     pub mod schedule {
         pub const UPDATE: &str = include_str!("sql/cell/schedule/update.sql");
         pub const DELETE: &str = include_str!("sql/cell/schedule/delete.sql");
@@ -39,9 +41,13 @@ pub mod sql_cell {
             include_str!("sql/cell/state_dump/dht_ops_in_integration_limbo.sql");
         pub const DHT_OPS_INTEGRATED: &str =
             include_str!("sql/cell/state_dump/dht_ops_integrated.sql");
+        pub const DHT_OPS_INTEGRATED_QUERY: &str =
+            include_str!("sql/cell/state_dump/dht_ops_integrated_query.sql");
         pub const DHT_OPS_IN_VALIDATION_LIMBO: &str =
             include_str!("sql/cell/state_dump/dht_ops_in_validation_limbo.sql");
         pub const DHT_OPS_ROW_ID: &str = include_str!("sql/cell/state_dump/dht_ops_row_id.sql");
+        pub const DHT_OPS_REJECTIONS: &str =
+            include_str!("sql/cell/state_dump/dht_ops_rejections.sql");
     }
 }
 
@@ -64,10 +70,15 @@ pub(crate) mod sql_p2p_agent_store {
     pub(crate) const EXTRAPOLATED_COVERAGE: &str =
         include_str!("sql/p2p_agent_store/extrapolated_coverage.sql");
     pub(crate) const PRUNE: &str = include_str!("sql/p2p_agent_store/prune.sql");
+    pub(crate) const INSERT_BLOCK: &str = include_str!("sql/p2p_agent_store/insert_block.sql");
+    pub(crate) const DELETE_BLOCK: &str = include_str!("sql/p2p_agent_store/delete_block.sql");
+    pub(crate) const SELECT_BLOCKS: &str = include_str!("sql/p2p_agent_store/select_blocks.sql");
 }
 
 pub(crate) mod sql_p2p_metrics {
     pub(crate) const SCHEMA: &str = include_str!("sql/p2p_metrics/schema.sql");
     pub(crate) const INSERT: &str = include_str!("sql/p2p_metrics/insert.sql");
     pub(crate) const PRUNE: &str = include_str!("sql/p2p_metrics/prune.sql");
+    pub(crate) const QUERY_MEAN: &str = include_str!("sql/p2p_metrics/query_mean.sql");
+    pub(crate) const QUERY_MAX: &str = include_str!("sql/p2p_metrics/query_max.sql");
 }
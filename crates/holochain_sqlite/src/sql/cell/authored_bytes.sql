@@ -0,0 +1,23 @@
+SELECT
+  COALESCE(SUM(LENGTH(Action.blob)), 0) + COALESCE(
+    (
+      SELECT
+        SUM(LENGTH(Entry.blob))
+      FROM
+        Entry
+      WHERE
+        Entry.hash IN (
+          SELECT
+            entry_hash
+          FROM
+            Action
+          WHERE
+            author = :author
+        )
+    ),
+    0
+  )
+FROM
+  Action
+WHERE
+  Action.author = :author
@@ -122,6 +122,10 @@ CREATE TABLE IF NOT EXISTS DhtOp (
     validation_stage            INTEGER     NULL,
     num_validation_attempts     INTEGER     NULL,
     last_validation_attempt     INTEGER     NULL,
+    -- The hash an op in stage 0 is waiting on.
+    -- The op is not sys validated again until this is held
+    -- or the op has waited long enough to retry fetching it.
+    sys_validation_dependency   BLOB        NULL,
 
     -- NB: I removed this because when_integrated covers it
     -- TODO: @freesig: Might be hard to index on various timestamps?
@@ -153,6 +157,47 @@ CREATE TABLE IF NOT EXISTS ValidationReceipt (
     FOREIGN KEY(op_hash) REFERENCES DhtOp(hash)
 );
 
+-- Why an op was rejected during sys or app validation.
+CREATE TABLE IF NOT EXISTS ValidationRejection (
+    op_hash         BLOB           PRIMARY KEY ON CONFLICT REPLACE,
+    blob            BLOB           NOT NULL,
+    FOREIGN KEY(op_hash) REFERENCES DhtOp(hash) ON DELETE CASCADE
+);
+
+-- Signed claims by validators that an author published invalid data.
+-- Not tied to the invalid op so they can be held by authorities and caches
+-- which never received it.
+CREATE TABLE IF NOT EXISTS Warrant (
+    action_hash     BLOB           NOT NULL,
+    warrantor       BLOB           NOT NULL,
+    author          BLOB           NOT NULL,
+    basis_hash      BLOB           NOT NULL,
+    blob            BLOB           NOT NULL,
+    PRIMARY KEY (action_hash, basis_hash, warrantor) ON CONFLICT IGNORE
+);
+CREATE INDEX IF NOT EXISTS Warrant_author_idx ON Warrant ( author );
+CREATE INDEX IF NOT EXISTS Warrant_basis_idx ON Warrant ( basis_hash );
+
+-- Warrants received from the network whose op has not yet been validated
+-- by this node. They are moved to the Warrant table if the op is rejected
+-- and dropped if it is valid.
+CREATE TABLE IF NOT EXISTS PendingWarrant (
+    action_hash     BLOB           NOT NULL,
+    warrantor       BLOB           NOT NULL,
+    basis_hash      BLOB           NOT NULL,
+    blob            BLOB           NOT NULL,
+    PRIMARY KEY (action_hash, basis_hash, warrantor) ON CONFLICT IGNORE
+);
+CREATE INDEX IF NOT EXISTS PendingWarrant_warrantor_idx ON PendingWarrant ( warrantor );
+CREATE INDEX IF NOT EXISTS Warrant_warrantor_idx ON Warrant ( warrantor );
+
+-- Validation packages built by authors for their own actions
+-- or received from the authors of actions being validated.
+CREATE TABLE IF NOT EXISTS ValidationPackage (
+    action_hash     BLOB           PRIMARY KEY ON CONFLICT REPLACE,
+    blob            BLOB           NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS ChainLock (
     lock BLOB PRIMARY KEY ON CONFLICT ROLLBACK,
     author BLOB NOT NULL,
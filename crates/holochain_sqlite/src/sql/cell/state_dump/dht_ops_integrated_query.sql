@@ -0,0 +1,24 @@
+-- no-sql-format --
+SELECT
+  Action.blob as action_blob,
+  Entry.blob as entry_blob,
+  DhtOp.type as type,
+  DhtOp.hash as hash,
+  DhtOp.validation_status as validation_status,
+  DhtOp.when_integrated as when_integrated,
+  DhtOp.rowid as rowid
+FROM
+  DhtOp
+  JOIN Action ON DhtOp.action_hash = Action.hash
+  LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+WHERE
+  DhtOp.when_integrated IS NOT NULL
+  AND DhtOp.rowid > :cursor
+  AND (:op_type IS NULL OR DhtOp.type = :op_type)
+  AND (:basis_hash IS NULL OR DhtOp.basis_hash = :basis_hash)
+  AND (:integrated_after IS NULL OR DhtOp.when_integrated >= :integrated_after)
+  AND (:integrated_before IS NULL OR DhtOp.when_integrated < :integrated_before)
+ORDER BY
+  DhtOp.rowid
+LIMIT
+  :limit
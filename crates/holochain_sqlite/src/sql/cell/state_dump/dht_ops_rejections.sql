@@ -0,0 +1,10 @@
+-- no-sql-format --
+SELECT
+  ValidationRejection.op_hash as dht_hash,
+  ValidationRejection.blob as rejection_blob,
+  DhtOp.rowid as rowid
+FROM
+  ValidationRejection
+  JOIN DhtOp ON DhtOp.hash = ValidationRejection.op_hash
+WHERE
+  DhtOp.validation_status IS NOT NULL
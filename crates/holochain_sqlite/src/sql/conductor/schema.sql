@@ -4,3 +4,13 @@ CREATE TABLE IF NOT EXISTS ConductorState (
     id              INTEGER        PRIMARY KEY ON CONFLICT REPLACE,
     blob            BLOB           NOT NULL
 );
+
+-- The nonces of signed zome calls which haven't expired yet,
+-- so a signed call can't be replayed after a restart.
+CREATE TABLE IF NOT EXISTS ZomeCallNonce (
+    agent           BLOB           NOT NULL,
+    nonce           BLOB           NOT NULL,
+    expires_at      INTEGER        NOT NULL,
+
+    PRIMARY KEY (agent, nonce) ON CONFLICT IGNORE
+);
@@ -0,0 +1,4 @@
+DELETE FROM
+  p2p_blocks
+WHERE
+  target = :target;
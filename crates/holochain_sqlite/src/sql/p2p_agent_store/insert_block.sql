@@ -0,0 +1,4 @@
+INSERT INTO
+  p2p_blocks (target, until_micros)
+VALUES
+  (:target, :until_micros);
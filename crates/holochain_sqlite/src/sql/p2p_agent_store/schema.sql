@@ -26,3 +26,13 @@ CREATE TABLE IF NOT EXISTS p2p_agent_store (
   storage_start_loc       INTEGER   NULL,
   storage_end_loc         INTEGER   NULL
 );
+
+-- blocked peers
+CREATE TABLE IF NOT EXISTS p2p_blocks (
+  -- msgpack encoded kitsune BlockTarget
+  target                  BLOB      PRIMARY KEY ON CONFLICT REPLACE,
+
+  -- microseconds since the epoch when the block ends
+  -- NULL blocks until the peer is unblocked
+  until_micros            INTEGER   NULL
+);
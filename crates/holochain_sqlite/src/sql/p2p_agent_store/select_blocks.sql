@@ -0,0 +1,5 @@
+SELECT
+  target,
+  until_micros
+FROM
+  p2p_blocks;
@@ -0,0 +1,11 @@
+SELECT
+  agent,
+  AVG(CAST(data AS REAL))
+FROM
+  p2p_metrics
+WHERE
+  kind = :kind
+  AND agent IS NOT NULL
+  AND recorded_at_utc_micros >= :since_micros
+GROUP BY
+  agent;
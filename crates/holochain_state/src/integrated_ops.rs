@@ -0,0 +1,215 @@
+//! Queries over the ops an authority has integrated, for diagnosing
+//! missing data on the DHT.
+
+use holo_hash::AnyDhtHash;
+use holo_hash::DhtOpHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::sql::sql_cell::state_dump::DHT_OPS_INTEGRATED_QUERY;
+use holochain_types::dht_op::DhtOp;
+use holochain_types::dht_op::DhtOpType;
+use holochain_zome_types::Timestamp;
+use holochain_zome_types::ValidationStatus;
+
+use crate::query::map_sql_dht_op_common;
+use crate::query::StateQueryResult;
+
+/// The number of ops returned when a query doesn't set a limit.
+pub const DEFAULT_INTEGRATED_OPS_LIMIT: u32 = 100;
+
+/// Which integrated ops to list. Every filter that is set must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IntegratedDhtOpsQuery {
+    /// Only list ops of this type.
+    pub op_type: Option<DhtOpType>,
+    /// Only list ops with this basis hash.
+    pub basis_hash: Option<AnyDhtHash>,
+    /// Only list ops integrated at or after this time.
+    pub integrated_after: Option<Timestamp>,
+    /// Only list ops integrated before this time.
+    pub integrated_before: Option<Timestamp>,
+    /// The cursor returned with the previous page.
+    /// Only ops after the cursor are listed.
+    pub cursor: Option<u64>,
+    /// The most ops to return in one page.
+    /// Defaults to [`DEFAULT_INTEGRATED_OPS_LIMIT`].
+    pub limit: Option<u32>,
+}
+
+/// An op that has been integrated into the DHT database.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IntegratedDhtOp {
+    /// The hash of the op.
+    pub hash: DhtOpHash,
+    /// The op. Entries are only included for public entry types.
+    pub op: DhtOp,
+    /// The outcome of validating the op.
+    pub validation_status: Option<ValidationStatus>,
+    /// When the op was integrated.
+    pub when_integrated: Timestamp,
+}
+
+/// One page of the results of an [`IntegratedDhtOpsQuery`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IntegratedDhtOpsPage {
+    /// The ops in this page, in the order they were added to the database.
+    pub ops: Vec<IntegratedDhtOp>,
+    /// Set this as the query's cursor to get the next page.
+    /// `None` if there are no more ops.
+    pub cursor: Option<u64>,
+}
+
+/// List the integrated ops that match a query.
+pub fn query_integrated(
+    txn: &Transaction,
+    query: &IntegratedDhtOpsQuery,
+) -> StateQueryResult<IntegratedDhtOpsPage> {
+    let limit = query.limit.unwrap_or(DEFAULT_INTEGRATED_OPS_LIMIT);
+    let mut stmt = txn.prepare_cached(DHT_OPS_INTEGRATED_QUERY)?;
+    let rows = stmt
+        .query_and_then(
+            named_params! {
+                ":cursor": query.cursor.unwrap_or(0),
+                ":op_type": query.op_type,
+                ":basis_hash": query.basis_hash,
+                ":integrated_after": query.integrated_after,
+                ":integrated_before": query.integrated_before,
+                ":limit": limit,
+            },
+            |row| {
+                let rowid: u64 = row.get("rowid")?;
+                let op = map_sql_dht_op_common(row)?
+                    .map(|op| {
+                        StateQueryResult::Ok(IntegratedDhtOp {
+                            hash: row.get("hash")?,
+                            op,
+                            validation_status: row.get("validation_status")?,
+                            when_integrated: row.get("when_integrated")?,
+                        })
+                    })
+                    .transpose()?;
+                StateQueryResult::Ok((rowid, op))
+            },
+        )?
+        .collect::<StateQueryResult<Vec<_>>>()?;
+
+    // A full page means there may be more ops after the last row.
+    let cursor = if rows.len() as u32 == limit {
+        rows.last().map(|(rowid, _)| *rowid)
+    } else {
+        None
+    };
+    Ok(IntegratedDhtOpsPage {
+        ops: rows.into_iter().filter_map(|(_, op)| op).collect(),
+        cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations;
+    use ::fixt::prelude::*;
+    use holo_hash::HasHash;
+    use holochain_sqlite::prelude::*;
+    use holochain_types::dht_op::DhtOpHashed;
+    use holochain_zome_types::fixt::*;
+
+    #[test]
+    fn test_query_integrated() {
+        let test_db = crate::test_utils::test_dht_db();
+        let env = test_db.to_db();
+
+        let activity = |n| {
+            let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+                fixt!(Signature),
+                fixt!(Create).into(),
+            ));
+            (op, Timestamp::from_micros(n))
+        };
+        let ops: Vec<_> = (1..=5).map(activity).collect();
+        let (pending, _) = activity(6);
+        let delete = DhtOpHashed::from_content_sync(DhtOp::RegisterRemoveLink(
+            fixt!(Signature),
+            fixt!(DeleteLink),
+        ));
+
+        env.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                for (op, when) in &ops {
+                    mutations::insert_op(txn, op)?;
+                    mutations::set_validation_status(txn, op.as_hash(), ValidationStatus::Valid)?;
+                    mutations::set_when_integrated(txn, op.as_hash(), *when)?;
+                }
+                mutations::insert_op(txn, &delete)?;
+                mutations::set_when_integrated(txn, delete.as_hash(), Timestamp::from_micros(3))?;
+                mutations::insert_op(txn, &pending)
+            })
+            .unwrap();
+
+        let mut g = env.conn().unwrap();
+        g.with_reader_test(|txn| {
+            let hashes = |page: &IntegratedDhtOpsPage| -> Vec<DhtOpHash> {
+                page.ops.iter().map(|op| op.hash.clone()).collect()
+            };
+
+            // - Ops waiting to be integrated are not listed
+            let all = query_integrated(&txn, &Default::default()).unwrap();
+            assert_eq!(all.ops.len(), 6);
+            assert_eq!(all.cursor, None);
+            assert!(!hashes(&all).contains(pending.as_hash()));
+            assert_eq!(all.ops[0].validation_status, Some(ValidationStatus::Valid));
+            assert_eq!(all.ops[0].when_integrated, Timestamp::from_micros(1));
+
+            let by_type = query_integrated(
+                &txn,
+                &IntegratedDhtOpsQuery {
+                    op_type: Some(DhtOpType::RegisterRemoveLink),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(hashes(&by_type), vec![delete.as_hash().clone()]);
+
+            let by_basis = query_integrated(
+                &txn,
+                &IntegratedDhtOpsQuery {
+                    basis_hash: Some(ops[1].0.dht_basis()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(hashes(&by_basis), vec![ops[1].0.as_hash().clone()]);
+
+            let by_time = query_integrated(
+                &txn,
+                &IntegratedDhtOpsQuery {
+                    op_type: Some(DhtOpType::RegisterAgentActivity),
+                    integrated_after: Some(Timestamp::from_micros(2)),
+                    integrated_before: Some(Timestamp::from_micros(4)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                hashes(&by_time),
+                vec![ops[1].0.as_hash().clone(), ops[2].0.as_hash().clone()]
+            );
+
+            // - Paging through the ops lists each op once
+            let mut query = IntegratedDhtOpsQuery {
+                limit: Some(4),
+                ..Default::default()
+            };
+            let first = query_integrated(&txn, &query).unwrap();
+            assert_eq!(first.ops.len(), 4);
+            assert!(first.cursor.is_some());
+            query.cursor = first.cursor;
+            let second = query_integrated(&txn, &query).unwrap();
+            assert_eq!(second.ops.len(), 2);
+            assert_eq!(second.cursor, None);
+            assert_eq!([hashes(&first), hashes(&second)].concat(), hashes(&all));
+        });
+    }
+}
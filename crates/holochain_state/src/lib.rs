@@ -41,6 +41,7 @@ pub mod dna_def;
 pub mod entry_def;
 pub mod host_fn_workspace;
 pub mod integrate;
+pub mod integrated_ops;
 pub mod mutations;
 #[allow(missing_docs)]
 pub mod prelude;
@@ -50,7 +51,9 @@ pub mod scratch;
 #[allow(missing_docs)]
 pub mod source_chain;
 pub mod validation_db;
+pub mod validation_package;
 pub mod validation_receipts;
+pub mod warrants;
 #[allow(missing_docs)]
 pub mod wasm;
 pub mod workspace;
@@ -16,6 +16,7 @@ use holochain_types::dht_op::{DhtOpHashed, DhtOpType};
 use holochain_types::prelude::DhtOpError;
 use holochain_types::prelude::DnaDefHashed;
 use holochain_types::prelude::DnaWasmHashed;
+use holochain_types::prelude::ValidationRejection;
 use holochain_types::sql::AsSql;
 use holochain_zome_types::entry::EntryHashed;
 use holochain_zome_types::*;
@@ -209,6 +210,71 @@ pub fn insert_validation_receipt(
     Ok(())
 }
 
+/// Insert the reason a [`DhtOp`](holochain_types::dht_op::DhtOp) was rejected
+/// into the database.
+pub fn insert_validation_rejection(
+    txn: &mut Transaction,
+    op_hash: &DhtOpHash,
+    rejection: ValidationRejection,
+) -> StateMutationResult<()> {
+    let bytes: UnsafeBytes = SerializedBytes::try_from(rejection)?.into();
+    let bytes: Vec<u8> = bytes.into();
+    sql_insert!(txn, ValidationRejection, {
+        "op_hash": op_hash,
+        "blob": bytes,
+    })?;
+    Ok(())
+}
+
+/// Insert a [`SignedWarrant`] into the database.
+/// A warrant already held from the same warrantor for the same action
+/// and basis is ignored.
+pub fn insert_warrant(txn: &mut Transaction, warrant: &SignedWarrant) -> StateMutationResult<()> {
+    let bytes: UnsafeBytes = SerializedBytes::try_from(warrant)?.into();
+    let bytes: Vec<u8> = bytes.into();
+    sql_insert!(txn, Warrant, {
+        "action_hash": warrant.warrant.action_hash,
+        "warrantor": warrant.warrant.warrantor,
+        "author": warrant.warrant.author,
+        "basis_hash": warrant.warrant.basis,
+        "blob": bytes,
+    })?;
+    Ok(())
+}
+
+/// Insert a [`SignedWarrant`] from the network whose op this node
+/// has not yet validated.
+pub fn insert_pending_warrant(
+    txn: &mut Transaction,
+    warrant: &SignedWarrant,
+) -> StateMutationResult<()> {
+    let bytes: UnsafeBytes = SerializedBytes::try_from(warrant)?.into();
+    let bytes: Vec<u8> = bytes.into();
+    sql_insert!(txn, PendingWarrant, {
+        "action_hash": warrant.warrant.action_hash,
+        "warrantor": warrant.warrant.warrantor,
+        "basis_hash": warrant.warrant.basis,
+        "blob": bytes,
+    })?;
+    Ok(())
+}
+
+/// Insert the [`ValidationPackage`] for an action into the database,
+/// replacing any package already held for it.
+pub fn insert_validation_package(
+    txn: &mut Transaction,
+    action_hash: &ActionHash,
+    package: &ValidationPackage,
+) -> StateMutationResult<()> {
+    let bytes: UnsafeBytes = SerializedBytes::try_from(package)?.into();
+    let bytes: Vec<u8> = bytes.into();
+    sql_insert!(txn, ValidationPackage, {
+        "action_hash": action_hash,
+        "blob": bytes,
+    })?;
+    Ok(())
+}
+
 /// Insert a [`DnaWasm`](holochain_types::prelude::DnaWasm) into the database.
 pub fn insert_wasm(txn: &mut Transaction, wasm: DnaWasmHashed) -> StateMutationResult<()> {
     let (wasm, hash) = wasm.into_inner();
@@ -308,12 +374,12 @@ pub fn set_validation_stage(
     hash: &DhtOpHash,
     status: ValidationLimboStatus,
 ) -> StateMutationResult<()> {
-    let stage = match status {
-        ValidationLimboStatus::Pending => None,
-        ValidationLimboStatus::AwaitingSysDeps(_) => Some(0),
-        ValidationLimboStatus::SysValidated => Some(1),
-        ValidationLimboStatus::AwaitingAppDeps(_) => Some(2),
-        ValidationLimboStatus::AwaitingIntegration => Some(3),
+    let (stage, sys_validation_dependency) = match status {
+        ValidationLimboStatus::Pending => (None, None),
+        ValidationLimboStatus::AwaitingSysDeps(dep) => (Some(0), Some(dep)),
+        ValidationLimboStatus::SysValidated => (Some(1), None),
+        ValidationLimboStatus::AwaitingAppDeps(_) => (Some(2), None),
+        ValidationLimboStatus::AwaitingIntegration => (Some(3), None),
     };
     let now = holochain_zome_types::Timestamp::now();
     txn.execute(
@@ -322,19 +388,28 @@ pub fn set_validation_stage(
         SET
         num_validation_attempts = IFNULL(num_validation_attempts, 0) + 1,
         last_validation_attempt = :last_validation_attempt,
-        validation_stage = :validation_stage
+        validation_stage = :validation_stage,
+        sys_validation_dependency = :sys_validation_dependency
         WHERE
         DhtOp.hash = :hash
         ",
         named_params! {
             ":last_validation_attempt": now,
             ":validation_stage": stage,
+            ":sys_validation_dependency": sys_validation_dependency,
             ":hash": hash,
         },
     )?;
     Ok(())
 }
 
+/// Drop a [`DhtOp`](holochain_types::dht_op::DhtOp) from the database.
+/// Used for ops which should never have been accepted, like counterfeits.
+pub fn delete_dht_op(txn: &mut Transaction, hash: &DhtOpHash) -> StateMutationResult<()> {
+    txn.execute("DELETE FROM DhtOp WHERE hash = ?", [hash])?;
+    Ok(())
+}
+
 /// Set when a [`DhtOp`](holochain_types::dht_op::DhtOp) was integrated.
 pub fn set_when_integrated(
     txn: &mut Transaction,
@@ -39,6 +39,27 @@ pub struct Scratch {
     chain_top_ordering: ChainTopOrdering,
     scheduled_fns: Vec<ScheduledFn>,
     chain_head: Option<(u32, usize)>,
+    /// Entries in the order they were first added, so they can be removed
+    /// again when rolling back to a savepoint.
+    new_entries: Vec<EntryHash>,
+    /// How many times the scratch has been drained, so that stale
+    /// savepoints can be detected.
+    drains: usize,
+}
+
+/// A point in a [`Scratch`] that it can be rolled back to.
+///
+/// Savepoints nest: rolling back to a savepoint also discards everything
+/// staged after any later savepoint. A savepoint can't be used once the
+/// scratch has been drained.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScratchSavepoint {
+    num_actions: usize,
+    num_new_entries: usize,
+    num_scheduled_fns: usize,
+    chain_top_ordering: ChainTopOrdering,
+    chain_head: Option<(u32, usize)>,
+    drains: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -105,7 +126,37 @@ impl Scratch {
     pub fn add_entry(&mut self, entry_hashed: EntryHashed, chain_top_ordering: ChainTopOrdering) {
         self.respect_chain_top_ordering(chain_top_ordering);
         let (entry, hash) = entry_hashed.into_inner();
-        self.entries.insert(hash, Arc::new(entry));
+        if self.entries.insert(hash.clone(), Arc::new(entry)).is_none() {
+            self.new_entries.push(hash);
+        }
+    }
+
+    /// Mark the current state of the scratch so that anything staged
+    /// afterwards can be discarded with [`Scratch::rollback_to`].
+    pub fn savepoint(&self) -> ScratchSavepoint {
+        ScratchSavepoint {
+            num_actions: self.actions.len(),
+            num_new_entries: self.new_entries.len(),
+            num_scheduled_fns: self.scheduled_fns.len(),
+            chain_top_ordering: self.chain_top_ordering,
+            chain_head: self.chain_head,
+            drains: self.drains,
+        }
+    }
+
+    /// Discard everything staged since the savepoint was taken.
+    pub fn rollback_to(&mut self, savepoint: &ScratchSavepoint) -> Result<(), ScratchError> {
+        if savepoint.drains != self.drains {
+            return Err(ScratchError::StaleSavepoint);
+        }
+        self.actions.truncate(savepoint.num_actions);
+        for hash in self.new_entries.drain(savepoint.num_new_entries..) {
+            self.entries.remove(&hash);
+        }
+        self.scheduled_fns.truncate(savepoint.num_scheduled_fns);
+        self.chain_top_ordering = savepoint.chain_top_ordering;
+        self.chain_head = savepoint.chain_head;
+        Ok(())
     }
 
     pub fn as_filter(&self, f: impl Fn(&SignedActionHashed) -> bool) -> FilteredScratch {
@@ -181,17 +232,21 @@ impl Scratch {
     }
 
     pub fn drain_scheduled_fns(&mut self) -> impl Iterator<Item = ScheduledFn> + '_ {
+        self.drains += 1;
         self.scheduled_fns.drain(..)
     }
 
     /// Drain out all the actions.
     pub fn drain_actions(&mut self) -> impl Iterator<Item = SignedActionHashed> + '_ {
+        self.drains += 1;
         self.chain_head = None;
         self.actions.drain(..)
     }
 
     /// Drain out all the entries.
     pub fn drain_entries(&mut self) -> impl Iterator<Item = EntryHashed> + '_ {
+        self.drains += 1;
+        self.new_entries.clear();
         self.entries.drain().map(|(hash, entry)| {
             EntryHashed::with_pre_hashed(
                 Arc::try_unwrap(entry).unwrap_or_else(|e| (*e).clone()),
@@ -313,6 +368,10 @@ pub enum ScratchError {
     #[error(transparent)]
     Action(#[from] ActionError),
 
+    /// The scratch was drained after the savepoint was taken
+    #[error("Can't roll back to a savepoint taken before the scratch was drained")]
+    StaleSavepoint,
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -383,3 +442,57 @@ CREATE TABLE mytable (
     assert_eq!(xs1, vec![1]);
     assert!(xs2.is_empty());
 }
+
+#[test]
+fn test_nested_savepoints() {
+    use ::fixt::prelude::*;
+
+    let mut scratch = Scratch::new();
+    let mk_action = |seq| {
+        let mut create = fixt!(Create);
+        create.action_seq = seq;
+        SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(create.into()),
+            fixt!(Signature),
+        )
+    };
+    let entry = || EntryHashed::from_content_sync(fixt!(Entry));
+    let num_entries = |scratch: &Scratch| scratch.entries().count();
+
+    let existing = entry();
+    scratch.add_action(mk_action(1), ChainTopOrdering::Relaxed);
+    scratch.add_entry(existing.clone(), ChainTopOrdering::Relaxed);
+    let outer = scratch.savepoint();
+
+    scratch.add_action(mk_action(2), ChainTopOrdering::Relaxed);
+    scratch.add_entry(entry(), ChainTopOrdering::Relaxed);
+    let inner = scratch.savepoint();
+
+    scratch.add_action(mk_action(3), ChainTopOrdering::Strict);
+    scratch.add_entry(entry(), ChainTopOrdering::Relaxed);
+    // Re-adding an entry that existed before the savepoint must not remove it on rollback.
+    scratch.add_entry(existing, ChainTopOrdering::Relaxed);
+    scratch.add_scheduled_fn(ScheduledFn::new("zome".into(), "fn".into()));
+    assert_eq!((scratch.len(), num_entries(&scratch)), (3, 3));
+    assert_eq!(scratch.chain_head().unwrap().1, 3);
+
+    // - Rolling back the inner savepoint keeps what was staged before it
+    scratch.rollback_to(&inner).unwrap();
+    assert_eq!((scratch.len(), num_entries(&scratch)), (2, 2));
+    assert_eq!(scratch.chain_head().unwrap().1, 2);
+    assert_eq!(scratch.chain_top_ordering(), ChainTopOrdering::Relaxed);
+    assert!(scratch.scheduled_fns().is_empty());
+
+    // - Rolling back the outer savepoint also discards the inner changes
+    scratch.add_action(mk_action(3), ChainTopOrdering::Relaxed);
+    scratch.rollback_to(&outer).unwrap();
+    assert_eq!((scratch.len(), num_entries(&scratch)), (1, 1));
+    assert_eq!(scratch.chain_head().unwrap().1, 1);
+
+    // - Savepoints can't be used after draining
+    scratch.drain_actions().for_each(drop);
+    assert!(matches!(
+        scratch.rollback_to(&outer),
+        Err(ScratchError::StaleSavepoint)
+    ));
+}
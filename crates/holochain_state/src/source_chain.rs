@@ -7,9 +7,11 @@ use crate::scratch::SyncScratchError;
 use async_recursion::async_recursion;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
 use holo_hash::DhtOpHash;
 use holo_hash::DnaHash;
 use holo_hash::HasHash;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_sqlite::rusqlite::Transaction;
@@ -57,6 +59,7 @@ use crate::chain_lock::is_lock_expired;
 use crate::prelude::*;
 use crate::query::chain_head::ChainHeadQuery;
 use crate::scratch::Scratch;
+use crate::scratch::ScratchSavepoint;
 use crate::scratch::SyncScratch;
 use holo_hash::EntryHash;
 use holochain_serialized_bytes::prelude::*;
@@ -102,6 +105,24 @@ pub struct SourceChainJsonRecord {
 //       not the entire source chain!
 /// Writable functions for a source chain with write access.
 impl SourceChain {
+    /// Lock the chain until `expires_at` or until it is unlocked, so only
+    /// writes flushed with [`SourceChain::flush_withheld`] under the same
+    /// lock succeed. Fails if the chain is already locked by another lock.
+    pub async fn lock_chain(&self, lock: Vec<u8>, expires_at: Timestamp) -> SourceChainResult<()> {
+        let author = self.author.clone();
+        self.vault
+            .async_commit(move |txn| {
+                if is_chain_locked(txn, &lock, author.as_ref())? {
+                    return Err(SourceChainError::ChainLocked);
+                }
+                // Clear expired locks, which might include an earlier one with this id.
+                unlock_chain(txn, &author)?;
+                lock_chain(txn, &lock, author.as_ref(), &expires_at)?;
+                SourceChainResult::Ok(())
+            })
+            .await
+    }
+
     pub async fn unlock_chain(&self) -> SourceChainResult<()> {
         let author = self.author.clone();
         self.vault
@@ -254,10 +275,33 @@ impl SourceChain {
         .await
     }
 
-    #[async_recursion]
     pub async fn flush(
         &self,
         network: &(dyn HolochainP2pDnaT + Send + Sync),
+    ) -> SourceChainResult<Vec<SignedActionHashed>> {
+        self.flush_inner(network, false, None).await
+    }
+
+    /// Write the scratch like [`SourceChain::flush`], but hold its ops back
+    /// from publishing and from the DHT database. Until they are released with
+    /// [`release_withheld_action`], nobody else can have seen the new actions,
+    /// so they can still be dropped with [`repair_chain`].
+    /// The chain must be locked with `lock` by [`SourceChain::lock_chain`],
+    /// so nothing else can be written on top of them in the meantime.
+    pub async fn flush_withheld(
+        &self,
+        network: &(dyn HolochainP2pDnaT + Send + Sync),
+        lock: Vec<u8>,
+    ) -> SourceChainResult<Vec<SignedActionHashed>> {
+        self.flush_inner(network, true, Some(lock)).await
+    }
+
+    #[async_recursion]
+    async fn flush_inner(
+        &self,
+        network: &(dyn HolochainP2pDnaT + Send + Sync),
+        withhold: bool,
+        held_lock: Option<Vec<u8>>,
     ) -> SourceChainResult<Vec<SignedActionHashed>> {
         // Nothing to write
         if self.scratch.apply(|s| s.is_empty())? {
@@ -282,10 +326,12 @@ impl SourceChain {
         {
             return Err(SourceChainError::DirtyCounterSigningWrite);
         }
-        let lock = lock_for_entry(maybe_countersigned_entry)?;
-
         // If the lock isn't empty this is a countersigning session.
-        let is_countersigning_session = !lock.is_empty();
+        let is_countersigning_session = !lock_for_entry(maybe_countersigned_entry)?.is_empty();
+        let lock = match held_lock.clone() {
+            Some(lock) => lock,
+            None => lock_for_entry(maybe_countersigned_entry)?,
+        };
 
         let ops_to_integrate = ops
             .iter()
@@ -341,7 +387,7 @@ impl SourceChain {
                     insert_op_lite_into_authored(txn, op, op_hash, op_order, timestamp)?;
                     // If this is a countersigning session we want to withhold
                     // publishing the ops until the session is successful.
-                    if is_countersigning_session {
+                    if is_countersigning_session || withhold {
                         set_withhold_publish(txn, op_hash)?;
                     }
                 }
@@ -388,7 +434,7 @@ impl SourceChain {
                             scratch.add_entry(entry, ChainTopOrdering::Relaxed);
                         }
                     })?;
-                    child_chain.flush(network).await
+                    child_chain.flush_inner(network, withhold, held_lock).await
                 } else {
                     Err(SourceChainError::HeadMoved(
                         actions,
@@ -399,14 +445,16 @@ impl SourceChain {
                 }
             }
             Ok(actions) => {
-                authored_ops_to_dht_db(
-                    network,
-                    ops_to_integrate,
-                    &self.vault,
-                    &self.dht_db,
-                    &self.dht_db_cache,
-                )
-                .await?;
+                if !withhold {
+                    authored_ops_to_dht_db(
+                        network,
+                        ops_to_integrate,
+                        &self.vault,
+                        &self.dht_db,
+                        &self.dht_db_cache,
+                    )
+                    .await?;
+                }
                 SourceChainResult::Ok(actions)
             }
             result => result,
@@ -534,6 +582,19 @@ where
         Ok(self.scratch.apply(|scratch| scratch.records().collect())?)
     }
 
+    /// Mark the writes staged so far, so that anything written afterwards
+    /// can be discarded with [`SourceChain::revert_to`].
+    pub fn checkpoint(&self) -> SourceChainResult<ScratchSavepoint> {
+        Ok(self.scratch.apply(|scratch| scratch.savepoint())?)
+    }
+
+    /// Discard every action, entry and scheduled function staged since the
+    /// checkpoint was taken. Fails if the chain was flushed in between.
+    pub fn revert_to(&self, checkpoint: &ScratchSavepoint) -> SourceChainResult<()> {
+        self.scratch
+            .apply_and_then(|scratch| Ok(scratch.rollback_to(checkpoint)?))
+    }
+
     pub fn has_initialized(&self) -> SourceChainResult<bool> {
         Ok(self.len()? > 3)
     }
@@ -692,16 +753,13 @@ where
     /// Query Actions in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
-    // FIXME: This query needs to be tested.
     pub async fn query(&self, query: QueryFilter) -> SourceChainResult<Vec<Record>> {
-        if query.sequence_range != ChainQueryFilterRange::Unbounded
-            && (query.action_type.is_some()
-                || query.entry_type.is_some()
-                || query.entry_hashes.is_some()
-                || query.include_entries)
-        {
-            return Err(SourceChainError::UnsupportedQuery(query));
-        }
+        // Hash bounded ranges walk the chain back through every action in
+        // the range, so the type filters can only be applied after the walk.
+        let filter_in_sql = matches!(
+            query.sequence_range,
+            ChainQueryFilterRange::Unbounded | ChainQueryFilterRange::ActionSeqRange(_, _)
+        );
         let author = self.author.clone();
         let public_only = self.public_only;
         let mut records = self
@@ -772,13 +830,15 @@ where
                 ORDER BY Action.seq ASC
                 ",
                     );
+                    let entry_type = query.entry_type.clone().filter(|_| filter_in_sql);
+                    let action_type = query.action_type.clone().filter(|_| filter_in_sql);
                     let mut stmt = txn.prepare(&sql)?;
                     let records = stmt
                         .query_and_then(
                         named_params! {
                                 ":author": author.as_ref(),
-                                ":entry_type": query.entry_type.as_sql(),
-                                ":action_type": query.action_type.as_sql(),
+                                ":entry_type": entry_type.as_sql(),
+                                ":action_type": action_type.as_sql(),
                                 ":range_start": match query.sequence_range {
                                     ChainQueryFilterRange::ActionSeqRange(start, _) => Some(start),
                                     _ => None,
@@ -949,16 +1009,19 @@ async fn rebase_actions_on(
     mut rebase_timestamp: Timestamp,
 ) -> Result<Vec<SignedActionHashed>, ScratchError> {
     actions.sort_by_key(|shh| shh.action().action_seq());
-    for shh in actions.iter_mut() {
-        let mut action = shh.action().clone();
+    let mut rebased = Vec::with_capacity(actions.len());
+    for shh in actions {
+        let mut action = shh.into_inner().0.into_content();
         action.rebase_on(rebase_action.clone(), rebase_seq, rebase_timestamp)?;
         rebase_seq = action.action_seq();
         rebase_timestamp = action.timestamp();
         let hh = ActionHashed::from_content_sync(action);
         rebase_action = hh.as_hash().clone();
-        let new_shh = SignedActionHashed::sign(keystore, hh).await?;
-        *shh = new_shh;
+        rebased.push(hh);
     }
+    // The new hashes don't depend on the signatures so
+    // all the actions can be signed at once.
+    let actions = SignedActionHashed::sign_batch(keystore, rebased).await?;
     Ok(actions)
 }
 
@@ -1236,6 +1299,200 @@ pub async fn dump_state(
         .await?)
 }
 
+/// Walk the source chain of this author from genesis, checking that every
+/// action is stored under its own hash, follows on from the action before it
+/// and is signed by the author.
+/// Returns the first break in the chain, if there is one.
+pub async fn check_chain_integrity(
+    vault: DbRead<DbKindAuthored>,
+    author: AgentPubKey,
+) -> SourceChainResult<Option<ChainCorruption>> {
+    let actions = vault
+        .async_reader({
+            let author = author.clone();
+            move |txn| {
+                txn.prepare(
+                    "
+                    SELECT hash, seq, blob FROM Action
+                    WHERE author = :author
+                    ORDER BY seq ASC
+                    ",
+                )?
+                .query_and_then(
+                    named_params! {
+                        ":author": author,
+                    },
+                    |row| {
+                        let hash: ActionHash = row.get("hash")?;
+                        let seq: u32 = row.get("seq")?;
+                        let blob: Vec<u8> = row.get("blob")?;
+                        StateQueryResult::Ok((hash, seq, blob))
+                    },
+                )?
+                .collect::<StateQueryResult<Vec<_>>>()
+            }
+        })
+        .await?;
+
+    let mut prev_action: Option<ActionHash> = None;
+    for (expected_seq, (action_hash, seq, blob)) in (0..).zip(actions) {
+        if seq < expected_seq {
+            return Ok(Some(ChainCorruption::Fork { seq, action_hash }));
+        }
+        if seq > expected_seq {
+            return Ok(Some(ChainCorruption::MissingAction { seq: expected_seq }));
+        }
+        let SignedAction(action, signature) = match from_blob(blob) {
+            Ok(action) => action,
+            Err(_) => return Ok(Some(ChainCorruption::Malformed { seq, action_hash })),
+        };
+        if ActionHash::with_data_sync(&action) != action_hash || action.action_seq() != seq {
+            return Ok(Some(ChainCorruption::HashMismatch { seq, action_hash }));
+        }
+        if action.prev_action() != prev_action.as_ref() {
+            return Ok(Some(ChainCorruption::BrokenLink { seq, action_hash }));
+        }
+        if !author.verify_signature(&signature, action).await {
+            return Ok(Some(ChainCorruption::InvalidSignature { seq, action_hash }));
+        }
+        prev_action = Some(action_hash);
+    }
+    Ok(None)
+}
+
+/// Publish the ops of the action at `seq` on this author's source chain,
+/// which was written with [`SourceChain::flush_withheld`], and hold them in
+/// the DHT database if an agent of this conductor is an authority for them.
+pub async fn release_withheld_action(
+    vault: DbWrite<DbKindAuthored>,
+    dht_db: DbWrite<DbKindDht>,
+    dht_db_cache: &DhtDbQueryCache,
+    network: &(dyn HolochainP2pDnaT + Send + Sync),
+    author: AgentPubKey,
+    seq: u32,
+) -> SourceChainResult<()> {
+    let ops = vault
+        .async_commit(move |txn| {
+            let ops = txn
+                .prepare(
+                    "
+                    SELECT DhtOp.hash, DhtOp.basis_hash FROM DhtOp
+                    JOIN Action ON DhtOp.action_hash = Action.hash
+                    WHERE Action.author = :author AND Action.seq = :seq
+                    ",
+                )?
+                .query_and_then(
+                    named_params! {
+                        ":author": author,
+                        ":seq": seq,
+                    },
+                    |row| StateMutationResult::Ok((row.get(0)?, row.get(1)?)),
+                )?
+                .collect::<StateMutationResult<Vec<(DhtOpHash, AnyDhtHash)>>>()?;
+            for (op_hash, _) in &ops {
+                unset_withhold_publish(txn, op_hash)?;
+            }
+            StateMutationResult::Ok(ops)
+        })
+        .await?;
+    authored_ops_to_dht_db(network, ops, &vault.into(), &dht_db, dht_db_cache).await?;
+    Ok(())
+}
+
+/// Drop the actions of this author's source chain from `seq` onwards,
+/// along with their ops, so the chain carries on from the action before.
+///
+/// Nothing is dropped if any of these actions may have been seen by others,
+/// which is when any of their ops have been published or they are already
+/// held in the DHT database. Returns whether the chain was repaired.
+pub async fn repair_chain(
+    vault: DbWrite<DbKindAuthored>,
+    dht_db: DbRead<DbKindDht>,
+    author: AgentPubKey,
+    seq: u32,
+) -> SourceChainResult<bool> {
+    let hashes: Vec<ActionHash> = vault
+        .async_reader({
+            let author = author.clone();
+            move |txn| {
+                txn.prepare(
+                    "
+                    SELECT hash FROM Action
+                    WHERE author = :author AND seq >= :seq
+                    ",
+                )?
+                .query_and_then(
+                    named_params! {
+                        ":author": author,
+                        ":seq": seq,
+                    },
+                    |row| StateQueryResult::Ok(row.get(0)?),
+                )?
+                .collect::<StateQueryResult<Vec<_>>>()
+            }
+        })
+        .await?;
+
+    let held_in_dht = dht_db
+        .async_reader({
+            let hashes = hashes.clone();
+            move |txn| {
+                let mut stmt = txn.prepare("SELECT EXISTS(SELECT 1 FROM Action WHERE hash = ?)")?;
+                for hash in &hashes {
+                    if stmt.query_row([hash], |row| row.get(0))? {
+                        return Ok(true);
+                    }
+                }
+                DatabaseResult::Ok(false)
+            }
+        })
+        .await?;
+    if held_in_dht {
+        return Ok(false);
+    }
+
+    Ok(vault
+        .async_commit(move |txn| {
+            let mut published = txn.prepare(
+                "
+                SELECT EXISTS(
+                    SELECT 1 FROM DhtOp
+                    WHERE action_hash = ? AND last_publish_time IS NOT NULL
+                )
+                ",
+            )?;
+            for hash in &hashes {
+                if published.query_row([hash], |row| row.get(0))? {
+                    return DatabaseResult::Ok(false);
+                }
+            }
+            let mut entry_hashes = Vec::new();
+            for hash in &hashes {
+                let entry_hash: Option<EntryHash> = txn.query_row(
+                    "SELECT entry_hash FROM Action WHERE hash = ?",
+                    [hash],
+                    |row| row.get(0),
+                )?;
+                entry_hashes.extend(entry_hash);
+                txn.execute("DELETE FROM DhtOp WHERE action_hash = ?", [hash])?;
+                txn.execute("DELETE FROM Action WHERE hash = ?", [hash])?;
+            }
+            // Drop the entries of the removed actions
+            // unless a remaining action still refers to them.
+            for entry_hash in &entry_hashes {
+                txn.execute(
+                    "
+                    DELETE FROM Entry WHERE hash = :hash
+                    AND NOT EXISTS(SELECT 1 FROM Action WHERE entry_hash = :hash)
+                    ",
+                    named_params! { ":hash": entry_hash },
+                )?;
+            }
+            Ok(true)
+        })
+        .await?)
+}
+
 impl From<SourceChain> for SourceChainRead {
     fn from(chain: SourceChain) -> Self {
         SourceChainRead {
@@ -1353,6 +1610,69 @@ pub mod tests {
 
         Ok(())
     }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn locked_chain_only_takes_writes_under_its_lock() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let new_chain = || {
+            SourceChain::new(
+                db.clone().into(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+        };
+        let lock = b"lock".to_vec();
+        let expires_at = (Timestamp::now() + std::time::Duration::from_secs(60)).unwrap();
+        new_chain()
+            .await?
+            .lock_chain(lock.clone(), expires_at)
+            .await?;
+        assert_matches!(
+            new_chain()
+                .await?
+                .lock_chain(b"other".to_vec(), expires_at)
+                .await,
+            Err(SourceChainError::ChainLocked)
+        );
+
+        let action_builder = builder::CloseChain {
+            new_dna_hash: fixt!(DnaHash),
+        };
+        let chain = new_chain().await?;
+        chain
+            .put(action_builder.clone(), None, ChainTopOrdering::Strict)
+            .await?;
+        assert_matches!(chain.flush(&mock).await, Err(SourceChainError::ChainLocked));
+
+        let chain = new_chain().await?;
+        chain
+            .put(action_builder, None, ChainTopOrdering::Strict)
+            .await?;
+        assert_eq!(chain.flush_withheld(&mock, lock).await?.len(), 1);
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_relaxed_ordering_with_entry() -> SourceChainResult<()> {
         let test_db = test_authored_db();
@@ -1902,6 +2222,193 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn writes_are_reverted_to_a_checkpoint() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        genesis(
+            vault.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fixt!(DnaHash),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChain::new(
+            vault.clone(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+        let action_builder = || builder::CloseChain {
+            new_dna_hash: fixt!(DnaHash),
+        };
+
+        let kept = chain
+            .put(action_builder(), None, ChainTopOrdering::Strict)
+            .await?;
+        let checkpoint = chain.checkpoint()?;
+        for _ in 0..2 {
+            chain
+                .put(action_builder(), None, ChainTopOrdering::Strict)
+                .await?;
+        }
+        assert_eq!(chain.scratch_records()?.len(), 3);
+
+        chain.revert_to(&checkpoint)?;
+        let records = chain.scratch_records()?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].action_address(), &kept);
+        assert_eq!(chain.chain_head()?.0, kept);
+
+        // The chain carries on from the checkpoint.
+        chain
+            .put(action_builder(), None, ChainTopOrdering::Strict)
+            .await?;
+        assert_eq!(chain.chain_head()?.1, 4);
+
+        // Checkpoints can't outlive a flush.
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        let checkpoint = chain.checkpoint()?;
+        chain.flush(&mock).await?;
+        assert_matches!(
+            chain.revert_to(&checkpoint),
+            Err(SourceChainError::ScratchError(ScratchError::StaleSavepoint))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chain_integrity_is_checked_and_repaired() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        genesis(
+            vault.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fixt!(DnaHash),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let mut hashes = Vec::new();
+        for _ in 0..2 {
+            let action_builder = builder::CloseChain {
+                new_dna_hash: fixt!(DnaHash),
+            };
+            hashes.push(
+                _put_db(
+                    vault.clone(),
+                    &keystore,
+                    Arc::new(alice.clone()),
+                    action_builder,
+                    None,
+                )
+                .await?,
+            );
+        }
+        let entry = Entry::App(fixt!(AppEntryBytes));
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        _put_db(
+            vault.clone(),
+            &keystore,
+            Arc::new(alice.clone()),
+            builder::Create {
+                entry_type: EntryType::App(fixt!(AppEntryType)),
+                entry_hash: entry_hash.clone(),
+            },
+            Some(entry),
+        )
+        .await?;
+        let check = || check_chain_integrity(vault.clone().into(), alice.clone());
+        assert_eq!(check().await?, None);
+
+        // Replace the signature of the action at seq 4.
+        vault.conn()?.with_commit_sync(|txn| {
+            let blob: Vec<u8> = txn.query_row(
+                "SELECT blob FROM Action WHERE hash = ?",
+                [&hashes[1]],
+                |row| row.get(0),
+            )?;
+            let SignedAction(action, _) = from_blob(blob)?;
+            txn.execute(
+                "UPDATE Action SET blob = ? WHERE hash = ?",
+                holochain_sqlite::rusqlite::params![
+                    to_blob(&SignedAction(action, fixt!(Signature)))?,
+                    &hashes[1]
+                ],
+            )?;
+            StateQueryResult::Ok(())
+        })?;
+        assert_eq!(
+            check().await?,
+            Some(ChainCorruption::InvalidSignature {
+                seq: 4,
+                action_hash: hashes[1].clone(),
+            })
+        );
+
+        // The rest of the chain was never published so it can be dropped,
+        // along with the entries only it referred to.
+        assert!(repair_chain(vault.clone(), dht_db.to_db().into(), alice.clone(), 4).await?);
+        assert_eq!(check().await?, None);
+        let (head, seq, _) =
+            fresh_reader_test!(vault, |txn| chain_head_db(&txn, Arc::new(alice.clone())))?;
+        assert_eq!((head, seq), (hashes[0].clone(), 3));
+        let entry_kept: bool = fresh_reader_test!(vault, |txn| txn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM Entry WHERE hash = ?)",
+                [&entry_hash],
+                |row| row.get(0)
+            )
+            .map_err(DatabaseError::from))?;
+        assert!(!entry_kept);
+
+        // Published actions are never dropped.
+        vault.conn()?.with_commit_sync(|txn| {
+            txn.execute(
+                "UPDATE DhtOp SET last_publish_time = 1 WHERE action_hash = ?",
+                [&hashes[0]],
+            )?;
+            StateQueryResult::Ok(())
+        })?;
+        assert!(!repair_chain(vault.clone(), dht_db.to_db().into(), alice.clone(), 3).await?);
+
+        // Neither are actions which are already held in the DHT database.
+        vault.conn()?.with_commit_sync(|txn| {
+            txn.execute("DELETE FROM Action WHERE seq = 2", [])?;
+            StateQueryResult::Ok(())
+        })?;
+        assert_eq!(
+            check().await?,
+            Some(ChainCorruption::MissingAction { seq: 2 })
+        );
+        assert!(!repair_chain(vault.clone(), dht_db.to_db().into(), alice.clone(), 2).await?);
+        assert_eq!(
+            check().await?,
+            Some(ChainCorruption::MissingAction { seq: 2 })
+        );
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn source_chain_query() {
         let test_db = test_authored_db();
@@ -1999,29 +2506,38 @@ pub mod tests {
                     action_type: action_type.clone(),
                     entry_type: entry_type.clone(),
                     entry_hashes: entry_hashes.clone(),
+                    time_range: None,
                     include_entries,
                 };
-                if sequence_range != ChainQueryFilterRange::Unbounded
-                    && (action_type.is_some()
-                        || entry_type.is_some()
-                        || entry_hashes.is_some()
-                        || include_entries)
-                {
-                    assert!(matches!(
-                        chain.query(query.clone()).await,
-                        Err(SourceChainError::UnsupportedQuery(_))
-                    ));
-                } else {
-                    let queried = chain.query(query.clone()).await.unwrap();
-                    let actual = queried.len();
-                    assert!(queried.iter().all(|e| e.action().author() == &alice));
-                    assert_eq!(
-                        num_expected, actual,
-                        "Expected {} items but got {} with filter {:?}",
-                        num_expected, actual, query
-                    );
-                }
+                let queried = chain.query(query.clone()).await.unwrap();
+                let actual = queried.len();
+                assert!(queried.iter().all(|e| e.action().author() == &alice));
+                assert!(queried
+                    .iter()
+                    .all(|e| include_entries || e.entry().as_option().is_none()));
+                assert_eq!(
+                    num_expected, actual,
+                    "Expected {} items but got {} with filter {:?}",
+                    num_expected, actual, query
+                );
             }
         }
+
+        // Time ranges include the start and exclude the end.
+        let timestamp = elements[2].action().timestamp();
+        let end = Timestamp::from_micros(timestamp.as_micros() + 1);
+        let queried = chain
+            .query(ChainQueryFilter::new().time_range(timestamp..end))
+            .await
+            .unwrap();
+        assert!(queried.iter().all(|e| e.action().timestamp() == timestamp));
+        assert!(queried
+            .iter()
+            .any(|e| e.action_address() == elements[2].action_address()));
+        let queried = chain
+            .query(ChainQueryFilter::new().time_range(timestamp..timestamp))
+            .await
+            .unwrap();
+        assert!(queried.is_empty());
     }
 }
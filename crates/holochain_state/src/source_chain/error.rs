@@ -110,11 +110,6 @@ pub enum SourceChainError {
     #[error("The source chain was missing for a host call that requires it.")]
     SourceChainMissing,
 
-    #[error("The supplied query parameters contains filters that are mutually incompatible.
-             In particular, `sequence_range` cannot currently be used with any other filter.
-             In the future, all filters will be compatible with each other and this will not be an error.")]
-    UnsupportedQuery(ChainQueryFilter),
-
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -155,4 +150,45 @@ pub enum ChainInvalidReason {
     MissingData(EntryHash),
 }
 
+/// A break in a source chain found by [`check_chain_integrity`](super::check_chain_integrity).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ChainCorruption {
+    #[error("No action was found at sequence number {seq}")]
+    MissingAction { seq: u32 },
+
+    #[error("The action {action_hash} at sequence number {seq} is a second action at that sequence number")]
+    Fork { seq: u32, action_hash: ActionHash },
+
+    #[error("The action {action_hash} at sequence number {seq} can't be deserialized")]
+    Malformed { seq: u32, action_hash: ActionHash },
+
+    #[error(
+        "The action stored as {action_hash} at sequence number {seq} does not hash to that address"
+    )]
+    HashMismatch { seq: u32, action_hash: ActionHash },
+
+    #[error(
+        "The action {action_hash} at sequence number {seq} does not point to the action before it"
+    )]
+    BrokenLink { seq: u32, action_hash: ActionHash },
+
+    #[error("The signature of the action {action_hash} at sequence number {seq} is invalid")]
+    InvalidSignature { seq: u32, action_hash: ActionHash },
+}
+
+impl ChainCorruption {
+    /// The sequence number the chain breaks at.
+    /// Every action before it is intact.
+    pub fn seq(&self) -> u32 {
+        match self {
+            ChainCorruption::MissingAction { seq }
+            | ChainCorruption::Fork { seq, .. }
+            | ChainCorruption::Malformed { seq, .. }
+            | ChainCorruption::HashMismatch { seq, .. }
+            | ChainCorruption::BrokenLink { seq, .. }
+            | ChainCorruption::InvalidSignature { seq, .. } => *seq,
+        }
+    }
+}
+
 pub type SourceChainResult<T> = Result<T, SourceChainError>;
@@ -0,0 +1,225 @@
+//! Storing the validation packages of actions.
+
+use holo_hash::ActionHash;
+use holo_hash::EntryHash;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::validate::RequiredValidationType;
+use holochain_zome_types::validate::ValidationPackage;
+use holochain_zome_types::Action;
+use holochain_zome_types::EntryVisibility;
+use holochain_zome_types::Record;
+use holochain_zome_types::RecordEntry;
+use std::collections::HashSet;
+
+use crate::prelude::from_blob;
+use crate::prelude::StateQueryResult;
+
+/// Get the validation package held for this action.
+pub fn get_validation_package(
+    txn: &Transaction,
+    action_hash: &ActionHash,
+) -> StateQueryResult<Option<ValidationPackage>> {
+    txn.query_row(
+        "SELECT blob FROM ValidationPackage WHERE action_hash = :action_hash",
+        named_params! {
+            ":action_hash": action_hash
+        },
+        |row| row.get(0),
+    )
+    .optional()?
+    .map(from_blob::<ValidationPackage>)
+    .transpose()
+}
+
+/// Check that a validation package only holds records from earlier in
+/// the chain of this action's author, that each record is signed by them
+/// and carries the entry its action references, and that the package
+/// holds every record the required validation type asks for.
+///
+/// A full chain package must hold the whole chain before the action.
+/// A sub chain package may only hold records of the action's entry type,
+/// but without the full chain it can't be checked that none are missing.
+pub async fn verify_validation_package(
+    package: &ValidationPackage,
+    action: &Action,
+    required_validation_type: RequiredValidationType,
+) -> bool {
+    for record in &package.0 {
+        if record.action().author() != action.author()
+            || record.action().action_seq() >= action.action_seq()
+            || !verify_record_entry(record)
+            || !action
+                .author()
+                .verify_signature(record.signature(), record.action().clone())
+                .await
+        {
+            return false;
+        }
+    }
+    match required_validation_type {
+        RequiredValidationType::Full => is_full_chain(package, action),
+        RequiredValidationType::SubChain => is_sub_chain(package, action),
+        RequiredValidationType::Record | RequiredValidationType::Custom => true,
+    }
+}
+
+/// Check that a record holds the entry its action references, unless
+/// the entry is private.
+fn verify_record_entry(record: &Record) -> bool {
+    match (record.action().entry_data(), record.entry()) {
+        (Some((entry_hash, _)), RecordEntry::Present(entry)) => {
+            EntryHash::with_data_sync(entry) == *entry_hash
+        }
+        (Some((_, entry_type)), RecordEntry::Hidden) => {
+            *entry_type.visibility() == EntryVisibility::Private
+        }
+        (None, RecordEntry::NotApplicable) => true,
+        _ => false,
+    }
+}
+
+/// Check that the package is the whole chain before the action,
+/// each record following the one before it.
+fn is_full_chain(package: &ValidationPackage, action: &Action) -> bool {
+    let mut records: Vec<_> = package.0.iter().collect();
+    records.sort_by_key(|record| record.action().action_seq());
+    let mut prev_action = None;
+    for (seq, record) in records.into_iter().enumerate() {
+        if record.action().action_seq() as usize != seq
+            || record.action().prev_action() != prev_action.as_ref()
+        {
+            return false;
+        }
+        prev_action = Some(ActionHash::with_data_sync(record.action()));
+    }
+    action.prev_action() == prev_action.as_ref()
+}
+
+/// Check that the package only holds distinct records of the
+/// action's entry type.
+fn is_sub_chain(package: &ValidationPackage, action: &Action) -> bool {
+    let mut seqs = HashSet::new();
+    package.0.iter().all(|record| {
+        record.action().entry_type() == action.entry_type()
+            && seqs.insert(record.action().action_seq())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::insert_validation_package;
+    use crate::prelude::*;
+    use fixt::prelude::*;
+    use holochain_sqlite::prelude::*;
+    use holochain_types::db_cache::DhtDbQueryCache;
+    use holochain_types::prelude::fake_dna_hash;
+    use holochain_zome_types::fixt::*;
+    use holochain_zome_types::ChainQueryFilter;
+    use holochain_zome_types::Entry;
+
+    /// The genesis records of a new chain, and an action that follows them.
+    async fn genesis_package() -> (ValidationPackage, Action) {
+        let db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        crate::source_chain::genesis(
+            db.to_db(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChainRead::new(
+            db.to_db().into(),
+            dht_db.to_db().into(),
+            dht_db_cache,
+            keystore,
+            alice.clone(),
+        )
+        .await
+        .unwrap();
+        let records = chain
+            .query(ChainQueryFilter::default().include_entries(true))
+            .await
+            .unwrap();
+
+        let mut create = fixt!(Create);
+        create.author = alice;
+        create.action_seq = records.len() as u32;
+        create.prev_action = records.last().unwrap().action_address().clone();
+        (ValidationPackage::new(records), Action::Create(create))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn full_chain_packages_must_be_complete() {
+        let (package, action) = genesis_package().await;
+        assert!(verify_validation_package(&package, &action, RequiredValidationType::Full).await);
+
+        let mut records = package.0.clone();
+        records.remove(1);
+        let incomplete = ValidationPackage::new(records);
+        assert!(
+            !verify_validation_package(&incomplete, &action, RequiredValidationType::Full).await
+        );
+        // Other packages don't have to hold the whole chain.
+        assert!(
+            verify_validation_package(&incomplete, &action, RequiredValidationType::Custom).await
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn package_entries_must_match_their_actions() {
+        let (package, action) = genesis_package().await;
+        let mut records = package.0.clone();
+        let (signed_action, _) = records.pop().unwrap().into_inner();
+        records.push(Record::new(
+            signed_action,
+            Some(Entry::Agent(fixt!(AgentPubKey, Predictable, 1))),
+        ));
+        let tampered = ValidationPackage::new(records);
+        assert!(
+            !verify_validation_package(&tampered, &action, RequiredValidationType::Custom).await
+        );
+    }
+
+    #[test]
+    fn validation_packages_are_stored_and_replaced() {
+        let test_db = crate::test_utils::test_cache_db();
+        let db = test_db.to_db();
+        let action_hash = fixt!(ActionHash);
+        let record = Record::new(fixt!(SignedActionHashed), None);
+        let empty = ValidationPackage::new(Vec::new());
+        let package = ValidationPackage::new(vec![record]);
+
+        db.conn().unwrap().with_reader_test(|txn| {
+            assert_eq!(get_validation_package(&txn, &action_hash).unwrap(), None);
+        });
+        db.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                insert_validation_package(txn, &action_hash, &empty)?;
+                insert_validation_package(txn, &action_hash, &package)
+            })
+            .unwrap();
+        db.conn().unwrap().with_reader_test(|txn| {
+            assert_eq!(
+                get_validation_package(&txn, &action_hash).unwrap(),
+                Some(package.clone())
+            );
+            assert_eq!(
+                get_validation_package(&txn, &fixt!(ActionHash)).unwrap(),
+                None
+            );
+        });
+    }
+}
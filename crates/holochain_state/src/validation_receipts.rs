@@ -81,6 +81,38 @@ impl ValidationReceipt {
             validators_signatures: signatures,
         }))
     }
+
+    /// Sign many validation receipts with a single batch of keystore requests.
+    /// Receipts without any validators are skipped.
+    pub async fn sign_batch(
+        receipts: Vec<Self>,
+        keystore: &MetaLairClient,
+    ) -> holochain_keystore::LairResult<Vec<SignedValidationReceipt>> {
+        let receipts: Vec<_> = receipts
+            .into_iter()
+            .filter(|receipt| !receipt.validators.is_empty())
+            .collect();
+        let mut batch = Vec::new();
+        for receipt in &receipts {
+            let data: std::sync::Arc<[u8]> = holochain_serialized_bytes::encode(receipt)
+                .map_err(one_err::OneErr::new)?
+                .into();
+            batch.extend(
+                receipt
+                    .validators
+                    .iter()
+                    .map(|validator| (validator.clone(), data.clone())),
+            );
+        }
+        let mut signatures = keystore.sign_batch(batch).await?.into_iter();
+        Ok(receipts
+            .into_iter()
+            .map(|receipt| SignedValidationReceipt {
+                validators_signatures: signatures.by_ref().take(receipt.validators.len()).collect(),
+                receipt,
+            })
+            .collect())
+    }
 }
 
 /// Try to collect a stream of futures that return results into a vec.
@@ -145,6 +177,47 @@ pub fn count_valid(txn: &Transaction, op_hash: &DhtOpHash) -> DatabaseResult<usi
     Ok(count)
 }
 
+/// How many validation receipts an authored op has collected.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ValidationReceiptCount {
+    /// The authored op.
+    pub dht_op_hash: DhtOpHash,
+    /// The number of receipts received for this op.
+    pub receipts: usize,
+    /// Whether enough receipts have been received to stop publishing this op.
+    pub receipts_complete: bool,
+}
+
+/// Count the validation receipts of every op authored by this agent.
+pub fn count_authored(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Vec<ValidationReceiptCount>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT DhtOp.hash, DhtOp.receipts_complete, COUNT(ValidationReceipt.hash)
+        FROM DhtOp
+        JOIN Action ON DhtOp.action_hash = Action.hash
+        LEFT JOIN ValidationReceipt ON ValidationReceipt.op_hash = DhtOp.hash
+        WHERE Action.author = :author
+        GROUP BY DhtOp.hash
+        ",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":author": author
+        },
+        |row| {
+            Ok(ValidationReceiptCount {
+                dht_op_hash: row.get(0)?,
+                receipts_complete: row.get::<_, Option<bool>>(1)?.unwrap_or(false),
+                receipts: row.get(2)?,
+            })
+        },
+    )?;
+    iter.collect()
+}
+
 pub fn add_if_unique(
     txn: &mut Transaction,
     receipt: SignedValidationReceipt,
@@ -231,6 +304,106 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_count_authored() {
+        let test_db = crate::test_utils::test_authored_db();
+        let env = test_db.to_db();
+        let keystore = crate::test_utils::test_keystore();
+
+        let author = fixt!(AgentPubKey);
+        let op = |author: AgentPubKey| {
+            let mut action = fixt!(Create);
+            action.author = author;
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+                fixt!(Signature),
+                action.into(),
+            ))
+        };
+        let with_receipts = op(author.clone());
+        let without_receipts = op(author.clone());
+        let someone_elses = op(fixt!(AgentPubKey));
+        let vr1 = fake_vr(with_receipts.as_hash(), &keystore).await;
+        let vr2 = fake_vr(with_receipts.as_hash(), &keystore).await;
+        let vr3 = fake_vr(someone_elses.as_hash(), &keystore).await;
+
+        env.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                for op in [&with_receipts, &without_receipts, &someone_elses] {
+                    mutations::insert_op(txn, op)?;
+                }
+                add_if_unique(txn, vr1)?;
+                add_if_unique(txn, vr2)?;
+                add_if_unique(txn, vr3)?;
+                mutations::set_receipts_complete(txn, with_receipts.as_hash(), true)
+            })
+            .unwrap();
+
+        let mut g = env.conn().unwrap();
+        let mut counts = g.with_reader_test(|reader| count_authored(&reader, &author).unwrap());
+        counts.sort_by_key(|c| c.receipts);
+        assert_eq!(
+            counts,
+            vec![
+                ValidationReceiptCount {
+                    dht_op_hash: without_receipts.as_hash().clone(),
+                    receipts: 0,
+                    receipts_complete: false,
+                },
+                ValidationReceiptCount {
+                    dht_op_hash: with_receipts.as_hash().clone(),
+                    receipts: 2,
+                    receipts_complete: true,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sign_batch() {
+        let keystore = crate::test_utils::test_keystore();
+        let agent_1 = keystore.new_sign_keypair_random().await.unwrap();
+        let agent_2 = keystore.new_sign_keypair_random().await.unwrap();
+        let receipt = |validators| ValidationReceipt {
+            dht_op_hash: fixt!(DhtOpHash),
+            validation_status: ValidationStatus::Valid,
+            validators,
+            when_integrated: Timestamp::now(),
+        };
+        let receipts = vec![
+            receipt(vec![agent_1.clone(), agent_2.clone()]),
+            receipt(vec![]),
+            receipt(vec![agent_2.clone()]),
+        ];
+
+        let signed = ValidationReceipt::sign_batch(receipts.clone(), &keystore)
+            .await
+            .unwrap();
+
+        // The receipt without validators is skipped.
+        assert_eq!(signed.len(), 2);
+        assert_eq!(signed[0].receipt, receipts[0]);
+        assert_eq!(signed[1].receipt, receipts[2]);
+        for signed in signed {
+            assert_eq!(
+                signed.validators_signatures.len(),
+                signed.receipt.validators.len()
+            );
+            for (validator, signature) in signed
+                .receipt
+                .validators
+                .iter()
+                .zip(&signed.validators_signatures)
+            {
+                assert!(
+                    validator
+                        .verify_signature(signature, signed.receipt.clone())
+                        .await
+                );
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_try_stream_of_results() {
         let iter: Vec<futures::future::Ready<Result<i32, String>>> = vec![];
@@ -0,0 +1,212 @@
+//! Storing and signing warrants against authors of invalid data.
+
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::LairResult;
+use holochain_keystore::MetaLairClient;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::warrant::SignedWarrant;
+use holochain_zome_types::warrant::Warrant;
+use holochain_zome_types::ValidationStatus;
+
+use crate::prelude::from_blob;
+use crate::prelude::StateMutationResult;
+use crate::prelude::StateQueryResult;
+
+/// The most warrants from the network held for any one warrantor,
+/// including those still waiting for their op to be validated.
+pub const MAX_WARRANTS_PER_WARRANTOR: usize = 1000;
+
+/// Sign a warrant with the key of its warrantor.
+pub async fn sign_warrant(
+    keystore: &MetaLairClient,
+    warrant: Warrant,
+) -> LairResult<SignedWarrant> {
+    let signature = warrant.warrantor.sign(keystore, &warrant).await?;
+    Ok(SignedWarrant::new(warrant, signature))
+}
+
+/// Check that a warrant was signed by its warrantor.
+pub async fn verify_warrant(warrant: &SignedWarrant) -> bool {
+    warrant
+        .warrant
+        .warrantor
+        .verify_signature(&warrant.signature, warrant.warrant.clone())
+        .await
+}
+
+/// Get every warrant held against data at this basis.
+pub fn get_warrants_for_basis(
+    txn: &Transaction,
+    basis: &AnyDhtHash,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    let mut stmt = txn.prepare_cached("SELECT blob FROM Warrant WHERE basis_hash = :basis_hash")?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":basis_hash": basis
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+/// Get every warrant held against this op, identified
+/// by its action and basis.
+pub fn get_warrants_for_op(
+    txn: &Transaction,
+    action_hash: &ActionHash,
+    basis: &AnyDhtHash,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    let mut stmt = txn.prepare_cached(
+        "SELECT blob FROM Warrant WHERE action_hash = :action_hash AND basis_hash = :basis_hash",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":action_hash": action_hash,
+            ":basis_hash": basis,
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+/// Get every warrant held against this author.
+pub fn get_warrants_for_author(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    let mut stmt = txn.prepare_cached("SELECT blob FROM Warrant WHERE author = :author")?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":author": author
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+/// Count the warrants held from this warrantor, pending or not.
+pub fn count_warrants_by_warrantor(
+    txn: &Transaction,
+    warrantor: &AgentPubKey,
+) -> StateQueryResult<usize> {
+    let count: usize = txn.query_row(
+        "
+        SELECT
+        (SELECT COUNT(*) FROM Warrant WHERE warrantor = :warrantor)
+        +
+        (SELECT COUNT(*) FROM PendingWarrant WHERE warrantor = :warrantor)
+        ",
+        named_params! {
+            ":warrantor": warrantor
+        },
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Settle the pending warrants whose op this node has integrated.
+/// A warrant is held if the op was rejected and dropped if it was valid,
+/// so only warrants this node agrees with are ever served.
+pub fn resolve_pending_warrants(txn: &Transaction) -> StateMutationResult<()> {
+    txn.execute(
+        "
+        INSERT INTO Warrant (action_hash, warrantor, author, basis_hash, blob)
+        SELECT
+        PendingWarrant.action_hash, PendingWarrant.warrantor, Action.author,
+        PendingWarrant.basis_hash, PendingWarrant.blob
+        FROM PendingWarrant
+        JOIN DhtOp ON DhtOp.action_hash = PendingWarrant.action_hash
+        AND DhtOp.basis_hash = PendingWarrant.basis_hash
+        JOIN Action ON Action.hash = DhtOp.action_hash
+        WHERE
+        DhtOp.when_integrated IS NOT NULL
+        AND
+        DhtOp.validation_status = :rejected
+        ",
+        named_params! {
+            ":rejected": ValidationStatus::Rejected,
+        },
+    )?;
+    txn.execute(
+        "
+        DELETE FROM PendingWarrant
+        WHERE EXISTS (
+            SELECT 1 FROM DhtOp
+            WHERE DhtOp.action_hash = PendingWarrant.action_hash
+            AND DhtOp.basis_hash = PendingWarrant.basis_hash
+            AND DhtOp.when_integrated IS NOT NULL
+        )
+        ",
+        [],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::insert_warrant;
+    use fixt::prelude::*;
+    use holochain_sqlite::prelude::*;
+    use holochain_zome_types::fixt::*;
+    use holochain_zome_types::Timestamp;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn warrants_are_signed_stored_and_queried() {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+        let keystore = crate::test_utils::test_keystore();
+        let warrantor = keystore.new_sign_keypair_random().await.unwrap();
+
+        let author = fixt!(AgentPubKey);
+        let action = fixt!(Action);
+        let basis: AnyDhtHash = fixt!(EntryHash).into();
+        let warrant = sign_warrant(
+            &keystore,
+            Warrant {
+                author: author.clone(),
+                action_hash: holo_hash::ActionHash::with_data_sync(&action),
+                basis: basis.clone(),
+                warrantor: warrantor.clone(),
+                timestamp: Timestamp::now(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(verify_warrant(&warrant).await);
+
+        let mut forged = warrant.clone();
+        forged.warrant.author = fixt!(AgentPubKey);
+        assert!(!verify_warrant(&forged).await);
+
+        db.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                insert_warrant(txn, &warrant)?;
+                // Storing the same warrant twice is a no-op.
+                insert_warrant(txn, &warrant)
+            })
+            .unwrap();
+
+        db.conn().unwrap().with_reader_test(|txn| {
+            assert_eq!(
+                get_warrants_for_basis(&txn, &basis).unwrap(),
+                vec![warrant.clone()]
+            );
+            assert_eq!(
+                get_warrants_for_author(&txn, &author).unwrap(),
+                vec![warrant.clone()]
+            );
+            assert!(get_warrants_for_author(&txn, &warrantor)
+                .unwrap()
+                .is_empty());
+            assert!(get_warrants_for_basis(&txn, &fixt!(EntryHash).into())
+                .unwrap()
+                .is_empty());
+        });
+    }
+}
@@ -56,32 +56,101 @@ pub struct RegisterDnaPayload {
     pub source: DnaSource,
 }
 
-/// The instructions on how to get the DNA to be registered
+/// The instructions on how to clone the DNA of an app role into a new Cell.
+///
+/// The DNA to clone and the agent of the new Cell are those of the role.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct CreateCloneCellPayload {
-    /// Properties to override when installing this Dna
-    pub properties: Option<YamlProperties>,
-    /// The DNA to clone
-    pub dna_hash: DnaHash,
-    /// The Agent key with which to create this Cell
-    /// (TODO: should this be derived from the App?)
-    pub agent_key: AgentPubKey,
     /// The App with which to associate the newly created Cell
     pub installed_app_id: InstalledAppId,
     /// The Role ID under which to create this clone
     /// (needed to track cloning permissions and `clone_count`)
     pub role_id: AppRoleId,
+    /// UID of the cloned DNA. A random one is used if not given.
+    #[serde(default)]
+    pub uid: Option<Uid>,
+    /// Properties of the cloned DNA
+    #[serde(default)]
+    pub properties: Option<YamlProperties>,
     /// Proof-of-membership, if required by this DNA
+    #[serde(default)]
+    pub membrane_proof: Option<MembraneProof>,
+}
+
+/// The instructions on how to disable a cloned Cell.
+///
+/// The Cell is removed from the app, but its data is kept.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DisableCloneCellPayload {
+    /// The App the clone belongs to
+    pub installed_app_id: InstalledAppId,
+    /// The Role ID under which the clone was created
+    pub role_id: AppRoleId,
+    /// The CellId of the clone
+    pub clone_cell_id: CellId,
+}
+
+/// The instructions for migrating the agent of an app role to a new version
+/// of the role's DNA.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MigrateCellPayload {
+    /// The App whose role is migrated
+    pub installed_app_id: InstalledAppId,
+    /// The Role ID to migrate
+    pub role_id: AppRoleId,
+    /// The hash of the new, already registered, DNA
+    pub new_dna_hash: DnaHash,
+    /// Proof-of-membership, if required by the new DNA
+    #[serde(default)]
     pub membrane_proof: Option<MembraneProof>,
+    /// Copy the capability grants and claims of the old source chain which
+    /// have not been deleted to the new source chain
+    #[serde(default)]
+    pub carry_forward: bool,
+}
+
+/// The instructions for granting zome call access to a signing key,
+/// e.g. one held by a UI which signs its own zome calls.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct GrantZomeCallCapabilityPayload {
+    /// The Cell on whose source chain the grant is written
+    pub cell_id: CellId,
+    /// The key which will be making the zome calls
+    pub signing_key: AgentPubKey,
+    /// A tag by which to later query for the grant
+    pub tag: String,
+    /// The zome functions the signing key may call
+    pub functions: GrantedFunctions,
+    /// How the grant is restricted
+    pub access: ZomeCallCapAccess,
 }
 
-impl CreateCloneCellPayload {
-    /// Get the CellId of the to-be-created clone cell
-    pub fn cell_id(&self) -> CellId {
-        CellId::new(self.dna_hash.clone(), self.agent_key.clone())
+impl GrantZomeCallCapabilityPayload {
+    /// Build the [`ZomeCallCapGrant`] for this payload.
+    ///
+    /// The secret is ignored for [`ZomeCallCapAccess::Unrestricted`] grants.
+    pub fn into_cap_grant(self, secret: CapSecret) -> ZomeCallCapGrant {
+        let access = match self.access {
+            ZomeCallCapAccess::Unrestricted => CapAccess::Unrestricted,
+            ZomeCallCapAccess::Transferable => CapAccess::Transferable { secret },
+            ZomeCallCapAccess::Assigned => (secret, self.signing_key).into(),
+        };
+        ZomeCallCapGrant::new(self.tag, access, self.functions)
     }
 }
 
+/// The kind of access granted by [`GrantZomeCallCapabilityPayload`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZomeCallCapAccess {
+    /// Only the signing key may call, and only with the returned secret.
+    Assigned,
+    /// Anyone holding the returned secret may call.
+    Transferable,
+    /// Anyone may call. No secret is needed.
+    Unrestricted,
+}
+
 /// A collection of [DnaHash]es paired with an [AgentPubKey] and an app id
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -117,6 +186,21 @@ pub struct InstallAppBundlePayload {
     /// The app can still use existing Cells, i.e. this does not require that
     /// all Cells have DNAs with the same overridden DNA.
     pub uid: Option<Uid>,
+
+    /// Optional: overrides the UID and/or properties of the DNA of
+    /// individual roles, keyed by the AppRoleId specified in the app
+    /// bundle manifest. These take precedence over `uid`.
+    #[serde(default)]
+    pub role_overrides: HashMap<AppRoleId, DnaPhenotypeOverrides>,
+}
+
+/// Overrides for the DNA of one role of an app being installed.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct DnaPhenotypeOverrides {
+    /// UID to override when installing this role's DNA
+    pub uid: Option<Uid>,
+    /// Properties to override when installing this role's DNA
+    pub properties: Option<YamlProperties>,
 }
 
 /// The possible locations of an AppBundle
@@ -457,19 +541,64 @@ impl InstalledAppCommon {
 
     /// Add a cloned cell
     pub fn add_clone(&mut self, role_id: &AppRoleId, cell_id: CellId) -> AppResult<()> {
+        self.check_can_add_clone(role_id)?;
         let role = self.role_mut(role_id)?;
         assert_eq!(
             cell_id.agent_pubkey(),
             role.agent_key(),
             "A clone cell must use the same agent key as the role it is added to"
         );
+        let _ = role.clones.insert(cell_id);
+        Ok(())
+    }
+
+    /// Check that another clone may be added to a role, without adding it
+    pub fn check_can_add_clone(&self, role_id: &AppRoleId) -> AppResult<()> {
+        let role = self.role(role_id)?;
         if role.clones.len() as u32 >= role.clone_limit {
             return Err(AppError::CloneLimitExceeded(role.clone_limit, role.clone()));
         }
-        let _ = role.clones.insert(cell_id);
         Ok(())
     }
 
+    /// Point a role at the cell of a new version of its DNA, after the agent
+    /// has been migrated to it. Returns the previous cell of the role.
+    pub fn migrate_role(&mut self, role_id: &AppRoleId, cell_id: CellId) -> AppResult<CellId> {
+        let role = self.role_mut(role_id)?;
+        assert_eq!(
+            cell_id.agent_pubkey(),
+            role.agent_key(),
+            "A role can only be migrated to a cell with the same agent key"
+        );
+        role.is_provisioned = true;
+        Ok(std::mem::replace(&mut role.base_cell_id, cell_id))
+    }
+
+    /// Move every cell of the app over to a new agent key, keeping their DNAs.
+    /// Returns the previous and new id of each cell.
+    pub fn rotate_agent_key(&mut self, new_key: AgentPubKey) -> Vec<(CellId, CellId)> {
+        let rotate = |cell_id: &CellId| CellId::new(cell_id.dna_hash().clone(), new_key.clone());
+        let mut rotated = Vec::new();
+        for role in self.role_assignments.values_mut() {
+            let base_cell_id = rotate(&role.base_cell_id);
+            rotated.push((
+                std::mem::replace(&mut role.base_cell_id, base_cell_id.clone()),
+                base_cell_id,
+            ));
+            role.clones = role
+                .clones
+                .drain()
+                .map(|clone_id| {
+                    let new_clone_id = rotate(&clone_id);
+                    rotated.push((clone_id, new_clone_id.clone()));
+                    new_clone_id
+                })
+                .collect();
+        }
+        self._agent_key = new_key;
+        rotated
+    }
+
     /// Remove a cloned cell
     pub fn remove_clone(&mut self, role_id: &AppRoleId, cell_id: &CellId) -> AppResult<bool> {
         let role = self.role_mut(role_id)?;
@@ -787,6 +916,16 @@ impl AppRoleAssignment {
         self.base_cell_id.agent_pubkey()
     }
 
+    /// Accessor
+    pub fn clone_limit(&self) -> u32 {
+        self.clone_limit
+    }
+
+    /// Accessor
+    pub fn clones(&self) -> &HashSet<CellId> {
+        &self.clones
+    }
+
     /// Accessor
     pub fn provisioned_cell(&self) -> Option<&CellId> {
         if self.is_provisioned {
@@ -828,9 +967,14 @@ mod tests {
         let clones: Vec<_> = vec![new_clone(), new_clone(), new_clone()];
         app.add_clone(&role_id, clones[0].clone()).unwrap();
         app.add_clone(&role_id, clones[1].clone()).unwrap();
+        app.check_can_add_clone(&role_id).unwrap();
         app.add_clone(&role_id, clones[2].clone()).unwrap();
 
         // Adding a clone beyond the clone_limit is an error
+        matches::assert_matches!(
+            app.check_can_add_clone(&role_id),
+            Err(AppError::CloneLimitExceeded(3, _))
+        );
         matches::assert_matches!(
             app.add_clone(&role_id, new_clone()),
             Err(AppError::CloneLimitExceeded(3, _))
@@ -860,4 +1004,59 @@ mod tests {
             app.all_cells().collect::<HashSet<_>>()
         );
     }
+
+    #[test]
+    fn role_migration() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let role_id: AppRoleId = "role_id".into();
+        let role = AppRoleAssignment::new(base_cell_id.clone(), false, 0);
+        let mut app: RunningApp =
+            InstalledAppCommon::new("app", agent.clone(), vec![(role_id.clone(), role)]).into();
+        assert_eq!(app.provisioned_cells().count(), 0);
+
+        let new_cell_id = CellId::new(fixt!(DnaHash), agent);
+        assert_eq!(
+            app.migrate_role(&role_id, new_cell_id.clone()).unwrap(),
+            base_cell_id
+        );
+        assert_eq!(
+            app.provisioned_cells().collect::<Vec<_>>(),
+            vec![(&role_id, &new_cell_id)]
+        );
+        matches::assert_matches!(
+            app.migrate_role(&"other".into(), new_cell_id),
+            Err(AppError::AppRoleIdMissing(_))
+        );
+    }
+
+    #[test]
+    fn agent_key_rotation() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let clone_id = CellId::new(fixt!(DnaHash), agent.clone());
+        let role_id: AppRoleId = "role_id".into();
+        let role = AppRoleAssignment::new(base_cell_id.clone(), true, 1);
+        let mut app: RunningApp =
+            InstalledAppCommon::new("app", agent, vec![(role_id.clone(), role)]).into();
+        app.add_clone(&role_id, clone_id.clone()).unwrap();
+
+        let new_key = fixt!(AgentPubKey);
+        let new_cell_id = CellId::new(base_cell_id.dna_hash().clone(), new_key.clone());
+        let new_clone_id = CellId::new(clone_id.dna_hash().clone(), new_key.clone());
+        assert_eq!(
+            app.rotate_agent_key(new_key.clone())
+                .into_iter()
+                .collect::<HashSet<_>>(),
+            maplit::hashset! {
+                (base_cell_id, new_cell_id.clone()),
+                (clone_id, new_clone_id.clone()),
+            }
+        );
+        assert_eq!(app._agent_key(), &new_key);
+        assert_eq!(
+            app.all_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &new_cell_id, &new_clone_id }
+        );
+    }
 }
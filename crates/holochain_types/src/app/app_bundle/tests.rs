@@ -69,3 +69,41 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that per-role overrides replace the phenotype given in the manifest
+#[tokio::test]
+async fn provisioning_1_create_with_role_overrides() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture().await;
+
+    let properties = YamlProperties::new(serde_yaml::Value::String("overridden".into()));
+    let mut manifest = bundle.manifest().to_owned();
+    manifest
+        .override_role(
+            &"role_id".into(),
+            Some("overridden uid".into()),
+            Some(properties.clone()),
+        )
+        .unwrap();
+    assert!(matches!(
+        manifest.override_role(&"no_such_role".into(), None, None),
+        Err(AppManifestError::UnknownRole(_))
+    ));
+    let bundle = AppBundle::from(bundle.into_inner().update_manifest(manifest).unwrap());
+
+    let dna = dna
+        .with_uid("overridden uid".to_string())
+        .await
+        .unwrap()
+        .with_properties(SerializedBytes::try_from(properties).unwrap())
+        .await
+        .unwrap();
+
+    let resolution = bundle
+        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(resolution.dnas_to_register, vec![(dna, None)]);
+}
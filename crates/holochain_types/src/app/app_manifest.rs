@@ -2,6 +2,7 @@
 
 //! Defines the hApp Manifest YAML format, including validation.
 
+use crate::prelude::{AppRoleId, YamlProperties};
 use holochain_zome_types::Uid;
 use mr_bundle::{Location, Manifest};
 use std::path::PathBuf;
@@ -70,6 +71,18 @@ impl AppManifest {
         }
     }
 
+    /// Override the UID and/or properties of the DNA for a single role.
+    pub fn override_role(
+        &mut self,
+        role_id: &AppRoleId,
+        uid: Option<Uid>,
+        properties: Option<YamlProperties>,
+    ) -> AppManifestResult<()> {
+        match self {
+            Self::V1(manifest) => manifest.override_role(role_id, uid, properties),
+        }
+    }
+
     /// Returns the list of app roles that this manifest declares
     pub fn app_roles(&self) -> Vec<AppRoleManifest> {
         match self {
@@ -204,6 +204,28 @@ impl AppManifestV1 {
         }
     }
 
+    /// Override the UID and/or properties of the DNA for a single role.
+    /// Unlike [`Self::set_uid`], this applies whatever the provisioning strategy.
+    pub fn override_role(
+        &mut self,
+        role_id: &AppRoleId,
+        uid: Option<Uid>,
+        properties: Option<YamlProperties>,
+    ) -> AppManifestResult<()> {
+        let role = self
+            .roles
+            .iter_mut()
+            .find(|role| &role.id == role_id)
+            .ok_or_else(|| AppManifestError::UnknownRole(role_id.clone()))?;
+        if uid.is_some() {
+            role.dna.uid = uid;
+        }
+        if properties.is_some() {
+            role.dna.properties = properties;
+        }
+        Ok(())
+    }
+
     /// Convert this human-focused manifest into a validated, concise representation
     pub fn validate(self) -> AppManifestResult<AppManifestValidated> {
         let AppManifestV1 {
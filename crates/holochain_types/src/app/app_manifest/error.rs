@@ -10,6 +10,9 @@ pub enum AppManifestError {
 
     #[error("Invalid manifest for app role '{0}': Using strategy 'disabled' with clone_limit == 0 is pointless")]
     InvalidStrategyDisabled(AppRoleId),
+
+    #[error("The app manifest has no role '{0}'")]
+    UnknownRole(AppRoleId),
 }
 
 pub type AppManifestResult<T> = Result<T, AppManifestError>;
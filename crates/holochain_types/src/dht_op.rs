@@ -1006,6 +1006,30 @@ pub struct RenderedOps {
     pub entry: Option<EntryHashed>,
     /// Op data to insert.
     pub ops: Vec<RenderedOp>,
+    /// Warrants against the authors of the ops.
+    pub warrants: Vec<SignedWarrant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+/// A warrant sent along with the op it claims is invalid,
+/// so the receiver can validate the op for itself.
+pub struct WarrantedOp {
+    /// The warrant against the author of the op.
+    pub warrant: SignedWarrant,
+    /// The op which the warrantor rejected.
+    pub op: DhtOp,
+}
+
+impl WarrantedOp {
+    /// Whether the op is the one the warrant is about: it has
+    /// the warranted action, author and basis.
+    pub fn op_matches_warrant(&self) -> bool {
+        let action = self.op.action();
+        let warrant = &self.warrant.warrant;
+        *action.author() == warrant.author
+            && ActionHash::with_data_sync(&action) == warrant.action_hash
+            && self.op.dht_basis() == warrant.basis
+    }
 }
 
 /// Type for deriving ordering of DhtOps
@@ -454,3 +454,33 @@ fn test_all_ops_basis() {
         check_all_ops(record);
     }
 }
+
+#[test]
+fn warranted_op_must_match_its_warrant() {
+    let op = DhtOp::RegisterAgentActivity(fixt!(Signature), fixt!(Action));
+    let action = op.action();
+    let warrant = Warrant {
+        author: action.author().clone(),
+        action_hash: ActionHash::with_data_sync(&action),
+        basis: op.dht_basis(),
+        warrantor: fixt!(AgentPubKey),
+        timestamp: Timestamp::now(),
+    };
+    let warranted = |warrant: Warrant| WarrantedOp {
+        warrant: SignedWarrant::new(warrant, fixt!(Signature)),
+        op: op.clone(),
+    };
+    assert!(warranted(warrant.clone()).op_matches_warrant());
+
+    let mut other_author = warrant.clone();
+    other_author.author = fixt!(AgentPubKey);
+    assert!(!warranted(other_author).op_matches_warrant());
+
+    let mut other_action = warrant.clone();
+    other_action.action_hash = fixt!(ActionHash);
+    assert!(!warranted(other_action).op_matches_warrant());
+
+    let mut other_basis = warrant;
+    other_basis.basis = fixt!(ActionHash).into();
+    assert!(!warranted(other_basis).op_matches_warrant());
+}
@@ -76,12 +76,13 @@ impl DnaBundle {
 
         let integrity_zomes = data[0]
             .iter()
-            .map(|(zome_name, hash, _, dependencies)| {
+            .map(|(zome_name, hash, _, dependencies, init_after)| {
                 (
                     zome_name.clone(),
                     ZomeDef::Wasm(WasmZome {
                         wasm_hash: hash.clone(),
                         dependencies: dependencies.clone(),
+                        init_after: init_after.clone(),
                     })
                     .into(),
                 )
@@ -89,12 +90,13 @@ impl DnaBundle {
             .collect();
         let coordinator_zomes = data[1]
             .iter()
-            .map(|(zome_name, hash, _, dependencies)| {
+            .map(|(zome_name, hash, _, dependencies, init_after)| {
                 (
                     zome_name.clone(),
                     ZomeDef::Wasm(WasmZome {
                         wasm_hash: hash.clone(),
                         dependencies: dependencies.clone(),
+                        init_after: init_after.clone(),
                     })
                     .into(),
                 )
@@ -103,7 +105,7 @@ impl DnaBundle {
         let code: BTreeMap<_, _> = data
             .into_iter()
             .flatten()
-            .map(|(_, hash, wasm, _)| (hash, wasm))
+            .map(|(_, hash, wasm, _, _)| (hash, wasm))
             .collect();
 
         let wasms = WasmMap::from(code);
@@ -184,6 +186,7 @@ impl DnaBundle {
                     .cloned()
                     .map(|name| ZomeDependency { name })
                     .collect();
+                let init_after = init_after_manifest(zome.as_any_zome_def());
                 zome.wasm_hash(&name).ok().map(|hash| {
                     let hash = WasmHashB64::from(hash);
                     let filename = format!("{}", hash);
@@ -192,6 +195,7 @@ impl DnaBundle {
                         hash: Some(hash),
                         location: Location::Bundled(PathBuf::from(filename)),
                         dependencies: Some(dependencies),
+                        init_after,
                     }
                 })
             })
@@ -207,6 +211,7 @@ impl DnaBundle {
                     .cloned()
                     .map(|name| ZomeDependency { name })
                     .collect();
+                let init_after = init_after_manifest(zome.as_any_zome_def());
                 zome.wasm_hash(&name).ok().map(|hash| {
                     let hash = WasmHashB64::from(hash);
                     let filename = format!("{}", hash);
@@ -215,6 +220,7 @@ impl DnaBundle {
                         hash: Some(hash),
                         location: Location::Bundled(PathBuf::from(filename)),
                         dependencies: Some(dependencies),
+                        init_after,
                     }
                 })
             })
@@ -241,7 +247,7 @@ impl DnaBundle {
 async fn hash_bytes(
     zomes: impl Iterator<Item = ZomeManifest>,
     resources: &mut HashMap<Location, ResourceBytes>,
-) -> DnaResult<Vec<(ZomeName, WasmHash, DnaWasm, Vec<ZomeName>)>> {
+) -> DnaResult<Vec<(ZomeName, WasmHash, DnaWasm, Vec<ZomeName>, Vec<ZomeName>)>> {
     let iter = zomes.map(|z| {
         let bytes = resources
             .remove(&z.location)
@@ -252,6 +258,9 @@ async fn hash_bytes(
         let dependencies = z.dependencies.map_or(Vec::with_capacity(0), |deps| {
             deps.into_iter().map(|d| d.name).collect()
         });
+        let init_after = z.init_after.map_or(Vec::with_capacity(0), |deps| {
+            deps.into_iter().map(|d| d.name).collect()
+        });
         async move {
             let hash = wasm.to_hash().await;
             if let Some(expected) = expected_hash {
@@ -259,7 +268,7 @@ async fn hash_bytes(
                     return Err(DnaError::WasmHashMismatch(expected, hash));
                 }
             }
-            DnaResult::Ok((zome_name, hash, wasm, dependencies))
+            DnaResult::Ok((zome_name, hash, wasm, dependencies, init_after))
         }
     });
     futures::stream::iter(iter)
@@ -270,6 +279,22 @@ async fn hash_bytes(
         .collect()
 }
 
+/// Only include `init_after` in the manifest if it's set.
+fn init_after_manifest(zome: &ZomeDef) -> Option<Vec<ZomeDependency>> {
+    let init_after = zome.init_after();
+    if init_after.is_empty() {
+        None
+    } else {
+        Some(
+            init_after
+                .iter()
+                .cloned()
+                .map(|name| ZomeDependency { name })
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -296,6 +321,7 @@ mod tests {
                         hash: None,
                         location: mr_bundle::Location::Bundled(path1.clone()),
                         dependencies: Default::default(),
+                        init_after: Default::default(),
                     },
                     ZomeManifest {
                         name: "zome2".into(),
@@ -303,6 +329,7 @@ mod tests {
                         hash: Some(hash1.clone().into()),
                         location: mr_bundle::Location::Bundled(path2.clone()),
                         dependencies: Default::default(),
+                        init_after: Default::default(),
                     },
                 ],
             },
@@ -119,3 +119,74 @@ coordinator:
         Err(DnaError::DanglingZomeDependency(dep, name)) if dep.as_str() == "zome2" && name.as_str() == "zome2"
     ));
 }
+
+#[test]
+fn init_after_not_pointing_at_another_zome_is_error() {
+    let manifest_yaml = r#"
+---
+manifest_version: "1"
+name: test_dna
+integrity:
+  zomes:
+    - name: zome1
+      bundled: zome-1.wasm
+coordinator:
+  zomes:
+    - name: zome2
+      bundled: zome-2.wasm
+      init_after:
+        - name: zome3
+    - name: zome3
+      bundled: zome-3.wasm
+      init_after:
+        - name: zome1
+        "#;
+
+    let manifest = serde_yaml::from_str::<DnaManifest>(manifest_yaml).unwrap();
+    assert!(ValidatedDnaManifest::try_from(manifest).is_ok());
+
+    let manifest_yaml = r#"
+---
+manifest_version: "1"
+name: test_dna
+integrity:
+  zomes:
+    - name: zome1
+      bundled: zome-1.wasm
+coordinator:
+  zomes:
+    - name: zome2
+      bundled: zome-2.wasm
+      init_after:
+        - name: zome20
+        "#;
+
+    let manifest = serde_yaml::from_str::<DnaManifest>(manifest_yaml).unwrap();
+    assert!(matches!(
+        ValidatedDnaManifest::try_from(manifest),
+        Err(DnaError::ZomeError(ZomeError::DanglingInitDependency(dep, name))) if dep == "zome20".into() && name == "zome2".into()
+    ));
+
+    // Fails when pointing to self.
+    let manifest_yaml = r#"
+---
+manifest_version: "1"
+name: test_dna
+integrity:
+  zomes:
+    - name: zome1
+      bundled: zome-1.wasm
+coordinator:
+  zomes:
+    - name: zome2
+      bundled: zome-2.wasm
+      init_after:
+        - name: zome2
+        "#;
+
+    let manifest = serde_yaml::from_str::<DnaManifest>(manifest_yaml).unwrap();
+    assert!(matches!(
+        ValidatedDnaManifest::try_from(manifest),
+        Err(DnaError::ZomeError(ZomeError::DanglingInitDependency(dep, name))) if dep == "zome2".into() && name == "zome2".into()
+    ));
+}
@@ -51,12 +51,38 @@ impl From<WasmMapSerialized> for WasmMap {
 ///       to indicate that this is simply a validated, fully-formed DnaBundle
 ///       (i.e. all Wasms are bundled and immediately available, not remote.)
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, SerializedBytes)]
+#[serde(from = "DnaFileSerialized", into = "DnaFileSerialized")]
 pub struct DnaFile {
     /// The hashable portion that can be shared with hApp code.
     pub(super) dna: DnaDefHashed,
 
     /// The bytes of the WASM zomes referenced in the Dna portion.
     pub(super) code: WasmMap,
+
+    /// Lookups of the zomes in the Dna portion by name.
+    /// Must be rebuilt whenever the zomes change.
+    pub(super) zome_index: DnaZomeIndex,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DnaFileSerialized {
+    dna: DnaDefHashed,
+    code: WasmMap,
+}
+
+impl From<DnaFile> for DnaFileSerialized {
+    fn from(f: DnaFile) -> Self {
+        Self {
+            dna: f.dna,
+            code: f.code,
+        }
+    }
+}
+
+impl From<DnaFileSerialized> for DnaFile {
+    fn from(f: DnaFileSerialized) -> Self {
+        Self::from_hashed(f.dna, f.code)
+    }
 }
 
 impl From<DnaFile> for (DnaDef, Vec<wasm::DnaWasm>) {
@@ -80,10 +106,16 @@ impl DnaFile {
             code.insert(wasm_hash, wasm);
         }
         let dna = DnaDefHashed::from_content_sync(dna);
-        Ok(Self {
+        Ok(Self::from_hashed(dna, code.into()))
+    }
+
+    fn from_hashed(dna: DnaDefHashed, code: WasmMap) -> Self {
+        let zome_index = DnaZomeIndex::new(&dna);
+        Self {
             dna,
-            code: code.into(),
-        })
+            code,
+            zome_index,
+        }
     }
 
     /// Hot swap coordinator zomes for this dna.
@@ -139,13 +171,18 @@ impl DnaFile {
             .filter_map(|name| coordinators.remove_entry(&name))
             .collect();
 
+        self.zome_index = DnaZomeIndex::new(&self.dna);
+
+        // The new coordinators must not break the init order.
+        self.dna.content.init_order()?;
+
         Ok(old_wasm_hashes)
     }
 
     /// Construct a DnaFile from its constituent parts
     #[cfg(feature = "fixturators")]
     pub fn from_parts(dna: DnaDefHashed, code: WasmMap) -> Self {
-        Self { dna, code }
+        Self::from_hashed(dna, code)
     }
 
     /// The DnaDef along with its hash
@@ -158,6 +195,68 @@ impl DnaFile {
         &self.dna
     }
 
+    /// Lookups of the zomes in the DnaDef by name
+    pub fn zome_index(&self) -> &DnaZomeIndex {
+        &self.zome_index
+    }
+
+    /// Find an integrity zome from a [`ZomeName`].
+    pub fn get_integrity_zome(&self, zome_name: &ZomeName) -> Result<IntegrityZome, ZomeError> {
+        self.zome_index
+            .integrity_zome_index(zome_name)
+            .and_then(|i| self.dna.integrity_zomes.get(i))
+            .cloned()
+            .map(|(name, def)| IntegrityZome::new(name, def))
+            .ok_or_else(|| zome_not_found(zome_name))
+    }
+
+    /// Find a coordinator zome from a [`ZomeName`].
+    pub fn get_coordinator_zome(&self, zome_name: &ZomeName) -> Result<CoordinatorZome, ZomeError> {
+        self.zome_index
+            .coordinator_zome_index(zome_name)
+            .and_then(|i| self.dna.coordinator_zomes.get(i))
+            .cloned()
+            .map(|(name, def)| CoordinatorZome::new(name, def))
+            .ok_or_else(|| zome_not_found(zome_name))
+    }
+
+    /// Find any zome from a [`ZomeName`].
+    pub fn get_zome(&self, zome_name: &ZomeName) -> Result<Zome, ZomeError> {
+        self.get_zome_def(zome_name)
+            .cloned()
+            .map(|def| Zome::new(zome_name.clone(), def))
+    }
+
+    /// Check if a zome is an integrity zome.
+    pub fn is_integrity_zome(&self, zome_name: &ZomeName) -> bool {
+        self.zome_index.is_integrity_zome(zome_name)
+    }
+
+    /// Return a Zome, error if not a WasmZome
+    pub fn get_wasm_zome(&self, zome_name: &ZomeName) -> Result<&WasmZome, ZomeError> {
+        match self.get_zome_def(zome_name)? {
+            ZomeDef::Wasm(wasm_zome) => Ok(wasm_zome),
+            _ => Err(ZomeError::NonWasmZome(zome_name.clone())),
+        }
+    }
+
+    fn get_zome_def(&self, zome_name: &ZomeName) -> Result<&ZomeDef, ZomeError> {
+        let integrity = self
+            .zome_index
+            .integrity_zome_index(zome_name)
+            .and_then(|i| self.dna.integrity_zomes.get(i))
+            .map(|(_, def)| def.as_any_zome_def());
+        let coordinator = || {
+            self.zome_index
+                .coordinator_zome_index(zome_name)
+                .and_then(|i| self.dna.coordinator_zomes.get(i))
+                .map(|(_, def)| def.as_any_zome_def())
+        };
+        integrity
+            .or_else(coordinator)
+            .ok_or_else(|| zome_not_found(zome_name))
+    }
+
     /// The hash of the DnaDef
     pub fn dna_hash(&self) -> &holo_hash::DnaHash {
         self.dna.as_hash()
@@ -214,7 +313,7 @@ impl DnaFile {
 
     /// Fetch the Webassembly byte code for a zome.
     pub fn get_wasm_for_zome(&self, zome_name: &ZomeName) -> Result<&wasm::DnaWasm, DnaError> {
-        let wasm_hash = &self.dna.get_wasm_zome(zome_name)?.wasm_hash;
+        let wasm_hash = &self.get_wasm_zome(zome_name)?.wasm_hash;
         self.code.0.get(wasm_hash).ok_or(DnaError::InvalidWasmHash)
     }
 
@@ -247,6 +346,10 @@ impl DnaFile {
     }
 }
 
+fn zome_not_found(zome_name: &ZomeName) -> ZomeError {
+    ZomeError::ZomeNotFound(format!("Zome '{}' not found", zome_name))
+}
+
 impl std::fmt::Debug for DnaFile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("DnaFile(dna = {:?})", self.dna))
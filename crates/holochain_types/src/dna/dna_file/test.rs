@@ -34,6 +34,7 @@ async fn test_hotswap() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&dna_wasms[2]).await,
                 dependencies: vec!["b".into()],
+                init_after: Default::default(),
             })),
         ),
         (
@@ -41,6 +42,7 @@ async fn test_hotswap() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&dna_wasms[3]).await,
                 dependencies: vec!["b".into(), "a".into()],
+                init_after: Default::default(),
             })),
         ),
     ];
@@ -65,6 +67,7 @@ async fn test_hotswap() {
         CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
             wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
             dependencies: vec!["b".into()],
+            init_after: Default::default(),
         })),
     )];
     let old_wasm = dna
@@ -98,6 +101,7 @@ async fn test_hotswap() {
         CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
             wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
             dependencies: vec!["a".into()],
+            init_after: Default::default(),
         })),
     )];
     let old_wasm = dna
@@ -139,6 +143,7 @@ async fn test_hotswap() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
                 dependencies: vec!["a".into()],
+                init_after: Default::default(),
             })),
         ),
         (
@@ -146,6 +151,7 @@ async fn test_hotswap() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[1]).await,
                 dependencies: vec!["a".into()],
+                init_after: Default::default(),
             })),
         ),
         (
@@ -153,6 +159,7 @@ async fn test_hotswap() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[2]).await,
                 dependencies: vec!["a".into()],
+                init_after: Default::default(),
             })),
         ),
         (
@@ -160,6 +167,7 @@ async fn test_hotswap() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[3]).await,
                 dependencies: vec!["a".into()],
+                init_after: Default::default(),
             })),
         ),
     ];
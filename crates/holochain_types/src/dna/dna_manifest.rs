@@ -100,6 +100,17 @@ impl TryFrom<DnaManifest> for ValidatedDnaManifest {
                     if !names.insert(z.name.clone()) {
                         return Err(DnaError::DuplicateZomeNames(z.name.to_string()));
                     }
+                    if let Some(init_after) = &z.init_after {
+                        // Check the init dependencies are other zomes in this dna.
+                        if let Some(dep) = init_after.iter().find(|ZomeDependency { name }| {
+                            *name == z.name || !m.all_zomes().any(|other| other.name == *name)
+                        }) {
+                            return Err(DnaError::ZomeError(ZomeError::DanglingInitDependency(
+                                dep.name.clone(),
+                                z.name.clone(),
+                            )));
+                        }
+                    }
                     if let Some(dependencies) = &z.dependencies {
                         // Check the dependency zome names exist in the integrity zomes
                         // and does not point to self.
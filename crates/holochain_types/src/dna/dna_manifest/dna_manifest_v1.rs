@@ -34,8 +34,13 @@ use serde_with::serde_as;
 ///       dependencies:
 ///         - name: zome1
 ///         - name: zome2
+///       init_after:
+///         - name: zome3
 /// ```
 ///
+/// `init_after` makes zome4's `init` callback run after zome3's.
+/// Cycles are rejected when the DNA is registered.
+///
 /// When there's only one integrity zome, it will automatically be a dependency
 /// of the coordinator zomes. It doesn't need to be specified explicitly.
 ///
@@ -159,6 +164,11 @@ pub struct ZomeManifest {
     /// The order of these must match the order the types
     /// are used in the zome.
     pub dependencies: Option<Vec<ZomeDependency>>,
+
+    /// Zomes whose `init` callback must run before this zome's.
+    /// Zomes without init dependencies run in the order they are listed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub init_after: Option<Vec<ZomeDependency>>,
 }
 
 /// Manifest for integrity zomes that another zome
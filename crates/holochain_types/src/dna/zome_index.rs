@@ -0,0 +1,62 @@
+use holochain_zome_types::prelude::*;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod test;
+
+/// Maps the name of each zome in a [`DnaDef`] to its position in the
+/// integrity or coordinator zome list.
+///
+/// This is built once when a [`DnaFile`](super::DnaFile) is created so
+/// zomes can be looked up by name without scanning the zome lists.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DnaZomeIndex {
+    integrity: HashMap<ZomeName, usize>,
+    coordinator: HashMap<ZomeName, usize>,
+    num_integrity: usize,
+}
+
+impl DnaZomeIndex {
+    /// Index the zomes of a [`DnaDef`].
+    ///
+    /// If a name appears more than once the first zome with that name
+    /// is indexed, which matches a linear scan of the zome lists.
+    pub fn new(dna_def: &DnaDef) -> Self {
+        fn index<'a>(names: impl Iterator<Item = &'a ZomeName>) -> HashMap<ZomeName, usize> {
+            let mut map = HashMap::new();
+            for (i, name) in names.enumerate() {
+                map.entry(name.clone()).or_insert(i);
+            }
+            map
+        }
+        Self {
+            integrity: index(dna_def.integrity_zomes.iter().map(|(n, _)| n)),
+            coordinator: index(dna_def.coordinator_zomes.iter().map(|(n, _)| n)),
+            num_integrity: dna_def.integrity_zomes.len(),
+        }
+    }
+
+    /// The position of an integrity zome in [`DnaDef::integrity_zomes`].
+    /// This is also the [`ZomeId`] of the zome.
+    pub fn integrity_zome_index(&self, zome_name: &ZomeName) -> Option<usize> {
+        self.integrity.get(zome_name).copied()
+    }
+
+    /// The position of a coordinator zome in [`DnaDef::coordinator_zomes`].
+    pub fn coordinator_zome_index(&self, zome_name: &ZomeName) -> Option<usize> {
+        self.coordinator.get(zome_name).copied()
+    }
+
+    /// The position of any zome in [`DnaDef::all_zomes`].
+    pub fn zome_position(&self, zome_name: &ZomeName) -> Option<usize> {
+        self.integrity_zome_index(zome_name).or_else(|| {
+            self.coordinator_zome_index(zome_name)
+                .map(|i| i + self.num_integrity)
+        })
+    }
+
+    /// Check if a zome is an integrity zome.
+    pub fn is_integrity_zome(&self, zome_name: &ZomeName) -> bool {
+        self.integrity.contains_key(zome_name)
+    }
+}
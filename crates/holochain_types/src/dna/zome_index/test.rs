@@ -0,0 +1,51 @@
+use super::*;
+
+fn dna_def(integrity: &[&str], coordinators: &[&str]) -> DnaDef {
+    let zome = || ZomeDef::Wasm(WasmZome::new(holo_hash::WasmHash::from_raw_32(vec![0; 32])));
+    DnaDef {
+        name: "test".to_string(),
+        uid: "test".to_string(),
+        properties: SerializedBytes::try_from(()).unwrap(),
+        origin_time: Timestamp::HOLOCHAIN_EPOCH,
+        integrity_zomes: integrity
+            .iter()
+            .map(|n| (ZomeName::from(*n), zome().into()))
+            .collect(),
+        coordinator_zomes: coordinators
+            .iter()
+            .map(|n| (ZomeName::from(*n), zome().into()))
+            .collect(),
+    }
+}
+
+#[test]
+fn zome_index_matches_zome_lists() {
+    let dna_def = dna_def(&["a", "b"], &["c", "d"]);
+    let index = DnaZomeIndex::new(&dna_def);
+
+    for name in ["a", "b", "c", "d", "e"] {
+        let name = ZomeName::from(name);
+        assert_eq!(
+            index.zome_position(&name),
+            dna_def.all_zomes().position(|(n, _)| *n == name)
+        );
+        assert_eq!(
+            index.is_integrity_zome(&name),
+            dna_def.is_integrity_zome(&name)
+        );
+    }
+    assert_eq!(index.integrity_zome_index(&"b".into()), Some(1));
+    assert_eq!(index.integrity_zome_index(&"c".into()), None);
+    assert_eq!(index.coordinator_zome_index(&"d".into()), Some(1));
+    assert_eq!(index.coordinator_zome_index(&"a".into()), None);
+}
+
+#[test]
+fn zome_index_keeps_first_duplicate() {
+    let dna_def = dna_def(&["a", "a"], &["b", "b"]);
+    let index = DnaZomeIndex::new(&dna_def);
+
+    assert_eq!(index.integrity_zome_index(&"a".into()), Some(0));
+    assert_eq!(index.coordinator_zome_index(&"b".into()), Some(0));
+    assert_eq!(index.zome_position(&"b".into()), Some(2));
+}
@@ -39,6 +39,9 @@ pub struct WireEntryOps {
     pub updates: Vec<Judged<WireUpdateRelationship>>,
     /// The entry data shared across all actions.
     pub entry: Option<EntryData>,
+    /// Warrants held against authors of data at this entry.
+    #[serde(default)]
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
@@ -62,6 +65,7 @@ impl WireEntryOps {
             deletes,
             updates,
             entry,
+            warrants,
         } = self;
         match entry {
             Some(EntryData { entry, entry_type }) => {
@@ -108,9 +112,13 @@ impl WireEntryOps {
                 Ok(RenderedOps {
                     entry: Some(entry_hashed),
                     ops,
+                    warrants,
                 })
             }
-            None => Ok(Default::default()),
+            None => Ok(RenderedOps {
+                warrants,
+                ..Default::default()
+            }),
         }
     }
 }
@@ -8,6 +8,7 @@ use holo_hash::EntryHash;
 use holochain_serialized_bytes::prelude::*;
 use holochain_zome_types::prelude::*;
 use regex::Regex;
+use std::collections::HashMap;
 
 use crate::dht_op::error::DhtOpError;
 use crate::dht_op::error::DhtOpResult;
@@ -74,6 +75,49 @@ impl WireLinkOps {
             ..Default::default()
         })
     }
+
+    /// Keep only the page of creates asked for, along with the deletes of
+    /// every create that was passed over while filling the page.
+    ///
+    /// Deleted creates don't count towards the limit, so a full page is
+    /// returned whenever there are enough live links after the cursor.
+    pub fn paginate(self, key: &WireLinkKey, pagination: &LinkPagination) -> Self {
+        let Self { creates, deletes } = self;
+        let mut deletes_by_create: HashMap<ActionHash, Vec<WireDeleteLink>> = HashMap::new();
+        for delete in deletes {
+            deletes_by_create
+                .entry(delete.link_add_address.clone())
+                .or_default()
+                .push(delete);
+        }
+        // Creates that fail to render would be ignored by the requester anyway.
+        let mut creates: Vec<_> = creates
+            .into_iter()
+            .filter_map(|create| {
+                let hash = create.clone().render(key).ok()?.action.as_hash().clone();
+                Some((hash, create))
+            })
+            .filter(|(hash, create)| pagination.is_after_cursor(&create.timestamp, hash))
+            .collect();
+        creates.sort_by(|(a_hash, a), (b_hash, b)| {
+            pagination.compare((&a.timestamp, a_hash), (&b.timestamp, b_hash))
+        });
+
+        let limit = pagination.limit.map_or(usize::MAX, |l| l as usize);
+        let mut page = Self::new();
+        let mut live = 0;
+        for (hash, create) in creates {
+            if live >= limit {
+                break;
+            }
+            match deletes_by_create.remove(&hash) {
+                Some(deletes) => page.deletes.extend(deletes),
+                None => live += 1,
+            }
+            page.creates.push(create);
+        }
+        page
+    }
 }
 
 #[allow(missing_docs)]
@@ -29,6 +29,9 @@ pub struct WireRecordOps {
     pub updates: Vec<Judged<WireUpdateRelationship>>,
     /// The entry if there is one.
     pub entry: Option<Entry>,
+    /// Warrants held against authors of data at this action.
+    #[serde(default)]
+    pub warrants: Vec<SignedWarrant>,
 }
 
 impl WireRecordOps {
@@ -43,6 +46,7 @@ impl WireRecordOps {
             deletes,
             updates,
             entry,
+            warrants,
         } = self;
         let mut ops = Vec::with_capacity(1 + deletes.len() + updates.len());
         if let Some(action) = action {
@@ -89,6 +93,7 @@ impl WireRecordOps {
         Ok(RenderedOps {
             entry: entry.map(EntryHashed::from_content_sync),
             ops,
+            warrants,
         })
     }
 }
@@ -351,6 +356,11 @@ pub trait SignedActionHashedExt {
         keystore: &MetaLairClient,
         action: ActionHashed,
     ) -> LairResult<SignedActionHashed>;
+    /// Sign many actions with a single batch of keystore requests.
+    async fn sign_batch(
+        keystore: &MetaLairClient,
+        actions: Vec<ActionHashed>,
+    ) -> LairResult<Vec<SignedActionHashed>>;
     /// Validate the data
     async fn validate(&self) -> Result<(), KeystoreError>;
 }
@@ -371,6 +381,26 @@ impl SignedActionHashedExt for SignedActionHashed {
         Ok(Self::with_presigned(action, signature))
     }
 
+    async fn sign_batch(
+        keystore: &MetaLairClient,
+        actions: Vec<ActionHashed>,
+    ) -> LairResult<Vec<Self>> {
+        let batch = actions
+            .iter()
+            .map(|action| {
+                let data =
+                    holochain_serialized_bytes::encode(&**action).map_err(one_err::OneErr::new)?;
+                Ok((action.author().clone(), data.into()))
+            })
+            .collect::<LairResult<Vec<_>>>()?;
+        let signatures = keystore.sign_batch(batch).await?;
+        Ok(actions
+            .into_iter()
+            .zip(signatures)
+            .map(|(action, signature)| Self::with_presigned(action, signature))
+            .collect())
+    }
+
     /// Validates a signed action
     async fn validate(&self) -> Result<(), KeystoreError> {
         if !self
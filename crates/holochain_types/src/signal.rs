@@ -1,6 +1,7 @@
 //! Signals which can be emitted from within Holochain, out across an interface.
 //! There are two main kinds of Signal: system-defined, and app-defined:
-//! - App-defined signals are produced via the `emit_signal` host function.
+//! - App-defined signals are produced via the `emit_signal` and
+//!   `emit_signal_ack_required` host functions.
 //! - System-defined signals are produced in various places in the system
 
 use crate::impl_from;
@@ -13,6 +14,9 @@ use holochain_zome_types::prelude::*;
 pub enum Signal {
     /// Signal from a Cell, generated by `emit_signal`
     App(CellId, AppSignal),
+    /// Signal from a Cell, generated by `emit_signal_ack_required`.
+    /// The client must acknowledge it by its id, the first field.
+    AppAckRequired(u64, CellId, AppSignal),
     /// System-defined signals
     System(SystemSignal),
 }
@@ -29,6 +33,16 @@ pub enum SystemSignal {
     Test(String),
     /// A countersigning session has successfully completed.
     SuccessfulCountersigning(holo_hash::EntryHash),
+    /// The outcome of sending a reliable remote signal to one agent.
+    RemoteSignalDelivery {
+        /// The cell which sent the signal.
+        cell_id: CellId,
+        /// The agent the signal was sent to.
+        to_agent: holo_hash::AgentPubKey,
+        /// Whether the agent received the signal before the
+        /// sender gave up retrying.
+        delivered: bool,
+    },
 }
 
 /// Create a test signal
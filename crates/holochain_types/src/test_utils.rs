@@ -51,6 +51,7 @@ pub fn fake_dna_zomes_named(uid: &str, name: &str, zomes: Vec<(ZomeName, DnaWasm
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash,
                     dependencies: Default::default(),
+                    init_after: Default::default(),
                 })
                 .into(),
             ));
@@ -16,3 +16,78 @@ use holochain_zome_types::prelude::*;
 )]
 /// Type for sending responses to `get_validation_package`
 pub struct ValidationPackageResponse(pub Option<ValidationPackage>);
+
+/// Records why an op was rejected during validation, so that the
+/// reason can be inspected later without re-running validation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct ValidationRejection {
+    /// The zome whose validation callback rejected the op.
+    /// `None` if the op was rejected by sys validation or before any
+    /// zome callback could run.
+    pub zome_name: Option<ZomeName>,
+    /// The callback which produced the rejection, e.g. `validate`.
+    pub callback: Option<String>,
+    /// Identifies the sys validation rule which rejected the op.
+    pub rule: Option<String>,
+    /// The reason given for the rejection.
+    pub reason: String,
+}
+
+impl ValidationRejection {
+    /// A rejection without any provenance besides the reason.
+    pub fn new<R: Into<String>>(reason: R) -> Self {
+        Self {
+            zome_name: None,
+            callback: None,
+            rule: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// A rejection returned by the `validate` callback of an app zome.
+    pub fn app<R: Into<String>>(zome_name: Option<ZomeName>, reason: R) -> Self {
+        Self {
+            zome_name,
+            callback: Some("validate".to_string()),
+            rule: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// A rejection produced by a sys validation rule.
+    pub fn sys<Rule: Into<String>, R: Into<String>>(rule: Rule, reason: R) -> Self {
+        Self {
+            zome_name: None,
+            callback: None,
+            rule: Some(rule.into()),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<String> for ValidationRejection {
+    fn from(reason: String) -> Self {
+        Self::new(reason)
+    }
+}
+
+impl From<&str> for ValidationRejection {
+    fn from(reason: &str) -> Self {
+        Self::new(reason)
+    }
+}
+
+impl std::fmt::Display for ValidationRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(zome_name) = &self.zome_name {
+            write!(f, "zome {} ", zome_name)?;
+        }
+        if let Some(callback) = &self.callback {
+            write!(f, "callback {} ", callback)?;
+        }
+        if let Some(rule) = &self.rule {
+            write!(f, "rule {} ", rule)?;
+        }
+        write!(f, "rejected: {}", self.reason)
+    }
+}
@@ -2,6 +2,7 @@ use criterion::criterion_group;
 use criterion::criterion_main;
 use criterion::BenchmarkId;
 use criterion::Criterion;
+use criterion::Throughput;
 
 use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::*;
@@ -13,7 +14,7 @@ use std::convert::TryInto;
 use tokio_stream::StreamExt;
 use url2::prelude::*;
 
-criterion_group!(benches, simple_bench);
+criterion_group!(benches, simple_bench, large_payload);
 
 criterion_main!(benches);
 
@@ -53,6 +54,42 @@ fn simple_bench(bench: &mut Criterion) {
     });
 }
 
+fn large_payload(bench: &mut Criterion) {
+    let _g = observability::test_run().ok();
+
+    let runtime = rt();
+
+    let (listener, listener_address, jh) = runtime.block_on(setup_echo());
+    let (mut send, _recv) = runtime.block_on(setup_client(listener_address));
+
+    let mut group = bench.benchmark_group("large_payload");
+    group.sample_size(20);
+    for size in [1 << 10, 1 << 20, 8 << 20] {
+        let payload = SerializedBytes::from(UnsafeBytes::from(vec![1u8; size]));
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("request", size), &payload, |b, payload| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let rsp: SerializedBytes = send.request(payload.clone()).await.unwrap();
+                    assert_eq!(rsp.bytes().len(), size);
+                });
+            });
+        });
+        let data = Bytes::from(payload.bytes().clone());
+        group.bench_with_input(BenchmarkId::new("signal_bytes", size), &data, |b, data| {
+            b.iter(|| {
+                runtime.block_on(send.signal_bytes(data.clone())).unwrap();
+            });
+        });
+    }
+    group.finish();
+    runtime.block_on(async move {
+        listener.close();
+        drop(send);
+        jh.await.unwrap();
+    });
+}
+
 async fn client_request(send: &mut WebsocketSender) -> () {
     let msg = TestMessage("test".to_string());
     // Make a request and get the echoed response
@@ -125,6 +162,36 @@ async fn setup() -> (ListenerHandle, Url2, JoinHandle<()>) {
     (handle, addr, jh)
 }
 
+async fn setup_echo() -> (ListenerHandle, Url2, JoinHandle<()>) {
+    // Create a server that echos back requests without deserializing them
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        std::sync::Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .unwrap();
+
+    let jh = tokio::task::spawn(async move {
+        let mut jhs = Vec::new();
+        while let Some(Ok((_send, mut recv))) = listener.next().await {
+            let jh = tokio::task::spawn(async move {
+                while let Some((msg, resp)) = recv.next().await {
+                    if resp.is_request() {
+                        resp.respond(msg).await.unwrap();
+                    }
+                }
+            });
+            jhs.push(jh);
+        }
+        for jh in jhs {
+            jh.await.unwrap();
+        }
+    });
+
+    let addr = handle.local_addr().clone();
+    (handle, addr, jh)
+}
+
 async fn setup_client(binding: Url2) -> (WebsocketSender, WebsocketReceiver) {
     // Connect the client to the server
     connect(binding, std::sync::Arc::new(WebsocketConfig::default()))
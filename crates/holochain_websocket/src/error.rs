@@ -19,6 +19,12 @@ pub enum WebsocketError {
     RespTimeout,
     #[error("The websocket connection has shutdown")]
     Shutdown,
+    #[error("The outgoing queue is full")]
+    QueueFull,
+    #[error("Channel {0} is already open")]
+    ChannelInUse(u32),
+    #[error("Connection rejected with {}: {}", .0.status, .0.reason)]
+    ConnectionRejected(crate::Rejection),
 }
 
 pub type WebsocketResult<T> = Result<T, WebsocketError>;
@@ -12,6 +12,21 @@
 //! [`WebsocketReceiver`]
 //! ).
 //!
+//! For a client connection that reconnects when the socket drops, use
+//! [`connect_persistent`].
+//!
+//! On unix both [`connect`] and [`WebsocketListener::bind`] also accept
+//! `unix:///path/to/sock` urls to use a unix domain socket instead of tcp.
+//! Access to a listener bound this way can be restricted with the
+//! filesystem permissions of the socket file or its directory.
+//!
+//! To check incoming connections before they are accepted, e.g. to require
+//! a token, set [`WebsocketConfig::on_connect`] on the listener. Clients can
+//! pass a token in the query string of the url they connect to.
+//!
+//! Independent request / response streams can share one connection
+//! by opening a channel on each side with [`WebsocketSender::open_channel`].
+//!
 //! If you want to be able to shutdown the stream use [`WebsocketListener::bind_with_handle`]
 //! which will give you a tuple ([`ListenerHandle`], [`ListenerStream`]).
 //! You can use [`ListenerHandle::close`] to close immediately or
@@ -80,16 +95,27 @@ use std::io::Error;
 use std::io::ErrorKind;
 use std::sync::Arc;
 
+pub use bytes::Bytes;
 use holochain_serialized_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::client::IntoClientRequest;
 use url2::Url2;
 use util::url_to_addr;
+use util::RawSocket;
 use websocket::Websocket;
 
 mod websocket_config;
 pub use websocket_config::*;
 
+mod websocket_metrics;
+pub use websocket_metrics::*;
+
+mod websocket_compression;
+
+mod websocket_auth;
+pub use websocket_auth::*;
+
 #[allow(missing_docs)]
 mod error;
 pub use error::*;
@@ -103,36 +129,59 @@ pub use websocket_sender::*;
 mod websocket_receiver;
 pub use websocket_receiver::*;
 
+mod websocket_persistent;
+pub use websocket_persistent::*;
+
 mod websocket;
 
 mod util;
 
 #[instrument(skip(config))]
 /// Create a new external websocket connection.
+///
+/// On unix a `unix:///path/to/sock` url connects over a unix domain socket.
 pub async fn connect(
     url: Url2,
     config: Arc<WebsocketConfig>,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    #[cfg(unix)]
+    if let Some(path) = util::url_to_path(&url) {
+        let socket = tokio::net::UnixStream::connect(&path).await?;
+        // The handshake needs a websocket url but the host is never used.
+        let request = url2::url2!("{}://localhost/", config.scheme);
+        return client_handshake(request, RawSocket::unix(socket, path), config).await;
+    }
     let addr = url_to_addr(&url, config.scheme).await?;
     let socket = tokio::net::TcpStream::connect(addr).await?;
     // TODO: find equivalent of this in new tokio
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
-    tracing::debug!("Client connected");
+    client_handshake(url, RawSocket::tcp(socket), config).await
+}
+
+async fn client_handshake(
+    url: Url2,
+    socket: RawSocket,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    websocket_compression::request_compression(&mut request, config.compression_threshold);
+    let (socket, response) =
+        tokio_tungstenite::client_async_with_config(request, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let compress = websocket_compression::accepts_compression(response.headers());
+    tracing::debug!(compress, "Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, compress, valve)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -140,28 +189,64 @@ pub async fn connect(
 /// The messages actually sent over the wire by this library.
 /// If you want to impliment your own server or client you
 /// will need this type or be able to serialize / deserialize it.
+///
+/// The data is held as [`Bytes`] so payloads can be shared between
+/// connections without copying. It is encoded as a message pack binary
+/// just like a `Vec<u8>` so the wire format is unchanged.
 pub enum WireMessage {
     /// A message without a response.
     Signal {
-        #[serde(with = "serde_bytes")]
+        /// The channel this message was sent on.
+        /// Omitted on the wire for the default channel `0`.
+        #[serde(default, skip_serializing_if = "is_default_channel")]
+        channel: u32,
         /// Actual bytes of the message serialized as [message pack](https://msgpack.org/).
-        data: Vec<u8>,
+        data: Bytes,
     },
     /// A request that requires a response.
     Request {
         /// The id of this request.
         /// Note ids are recycled once they are used.
         id: u64,
-        #[serde(with = "serde_bytes")]
+        /// The channel this request was sent on.
+        /// Omitted on the wire for the default channel `0`.
+        #[serde(default, skip_serializing_if = "is_default_channel")]
+        channel: u32,
         /// Actual bytes of the message serialized as [message pack](https://msgpack.org/).
-        data: Vec<u8>,
+        data: Bytes,
     },
     /// The response to a request.
     Response {
         /// The id of the request that this response is for.
         id: u64,
-        #[serde(with = "serde_bytes")]
         /// Actual bytes of the message serialized as [message pack](https://msgpack.org/).
-        data: Option<Vec<u8>>,
+        data: Option<Bytes>,
     },
 }
+
+impl WireMessage {
+    /// The kind of this message.
+    pub(crate) fn kind(&self) -> WebsocketMessageKind {
+        match self {
+            WireMessage::Signal { .. } => WebsocketMessageKind::Signal,
+            WireMessage::Request { .. } => WebsocketMessageKind::Request,
+            WireMessage::Response { .. } => WebsocketMessageKind::Response,
+        }
+    }
+
+    /// The length of the payload carried by this message.
+    pub(crate) fn data_len(&self) -> usize {
+        match self {
+            WireMessage::Signal { data, .. } | WireMessage::Request { data, .. } => data.len(),
+            WireMessage::Response { data, .. } => data.as_ref().map_or(0, Bytes::len),
+        }
+    }
+}
+
+/// The channel used by connections unless another is opened
+/// with [`WebsocketSender::open_channel`].
+pub(crate) const DEFAULT_CHANNEL: u32 = 0;
+
+fn is_default_channel(channel: &u32) -> bool {
+    *channel == DEFAULT_CHANNEL
+}
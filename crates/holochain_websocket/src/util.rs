@@ -1,12 +1,127 @@
 //! internal websocket utility types and code
 
 use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
 
+use bytes::Bytes;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
 use url2::{url2, Url2};
 
 use std::io::{Error, ErrorKind, Result};
 
-pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<RawSocket>;
+
+/// The url scheme for connecting over a unix domain socket,
+/// e.g. `unix:///path/to/sock`.
+#[cfg(unix)]
+pub(crate) const UNIX_SCHEME: &str = "unix";
+
+/// The stream a websocket runs over.
+#[derive(Debug)]
+pub(crate) struct RawSocket {
+    stream: RawStream,
+    /// Bytes already read from the stream which are read again first.
+    read_ahead: Bytes,
+}
+
+#[derive(Debug)]
+enum RawStream {
+    /// A tcp connection.
+    Tcp(tokio::net::TcpStream),
+    /// A unix domain socket connection and the path of the socket.
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream, PathBuf),
+}
+
+impl RawSocket {
+    /// A socket over a tcp connection.
+    pub(crate) fn tcp(socket: tokio::net::TcpStream) -> Self {
+        Self {
+            stream: RawStream::Tcp(socket),
+            read_ahead: Bytes::new(),
+        }
+    }
+
+    /// A socket over a unix domain socket connection at this path.
+    #[cfg(unix)]
+    pub(crate) fn unix(socket: tokio::net::UnixStream, path: PathBuf) -> Self {
+        Self {
+            stream: RawStream::Unix(socket, path),
+            read_ahead: Bytes::new(),
+        }
+    }
+
+    /// The url of the other side of this socket.
+    ///
+    /// Unix domain socket clients are usually unnamed
+    /// so for these the url of the socket path is used.
+    pub(crate) fn peer_url(&self, scheme: &str) -> Result<Url2> {
+        match &self.stream {
+            RawStream::Tcp(socket) => Ok(addr_to_url(socket.peer_addr()?, scheme)),
+            #[cfg(unix)]
+            RawStream::Unix(_, path) => Ok(path_to_url(path)),
+        }
+    }
+
+    /// Put bytes that have been read back so they are read again
+    /// before anything else from the stream.
+    pub(crate) fn unread(&mut self, bytes: Bytes) {
+        debug_assert!(self.read_ahead.is_empty());
+        self.read_ahead = bytes;
+    }
+}
+
+impl AsyncRead for RawSocket {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        if !this.read_ahead.is_empty() {
+            let len = std::cmp::min(this.read_ahead.len(), buf.remaining());
+            buf.put_slice(&this.read_ahead.split_to(len));
+            return Poll::Ready(Ok(()));
+        }
+        match &mut this.stream {
+            RawStream::Tcp(socket) => Pin::new(socket).poll_read(cx, buf),
+            #[cfg(unix)]
+            RawStream::Unix(socket, _) => Pin::new(socket).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for RawSocket {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        match &mut self.get_mut().stream {
+            RawStream::Tcp(socket) => Pin::new(socket).poll_write(cx, buf),
+            #[cfg(unix)]
+            RawStream::Unix(socket, _) => Pin::new(socket).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match &mut self.get_mut().stream {
+            RawStream::Tcp(socket) => Pin::new(socket).poll_flush(cx),
+            #[cfg(unix)]
+            RawStream::Unix(socket, _) => Pin::new(socket).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match &mut self.get_mut().stream {
+            RawStream::Tcp(socket) => Pin::new(socket).poll_shutdown(cx),
+            #[cfg(unix)]
+            RawStream::Unix(socket, _) => Pin::new(socket).poll_shutdown(cx),
+        }
+    }
+}
 
 /// Amount of time to spend waiting for channels to empty before forcing them to close.
 pub(crate) const CLOSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
@@ -16,6 +131,19 @@ pub(crate) fn addr_to_url(a: SocketAddr, scheme: &str) -> Url2 {
     url2!("{}://{}", scheme, a)
 }
 
+/// internal helper to convert a unix socket path to a url
+#[cfg(unix)]
+pub(crate) fn path_to_url(path: &std::path::Path) -> Url2 {
+    url2!("{}://{}", UNIX_SCHEME, path.display())
+}
+
+/// internal helper to get the socket path out of a `unix://` url.
+/// Returns `None` for any other scheme.
+#[cfg(unix)]
+pub(crate) fn url_to_path(url: &Url2) -> Option<PathBuf> {
+    (url.scheme() == UNIX_SCHEME).then(|| PathBuf::from(url.path()))
+}
+
 /// internal helper convert urls to socket addrs for binding / connection
 pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> Result<SocketAddr> {
     if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use bytes::Bytes;
 use futures::FutureExt;
 use futures::SinkExt;
 use futures::StreamExt;
@@ -16,28 +18,67 @@ use ghost_actor::*;
 use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::protocol::CloseFrame;
 
-use crate::util::addr_to_url;
+use crate::sb_to_bytes;
 use crate::util::ToFromSocket;
 use crate::util::CLOSE_TIMEOUT;
+use crate::websocket_compression::FrameCompression;
+use crate::websocket_metrics::ConnectionMetrics;
 use crate::CancelResponse;
 use crate::IncomingMessage;
 use crate::OutgoingMessage;
 use crate::RegisterResponse;
 use crate::Respond;
+use crate::TxChannelOpened;
 use crate::TxRequestsDebug;
 use crate::TxStaleRequest;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
+use crate::WebsocketMessageKind;
 use crate::WebsocketReceiver;
 use crate::WebsocketResult;
 use crate::WebsocketSender;
 use crate::WireMessage;
+use crate::DEFAULT_CHANNEL;
 
 type GhostResult<T> = std::result::Result<T, GhostError>;
 
+/// Room for the message type, id and field names
+/// that are encoded along side the data.
+const WIRE_MESSAGE_OVERHEAD: usize = 64;
+
 #[derive(Debug, Clone)]
 /// Actor that tracks responses.
-pub struct Websocket(GhostActor<WebsocketInner>);
+/// Also holds this connection's metrics if they are enabled
+/// and how its frames are compressed.
+pub struct Websocket(
+    GhostActor<WebsocketInner>,
+    Option<Arc<ConnectionMetrics>>,
+    FrameCompression,
+);
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type")]
+/// A [`WireMessage`] that borrows its data from the incoming frame.
+enum WireMessageRef<'a> {
+    Signal {
+        #[serde(default)]
+        channel: u32,
+        #[serde(borrow)]
+        data: &'a [u8],
+    },
+    Request {
+        id: u64,
+        #[serde(default)]
+        channel: u32,
+        #[serde(borrow)]
+        data: &'a [u8],
+    },
+    Response {
+        id: u64,
+        #[serde(borrow)]
+        data: Option<&'a [u8]>,
+    },
+}
 
 #[derive(Debug)]
 struct ResponseTracker {
@@ -45,11 +86,16 @@ struct ResponseTracker {
     responses: HashMap<u64, RegisterResponse>,
     /// The next key to use.
     index: u64,
+    /// Reports the number of registered responses.
+    metrics: Option<Arc<ConnectionMetrics>>,
 }
 
 /// Inner GhostActor data.
 struct WebsocketInner {
     responses: ResponseTracker,
+    /// Receivers for the channels opened on this connection.
+    /// The default channel isn't in here.
+    channels: HashMap<u32, TxFromWebsocket>,
 }
 
 // Channel from the application to the websocket and out to the external socket.
@@ -74,6 +120,18 @@ pub struct PairShutdown {
     close_to_socket: TxToWebsocket,
 }
 
+#[derive(Debug, Clone)]
+/// Keep-alive pings that have been sent to the other
+/// side but haven't been answered yet.
+struct Heartbeat {
+    /// How often to ping. Pings are disabled if this is zero.
+    interval: std::time::Duration,
+    /// Pings sent since we last heard from the other side.
+    missed: Arc<AtomicUsize>,
+    /// How many pings can go unanswered before we give up.
+    max_missed: usize,
+}
+
 /// Allows returning from inner functions with
 /// success (continue to next line not continue loop),
 /// continue (continue the loop), break for the outer task loop.
@@ -120,16 +178,18 @@ impl Task {
 }
 
 impl Websocket {
-    #[instrument(skip(config, socket, listener_shutdown))]
+    #[instrument(skip(config, socket, compress, listener_shutdown))]
     /// Create the ends of this websocket channel.
+    /// Large messages are compressed if both sides agreed to `compress`.
     pub fn create_ends(
         config: Arc<WebsocketConfig>,
         socket: ToFromSocket,
+        compress: bool,
         listener_shutdown: Valve,
     ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
         let remote_addr = url2::url2!(
             "{}#{}",
-            addr_to_url(socket.get_ref().peer_addr()?, config.scheme),
+            socket.get_ref().peer_url(config.scheme)?,
             nanoid::nanoid!(),
         );
 
@@ -158,9 +218,16 @@ impl Websocket {
         // TODO: Should this shutdown immediately or gracefully? Currently it is immediately.
         let rx_from_websocket = listener_shutdown.wrap(rx_from_websocket_stream);
 
+        let heartbeat = Heartbeat::new(&config);
+        let compression = FrameCompression::new(&config, compress);
+        let metrics = ConnectionMetrics::open(config.metrics.as_ref(), &remote_addr);
+
         // Run the to and from external socket tasks.
         Websocket::run(
             socket,
+            heartbeat,
+            metrics,
+            compression,
             tx_to_websocket.clone(),
             rx_to_websocket_stream,
             tx_from_websocket,
@@ -172,14 +239,20 @@ impl Websocket {
             tx_to_websocket,
             listener_shutdown,
             pair_shutdown_handle.clone(),
+            remote_addr.clone(),
+            config,
         );
         // Create the receiver end.
         let receiver = WebsocketReceiver::new(rx_from_websocket, remote_addr, pair_shutdown_handle);
         Ok((sender, receiver))
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip(
         socket,
+        heartbeat,
+        metrics,
+        compression,
         tx_to_websocket,
         rx_to_websocket,
         tx_from_websocket,
@@ -187,6 +260,9 @@ impl Websocket {
     ))]
     fn run(
         socket: ToFromSocket,
+        heartbeat: Heartbeat,
+        metrics: Option<Arc<ConnectionMetrics>>,
+        compression: FrameCompression,
         tx_to_websocket: TxToWebsocket,
         rx_to_websocket: RxToWebsocket,
         tx_from_websocket: TxFromWebsocket,
@@ -194,12 +270,14 @@ impl Websocket {
     ) {
         // Spawn the actor and run the socket tasks
         let (actor, driver) = GhostActor::new(WebsocketInner {
-            responses: ResponseTracker::new(),
+            responses: ResponseTracker::new(metrics.clone()),
+            channels: HashMap::new(),
         });
         tokio::task::spawn(driver);
-        let actor = Self(actor);
+        let actor = Self(actor, metrics, compression);
         actor.run_socket(
             socket,
+            heartbeat,
             tx_to_websocket,
             rx_to_websocket,
             tx_from_websocket,
@@ -210,6 +288,7 @@ impl Websocket {
     fn run_socket(
         self,
         socket: ToFromSocket,
+        heartbeat: Heartbeat,
         send_response: TxToWebsocket,
         to_websocket: RxToWebsocket,
         from_websocket: TxFromWebsocket,
@@ -232,7 +311,12 @@ impl Websocket {
         // Spawn the "to" external task.
         tokio::task::spawn(
             self.clone()
-                .run_to_socket(to_socket, to_websocket, shutdown_from_socket)
+                .run_to_socket(
+                    to_socket,
+                    to_websocket,
+                    heartbeat.clone(),
+                    shutdown_from_socket,
+                )
                 .in_current_span(),
         );
         // Spawn the "from" external task.
@@ -241,18 +325,20 @@ impl Websocket {
                 from_socket,
                 from_websocket,
                 send_response,
+                heartbeat,
                 shutdown_to_socket,
             )
             .in_current_span(),
         );
     }
 
-    #[instrument(skip(self, to_socket, to_websocket, _shutdown_from_socket))]
+    #[instrument(skip(self, to_socket, to_websocket, heartbeat, _shutdown_from_socket))]
     /// Task that sends out messages to the network.
     async fn run_to_socket(
         self,
         to_socket: impl futures::sink::Sink<tungstenite::Message, Error = tungstenite::error::Error>,
         to_websocket: impl futures::stream::Stream<Item = OutgoingMessage>,
+        heartbeat: Heartbeat,
         // When dropped this will shutdown the `from_socket` task.
         _shutdown_from_socket: Trigger,
     ) {
@@ -260,11 +346,13 @@ impl Websocket {
         tracing::trace!("starting sending external socket");
         futures::pin_mut!(to_socket);
         futures::pin_mut!(to_websocket);
+        let mut ping_interval = heartbeat.ping_interval();
         loop {
-            if let Err(t) = self
-                .process_to_websocket(to_websocket.next().await, &mut to_socket)
-                .await
-            {
+            let r = tokio::select! {
+                msg = to_websocket.next() => self.process_to_websocket(msg, &mut to_socket).await,
+                _ = Heartbeat::tick(&mut ping_interval) => heartbeat.ping(&mut to_socket).await,
+            };
+            if let Err(t) = r {
                 task = t;
             }
             // If during processing a message we encounter
@@ -308,20 +396,30 @@ impl Websocket {
                 // Map outgoing messages to wire messages.
                 let msg = match msg {
                     OutgoingMessage::Close => return Task::exit(),
-                    OutgoingMessage::Signal(msg) => WireMessage::Signal {
-                        data: UnsafeBytes::from(msg).into(),
-                    },
-                    OutgoingMessage::Request(msg, register_response, tx_stale_response) => {
-                        self.handle_outgoing_request(msg, register_response, tx_stale_response)
-                            .await?
+                    OutgoingMessage::Signal(channel, data) => WireMessage::Signal { channel, data },
+                    OutgoingMessage::Request(
+                        channel,
+                        msg,
+                        register_response,
+                        tx_stale_response,
+                    ) => {
+                        self.handle_outgoing_request(
+                            channel,
+                            msg,
+                            register_response,
+                            tx_stale_response,
+                        )
+                        .await?
                     }
-                    OutgoingMessage::Response(msg, id) => WireMessage::Response {
-                        id,
-                        data: msg.map(|m| UnsafeBytes::from(m).into()),
-                    },
+                    OutgoingMessage::Response(data, id) => WireMessage::Response { id, data },
                     OutgoingMessage::StaleRequest(id) => {
                         return self.handle_stale_request(id).await;
                     }
+                    OutgoingMessage::OpenChannel(channel, tx_from_websocket, tx_opened) => {
+                        return self
+                            .handle_open_channel(channel, tx_from_websocket, tx_opened)
+                            .await;
+                    }
                     OutgoingMessage::Pong(data) => {
                         // No need to deserialize, just send the data back
                         // and continue.
@@ -332,12 +430,25 @@ impl Websocket {
                         return self.handle_requests_debug(tx_requests_debug).await;
                     }
                 };
+                let kind = msg.kind();
                 let msg = Self::serialize_msg(msg)?;
+                let msg = match msg {
+                    tungstenite::Message::Binary(frame) => {
+                        tungstenite::Message::Binary(self.2.compress(frame))
+                    }
+                    msg => msg,
+                };
+                let len = msg.len();
 
                 // Write to_socket
                 match to_socket.send(msg).await {
                     // Successful send.
-                    Ok(_) => Task::cont(),
+                    Ok(_) => {
+                        if let Some(metrics) = &self.1 {
+                            metrics.message_sent(kind, len);
+                        }
+                        Task::cont()
+                    }
                     // Connection is already closed so exit immediately.
                     Err(tungstenite::Error::ConnectionClosed) => Task::exit_now(),
                     Err(e) => {
@@ -357,6 +468,7 @@ impl Websocket {
         from_socket,
         from_websocket,
         send_response,
+        heartbeat,
         shutdown_to_socket_immediately
     ))]
     /// Task that takes in messages from the network.
@@ -367,6 +479,7 @@ impl Websocket {
         >,
         mut from_websocket: TxFromWebsocket,
         mut send_response: TxToWebsocket,
+        heartbeat: Heartbeat,
         shutdown_to_socket_immediately: Trigger,
     ) {
         let mut task = Task::Continue;
@@ -377,6 +490,10 @@ impl Websocket {
         // and will close when that connection closes.
         loop {
             let msg = from_socket.next().await;
+            if let Some(Ok(_)) = msg {
+                // Anything from the other side means it's still alive.
+                heartbeat.alive();
+            }
             if let Err(t) = self
                 .process_from_websocket(msg, &mut from_websocket, &mut send_response)
                 .await
@@ -423,30 +540,47 @@ impl Websocket {
                 // Deserialize the incoming wire message.
                 match msg {
                     tungstenite::Message::Binary(bytes) => {
-                        let msg = Self::deserialize_message(bytes)?;
-                        let (msg, resp) = match msg {
-                            WireMessage::Signal { data } => {
-                                (Self::deserialize_bytes(data)?, Respond::Signal)
+                        let received = bytes.len();
+                        let decompressed = match self.decompress(&bytes)? {
+                            std::borrow::Cow::Owned(frame) => Some(frame),
+                            std::borrow::Cow::Borrowed(_) => None,
+                        };
+                        let frame = decompressed.unwrap_or(bytes);
+                        let msg = Self::deserialize_message(&frame)?;
+                        if let Some(metrics) = &self.1 {
+                            metrics.message_received(msg.kind(), received);
+                        }
+                        let (channel, (msg, resp)) = match msg {
+                            WireMessageRef::Signal { channel, data } => {
+                                let data = data_range(&frame, data);
+                                (channel, (Self::take_data(frame, data), Respond::Signal))
                             }
-                            WireMessage::Request { data, id } => Self::handle_incoming_request(
-                                send_response,
-                                Self::deserialize_bytes(data)?,
+                            WireMessageRef::Request { id, channel, data } => {
+                                let data = data_range(&frame, data);
+                                (
+                                    channel,
+                                    Self::handle_incoming_request(
+                                        send_response,
+                                        Self::take_data(frame, data),
+                                        id,
+                                    ),
+                                )
+                            }
+                            WireMessageRef::Response {
                                 id,
-                            ),
-                            WireMessage::Response {
                                 data: Some(data),
-                                id,
                             } => {
+                                let data = data_range(&frame, data);
                                 // Send this response to the WebsocketSender who
                                 // made the original request.
                                 return self
                                     .handle_incoming_response(
-                                        Some(Self::deserialize_bytes(data)?),
+                                        Some(Self::take_data(frame, data)),
                                         id,
                                     )
                                     .await;
                             }
-                            WireMessage::Response { data: None, id } => {
+                            WireMessageRef::Response { id, data: None } => {
                                 tracing::trace!(canceled = ?id);
                                 // A response that has been canceled.
                                 // This means the other sides receiver has shutdown.
@@ -454,6 +588,10 @@ impl Websocket {
                             }
                         };
 
+                        if channel != DEFAULT_CHANNEL {
+                            return self.handle_channel_message(channel, msg, resp).await;
+                        }
+
                         // Forward the incoming message to the WebsocketReceiver.
                         if from_websocket
                             .send(IncomingMessage::Msg(msg, resp))
@@ -491,6 +629,11 @@ impl Websocket {
                         send_response.send(OutgoingMessage::Pong(data)).await.ok();
                         Task::cont()
                     }
+                    tungstenite::Message::Pong(_) => {
+                        // An answer to one of our keep-alive pings, which
+                        // already counts as activity on the connection.
+                        Task::cont()
+                    }
                     m => {
                         // Received a text message which we don't support.
                         tracing::error!("Websocket: Bad message type {:?}", m);
@@ -530,7 +673,7 @@ impl Websocket {
             // Callback to respond to the request
             move |msg| {
                 async move {
-                    let msg = OutgoingMessage::Response(Some(msg), id);
+                    let msg = OutgoingMessage::Response(Some(sb_to_bytes(msg)), id);
 
                     // Send the response to the to_socket task
                     send_response
@@ -551,10 +694,103 @@ impl Websocket {
         (msg, resp)
     }
 
+    /// Forward a message coming in from the network to the
+    /// receiver for its channel.
+    /// Messages for channels that aren't open are dropped which
+    /// cancels any request. Unlike the default channel this doesn't
+    /// shutdown the connection.
+    async fn handle_channel_message(
+        &self,
+        channel: u32,
+        msg: SerializedBytes,
+        resp: Respond,
+    ) -> Loop<()> {
+        // If the actor has closed we can't find the channel.
+        if !self.0.is_active() {
+            tracing::error!("Actor is closed");
+            return Task::exit();
+        }
+        let tx_from_websocket = match self
+            .0
+            .invoke(move |state| GhostResult::Ok(state.channels.get(&channel).cloned()))
+            .await
+        {
+            Ok(tx) => tx,
+            Err(e) => {
+                // Failed to find the channel so something is
+                // wrong with the actor and we should shutdown.
+                tracing::error!(?e);
+                return Task::exit();
+            }
+        };
+        match tx_from_websocket {
+            Some(tx) => {
+                if tx.send(IncomingMessage::Msg(msg, resp)).await.is_err() {
+                    // The receiver for this channel has been dropped
+                    // so close the channel.
+                    tracing::warn!(channel, "Websocket: Received message for closed channel");
+                    self.0
+                        .invoke(move |state| {
+                            state.channels.retain(|_, tx| !tx.is_closed());
+                            GhostResult::Ok(())
+                        })
+                        .await
+                        .ok();
+                }
+            }
+            None => {
+                tracing::warn!(
+                    channel,
+                    "Websocket: Received message for channel that isn't open"
+                );
+            }
+        }
+        Task::cont()
+    }
+
+    /// Register the receiver for a channel opened by the application.
+    async fn handle_open_channel(
+        &self,
+        channel: u32,
+        tx_from_websocket: TxFromWebsocket,
+        tx_opened: TxChannelOpened,
+    ) -> Loop<()> {
+        // If the actor has closed we can't open the channel.
+        if !self.0.is_active() {
+            tracing::error!("Actor is closed");
+            return Task::exit();
+        }
+        match self
+            .0
+            .invoke(move |state| {
+                // Channels can be opened again once their receiver is dropped.
+                state.channels.retain(|_, tx| !tx.is_closed());
+                let opened = !state.channels.contains_key(&channel);
+                if opened {
+                    state.channels.insert(channel, tx_from_websocket);
+                }
+                GhostResult::Ok(opened)
+            })
+            .await
+        {
+            Ok(opened) => {
+                tx_opened.send(opened).ok();
+                Task::cont()
+            }
+            Err(e) => {
+                // Failed to open the channel, something is
+                // wrong with the actor and we should shutdown.
+                tracing::error!(?e);
+                Task::exit()
+            }
+        }
+    }
+
     /// Handle a request going out to the network.
     async fn handle_outgoing_request(
         &self,
-        msg: SerializedBytes,
+        channel: u32,
+        data: Bytes,
         register_response: RegisterResponse,
         tx_stale_request: TxStaleRequest,
     ) -> Loop<WireMessage> {
@@ -586,8 +822,7 @@ impl Websocket {
                 Err(task) => return Err(task),
             }
         }
-        let data = UnsafeBytes::from(msg).into();
-        Ok(WireMessage::Request { data, id })
+        Ok(WireMessage::Request { id, channel, data })
     }
 
     /// Handle a request that has gone stale.
@@ -679,42 +914,43 @@ impl Websocket {
     /// Try to serialize the wire message and continue to next
     /// message if failure.
     fn serialize_msg(msg: WireMessage) -> Loop<tungstenite::Message> {
-        let msg: SerializedBytes = match msg.try_into() {
-            Ok(msg) => msg,
-            Err(e) => {
-                tracing::error!("Websocket: Message failed to serialize {:?}", e);
-                // Should not kill the websocket just because a single message
-                // failed serialization.
-                return Task::cont();
-            }
-        };
-        let bytes: Vec<u8> = UnsafeBytes::from(msg).into();
+        // Size the buffer up front so the payload is copied into
+        // the frame once instead of every time the buffer grows.
+        let mut bytes = Vec::with_capacity(msg.data_len() + WIRE_MESSAGE_OVERHEAD);
+        let mut se = rmp_serde::encode::Serializer::new(&mut bytes)
+            .with_struct_map()
+            .with_string_variants();
+        if let Err(e) = msg.serialize(&mut se) {
+            tracing::error!("Websocket: Message failed to serialize {:?}", e);
+            // Should not kill the websocket just because a single message
+            // failed serialization.
+            return Task::cont();
+        }
 
         let msg = tungstenite::Message::Binary(bytes);
         Ok(msg)
     }
 
-    /// Try to deserialize the wire message and continue to next
+    /// Try to decompress the frame and continue to next
     /// message if failure.
-    fn deserialize_message(bytes: Vec<u8>) -> Loop<WireMessage> {
-        match SerializedBytes::try_from(UnsafeBytes::from(bytes))
-            .map_err(WebsocketError::from)
-            .and_then(|sb| Ok(WireMessage::try_from(sb)?))
-        {
-            Ok(msg) => Ok(msg),
+    fn decompress<'a>(&self, bytes: &'a [u8]) -> Loop<std::borrow::Cow<'a, [u8]>> {
+        match self.2.decompress(bytes) {
+            Ok(frame) => Ok(frame),
             Err(e) => {
-                tracing::error!("Websocket failed to deserialize {:?}", e,);
+                tracing::error!("Websocket failed to decompress {:?}", e);
                 // Should not kill the websocket just because a single message
-                // failed serialization.
+                // failed decompression.
                 Task::cont()
             }
         }
     }
-    /// Try to deserialize the data and continue to next
+
+    /// Try to deserialize the wire message and continue to next
     /// message if failure.
-    fn deserialize_bytes(data: Vec<u8>) -> Loop<SerializedBytes> {
-        let msg: Result<SerializedBytes, _> = UnsafeBytes::from(data).try_into();
-        match msg {
+    /// The data is borrowed from the frame so it can be taken
+    /// out of the frame without copying.
+    fn deserialize_message(bytes: &[u8]) -> Loop<WireMessageRef<'_>> {
+        match holochain_serialized_bytes::decode(bytes).map_err(WebsocketError::from) {
             Ok(msg) => Ok(msg),
             Err(e) => {
                 tracing::error!("Websocket failed to deserialize {:?}", e,);
@@ -724,13 +960,101 @@ impl Websocket {
             }
         }
     }
+    /// Move the data out of the frame it was received in.
+    /// The frame's buffer is reused for the message so the
+    /// payload is never copied into a new allocation.
+    fn take_data(mut frame: Vec<u8>, data: std::ops::Range<usize>) -> SerializedBytes {
+        frame.truncate(data.end);
+        frame.drain(..data.start);
+        UnsafeBytes::from(frame).into()
+    }
+}
+
+impl Heartbeat {
+    fn new(config: &WebsocketConfig) -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(config.ping_interval_s as u64),
+            missed: Arc::new(AtomicUsize::new(0)),
+            max_missed: config.max_missed_pongs,
+        }
+    }
+
+    /// The interval to send pings on if pings are enabled.
+    fn ping_interval(&self) -> Option<tokio::time::Interval> {
+        if self.interval.is_zero() {
+            return None;
+        }
+        let mut interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + self.interval, self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Some(interval)
+    }
+
+    /// Wait for the next ping or forever if pings are disabled.
+    async fn tick(interval: &mut Option<tokio::time::Interval>) {
+        match interval {
+            Some(interval) => {
+                interval.tick().await;
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// We have heard from the other side.
+    fn alive(&self) {
+        self.missed.store(0, Ordering::Relaxed);
+    }
+
+    /// Send a ping unless too many have gone unanswered,
+    /// in which case the connection is dead and we exit.
+    async fn ping(
+        &self,
+        to_socket: &mut std::pin::Pin<
+            &mut impl futures::sink::Sink<tungstenite::Message, Error = tungstenite::error::Error>,
+        >,
+    ) -> Loop<()> {
+        let missed = self.missed.fetch_add(1, Ordering::Relaxed);
+        if missed >= self.max_missed {
+            tracing::warn!(
+                missed,
+                "Websocket: Other side stopped responding to pings, closing connection"
+            );
+            return Task::exit_now();
+        }
+        match to_socket.send(tungstenite::Message::Ping(Vec::new())).await {
+            Ok(_) => Task::cont(),
+            Err(tungstenite::Error::ConnectionClosed) => Task::exit_now(),
+            Err(e) => {
+                tracing::error!(to_socket_error = ?e);
+                Task::exit()
+            }
+        }
+    }
+}
+
+/// Where the data borrowed from a frame is in that frame.
+fn data_range(frame: &[u8], data: &[u8]) -> std::ops::Range<usize> {
+    let start = data.as_ptr() as usize - frame.as_ptr() as usize;
+    start..start + data.len()
+}
+
+impl<'a> WireMessageRef<'a> {
+    /// The kind of this message.
+    fn kind(&self) -> WebsocketMessageKind {
+        match self {
+            WireMessageRef::Signal { .. } => WebsocketMessageKind::Signal,
+            WireMessageRef::Request { .. } => WebsocketMessageKind::Request,
+            WireMessageRef::Response { .. } => WebsocketMessageKind::Response,
+        }
+    }
 }
 
 impl ResponseTracker {
-    fn new() -> Self {
+    fn new(metrics: Option<Arc<ConnectionMetrics>>) -> Self {
         Self {
             responses: HashMap::new(),
             index: 0,
+            metrics,
         }
     }
 
@@ -741,12 +1065,23 @@ impl ResponseTracker {
         self.index += 1;
 
         self.responses.insert(index, response);
+        self.report_pending();
         index
     }
 
     /// Retrieve the response at this id.
     fn pop(&mut self, id: u64) -> Option<RegisterResponse> {
-        self.responses.remove(&id)
+        let response = self.responses.remove(&id);
+        if response.is_some() {
+            self.report_pending();
+        }
+        response
+    }
+
+    fn report_pending(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.pending_requests(self.responses.len());
+        }
     }
 
     /// Show outstanding responses.
@@ -816,4 +1151,103 @@ mod tests {
             .unwrap();
         sjh.await.unwrap();
     }
+
+    #[test]
+    fn test_wire_message_round_trip() {
+        let data = Bytes::from(vec![7u8; 1 << 20]);
+        let msgs = vec![
+            WireMessage::Signal {
+                channel: 0,
+                data: data.clone(),
+            },
+            WireMessage::Request {
+                id: 3,
+                channel: 2,
+                data: data.clone(),
+            },
+            WireMessage::Response {
+                id: 4,
+                data: Some(data.clone()),
+            },
+            WireMessage::Response { id: 5, data: None },
+        ];
+        for msg in msgs {
+            let frame = match Websocket::serialize_msg(msg).ok().unwrap() {
+                tungstenite::Message::Binary(frame) => frame,
+                m => panic!("unexpected message {:?}", m),
+            };
+            // The frame never needs to grow past the initial allocation.
+            assert!(frame.capacity() <= data.len() + WIRE_MESSAGE_OVERHEAD);
+
+            // The wire format is the same as the serialized bytes encoding.
+            let wire: WireMessage = SerializedBytes::from(UnsafeBytes::from(frame.clone()))
+                .try_into()
+                .unwrap();
+
+            match (wire, Websocket::deserialize_message(&frame).ok().unwrap()) {
+                (
+                    WireMessage::Signal {
+                        channel: 0,
+                        data: a,
+                    },
+                    WireMessageRef::Signal {
+                        channel: 0,
+                        data: b,
+                    },
+                ) => {
+                    assert_eq!(a, data);
+                    assert_eq!(b, &data[..]);
+                }
+                (
+                    WireMessage::Request {
+                        id: 3,
+                        channel: 2,
+                        data: a,
+                    },
+                    WireMessageRef::Request {
+                        id: 3,
+                        channel: 2,
+                        data: b,
+                    },
+                ) => {
+                    assert_eq!(a, data);
+                    assert_eq!(b, &data[..]);
+                }
+                (
+                    WireMessage::Response { id: 4, data: a },
+                    WireMessageRef::Response { id: 4, data: b },
+                ) => {
+                    assert_eq!(a, Some(data.clone()));
+                    assert_eq!(b, Some(&data[..]));
+                }
+                (
+                    WireMessage::Response { id: 5, data: None },
+                    WireMessageRef::Response { id: 5, data: None },
+                ) => (),
+                m => panic!("unexpected messages {:?}", m),
+            }
+        }
+    }
+
+    #[test]
+    fn test_incoming_data_reuses_frame() {
+        let data = Bytes::from(vec![7u8; 1 << 20]);
+        let msg = WireMessage::Request {
+            id: 3,
+            channel: 0,
+            data: data.clone(),
+        };
+        let frame = match Websocket::serialize_msg(msg).ok().unwrap() {
+            tungstenite::Message::Binary(frame) => frame,
+            m => panic!("unexpected message {:?}", m),
+        };
+        let buffer = frame.as_ptr();
+        let range = match Websocket::deserialize_message(&frame).ok().unwrap() {
+            WireMessageRef::Request { data, .. } => data_range(&frame, data),
+            m => panic!("unexpected message {:?}", m),
+        };
+        let msg = Websocket::take_data(frame, range);
+        assert_eq!(msg.bytes()[..], data[..]);
+        assert_eq!(msg.bytes().as_ptr(), buffer);
+    }
 }
@@ -0,0 +1,184 @@
+//! Opt-in checks on incoming connections before they are accepted.
+//!
+//! tungstenite only offers a synchronous callback during the handshake,
+//! so when a hook is set the listener reads the http upgrade request
+//! itself, runs the hook and then hands the request on to tungstenite.
+
+use std::future::Future;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use bytes::BytesMut;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tungstenite::http::header::HeaderName;
+use tungstenite::http::header::HeaderValue;
+use tungstenite::http::HeaderMap;
+use tungstenite::http::StatusCode;
+use tungstenite::http::Uri;
+use url2::Url2;
+
+use crate::util::RawSocket;
+use crate::WebsocketError;
+use crate::WebsocketResult;
+
+/// The largest handshake request that will be read.
+const MAX_REQUEST_LEN: usize = 16 * 1024;
+
+/// The most headers a handshake request can have.
+const MAX_HEADERS: usize = 64;
+
+/// The handshake of an incoming connection, passed to an [`OnConnect`] hook.
+#[derive(Debug, Clone)]
+pub struct HandshakeInfo {
+    /// The url of the connecting peer.
+    /// For unix domain sockets this is the url of the socket path.
+    pub remote_addr: Url2,
+    /// The path and query the client connected to, e.g. `/?token=abc`.
+    pub uri: Uri,
+    /// The headers of the upgrade request.
+    pub headers: HeaderMap,
+}
+
+/// Returned by an [`OnConnect`] hook to refuse a connection.
+///
+/// The client receives this as an http error response
+/// in place of the websocket upgrade.
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    /// The http status sent to the client.
+    pub status: StatusCode,
+    /// Sent to the client as the body of the response.
+    pub reason: String,
+}
+
+impl Rejection {
+    /// Reject with any status.
+    pub fn new(status: StatusCode, reason: impl Into<String>) -> Self {
+        Self {
+            status,
+            reason: reason.into(),
+        }
+    }
+
+    /// Reject with `401 Unauthorized`.
+    pub fn unauthorized(reason: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, reason)
+    }
+
+    /// Reject with `403 Forbidden`.
+    pub fn forbidden(reason: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, reason)
+    }
+}
+
+type OnConnectFn =
+    dyn Fn(HandshakeInfo) -> BoxFuture<'static, Result<(), Rejection>> + Send + Sync + 'static;
+
+/// An async hook that runs for every incoming connection
+/// before its websocket handshake completes.
+///
+/// Set with [`WebsocketConfig::on_connect`](crate::WebsocketConfig::on_connect).
+/// The hook can look at the remote address, the path and query and
+/// the headers of the request, e.g. to require a token.
+/// If it returns a [`Rejection`] the connection is closed and the
+/// listener yields [`WebsocketError::ConnectionRejected`].
+#[derive(Clone)]
+pub struct OnConnect(Arc<OnConnectFn>);
+
+impl OnConnect {
+    /// Create a hook from an async function.
+    pub fn new<F, Fut>(f: F) -> Self
+    where
+        F: Fn(HandshakeInfo) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Rejection>> + Send + 'static,
+    {
+        Self(Arc::new(move |info| f(info).boxed()))
+    }
+}
+
+impl std::fmt::Debug for OnConnect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OnConnect")
+    }
+}
+
+/// Run the hook for an incoming connection.
+/// The request is left in the socket for the websocket handshake.
+pub(crate) async fn check_connection(
+    on_connect: &OnConnect,
+    socket: &mut RawSocket,
+    scheme: &str,
+) -> WebsocketResult<()> {
+    let remote_addr = socket.peer_url(scheme)?;
+    let (uri, headers, request) = read_request(socket).await?;
+    socket.unread(request);
+
+    let info = HandshakeInfo {
+        remote_addr,
+        uri,
+        headers,
+    };
+    let rejection = match (on_connect.0)(info).await {
+        Ok(()) => return Ok(()),
+        Err(rejection) => rejection,
+    };
+    tracing::debug!(status = %rejection.status, reason = %rejection.reason, "Rejected connection");
+
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nConnection: close\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+        rejection.status.as_u16(),
+        rejection.status.canonical_reason().unwrap_or_default(),
+        rejection.reason.len(),
+        rejection.reason,
+    );
+    // The connection is dropped whether or not the client gets the response.
+    socket.write_all(response.as_bytes()).await.ok();
+    socket.shutdown().await.ok();
+    Err(WebsocketError::ConnectionRejected(rejection))
+}
+
+/// Read the http request that starts the websocket handshake.
+/// Returns its uri and headers along with all the bytes read.
+async fn read_request(socket: &mut RawSocket) -> WebsocketResult<(Uri, HeaderMap, Bytes)> {
+    let mut buf = BytesMut::with_capacity(1024);
+    loop {
+        if socket.read_buf(&mut buf).await? == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "connection closed during handshake",
+            )
+            .into());
+        }
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut request = httparse::Request::new(&mut headers);
+        let parsed = match request.parse(&buf).map_err(invalid)? {
+            httparse::Status::Complete(_) => {
+                let uri = request.path.unwrap_or("/").parse().map_err(invalid)?;
+                let mut header_map = HeaderMap::new();
+                for header in request.headers.iter() {
+                    header_map.append(
+                        HeaderName::from_bytes(header.name.as_bytes()).map_err(invalid)?,
+                        HeaderValue::from_bytes(header.value).map_err(invalid)?,
+                    );
+                }
+                Some((uri, header_map))
+            }
+            httparse::Status::Partial if buf.len() >= MAX_REQUEST_LEN => {
+                return Err(invalid("handshake request is too large"));
+            }
+            httparse::Status::Partial => None,
+        };
+        if let Some((uri, headers)) = parsed {
+            return Ok((uri, headers, buf.freeze()));
+        }
+    }
+}
+
+fn invalid(e: impl ToString) -> WebsocketError {
+    Error::new(ErrorKind::InvalidData, e.to_string()).into()
+}
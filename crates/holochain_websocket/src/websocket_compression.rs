@@ -0,0 +1,159 @@
+//! Application level compression of large wire messages.
+//!
+//! tungstenite doesn't support the permessage-deflate extension so
+//! instead both sides agree on compression with a header during the
+//! handshake and large frames are deflated before they are sent.
+//! A compressed frame starts with a byte that can never start a message
+//! pack encoded [`WireMessage`](crate::WireMessage) so the receiving
+//! side can tell them apart from uncompressed frames.
+
+use std::borrow::Cow;
+use std::io::Read;
+use std::io::Write;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use tungstenite::handshake::client::Request;
+use tungstenite::http::HeaderValue;
+
+use crate::WebsocketConfig;
+
+/// Header used to agree on compression during the handshake.
+const COMPRESSION_HEADER: &str = "x-holochain-compression";
+
+/// The only compression currently supported.
+const DEFLATE: &str = "deflate";
+
+/// Marks a compressed frame.
+/// This byte is never used by message pack.
+const COMPRESSED_FRAME: u8 = 0xc1;
+
+/// Ask the server for compression if it's enabled on this side.
+pub(crate) fn request_compression(request: &mut Request, threshold: Option<usize>) {
+    if threshold.is_some() {
+        request
+            .headers_mut()
+            .insert(COMPRESSION_HEADER, HeaderValue::from_static(DEFLATE));
+    }
+}
+
+/// Check if the other side asked for, or agreed to, compression.
+pub(crate) fn accepts_compression(headers: &tungstenite::http::HeaderMap) -> bool {
+    headers
+        .get(COMPRESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|v| v.trim() == DEFLATE))
+        .unwrap_or(false)
+}
+
+/// Agree to compression in the server's handshake response.
+pub(crate) fn accept_compression(headers: &mut tungstenite::http::HeaderMap) {
+    headers.insert(COMPRESSION_HEADER, HeaderValue::from_static(DEFLATE));
+}
+
+#[derive(Debug, Clone, Copy)]
+/// How frames are compressed on a single connection.
+pub(crate) struct FrameCompression {
+    /// Frames larger than this are compressed.
+    /// None if compression wasn't agreed on.
+    threshold: Option<usize>,
+    /// Largest frame we will decompress.
+    max_size: usize,
+}
+
+impl FrameCompression {
+    /// Compress frames if both sides agreed on it.
+    pub(crate) fn new(config: &WebsocketConfig, agreed: bool) -> Self {
+        Self {
+            threshold: config.compression_threshold.filter(|_| agreed),
+            max_size: config.max_message_size,
+        }
+    }
+
+    /// Compress the frame if compression is on and it's large enough.
+    pub(crate) fn compress(&self, frame: Vec<u8>) -> Vec<u8> {
+        match self.threshold {
+            Some(threshold) => compress(frame, threshold),
+            None => frame,
+        }
+    }
+
+    /// Decompress the frame if it was compressed.
+    /// Compressed frames are accepted even if we didn't ask for them.
+    pub(crate) fn decompress<'a>(&self, frame: &'a [u8]) -> std::io::Result<Cow<'a, [u8]>> {
+        decompress(frame, self.max_size)
+    }
+}
+
+/// Compress the frame if it's larger than the threshold.
+/// Frames that don't get smaller are sent as is.
+fn compress(frame: Vec<u8>, threshold: usize) -> Vec<u8> {
+    if frame.len() <= threshold {
+        return frame;
+    }
+    let mut out = Vec::with_capacity(frame.len() / 2);
+    out.push(COMPRESSED_FRAME);
+    let mut encoder = DeflateEncoder::new(out, Compression::fast());
+    match encoder.write_all(&frame).and_then(|_| encoder.finish()) {
+        Ok(out) if out.len() < frame.len() => out,
+        Ok(_) => frame,
+        Err(e) => {
+            tracing::warn!("Websocket: Failed to compress message {:?}", e);
+            frame
+        }
+    }
+}
+
+/// Decompress the frame if it was compressed.
+/// Fails if the frame is corrupt or would decompress to more than `max_size`.
+fn decompress(frame: &[u8], max_size: usize) -> std::io::Result<Cow<'_, [u8]>> {
+    match frame.split_first() {
+        Some((&COMPRESSED_FRAME, compressed)) => {
+            let mut out = Vec::new();
+            DeflateDecoder::new(compressed)
+                .take(max_size as u64 + 1)
+                .read_to_end(&mut out)?;
+            if out.len() > max_size {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "decompressed message is larger than the max message size of {}",
+                        max_size
+                    ),
+                ));
+            }
+            Ok(Cow::Owned(out))
+        }
+        _ => Ok(Cow::Borrowed(frame)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compresses_above_threshold() {
+        let frame = vec![1u8; 1000];
+        assert_eq!(compress(frame.clone(), 1000), frame);
+
+        let compressed = compress(frame.clone(), 999);
+        assert_eq!(compressed[0], COMPRESSED_FRAME);
+        assert!(compressed.len() < frame.len());
+        assert_eq!(decompress(&compressed, 1000).unwrap(), &frame[..]);
+        assert!(decompress(&compressed, 999).is_err());
+
+        // Uncompressed frames pass through.
+        assert!(matches!(decompress(&frame, 0).unwrap(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn negotiates_with_header() {
+        let mut request = Request::new(());
+        request_compression(&mut request, None);
+        assert!(!accepts_compression(request.headers()));
+        request_compression(&mut request, Some(0));
+        assert!(accepts_compression(request.headers()));
+    }
+}
@@ -1,5 +1,13 @@
 //! defines a builder-style config struct for setting up websockets
 
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::HandshakeInfo;
+use crate::OnConnect;
+use crate::Rejection;
+use crate::WebsocketMetrics;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -16,6 +24,12 @@ pub struct WebsocketConfig {
     pub tcp_keepalive_s: usize,
 
     /// How many items are allowed in the outgoing queue. [default = 10]
+    ///
+    /// When the queue is full [`WebsocketSender::signal`](crate::WebsocketSender::signal)
+    /// and [`WebsocketSender::request`](crate::WebsocketSender::request) wait for
+    /// space, so a slow peer slows down the sender instead of growing the queue.
+    /// Use [`WebsocketSender::try_signal`](crate::WebsocketSender::try_signal)
+    /// to drop messages instead.
     pub max_send_queue: usize,
 
     /// Maximum total message size of a websocket message. [default = 64M]
@@ -26,6 +40,41 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// Seconds between keep-alive pings sent to the other side.
+    /// Set to 0 to disable pings. [default = 10]
+    pub ping_interval_s: usize,
+
+    /// How many pings can go unanswered before the connection is
+    /// treated as dead and closed. [default = 3]
+    pub max_missed_pongs: usize,
+
+    /// Where to report connection level metrics. [default = None]
+    pub metrics: Option<Arc<dyn WebsocketMetrics>>,
+
+    /// Compress messages larger than this many bytes.
+    /// Compression is only used if both sides of the connection enable it,
+    /// which they agree on when connecting. [default = None]
+    pub compression_threshold: Option<usize>,
+
+    /// Milliseconds to wait before the first reconnect attempt of a
+    /// [`connect_persistent`](crate::connect_persistent) connection.
+    /// The wait doubles after every failed attempt. [default = 100]
+    pub reconnect_initial_backoff_ms: u64,
+
+    /// The longest wait between reconnect attempts in milliseconds.
+    /// [default = 10000]
+    pub reconnect_max_backoff_ms: u64,
+
+    /// How many outgoing messages are held while reconnecting.
+    /// Once full, signals are dropped and requests fail with
+    /// [`WebsocketError::QueueFull`](crate::WebsocketError::QueueFull).
+    /// [default = 100]
+    pub max_reconnect_queue: usize,
+
+    /// Checks every incoming connection before it is accepted by a
+    /// [`WebsocketListener`](crate::WebsocketListener). [default = None]
+    pub on_connect: Option<OnConnect>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +87,14 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            ping_interval_s: 10,
+            max_missed_pongs: 3,
+            metrics: None,
+            compression_threshold: None,
+            reconnect_initial_backoff_ms: 100,
+            reconnect_max_backoff_ms: 10_000,
+            max_reconnect_queue: 100,
+            on_connect: None,
         }
     }
 }
@@ -78,6 +135,53 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn ping_interval_s(mut self, s: usize) -> Self {
+        self.ping_interval_s = s;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn max_missed_pongs(mut self, max: usize) -> Self {
+        self.max_missed_pongs = max;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn with_metrics(mut self, metrics: Arc<dyn WebsocketMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn compression_threshold(mut self, bytes: usize) -> Self {
+        self.compression_threshold = Some(bytes);
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn reconnect_backoff_ms(mut self, initial: u64, max: u64) -> Self {
+        self.reconnect_initial_backoff_ms = initial;
+        self.reconnect_max_backoff_ms = max;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn max_reconnect_queue(mut self, max: usize) -> Self {
+        self.max_reconnect_queue = max;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn on_connect<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(HandshakeInfo) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Rejection>> + Send + 'static,
+    {
+        self.on_connect = Some(OnConnect::new(f));
+        self
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
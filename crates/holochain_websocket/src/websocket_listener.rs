@@ -8,12 +8,19 @@ use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::handshake::server::Request;
+use tungstenite::handshake::server::Response;
 
 use url2::Url2;
 
 use crate::util::addr_to_url;
 use crate::util::url_to_addr;
+use crate::util::RawSocket;
+#[cfg(unix)]
+use crate::util::{path_to_url, url_to_path};
 use crate::websocket::Websocket;
+use crate::websocket_auth;
+use crate::websocket_compression;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
 use crate::WebsocketReceiver;
@@ -86,6 +93,10 @@ pub type ListenerStream = BoxStream<'static, ListenerItem>;
 
 impl WebsocketListener {
     /// Bind to a socket to accept incoming connections.
+    ///
+    /// On unix a `unix:///path/to/sock` url binds a unix domain socket.
+    /// A socket file left at that path by a listener which is no longer
+    /// running is replaced.
     pub async fn bind(addr: Url2, config: Arc<WebsocketConfig>) -> WebsocketResult<Self> {
         let (handle, stream) = Self::bind_with_handle(addr, config).await?;
         Ok(Self {
@@ -191,18 +202,7 @@ async fn websocket_bind(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
-    let addr = url_to_addr(&addr, config.scheme).await?;
-    let socket = match &addr {
-        SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
-        SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
-    }
-    .reuse_address(true)?
-    .bind(addr)?
-    .listen(config.max_pending_connections as i32)?;
-    socket.set_nonblocking(true)?;
-    let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
-    let listener = tokio::net::TcpListener::from_std(socket)?;
-    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let (local_addr, listener_stream) = bind_raw(&addr, &config).await?;
 
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
@@ -227,10 +227,58 @@ async fn websocket_bind(
     Ok((listener_handle, stream))
 }
 
+/// Bind the socket the listener accepts connections on.
+/// Returns the url of the bound socket and a stream of incoming connections.
+async fn bind_raw(
+    addr: &Url2,
+    config: &WebsocketConfig,
+) -> WebsocketResult<(Url2, BoxStream<'static, std::io::Result<RawSocket>>)> {
+    #[cfg(unix)]
+    if let Some(path) = url_to_path(addr) {
+        remove_stale_socket(&path)?;
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        let local_addr = path_to_url(&path);
+        let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener)
+            .map_ok(move |socket| RawSocket::unix(socket, path.clone()));
+        return Ok((local_addr, listener_stream.boxed()));
+    }
+    let addr = url_to_addr(addr, config.scheme).await?;
+    let socket = match &addr {
+        SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
+        SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
+    }
+    .reuse_address(true)?
+    .bind(addr)?
+    .listen(config.max_pending_connections as i32)?;
+    socket.set_nonblocking(true)?;
+    let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
+    let listener = tokio::net::TcpListener::from_std(socket)?;
+    let listener_stream =
+        tokio_stream::wrappers::TcpListenerStream::new(listener).map_ok(RawSocket::tcp);
+    Ok((local_addr, listener_stream.boxed()))
+}
+
+/// Remove a socket file left behind by a listener that is no longer running
+/// so its path can be bound again. A socket that is still accepting
+/// connections is left alone and binding to it will fail.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => {
+            if std::os::unix::net::UnixStream::connect(path).is_err() {
+                std::fs::remove_file(path)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    mut socket: RawSocket,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,10 +287,26 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %socket.peer_url(config.scheme)?,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
+    if let Some(on_connect) = &config.on_connect {
+        websocket_auth::check_connection(on_connect, &mut socket, config.scheme).await?;
+    }
+    let mut compress = false;
+    // The error type is decided by tungstenite.
+    #[allow(clippy::result_large_err)]
+    let negotiate = |request: &Request, mut response: Response| {
+        if config.compression_threshold.is_some()
+            && websocket_compression::accepts_compression(request.headers())
+        {
+            websocket_compression::accept_compression(response.headers_mut());
+            compress = true;
+        }
+        Ok(response)
+    };
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
         socket,
+        negotiate,
         Some(tungstenite::protocol::WebSocketConfig {
             max_send_queue: Some(config.max_send_queue),
             max_message_size: Some(config.max_message_size),
@@ -253,5 +317,5 @@ async fn connect(
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, compress, valve)
 }
@@ -0,0 +1,93 @@
+//! Opt-in connection level metrics.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use url2::Url2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The kind of [`WireMessage`](crate::WireMessage) sent or received.
+pub enum WebsocketMessageKind {
+    /// A message without a response.
+    Signal,
+    /// A request that requires a response.
+    Request,
+    /// The response to a request.
+    Response,
+}
+
+/// Receives metrics from websocket connections.
+///
+/// Set with [`WebsocketConfig::with_metrics`](crate::WebsocketConfig::with_metrics).
+/// Every connection created with that config reports to the same
+/// implementation, so the connection's remote address is passed
+/// to every call. The address includes a unique id fragment so
+/// connections from the same host can be told apart.
+///
+/// All methods default to doing nothing so you only need to
+/// implement the ones you're interested in.
+/// They are called from the connection tasks, so they should not block.
+pub trait WebsocketMetrics: 'static + Send + Sync + std::fmt::Debug {
+    /// A connection was opened.
+    fn connection_opened(&self, _remote_addr: &Url2) {}
+
+    /// A connection was closed after being open for `duration`.
+    fn connection_closed(&self, _remote_addr: &Url2, _duration: Duration) {}
+
+    /// A message was written to the socket.
+    /// `bytes` is the size of the encoded frame.
+    fn message_sent(&self, _remote_addr: &Url2, _kind: WebsocketMessageKind, _bytes: usize) {}
+
+    /// A message was read from the socket.
+    /// `bytes` is the size of the encoded frame.
+    fn message_received(&self, _remote_addr: &Url2, _kind: WebsocketMessageKind, _bytes: usize) {}
+
+    /// The number of outgoing requests waiting for a response changed.
+    fn pending_requests(&self, _remote_addr: &Url2, _count: usize) {}
+}
+
+#[derive(Debug)]
+/// Reports metrics for a single connection.
+/// The connection is reported closed when this is dropped.
+pub(crate) struct ConnectionMetrics {
+    metrics: Arc<dyn WebsocketMetrics>,
+    remote_addr: Url2,
+    opened: Instant,
+}
+
+impl ConnectionMetrics {
+    /// Report a newly opened connection if metrics are enabled.
+    pub(crate) fn open(
+        metrics: Option<&Arc<dyn WebsocketMetrics>>,
+        remote_addr: &Url2,
+    ) -> Option<Arc<Self>> {
+        let metrics = metrics?.clone();
+        metrics.connection_opened(remote_addr);
+        Some(Arc::new(Self {
+            metrics,
+            remote_addr: remote_addr.clone(),
+            opened: Instant::now(),
+        }))
+    }
+
+    pub(crate) fn message_sent(&self, kind: WebsocketMessageKind, bytes: usize) {
+        self.metrics.message_sent(&self.remote_addr, kind, bytes);
+    }
+
+    pub(crate) fn message_received(&self, kind: WebsocketMessageKind, bytes: usize) {
+        self.metrics
+            .message_received(&self.remote_addr, kind, bytes);
+    }
+
+    pub(crate) fn pending_requests(&self, count: usize) {
+        self.metrics.pending_requests(&self.remote_addr, count);
+    }
+}
+
+impl Drop for ConnectionMetrics {
+    fn drop(&mut self) {
+        self.metrics
+            .connection_closed(&self.remote_addr, self.opened.elapsed());
+    }
+}
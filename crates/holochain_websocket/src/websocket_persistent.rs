@@ -0,0 +1,494 @@
+//! Client connections that reconnect when the socket drops.
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use holochain_serialized_bytes::{SerializedBytes, SerializedBytesError};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::instrument;
+use tracing::Instrument;
+use url2::Url2;
+
+use crate::connect;
+use crate::websocket_sender::sb_to_bytes;
+use crate::Respond;
+use crate::WebsocketConfig;
+use crate::WebsocketError;
+use crate::WebsocketReceiver;
+use crate::WebsocketResult;
+use crate::WebsocketSender;
+
+#[instrument(skip(config))]
+/// Create a client connection that reconnects when the socket drops.
+///
+/// The first connection attempt must succeed. After that, if the
+/// connection drops it is re-established with exponential backoff
+/// (see [`WebsocketConfig::reconnect_initial_backoff_ms`]) until every
+/// [`PersistentWebsocketSender`] and the [`PersistentWebsocketReceiver`]
+/// have been dropped.
+///
+/// Messages sent while reconnecting are held and sent once the connection
+/// is back, up to [`WebsocketConfig::max_reconnect_queue`].
+/// Requests that were already sent when the connection dropped fail with
+/// [`WebsocketError::Shutdown`] rather than being sent twice.
+///
+/// Reading the [`PersistentWebsocketReceiver`] never holds up sending.
+/// If it falls more than [`WebsocketConfig::max_send_queue`] events
+/// behind, incoming signals are dropped and incoming requests are
+/// canceled until it catches up.
+/// [`PersistentEvent::Disconnected`] and [`PersistentEvent::Reconnected`]
+/// are never dropped. If they don't fit they are held back, keeping only
+/// the latest state, and delivered once the receiver catches up.
+///
+/// # Example
+/// ```no_run
+/// use futures::stream::StreamExt;
+/// use holochain_websocket::*;
+/// use url2::url2;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (_send, mut recv) = connect_persistent(
+///         url2!("ws://127.0.0.1:12345"),
+///         std::sync::Arc::new(WebsocketConfig::default()),
+///     )
+///     .await
+///     .unwrap();
+///     while let Some(event) = recv.next().await {
+///         match event {
+///             PersistentEvent::Message(_msg, _resp) => (),
+///             PersistentEvent::Disconnected => eprintln!("Lost connection, reconnecting"),
+///             PersistentEvent::Reconnected => eprintln!("Reconnected"),
+///         }
+///     }
+/// }
+/// ```
+pub async fn connect_persistent(
+    url: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(PersistentWebsocketSender, PersistentWebsocketReceiver)> {
+    let connection = connect(url.clone(), config.clone()).await?;
+    let (tx_outgoing, rx_outgoing) = tokio::sync::mpsc::channel(config.max_send_queue);
+    let (tx_event, rx_event) = tokio::sync::mpsc::channel(config.max_send_queue);
+    let missed = Arc::new(std::sync::Mutex::new(MissedEvents::default()));
+    tokio::task::spawn(
+        Reconnect {
+            url,
+            config,
+            pending: VecDeque::new(),
+            rx_outgoing: Some(rx_outgoing),
+            tx_event,
+            missed: missed.clone(),
+        }
+        .run(connection)
+        .in_current_span(),
+    );
+    Ok((
+        PersistentWebsocketSender { tx_outgoing },
+        PersistentWebsocketReceiver {
+            rx_event: tokio_stream::wrappers::ReceiverStream::new(rx_event),
+            missed,
+        },
+    ))
+}
+
+/// Something that happened on a [`connect_persistent`] connection.
+pub enum PersistentEvent {
+    /// A signal or request from the other side.
+    Message(SerializedBytes, Respond),
+    /// The connection dropped and is being re-established.
+    Disconnected,
+    /// The connection was re-established.
+    Reconnected,
+}
+
+impl std::fmt::Debug for PersistentEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistentEvent::Message(msg, resp) => f
+                .debug_struct("Message")
+                .field("msg", msg)
+                .field("is_request", &resp.is_request())
+                .finish(),
+            PersistentEvent::Disconnected => write!(f, "Disconnected"),
+            PersistentEvent::Reconnected => write!(f, "Reconnected"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The sender half of a [`connect_persistent`] connection.
+///
+/// Works like [`WebsocketSender`] but keeps working across reconnects.
+pub struct PersistentWebsocketSender {
+    tx_outgoing: tokio::sync::mpsc::Sender<Outgoing>,
+}
+
+/// The receiver half of a [`connect_persistent`] connection.
+///
+/// The stream ends once the connection is closed for good.
+pub struct PersistentWebsocketReceiver {
+    rx_event: tokio_stream::wrappers::ReceiverStream<PersistentEvent>,
+    missed: Arc<std::sync::Mutex<MissedEvents>>,
+}
+
+#[derive(Debug, Default)]
+/// Connection events which didn't fit in the receiver's queue.
+/// Only the latest state is kept: a reconnect is forgotten if the
+/// connection drops again before the receiver catches up.
+struct MissedEvents {
+    disconnected: bool,
+    reconnected: bool,
+}
+
+impl MissedEvents {
+    fn is_empty(&self) -> bool {
+        !self.disconnected && !self.reconnected
+    }
+
+    fn add(&mut self, event: PersistentEvent) {
+        match event {
+            PersistentEvent::Disconnected => {
+                self.disconnected = true;
+                self.reconnected = false;
+            }
+            PersistentEvent::Reconnected => self.reconnected = true,
+            PersistentEvent::Message(_, _) => (),
+        }
+    }
+
+    fn take(&mut self) -> Option<PersistentEvent> {
+        if std::mem::take(&mut self.disconnected) {
+            Some(PersistentEvent::Disconnected)
+        } else if std::mem::take(&mut self.reconnected) {
+            Some(PersistentEvent::Reconnected)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A message waiting to be sent on the current connection.
+enum Outgoing {
+    Signal(Bytes),
+    Request(
+        Bytes,
+        tokio::sync::oneshot::Sender<WebsocketResult<SerializedBytes>>,
+    ),
+}
+
+impl PersistentWebsocketSender {
+    #[tracing::instrument(skip(self))]
+    /// Make a request for the other side to respond to.
+    /// See [`WebsocketSender::request_timeout`].
+    pub async fn request_timeout<I, O>(
+        &mut self,
+        msg: I,
+        timeout: std::time::Duration,
+    ) -> WebsocketResult<O>
+    where
+        I: std::fmt::Debug,
+        O: std::fmt::Debug,
+        WebsocketError: From<SerializedBytesError>,
+        I: Serialize,
+        O: DeserializeOwned,
+    {
+        match tokio::time::timeout(timeout, self.request(msg)).await {
+            Ok(r) => r,
+            Err(_) => Err(WebsocketError::RespTimeout),
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    /// Make a request for the other side to respond to.
+    /// If the connection is down the request is sent once it's back.
+    /// See [`WebsocketSender::request`].
+    pub async fn request<I, O>(&mut self, msg: I) -> WebsocketResult<O>
+    where
+        I: std::fmt::Debug,
+        O: std::fmt::Debug,
+        WebsocketError: From<SerializedBytesError>,
+        I: Serialize,
+        O: DeserializeOwned,
+    {
+        use holochain_serialized_bytes as hsb;
+        let (tx_resp, rx_resp) = tokio::sync::oneshot::channel();
+        let msg = Outgoing::Request(Bytes::from(hsb::encode(&msg)?), tx_resp);
+        self.tx_outgoing
+            .send(msg)
+            .await
+            .map_err(|_| WebsocketError::Shutdown)?;
+        let sb = rx_resp.await.map_err(|_| WebsocketError::Shutdown)??;
+        let resp: O = hsb::decode(&Vec::from(hsb::UnsafeBytes::from(sb)))?;
+        Ok(resp)
+    }
+
+    #[tracing::instrument(skip(self))]
+    /// Send a message to the other side that doesn't require a response.
+    /// If the connection is down the signal is sent once it's back.
+    /// See [`WebsocketSender::signal`].
+    pub async fn signal<I, E>(&mut self, msg: I) -> WebsocketResult<()>
+    where
+        I: std::fmt::Debug,
+        WebsocketError: From<E>,
+        SerializedBytes: TryFrom<I, Error = E>,
+    {
+        let msg = Outgoing::Signal(sb_to_bytes(msg.try_into()?));
+        self.tx_outgoing
+            .send(msg)
+            .await
+            .map_err(|_| WebsocketError::Shutdown)
+    }
+}
+
+impl futures::stream::Stream for PersistentWebsocketReceiver {
+    type Item = PersistentEvent;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        // Missed connection events come after the messages
+        // which were queued before them.
+        let poll = std::pin::Pin::new(&mut self.rx_event).poll_next(cx);
+        if let std::task::Poll::Ready(Some(event)) = poll {
+            return std::task::Poll::Ready(Some(event));
+        }
+        match self.missed.lock().expect("missed events lock").take() {
+            Some(event) => std::task::Poll::Ready(Some(event)),
+            None => poll,
+        }
+    }
+}
+
+/// Drives a persistent connection.
+struct Reconnect {
+    url: Url2,
+    config: Arc<WebsocketConfig>,
+    /// Messages held while reconnecting.
+    pending: VecDeque<Outgoing>,
+    /// None once every sender has been dropped.
+    rx_outgoing: Option<tokio::sync::mpsc::Receiver<Outgoing>>,
+    tx_event: tokio::sync::mpsc::Sender<PersistentEvent>,
+    /// Connection events waiting for room in the receiver's queue.
+    missed: Arc<std::sync::Mutex<MissedEvents>>,
+}
+
+impl Reconnect {
+    async fn run(mut self, connection: (WebsocketSender, WebsocketReceiver)) {
+        let mut connection = Some(connection);
+        loop {
+            let (sender, receiver) = match connection.take() {
+                Some(connection) => connection,
+                None => match self.reconnect().await {
+                    Some(connection) => {
+                        self.control_event(PersistentEvent::Reconnected);
+                        connection
+                    }
+                    None => break,
+                },
+            };
+            if !self.run_connection(sender, receiver).await {
+                break;
+            }
+            tracing::info!(url = %self.url, "Websocket: Connection dropped, reconnecting");
+            self.control_event(PersistentEvent::Disconnected);
+        }
+        // Fail anything still waiting.
+        while let Some(msg) = self.pending.pop_front() {
+            Self::fail(msg, WebsocketError::Shutdown);
+        }
+        tracing::debug!(url = %self.url, "Websocket: Persistent connection closed");
+    }
+
+    /// Send and receive on a connection until it drops.
+    /// Returns false if the persistent connection should close.
+    async fn run_connection(
+        &mut self,
+        mut sender: WebsocketSender,
+        mut receiver: WebsocketReceiver,
+    ) -> bool {
+        while let Some(msg) = self.pending.pop_front() {
+            if let Err(msg) = Self::send(&mut sender, msg).await {
+                self.pending.push_front(msg);
+                return true;
+            }
+        }
+        loop {
+            tokio::select! {
+                msg = Self::next_outgoing(&mut self.rx_outgoing) => match msg {
+                    Some(msg) => {
+                        if let Err(msg) = Self::send(&mut sender, msg).await {
+                            self.pending.push_front(msg);
+                            return true;
+                        }
+                    }
+                    None => {
+                        self.rx_outgoing = None;
+                        if self.tx_event.is_closed() {
+                            return false;
+                        }
+                    }
+                },
+                msg = receiver.next() => match msg {
+                    Some((msg, resp)) => {
+                        if !self.message(msg, resp)
+                            && self.rx_outgoing.is_none()
+                        {
+                            return false;
+                        }
+                    }
+                    None => return true,
+                },
+                _ = self.tx_event.closed(), if self.rx_outgoing.is_none() => return false,
+            }
+        }
+    }
+
+    /// Try to connect until it works, holding outgoing messages in the meantime.
+    /// Returns None if the persistent connection should close.
+    async fn reconnect(&mut self) -> Option<(WebsocketSender, WebsocketReceiver)> {
+        let max_backoff = std::time::Duration::from_millis(self.config.reconnect_max_backoff_ms);
+        let mut backoff =
+            std::time::Duration::from_millis(self.config.reconnect_initial_backoff_ms)
+                .min(max_backoff);
+        loop {
+            let sleep = tokio::time::sleep(backoff);
+            futures::pin_mut!(sleep);
+            loop {
+                tokio::select! {
+                    _ = &mut sleep => break,
+                    msg = Self::next_outgoing(&mut self.rx_outgoing) => match msg {
+                        Some(msg) => self.hold(msg),
+                        None => self.rx_outgoing = None,
+                    },
+                }
+                if self.is_closed() {
+                    return None;
+                }
+            }
+            match connect(self.url.clone(), self.config.clone()).await {
+                Ok(connection) => return Some(connection),
+                Err(e) => {
+                    tracing::debug!(url = %self.url, ?backoff, "Websocket: Failed to reconnect {:?}", e);
+                }
+            }
+            if self.is_closed() {
+                return None;
+            }
+            backoff = (backoff * 2).min(max_backoff);
+        }
+    }
+
+    /// The next outgoing message or pending forever if the senders are gone.
+    async fn next_outgoing(
+        rx_outgoing: &mut Option<tokio::sync::mpsc::Receiver<Outgoing>>,
+    ) -> Option<Outgoing> {
+        match rx_outgoing {
+            Some(rx) => rx.recv().await,
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// Hold a message until the connection is back.
+    fn hold(&mut self, msg: Outgoing) {
+        if self.pending.len() < self.config.max_reconnect_queue {
+            self.pending.push_back(msg);
+        } else {
+            tracing::warn!(url = %self.url, "Websocket: Reconnect queue is full, dropping message");
+            Self::fail(msg, WebsocketError::QueueFull);
+        }
+    }
+
+    /// Send a message on the current connection.
+    /// Gives the message back if the connection has dropped.
+    async fn send(sender: &mut WebsocketSender, msg: Outgoing) -> Result<(), Outgoing> {
+        match msg {
+            Outgoing::Signal(data) => match sender.signal_bytes(data.clone()).await {
+                Err(WebsocketError::Shutdown) => Err(Outgoing::Signal(data)),
+                r => {
+                    if let Err(e) = r {
+                        tracing::warn!("Websocket: Failed to send signal {:?}", e);
+                    }
+                    Ok(())
+                }
+            },
+            Outgoing::Request(data, respond) => {
+                // Wait for the response in the background so other
+                // messages aren't held up.
+                let mut sender = sender.clone();
+                tokio::task::spawn(async move {
+                    respond.send(sender.request_bytes(data).await).ok();
+                });
+                Ok(())
+            }
+        }
+    }
+
+    fn fail(msg: Outgoing, e: WebsocketError) {
+        if let Outgoing::Request(_, respond) = msg {
+            respond.send(Err(e)).ok();
+        }
+    }
+
+    /// Pass a message to the receiver without waiting, so a slow receiver
+    /// can't hold up outgoing messages.
+    /// If the receiver has fallen behind a signal is dropped and a request
+    /// is canceled, so the other side gets an error instead of waiting
+    /// for a response that will never come.
+    /// The receiver counts as behind until it has caught up with any
+    /// missed connection events, so messages never overtake them.
+    /// Returns false if the receiver has been dropped.
+    fn message(&mut self, msg: SerializedBytes, resp: Respond) -> bool {
+        let event = PersistentEvent::Message(msg, resp);
+        let sent = if self.missed.lock().expect("missed events lock").is_empty() {
+            self.tx_event.try_send(event)
+        } else if self.tx_event.is_closed() {
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(event))
+        } else {
+            Err(tokio::sync::mpsc::error::TrySendError::Full(event))
+        };
+        match sent {
+            Ok(()) => true,
+            Err(tokio::sync::mpsc::error::TrySendError::Full(event)) => {
+                tracing::warn!(url = %self.url, ?event, "Websocket: Receiver is full, dropping message");
+                // Dropping the respond half of a request sends the cancel response.
+                drop(event);
+                true
+            }
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+
+    /// Pass a connection event to the receiver without waiting, like [`Self::message`].
+    /// These must not be lost, so if the receiver has fallen behind the event
+    /// is held in [`MissedEvents`] until it catches up.
+    fn control_event(&mut self, event: PersistentEvent) {
+        let mut missed = self.missed.lock().expect("missed events lock");
+        // Events which were already missed go first.
+        if !missed.is_empty() {
+            missed.add(event);
+            return;
+        }
+        match self.tx_event.try_send(event) {
+            Ok(()) => (),
+            Err(tokio::sync::mpsc::error::TrySendError::Full(event)) => {
+                tracing::debug!(url = %self.url, ?event, "Websocket: Receiver is full, holding connection event");
+                missed.add(event);
+            }
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                tracing::debug!(url = %self.url, "Websocket: Receiver dropped before connection event");
+            }
+        }
+    }
+
+    /// Both the senders and the receiver have been dropped.
+    fn is_closed(&self) -> bool {
+        self.rx_outgoing.is_none() && self.tx_event.is_closed()
+    }
+}
@@ -1,14 +1,20 @@
+use bytes::Bytes;
 use futures::FutureExt;
 use futures::StreamExt;
 use holochain_serialized_bytes::{SerializedBytes, SerializedBytesError};
 use serde::{de::DeserializeOwned, Serialize};
 use stream_cancel::Valve;
+use url2::Url2;
 use websocket::PairShutdown;
+use websocket::TxFromWebsocket;
 use websocket::TxToWebsocket;
 
 use crate::websocket;
+use crate::WebsocketConfig;
 use crate::WebsocketError;
+use crate::WebsocketReceiver;
 use crate::WebsocketResult;
+use crate::DEFAULT_CHANNEL;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::sync::Arc;
@@ -56,6 +62,11 @@ pub struct WebsocketSender {
     tx_to_websocket: TxToWebsocket,
     listener_shutdown: Valve,
     __pair_shutdown: Arc<PairShutdown>,
+    /// The channel messages from this sender are sent on.
+    channel: u32,
+    /// Needed to create the receivers for new channels.
+    remote_addr: Url2,
+    config: Arc<WebsocketConfig>,
 }
 
 #[derive(Debug)]
@@ -73,6 +84,9 @@ pub(crate) type TxStaleRequest = tokio::sync::oneshot::Sender<u64>;
 /// Get the current state of the registered responses.
 pub(crate) type TxRequestsDebug = tokio::sync::oneshot::Sender<(Vec<u64>, u64)>;
 
+/// Find out if a channel was opened.
+pub(crate) type TxChannelOpened = tokio::sync::oneshot::Sender<bool>;
+
 impl RegisterResponse {
     pub(crate) fn new(respond: tokio::sync::oneshot::Sender<Option<SerializedBytes>>) -> Self {
         Self { respond }
@@ -92,10 +106,11 @@ impl RegisterResponse {
 /// A message going **out** to the external socket.
 pub(crate) enum OutgoingMessage {
     Close,
-    Signal(SerializedBytes),
-    Request(SerializedBytes, RegisterResponse, TxStaleRequest),
-    Response(Option<SerializedBytes>, u64),
+    Signal(u32, Bytes),
+    Request(u32, Bytes, RegisterResponse, TxStaleRequest),
+    Response(Option<Bytes>, u64),
     StaleRequest(u64),
+    OpenChannel(u32, TxFromWebsocket, TxChannelOpened),
     Pong(Vec<u8>),
     #[allow(dead_code)]
     Debug(TxRequestsDebug),
@@ -106,12 +121,76 @@ impl WebsocketSender {
         tx_to_websocket: TxToWebsocket,
         listener_shutdown: Valve,
         pair_shutdown: Arc<PairShutdown>,
+        remote_addr: Url2,
+        config: Arc<WebsocketConfig>,
     ) -> Self {
         Self {
             tx_to_websocket,
             listener_shutdown,
             __pair_shutdown: pair_shutdown,
+            channel: DEFAULT_CHANNEL,
+            remote_addr,
+            config,
+        }
+    }
+
+    /// The channel messages from this sender are sent on.
+    /// This is `0` unless the sender came from [`WebsocketSender::open_channel`].
+    pub fn channel(&self) -> u32 {
+        self.channel
+    }
+
+    #[tracing::instrument(skip(self))]
+    /// Open a logical channel on this connection.
+    ///
+    /// Messages sent with the returned sender are tagged with the channel
+    /// and the other side delivers them to the receiver it opened for the
+    /// same channel. This lets independent request / response streams,
+    /// like admin, app and signals, share one connection.
+    ///
+    /// Both sides need to open the channel. Messages that arrive for a
+    /// channel that isn't open are dropped and requests on it are canceled.
+    /// A channel can be opened again once its receiver is dropped.
+    ///
+    /// Returns [`WebsocketError::ChannelInUse`] if the channel is already
+    /// open. Channel `0` is the connection's default channel and is always open.
+    pub async fn open_channel(
+        &self,
+        channel: u32,
+    ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+        if channel == DEFAULT_CHANNEL {
+            return Err(WebsocketError::ChannelInUse(channel));
+        }
+        let (tx_from_websocket, rx_from_websocket) =
+            tokio::sync::mpsc::channel(self.config.max_send_queue);
+        let (tx_opened, rx_opened) = tokio::sync::oneshot::channel();
+        self.tx_to_websocket
+            .send(OutgoingMessage::OpenChannel(
+                channel,
+                tx_from_websocket,
+                tx_opened,
+            ))
+            .await
+            .map_err(|_| WebsocketError::Shutdown)?;
+        if !rx_opened.await.map_err(|_| WebsocketError::Shutdown)? {
+            return Err(WebsocketError::ChannelInUse(channel));
         }
+
+        let rx_from_websocket =
+            self.listener_shutdown
+                .wrap(tokio_stream::wrappers::ReceiverStream::new(
+                    rx_from_websocket,
+                ));
+        let receiver = WebsocketReceiver::new(
+            rx_from_websocket,
+            self.remote_addr.clone(),
+            self.__pair_shutdown.clone(),
+        );
+        let sender = Self {
+            channel,
+            ..self.clone()
+        };
+        Ok((sender, receiver))
     }
 
     #[tracing::instrument(skip(self))]
@@ -140,6 +219,8 @@ impl WebsocketSender {
     /// Note:
     /// There is no timeouts in this code. You either need to wrap
     /// this future in a timeout or use [`WebsocketSender::request_timeout`].
+    ///
+    /// Waits if the outgoing queue is full.
     pub async fn request<I, O>(&mut self, msg: I) -> WebsocketResult<O>
     where
         I: std::fmt::Debug,
@@ -149,17 +230,21 @@ impl WebsocketSender {
         O: DeserializeOwned,
     {
         use holochain_serialized_bytes as hsb;
+        let sb = self.request_bytes(Bytes::from(hsb::encode(&msg)?)).await?;
+        let resp: O = hsb::decode(&Vec::from(hsb::UnsafeBytes::from(sb)))?;
+        Ok(resp)
+    }
+
+    /// Make a request with an already serialized payload
+    /// and get the serialized response.
+    pub(crate) async fn request_bytes(&mut self, data: Bytes) -> WebsocketResult<SerializedBytes> {
         tracing::trace!("Sending");
 
         let (tx_resp, rx_resp) = tokio::sync::oneshot::channel();
         let (tx_stale_resp, rx_stale_resp) = tokio::sync::oneshot::channel();
         let mut rx_resp = self.listener_shutdown.wrap(rx_resp.into_stream());
         let resp = RegisterResponse::new(tx_resp);
-        let msg = OutgoingMessage::Request(
-            hsb::UnsafeBytes::from(hsb::encode(&msg)?).try_into()?,
-            resp,
-            tx_stale_resp,
-        );
+        let msg = OutgoingMessage::Request(self.channel, data, resp, tx_stale_resp);
 
         self.tx_to_websocket
             .send(msg)
@@ -176,15 +261,17 @@ impl WebsocketSender {
             .ok_or(WebsocketError::Shutdown)?
             .map_err(|_| WebsocketError::FailedToRecvResp)?
             .ok_or(WebsocketError::FailedToRecvResp)?;
-        let resp: O = hsb::decode(&Vec::from(hsb::UnsafeBytes::from(sb)))?;
         stale_request_guard.response_received();
-        Ok(resp)
+        Ok(sb)
     }
 
     #[tracing::instrument(skip(self))]
     /// Send a message to the other side that doesn't require a response.
     /// There is no guarantee this message will arrive. If you need confirmation
     /// of receipt use [`WebsocketSender::request`].
+    ///
+    /// Waits if the outgoing queue is full.
+    /// See [`WebsocketConfig::max_send_queue`](crate::WebsocketConfig::max_send_queue).
     pub async fn signal<I, E>(&mut self, msg: I) -> WebsocketResult<()>
     where
         I: std::fmt::Debug,
@@ -192,7 +279,7 @@ impl WebsocketSender {
         SerializedBytes: TryFrom<I, Error = E>,
     {
         tracing::trace!("Sending");
-        let msg = OutgoingMessage::Signal(msg.try_into()?);
+        let msg = OutgoingMessage::Signal(self.channel, sb_to_bytes(msg.try_into()?));
 
         self.tx_to_websocket
             .send(msg)
@@ -203,6 +290,60 @@ impl WebsocketSender {
         Ok(())
     }
 
+    #[tracing::instrument(skip(self, data), fields(len = data.len()))]
+    /// Send an already serialized [message pack](https://msgpack.org/)
+    /// payload as a signal.
+    ///
+    /// [`Bytes`] are reference counted so the same payload can be sent
+    /// to many connections without serializing or copying it for each one.
+    pub async fn signal_bytes(&mut self, data: Bytes) -> WebsocketResult<()> {
+        tracing::trace!("Sending");
+        let msg = OutgoingMessage::Signal(self.channel, data);
+
+        self.tx_to_websocket
+            .send(msg)
+            .await
+            .map_err(|_| WebsocketError::Shutdown)?;
+
+        tracing::trace!("Sent");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    /// Send a signal without waiting for space in the outgoing queue.
+    ///
+    /// Returns [`WebsocketError::QueueFull`] if the other side isn't
+    /// keeping up, for callers that would rather drop the signal than wait.
+    pub fn try_signal<I, E>(&mut self, msg: I) -> WebsocketResult<()>
+    where
+        I: std::fmt::Debug,
+        WebsocketError: From<E>,
+        SerializedBytes: TryFrom<I, Error = E>,
+    {
+        let data = sb_to_bytes(msg.try_into()?);
+        self.try_send(OutgoingMessage::Signal(self.channel, data))
+    }
+
+    #[tracing::instrument(skip(self, data), fields(len = data.len()))]
+    /// Send an already serialized payload as a signal without waiting
+    /// for space in the outgoing queue.
+    /// See [`WebsocketSender::try_signal`].
+    pub fn try_signal_bytes(&mut self, data: Bytes) -> WebsocketResult<()> {
+        self.try_send(OutgoingMessage::Signal(self.channel, data))
+    }
+
+    fn try_send(&mut self, msg: OutgoingMessage) -> WebsocketResult<()> {
+        use tokio::sync::mpsc::error::TrySendError;
+        match self.tx_to_websocket.try_send(msg) {
+            Ok(()) => {
+                tracing::trace!("Sent");
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => Err(WebsocketError::QueueFull),
+            Err(TrySendError::Closed(_)) => Err(WebsocketError::Shutdown),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) async fn debug(&mut self) -> WebsocketResult<(Vec<u64>, u64)> {
         let (tx_resp, rx_resp) = tokio::sync::oneshot::channel();
@@ -215,6 +356,115 @@ impl WebsocketSender {
     }
 }
 
+/// A [`WebsocketSender`] for a connection that only makes one type of
+/// request and gets one type of response back, such as the conductor's
+/// admin and app interfaces.
+///
+/// The response type doesn't need to be annotated at every call site.
+///
+/// # Example
+/// ```no_run
+/// use holochain_websocket::*;
+/// use url2::url2;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// enum Request {
+///     Ping,
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// enum Response {
+///     Pong,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (send, _) = connect(
+///         url2!("ws://127.0.0.1:12345"),
+///         std::sync::Arc::new(WebsocketConfig::default()),
+///     )
+///     .await
+///     .unwrap();
+///     let mut send = TypedWebsocketSender::<Request, Response>::new(send);
+///
+///     let resp = send.request(Request::Ping).await.unwrap();
+///     println!("Got {:?}", resp);
+/// }
+/// ```
+pub struct TypedWebsocketSender<Req, Res> {
+    sender: WebsocketSender,
+    _types: std::marker::PhantomData<fn(Req) -> Res>,
+}
+
+impl<Req, Res> TypedWebsocketSender<Req, Res>
+where
+    Req: Serialize + std::fmt::Debug,
+    Res: DeserializeOwned + std::fmt::Debug,
+{
+    /// Only make `Req` requests on this sender.
+    pub fn new(sender: WebsocketSender) -> Self {
+        Self {
+            sender,
+            _types: std::marker::PhantomData,
+        }
+    }
+
+    /// Make a request for the other side to respond to.
+    /// See [`WebsocketSender::request`].
+    pub async fn request(&mut self, msg: Req) -> WebsocketResult<Res> {
+        self.sender.request(msg).await
+    }
+
+    /// Make a request for the other side to respond to.
+    /// See [`WebsocketSender::request_timeout`].
+    pub async fn request_timeout(
+        &mut self,
+        msg: Req,
+        timeout: std::time::Duration,
+    ) -> WebsocketResult<Res> {
+        self.sender.request_timeout(msg, timeout).await
+    }
+
+    /// Get the untyped sender back.
+    pub fn into_inner(self) -> WebsocketSender {
+        self.sender
+    }
+}
+
+impl<Req, Res> From<WebsocketSender> for TypedWebsocketSender<Req, Res>
+where
+    Req: Serialize + std::fmt::Debug,
+    Res: DeserializeOwned + std::fmt::Debug,
+{
+    fn from(sender: WebsocketSender) -> Self {
+        Self::new(sender)
+    }
+}
+
+impl<Req, Res> Clone for TypedWebsocketSender<Req, Res> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            _types: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Req, Res> std::fmt::Debug for TypedWebsocketSender<Req, Res> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedWebsocketSender")
+            .field("sender", &self.sender)
+            .field("request", &std::any::type_name::<Req>())
+            .field("response", &std::any::type_name::<Res>())
+            .finish()
+    }
+}
+
+/// Take ownership of the serialized bytes without copying them.
+pub(crate) fn sb_to_bytes(sb: SerializedBytes) -> Bytes {
+    Bytes::from(Vec::from(holochain_serialized_bytes::UnsafeBytes::from(sb)))
+}
+
 impl StaleRequest {
     /// To remove responses we need the channel to the websocket
     /// and the id of the request.
@@ -3,11 +3,18 @@ use std::sync::Arc;
 use futures::StreamExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::connect;
+use holochain_websocket::connect_persistent;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::PersistentEvent;
+use holochain_websocket::Rejection;
+use holochain_websocket::TypedWebsocketSender;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketError;
 use holochain_websocket::WebsocketListener;
+use holochain_websocket::WebsocketMessageKind;
+use holochain_websocket::WebsocketMetrics;
+use holochain_websocket::WebsocketResult;
 use stream_cancel::Tripwire;
 use tracing::Instrument;
 use url2::url2;
@@ -452,3 +459,713 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unresponsive_peer_closes_connection() {
+    observability::test_run().ok();
+    // A server that completes the handshake but never reads
+    // from the socket so our pings go unanswered.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let s_jh = tokio::task::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let _socket = tokio_tungstenite::accept_async(socket).await.unwrap();
+        futures::future::pending::<()>().await;
+    });
+
+    let config = WebsocketConfig::default()
+        .ping_interval_s(1)
+        .max_missed_pongs(1);
+    let (_sender, mut receiver) = connect(url2!("ws://{}", addr), Arc::new(config))
+        .instrument(tracing::debug_span!("client:unresponsive_peer"))
+        .await
+        .unwrap();
+
+    // The receiver closes once the ping goes unanswered.
+    let r = tokio::time::timeout(std::time::Duration::from_secs(10), receiver.next())
+        .await
+        .expect("connection should close when the peer stops responding");
+    assert!(r.is_none());
+    s_jh.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pings_keep_idle_connection_open() {
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let s_jh = tokio::task::spawn(async move {
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        while let Some((msg, resp)) = receiver.next().await {
+            resp.respond(msg).await.unwrap();
+        }
+    });
+
+    let config = WebsocketConfig::default()
+        .ping_interval_s(1)
+        .max_missed_pongs(1);
+    let binding = handle.local_addr().clone();
+    let (mut sender, _receiver) = connect(binding, Arc::new(config))
+        .instrument(tracing::debug_span!("client:idle_connection"))
+        .await
+        .unwrap();
+
+    // Stay idle for several ping intervals.
+    tokio::time::sleep(std::time::Duration::from_secs(4)).await;
+
+    let r: TestString = sender
+        .request(TestString("still here".into()))
+        .await
+        .unwrap();
+    assert_eq!(r.0, "still here");
+    handle.close();
+    s_jh.abort();
+}
+
+#[derive(Debug, Default)]
+struct RecordMetrics {
+    opened: std::sync::atomic::AtomicUsize,
+    closed: std::sync::atomic::AtomicUsize,
+    sent: std::sync::Mutex<Vec<(WebsocketMessageKind, usize)>>,
+    received: std::sync::Mutex<Vec<(WebsocketMessageKind, usize)>>,
+    max_pending: std::sync::atomic::AtomicUsize,
+}
+
+impl WebsocketMetrics for RecordMetrics {
+    fn connection_opened(&self, _remote_addr: &url2::Url2) {
+        self.opened
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn connection_closed(&self, _remote_addr: &url2::Url2, _duration: std::time::Duration) {
+        self.closed
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn message_sent(&self, _remote_addr: &url2::Url2, kind: WebsocketMessageKind, bytes: usize) {
+        self.sent.lock().unwrap().push((kind, bytes));
+    }
+
+    fn message_received(
+        &self,
+        _remote_addr: &url2::Url2,
+        kind: WebsocketMessageKind,
+        bytes: usize,
+    ) {
+        self.received.lock().unwrap().push((kind, bytes));
+    }
+
+    fn pending_requests(&self, _remote_addr: &url2::Url2, count: usize) {
+        self.max_pending
+            .fetch_max(count, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metrics_are_reported() {
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let s_jh = tokio::task::spawn(async move {
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        while let Some((msg, resp)) = receiver.next().await {
+            if resp.is_request() {
+                resp.respond(msg).await.unwrap();
+            }
+        }
+    });
+
+    let metrics = Arc::new(RecordMetrics::default());
+    let binding = handle.local_addr().clone();
+    let (mut sender, receiver) = connect(
+        binding,
+        Arc::new(WebsocketConfig::default().with_metrics(metrics.clone())),
+    )
+    .instrument(tracing::debug_span!("client:metrics"))
+    .await
+    .unwrap();
+    assert_eq!(metrics.opened.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    sender.signal(TestString("signal".into())).await.unwrap();
+    let _: TestString = sender.request(TestString("request".into())).await.unwrap();
+
+    {
+        let sent = metrics.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].0, WebsocketMessageKind::Signal);
+        assert_eq!(sent[1].0, WebsocketMessageKind::Request);
+        assert!(sent.iter().all(|(_, bytes)| *bytes > 0));
+        let received = metrics.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].0, WebsocketMessageKind::Response);
+    }
+    assert_eq!(
+        metrics
+            .max_pending
+            .load(std::sync::atomic::Ordering::SeqCst),
+        1
+    );
+
+    drop(sender);
+    drop(receiver);
+    handle.close();
+    s_jh.await.unwrap();
+
+    // The connection tasks shut down in the background.
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while metrics.closed.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn compresses_large_messages() {
+    observability::test_run().ok();
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(WebsocketConfig::default().compression_threshold(1024)),
+    )
+    .await
+    .unwrap();
+    let s_jh = tokio::task::spawn(async move {
+        while let Some(Ok((_sender, mut receiver))) = listener.next().await {
+            tokio::task::spawn(async move {
+                while let Some((msg, resp)) = receiver.next().await {
+                    if resp.is_request() {
+                        resp.respond(msg).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+    let binding = handle.local_addr().clone();
+    let large = TestString("a".repeat(100_000));
+
+    // - Both sides enabled compression.
+    let metrics = Arc::new(RecordMetrics::default());
+    let (mut sender, _receiver) = connect(
+        binding.clone(),
+        Arc::new(
+            WebsocketConfig::default()
+                .compression_threshold(1024)
+                .with_metrics(metrics.clone()),
+        ),
+    )
+    .instrument(tracing::debug_span!("client:compressed"))
+    .await
+    .unwrap();
+    let resp: TestString = sender.request(TestString(large.0.clone())).await.unwrap();
+    assert_eq!(resp.0, large.0);
+    // - Small messages are not compressed.
+    let resp: TestString = sender.request(TestString("small".into())).await.unwrap();
+    assert_eq!(resp.0, "small");
+    {
+        let sent = metrics.sent.lock().unwrap();
+        let received = metrics.received.lock().unwrap();
+        assert!(sent[0].1 < 10_000);
+        assert!(received[0].1 < 10_000);
+        assert!(sent[1].1 < 1024);
+    }
+
+    // - Only the server enabled compression.
+    let metrics = Arc::new(RecordMetrics::default());
+    let (mut sender, _receiver) = connect(
+        binding,
+        Arc::new(WebsocketConfig::default().with_metrics(metrics.clone())),
+    )
+    .instrument(tracing::debug_span!("client:uncompressed"))
+    .await
+    .unwrap();
+    let resp: TestString = sender.request(TestString(large.0.clone())).await.unwrap();
+    assert_eq!(resp.0, large.0);
+    {
+        let sent = metrics.sent.lock().unwrap();
+        let received = metrics.received.lock().unwrap();
+        assert!(sent[0].1 > 100_000);
+        assert!(received[0].1 > 100_000);
+    }
+
+    handle.close();
+    s_jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn try_signal_fails_when_queue_is_full() {
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let (start_reading, reading) = tokio::sync::oneshot::channel();
+    let s_jh = tokio::task::spawn(async move {
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        // - Don't read anything until the client's queue is full.
+        reading.await.unwrap();
+        while receiver.next().await.is_some() {}
+    });
+    let binding = handle.local_addr().clone();
+    let (mut sender, _receiver) = connect(
+        binding,
+        Arc::new(WebsocketConfig::default().max_send_queue(1)),
+    )
+    .await
+    .unwrap();
+
+    // - Fill up the socket and then the outgoing queue.
+    let large = TestString("a".repeat(1 << 20));
+    let mut full = false;
+    for _ in 0..1000 {
+        match sender.try_signal(TestString(large.0.clone())) {
+            Ok(()) => tokio::task::yield_now().await,
+            Err(WebsocketError::QueueFull) => {
+                full = true;
+                break;
+            }
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    }
+    assert!(full);
+
+    // - Waiting signals go through once the other side catches up.
+    start_reading.send(()).unwrap();
+    tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        sender.signal(TestString("Hey from client".into())),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    drop(sender);
+    handle.close();
+    s_jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_typed_request() {
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let s_jh = tokio::task::spawn(async move {
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        while let Some((msg, resp)) = receiver.next().await {
+            let msg: TestString = msg.try_into().unwrap();
+            resp.respond(TestString(format!("{} back", msg.0)).try_into().unwrap())
+                .await
+                .unwrap();
+        }
+    });
+
+    let binding = handle.local_addr().clone();
+    let (sender, _receiver) = connect(binding, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let mut sender = TypedWebsocketSender::<TestString, TestString>::new(sender);
+
+    let resp = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(resp.0, "Hey from client back");
+    let resp = sender
+        .request_timeout(
+            TestString("Hey again".into()),
+            std::time::Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.0, "Hey again back");
+
+    drop(sender);
+    handle.close();
+    s_jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn persistent_connection_reconnects() {
+    observability::test_run().ok();
+
+    // Echo requests and pass signals on to the test.
+    fn echo_server(
+        mut listener: impl futures::stream::Stream<Item = ListenerItem> + Unpin + Send + 'static,
+        signals: tokio::sync::mpsc::Sender<String>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::task::spawn(async move {
+            while let Some(Ok((_sender, mut receiver))) = listener.next().await {
+                let signals = signals.clone();
+                tokio::task::spawn(async move {
+                    while let Some((msg, resp)) = receiver.next().await {
+                        let msg: TestString = msg.try_into().unwrap();
+                        if resp.is_request() {
+                            resp.respond(msg.try_into().unwrap()).await.unwrap();
+                        } else {
+                            signals.send(msg.0).await.unwrap();
+                        }
+                    }
+                });
+            }
+        })
+    }
+
+    let (tx_signals, mut rx_signals) = tokio::sync::mpsc::channel(10);
+    let (handle, listener) = server().await;
+    let binding = handle.local_addr().clone();
+    let s_jh = echo_server(listener, tx_signals.clone());
+
+    let (mut sender, mut receiver) = connect_persistent(
+        binding.clone(),
+        Arc::new(WebsocketConfig::default().reconnect_backoff_ms(10, 100)),
+    )
+    .await
+    .unwrap();
+    let resp: TestString = sender.request(TestString("one".into())).await.unwrap();
+    assert_eq!(resp.0, "one");
+
+    // - Shutdown the server.
+    handle.close();
+    s_jh.await.unwrap();
+    assert!(matches!(
+        receiver.next().await,
+        Some(PersistentEvent::Disconnected)
+    ));
+
+    // - Signals sent while disconnected are held.
+    sender.signal(TestString("two".into())).await.unwrap();
+
+    // - Start the server again on the same address.
+    let (handle, listener) =
+        WebsocketListener::bind_with_handle(binding.clone(), Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    let s_jh = echo_server(listener, tx_signals);
+
+    assert!(matches!(
+        receiver.next().await,
+        Some(PersistentEvent::Reconnected)
+    ));
+    assert_eq!(rx_signals.recv().await.unwrap(), "two");
+    let resp: TestString = sender.request(TestString("three".into())).await.unwrap();
+    assert_eq!(resp.0, "three");
+
+    handle.close();
+    s_jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn channels_share_one_connection() {
+    observability::test_run().ok();
+
+    // Respond to requests with the name of the channel.
+    fn echo(
+        name: &'static str,
+        mut receiver: holochain_websocket::WebsocketReceiver,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::task::spawn(async move {
+            while let Some((msg, resp)) = receiver.next().await {
+                let msg: TestString = msg.try_into().unwrap();
+                let msg = TestString(format!("{}: {}", name, msg.0));
+                resp.respond(msg.try_into().unwrap()).await.unwrap();
+            }
+        })
+    }
+
+    let (handle, mut listener) = server().await;
+    let binding = handle.local_addr().clone();
+    let s_jh = tokio::task::spawn(async move {
+        let (sender, receiver) = listener.next().await.unwrap().unwrap();
+        // Open the channel before answering on the default channel.
+        let (_admin_sender, admin_receiver) = sender.open_channel(1).await.unwrap();
+        let admin_jh = echo("admin", admin_receiver);
+        echo("default", receiver).await.unwrap();
+        admin_jh.await.unwrap();
+    });
+
+    let (mut sender, _receiver) = connect(binding, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let resp: TestString = sender.request(TestString("one".into())).await.unwrap();
+    assert_eq!(resp.0, "default: one");
+
+    let (mut admin_sender, _admin_receiver) = sender.open_channel(1).await.unwrap();
+    assert_eq!(admin_sender.channel(), 1);
+    let resp: TestString = admin_sender
+        .request(TestString("two".into()))
+        .await
+        .unwrap();
+    assert_eq!(resp.0, "admin: two");
+
+    // - The default channel and open channels can't be opened again.
+    assert!(matches!(
+        sender.open_channel(0).await,
+        Err(WebsocketError::ChannelInUse(0))
+    ));
+    assert!(matches!(
+        sender.open_channel(1).await,
+        Err(WebsocketError::ChannelInUse(1))
+    ));
+
+    // - Requests on a channel the other side hasn't opened are canceled.
+    let (mut app_sender, app_receiver) = sender.open_channel(2).await.unwrap();
+    assert!(matches!(
+        app_sender
+            .request::<_, TestString>(TestString("three".into()))
+            .await,
+        Err(WebsocketError::FailedToRecvResp)
+    ));
+
+    // - A channel can be opened again once its receiver is dropped.
+    drop(app_receiver);
+    sender.open_channel(2).await.unwrap();
+
+    drop(sender);
+    drop(admin_sender);
+    drop(app_sender);
+    handle.close();
+    s_jh.await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_connect_over_unix_socket() {
+    observability::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let url = url2!("unix://{}", dir.path().join("admin.sock").display());
+
+    let echo = |mut listener: WebsocketListener| {
+        tokio::task::spawn(async move {
+            let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+            let (msg, resp) = receiver.next().await.unwrap();
+            let msg: TestString = msg.try_into().unwrap();
+            resp.respond(TestString(format!("echo: {}", msg.0)).try_into().unwrap())
+                .await
+                .unwrap();
+        })
+    };
+
+    let listener = WebsocketListener::bind(url.clone(), Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    assert_eq!(listener.local_addr(), &url);
+    let jh = echo(listener);
+
+    let (mut sender, receiver) = connect(url.clone(), Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    assert!(receiver.remote_addr().as_str().starts_with(url.as_str()));
+    let msg: TestString = sender.request(TestString("hey".into())).await.unwrap();
+    assert_eq!(msg.0, "echo: hey");
+    jh.await.unwrap();
+
+    // The socket file left behind by the dropped listener is replaced
+    // but a live socket can't be taken over by another listener.
+    let listener = WebsocketListener::bind(url.clone(), Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    assert!(
+        WebsocketListener::bind(url.clone(), Arc::new(WebsocketConfig::default()))
+            .await
+            .is_err()
+    );
+
+    listener.close();
+    let listener = WebsocketListener::bind(url.clone(), Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let jh = echo(listener);
+    let (mut sender, _receiver) = connect(url, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender.request(TestString("again".into())).await.unwrap();
+    assert_eq!(msg.0, "echo: again");
+    jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn on_connect_can_reject_connections() {
+    observability::test_run().ok();
+    let config = WebsocketConfig::default().on_connect(|info| async move {
+        assert!(info.headers.contains_key("sec-websocket-key"));
+        match info.uri.query() {
+            Some("token=secret") => Ok(()),
+            _ => Err(Rejection::unauthorized("missing token")),
+        }
+    });
+    let (handle, mut listener) =
+        WebsocketListener::bind_with_handle(url2!("ws://127.0.0.1:0"), Arc::new(config))
+            .await
+            .unwrap();
+    let jh = tokio::task::spawn(async move {
+        assert!(matches!(
+            listener.next().await.unwrap(),
+            Err(WebsocketError::ConnectionRejected(_))
+        ));
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        resp.respond(TestString(format!("echo: {}", msg.0)).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let mut url = handle.local_addr().clone();
+    assert!(connect(url.clone(), Arc::new(WebsocketConfig::default()))
+        .await
+        .is_err());
+
+    url.set_query(Some("token=secret"));
+    let (mut sender, _receiver) = connect(url, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender.request(TestString("hey".into())).await.unwrap();
+    assert_eq!(msg.0, "echo: hey");
+    jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn persistent_connection_slow_receiver_does_not_stall() {
+    observability::test_run().ok();
+
+    let (handle, mut listener) = server().await;
+    let binding = handle.local_addr().clone();
+    let s_jh = tokio::task::spawn(async move {
+        while let Some(Ok((mut sender, mut receiver))) = listener.next().await {
+            tokio::task::spawn(async move {
+                // - Send more signals than the client's receiver can hold.
+                for i in 0..20 {
+                    sender
+                        .signal(TestString(format!("signal {}", i)))
+                        .await
+                        .unwrap();
+                }
+                while let Some((msg, resp)) = receiver.next().await {
+                    if resp.is_request() {
+                        let msg: TestString = msg.try_into().unwrap();
+                        resp.respond(msg.try_into().unwrap()).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    let (mut sender, mut receiver) = connect_persistent(
+        binding,
+        Arc::new(WebsocketConfig::default().max_send_queue(2)),
+    )
+    .await
+    .unwrap();
+
+    // - Requests still get through while nothing reads the receiver.
+    let resp: TestString = sender
+        .request_timeout(
+            TestString("echo".into()),
+            std::time::Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.0, "echo");
+
+    // - The receiver only holds as many events as the queue allows.
+    let mut received = 0;
+    while let Ok(Some(_)) =
+        tokio::time::timeout(std::time::Duration::from_millis(100), receiver.next()).await
+    {
+        received += 1;
+    }
+    assert!(received <= 2);
+
+    handle.close();
+    s_jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn persistent_connection_lagging_receiver_gets_reconnected() {
+    observability::test_run().ok();
+
+    let (handle, mut listener) = server().await;
+    let binding = handle.local_addr().clone();
+    let (tx_result, mut rx_result) = tokio::sync::mpsc::channel(1);
+    let s_jh = tokio::task::spawn(async move {
+        while let Some(Ok((mut sender, _receiver))) = listener.next().await {
+            // - Fill the client's receiver with signals.
+            for i in 0..20 {
+                sender
+                    .signal(TestString(format!("signal {}", i)))
+                    .await
+                    .unwrap();
+            }
+            // - A request that doesn't fit is canceled rather than left waiting.
+            let r: WebsocketResult<TestString> = sender
+                .request_timeout(
+                    TestString("request".into()),
+                    std::time::Duration::from_secs(10),
+                )
+                .await;
+            tx_result.send(r).await.unwrap();
+        }
+    });
+
+    let (mut sender, mut receiver) = connect_persistent(
+        binding.clone(),
+        Arc::new(
+            WebsocketConfig::default()
+                .max_send_queue(2)
+                .reconnect_backoff_ms(10, 100),
+        ),
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(
+        rx_result.recv().await.unwrap(),
+        Err(WebsocketError::FailedToRecvResp)
+    ));
+
+    // - Restart the server while the receiver is still full.
+    handle.close();
+    s_jh.await.unwrap();
+    let (handle, mut listener) =
+        WebsocketListener::bind_with_handle(binding, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    let s_jh = tokio::task::spawn(async move {
+        while let Some(Ok((_sender, mut receiver))) = listener.next().await {
+            tokio::task::spawn(async move {
+                while let Some((msg, resp)) = receiver.next().await {
+                    if resp.is_request() {
+                        let msg: TestString = msg.try_into().unwrap();
+                        resp.respond(msg.try_into().unwrap()).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    // - The held back connection events don't stop the reconnect.
+    // Requests fail until the client notices the old connection is gone.
+    let resp: TestString = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        loop {
+            match sender.request(TestString("echo".into())).await {
+                Ok(resp) => break resp,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(resp.0, "echo");
+
+    // - Catching up still delivers both connection events.
+    let mut disconnected = false;
+    loop {
+        match tokio::time::timeout(std::time::Duration::from_secs(10), receiver.next())
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            PersistentEvent::Message(_, _) => (),
+            PersistentEvent::Disconnected => disconnected = true,
+            PersistentEvent::Reconnected => break,
+        }
+    }
+    assert!(disconnected);
+
+    handle.close();
+    s_jh.await.unwrap();
+}
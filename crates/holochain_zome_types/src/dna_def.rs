@@ -3,7 +3,6 @@
 use super::zome;
 use crate::prelude::*;
 
-#[cfg(feature = "full-dna-def")]
 use crate::zome::error::ZomeError;
 #[cfg(feature = "full-dna-def")]
 use holo_hash::*;
@@ -97,6 +96,45 @@ impl DnaDef {
                     .map(|(n, def)| (n, def.as_any_zome_def())),
             )
     }
+
+    /// Get all zomes in the order their `init` callbacks run.
+    ///
+    /// Each step takes the first zome, in definition order, whose
+    /// init dependencies have all run, so the order is always the same.
+    /// Errors if an init dependency doesn't exist or the dependencies form a cycle.
+    pub fn init_order(&self) -> Result<Vec<(&ZomeName, &zome::ZomeDef)>, ZomeError> {
+        let mut remaining: Vec<_> = self.all_zomes().collect();
+        for (name, def) in &remaining {
+            if let Some(dep) = def
+                .init_after()
+                .iter()
+                .find(|dep| *dep == *name || !remaining.iter().any(|(n, _)| n == dep))
+            {
+                return Err(ZomeError::DanglingInitDependency(
+                    dep.clone(),
+                    (*name).clone(),
+                ));
+            }
+        }
+        let mut order: Vec<(&ZomeName, &zome::ZomeDef)> = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            // The first zome that isn't waiting on any zome still remaining.
+            let next = remaining.iter().position(|(_, def)| {
+                def.init_after()
+                    .iter()
+                    .all(|dep| order.iter().any(|(n, _)| *n == dep))
+            });
+            match next {
+                Some(i) => order.push(remaining.remove(i)),
+                None => {
+                    return Err(ZomeError::InitDependencyCycle(
+                        remaining.into_iter().map(|(n, _)| n.clone()).collect(),
+                    ))
+                }
+            }
+        }
+        Ok(order)
+    }
 }
 
 #[cfg(feature = "full-dna-def")]
@@ -233,3 +271,73 @@ impl HashableContent for DnaDef {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zome(init_after: &[&str]) -> zome::ZomeDef {
+        zome::ZomeDef::Wasm(zome::WasmZome {
+            wasm_hash: holo_hash::WasmHash::from_raw_36(vec![0; 36]),
+            dependencies: Default::default(),
+            init_after: init_after.iter().map(|n| ZomeName::from(*n)).collect(),
+        })
+    }
+
+    fn dna_def(zomes: Vec<(&str, zome::ZomeDef)>) -> DnaDef {
+        DnaDef {
+            name: "test".into(),
+            uid: "test".into(),
+            properties: SerializedBytes::try_from(()).unwrap(),
+            origin_time: Timestamp::HOLOCHAIN_EPOCH,
+            integrity_zomes: vec![],
+            coordinator_zomes: zomes
+                .into_iter()
+                .map(|(n, d)| (n.into(), d.into()))
+                .collect(),
+        }
+    }
+
+    fn names(order: Vec<(&ZomeName, &zome::ZomeDef)>) -> Vec<String> {
+        order.into_iter().map(|(n, _)| n.to_string()).collect()
+    }
+
+    #[test]
+    fn init_order_respects_dependencies() {
+        let dna = dna_def(vec![("a", zome(&[])), ("b", zome(&[])), ("c", zome(&[]))]);
+        assert_eq!(names(dna.init_order().unwrap()), vec!["a", "b", "c"]);
+
+        let dna = dna_def(vec![
+            ("a", zome(&["c"])),
+            ("b", zome(&[])),
+            ("c", zome(&["b"])),
+            ("d", zome(&[])),
+        ]);
+        assert_eq!(names(dna.init_order().unwrap()), vec!["b", "c", "a", "d"]);
+    }
+
+    #[test]
+    fn init_order_rejects_bad_dependencies() {
+        let dna = dna_def(vec![
+            ("a", zome(&["b"])),
+            ("b", zome(&["a"])),
+            ("c", zome(&[])),
+        ]);
+        assert!(matches!(
+            dna.init_order(),
+            Err(ZomeError::InitDependencyCycle(zomes)) if zomes == vec!["a".into(), "b".into()]
+        ));
+
+        let dna = dna_def(vec![("a", zome(&["a"]))]);
+        assert!(matches!(
+            dna.init_order(),
+            Err(ZomeError::DanglingInitDependency(_, _))
+        ));
+
+        let dna = dna_def(vec![("a", zome(&["missing"]))]);
+        assert!(matches!(
+            dna.init_order(),
+            Err(ZomeError::DanglingInitDependency(_, _))
+        ));
+    }
+}
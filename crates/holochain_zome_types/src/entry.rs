@@ -152,10 +152,39 @@ impl AsRef<crate::Entry> for CreateInput {
     }
 }
 
-/// Zome input for get and get_details calls.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+/// What happened when getting data from the DHT.
+///
+/// Unlike an `Option` this tells data that doesn't exist apart from
+/// data that couldn't be looked up because no authority answered.
+pub enum GetOutcome<T> {
+    /// The data was found.
+    Found(T),
+    /// The data was not found locally and the authorities that answered
+    /// don't hold it, or this agent is an authority and doesn't hold it.
+    NotFound,
+    /// The data was not found locally and no authority answered
+    /// because none could be reached.
+    Unavailable,
+    /// The data was not found locally and the authorities that were
+    /// asked didn't respond in time.
+    TimedOut,
+}
+
+impl<T> GetOutcome<T> {
+    /// The data if it was found.
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            GetOutcome::Found(t) => Some(t),
+            GetOutcome::NotFound | GetOutcome::Unavailable | GetOutcome::TimedOut => None,
+        }
+    }
+}
+
+/// Zome input for get, get_details and get_outcome calls.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct GetInput {
-    /// Any DHT hash to pass to get or get_details.
+    /// Any DHT hash to pass to get, get_details or get_outcome.
     pub any_dht_hash: holo_hash::AnyDhtHash,
     /// Options for the call.
     pub get_options: crate::entry::GetOptions,
@@ -35,8 +35,15 @@ impl AgentInfo {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AppInfo;
+/// Information about the installed app the calling cell is part of.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct AppInfo {
+    /// The id the app was installed with.
+    pub installed_app_id: String,
+    /// The role of the calling cell in the app.
+    /// For cloned cells this is the role they were cloned from.
+    pub role_id: String,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CallInfo {
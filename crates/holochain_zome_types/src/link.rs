@@ -83,6 +83,9 @@ pub struct GetLinksInput {
     /// The link types to include in this get.
     pub link_type: LinkTypeFilter,
     pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Order and page the links instead of returning them all.
+    #[serde(default)]
+    pub pagination: Option<LinkPagination>,
 }
 
 impl GetLinksInput {
@@ -95,8 +98,103 @@ impl GetLinksInput {
             base_address,
             link_type,
             tag_prefix,
+            pagination: None,
         }
     }
+
+    /// Only get one page of the links.
+    pub fn with_pagination(mut self, pagination: LinkPagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+}
+
+/// The order links are returned in when they are paginated.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub enum LinkOrder {
+    /// Oldest links first.
+    #[default]
+    TimestampAsc,
+    /// Newest links first.
+    TimestampDesc,
+}
+
+/// The position of a link in the ordering, used to ask for the page of links
+/// after it. Links with equal timestamps are ordered by their create hash.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct LinkCursor {
+    pub timestamp: crate::Timestamp,
+    pub create_link_hash: ActionHash,
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        Self {
+            timestamp: link.timestamp,
+            create_link_hash: link.create_link_hash.clone(),
+        }
+    }
+}
+
+/// Which page of links to get.
+/// The cursor of the last link of a page is the `after` of the next page.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LinkPagination {
+    pub order: LinkOrder,
+    /// The most links to return. `None` returns all of them.
+    pub limit: Option<u32>,
+    /// Only return links which come after this one in the ordering.
+    pub after: Option<LinkCursor>,
+}
+
+impl LinkPagination {
+    pub fn new(order: LinkOrder, limit: Option<u32>, after: Option<LinkCursor>) -> Self {
+        Self {
+            order,
+            limit,
+            after,
+        }
+    }
+
+    /// Compare two link positions in this ordering.
+    pub fn compare(
+        &self,
+        a: (&crate::Timestamp, &ActionHash),
+        b: (&crate::Timestamp, &ActionHash),
+    ) -> std::cmp::Ordering {
+        match self.order {
+            LinkOrder::TimestampAsc => a.cmp(&b),
+            LinkOrder::TimestampDesc => b.cmp(&a),
+        }
+    }
+
+    /// Is this link position after the cursor?
+    pub fn is_after_cursor(&self, timestamp: &crate::Timestamp, hash: &ActionHash) -> bool {
+        match &self.after {
+            Some(cursor) => {
+                self.compare(
+                    (timestamp, hash),
+                    (&cursor.timestamp, &cursor.create_link_hash),
+                ) == std::cmp::Ordering::Greater
+            }
+            None => true,
+        }
+    }
+
+    /// Sort the links and keep only this page of them.
+    pub fn apply(&self, mut links: Vec<Link>) -> Vec<Link> {
+        links.sort_by(|a, b| {
+            self.compare(
+                (&a.timestamp, &a.create_link_hash),
+                (&b.timestamp, &b.create_link_hash),
+            )
+        });
+        links
+            .into_iter()
+            .filter(|link| self.is_after_cursor(&link.timestamp, &link.create_link_hash))
+            .take(self.limit.map_or(usize::MAX, |l| l as usize))
+            .collect()
+    }
 }
 
 type CreateLinkWithDeleteLinks = Vec<(SignedActionHashed, Vec<SignedActionHashed>)>;
@@ -2,12 +2,14 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ops::Range;
 
 use crate::action::ActionType;
 use crate::action::EntryType;
-use crate::warrant::Warrant;
+use crate::warrant::SignedWarrant;
 use crate::ActionHashed;
 use crate::Record;
+use crate::Timestamp;
 use holo_hash::ActionHash;
 use holo_hash::EntryHash;
 use holo_hash::HasHash;
@@ -76,6 +78,10 @@ pub struct ChainQueryFilter {
     // NB: if this filter is set, you can't verify the results, so don't
     //     use this in validation
     pub action_type: Option<ActionType>,
+    /// Filter by the timestamp of the action.
+    /// Inclusive start, exclusive end.
+    #[serde(default)]
+    pub time_range: Option<Range<Timestamp>>,
     /// Include the entries in the records
     pub include_entries: bool,
 }
@@ -92,9 +98,8 @@ pub struct AgentActivity {
     /// The highest chain action that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
-    /// Warrants about this AgentActivity.
-    /// Placeholder for future.
-    pub warrants: Vec<Warrant>,
+    /// Warrants issued against this agent by validators of its data.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -201,6 +206,12 @@ impl ChainQueryFilter {
         self
     }
 
+    /// Filter on the timestamp of the action.
+    pub fn time_range(mut self, time_range: Range<Timestamp>) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
     /// Include the entries in the RecordsVec that is returned.
     pub fn include_entries(mut self, include_entries: bool) -> Self {
         self.include_entries = include_entries;
@@ -286,6 +297,11 @@ impl ChainQueryFilter {
                             None => false,
                         })
                         .unwrap_or(true)
+                    && self
+                        .time_range
+                        .as_ref()
+                        .map(|time_range| time_range.contains(&action.timestamp()))
+                        .unwrap_or(true)
             })
             .collect()
     }
@@ -318,6 +334,7 @@ mod tests {
     use crate::fixt::*;
     use crate::ActionHashed;
     use crate::ChainQueryFilterRange;
+    use crate::Timestamp;
     use ::fixt::prelude::*;
     use holo_hash::HasHash;
 
@@ -332,23 +349,27 @@ mod tests {
         let mut h0 = fixt!(Create);
         h0.entry_type = entry_type_1.clone();
         h0.action_seq = 0;
+        h0.timestamp = Timestamp::from_micros(0);
         h0.entry_hash = entry_hash_0.clone();
         let hh0 = ActionHashed::from_content_sync(h0.into());
 
         let mut h1 = fixt!(Update);
         h1.entry_type = entry_type_2.clone();
         h1.action_seq = 1;
+        h1.timestamp = Timestamp::from_micros(10);
         h1.prev_action = hh0.as_hash().clone();
         let hh1 = ActionHashed::from_content_sync(h1.into());
 
         let mut h2 = fixt!(CreateLink);
         h2.action_seq = 2;
+        h2.timestamp = Timestamp::from_micros(20);
         h2.prev_action = hh1.as_hash().clone();
         let hh2 = ActionHashed::from_content_sync(h2.into());
 
         let mut h3 = fixt!(Create);
         h3.entry_type = entry_type_2.clone();
         h3.action_seq = 3;
+        h3.timestamp = Timestamp::from_micros(30);
         h3.prev_action = hh2.as_hash().clone();
         let hh3 = ActionHashed::from_content_sync(h3.into());
 
@@ -356,6 +377,7 @@ mod tests {
         let mut h3a = fixt!(Create);
         h3a.entry_type = entry_type_1.clone();
         h3a.action_seq = 3;
+        h3a.timestamp = Timestamp::from_micros(30);
         h3a.prev_action = hh2.as_hash().clone();
         let hh3a = ActionHashed::from_content_sync(h3a.into());
 
@@ -364,11 +386,13 @@ mod tests {
         // same entry content as h0
         h4.entry_hash = entry_hash_0;
         h4.action_seq = 4;
+        h4.timestamp = Timestamp::from_micros(40);
         h4.prev_action = hh3.as_hash().clone();
         let hh4 = ActionHashed::from_content_sync(h4.into());
 
         let mut h5 = fixt!(CreateLink);
         h5.action_seq = 5;
+        h5.timestamp = Timestamp::from_micros(50);
         h5.prev_action = hh4.as_hash().clone();
         let hh5 = ActionHashed::from_content_sync(h5.into());
 
@@ -445,6 +469,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn filter_by_time_range() {
+        let actions = fixtures();
+
+        let query_1 = ChainQueryFilter::new()
+            .time_range(Timestamp::from_micros(10)..Timestamp::from_micros(30));
+        let query_2 = ChainQueryFilter::new()
+            .time_range(Timestamp::from_micros(30)..Timestamp::from_micros(31));
+
+        assert_eq!(
+            map_query(&query_1, &actions),
+            [false, true, true, false, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(&query_2, &actions),
+            [false, false, false, true, true, false, false].to_vec()
+        );
+    }
+
     #[test]
     fn filter_by_chain_sequence() {
         let actions = fixtures();
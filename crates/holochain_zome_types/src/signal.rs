@@ -28,4 +28,8 @@ pub struct RemoteSignal {
     pub agents: Vec<AgentPubKey>,
     /// The signal to send.
     pub signal: crate::ExternIO,
+    /// Retry sends which fail for a while, and report whether each agent
+    /// received the signal with a system signal to the sender's clients.
+    #[serde(default)]
+    pub reliable: bool,
 }
@@ -1,4 +1,3 @@
-use crate::record::Record;
 use crate::CallbackResult;
 use holo_hash::AnyDhtHash;
 use holochain_serialized_bytes::prelude::*;
@@ -50,9 +49,6 @@ impl CallbackResult for ValidateCallbackResult {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
-pub struct ValidationPackage(pub Vec<Record>);
-
 #[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
 pub enum ValidationPackageCallbackResult {
     Success(ValidationPackage),
@@ -84,12 +80,6 @@ impl CallbackResult for ValidationPackageCallbackResult {
     }
 }
 
-impl ValidationPackage {
-    pub fn new(records: Vec<Record>) -> Self {
-        Self(records)
-    }
-}
-
 #[cfg(feature = "full")]
 impl rusqlite::ToSql for ValidationStatus {
     fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput> {
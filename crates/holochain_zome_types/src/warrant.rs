@@ -1,6 +1,42 @@
 //! Types for warrants
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
+use holochain_integrity_types::signature::Signature;
+use holochain_integrity_types::Timestamp;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+/// A claim by a validator that an author published data which failed validation.
+pub struct Warrant {
+    /// The author of the invalid data.
+    pub author: AgentPubKey,
+    /// The action of the op which failed validation.
+    pub action_hash: ActionHash,
+    /// The basis of the op which failed validation.
+    pub basis: AnyDhtHash,
+    /// The agent which validated the op and issued this warrant.
+    pub warrantor: AgentPubKey,
+    /// When the warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+/// A [`Warrant`] signed by its warrantor.
+pub struct SignedWarrant {
+    /// The warrant.
+    pub warrant: Warrant,
+    /// The warrantor's signature of the warrant.
+    pub signature: Signature,
+}
+
+impl SignedWarrant {
+    /// Pair a warrant with its signature.
+    pub fn new(warrant: Warrant, signature: Signature) -> Self {
+        Self { warrant, signature }
+    }
+}
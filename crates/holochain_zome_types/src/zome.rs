@@ -156,6 +156,7 @@ pub enum ZomeDef {
     Inline {
         inline_zome: self::inline_zome::DynInlineZome,
         dependencies: Vec<ZomeName>,
+        init_after: Vec<ZomeName>,
     },
 }
 
@@ -191,6 +192,11 @@ impl IntegrityZomeDef {
     pub fn as_any_zome_def(&self) -> &ZomeDef {
         &self.0
     }
+
+    /// Run this zome's `init` after another zome's.
+    pub fn set_init_dependency(&mut self, zome_name: impl Into<ZomeName>) {
+        self.0.set_init_dependency(zome_name);
+    }
 }
 
 impl CoordinatorZomeDef {
@@ -207,6 +213,11 @@ impl CoordinatorZomeDef {
             ZomeDef::Inline { dependencies, .. } => dependencies.push(zome_name.into()),
         }
     }
+
+    /// Run this zome's `init` after another zome's.
+    pub fn set_init_dependency(&mut self, zome_name: impl Into<ZomeName>) {
+        self.0.set_init_dependency(zome_name);
+    }
 }
 
 #[cfg(feature = "full-dna-def")]
@@ -215,6 +226,7 @@ impl From<InlineIntegrityZome> for ZomeDef {
         Self::Inline {
             inline_zome: inline_zome::DynInlineZome(Arc::new(iz)),
             dependencies: Default::default(),
+            init_after: Default::default(),
         }
     }
 }
@@ -225,6 +237,7 @@ impl From<InlineIntegrityZome> for IntegrityZomeDef {
         Self(ZomeDef::Inline {
             inline_zome: inline_zome::DynInlineZome(Arc::new(iz)),
             dependencies: Default::default(),
+            init_after: Default::default(),
         })
     }
 }
@@ -235,6 +248,7 @@ impl From<crate::InlineCoordinatorZome> for ZomeDef {
         Self::Inline {
             inline_zome: inline_zome::DynInlineZome(Arc::new(iz)),
             dependencies: Default::default(),
+            init_after: Default::default(),
         }
     }
 }
@@ -245,6 +259,7 @@ impl From<crate::InlineCoordinatorZome> for CoordinatorZomeDef {
         Self(ZomeDef::Inline {
             inline_zome: inline_zome::DynInlineZome(Arc::new(iz)),
             dependencies: Default::default(),
+            init_after: Default::default(),
         })
     }
 }
@@ -271,6 +286,24 @@ impl ZomeDef {
             ZomeDef::Inline { dependencies, .. } => &dependencies[..],
         }
     }
+
+    /// Get the zomes whose `init` must run before this zome's.
+    pub fn init_after(&self) -> &[ZomeName] {
+        match self {
+            ZomeDef::Wasm(WasmZome { init_after, .. }) => &init_after[..],
+            #[cfg(feature = "full-dna-def")]
+            ZomeDef::Inline { init_after, .. } => &init_after[..],
+        }
+    }
+
+    /// Run this zome's `init` after another zome's.
+    pub fn set_init_dependency(&mut self, zome_name: impl Into<ZomeName>) {
+        match self {
+            ZomeDef::Wasm(WasmZome { init_after, .. }) => init_after.push(zome_name.into()),
+            #[cfg(feature = "full-dna-def")]
+            ZomeDef::Inline { init_after, .. } => init_after.push(zome_name.into()),
+        }
+    }
 }
 
 impl IntegrityZomeDef {
@@ -328,6 +361,10 @@ pub struct WasmZome {
     pub wasm_hash: holo_hash::WasmHash,
     /// Integrity zomes this zome depends on.
     pub dependencies: Vec<ZomeName>,
+    /// Zomes whose `init` callback must run before this zome's.
+    /// Skipped when empty so the hash of existing integrity zomes is unchanged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub init_after: Vec<ZomeName>,
 }
 
 impl WasmZome {
@@ -336,6 +373,7 @@ impl WasmZome {
         Self {
             wasm_hash,
             dependencies: Default::default(),
+            init_after: Default::default(),
         }
     }
 }
@@ -346,6 +384,7 @@ impl ZomeDef {
         Self::Wasm(WasmZome {
             wasm_hash,
             dependencies: Default::default(),
+            init_after: Default::default(),
         })
     }
 }
@@ -11,6 +11,14 @@ pub enum ZomeError {
     #[error("Accessed a zome expecting to find a WasmZome, but found other type. Zome name: {0}")]
     NonWasmZome(ZomeName),
 
+    /// A zome's `init` is set to run after a zome that doesn't exist.
+    #[error("Zome {1} is set to init after {0} which is not another zome in this DNA")]
+    DanglingInitDependency(ZomeName, ZomeName),
+
+    /// The `init` dependencies of these zomes form a cycle.
+    #[error("The init dependencies of these zomes form a cycle: {0:?}")]
+    InitDependencyCycle(Vec<ZomeName>),
+
     /// SerializedBytesError (can occur during DnaDef::modify_phenotype)
     #[error(transparent)]
     SerializedBytesError(#[from] holochain_serialized_bytes::SerializedBytesError),
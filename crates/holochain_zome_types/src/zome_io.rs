@@ -43,10 +43,13 @@ wasm_io_types! {
     // Info about the calling agent.
     fn agent_info (()) -> zt::info::AgentInfo;
 
-    // @todo
+    // Info about the installed app of the calling cell.
+    fn app_info (()) -> zt::info::AppInfo;
+
+    // Info about the current DNA.
     fn dna_info (()) -> zt::info::DnaInfo;
 
-    // @todo
+    // Info about the current call.
     fn call_info (()) -> zt::info::CallInfo;
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
@@ -82,10 +85,16 @@ wasm_io_types! {
     // Emit a Signal::App to subscribers on the interface
     fn emit_signal (zt::signal::AppSignal) -> ();
 
+    // Emit a Signal::AppAckRequired which clients must acknowledge.
+    fn emit_signal_ack_required (zt::signal::AppSignal) -> ();
+
     fn get_agent_activity (zt::agent_activity::GetAgentActivityInput) -> zt::query::AgentActivity;
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
 
+    // Attempt to get a live entry from the cascade and say why it is missing.
+    fn get_outcome (Vec<zt::entry::GetInput>) -> Vec<zt::entry::GetOutcome<zt::record::Record>>;
+
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
     // Get links by entry hash from the cascade.
@@ -106,6 +115,9 @@ wasm_io_types! {
     // Retrieve an action from the DHT or short circuit.
     fn must_get_action (zt::entry::MustGetActionInput) -> zt::SignedActionHashed;
 
+    // Retrieve the validation package of an action or short circuit.
+    fn must_get_validation_package (zt::validate::MustGetValidationPackageInput) -> zt::validate::ValidationPackage;
+
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<crate::Record>;
 
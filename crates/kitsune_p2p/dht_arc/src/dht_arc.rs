@@ -133,6 +133,23 @@ impl DhtArc {
         *self = Self::from_start_and_len(self.start_loc(), new_length)
     }
 
+    /// Grow or shrink the arc from its start so that its coverage
+    /// is between `min` and `max` (as fractions of the full circle).
+    /// Returns true if the length changed.
+    pub fn clamp_coverage(&mut self, min: f64, max: f64) -> bool {
+        let coverage = self.coverage();
+        let target = if coverage < min {
+            min
+        } else if coverage > max {
+            max
+        } else {
+            return false;
+        };
+        let new_length = (target.clamp(0.0, 1.0) * U32_LEN as f64).round() as u64;
+        self.update_length(new_length);
+        true
+    }
+
     /// Get the range of the arc
     pub fn range(&self) -> ArcRange {
         match (self.0, self.1) {
@@ -489,6 +506,30 @@ mod tests {
         assert_eq!(DhtArcRange::from_bounds(2, 0).length(), full - 1);
     }
 
+    #[test]
+    fn test_clamp_coverage() {
+        let full = 2u64.pow(32);
+        let mut arc = DhtArc::from_start_and_len(100, full / 10);
+
+        assert!(!arc.clamp_coverage(0.0, 1.0));
+        assert_eq!(arc.length(), full / 10);
+
+        assert!(arc.clamp_coverage(0.5, 1.0));
+        assert_eq!(arc.length(), full / 2);
+        assert_eq!(arc.start_loc(), DhtLocation::new(100));
+
+        assert!(arc.clamp_coverage(0.0, 0.25));
+        assert_eq!(arc.length(), full / 4);
+
+        let mut arc = DhtArc::empty(DhtLocation::new(100));
+        assert!(arc.clamp_coverage(1.0, 1.0));
+        assert!(arc.is_full());
+
+        let mut arc = DhtArc::full(DhtLocation::new(100));
+        assert!(arc.clamp_coverage(0.0, 0.0));
+        assert!(arc.is_empty());
+    }
+
     #[test]
     fn test_ascii() {
         let cent = u32::MAX / 100 + 1;
@@ -1,8 +1,15 @@
 use super::*;
 use futures::stream::StreamExt;
+use kitsune_p2p_types::dependencies::lair_keystore_api::dependencies::url;
+use kitsune_p2p_types::dependencies::lair_keystore_api::ipc_keystore::ipc_keystore_connect;
+use kitsune_p2p_types::dependencies::lair_keystore_api::LairClient;
 
 pub(crate) async fn run(opt: KdOptNode) -> KdResult<()> {
-    let persist = new_persist_mem();
+    let persist = match (&opt.persist_path, &opt.lair_connection_url) {
+        (Some(path), Some(url)) => new_persist_sqlite(path, connect_lair(url).await?)?,
+        (Some(_), None) => return Err("--persist-path requires --lair-connection-url".into()),
+        (None, _) => new_persist_mem(),
+    };
 
     let conf = KitsuneDirectV1Config {
         tuning_params: Default::default(),
@@ -33,6 +40,22 @@ pub(crate) async fn run(opt: KdOptNode) -> KdResult<()> {
     Ok(())
 }
 
+/// connect to lair, reading its passphrase from stdin
+async fn connect_lair(url: &str) -> KdResult<LairClient> {
+    let url = url::Url::parse(url).map_err(KdError::other)?;
+    let passphrase = tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).map(|_| line)
+    })
+    .await
+    .map_err(KdError::other)?
+    .map_err(KdError::other)?;
+    let passphrase = sodoken::BufRead::new_no_lock(passphrase.trim_end().as_bytes());
+    ipc_keystore_connect(url, passphrase)
+        .await
+        .map_err(KdError::other)
+}
+
 const ICON: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
 <svg version="1.1" xmlns="http://www.w3.org/2000/svg" width="256" height="256">
     <path d="M 24 16 L 24 240 L 48 240 L 48 152 L 104 240 L 192 240 L 240 128 L 192 16 L 104 16 L 48 104 L 48 16 L 24 16 z M 128 32 L 128 224 L 64 128 L 128 32 z M 152 32 L 176 32 L 216 128 L 176 224 L 152 224 L 152 32 z " />
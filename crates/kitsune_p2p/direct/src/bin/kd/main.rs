@@ -14,6 +14,17 @@ struct KdOptNode {
 
     /// You must specify a proxy address to connect to
     proxy_url: String,
+
+    /// Keep the node's entries and public keys in a sqlite database at this
+    /// path so they survive restarts. Without it they are only kept in memory.
+    /// The signing keys are kept in the lair keystore given by
+    /// --lair-connection-url, whose passphrase is read from stdin.
+    #[structopt(long, requires = "lair-connection-url")]
+    persist_path: Option<std::path::PathBuf>,
+
+    /// The connection url of the lair keystore to keep signing keys in.
+    #[structopt(long)]
+    lair_connection_url: Option<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -6,7 +6,7 @@ use futures::stream::{BoxStream, StreamExt};
 use kitsune_p2p_direct_api::kd_entry::KdEntryBinary;
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::tx2::tx2_utils::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Create a new KitsuneDirect controller handle over the websocket channel.
@@ -151,6 +151,35 @@ impl AsKdHnd for Hnd {
         .boxed()
     }
 
+    fn keypair_list_tags(&self) -> BoxFuture<'static, KdResult<BTreeMap<String, KdHash>>> {
+        let msg_id = new_msg_id();
+        let api = KdApi::KeypairListTagsReq { msg_id };
+        let api = self.request(api);
+        async move {
+            match api.await {
+                Ok(KdApi::KeypairListTagsRes { tags, .. }) => Ok(tags),
+                oth => Err(format!("unexpected: {:?}", oth).into()),
+            }
+        }
+        .boxed()
+    }
+
+    fn keypair_delete_tag(&self, tag: &str) -> BoxFuture<'static, KdResult<()>> {
+        let msg_id = new_msg_id();
+        let api = KdApi::KeypairDeleteTagReq {
+            msg_id,
+            tag: tag.to_string(),
+        };
+        let api = self.request(api);
+        async move {
+            match api.await {
+                Ok(KdApi::KeypairDeleteTagRes { .. }) => Ok(()),
+                oth => Err(format!("unexpected: {:?}", oth).into()),
+            }
+        }
+        .boxed()
+    }
+
     fn app_join(&self, root: KdHash, agent: KdHash) -> BoxFuture<'static, KdResult<()>> {
         let msg_id = new_msg_id();
         let api = KdApi::AppJoinReq {
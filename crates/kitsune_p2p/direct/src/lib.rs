@@ -16,6 +16,9 @@ pub mod types;
 mod persist_mem;
 pub use persist_mem::*;
 
+mod persist_sqlite;
+pub use persist_sqlite::*;
+
 mod srv;
 pub use srv::*;
 
@@ -38,6 +41,7 @@ pub mod dependencies {
 pub mod prelude {
     pub use crate::handle_ws::*;
     pub use crate::persist_mem::*;
+    pub use crate::persist_sqlite::*;
     pub use crate::srv::*;
     pub use crate::types::direct::{KitsuneDirect, KitsuneDirectDriver};
     pub use crate::types::handle::{KdHnd, KdHndEvt, KdHndEvtStream};
@@ -5,11 +5,12 @@ use crate::*;
 use futures::future::{BoxFuture, FutureExt};
 use kitsune_p2p::dht::spacetime::Topology;
 use kitsune_p2p::dht_arc::{DhtArcSet, DhtLocation};
-use kitsune_p2p::event::TimeWindow;
+use kitsune_p2p::event::{MetricQuery, MetricQueryAnswer, MetricRecord, TimeWindow};
 use kitsune_p2p_types::dht::PeerStrat;
 use kitsune_p2p_types::tls::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 /// construct a new in-memory persistence module for kitsune direct
@@ -17,6 +18,111 @@ pub fn new_persist_mem() -> KdPersist {
     KdPersist(PersistMem::new())
 }
 
+/// the favicon served by kitsune direct persistence modules
+pub(crate) const FAVICON_SVG: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<svg version="1.1" xmlns="http://www.w3.org/2000/svg" width="256" height="256">
+    <path d="M 24 16 L 24 240 L 48 240 L 48 152 L 104 240 L 192 240 L 240 128 L 192 16 L 104 16 L 48 104 L 48 16 L 24 16 z M 128 32 L 128 224 L 64 128 L 128 32 z M 152 32 L 176 32 L 216 128 L 176 224 L 152 224 L 152 32 z " />
+</svg>"#;
+
+/// is this the path of the app index page
+pub(crate) fn is_index_path(path: &str) -> bool {
+    path.is_empty() || path == "/" || path == "/index.html"
+}
+
+/// render the app index page listing these roots
+pub(crate) fn index_html(roots: Vec<KdHash>) -> Vec<u8> {
+    let roots = roots
+        .into_iter()
+        .map(|h| format!(r#"<li><a href="/{}/index.html">{}</a></li>"#, h, h))
+        .collect::<Vec<_>>();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="UTF-8" />
+    <link rel="icon" type="image/svg+xml" href="favicon.svg" />
+  </head>
+  <body>
+    <h1>App Index:</h1>
+    <ul>
+      {}
+    </ul>
+  </body>
+</html>"#,
+        roots.join("\n")
+    )
+    .into_bytes()
+}
+
+/// generate a signing keypair, returning the pub key as a KdHash
+/// along with the secret key
+pub(crate) async fn new_signing_keypair() -> KdResult<(KdHash, sodoken::BufReadSized<64>)> {
+    let pk = sodoken::BufWriteSized::new_no_lock();
+    let sk = sodoken::BufWriteSized::new_mem_locked().map_err(KdError::other)?;
+
+    sodoken::sign::keypair(pk.clone(), sk.clone())
+        .await
+        .map_err(KdError::other)?;
+
+    let mut pk_hash = [0; 32];
+    pk_hash.copy_from_slice(&pk.read_lock()[0..32]);
+    let pk_hash = KdHash::from_coerced_pubkey(pk_hash)
+        .await
+        .map_err(KdError::other)?;
+
+    Ok((pk_hash, sk.to_read_sized()))
+}
+
+/// sign data with a secret key
+pub(crate) async fn sign_detached(
+    sk: sodoken::BufReadSized<64>,
+    data: sodoken::BufRead,
+) -> KdResult<Arc<[u8; 64]>> {
+    let sig = <sodoken::BufWriteSized<64>>::new_no_lock();
+    sodoken::sign::detached(sig.clone(), data, sk)
+        .await
+        .map_err(KdError::other)?;
+    let mut out = [0; 64];
+    out.copy_from_slice(&sig.read_lock());
+    Ok(Arc::new(out))
+}
+
+/// the `limit` agents whose storage arcs are nearest to a basis location
+pub(crate) fn agent_info_near_basis(
+    agent_info_list: Vec<KdAgentInfo>,
+    basis_loc: u32,
+    limit: u32,
+) -> Vec<KdAgentInfo> {
+    let mut with_dist = agent_info_list
+        .into_iter()
+        .map(|info| (info.basis_distance_to_storage(basis_loc.into()), info))
+        .collect::<Vec<_>>();
+    with_dist.sort_by_key(|a| a.0);
+    with_dist
+        .into_iter()
+        .map(|(_, info)| info)
+        .take(limit as usize)
+        .collect()
+}
+
+/// the peer view of an arc given the agents in a space
+pub(crate) fn peer_view(
+    agent_info_list: Vec<KdAgentInfo>,
+    dht_arc: DhtArc,
+) -> kitsune_p2p_types::dht::PeerView {
+    let topo = Topology::standard_epoch_full();
+    let arcs: Vec<_> = agent_info_list
+        .into_iter()
+        .map(|v| {
+            let loc = DhtLocation::from(v.agent().as_loc());
+            DhtArc::from_parts(*v.storage_arc(), loc)
+        })
+        .collect();
+
+    // contains is already checked in the iterator
+    PeerStrat::default().view(topo, dht_arc, arcs.as_slice())
+}
+
 // -- private -- //
 
 struct AgentStoreInner {
@@ -106,10 +212,17 @@ impl EntryStore {
             .share_mut(move |i, _| Ok(i.hash_to_entry_map.values().cloned().collect()))
             .map_err(KdError::other)
     }
+
+    pub fn get_all_hashes(&self) -> KdResult<Vec<KdHash>> {
+        self.0
+            .share_mut(move |i, _| Ok(i.hash_to_entry_map.keys().cloned().collect()))
+            .map_err(KdError::other)
+    }
 }
 
 struct AgentEntryStoreInner {
     agent_to_entry_store_map: HashMap<KdHash, Arc<EntryStore>>,
+    parent_to_child_map: HashMap<KdHash, HashMap<KdHash, KdEntrySigned>>,
 }
 
 struct AgentEntryStore(Share<AgentEntryStoreInner>);
@@ -118,6 +231,7 @@ impl AgentEntryStore {
     pub fn new() -> Arc<Self> {
         Arc::new(Self(Share::new(AgentEntryStoreInner {
             agent_to_entry_store_map: HashMap::new(),
+            parent_to_child_map: HashMap::new(),
         })))
     }
 
@@ -140,6 +254,37 @@ impl AgentEntryStore {
             })
             .map_err(KdError::other)
     }
+
+    pub fn insert_child(&self, entry_signed: KdEntrySigned) -> KdResult<()> {
+        self.0
+            .share_mut(move |i, _| {
+                i.parent_to_child_map
+                    .entry(entry_signed.parent().clone())
+                    .or_insert_with(HashMap::new)
+                    .insert(entry_signed.hash().clone(), entry_signed);
+                Ok(())
+            })
+            .map_err(KdError::other)
+    }
+
+    pub fn get_children(
+        &self,
+        parent: &KdHash,
+        kind: Option<&str>,
+    ) -> KdResult<Vec<KdEntrySigned>> {
+        self.0
+            .share_mut(move |i, _| {
+                Ok(match i.parent_to_child_map.get(parent) {
+                    None => vec![],
+                    Some(children) => children
+                        .values()
+                        .filter(|e| kind.map(|kind| e.kind() == kind).unwrap_or(true))
+                        .cloned()
+                        .collect(),
+                })
+            })
+            .map_err(KdError::other)
+    }
 }
 
 struct UiEntry {
@@ -202,6 +347,7 @@ impl UiStore {
 struct PersistMemInner {
     tls: Option<TlsConfig>,
     priv_keys: HashMap<KdHash, sodoken::BufReadSized<64>>,
+    tags: BTreeMap<String, KdHash>,
     agent_info: HashMap<KdHash, Arc<AgentStore>>,
     entries: HashMap<KdHash, Arc<AgentEntryStore>>,
     ui_cache: Arc<UiStore>,
@@ -215,6 +361,7 @@ impl PersistMem {
             Share::new(PersistMemInner {
                 tls: None,
                 priv_keys: HashMap::new(),
+                tags: BTreeMap::new(),
                 agent_info: HashMap::new(),
                 entries: HashMap::new(),
                 ui_cache: UiStore::new(),
@@ -267,23 +414,12 @@ impl AsKdPersist for PersistMem {
     fn generate_signing_keypair(&self) -> BoxFuture<'static, KdResult<KdHash>> {
         let inner = self.0.clone();
         async move {
-            let pk = sodoken::BufWriteSized::new_no_lock();
-            let sk = sodoken::BufWriteSized::new_mem_locked().map_err(KdError::other)?;
-
-            sodoken::sign::keypair(pk.clone(), sk.clone())
-                .await
-                .map_err(KdError::other)?;
-
-            let mut pk_hash = [0; 32];
-            pk_hash.copy_from_slice(&pk.read_lock()[0..32]);
-            let pk_hash = KdHash::from_coerced_pubkey(pk_hash)
-                .await
-                .map_err(KdError::other)?;
+            let (pk_hash, sk) = new_signing_keypair().await?;
 
             let pk_hash_clone = pk_hash.clone();
             inner
                 .share_mut(move |i, _| {
-                    i.priv_keys.insert(pk_hash_clone, sk.to_read_sized());
+                    i.priv_keys.insert(pk_hash_clone, sk);
                     Ok(())
                 })
                 .map_err(KdError::other)?;
@@ -293,6 +429,37 @@ impl AsKdPersist for PersistMem {
         .boxed()
     }
 
+    fn get_or_create_tagged_keypair(&self, tag: &str) -> BoxFuture<'static, KdResult<KdHash>> {
+        let tag = tag.to_string();
+        let existing = self.0.share_mut(|i, _| Ok(i.tags.get(&tag).cloned()));
+        let new_keypair = self.generate_signing_keypair();
+        let inner = self.0.clone();
+        async move {
+            if let Some(pub_key) = existing.map_err(KdError::other)? {
+                return Ok(pub_key);
+            }
+            let pub_key = new_keypair.await?;
+            // if another task got there first, keep its keypair
+            inner
+                .share_mut(move |i, _| Ok(i.tags.entry(tag).or_insert(pub_key).clone()))
+                .map_err(KdError::other)
+        }
+        .boxed()
+    }
+
+    fn list_tags(&self) -> BoxFuture<'static, KdResult<BTreeMap<String, KdHash>>> {
+        let tags = self.0.share_mut(|i, _| Ok(i.tags.clone()));
+        async move { tags.map_err(KdError::other) }.boxed()
+    }
+
+    fn delete_tag(&self, tag: &str) -> BoxFuture<'static, KdResult<()>> {
+        let r = self.0.share_mut(|i, _| {
+            i.tags.remove(tag);
+            Ok(())
+        });
+        async move { r.map_err(KdError::other) }.boxed()
+    }
+
     fn sign(&self, pub_key: KdHash, data: &[u8]) -> BoxFuture<'static, KdResult<Arc<[u8; 64]>>> {
         let data = sodoken::BufRead::new_no_lock(data);
         let sk = self
@@ -304,13 +471,7 @@ impl AsKdPersist for PersistMem {
                 None => return Err(format!("invalid agent: {:?}", pub_key).into()),
                 Some(sk) => sk,
             };
-            let sig = <sodoken::BufWriteSized<64>>::new_no_lock();
-            sodoken::sign::detached(sig.clone(), data, sk)
-                .await
-                .map_err(KdError::other)?;
-            let mut out = [0; 64];
-            out.copy_from_slice(&*sig.read_lock());
-            Ok(Arc::new(out))
+            sign_detached(sk, data).await
         }
         .boxed()
     }
@@ -368,17 +529,7 @@ impl AsKdPersist for PersistMem {
                 Err(_) => return Ok(vec![]),
                 Ok(store) => store,
             };
-            let mut with_dist = store
-                .get_all()?
-                .into_iter()
-                .map(|info| (info.basis_distance_to_storage(basis_loc.into()), info))
-                .collect::<Vec<_>>();
-            with_dist.sort_by(|a, b| a.0.cmp(&b.0));
-            Ok(with_dist
-                .into_iter()
-                .map(|(_, info)| info)
-                .take(limit as usize)
-                .collect())
+            Ok(agent_info_near_basis(store.get_all()?, basis_loc, limit))
         }
         .boxed()
     }
@@ -388,27 +539,16 @@ impl AsKdPersist for PersistMem {
         root: KdHash,
         dht_arc: kitsune_p2p_types::dht_arc::DhtArc,
     ) -> BoxFuture<'static, KdResult<kitsune_p2p_types::dht::PeerView>> {
-        let topo = Topology::standard_epoch_full();
         let store = self.0.share_mut(move |i, _| match i.agent_info.get(&root) {
             Some(store) => Ok(store.clone()),
             None => Err("root not found".into()),
         });
         async move {
             let store = match store {
-                Err(_) => return Ok(PeerStrat::default().view(topo.clone(), dht_arc, &[])),
+                Err(_) => return Ok(peer_view(Vec::new(), dht_arc)),
                 Ok(store) => store,
             };
-            let arcs: Vec<_> = store
-                .get_all()?
-                .into_iter()
-                .map(|v| {
-                    let loc = DhtLocation::from(v.agent().as_loc());
-                    DhtArc::from_parts(*v.storage_arc(), loc)
-                })
-                .collect();
-
-            // contains is already checked in the iterator
-            Ok(PeerStrat::default().view(topo, dht_arc, arcs.as_slice()))
+            Ok(peer_view(store.get_all()?, dht_arc))
         }
         .boxed()
     }
@@ -432,6 +572,7 @@ impl AsKdPersist for PersistMem {
         async move {
             let (ui_cache, agent_map) = r.map_err(KdError::other)?;
             let _ = ui_cache.check_add(&root, &entry);
+            agent_map.insert_child(entry.clone())?;
             agent_map.get_mut(agent)?.insert(entry)
         }
         .boxed()
@@ -473,45 +614,61 @@ impl AsKdPersist for PersistMem {
         .boxed()
     }
 
+    fn query_entry_hashes(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        _window: TimeWindow,
+        _dht_arc: DhtArcSet,
+    ) -> BoxFuture<'static, KdResult<Vec<KdHash>>> {
+        // TODO - actually filter
+
+        let agent_map = self.0.share_mut(move |i, _| match i.entries.get(&root) {
+            Some(agent_map) => Ok(agent_map.clone()),
+            None => Err("root not found".into()),
+        });
+        async move {
+            let agent_map = match agent_map {
+                Err(_) => return Ok(vec![]),
+                Ok(agent_map) => agent_map,
+            };
+            match agent_map.get(&agent) {
+                // remote agents hold no entries in this store
+                Err(_) => Ok(vec![]),
+                Ok(entry_store) => entry_store.get_all_hashes(),
+            }
+        }
+        .boxed()
+    }
+
+    fn query_entry_children(
+        &self,
+        root: KdHash,
+        parent: KdHash,
+        kind: Option<String>,
+    ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>> {
+        let agent_map = self
+            .0
+            .share_mut(move |i, _| Ok(i.entries.get(&root).cloned()));
+        async move {
+            match agent_map.map_err(KdError::other)? {
+                None => Ok(vec![]),
+                Some(agent_map) => agent_map.get_children(&parent, kind.as_deref()),
+            }
+        }
+        .boxed()
+    }
+
     fn get_ui_file(&self, path: &str) -> BoxFuture<'static, KdResult<(String, Vec<u8>)>> {
         if path == "/favicon.svg" {
-            return async move {
-                Ok((
-                    "image/svg+xml".to_string(),
-                    br#"<?xml version="1.0" encoding="UTF-8"?>
-<svg version="1.1" xmlns="http://www.w3.org/2000/svg" width="256" height="256">
-    <path d="M 24 16 L 24 240 L 48 240 L 48 152 L 104 240 L 192 240 L 240 128 L 192 16 L 104 16 L 48 104 L 48 16 L 24 16 z M 128 32 L 128 224 L 64 128 L 128 32 z M 152 32 L 176 32 L 216 128 L 176 224 L 152 224 L 152 32 z " />
-</svg>"#.to_vec(),
-                ))
-            }.boxed();
-        } else if path.is_empty() || path == "/" || path == "/index.html" {
+            return async move { Ok(("image/svg+xml".to_string(), FAVICON_SVG.to_vec())) }.boxed();
+        } else if is_index_path(path) {
             let roots = self
                 .0
                 .share_mut(|i, _| Ok(i.entries.keys().cloned().collect::<Vec<_>>()));
             return async move {
-                let roots = roots
-                    .map_err(KdError::other)?
-                    .into_iter()
-                    .map(|h| format!(r#"<li><a href="/{}/index.html">{}</a></li>"#, h, h))
-                    .collect::<Vec<_>>();
-                let content = format!(
-                    r#"<!DOCTYPE html>
-<html>
-  <head>
-    <meta charset="UTF-8" />
-    <link rel="icon" type="image/svg+xml" href="favicon.svg" />
-  </head>
-  <body>
-    <h1>App Index:</h1>
-    <ul>
-      {}
-    </ul>
-  </body>
-</html>"#,
-                    roots.join("\n")
-                )
-                .into_bytes();
-                Ok(("text/html".to_string(), content))
+                let roots = roots.map_err(KdError::other)?;
+                Ok(("text/html".to_string(), index_html(roots)))
             }
             .boxed();
         }
@@ -525,4 +682,22 @@ impl AsKdPersist for PersistMem {
         }
         .boxed()
     }
+
+    // metrics are not kept in memory
+
+    fn record_metrics(
+        &self,
+        _root: KdHash,
+        _records: Vec<MetricRecord>,
+    ) -> BoxFuture<'static, KdResult<()>> {
+        async move { Ok(()) }.boxed()
+    }
+
+    fn query_metrics(
+        &self,
+        _root: KdHash,
+        query: MetricQuery,
+    ) -> BoxFuture<'static, KdResult<MetricQueryAnswer>> {
+        async move { Ok(query.empty_answer()) }.boxed()
+    }
 }
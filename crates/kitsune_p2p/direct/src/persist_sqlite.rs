@@ -0,0 +1,1087 @@
+//! sqlite persistence module for kitsune direct
+
+use crate::persist_mem::{
+    agent_info_near_basis, index_html, is_index_path, peer_view, FAVICON_SVG,
+};
+use crate::types::persist::*;
+use crate::*;
+use futures::future::{BoxFuture, FutureExt};
+use kitsune_p2p::dependencies::kitsune_p2p_timestamp::Timestamp;
+use kitsune_p2p::dht_arc::DhtArcSet;
+use kitsune_p2p::event::{
+    MetricQuery, MetricQueryAnswer, MetricRecord, MetricRecordKind, TimeWindow,
+};
+use kitsune_p2p_types::bin_types::{KitsuneAgent, KitsuneBinType};
+use kitsune_p2p_types::dependencies::lair_keystore_api::LairClient;
+use kitsune_p2p_types::tls::*;
+use rusqlite::{named_params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// open a sqlite persistence module for kitsune direct,
+/// creating the database file if it does not exist
+/// and migrating it to the latest schema if it does.
+///
+/// Signing keypairs are generated in and used through lair,
+/// only their public keys are stored in the database.
+pub fn new_persist_sqlite<P: AsRef<Path>>(path: P, lair: LairClient) -> KdResult<KdPersist> {
+    let mut cons = Vec::with_capacity(POOL_SIZE);
+    for _ in 0..POOL_SIZE {
+        let con = Connection::open(path.as_ref()).map_err(KdError::other)?;
+        con.pragma_update(None, "journal_mode", "WAL")
+            .map_err(KdError::other)?;
+        con.busy_timeout(std::time::Duration::from_secs(30))
+            .map_err(KdError::other)?;
+        cons.push(con);
+    }
+    migrate(&mut cons[0])?;
+    Ok(KdPersist(Arc::new(PersistSqlite {
+        pool: Arc::new(ConPool {
+            permits: Arc::new(tokio::sync::Semaphore::new(POOL_SIZE)),
+            cons: std::sync::Mutex::new(cons),
+        }),
+        lair,
+        closed: AtomicBool::new(false),
+        uniq: Uniq::default(),
+    })))
+}
+
+// -- private -- //
+
+/// Each migration moves the schema up one version.
+/// Append new migrations, never edit released ones.
+const MIGRATIONS: &[&str] = &[r#"
+CREATE TABLE tls (
+    id              INTEGER     PRIMARY KEY CHECK (id = 0),
+    cert            BLOB        NOT NULL,
+    cert_priv_key   BLOB        NOT NULL,
+    cert_digest     BLOB        NOT NULL
+);
+
+-- signing secrets are kept in lair, only public keys are stored here
+CREATE TABLE keypair (
+    pub_key         TEXT        PRIMARY KEY
+);
+
+CREATE TABLE keypair_tag (
+    tag             TEXT        PRIMARY KEY,
+    pub_key         TEXT        NOT NULL REFERENCES keypair (pub_key)
+);
+
+CREATE TABLE agent_info (
+    root            TEXT        NOT NULL,
+    agent           TEXT        NOT NULL,
+    signed_at_ms    INTEGER     NOT NULL,
+    info            TEXT        NOT NULL,
+    PRIMARY KEY (root, agent)
+);
+
+CREATE TABLE entry (
+    root            TEXT        NOT NULL,
+    agent           TEXT        NOT NULL,
+    hash            TEXT        NOT NULL,
+    parent          TEXT        NOT NULL,
+    kind            TEXT        NOT NULL,
+    wire            BLOB        NOT NULL,
+    PRIMARY KEY (root, agent, hash)
+);
+
+CREATE INDEX entry_parent_idx ON entry (root, parent, kind);
+
+CREATE TABLE ui_file (
+    path            TEXT        PRIMARY KEY,
+    mime            TEXT        NOT NULL,
+    data            BLOB        NOT NULL
+);
+
+CREATE TABLE metric (
+    root                    TEXT        NOT NULL,
+    kind                    TEXT        NOT NULL,
+    agent                   BLOB,
+    recorded_at_utc_micros  INTEGER     NOT NULL,
+    expires_at_utc_micros   INTEGER     NOT NULL,
+    data                    TEXT
+);
+
+CREATE INDEX metric_kind_idx ON metric (root, kind, recorded_at_utc_micros);
+CREATE INDEX metric_expires_idx ON metric (expires_at_utc_micros);
+"#];
+
+fn migrate(con: &mut Connection) -> KdResult<()> {
+    let txn = con.transaction().map_err(KdError::other)?;
+    let version: usize = txn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(KdError::other)?;
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "database schema version {} is newer than this kdirect ({})",
+            version,
+            MIGRATIONS.len()
+        )
+        .into());
+    }
+    for migration in &MIGRATIONS[version..] {
+        txn.execute_batch(migration).map_err(KdError::other)?;
+    }
+    txn.pragma_update(None, "user_version", MIGRATIONS.len())
+        .map_err(KdError::other)?;
+    txn.commit().map_err(KdError::other)
+}
+
+/// How many connections are opened on the database.
+/// Queries beyond this many wait for a connection to be free.
+const POOL_SIZE: usize = 4;
+
+struct ConPool {
+    permits: Arc<tokio::sync::Semaphore>,
+    cons: std::sync::Mutex<Vec<Connection>>,
+}
+
+impl ConPool {
+    /// run a query on a blocking thread, with a connection to itself
+    async fn run<R, F>(self: Arc<Self>, f: F) -> KdResult<R>
+    where
+        R: 'static + Send,
+        F: 'static + Send + FnOnce(&mut Connection) -> rusqlite::Result<R>,
+    {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(KdError::other)?;
+        // a permit guarantees a free connection, the lock is only held to take it
+        let mut con = self
+            .cons
+            .lock()
+            .map_err(|_| KdError::from("persist poisoned"))?
+            .pop()
+            .ok_or_else(|| KdError::from("persist has no free connection"))?;
+        let res = tokio::task::spawn_blocking(move || {
+            let r = f(&mut con);
+            (con, r)
+        })
+        .await;
+        match res {
+            Ok((con, r)) => {
+                self.cons
+                    .lock()
+                    .map_err(|_| KdError::from("persist poisoned"))?
+                    .push(con);
+                r.map_err(KdError::other)
+            }
+            Err(e) => {
+                // the connection was lost with the panicking task,
+                // so shrink the pool to match
+                permit.forget();
+                Err(KdError::other(e))
+            }
+        }
+    }
+}
+
+struct PersistSqlite {
+    pool: Arc<ConPool>,
+    lair: LairClient,
+    closed: AtomicBool,
+    uniq: Uniq,
+}
+
+impl PersistSqlite {
+    /// run a query on a blocking thread
+    fn with_con<R, F>(&self, f: F) -> BoxFuture<'static, KdResult<R>>
+    where
+        R: 'static + Send,
+        F: 'static + Send + FnOnce(&mut Connection) -> rusqlite::Result<R>,
+    {
+        if self.is_closed() {
+            return async move { Err("persist closed".into()) }.boxed();
+        }
+        self.pool.clone().run(f).boxed()
+    }
+
+    fn query_agent_info_sync(con: &Connection, root: &str) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = con.prepare_cached("SELECT info FROM agent_info WHERE root = :root")?;
+        let out = stmt
+            .query_map(named_params! { ":root": root }, |row| row.get(0))?
+            .collect();
+        out
+    }
+}
+
+fn decode_agent_info(list: Vec<String>) -> KdResult<Vec<KdAgentInfo>> {
+    list.iter().map(|s| KdAgentInfo::from_str(s)).collect()
+}
+
+impl AsKdPersist for PersistSqlite {
+    fn uniq(&self) -> Uniq {
+        self.uniq
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    fn close(&self) -> BoxFuture<'static, ()> {
+        self.closed.store(true, Ordering::Release);
+        async move {}.boxed()
+    }
+
+    fn singleton_tls_config(&self) -> BoxFuture<'static, KdResult<TlsConfig>> {
+        let existing = self.with_con(|con| {
+            con.query_row(
+                "SELECT cert, cert_priv_key, cert_digest FROM tls WHERE id = 0",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+        });
+        let pool = self.pool.clone();
+        async move {
+            let (cert, cert_priv_key, cert_digest): (Vec<u8>, Vec<u8>, Vec<u8>) =
+                match existing.await? {
+                    Some(tls) => tls,
+                    None => {
+                        let tls = TlsConfig::new_ephemeral().await.map_err(KdError::other)?;
+                        let tls = (
+                            tls.cert.0.to_vec(),
+                            tls.cert_priv_key.0.to_vec(),
+                            tls.cert_digest.0.to_vec(),
+                        );
+                        // if another task got there first, keep its cert
+                        pool.run(move |con| {
+                            con.execute(
+                                "INSERT OR IGNORE INTO tls (id, cert, cert_priv_key, cert_digest)
+                                VALUES (0, :cert, :cert_priv_key, :cert_digest)",
+                                named_params! {
+                                    ":cert": tls.0,
+                                    ":cert_priv_key": tls.1,
+                                    ":cert_digest": tls.2,
+                                },
+                            )?;
+                            con.query_row(
+                                "SELECT cert, cert_priv_key, cert_digest FROM tls WHERE id = 0",
+                                [],
+                                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                            )
+                        })
+                        .await?
+                    }
+                };
+            Ok(TlsConfig {
+                cert: cert.into(),
+                cert_priv_key: cert_priv_key.into(),
+                cert_digest: cert_digest.into(),
+            })
+        }
+        .boxed()
+    }
+
+    fn generate_signing_keypair(&self) -> BoxFuture<'static, KdResult<KdHash>> {
+        let closed = self.is_closed();
+        let lair = self.lair.clone();
+        let pool = self.pool.clone();
+        async move {
+            if closed {
+                return Err("persist closed".into());
+            }
+            // lair tags must be unique, ours are only ever looked up by pub key
+            let tag = format!("kdirect-{:032x}", rand::random::<u128>());
+            let seed = lair
+                .new_seed(tag.into(), None, false)
+                .await
+                .map_err(KdError::other)?;
+            let pk_hash = KdHash::from_coerced_pubkey(*seed.ed25519_pub_key.0).await?;
+            let pub_key = pk_hash.to_string();
+            pool.run(move |con| {
+                con.execute(
+                    "INSERT INTO keypair (pub_key) VALUES (:pub_key)",
+                    named_params! { ":pub_key": pub_key },
+                )
+            })
+            .await?;
+            Ok(pk_hash)
+        }
+        .boxed()
+    }
+
+    fn get_or_create_tagged_keypair(&self, tag: &str) -> BoxFuture<'static, KdResult<KdHash>> {
+        let tag = tag.to_string();
+        let tag2 = tag.clone();
+        let existing = self.with_con(move |con| {
+            con.query_row(
+                "SELECT pub_key FROM keypair_tag WHERE tag = :tag",
+                named_params! { ":tag": tag2 },
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+        });
+        let new_keypair = self.generate_signing_keypair();
+        let pool = self.pool.clone();
+        async move {
+            if let Some(pub_key) = existing.await? {
+                return KdHash::from_str_slice(&pub_key);
+            }
+            let pub_key = new_keypair.await?.to_string();
+            // if another task got there first, keep its keypair
+            let pub_key = pool
+                .run(move |con| {
+                    con.execute(
+                        "INSERT OR IGNORE INTO keypair_tag (tag, pub_key) VALUES (:tag, :pub_key)",
+                        named_params! { ":tag": tag, ":pub_key": pub_key },
+                    )?;
+                    con.query_row(
+                        "SELECT pub_key FROM keypair_tag WHERE tag = :tag",
+                        named_params! { ":tag": tag },
+                        |row| row.get::<_, String>(0),
+                    )
+                })
+                .await?;
+            KdHash::from_str_slice(&pub_key)
+        }
+        .boxed()
+    }
+
+    fn list_tags(&self) -> BoxFuture<'static, KdResult<BTreeMap<String, KdHash>>> {
+        let list = self.with_con(|con| {
+            let mut stmt = con.prepare_cached("SELECT tag, pub_key FROM keypair_tag")?;
+            let out = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>();
+            out
+        });
+        async move {
+            list.await?
+                .into_iter()
+                .map(|(tag, pub_key)| Ok((tag, KdHash::from_str_slice(&pub_key)?)))
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn delete_tag(&self, tag: &str) -> BoxFuture<'static, KdResult<()>> {
+        let tag = tag.to_string();
+        self.with_con(move |con| {
+            con.execute(
+                "DELETE FROM keypair_tag WHERE tag = :tag",
+                named_params! { ":tag": tag },
+            )?;
+            Ok(())
+        })
+    }
+
+    fn sign(&self, pub_key: KdHash, data: &[u8]) -> BoxFuture<'static, KdResult<Arc<[u8; 64]>>> {
+        let data: Arc<[u8]> = data.into();
+        let key = pub_key.to_string();
+        // only sign with keypairs this store generated
+        let known = self.with_con(move |con| {
+            con.query_row(
+                "SELECT 1 FROM keypair WHERE pub_key = :pub_key",
+                named_params! { ":pub_key": key },
+                |_| Ok(()),
+            )
+            .optional()
+        });
+        let lair = self.lair.clone();
+        async move {
+            if known.await?.is_none() {
+                return Err(format!("invalid agent: {:?}", pub_key).into());
+            }
+            let sig = lair
+                .sign_by_pub_key((*pub_key.as_core_bytes()).into(), None, data)
+                .await
+                .map_err(KdError::other)?;
+            Ok(sig.0)
+        }
+        .boxed()
+    }
+
+    fn store_agent_info(&self, agent_info: KdAgentInfo) -> BoxFuture<'static, KdResult<()>> {
+        let root = agent_info.root().to_string();
+        let agent = agent_info.agent().to_string();
+        let signed_at_ms = agent_info.signed_at_ms();
+        let info = agent_info.to_string();
+        // only replace existing info with newer info, like the mem store
+        self.with_con(move |con| {
+            con.execute(
+                "INSERT INTO agent_info (root, agent, signed_at_ms, info)
+                VALUES (:root, :agent, :signed_at_ms, :info)
+                ON CONFLICT (root, agent) DO UPDATE SET
+                    signed_at_ms = excluded.signed_at_ms,
+                    info = excluded.info
+                WHERE excluded.signed_at_ms > agent_info.signed_at_ms",
+                named_params! {
+                    ":root": root,
+                    ":agent": agent,
+                    ":signed_at_ms": signed_at_ms,
+                    ":info": info,
+                },
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_agent_info(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+    ) -> BoxFuture<'static, KdResult<KdAgentInfo>> {
+        let info = self.with_con(move |con| {
+            con.query_row(
+                "SELECT info FROM agent_info WHERE root = :root AND agent = :agent",
+                named_params! { ":root": root.to_string(), ":agent": agent.to_string() },
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+        });
+        async move {
+            match info.await? {
+                None => Err("agent not found".into()),
+                Some(info) => KdAgentInfo::from_str(&info),
+            }
+        }
+        .boxed()
+    }
+
+    fn query_agent_info(&self, root: KdHash) -> BoxFuture<'static, KdResult<Vec<KdAgentInfo>>> {
+        let list = self.with_con(move |con| Self::query_agent_info_sync(con, root.as_ref()));
+        async move { decode_agent_info(list.await?) }.boxed()
+    }
+
+    fn query_agent_info_near_basis(
+        &self,
+        root: KdHash,
+        basis_loc: u32,
+        limit: u32,
+    ) -> BoxFuture<'static, KdResult<Vec<KdAgentInfo>>> {
+        let list = self.query_agent_info(root);
+        async move { Ok(agent_info_near_basis(list.await?, basis_loc, limit)) }.boxed()
+    }
+
+    fn query_peer_density(
+        &self,
+        root: KdHash,
+        dht_arc: kitsune_p2p_types::dht_arc::DhtArc,
+    ) -> BoxFuture<'static, KdResult<kitsune_p2p_types::dht::PeerView>> {
+        let list = self.query_agent_info(root);
+        async move { Ok(peer_view(list.await?, dht_arc)) }.boxed()
+    }
+
+    fn store_entry(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        entry: KdEntrySigned,
+    ) -> BoxFuture<'static, KdResult<()>> {
+        use kitsune_p2p_direct_api::kd_sys_kind::*;
+
+        let ui_file = match KdSysKind::from_kind(entry.kind(), entry.raw_data().clone()) {
+            Ok(KdSysKind::File(file)) => Some((
+                format!("/{}/{}", root, file.name),
+                file.mime,
+                entry.as_binary_ref().to_vec(),
+            )),
+            _ => None,
+        };
+        let hash = entry.hash().to_string();
+        let parent = entry.parent().to_string();
+        let kind = entry.kind().to_string();
+        let wire = entry.as_wire_data_ref().to_vec();
+        self.with_con(move |con| {
+            let txn = con.transaction()?;
+            txn.execute(
+                "INSERT OR REPLACE INTO entry (root, agent, hash, parent, kind, wire)
+                VALUES (:root, :agent, :hash, :parent, :kind, :wire)",
+                named_params! {
+                    ":root": root.to_string(),
+                    ":agent": agent.to_string(),
+                    ":hash": hash,
+                    ":parent": parent,
+                    ":kind": kind,
+                    ":wire": wire,
+                },
+            )?;
+            if let Some((path, mime, data)) = ui_file {
+                txn.execute(
+                    "INSERT OR REPLACE INTO ui_file (path, mime, data)
+                    VALUES (:path, :mime, :data)",
+                    named_params! { ":path": path, ":mime": mime, ":data": data },
+                )?;
+            }
+            txn.commit()
+        })
+    }
+
+    fn get_entry(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        hash: KdHash,
+    ) -> BoxFuture<'static, KdResult<KdEntrySigned>> {
+        let wire = self.with_con(move |con| {
+            con.query_row(
+                "SELECT wire FROM entry
+                WHERE root = :root AND agent = :agent AND hash = :hash",
+                named_params! {
+                    ":root": root.to_string(),
+                    ":agent": agent.to_string(),
+                    ":hash": hash.to_string(),
+                },
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()
+        });
+        async move {
+            match wire.await? {
+                None => Err("hash not found".into()),
+                Some(wire) => KdEntrySigned::from_wire_unchecked(wire.into_boxed_slice()),
+            }
+        }
+        .boxed()
+    }
+
+    fn query_entries(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        _window: TimeWindow,
+        _dht_arc: DhtArcSet,
+    ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>> {
+        // TODO - actually filter
+
+        let list = self.with_con(move |con| {
+            let mut stmt =
+                con.prepare_cached("SELECT wire FROM entry WHERE root = :root AND agent = :agent")?;
+            let out = stmt
+                .query_map(
+                    named_params! { ":root": root.to_string(), ":agent": agent.to_string() },
+                    |row| row.get::<_, Vec<u8>>(0),
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>();
+            out
+        });
+        async move {
+            list.await?
+                .into_iter()
+                .map(|wire| KdEntrySigned::from_wire_unchecked(wire.into_boxed_slice()))
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn query_entry_hashes(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        _window: TimeWindow,
+        _dht_arc: DhtArcSet,
+    ) -> BoxFuture<'static, KdResult<Vec<KdHash>>> {
+        // TODO - actually filter
+
+        let list = self.with_con(move |con| {
+            let mut stmt =
+                con.prepare_cached("SELECT hash FROM entry WHERE root = :root AND agent = :agent")?;
+            let out = stmt
+                .query_map(
+                    named_params! { ":root": root.to_string(), ":agent": agent.to_string() },
+                    |row| row.get::<_, String>(0),
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>();
+            out
+        });
+        async move {
+            list.await?
+                .iter()
+                .map(|hash| KdHash::from_str_slice(hash))
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn query_entry_children(
+        &self,
+        root: KdHash,
+        parent: KdHash,
+        kind: Option<String>,
+    ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>> {
+        // the same entry may be stored for more than one agent
+        let list = self.with_con(move |con| {
+            let mut stmt = con.prepare_cached(
+                "SELECT wire FROM entry
+                WHERE root = :root AND parent = :parent
+                AND (:kind IS NULL OR kind = :kind)
+                GROUP BY hash",
+            )?;
+            let out = stmt
+                .query_map(
+                    named_params! {
+                        ":root": root.to_string(),
+                        ":parent": parent.to_string(),
+                        ":kind": kind,
+                    },
+                    |row| row.get::<_, Vec<u8>>(0),
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>();
+            out
+        });
+        async move {
+            list.await?
+                .into_iter()
+                .map(|wire| KdEntrySigned::from_wire_unchecked(wire.into_boxed_slice()))
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn get_ui_file(&self, path: &str) -> BoxFuture<'static, KdResult<(String, Vec<u8>)>> {
+        if path == "/favicon.svg" {
+            return async move { Ok(("image/svg+xml".to_string(), FAVICON_SVG.to_vec())) }.boxed();
+        } else if is_index_path(path) {
+            let roots = self.with_con(|con| {
+                let mut stmt = con.prepare_cached("SELECT DISTINCT root FROM entry")?;
+                let out = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>();
+                out
+            });
+            return async move {
+                let roots = roots
+                    .await?
+                    .iter()
+                    .map(|root| KdHash::from_str_slice(root))
+                    .collect::<KdResult<Vec<_>>>()?;
+                Ok(("text/html".to_string(), index_html(roots)))
+            }
+            .boxed();
+        }
+
+        let path = path.to_string();
+        let path2 = path.clone();
+        let file = self.with_con(move |con| {
+            con.query_row(
+                "SELECT mime, data FROM ui_file WHERE path = :path",
+                named_params! { ":path": path2 },
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+        });
+        async move {
+            match file.await? {
+                None => Err(format!("404: {}", path).into()),
+                Some(file) => Ok(file),
+            }
+        }
+        .boxed()
+    }
+
+    fn record_metrics(
+        &self,
+        root: KdHash,
+        records: Vec<MetricRecord>,
+    ) -> BoxFuture<'static, KdResult<()>> {
+        self.with_con(move |con| {
+            let txn = con.transaction()?;
+            for record in records {
+                txn.execute(
+                    "INSERT INTO metric
+                    (root, kind, agent, recorded_at_utc_micros, expires_at_utc_micros, data)
+                    VALUES (:root, :kind, :agent, :recorded_at, :expires_at, :data)",
+                    named_params! {
+                        ":root": root.to_string(),
+                        ":kind": record.kind.to_db(),
+                        ":agent": record.agent.map(|a| a.0.clone()),
+                        ":recorded_at": record.recorded_at_utc.as_micros(),
+                        ":expires_at": record.expires_at_utc.as_micros(),
+                        ":data": record.data.to_string(),
+                    },
+                )?;
+            }
+            txn.execute(
+                "DELETE FROM metric WHERE expires_at_utc_micros <= :now",
+                named_params! { ":now": Timestamp::now().as_micros() },
+            )?;
+            txn.commit()
+        })
+    }
+
+    fn query_metrics(
+        &self,
+        root: KdHash,
+        query: MetricQuery,
+    ) -> BoxFuture<'static, KdResult<MetricQueryAnswer>> {
+        self.with_con(move |con| {
+            let root = root.to_string();
+            Ok(match query {
+                MetricQuery::LatencyMicros { since } => {
+                    MetricQueryAnswer::LatencyMicros(query_metric_by_agent(
+                        con,
+                        METRIC_MEAN,
+                        &root,
+                        MetricRecordKind::LatencyMicros,
+                        since,
+                    )?)
+                }
+                MetricQuery::ErrorRate { since } => {
+                    MetricQueryAnswer::ErrorRate(query_metric_by_agent(
+                        con,
+                        METRIC_MEAN,
+                        &root,
+                        MetricRecordKind::ErrorRate,
+                        since,
+                    )?)
+                }
+                MetricQuery::LastSeen => MetricQueryAnswer::LastSeen(
+                    query_metric_by_agent(
+                        con,
+                        METRIC_MAX,
+                        &root,
+                        MetricRecordKind::LastSeen,
+                        Timestamp::MIN,
+                    )?
+                    .into_iter()
+                    .map(|(agent, micros): (_, i64)| (agent, Timestamp::from_micros(micros)))
+                    .collect(),
+                ),
+            })
+        })
+    }
+}
+
+const METRIC_MEAN: &str = "SELECT agent, AVG(CAST(data AS REAL)) FROM metric
+    WHERE root = :root AND kind = :kind AND agent IS NOT NULL
+    AND recorded_at_utc_micros >= :since
+    GROUP BY agent";
+
+const METRIC_MAX: &str = "SELECT agent, MAX(CAST(data AS INTEGER)) FROM metric
+    WHERE root = :root AND kind = :kind AND agent IS NOT NULL
+    AND recorded_at_utc_micros >= :since
+    GROUP BY agent";
+
+/// Run an aggregate query over the records of one kind made
+/// at or after `since`, returning a value per agent.
+fn query_metric_by_agent<T: rusqlite::types::FromSql>(
+    con: &Connection,
+    sql: &str,
+    root: &str,
+    kind: MetricRecordKind,
+    since: Timestamp,
+) -> rusqlite::Result<Vec<(Arc<KitsuneAgent>, T)>> {
+    let mut stmt = con.prepare_cached(sql)?;
+    let out = stmt
+        .query_map(
+            named_params! {
+                ":root": root,
+                ":kind": kind.to_db(),
+                ":since": since.as_micros(),
+            },
+            |row| {
+                Ok((
+                    Arc::new(KitsuneAgent::new(row.get::<_, Vec<u8>>(0)?)),
+                    row.get(1)?,
+                ))
+            },
+        )?
+        .collect();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::dependencies::lair_keystore_api::prelude::*;
+
+    /// an in-memory lair, which outlives the persist instances
+    /// opened on it like a real lair outlives kdirect
+    async fn test_lair() -> LairClient {
+        let passphrase = sodoken::BufWrite::new_mem_locked(32).unwrap();
+        sodoken::random::bytes_buf(passphrase.clone())
+            .await
+            .unwrap();
+        let config = Arc::new(
+            PwHashLimits::Minimum
+                .with_exec(|| {
+                    kitsune_p2p_types::dependencies::lair_keystore_api::config::LairServerConfigInner::new(
+                        "/",
+                        passphrase.to_read(),
+                    )
+                })
+                .await
+                .unwrap(),
+        );
+        let keystore =
+            kitsune_p2p_types::dependencies::lair_keystore_api::in_proc_keystore::InProcKeystore::new(
+                config,
+                kitsune_p2p_types::dependencies::lair_keystore_api::mem_store::create_mem_store_factory(),
+                passphrase.to_read(),
+            )
+            .await
+            .unwrap();
+        keystore.new_client().await.unwrap()
+    }
+
+    async fn signed_entry(persist: &KdPersist, root: &KdHash) -> KdEntrySigned {
+        signed_child(persist, root, root, "u.test").await
+    }
+
+    async fn signed_child(
+        persist: &KdPersist,
+        root: &KdHash,
+        parent: &KdHash,
+        kind: &str,
+    ) -> KdEntrySigned {
+        KdEntrySigned::from_content(
+            persist,
+            KdEntryContent {
+                kind: kind.to_string(),
+                parent: parent.clone(),
+                author: root.clone(),
+                verify: "".to_string(),
+                data: serde_json::json!({ "test": true }),
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kdirect.sqlite3");
+        let lair = test_lair().await;
+
+        let persist = new_persist_sqlite(&path, lair.clone()).unwrap();
+        let tls = persist.singleton_tls_config().await.unwrap();
+        let root = persist.generate_signing_keypair().await.unwrap();
+        let entry = signed_entry(&persist, &root).await;
+        persist
+            .store_entry(root.clone(), root.clone(), entry.clone())
+            .await
+            .unwrap();
+        persist.close().await;
+        assert!(persist
+            .get_entry(root.clone(), root.clone(), entry.hash().clone())
+            .await
+            .is_err());
+        drop(persist);
+
+        let persist = new_persist_sqlite(&path, lair.clone()).unwrap();
+        assert_eq!(
+            *persist.singleton_tls_config().await.unwrap().cert_digest.0,
+            *tls.cert_digest.0
+        );
+        let got = persist
+            .get_entry(root.clone(), root.clone(), entry.hash().clone())
+            .await
+            .unwrap();
+        assert_eq!(got.hash(), entry.hash());
+        assert_eq!(
+            persist
+                .query_entry_hashes(
+                    root.clone(),
+                    root.clone(),
+                    kitsune_p2p::event::full_time_window(),
+                    DhtArcSet::Full
+                )
+                .await
+                .unwrap(),
+            vec![entry.hash().clone()]
+        );
+
+        // - the keypair survived, so entries can still be signed
+        let again = signed_entry(&persist, &root).await;
+        assert_eq!(again.author(), &root);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_migrations_are_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kdirect.sqlite3");
+        let lair = test_lair().await;
+        drop(new_persist_sqlite(&path, lair.clone()).unwrap());
+
+        let mut con = Connection::open(&path).unwrap();
+        migrate(&mut con).unwrap();
+        let version: usize = con
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+
+        // - a database from a newer kdirect is refused
+        con.pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        assert!(migrate(&mut con).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_query_entry_children() {
+        let dir = tempfile::tempdir().unwrap();
+        let persist =
+            new_persist_sqlite(dir.path().join("kdirect.sqlite3"), test_lair().await).unwrap();
+        let root = persist.generate_signing_keypair().await.unwrap();
+        let other = persist.generate_signing_keypair().await.unwrap();
+
+        let parent = signed_entry(&persist, &root).await;
+        let post = signed_child(&persist, &root, parent.hash(), "u.post").await;
+        let like = signed_child(&persist, &root, parent.hash(), "u.like").await;
+        for entry in [&parent, &post, &like] {
+            persist
+                .store_entry(root.clone(), root.clone(), entry.clone())
+                .await
+                .unwrap();
+        }
+        // - an entry held for more than one agent is only listed once
+        persist
+            .store_entry(root.clone(), other, post.clone())
+            .await
+            .unwrap();
+
+        let mut all = persist
+            .query_entry_children(root.clone(), parent.hash().clone(), None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.hash().clone())
+            .collect::<Vec<_>>();
+        all.sort();
+        let mut expected = vec![post.hash().clone(), like.hash().clone()];
+        expected.sort();
+        assert_eq!(all, expected);
+
+        let posts = persist
+            .query_entry_children(
+                root.clone(),
+                parent.hash().clone(),
+                Some("u.post".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].hash(), post.hash());
+
+        assert!(persist
+            .query_entry_children(root.clone(), post.hash().clone(), None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_tagged_keypairs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kdirect.sqlite3");
+        let lair = test_lair().await;
+
+        let persist = new_persist_sqlite(&path, lair.clone()).unwrap();
+        let alice = persist.get_or_create_tagged_keypair("alice").await.unwrap();
+        let bob = persist.get_or_create_tagged_keypair("bob").await.unwrap();
+        assert_ne!(alice, bob);
+        assert_eq!(
+            persist.get_or_create_tagged_keypair("alice").await.unwrap(),
+            alice
+        );
+        drop(persist);
+
+        // - tags survive a restart
+        let persist = new_persist_sqlite(&path, lair.clone()).unwrap();
+        let tags = persist.list_tags().await.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags["alice"], alice);
+        assert_eq!(tags["bob"], bob);
+
+        // - a deleted tag gets a new keypair, but the old one can still sign
+        persist.delete_tag("alice").await.unwrap();
+        assert!(!persist.list_tags().await.unwrap().contains_key("alice"));
+        assert_ne!(
+            persist.get_or_create_tagged_keypair("alice").await.unwrap(),
+            alice
+        );
+        assert!(persist.sign(alice, b"test").await.is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_keeps_no_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kdirect.sqlite3");
+        let lair = test_lair().await;
+        let persist = new_persist_sqlite(&path, lair.clone()).unwrap();
+        let root = persist.generate_signing_keypair().await.unwrap();
+        let sig = persist.sign(root.clone(), b"test").await.unwrap();
+        assert!(
+            root.verify_signature(sodoken::BufRead::new_no_lock(b"test"), sig)
+                .await
+        );
+
+        // - only the pub key is in the database
+        let con = Connection::open(&path).unwrap();
+        let columns = con
+            .prepare("SELECT * FROM keypair")
+            .unwrap()
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec!["pub_key".to_string()]);
+
+        // - keys not generated by this store are not used
+        let other = new_persist_sqlite(dir.path().join("other.sqlite3"), lair)
+            .unwrap()
+            .generate_signing_keypair()
+            .await
+            .unwrap();
+        assert!(persist.sign(other, b"test").await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persist_sqlite_metrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kdirect.sqlite3");
+        let lair = test_lair().await;
+        let persist = new_persist_sqlite(&path, lair.clone()).unwrap();
+        let root = persist.generate_signing_keypair().await.unwrap();
+        let other_root = persist.generate_signing_keypair().await.unwrap();
+        let agent = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let now = Timestamp::now();
+        let record = |kind, data: serde_json::Value, expires_at_utc| MetricRecord {
+            kind,
+            agent: Some(agent.clone()),
+            recorded_at_utc: now,
+            expires_at_utc,
+            data,
+        };
+        let later = (now + std::time::Duration::from_secs(60)).unwrap();
+        persist
+            .record_metrics(
+                root.clone(),
+                vec![
+                    record(MetricRecordKind::LatencyMicros, 100.into(), later),
+                    record(MetricRecordKind::LatencyMicros, 300.into(), later),
+                    // - expired records are pruned
+                    record(MetricRecordKind::LatencyMicros, 5000.into(), now),
+                ],
+            )
+            .await
+            .unwrap();
+        drop(persist);
+
+        // - metrics survive a restart and are kept per root
+        let persist = new_persist_sqlite(&path, lair).unwrap();
+        let since = Timestamp::MIN;
+        assert_eq!(
+            persist
+                .query_metrics(root, MetricQuery::LatencyMicros { since })
+                .await
+                .unwrap(),
+            MetricQueryAnswer::LatencyMicros(vec![(agent, 200.0)])
+        );
+        assert_eq!(
+            persist
+                .query_metrics(other_root, MetricQuery::LatencyMicros { since })
+                .await
+                .unwrap(),
+            MetricQueryAnswer::LatencyMicros(vec![])
+        );
+    }
+}
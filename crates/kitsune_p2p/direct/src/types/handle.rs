@@ -3,6 +3,7 @@
 use crate::*;
 use futures::future::BoxFuture;
 use kitsune_p2p_direct_api::kd_entry::KdEntryBinary;
+use std::collections::BTreeMap;
 use std::future::Future;
 
 /// Respond to an incoming Hello request
@@ -45,6 +46,12 @@ pub trait AsKdHnd: 'static + Send + Sync {
     /// Get or create a tagged keypair pub key hash
     fn keypair_get_or_create_tagged(&self, tag: &str) -> BoxFuture<'static, KdResult<KdHash>>;
 
+    /// List the tags that have a keypair, with their pub key hashes
+    fn keypair_list_tags(&self) -> BoxFuture<'static, KdResult<BTreeMap<String, KdHash>>>;
+
+    /// Forget the keypair associated with a tag
+    fn keypair_delete_tag(&self, tag: &str) -> BoxFuture<'static, KdResult<()>>;
+
     /// Join an agent to an app root hash
     fn app_join(&self, root: KdHash, agent: KdHash) -> BoxFuture<'static, KdResult<()>>;
 
@@ -145,6 +152,21 @@ impl KdHnd {
         AsKdHnd::keypair_get_or_create_tagged(&*self.0, tag)
     }
 
+    /// List the tags that have a keypair, with their pub key hashes
+    pub fn keypair_list_tags(
+        &self,
+    ) -> impl Future<Output = KdResult<BTreeMap<String, KdHash>>> + 'static + Send {
+        AsKdHnd::keypair_list_tags(&*self.0)
+    }
+
+    /// Forget the keypair associated with a tag
+    pub fn keypair_delete_tag(
+        &self,
+        tag: &str,
+    ) -> impl Future<Output = KdResult<()>> + 'static + Send {
+        AsKdHnd::keypair_delete_tag(&*self.0, tag)
+    }
+
     /// Join an agent to an app root hash
     pub fn app_join(
         &self,
@@ -3,8 +3,9 @@
 use crate::*;
 use futures::future::BoxFuture;
 use kitsune_p2p::dht_arc::DhtArcSet;
-use kitsune_p2p::event::TimeWindow;
+use kitsune_p2p::event::{MetricQuery, MetricQueryAnswer, MetricRecord, TimeWindow};
 use kitsune_p2p_types::tls::TlsConfig;
+use std::collections::BTreeMap;
 use std::future::Future;
 
 /// Trait representing a persistence store.
@@ -24,6 +25,17 @@ pub trait AsKdPersist: 'static + Send + Sync {
     /// Generate a signature keypair, returning the pub key as a KdHash.
     fn generate_signing_keypair(&self) -> BoxFuture<'static, KdResult<KdHash>>;
 
+    /// Get the pub key of the signature keypair associated with tag,
+    /// generating and associating a new keypair if there is none.
+    fn get_or_create_tagged_keypair(&self, tag: &str) -> BoxFuture<'static, KdResult<KdHash>>;
+
+    /// List the tags that have a keypair associated with them.
+    fn list_tags(&self) -> BoxFuture<'static, KdResult<BTreeMap<String, KdHash>>>;
+
+    /// Forget the association between tag and its keypair.
+    /// The keypair itself is kept, so it can still sign.
+    fn delete_tag(&self, tag: &str) -> BoxFuture<'static, KdResult<()>>;
+
     /// Sign arbitrary data with the secret key associated with given KdHash.
     fn sign(&self, pub_key: KdHash, data: &[u8]) -> BoxFuture<'static, KdResult<Arc<[u8; 64]>>>;
 
@@ -80,8 +92,43 @@ pub trait AsKdPersist: 'static + Send + Sync {
         dht_arc: DhtArcSet,
     ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>>;
 
+    /// Get the hashes of the entries a query_entries call would return,
+    /// without loading the entries themselves
+    fn query_entry_hashes(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        window: TimeWindow,
+        dht_arc: DhtArcSet,
+    ) -> BoxFuture<'static, KdResult<Vec<KdHash>>>;
+
+    /// Get the entries stored under root that name this parent,
+    /// optionally only those of a specific kind
+    fn query_entry_children(
+        &self,
+        root: KdHash,
+        parent: KdHash,
+        kind: Option<String>,
+    ) -> BoxFuture<'static, KdResult<Vec<KdEntrySigned>>>;
+
     /// Get ui file
     fn get_ui_file(&self, path: &str) -> BoxFuture<'static, KdResult<(String, Vec<u8>)>>;
+
+    /// Record network metrics about the peers of root.
+    /// A store may choose not to keep metrics.
+    fn record_metrics(
+        &self,
+        root: KdHash,
+        records: Vec<MetricRecord>,
+    ) -> BoxFuture<'static, KdResult<()>>;
+
+    /// Answer an aggregate query over the metrics recorded for root.
+    /// A store that does not keep metrics answers as if there were none.
+    fn query_metrics(
+        &self,
+        root: KdHash,
+        query: MetricQuery,
+    ) -> BoxFuture<'static, KdResult<MetricQueryAnswer>>;
 }
 
 /// Handle to a persistence store.
@@ -127,6 +174,28 @@ impl KdPersist {
         AsKdPersist::generate_signing_keypair(&*self.0)
     }
 
+    /// Get the pub key of the signature keypair associated with tag,
+    /// generating and associating a new keypair if there is none.
+    pub fn get_or_create_tagged_keypair(
+        &self,
+        tag: &str,
+    ) -> impl Future<Output = KdResult<KdHash>> + 'static + Send {
+        AsKdPersist::get_or_create_tagged_keypair(&*self.0, tag)
+    }
+
+    /// List the tags that have a keypair associated with them.
+    pub fn list_tags(
+        &self,
+    ) -> impl Future<Output = KdResult<BTreeMap<String, KdHash>>> + 'static + Send {
+        AsKdPersist::list_tags(&*self.0)
+    }
+
+    /// Forget the association between tag and its keypair.
+    /// The keypair itself is kept, so it can still sign.
+    pub fn delete_tag(&self, tag: &str) -> impl Future<Output = KdResult<()>> + 'static + Send {
+        AsKdPersist::delete_tag(&*self.0, tag)
+    }
+
     /// Sign arbitrary data with the secret key associated with given KdHash.
     pub fn sign(
         &self,
@@ -211,6 +280,29 @@ impl KdPersist {
         AsKdPersist::query_entries(&*self.0, root, agent, window, dht_arc)
     }
 
+    /// Get the hashes of the entries a query_entries call would return,
+    /// without loading the entries themselves
+    pub fn query_entry_hashes(
+        &self,
+        root: KdHash,
+        agent: KdHash,
+        window: TimeWindow,
+        dht_arc: DhtArcSet,
+    ) -> impl Future<Output = KdResult<Vec<KdHash>>> + 'static + Send {
+        AsKdPersist::query_entry_hashes(&*self.0, root, agent, window, dht_arc)
+    }
+
+    /// Get the entries stored under root that name this parent,
+    /// optionally only those of a specific kind
+    pub fn query_entry_children(
+        &self,
+        root: KdHash,
+        parent: KdHash,
+        kind: Option<String>,
+    ) -> impl Future<Output = KdResult<Vec<KdEntrySigned>>> + 'static + Send {
+        AsKdPersist::query_entry_children(&*self.0, root, parent, kind)
+    }
+
     /// Get ui file
     pub fn get_ui_file(
         &self,
@@ -218,4 +310,22 @@ impl KdPersist {
     ) -> impl Future<Output = KdResult<(String, Vec<u8>)>> + 'static + Send {
         AsKdPersist::get_ui_file(&*self.0, path)
     }
+
+    /// Record network metrics about the peers of root.
+    pub fn record_metrics(
+        &self,
+        root: KdHash,
+        records: Vec<MetricRecord>,
+    ) -> impl Future<Output = KdResult<()>> + 'static + Send {
+        AsKdPersist::record_metrics(&*self.0, root, records)
+    }
+
+    /// Answer an aggregate query over the metrics recorded for root.
+    pub fn query_metrics(
+        &self,
+        root: KdHash,
+        query: MetricQuery,
+    ) -> impl Future<Output = KdResult<MetricQueryAnswer>> + 'static + Send {
+        AsKdPersist::query_metrics(&*self.0, root, query)
+    }
 }
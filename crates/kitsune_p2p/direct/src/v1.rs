@@ -189,6 +189,19 @@ pub fn new_kitsune_direct_v1(
 
 // -- private -- //
 
+/// How long to wait before each attempt to publish a new entry.
+/// The peers covering an entry's basis may not be discoverable yet
+/// when it is authored, so publishing is repeated a few times
+/// before the entry is left to gossip.
+const PUBLISH_ATTEMPT_DELAYS: [std::time::Duration; 3] = [
+    std::time::Duration::from_secs(0),
+    std::time::Duration::from_secs(5),
+    std::time::Duration::from_secs(30),
+];
+
+/// How many publish attempts may be in flight at once.
+const MAX_CONCURRENT_PUBLISH: usize = 32;
+
 struct Kd1Inner {
     srv: KdSrv,
     p2p: ghost_actor::GhostSender<actor::KitsuneP2p>,
@@ -199,6 +212,7 @@ struct Kd1Inner {
 struct Kd1 {
     uniq: Uniq,
     persist: KdPersist,
+    publish_limit: Arc<tokio::sync::Semaphore>,
     inner: Share<Kd1Inner>,
 }
 
@@ -211,6 +225,7 @@ impl Kd1 {
         Arc::new(Self {
             uniq: Uniq::default(),
             persist,
+            publish_limit: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_PUBLISH)),
             inner: Share::new(Kd1Inner {
                 srv,
                 p2p,
@@ -218,6 +233,50 @@ impl Kd1 {
             }),
         })
     }
+
+    /// Broadcast a new entry to the agents covering its basis hash,
+    /// repeating after each of the [`PUBLISH_ATTEMPT_DELAYS`].
+    /// Runs in the background, so the author doesn't wait on the network.
+    pub fn publish_entry(
+        &self,
+        tuning_params: KitsuneP2pTuningParams,
+        root: KdHash,
+        entry_signed: KdEntrySigned,
+    ) {
+        let kdirect = self.clone();
+        let space = root.to_kitsune_space();
+        let basis = entry_signed.hash().to_kitsune_basis();
+        let payload = entry_signed.as_wire_data_ref().to_vec();
+        tokio::task::spawn(async move {
+            for delay in PUBLISH_ATTEMPT_DELAYS {
+                tokio::time::sleep(delay).await;
+                if kdirect.is_closed() {
+                    return;
+                }
+                let _permit = match kdirect.publish_limit.clone().acquire_owned().await {
+                    Ok(permit) => permit,
+                    Err(_) => return,
+                };
+                let fut = kdirect.inner.share_mut(|i, _| {
+                    Ok(i.p2p.broadcast(
+                        space.clone(),
+                        basis.clone(),
+                        tuning_params.implicit_timeout(),
+                        BroadcastTo::Notify,
+                        payload.clone(),
+                    ))
+                });
+                let res = match fut {
+                    Ok(fut) => fut.await.map_err(KdError::other),
+                    // kdirect has been closed
+                    Err(_) => return,
+                };
+                if let Err(err) = res {
+                    tracing::warn!(?err, "publish error");
+                }
+            }
+        });
+    }
 }
 
 impl AsKitsuneDirect for Kd1 {
@@ -302,6 +361,26 @@ impl KitsuneHostDefaultError for Kd1 {
         .boxed()
         .into()
     }
+
+    fn record_metrics(
+        &self,
+        space: Arc<KitsuneSpace>,
+        records: Vec<MetricRecord>,
+    ) -> KitsuneHostResult<()> {
+        let root = KdHash::from_kitsune_space(&space);
+        let fut = self.persist.record_metrics(root, records);
+        async move { Ok(fut.await?) }.boxed().into()
+    }
+
+    fn query_metrics(
+        &self,
+        space: Arc<KitsuneSpace>,
+        query: MetricQuery,
+    ) -> KitsuneHostResult<MetricQueryAnswer> {
+        let root = KdHash::from_kitsune_space(&space);
+        let fut = self.persist.query_metrics(root, query);
+        async move { Ok(fut.await?) }.boxed().into()
+    }
 }
 
 async fn handle_srv_events(
@@ -346,10 +425,16 @@ async fn handle_srv_events(
                         }
                     }
                     KdSrvEvt::WebsocketConnected { con } => {
-                        if let Err(err) = srv.websocket_send(con, KdApi::HelloReq {
-                            msg_id: "".to_string(),
-                            salt: vec![1, 2, 3, 4].into_boxed_slice().into(),
-                        }).await {
+                        if let Err(err) = srv
+                            .websocket_send(
+                                con,
+                                KdApi::HelloReq {
+                                    msg_id: "".to_string(),
+                                    salt: vec![1, 2, 3, 4].into_boxed_slice().into(),
+                                },
+                            )
+                            .await
+                        {
                             tracing::error!(?err, "ws send error");
                         }
                     }
@@ -362,15 +447,22 @@ async fn handle_srv_events(
                             });
                             return;
                         }
-                        match kdirect.inner.share_mut(|i, _| {
-                            Ok(i.auth_set.contains(&con))
-                        }) {
+                        match kdirect
+                            .inner
+                            .share_mut(|i, _| Ok(i.auth_set.contains(&con)))
+                        {
                             Ok(true) => (),
                             _ => {
-                                if let Err(err) = srv.websocket_send(con, KdApi::ErrorRes {
-                                    msg_id,
-                                    reason: "unauthenticated".to_string(),
-                                }).await {
+                                if let Err(err) = srv
+                                    .websocket_send(
+                                        con,
+                                        KdApi::ErrorRes {
+                                            msg_id,
+                                            reason: "unauthenticated".to_string(),
+                                        },
+                                    )
+                                    .await
+                                {
                                     tracing::error!(?err, "ws send error");
                                 }
                                 return;
@@ -382,10 +474,7 @@ async fn handle_srv_events(
                                 Ok(api) => api,
                                 Err(err) => {
                                     let reason = format!("{:?}", err);
-                                    KdApi::ErrorRes {
-                                        msg_id,
-                                        reason,
-                                    }
+                                    KdApi::ErrorRes { msg_id, reason }
                                 }
                             };
                             if let Err(err) = srv.websocket_send(con, api).await {
@@ -397,19 +486,50 @@ async fn handle_srv_events(
                             KdApi::User { user } => {
                                 tracing::debug!(?user, "recv user data");
                             }
-                            KdApi::KeypairGetOrCreateTaggedReq {
-                                msg_id,
-                                tag: _,
-                                ..
-                            } => {
-                                // TODO - tagging!!!
-                                exec(msg_id.clone(), async {
-                                    let pub_key = kdirect.persist.generate_signing_keypair().await.map_err(KdError::other)?;
-                                    Ok(KdApi::KeypairGetOrCreateTaggedRes {
-                                        msg_id,
-                                        pub_key,
-                                    })
-                                }.boxed()).await;
+                            KdApi::KeypairGetOrCreateTaggedReq { msg_id, tag, .. } => {
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let pub_key = kdirect
+                                            .persist
+                                            .get_or_create_tagged_keypair(&tag)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::KeypairGetOrCreateTaggedRes { msg_id, pub_key })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
+                            }
+                            KdApi::KeypairListTagsReq { msg_id, .. } => {
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let tags = kdirect
+                                            .persist
+                                            .list_tags()
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::KeypairListTagsRes { msg_id, tags })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
+                            }
+                            KdApi::KeypairDeleteTagReq { msg_id, tag, .. } => {
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        kdirect
+                                            .persist
+                                            .delete_tag(&tag)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::KeypairDeleteTagRes { msg_id })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::AppJoinReq {
                                 msg_id,
@@ -417,14 +537,26 @@ async fn handle_srv_events(
                                 agent,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    kdirect.inner.share_mut(|i, _| {
-                                        Ok(i.p2p.join(root.to_kitsune_space(), agent.to_kitsune_agent(), None))
-                                    }).map_err(KdError::other)?.await.map_err(KdError::other)?;
-                                    Ok(KdApi::AppJoinRes {
-                                        msg_id,
-                                    })
-                                }.boxed()).await;
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        kdirect
+                                            .inner
+                                            .share_mut(|i, _| {
+                                                Ok(i.p2p.join(
+                                                    root.to_kitsune_space(),
+                                                    agent.to_kitsune_agent(),
+                                                    None,
+                                                ))
+                                            })
+                                            .map_err(KdError::other)?
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::AppJoinRes { msg_id })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::AppLeaveReq {
                                 msg_id,
@@ -432,26 +564,42 @@ async fn handle_srv_events(
                                 agent,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    kdirect.inner.share_mut(|i, _| {
-                                        Ok(i.p2p.leave(root.to_kitsune_space(), agent.to_kitsune_agent()))
-                                    }).map_err(KdError::other)?.await.map_err(KdError::other)?;
-                                    Ok(KdApi::AppLeaveRes {
-                                        msg_id,
-                                    })
-                                }.boxed()).await;
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        kdirect
+                                            .inner
+                                            .share_mut(|i, _| {
+                                                Ok(i.p2p.leave(
+                                                    root.to_kitsune_space(),
+                                                    agent.to_kitsune_agent(),
+                                                ))
+                                            })
+                                            .map_err(KdError::other)?
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::AppLeaveRes { msg_id })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::AgentInfoStoreReq {
-                                msg_id,
-                                agent_info,
-                                ..
+                                msg_id, agent_info, ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    kdirect.persist.store_agent_info(agent_info).await.map_err(KdError::other)?;
-                                    Ok(KdApi::AgentInfoStoreRes {
-                                        msg_id,
-                                    })
-                                }.boxed()).await;
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        kdirect
+                                            .persist
+                                            .store_agent_info(agent_info)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::AgentInfoStoreRes { msg_id })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::AgentInfoGetReq {
                                 msg_id,
@@ -459,26 +607,37 @@ async fn handle_srv_events(
                                 agent,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    let agent_info = kdirect.persist.get_agent_info(root, agent).await.map_err(KdError::other)?;
-                                    Ok(KdApi::AgentInfoGetRes {
-                                        msg_id,
-                                        agent_info,
-                                    })
-                                }.boxed()).await;
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let agent_info = kdirect
+                                            .persist
+                                            .get_agent_info(root, agent)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::AgentInfoGetRes { msg_id, agent_info })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
-                            KdApi::AgentInfoQueryReq {
-                                msg_id,
-                                root,
-                                ..
-                            } => {
-                                exec(msg_id.clone(), async {
-                                    let agent_info_list = kdirect.persist.query_agent_info(root).await.map_err(KdError::other)?;
-                                    Ok(KdApi::AgentInfoQueryRes {
-                                        msg_id,
-                                        agent_info_list,
-                                    })
-                                }.boxed()).await;
+                            KdApi::AgentInfoQueryReq { msg_id, root, .. } => {
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let agent_info_list = kdirect
+                                            .persist
+                                            .query_agent_info(root)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::AgentInfoQueryRes {
+                                            msg_id,
+                                            agent_info_list,
+                                        })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::IsAuthorityReq {
                                 msg_id,
@@ -486,17 +645,27 @@ async fn handle_srv_events(
                                 basis,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    let space = root.to_kitsune_space();
-                                    let basis = basis.to_kitsune_basis();
-                                    let is_authority = kdirect.inner.share_mut(move |i, _| {
-                                        Ok(i.p2p.authority_for_hash(space, basis))
-                                    }).map_err(KdError::other)?.await.map_err(KdError::other)?;
-                                    Ok(KdApi::IsAuthorityRes {
-                                        msg_id,
-                                        is_authority,
-                                    })
-                                }.boxed()).await;
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let space = root.to_kitsune_space();
+                                        let basis = basis.to_kitsune_basis();
+                                        let is_authority = kdirect
+                                            .inner
+                                            .share_mut(move |i, _| {
+                                                Ok(i.p2p.authority_for_hash(space, basis))
+                                            })
+                                            .map_err(KdError::other)?
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::IsAuthorityRes {
+                                            msg_id,
+                                            is_authority,
+                                        })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::MessageSendReq {
                                 msg_id,
@@ -506,25 +675,38 @@ async fn handle_srv_events(
                                 binary,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    let space = root.to_kitsune_space();
-                                    let to_agent = to_agent.to_kitsune_agent();
-                                    let content = content.to_string().into_bytes();
-                                    let mut payload = Vec::with_capacity(4 + content.len() + binary.len());
-                                    let binary_len = (binary.len() as u32).to_le_bytes();
-                                    payload.extend_from_slice(&binary_len);
-                                    payload.extend_from_slice(&binary);
-                                    payload.extend_from_slice(&content);
-                                    let res = kdirect.inner.share_mut(move |i, _| {
-                                        Ok(i.p2p.rpc_single(space, to_agent, payload, None))
-                                    }).map_err(KdError::other)?.await.map_err(KdError::other)?;
-                                    if res != b"success" {
-                                        return Err(format!("unexpected: {}", String::from_utf8_lossy(&res)).into());
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let space = root.to_kitsune_space();
+                                        let to_agent = to_agent.to_kitsune_agent();
+                                        let content = content.to_string().into_bytes();
+                                        let mut payload =
+                                            Vec::with_capacity(4 + content.len() + binary.len());
+                                        let binary_len = (binary.len() as u32).to_le_bytes();
+                                        payload.extend_from_slice(&binary_len);
+                                        payload.extend_from_slice(&binary);
+                                        payload.extend_from_slice(&content);
+                                        let res = kdirect
+                                            .inner
+                                            .share_mut(move |i, _| {
+                                                Ok(i.p2p.rpc_single(space, to_agent, payload, None))
+                                            })
+                                            .map_err(KdError::other)?
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        if res != b"success" {
+                                            return Err(format!(
+                                                "unexpected: {}",
+                                                String::from_utf8_lossy(&res)
+                                            )
+                                            .into());
+                                        }
+                                        Ok(KdApi::MessageSendRes { msg_id })
                                     }
-                                    Ok(KdApi::MessageSendRes {
-                                        msg_id,
-                                    })
-                                }.boxed()).await;
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::EntryAuthorReq {
                                 msg_id,
@@ -534,48 +716,44 @@ async fn handle_srv_events(
                                 binary,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    if author != content.author {
-                                        return Err("author mismatch".into());
-                                    }
-                                    let entry_signed = KdEntrySigned::from_content_with_binary(&kdirect.persist, content, &binary).await?;
-
-                                    // first, put this in our store
-                                    // so it can begin gossiping
-                                    kdirect.persist.store_entry(root.clone(), author, entry_signed.clone()).await.map_err(KdError::other)?;
-
-                                    // next, let's try to publish it
-                                    //
-                                    // TODO - make a publish queue
-                                    //        so we don't blow out memory
-                                    //        spawning all these tasks!
-                                    //
-                                    //        we don't want to do this inline
-                                    //        because in the not connected
-                                    //        case, it'll take 30 seconds...
-                                    let basis = entry_signed.hash().to_kitsune_basis();
-                                    let timeout = tuning_params.implicit_timeout();
-                                    let payload = entry_signed.as_wire_data_ref().to_vec();
-                                    let fut = kdirect.inner.share_mut(|i, _| {
-                                        Ok(i.p2p.broadcast(
-                                            root.to_kitsune_space(),
-                                            basis,
-                                            timeout,
-                                            BroadcastTo::Notify,
-                                            payload,
-                                        ))
-                                    }).map_err(KdError::other)?;
-                                    tokio::task::spawn(async move {
-                                        if let Err(err) = fut.await.map_err(KdError::other) {
-                                            tracing::warn!(?err, "publish error");
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        if author != content.author {
+                                            return Err("author mismatch".into());
                                         }
-                                    });
-
-                                    Ok(KdApi::EntryAuthorRes {
-                                        msg_id,
-                                        entry_signed,
-                                    })
-                                }.boxed()).await;
+                                        let entry_signed = KdEntrySigned::from_content_with_binary(
+                                            &kdirect.persist,
+                                            content,
+                                            &binary,
+                                        )
+                                        .await?;
+
+                                        // first, put this in our store
+                                        // so it can begin gossiping
+                                        kdirect
+                                            .persist
+                                            .store_entry(root.clone(), author, entry_signed.clone())
+                                            .await
+                                            .map_err(KdError::other)?;
+
+                                        // next, publish it to the neighborhood
+                                        // of its basis, rather than waiting for
+                                        // a gossip round to pick it up
+                                        kdirect.publish_entry(
+                                            tuning_params.clone(),
+                                            root,
+                                            entry_signed.clone(),
+                                        );
+
+                                        Ok(KdApi::EntryAuthorRes {
+                                            msg_id,
+                                            entry_signed,
+                                        })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::EntryGetReq {
                                 msg_id,
@@ -584,43 +762,68 @@ async fn handle_srv_events(
                                 hash,
                                 ..
                             } => {
-                                exec(msg_id.clone(), async {
-                                    let entry_signed = kdirect.persist.get_entry(root, agent, hash).await.map_err(KdError::other)?;
-                                    Ok(KdApi::EntryGetRes {
-                                        msg_id,
-                                        entry_signed,
-                                    })
-                                }.boxed()).await;
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let entry_signed = kdirect
+                                            .persist
+                                            .get_entry(root, agent, hash)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::EntryGetRes {
+                                            msg_id,
+                                            entry_signed,
+                                        })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
                             KdApi::EntryGetChildrenReq {
-                                //msg_id,
-                                //root,
-                                //parent,
-                                //kind,
+                                msg_id,
+                                root,
+                                parent,
+                                kind,
                                 ..
                             } => {
-                                // TODO -- FIXME
-                                unimplemented!("TODO")
+                                exec(
+                                    msg_id.clone(),
+                                    async {
+                                        let entry_signed_list = kdirect
+                                            .persist
+                                            .query_entry_children(root, parent, kind)
+                                            .await
+                                            .map_err(KdError::other)?;
+                                        Ok(KdApi::EntryGetChildrenRes {
+                                            msg_id,
+                                            entry_signed_list,
+                                        })
+                                    }
+                                    .boxed(),
+                                )
+                                .await;
                             }
-                            oth @ KdApi::ErrorRes { .. } |
-                            oth @ KdApi::HelloReq { .. } |
-                            oth @ KdApi::KeypairGetOrCreateTaggedRes { .. } |
-                            oth @ KdApi::AppJoinRes { .. } |
-                            oth @ KdApi::AppLeaveRes { .. } |
-                            oth @ KdApi::AgentInfoStoreRes { .. } |
-                            oth @ KdApi::AgentInfoGetRes { .. } |
-                            oth @ KdApi::AgentInfoQueryRes { .. } |
-                            oth @ KdApi::IsAuthorityRes { .. } |
-                            oth @ KdApi::MessageSendRes { .. } |
-                            oth @ KdApi::MessageRecvEvt { .. } |
-                            oth @ KdApi::EntryAuthorRes { .. } |
-                            oth @ KdApi::EntryGetRes { .. } |
-                            oth @ KdApi::EntryGetChildrenRes { .. } => {
+                            oth @ KdApi::ErrorRes { .. }
+                            | oth @ KdApi::HelloReq { .. }
+                            | oth @ KdApi::KeypairGetOrCreateTaggedRes { .. }
+                            | oth @ KdApi::KeypairListTagsRes { .. }
+                            | oth @ KdApi::KeypairDeleteTagRes { .. }
+                            | oth @ KdApi::AppJoinRes { .. }
+                            | oth @ KdApi::AppLeaveRes { .. }
+                            | oth @ KdApi::AgentInfoStoreRes { .. }
+                            | oth @ KdApi::AgentInfoGetRes { .. }
+                            | oth @ KdApi::AgentInfoQueryRes { .. }
+                            | oth @ KdApi::IsAuthorityRes { .. }
+                            | oth @ KdApi::MessageSendRes { .. }
+                            | oth @ KdApi::MessageRecvEvt { .. }
+                            | oth @ KdApi::EntryAuthorRes { .. }
+                            | oth @ KdApi::EntryGetRes { .. }
+                            | oth @ KdApi::EntryGetChildrenRes { .. } => {
                                 let reason = format!("unexpected {}", oth);
-                                if let Err(err) = srv.websocket_send(con, KdApi::ErrorRes {
-                                    msg_id,
-                                    reason,
-                                }).await {
+                                if let Err(err) = srv
+                                    .websocket_send(con, KdApi::ErrorRes { msg_id, reason })
+                                    .await
+                                {
                                     tracing::error!(?err, "ws send error");
                                 }
                             }
@@ -872,11 +1075,7 @@ async fn handle_query_op_hashes(
 
     let root = KdHash::from_kitsune_space(&space);
 
-    // TODO - it's ok for now to just get the full entries
-    //        since they'll just get Arc::clone-d
-    //        but once this is a persisted database
-    //        we'll want an api to just get the hashes
-    let mut entries = vec![];
+    let mut hashes = vec![];
 
     let agent_info_list = kdirect
         .persist
@@ -885,22 +1084,19 @@ async fn handle_query_op_hashes(
         .map_err(KdError::other)?;
     for info in agent_info_list {
         let agent = info.agent();
-        let es = kdirect
+        let hs = kdirect
             .persist
-            .query_entries(root.clone(), agent.clone(), window.clone(), arc_set.clone())
+            .query_entry_hashes(root.clone(), agent.clone(), window.clone(), arc_set.clone())
             .await?;
-        entries.extend(es.into_iter());
+        hashes.extend(hs.into_iter());
     }
 
-    let mut entries: Vec<_> = entries
-        .into_iter()
-        .map(|e| e.hash().clone().to_kitsune_op_hash())
-        .collect();
-    entries.sort();
-    entries.dedup();
+    let mut hashes: Vec<_> = hashes.into_iter().map(|h| h.to_kitsune_op_hash()).collect();
+    hashes.sort();
+    hashes.dedup();
 
     // TODO: produce proper time window of actual data returned
-    Ok(Some((entries, window.start..=window.end)))
+    Ok(Some((hashes, window.start..=window.end)))
 }
 
 async fn handle_fetch_op_data(
@@ -74,6 +74,48 @@ pub enum KdApi {
         pub_key: KdHash,
     },
 
+    /// List the tags that have a keypair associated with them.
+    #[serde(rename = "keypairListTagsReq")]
+    KeypairListTagsReq {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+    },
+
+    /// Returns the pubkey associated with each tag
+    #[serde(rename = "keypairListTagsRes")]
+    KeypairListTagsRes {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+
+        /// tag -> pubkey
+        #[serde(rename = "tags")]
+        tags: std::collections::BTreeMap<String, KdHash>,
+    },
+
+    /// Forget the keypair associated with this tag.
+    /// A later KeypairGetOrCreateTaggedReq for the tag
+    /// will generate a new pair.
+    #[serde(rename = "keypairDeleteTagReq")]
+    KeypairDeleteTagReq {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+
+        /// tag to forget
+        #[serde(rename = "tag")]
+        tag: String,
+    },
+
+    /// the tag was deleted
+    #[serde(rename = "keypairDeleteTagRes")]
+    KeypairDeleteTagRes {
+        /// message id
+        #[serde(rename = "msgId")]
+        msg_id: String,
+    },
+
     /// Join an agent to an app root hash
     #[serde(rename = "appJoinReq")]
     AppJoinReq {
@@ -417,6 +459,10 @@ impl KdApi {
             Self::HelloRes { msg_id, .. } => msg_id,
             Self::KeypairGetOrCreateTaggedReq { msg_id, .. } => msg_id,
             Self::KeypairGetOrCreateTaggedRes { msg_id, .. } => msg_id,
+            Self::KeypairListTagsReq { msg_id, .. } => msg_id,
+            Self::KeypairListTagsRes { msg_id, .. } => msg_id,
+            Self::KeypairDeleteTagReq { msg_id, .. } => msg_id,
+            Self::KeypairDeleteTagRes { msg_id, .. } => msg_id,
             Self::AppJoinReq { msg_id, .. } => msg_id,
             Self::AppJoinRes { msg_id, .. } => msg_id,
             Self::AppLeaveReq { msg_id, .. } => msg_id,
@@ -448,6 +494,8 @@ impl KdApi {
             Self::ErrorRes { .. } => true,
             Self::HelloRes { .. } => true,
             Self::KeypairGetOrCreateTaggedRes { .. } => true,
+            Self::KeypairListTagsRes { .. } => true,
+            Self::KeypairDeleteTagRes { .. } => true,
             Self::AppJoinRes { .. } => true,
             Self::AppLeaveRes { .. } => true,
             Self::AgentInfoStoreRes { .. } => true,
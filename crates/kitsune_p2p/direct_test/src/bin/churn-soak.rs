@@ -0,0 +1,72 @@
+use futures::stream::StreamExt;
+use kitsune_p2p_direct::dependencies::*;
+use kitsune_p2p_direct_test::churn_soak::*;
+use kitsune_p2p_direct_test::direct_test_local_periodic::init_tracing;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "churn-soak")]
+struct Opt {
+    /// how many nodes to create
+    #[structopt(short = "n", long, default_value = "10")]
+    node_count: usize,
+
+    /// how many agents to join on each node
+    #[structopt(short = "a", long, default_value = "2")]
+    agents_per_node: usize,
+
+    /// every agent authors a new entry at this interval
+    #[structopt(short = "e", long, default_value = "5000")]
+    author_interval_ms: u64,
+
+    /// restart a random node at this interval (0 for never)
+    #[structopt(short = "r", long, default_value = "10000")]
+    restart_interval_ms: u64,
+
+    /// restarted nodes come back with an empty store and new agents
+    #[structopt(short = "w", long)]
+    wipe_on_restart: bool,
+
+    /// stop and print a summary after this many seconds (0 for never)
+    #[structopt(short = "s", long, default_value = "0")]
+    run_time_s: u64,
+
+    /// reconfigure the tuning_param that controls delay
+    /// re-gossiping with a remote node after a successful gossip
+    #[structopt(short = "d", long, default_value = "1000")]
+    peer_gossip_success_delay_ms: u32,
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    init_tracing();
+
+    let Opt {
+        node_count,
+        agents_per_node,
+        author_interval_ms,
+        restart_interval_ms,
+        wipe_on_restart,
+        run_time_s,
+        peer_gossip_success_delay_ms,
+    } = Opt::from_args();
+
+    let mut tuning_params =
+        kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+    tuning_params.gossip_peer_on_success_next_gossip_delay_ms = peer_gossip_success_delay_ms;
+    let tuning_params = std::sync::Arc::new(tuning_params);
+
+    let (mut progress, _shutdown) = run(Config {
+        tuning_params,
+        node_count,
+        agents_per_node,
+        author_interval_ms,
+        restart_interval_ms: Some(restart_interval_ms).filter(|i| *i > 0),
+        wipe_on_restart,
+        run_time_s: Some(run_time_s).filter(|s| *s > 0),
+    });
+
+    while let Some(progress) = progress.next().await {
+        println!("{}", progress);
+    }
+}
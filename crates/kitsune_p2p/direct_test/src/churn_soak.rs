@@ -0,0 +1,683 @@
+//! long running soak test with nodes restarting while entries are authored
+
+use kitsune_p2p_direct::dependencies::kitsune_p2p::event::full_time_window;
+use kitsune_p2p_direct::dependencies::kitsune_p2p_types::dht_arc::DhtArcRange;
+use kitsune_p2p_direct::dependencies::kitsune_p2p_types::dht_arc::DhtArcSet;
+use kitsune_p2p_direct::dependencies::*;
+use kitsune_p2p_direct::prelude::*;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+
+use std::collections::HashSet;
+
+/// configuration for churn_soak test
+pub struct Config {
+    /// tuning_params
+    pub tuning_params: KitsuneP2pTuningParams,
+
+    /// how many nodes to create
+    pub node_count: usize,
+
+    /// how many agents to join on each node
+    pub agents_per_node: usize,
+
+    /// every agent authors a new entry at this interval
+    pub author_interval_ms: u64,
+
+    /// restart a random node at this interval (None for never)
+    pub restart_interval_ms: Option<u64>,
+
+    /// if true restarted nodes come back with an empty store and new agents,
+    /// as if the old node left and a new one joined
+    pub wipe_on_restart: bool,
+
+    /// stop the test and emit a summary after this long (None for never)
+    pub run_time_s: Option<u64>,
+}
+
+/// progress emitted by this test
+#[derive(Debug)]
+pub enum Progress {
+    /// The test has started
+    TestStarted {
+        /// how long the test has been running
+        run_time_s: f64,
+
+        /// how many nodes were created
+        node_count: usize,
+
+        /// how many agents were joined on each node
+        agents_per_node: usize,
+
+        /// test will restart a node at this interval
+        restart_interval_ms: Option<u64>,
+
+        /// restarted nodes lose their data
+        wipe_on_restart: bool,
+    },
+
+    /// A node was restarted
+    NodeRestarted {
+        /// how long the test has been running
+        run_time_s: f64,
+
+        /// the index of the restarted node
+        node_idx: usize,
+
+        /// the node came back with an empty store
+        wiped: bool,
+    },
+
+    /// A periodic interim report to show progress
+    InterimState {
+        /// how long the test has been running
+        run_time_s: f64,
+
+        /// the number of entries authored so far
+        authored_op_count: usize,
+
+        /// the number of (authority, op) pairs that should be held
+        target_hold_count: usize,
+
+        /// the number of (authority, op) pairs that are held
+        hold_count: usize,
+
+        /// authored entries that are not held by any node
+        lost_op_count: usize,
+    },
+
+    /// All authorities hold all the ops they should
+    Converged {
+        /// how long the test has been running
+        run_time_s: f64,
+
+        /// how long it took to converge since the
+        /// first report that was not converged
+        convergence_s: f64,
+
+        /// the number of entries authored so far
+        authored_op_count: usize,
+    },
+
+    /// The test has finished
+    Summary {
+        /// how long the test has been running
+        run_time_s: f64,
+
+        /// the number of entries authored
+        authored_op_count: usize,
+
+        /// how many times a node was restarted
+        restart_count: usize,
+
+        /// how many times the nodes converged
+        converged_count: usize,
+
+        /// the average time it took to converge
+        avg_convergence_s: f64,
+
+        /// the longest time it took to converge
+        max_convergence_s: f64,
+
+        /// authored entries that are not held by any node at the end
+        lost_op_count: usize,
+    },
+}
+
+impl std::fmt::Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Progress::TestStarted {
+                run_time_s,
+                node_count,
+                agents_per_node,
+                restart_interval_ms,
+                wipe_on_restart,
+            } => {
+                write!(
+                    f,
+                    r#"{:.4}s: TestStarted
+ -- {} agents / {} nodes
+ -- restart_interval_ms: {:?}
+ -- wipe_on_restart: {}"#,
+                    run_time_s, agents_per_node, node_count, restart_interval_ms, wipe_on_restart,
+                )
+            }
+            Progress::NodeRestarted {
+                run_time_s,
+                node_idx,
+                wiped,
+            } => {
+                write!(
+                    f,
+                    "{:.4}s: NodeRestarted {} wiped: {}",
+                    run_time_s, node_idx, wiped,
+                )
+            }
+            Progress::InterimState {
+                run_time_s,
+                authored_op_count,
+                target_hold_count,
+                hold_count,
+                lost_op_count,
+            } => {
+                write!(
+                    f,
+                    "{:.4}s: InterimState {} ops {}/{} held {} lost",
+                    run_time_s, authored_op_count, hold_count, target_hold_count, lost_op_count,
+                )
+            }
+            Progress::Converged {
+                run_time_s,
+                convergence_s,
+                authored_op_count,
+            } => {
+                write!(
+                    f,
+                    "--!!--\n{:.4}s: ! Converged ! {:.4}s {} ops\n--!!--",
+                    run_time_s, convergence_s, authored_op_count,
+                )
+            }
+            Progress::Summary {
+                run_time_s,
+                authored_op_count,
+                restart_count,
+                converged_count,
+                avg_convergence_s,
+                max_convergence_s,
+                lost_op_count,
+            } => {
+                write!(
+                    f,
+                    r#"{:.4}s: Summary
+ -- authored ops: {}
+ -- restarts: {}
+ -- converged: {} times avg {:.4}s max {:.4}s
+ -- lost ops: {}"#,
+                    run_time_s,
+                    authored_op_count,
+                    restart_count,
+                    converged_count,
+                    avg_convergence_s,
+                    max_convergence_s,
+                    lost_op_count,
+                )
+            }
+        }
+    }
+}
+
+/// run the churn_soak test
+///
+/// The progress stream ends after the [`Progress::Summary`]
+/// if `run_time_s` is set.
+pub fn run(
+    config: Config,
+) -> (
+    impl futures::stream::Stream<Item = Progress>,
+    impl FnOnce() -> BoxFuture<'static, ()>,
+) {
+    let (p_send, p_recv) = futures::channel::mpsc::channel(1024);
+    let task = tokio::task::spawn(test(config, p_send));
+    let shutdown = move || {
+        async move {
+            task.abort();
+        }
+        .boxed()
+    };
+
+    (p_recv, shutdown)
+}
+
+// -- private -- //
+
+struct TestNode {
+    kdirect: KitsuneDirect,
+    kdhnd: KdHnd,
+    agents: Vec<KdHash>,
+}
+
+struct State {
+    /// the number of (authority, op) pairs that should be held
+    target_hold_count: usize,
+
+    /// the number of (authority, op) pairs that are held
+    hold_count: usize,
+
+    /// authored ops not held by any node
+    lost_op_count: usize,
+}
+
+struct Test {
+    config: Config,
+    p_send: futures::channel::mpsc::Sender<Progress>,
+    bootstrap_url: TxUrl,
+    proxy_url: TxUrl,
+    root: KdHash,
+    app_entry: KdEntrySigned,
+    app_entry_hash: KdHash,
+    nodes: Vec<TestNode>,
+
+    /// every entry authored during the test
+    authored: HashSet<KdHash>,
+
+    /// bootstrap and proxy close callbacks,
+    /// in a mutex so the test can be borrowed across awaits
+    close: std::sync::Mutex<Vec<CloseCb>>,
+
+    time_test_start: std::time::Instant,
+}
+
+impl Drop for Test {
+    fn drop(&mut self) {
+        for node in self.nodes.drain(..) {
+            tokio::task::spawn(async move {
+                node.kdhnd.close(0, "").await;
+                node.kdirect.close(0, "").await;
+            });
+        }
+        for close in self.close.get_mut().unwrap().drain(..) {
+            tokio::task::spawn(close(0, ""));
+        }
+    }
+}
+
+impl Test {
+    async fn new(config: Config, p_send: futures::channel::mpsc::Sender<Progress>) -> Self {
+        let time_test_start = std::time::Instant::now();
+
+        let (bootstrap_url, driver, bootstrap_close) =
+            new_quick_bootstrap_v1(config.tuning_params.clone())
+                .await
+                .unwrap();
+        tokio::task::spawn(driver);
+
+        let (proxy_url, driver, proxy_close) = new_quick_proxy_v1(config.tuning_params.clone())
+            .await
+            .unwrap();
+        tokio::task::spawn(driver);
+
+        let (root, app_entry) = {
+            let root_persist = new_persist_mem();
+            let root = root_persist.generate_signing_keypair().await.unwrap();
+            let app_entry = KdEntryContent {
+                kind: "s.app".to_string(),
+                parent: root.clone(),
+                author: root.clone(),
+                verify: "".to_string(),
+                data: serde_json::json!({}),
+            };
+            let app_entry = KdEntrySigned::from_content(&root_persist, app_entry)
+                .await
+                .unwrap();
+            (root, app_entry)
+        };
+
+        let app_entry_hash = app_entry.hash().clone();
+
+        Self {
+            config,
+            p_send,
+            bootstrap_url,
+            proxy_url,
+            root,
+            app_entry,
+            app_entry_hash,
+            nodes: Vec::new(),
+            authored: HashSet::new(),
+
+            close: std::sync::Mutex::new(vec![bootstrap_close, proxy_close]),
+
+            time_test_start,
+        }
+    }
+
+    fn run_time_s(&self) -> f64 {
+        self.time_test_start.elapsed().as_secs_f64()
+    }
+
+    async fn spawn_node(&self, persist: KdPersist) -> (KitsuneDirect, KdHnd) {
+        let conf = KitsuneDirectV1Config {
+            tuning_params: self.config.tuning_params.clone(),
+            persist,
+            bootstrap: self.bootstrap_url.clone(),
+            proxy: self.proxy_url.clone(),
+            ui_port: 0,
+        };
+
+        let (kdirect, driver) = new_kitsune_direct_v1(conf).await.unwrap();
+        tokio::task::spawn(driver);
+
+        let (kdhnd, mut evt) = kdirect.bind_control_handle().await.unwrap();
+        tokio::task::spawn(async move {
+            while let Some(evt) = evt.next().await {
+                tracing::trace!(?evt);
+            }
+        });
+
+        (kdirect, kdhnd)
+    }
+
+    async fn join_agent(&self, kdirect: &KitsuneDirect, kdhnd: &KdHnd, agent: KdHash) {
+        kdhnd
+            .app_join(self.root.clone(), agent.clone())
+            .await
+            .unwrap();
+
+        // sneak this directly into the db : )
+        kdirect
+            .get_persist()
+            .store_entry(self.root.clone(), agent, self.app_entry.clone())
+            .await
+            .unwrap();
+    }
+
+    async fn new_agents(&self, kdirect: &KitsuneDirect, kdhnd: &KdHnd) -> Vec<KdHash> {
+        let mut agents = Vec::new();
+        for _ in 0..self.config.agents_per_node {
+            let agent = kdirect
+                .get_persist()
+                .generate_signing_keypair()
+                .await
+                .unwrap();
+            self.join_agent(kdirect, kdhnd, agent.clone()).await;
+            agents.push(agent);
+        }
+        agents
+    }
+
+    async fn add_node(&mut self) {
+        let (kdirect, kdhnd) = self.spawn_node(new_persist_mem()).await;
+        let agents = self.new_agents(&kdirect, &kdhnd).await;
+        self.nodes.push(TestNode {
+            kdirect,
+            kdhnd,
+            agents,
+        });
+    }
+
+    /// Shut down a random node and start it again.
+    /// Unless wiping, the node keeps its store and rejoins its agents.
+    async fn restart_node(&mut self) {
+        use rand::Rng;
+
+        let node_idx = rand::thread_rng().gen_range(0..self.nodes.len());
+        let wiped = self.config.wipe_on_restart;
+
+        let node = &self.nodes[node_idx];
+        node.kdhnd.close(0, "").await;
+        node.kdirect.close(0, "").await;
+
+        let persist = if wiped {
+            new_persist_mem()
+        } else {
+            node.kdirect.get_persist()
+        };
+        let (kdirect, kdhnd) = self.spawn_node(persist).await;
+        let agents = if wiped {
+            self.new_agents(&kdirect, &kdhnd).await
+        } else {
+            for agent in self.nodes[node_idx].agents.iter() {
+                self.join_agent(&kdirect, &kdhnd, agent.clone()).await;
+            }
+            self.nodes[node_idx].agents.clone()
+        };
+        self.nodes[node_idx] = TestNode {
+            kdirect,
+            kdhnd,
+            agents,
+        };
+
+        self.emit(Progress::NodeRestarted {
+            run_time_s: self.run_time_s(),
+            node_idx,
+            wiped,
+        })
+        .await;
+    }
+
+    async fn author_entries(&mut self) {
+        for node in self.nodes.iter() {
+            for agent in node.agents.iter() {
+                let entry = node
+                    .kdhnd
+                    .entry_author(
+                        self.root.clone(),
+                        agent.clone(),
+                        KdEntryContent {
+                            kind: "u.foo".to_string(),
+                            parent: self.app_entry_hash.clone(),
+                            author: agent.clone(),
+                            verify: "".to_string(),
+                            data: serde_json::json!({
+                                "nonce": std::time::SystemTime::now()
+                                    .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                                    .unwrap()
+                                    .as_secs_f64(),
+                            }),
+                        },
+                        vec![].into_boxed_slice().into(),
+                    )
+                    .await
+                    .unwrap();
+                self.authored.insert(entry.hash().clone());
+            }
+        }
+    }
+
+    async fn calc_state(&self) -> State {
+        let mut out = State {
+            target_hold_count: 0,
+            hold_count: 0,
+            lost_op_count: 0,
+        };
+
+        let mut held_anywhere = HashSet::new();
+        for node in self.nodes.iter() {
+            for agent in node.agents.iter() {
+                // which authored ops does this agent hold?
+                let mut has_ops = HashSet::new();
+                for op in node
+                    .kdirect
+                    .get_persist()
+                    .query_entries(
+                        self.root.clone(),
+                        agent.clone(),
+                        full_time_window(),
+                        DhtArcSet::from(DhtArcRange::Full),
+                    )
+                    .await
+                    .unwrap()
+                {
+                    if self.authored.contains(op.hash()) {
+                        has_ops.insert(op.hash().clone());
+                    }
+                }
+
+                // of the ops this agent *should* hold,
+                // which *are* they holding?
+                for op in self.authored.iter() {
+                    let should_hold = node
+                        .kdhnd
+                        .is_authority(self.root.clone(), agent.clone(), op.clone())
+                        .await
+                        .unwrap();
+                    if should_hold {
+                        out.target_hold_count += 1;
+                        if has_ops.contains(op) {
+                            out.hold_count += 1;
+                        }
+                    }
+                }
+
+                held_anywhere.extend(has_ops);
+            }
+        }
+
+        out.lost_op_count = self.authored.len() - held_anywhere.len();
+
+        out
+    }
+
+    async fn emit(&mut self, progress: Progress) {
+        // the receiver may have been dropped, the test keeps running
+        // until it's shut down
+        let _ = self.p_send.send(progress).await;
+    }
+}
+
+async fn test(config: Config, p_send: futures::channel::mpsc::Sender<Progress>) {
+    let author_interval = std::time::Duration::from_millis(config.author_interval_ms);
+    let restart_interval = config
+        .restart_interval_ms
+        .map(std::time::Duration::from_millis);
+    let run_time = config.run_time_s.map(std::time::Duration::from_secs);
+
+    let mut test = Test::new(config, p_send).await;
+
+    for _ in 0..test.config.node_count {
+        test.add_node().await;
+    }
+
+    test.emit(Progress::TestStarted {
+        run_time_s: test.run_time_s(),
+        node_count: test.config.node_count,
+        agents_per_node: test.config.agents_per_node,
+        restart_interval_ms: test.config.restart_interval_ms,
+        wipe_on_restart: test.config.wipe_on_restart,
+    })
+    .await;
+
+    let start = tokio::time::Instant::now();
+    let mut next_author = start;
+    let mut next_restart = restart_interval.map(|i| start + i);
+
+    let mut restart_count = 0;
+    let mut convergence_times = Vec::new();
+    let mut unconverged_since: Option<std::time::Instant> = None;
+
+    // this loop authors entries, restarts nodes and
+    // measures how long it takes to converge again
+    loop {
+        let now = tokio::time::Instant::now();
+
+        if let Some(run_time) = run_time {
+            if now >= start + run_time {
+                break;
+            }
+        }
+
+        if now >= next_author {
+            test.author_entries().await;
+            next_author = now + author_interval;
+        }
+
+        if let (Some(next), Some(interval)) = (next_restart, restart_interval) {
+            if now >= next {
+                test.restart_node().await;
+                restart_count += 1;
+                next_restart = Some(now + interval);
+            }
+        }
+
+        let state = test.calc_state().await;
+        test.emit(Progress::InterimState {
+            run_time_s: test.run_time_s(),
+            authored_op_count: test.authored.len(),
+            target_hold_count: state.target_hold_count,
+            hold_count: state.hold_count,
+            lost_op_count: state.lost_op_count,
+        })
+        .await;
+
+        if state.hold_count >= state.target_hold_count {
+            if let Some(since) = unconverged_since.take() {
+                let convergence_s = since.elapsed().as_secs_f64();
+                convergence_times.push(convergence_s);
+                test.emit(Progress::Converged {
+                    run_time_s: test.run_time_s(),
+                    convergence_s,
+                    authored_op_count: test.authored.len(),
+                })
+                .await;
+            }
+        } else if unconverged_since.is_none() {
+            unconverged_since = Some(std::time::Instant::now());
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    let state = test.calc_state().await;
+    let converged_count = convergence_times.len();
+    let avg_convergence_s = if converged_count == 0 {
+        0.0
+    } else {
+        convergence_times.iter().sum::<f64>() / converged_count as f64
+    };
+    let max_convergence_s = convergence_times.iter().cloned().fold(0.0, f64::max);
+
+    test.emit(Progress::Summary {
+        run_time_s: test.run_time_s(),
+        authored_op_count: test.authored.len(),
+        restart_count,
+        converged_count,
+        avg_convergence_s,
+        max_convergence_s,
+        lost_op_count: state.lost_op_count,
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn churn_soak_test() {
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_peer_on_success_next_gossip_delay_ms = 1000;
+        let tuning_params = std::sync::Arc::new(tuning_params);
+        let (mut progress, shutdown) = run(Config {
+            tuning_params,
+            node_count: 2,
+            agents_per_node: 2,
+            author_interval_ms: 1000,
+            restart_interval_ms: Some(2000),
+            wipe_on_restart: false,
+            run_time_s: Some(5),
+        });
+
+        let mut summary = None;
+        while let Ok(Some(progress)) =
+            tokio::time::timeout(std::time::Duration::from_secs(30), progress.next()).await
+        {
+            println!("{}", progress);
+            if let Progress::Summary { .. } = progress {
+                summary = Some(progress);
+            }
+        }
+
+        shutdown().await;
+
+        match summary {
+            Some(Progress::Summary {
+                authored_op_count,
+                restart_count,
+                lost_op_count,
+                ..
+            }) => {
+                assert!(authored_op_count > 0);
+                assert!(restart_count > 0);
+                // restarted nodes keep their store so nothing can be lost
+                assert_eq!(0, lost_op_count);
+            }
+            _ => panic!("expected a summary"),
+        }
+    }
+}
@@ -5,5 +5,6 @@
 #![deny(unsafe_code)]
 #![allow(clippy::blocks_in_if_conditions)]
 
+pub mod churn_soak;
 pub mod consistency_stress;
 pub mod direct_test_local_periodic;
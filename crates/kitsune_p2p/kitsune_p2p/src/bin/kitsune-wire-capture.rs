@@ -0,0 +1,117 @@
+use kitsune_p2p::gossip::sharded_gossip::{GossipType, ShardedGossipLocal, ShardedGossipWire};
+use kitsune_p2p::wire_capture::{read_wire_capture, WireCaptureRecord};
+use kitsune_p2p::GossipModuleType;
+use kitsune_p2p_types::codec::Codec;
+use kitsune_p2p_types::tx2::tx2_api::Tx2Direction;
+use kitsune_p2p_types::*;
+use structopt::StructOpt;
+
+/// Inspect and replay wire capture logs, as written by a node
+/// with the `wire_capture_path` tuning param set.
+#[derive(structopt::StructOpt, Debug)]
+#[structopt(name = "kitsune-wire-capture")]
+pub enum Opt {
+    /// Print every record in the log.
+    Dump {
+        /// The wire capture log.
+        path: std::path::PathBuf,
+    },
+    /// Feed the captured gossip back into the gossip state machine
+    /// and compare its replies with the captured ones.
+    Replay {
+        /// The wire capture log.
+        path: std::path::PathBuf,
+        /// Replay recent gossip instead of historical gossip.
+        #[structopt(long)]
+        recent: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    observability::test_run().ok();
+
+    if let Err(e) = inner().await {
+        eprintln!("{:?}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn inner() -> KitsuneResult<()> {
+    match Opt::from_args() {
+        Opt::Dump { path } => {
+            for record in read(&path)? {
+                let gossip = gossip(&record).map(|(_, msg)| msg.variant_type());
+                println!(
+                    "{} {:?} {:?} {} {} bytes {}",
+                    record.timestamp,
+                    record.direction,
+                    record.peer_cert(),
+                    record.kind,
+                    record.len,
+                    gossip.unwrap_or_default(),
+                );
+            }
+        }
+        Opt::Replay { path, recent } => {
+            let gossip_type = if recent {
+                GossipType::Recent
+            } else {
+                GossipType::Historical
+            };
+            let records = read(&path)?;
+            let captured: Vec<_> = records
+                .iter()
+                .filter(|record| record.direction == Tx2Direction::Outbound)
+                .filter_map(|record| gossip(record).filter(|(module, _)| *module == gossip_type))
+                .map(|(_, msg)| msg)
+                .filter(|msg| !matches!(msg, ShardedGossipWire::Initiate(_)))
+                .collect();
+            let node =
+                ShardedGossipLocal::for_capture(Default::default(), gossip_type, &records).await?;
+            let replayed = node.replay(records).await?;
+
+            let mut differ = 0;
+            for i in 0..captured.len().max(replayed.len()) {
+                let captured = captured.get(i).map(|msg| msg.variant_type());
+                let replayed = replayed.get(i).map(|(_, msg)| msg.variant_type());
+                let mark = if captured == replayed {
+                    " "
+                } else {
+                    differ += 1;
+                    "!"
+                };
+                println!(
+                    "{} {:<24} {}",
+                    mark,
+                    captured.unwrap_or("-"),
+                    replayed.unwrap_or("-")
+                );
+            }
+            println!(
+                "{} captured replies, {} replayed replies, {} differ",
+                captured.len(),
+                replayed.len(),
+                differ
+            );
+        }
+    }
+    Ok(())
+}
+
+fn read(path: &std::path::Path) -> KitsuneResult<Vec<WireCaptureRecord>> {
+    let file = std::fs::File::open(path).map_err(KitsuneError::other)?;
+    read_wire_capture(std::io::BufReader::new(file)).map_err(KitsuneError::other)
+}
+
+/// The sharded gossip in a record and the gossip type it is for.
+fn gossip(record: &WireCaptureRecord) -> Option<(GossipType, ShardedGossipWire)> {
+    let gossip = record.gossip.as_ref()?;
+    let gossip_type = match gossip.module {
+        GossipModuleType::ShardedRecent => GossipType::Recent,
+        GossipModuleType::ShardedHistorical => GossipType::Historical,
+        _ => return None,
+    };
+    let (_, msg) = ShardedGossipWire::decode_ref(&gossip.data).ok()?;
+    Some((gossip_type, msg))
+}
@@ -28,6 +28,7 @@ use std::time::{Duration, SystemTime};
 use tokio::time::Instant;
 
 pub use self::bandwidth::BandwidthThrottle;
+use self::bandwidth::RoundBandwidthThrottles;
 use self::ops::OpsBatchQueue;
 use self::state_map::RoundStateMap;
 use crate::metrics::MetricsSync;
@@ -45,6 +46,8 @@ mod agents;
 mod bloom;
 mod initiate;
 mod ops;
+mod replay;
+pub use replay::CaptureHost;
 mod state_map;
 mod store;
 
@@ -112,6 +115,8 @@ pub struct ShardedGossip {
     pub(crate) state: Share<ShardedGossipState>,
     /// Bandwidth for incoming and outgoing gossip.
     bandwidth: Arc<BandwidthThrottle>,
+    /// Bandwidth for incoming and outgoing gossip in each round.
+    round_bandwidth: parking_lot::Mutex<RoundBandwidthThrottles>,
 }
 
 impl std::fmt::Debug for ShardedGossip {
@@ -178,6 +183,7 @@ impl ShardedGossip {
         let this = Arc::new(Self {
             ep_hnd,
             state: Share::new(state),
+            round_bandwidth: parking_lot::Mutex::new(RoundBandwidthThrottles::new(&tuning_params)),
             gossip: ShardedGossipLocal {
                 tuning_params,
                 space,
@@ -234,6 +240,10 @@ impl ShardedGossip {
                 }
             }
         };
+        let num_ops = match &gossip {
+            ShardedGossipWire::MissingOps(MissingOps { ops, .. }) => ops.len() as u64,
+            _ => 0,
+        };
         let gossip = gossip.encode_vec().map_err(KitsuneError::other)?;
         let bytes = gossip.len();
         let gossip = wire::Wire::gossip(
@@ -245,6 +255,10 @@ impl ShardedGossip {
         let timeout = self.gossip.tuning_params.implicit_timeout();
 
         self.bandwidth.outgoing_bytes(bytes).await;
+        let round_bandwidth = self.round_bandwidth.lock().get(&cert);
+        if let Some(round_bandwidth) = round_bandwidth {
+            round_bandwidth.outgoing_bytes(bytes).await;
+        }
 
         let con = match how.clone() {
             HowToConnect::Con(con, remote_url) => {
@@ -259,13 +273,20 @@ impl ShardedGossip {
         // Wait for enough available outgoing bandwidth here before
         // actually sending the gossip.
         con.notify(&gossip, timeout).await?;
-        Ok(())
+        self.gossip.inner.share_mut(|i, _| {
+            i.ops_sent += num_ops;
+            Ok(())
+        })
     }
 
     async fn process_incoming_outgoing(&self) -> KitsuneResult<()> {
         let (incoming, outgoing) = self.pop_queues()?;
         if let Some((con, remote_url, msg, bytes)) = incoming {
             self.bandwidth.incoming_bytes(bytes).await;
+            let round_bandwidth = self.round_bandwidth.lock().get(&con.peer_cert());
+            if let Some(round_bandwidth) = round_bandwidth {
+                round_bandwidth.incoming_bytes(bytes).await;
+            }
             let outgoing = match self.gossip.process_incoming(con.peer_cert(), msg).await {
                 Ok(r) => r,
                 Err(e) => {
@@ -319,6 +340,10 @@ impl ShardedGossip {
             tracing::error!("Gossip failed to process a message because of: {:?}", err);
         }
         self.gossip.record_timeouts();
+        match self.gossip.current_rounds() {
+            Ok(current_rounds) => self.round_bandwidth.lock().retain(&current_rounds),
+            Err(err) => tracing::error!("Gossip failed to get the current rounds {:?}", err),
+        }
     }
 
     fn pop_queues(&self) -> KitsuneResult<(Option<Incoming>, Option<Outgoing>)> {
@@ -406,6 +431,12 @@ pub struct ShardedGossipLocalState {
     /// Metrics that track remote node states and help guide
     /// the next node to gossip with.
     metrics: MetricsSync,
+    /// The number of rounds that finished without error.
+    completed_rounds: u64,
+    /// The number of ops sent to remote nodes.
+    ops_sent: u64,
+    /// The number of ops received from remote nodes.
+    ops_received: u64,
 }
 
 impl ShardedGossipLocalState {
@@ -434,7 +465,14 @@ impl ShardedGossipLocalState {
             if error {
                 self.metrics.write().record_error(&r.remote_agent_list);
             } else {
-                self.metrics.write().record_success(&r.remote_agent_list);
+                let mut metrics = self.metrics.write();
+                metrics.record_success(&r.remote_agent_list);
+                // Only historical rounds sync every op in the common arc set,
+                // which is when the regions were sent.
+                if r.region_set_sent.is_some() {
+                    metrics.record_synced(&r.remote_agent_list, &r.common_arc_set);
+                }
+                self.completed_rounds += 1;
             }
         } else if init_tgt && error {
             self.metrics.write().record_error(&remote_agent_list);
@@ -807,6 +845,10 @@ impl ShardedGossipLocal {
             ShardedGossipWire::MissingOps(MissingOps { ops, finished }) => {
                 let mut gossip = Vec::with_capacity(0);
                 let finished = MissingOpsStatus::try_from(finished)?;
+                self.inner.share_mut(|i, _| {
+                    i.ops_received += ops.len() as u64;
+                    Ok(())
+                })?;
 
                 let state = match finished {
                     // This is a single chunk of ops. No need to reply.
@@ -915,6 +957,42 @@ impl ShardedGossipLocal {
             .ok();
     }
 
+    /// The nodes we are in a round with or are initiating a round with.
+    fn current_rounds(&self) -> KitsuneResult<HashSet<Tx2Cert>> {
+        self.inner.share_mut(|i, _| {
+            let mut rounds = i.round_map.current_rounds();
+            rounds.extend(i.initiate_tgt.as_ref().map(|tgt| tgt.cert.clone()));
+            Ok(rounds)
+        })
+    }
+
+    fn status(&self) -> KitsuneResult<GossipStatus> {
+        self.inner.share_mut(|i, _| {
+            let current_rounds = i
+                .round_map
+                .iter()
+                .map(|(cert, round)| GossipRoundStatus {
+                    peer: cert.as_str().to_string(),
+                    remote_agents: round
+                        .remote_agent_list
+                        .iter()
+                        .map(|info| info.agent.clone())
+                        .collect(),
+                    idle_ms: round.last_touch.elapsed().as_millis() as u64,
+                    op_blooms_sent: round.num_sent_op_blooms,
+                    received_all_op_blooms: round.received_all_incoming_op_blooms,
+                })
+                .collect();
+            Ok(GossipStatus {
+                module: self.gossip_type.into(),
+                current_rounds,
+                completed_rounds: i.completed_rounds,
+                ops_sent: i.ops_sent,
+                ops_received: i.ops_received,
+            })
+        })
+    }
+
     fn show_local_agents(&self) -> HashSet<Arc<KitsuneAgent>> {
         self.inner
             .share_mut(|i, _| Ok(i.local_agents.clone()))
@@ -1178,6 +1256,10 @@ impl AsGossipModule for ShardedGossip {
             Ok(())
         });
     }
+
+    fn status(&self) -> Option<GossipStatus> {
+        self.gossip.status().ok()
+    }
 }
 
 struct ShardedRecentGossipFactory {
@@ -42,6 +42,45 @@ impl BandwidthThrottles {
     }
 }
 
+/// Bandwidth throttles for each round of a gossip loop, so that a single
+/// gossip partner can't use all of the loop's bandwidth.
+pub(crate) struct RoundBandwidthThrottles {
+    inbound_mbps: f64,
+    outbound_mbps: f64,
+    rounds: HashMap<Tx2Cert, Arc<BandwidthThrottle>>,
+}
+
+impl RoundBandwidthThrottles {
+    /// Create the per round throttles from the configuration.
+    pub(crate) fn new(tuning_params: &KitsuneP2pTuningParams) -> Self {
+        Self {
+            inbound_mbps: tuning_params.gossip_round_inbound_target_mbps,
+            outbound_mbps: tuning_params.gossip_round_outbound_target_mbps,
+            rounds: HashMap::new(),
+        }
+    }
+
+    /// Get the throttle for the round with this node,
+    /// or `None` if rounds are not limited.
+    pub(crate) fn get(&mut self, cert: &Tx2Cert) -> Option<Arc<BandwidthThrottle>> {
+        if self.inbound_mbps <= 0.0 && self.outbound_mbps <= 0.0 {
+            return None;
+        }
+        let (inbound_mbps, outbound_mbps) = (self.inbound_mbps, self.outbound_mbps);
+        Some(
+            self.rounds
+                .entry(cert.clone())
+                .or_insert_with(|| Arc::new(BandwidthThrottle::new(inbound_mbps, outbound_mbps)))
+                .clone(),
+        )
+    }
+
+    /// Drop the throttles of rounds that have ended.
+    pub(crate) fn retain(&mut self, current_rounds: &HashSet<Tx2Cert>) {
+        self.rounds.retain(|cert, _| current_rounds.contains(cert));
+    }
+}
+
 /// Manages incoming and outgoing bandwidth by providing methods which
 /// asynchronously wait for enough bandwidth to become available before
 /// processing a chunk of bytes
@@ -283,4 +322,24 @@ mod tests {
         // Allow for small rounding error.
         assert!(mbps < 0.11);
     }
+
+    #[test]
+    fn round_throttles_are_per_round() {
+        let mut t = tuning_params_struct::KitsuneP2pTuningParams::default();
+        assert!(RoundBandwidthThrottles::new(&Arc::new(t.clone()))
+            .get(&Tx2Cert::from(vec![1; 32]))
+            .is_none());
+
+        t.gossip_round_outbound_target_mbps = 0.1;
+        let mut rounds = RoundBandwidthThrottles::new(&Arc::new(t));
+        let a = Tx2Cert::from(vec![1; 32]);
+        let b = Tx2Cert::from(vec![2; 32]);
+        let throttle = rounds.get(&a).unwrap();
+        assert!(Arc::ptr_eq(&throttle, &rounds.get(&a).unwrap()));
+        assert!(!Arc::ptr_eq(&throttle, &rounds.get(&b).unwrap()));
+
+        // - Ended rounds start again with a fresh throttle
+        rounds.retain(&[b].into_iter().collect());
+        assert!(!Arc::ptr_eq(&throttle, &rounds.get(&a).unwrap()));
+    }
 }
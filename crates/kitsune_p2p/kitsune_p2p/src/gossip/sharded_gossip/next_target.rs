@@ -3,6 +3,14 @@ use std::cmp::Ordering;
 use super::*;
 use crate::metrics::*;
 
+/// How far back to look in the recorded metrics for
+/// how responsive remote agents have been (currently 1 day).
+const PEER_HISTORY_WINDOW_MICROS: i64 = 1000 * 1000 * 60 * 60 * 24;
+
+/// Nodes whose recorded error rate is at least this high are
+/// only chosen once there is no other node to gossip with.
+const UNRESPONSIVE_ERROR_RATE: f64 = 0.5;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// A remote node we can connect to.
 /// Note that a node can contain many agents.
@@ -12,6 +20,70 @@ pub(crate) struct Node {
     pub(crate) url: TxUrl,
 }
 
+/// How responsive remote agents have been, according to the metrics
+/// recorded by the host. Unlike [`MetricsSync`] this survives restarts.
+#[derive(Debug, Default)]
+pub(crate) struct PeerHistory {
+    error_rate: HashMap<Arc<KitsuneAgent>, f64>,
+    latency_micros: HashMap<Arc<KitsuneAgent>, f64>,
+}
+
+impl PeerHistory {
+    /// Query the host for recent error rates and latencies.
+    /// Hosts that don't record metrics give an empty history.
+    async fn query(host_api: &HostApi, space: Arc<KitsuneSpace>) -> Self {
+        let since =
+            Timestamp::from_micros(Timestamp::now().as_micros() - PEER_HISTORY_WINDOW_MICROS);
+        let mut history = Self::default();
+        match host_api
+            .query_metrics(space.clone(), MetricQuery::ErrorRate { since })
+            .await
+        {
+            Ok(MetricQueryAnswer::ErrorRate(rates)) => history.error_rate.extend(rates),
+            Ok(_) => (),
+            Err(e) => tracing::debug!("Failed to query peer error rates: {:?}", e),
+        }
+        match host_api
+            .query_metrics(space, MetricQuery::LatencyMicros { since })
+            .await
+        {
+            Ok(MetricQueryAnswer::LatencyMicros(latencies)) => {
+                history.latency_micros.extend(latencies)
+            }
+            Ok(_) => (),
+            Err(e) => tracing::debug!("Failed to query peer latencies: {:?}", e),
+        }
+        history
+    }
+
+    /// Has this node mostly failed to complete gossip rounds?
+    fn is_unresponsive(&self, node: &Node) -> bool {
+        match mean(&self.error_rate, node) {
+            Some(error_rate) => error_rate >= UNRESPONSIVE_ERROR_RATE,
+            None => false,
+        }
+    }
+
+    /// The mean recorded latency of the agents on this node.
+    fn latency_micros(&self, node: &Node) -> Option<f64> {
+        mean(&self.latency_micros, node)
+    }
+}
+
+/// The mean of the values recorded for the agents on this node, if any.
+fn mean(values: &HashMap<Arc<KitsuneAgent>, f64>, node: &Node) -> Option<f64> {
+    let (sum, cnt) = node
+        .agent_info_list
+        .iter()
+        .filter_map(|info| values.get(&info.agent))
+        .fold((0.0, 0), |acc, x| (acc.0 + x, acc.1 + 1));
+    if cnt == 0 {
+        None
+    } else {
+        Some(sum / cnt as f64)
+    }
+}
+
 impl ShardedGossipLocal {
     /// Find a remote endpoint from agents within arc set.
     pub(super) async fn find_remote_agent_within_arcset(
@@ -85,11 +157,12 @@ impl ShardedGossipLocal {
         }
 
         let remote_nodes = remote_nodes.into_iter().map(|(_, v)| v).collect();
+        let history = PeerHistory::query(&self.host_api, self.space.clone()).await;
         let tuning_params = self.tuning_params.clone();
         // We could clone the metrics store out of the lock here but I don't think
         // the next_remote_node will be that slow so we can just choose the next node inline.
         self.inner.share_mut(|i, _| {
-            let node = next_remote_node(remote_nodes, &i.metrics, tuning_params);
+            let node = next_remote_node(remote_nodes, &i.metrics, &history, tuning_params);
             Ok(node)
         })
     }
@@ -99,6 +172,7 @@ impl ShardedGossipLocal {
 fn next_remote_node(
     mut remote_nodes: Vec<Node>,
     metrics: &MetricsSync,
+    history: &PeerHistory,
     tuning_params: KitsuneP2pTuningParams,
 ) -> Option<Node> {
     use rand::prelude::*;
@@ -106,10 +180,28 @@ fn next_remote_node(
 
     // dbg!(&remote_nodes, metrics);
 
-    // Sort the nodes by longest time since we last successfully gossiped with them.
-    // Randomly break ties between nodes we haven't successfully gossiped with.
+    // Put nodes that have mostly failed to gossip with us behind the rest.
+    // Then put nodes that would receive under-replicated ops they don't hold yet first.
+    // Then sort the nodes by longest time since we last successfully gossiped with them.
+    // Break ties between nodes we haven't successfully gossiped with by
+    // their recorded latency, putting nodes without one last.
+    // Shuffling first and using a stable sort breaks any remaining ties randomly.
     // Note the smaller an Instant the longer it is in the past.
-    remote_nodes.sort_unstable_by(|a, b| {
+    remote_nodes.shuffle(&mut rng);
+    remote_nodes.sort_by(|a, b| {
+        match (history.is_unresponsive(a), history.is_unresponsive(b)) {
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            _ => (),
+        }
+        match (
+            metrics.read().adds_redundancy(&a.agent_info_list),
+            metrics.read().adds_redundancy(&b.agent_info_list),
+        ) {
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => (),
+        }
         match (
             metrics.read().last_success(&a.agent_info_list),
             metrics.read().last_success(&b.agent_info_list),
@@ -120,14 +212,13 @@ fn next_remote_node(
             (Some(_), None) => Ordering::Greater,
             // Put b behind a that hasn't been gossiped with.
             (None, Some(_)) => Ordering::Less,
-            // Randomly break ties.
-            (None, None) => {
-                if rng.gen() {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
-                }
-            }
+            (None, None) => match (history.latency_micros(a), history.latency_micros(b)) {
+                // Choose the node that has answered faster.
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
         }
     });
 
@@ -238,6 +329,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &Default::default(),
+            &PeerHistory::default(),
             tuning_params_no_delay(),
         );
 
@@ -273,7 +365,12 @@ mod tests {
         // - Push the last node back into the remote nodes.
         remote_nodes.push(last);
 
-        let r = next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay());
+        let r = next_remote_node(
+            remote_nodes.clone(),
+            &metrics,
+            &PeerHistory::default(),
+            tuning_params_no_delay(),
+        );
 
         // - Expect the last node to be chosen because it was the least recently gossiped with.
         assert_eq!(r, remote_nodes.last().cloned());
@@ -303,7 +400,12 @@ mod tests {
         // - Push the last node back into the remote nodes.
         remote_nodes.push(last);
 
-        let r = next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay());
+        let r = next_remote_node(
+            remote_nodes.clone(),
+            &metrics,
+            &PeerHistory::default(),
+            tuning_params_no_delay(),
+        );
 
         // - Expect the last node to be chosen because it was never gossiped with.
         assert_eq!(r, remote_nodes.last().cloned());
@@ -336,8 +438,13 @@ mod tests {
         let mut chose_last = false;
         let mut chose_second_last = false;
         for _ in 0..100 {
-            let r =
-                next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay()).unwrap();
+            let r = next_remote_node(
+                remote_nodes.clone(),
+                &metrics,
+                &PeerHistory::default(),
+                tuning_params_no_delay(),
+            )
+            .unwrap();
             if r == last {
                 chose_last = true;
             } else if r == second_last {
@@ -347,6 +454,52 @@ mod tests {
         assert!(chose_last && chose_second_last);
     }
 
+    #[test]
+    /// Test we prefer nodes that have historically
+    /// completed gossip rounds and answered quickly.
+    fn prefer_responsive_nodes() {
+        // - Create 3 remote nodes we've never talked to.
+        let remote_nodes = create_remote_nodes(3);
+        let agent = |i: usize| remote_nodes[i].agent_info_list[0].agent.clone();
+
+        let metrics = MetricsSync::default();
+
+        // - The first node mostly errored and the second is slower than the third.
+        let mut history = PeerHistory::default();
+        history.error_rate.insert(agent(0), 0.9);
+        history.error_rate.insert(agent(1), 0.1);
+        history.latency_micros.insert(agent(0), 10.0);
+        history.latency_micros.insert(agent(1), 2000.0);
+        history.latency_micros.insert(agent(2), 1000.0);
+
+        // - Expect the third node to always be chosen.
+        for _ in 0..100 {
+            let r = next_remote_node(
+                remote_nodes.clone(),
+                &metrics,
+                &history,
+                tuning_params_no_delay(),
+            );
+            assert_eq!(r, remote_nodes.get(2).cloned());
+        }
+
+        // - Record successful rounds for the second and third nodes.
+        for node in &remote_nodes[1..] {
+            metrics.write().record_initiate(&node.agent_info_list);
+            metrics.write().record_success(&node.agent_info_list);
+        }
+
+        // - Expect the unresponsive node to still come last even though
+        //   it is the only one we haven't gossiped with.
+        let r = next_remote_node(
+            remote_nodes.clone(),
+            &metrics,
+            &history,
+            tuning_params_no_delay(),
+        );
+        assert_eq!(r, remote_nodes.get(1).cloned());
+    }
+
     /// Test that given N remote nodes we never choose a current round.
     #[test_case(1)]
     #[test_case(2)]
@@ -367,7 +520,12 @@ mod tests {
             metrics.write().record_remote_round(&node.agent_info_list);
         }
 
-        let r = next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay());
+        let r = next_remote_node(
+            remote_nodes.clone(),
+            &metrics,
+            &PeerHistory::default(),
+            tuning_params_no_delay(),
+        );
 
         // - Without the last node we expect no nodes to be chosen.
         assert!(r.is_none());
@@ -377,7 +535,12 @@ mod tests {
         metrics.write().record_success(&last.agent_info_list);
         remote_nodes.push(last);
 
-        let r = next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay());
+        let r = next_remote_node(
+            remote_nodes.clone(),
+            &metrics,
+            &PeerHistory::default(),
+            tuning_params_no_delay(),
+        );
 
         // - Now we expect the last node to be chosen.
         // (because we're using "no delay" for the tuning params)
@@ -401,6 +564,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -414,6 +578,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -424,6 +589,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a 9 ms after the successful round.
             tuning_params_delay(9, 0),
         );
@@ -440,6 +606,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(0, 1000 * 60),
         );
@@ -453,6 +620,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(0, 1000 * 60),
         );
@@ -463,6 +631,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a 9 ms after an error round.
             tuning_params_delay(1000 * 60, 9),
         );
@@ -502,6 +671,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -515,6 +685,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -531,6 +702,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -546,6 +718,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -577,6 +750,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -592,6 +766,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -607,6 +782,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -620,6 +796,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -638,6 +815,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -653,6 +831,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -668,6 +847,7 @@ mod tests {
         let r = next_remote_node(
             remote_nodes.clone(),
             &metrics,
+            &PeerHistory::default(),
             // - Set the tuning params to a delay in the future.
             tuning_params_delay(1000 * 60, 0),
         );
@@ -675,4 +855,36 @@ mod tests {
         // - Now the reset has run out we get no nodes.
         assert!(r.is_none());
     }
+
+    /// Test that when part of our arc is under-replicated we choose
+    /// a node that doesn't hold it yet over one we talked to longer ago.
+    #[test]
+    fn next_remote_node_adds_redundancy() {
+        let remote_nodes = create_remote_nodes(2);
+        let metrics = MetricsSync::default();
+
+        // - Nobody else holds our arc yet.
+        metrics.write().record_redundancy(&DhtArcSet::Full, 3.0);
+
+        // - The first node finished a historical round with us a while ago.
+        let holder = &remote_nodes[0].agent_info_list;
+        metrics.write().record_initiate(holder);
+        metrics.write().record_success(holder);
+        metrics.write().record_synced(holder, &DhtArcSet::Full);
+
+        // - The second node gossiped with us more recently but doesn't hold our ops.
+        let other = &remote_nodes[1].agent_info_list;
+        metrics.write().record_initiate(other);
+        metrics.write().record_success(other);
+
+        let r = next_remote_node(
+            remote_nodes.clone(),
+            &metrics,
+            &PeerHistory::default(),
+            tuning_params_no_delay(),
+        );
+
+        // - The second node is chosen so our ops get pushed to it.
+        assert_eq!(r, remote_nodes.get(1).cloned());
+    }
 }
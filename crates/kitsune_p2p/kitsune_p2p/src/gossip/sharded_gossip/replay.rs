@@ -0,0 +1,341 @@
+use crate::wire_capture::WireCaptureRecord;
+use crate::{KitsuneHost, KitsuneHostResult};
+use kitsune_p2p_types::dht::prelude::{ArqBoundsSet, RegionCoordSetLtcs};
+use kitsune_p2p_types::dht::spacetime::{TelescopingTimes, Topology};
+use kitsune_p2p_types::dht::ArqStrat;
+
+use super::*;
+
+impl ShardedGossipLocal {
+    /// Create the gossip state machine for a node with these local agents,
+    /// to replay a wire capture against.
+    pub fn for_replay(
+        tuning_params: KitsuneP2pTuningParams,
+        space: Arc<KitsuneSpace>,
+        evt_sender: EventSender,
+        host_api: HostApi,
+        gossip_type: GossipType,
+        local_agents: HashSet<Arc<KitsuneAgent>>,
+    ) -> Self {
+        Self {
+            gossip_type,
+            tuning_params,
+            space,
+            evt_sender,
+            host_api,
+            inner: Share::new(ShardedGossipLocalState {
+                local_agents,
+                ..Default::default()
+            }),
+            closing: AtomicBool::new(false),
+        }
+    }
+
+    /// Create the gossip state machine for the node a wire capture was
+    /// taken on, as far as it can be rebuilt from the capture alone.
+    /// See [`CaptureHost`] for what the node knows.
+    pub async fn for_capture(
+        tuning_params: KitsuneP2pTuningParams,
+        gossip_type: GossipType,
+        records: &[WireCaptureRecord],
+    ) -> KitsuneResult<Self> {
+        let (host, local_agents) = CaptureHost::new(gossip_type, records)?;
+        let space = host.space.clone();
+        let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
+        let (evt_sender, evt_recv) = futures::channel::mpsc::channel(4096);
+        builder
+            .channel_factory()
+            .attach_receiver(evt_recv)
+            .await
+            .map_err(KitsuneError::other)?;
+        tokio::task::spawn(builder.spawn(host.clone()));
+        Ok(Self::for_replay(
+            tuning_params,
+            space,
+            evt_sender,
+            Arc::new(host),
+            gossip_type,
+            local_agents,
+        ))
+    }
+
+    /// Feed the gossip in a wire capture back through this state machine
+    /// in the order it was captured.
+    ///
+    /// Received gossip is processed as if it had just arrived, and the
+    /// replies are returned with the peer they are for, to compare with
+    /// the replies in the capture. Initiates this node sent are restored
+    /// as the current initiate target, so the accept that follows is
+    /// handled like the original. Gossip for other modules is skipped.
+    pub async fn replay(
+        &self,
+        records: impl IntoIterator<Item = WireCaptureRecord>,
+    ) -> KitsuneResult<Vec<(Tx2Cert, ShardedGossipWire)>> {
+        let module = GossipModuleType::from(self.gossip_type);
+        let mut replies = Vec::new();
+        for record in records {
+            let cert = record.peer_cert();
+            let gossip = match record.gossip {
+                Some(gossip) if gossip.module == module => gossip,
+                _ => continue,
+            };
+            let (_, msg) =
+                ShardedGossipWire::decode_ref(&gossip.data).map_err(KitsuneError::other)?;
+            match (record.direction, msg) {
+                (Tx2Direction::Inbound, msg) => {
+                    let out = self.process_incoming(cert.clone(), msg).await?;
+                    replies.extend(out.into_iter().map(|msg| (cert.clone(), msg)));
+                }
+                (Tx2Direction::Outbound, ShardedGossipWire::Initiate(Initiate { id, .. })) => {
+                    self.inner.share_mut(|i, _| {
+                        i.initiate_tgt = Some(ShardedGossipTarget {
+                            remote_agent_list: Vec::new(),
+                            cert,
+                            tie_break: id,
+                            when_initiated: Some(Instant::now()),
+                            url: TxUrl::from("kitsune-replay://"),
+                        });
+                        Ok(())
+                    })?;
+                }
+                (Tx2Direction::Outbound, _) => (),
+            }
+        }
+        Ok(replies)
+    }
+}
+
+/// The host and event handler for a node rebuilt from a wire capture.
+///
+/// The node knows the agent infos that were exchanged in the captured
+/// gossip, and its local agents are the ones it sent in its own initiates
+/// and accepts. It holds no ops, so replies that depend on op data will
+/// differ from the captured ones.
+#[derive(Clone)]
+pub struct CaptureHost {
+    space: Arc<KitsuneSpace>,
+    infos: Arc<Vec<AgentInfoSigned>>,
+    topology: Topology,
+}
+
+impl CaptureHost {
+    fn new(
+        gossip_type: GossipType,
+        records: &[WireCaptureRecord],
+    ) -> KitsuneResult<(Self, HashSet<Arc<KitsuneAgent>>)> {
+        let module = GossipModuleType::from(gossip_type);
+        let mut infos = HashMap::new();
+        let mut local_agents = HashSet::new();
+        for record in records {
+            let gossip = match &record.gossip {
+                Some(gossip) if gossip.module == module => gossip,
+                _ => continue,
+            };
+            let (_, msg) =
+                ShardedGossipWire::decode_ref(&gossip.data).map_err(KitsuneError::other)?;
+            let agent_list = match msg {
+                ShardedGossipWire::Initiate(Initiate { agent_list, .. })
+                | ShardedGossipWire::Accept(Accept { agent_list, .. }) => {
+                    if record.direction == Tx2Direction::Outbound {
+                        local_agents.extend(agent_list.iter().map(|info| info.agent.clone()));
+                    }
+                    agent_list
+                }
+                ShardedGossipWire::MissingAgents(MissingAgents { agents }) => {
+                    agents.iter().map(|info| (**info).clone()).collect()
+                }
+                _ => continue,
+            };
+            for info in agent_list {
+                infos.insert(info.agent.clone(), info);
+            }
+        }
+        let space = infos
+            .values()
+            .next()
+            .map(|info| info.space.clone())
+            .ok_or_else(|| KitsuneError::other("the capture has no agent infos"))?;
+        let host = Self {
+            space,
+            infos: Arc::new(infos.into_values().collect()),
+            topology: Topology::standard_epoch_full(),
+        };
+        Ok((host, local_agents))
+    }
+}
+
+impl ghost_actor::GhostHandler<KitsuneP2pEvent> for CaptureHost {}
+impl ghost_actor::GhostControlHandler for CaptureHost {}
+
+impl KitsuneHost for CaptureHost {
+    fn get_agent_info_signed(
+        &self,
+        input: GetAgentInfoSignedEvt,
+    ) -> KitsuneHostResult<Option<AgentInfoSigned>> {
+        let info = self.infos.iter().find(|info| info.agent == input.agent);
+        box_fut(Ok(info.cloned()))
+    }
+
+    fn peer_extrapolated_coverage(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _dht_arc_set: DhtArcSet,
+    ) -> KitsuneHostResult<Vec<f64>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn query_region_set(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        dht_arc_set: Arc<DhtArcSet>,
+    ) -> KitsuneHostResult<RegionSetLtcs> {
+        let region_set =
+            ArqBoundsSet::from_dht_arc_set(&self.topology, &ArqStrat::default(), &dht_arc_set)
+                .map(|arqs| {
+                    let coords = RegionCoordSetLtcs::new(TelescopingTimes::new(1.into()), arqs);
+                    RegionSetLtcs::from_data(coords, vec![])
+                })
+                .ok_or_else(|| "an arc in the set could not be quantized".into());
+        box_fut(region_set)
+    }
+
+    fn query_size_limited_regions(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _size_limit: u32,
+        regions: Vec<dht::region::Region>,
+    ) -> KitsuneHostResult<Vec<dht::region::Region>> {
+        box_fut(Ok(regions))
+    }
+
+    fn record_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _records: Vec<MetricRecord>,
+    ) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn query_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        query: MetricQuery,
+    ) -> KitsuneHostResult<MetricQueryAnswer> {
+        box_fut(Ok(query.empty_answer()))
+    }
+
+    fn get_topology(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology> {
+        box_fut(Ok(self.topology.clone()))
+    }
+
+    fn get_blocks(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<Vec<crate::block::Block>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn put_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _block: crate::block::Block,
+    ) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn delete_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _target: crate::block::BlockTarget,
+    ) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn network_rejoined(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+}
+
+impl KitsuneP2pEventHandler for CaptureHost {
+    fn handle_put_agent_info_signed(
+        &mut self,
+        _input: PutAgentInfoSignedEvt,
+    ) -> KitsuneP2pEventHandlerResult<()> {
+        ok_fut(Ok(()))
+    }
+
+    fn handle_query_agents(
+        &mut self,
+        QueryAgentsEvt {
+            agents, arc_set, ..
+        }: QueryAgentsEvt,
+    ) -> KitsuneP2pEventHandlerResult<Vec<AgentInfoSigned>> {
+        let arc_set = arc_set.unwrap_or_else(|| Arc::new(DhtArcSet::Full));
+        let infos = self
+            .infos
+            .iter()
+            .filter(|info| agents.as_ref().map_or(true, |a| a.contains(&info.agent)))
+            .filter(|info| arc_set.contains(info.agent.get_loc()))
+            .cloned()
+            .collect();
+        ok_fut(Ok(infos))
+    }
+
+    fn handle_query_peer_density(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        dht_arc: kitsune_p2p_types::dht_arc::DhtArc,
+    ) -> KitsuneP2pEventHandlerResult<kitsune_p2p_types::dht::PeerView> {
+        let arcs: Vec<_> = self.infos.iter().map(|info| info.storage_arc).collect();
+        let view = kitsune_p2p_types::dht::PeerStrat::default().view(
+            self.topology.clone(),
+            dht_arc,
+            arcs.as_slice(),
+        );
+        ok_fut(Ok(view))
+    }
+
+    fn handle_call(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _to_agent: Arc<KitsuneAgent>,
+        _payload: Vec<u8>,
+    ) -> KitsuneP2pEventHandlerResult<Vec<u8>> {
+        Err("a replayed node can't be called".into())
+    }
+
+    fn handle_notify(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _to_agent: Arc<KitsuneAgent>,
+        _payload: Vec<u8>,
+    ) -> KitsuneP2pEventHandlerResult<()> {
+        ok_fut(Ok(()))
+    }
+
+    fn handle_gossip(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _ops: Vec<KOp>,
+    ) -> KitsuneP2pEventHandlerResult<()> {
+        ok_fut(Ok(()))
+    }
+
+    fn handle_query_op_hashes(
+        &mut self,
+        _input: QueryOpHashesEvt,
+    ) -> KitsuneP2pEventHandlerResult<Option<(Vec<Arc<KitsuneOpHash>>, TimeWindowInclusive)>> {
+        ok_fut(Ok(None))
+    }
+
+    fn handle_fetch_op_data(
+        &mut self,
+        _input: FetchOpDataEvt,
+    ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<KitsuneOpHash>, KOp)>> {
+        ok_fut(Ok(vec![]))
+    }
+
+    fn handle_sign_network_data(
+        &mut self,
+        _input: SignNetworkDataEvt,
+    ) -> KitsuneP2pEventHandlerResult<KitsuneSignature> {
+        Err("a replayed node can't sign".into())
+    }
+}
@@ -63,6 +63,11 @@ impl RoundStateMap {
         self.map.contains_key(key)
     }
 
+    /// Iterate over the current rounds without touching them.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (&StateKey, &RoundState)> {
+        self.map.iter()
+    }
+
     /// Get all timed out rounds.
     pub(super) fn take_timed_out_rounds(&mut self) -> Vec<(StateKey, RoundState)> {
         std::mem::take(&mut self.timed_out)
@@ -10,6 +10,7 @@ use fixt::prelude::*;
 mod bloom;
 mod common;
 mod ops;
+mod replay;
 mod test_two_nodes;
 
 impl ShardedGossipLocal {
@@ -88,12 +88,47 @@ impl KitsuneHost for StandardResponsesHostApi {
         box_fut(Ok(()))
     }
 
+    fn query_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        query: MetricQuery,
+    ) -> crate::KitsuneHostResult<MetricQueryAnswer> {
+        box_fut(Ok(query.empty_answer()))
+    }
+
     fn get_topology(
         &self,
         _space: Arc<KitsuneSpace>,
     ) -> crate::KitsuneHostResult<dht::spacetime::Topology> {
         box_fut(Ok(self.topology.clone()))
     }
+
+    fn get_blocks(
+        &self,
+        _space: Arc<KitsuneSpace>,
+    ) -> crate::KitsuneHostResult<Vec<crate::block::Block>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn put_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _block: crate::block::Block,
+    ) -> crate::KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn delete_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _target: crate::block::BlockTarget,
+    ) -> crate::KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn network_rejoined(&self, _space: Arc<KitsuneSpace>) -> crate::KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
 }
 
 // TODO: integrate with `HandlerBuilder`
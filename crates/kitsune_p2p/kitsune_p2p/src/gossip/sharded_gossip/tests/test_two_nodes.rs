@@ -693,3 +693,54 @@ async fn initiate_times_out() {
         })
         .unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+/// This test checks that the gossip status shows the current
+/// round until it finishes and then counts it as completed.
+async fn status_tracks_rounds_and_ops() {
+    let mut u = arbitrary::Unstructured::new(&NOISE);
+    let cert = Tx2Cert::arbitrary(&mut u).unwrap();
+
+    let bob = setup_standard_player(
+        ShardedGossipLocalState {
+            round_map: maplit::hashmap! {
+                cert.clone() => RoundState {
+                    remote_agent_list: vec![],
+                    common_arc_set: Arc::new(DhtArcSet::Full),
+                    num_sent_op_blooms: 1,
+                    received_all_incoming_op_blooms: true,
+                    has_pending_historical_op_data: false,
+                    last_touch: Instant::now(),
+                    round_timeout: std::time::Duration::MAX,
+                    bloom_batch_cursor: None,
+                    ops_batch_queue: OpsBatchQueue::new(),
+                    region_set_sent: None,
+                }
+            }
+            .into(),
+            ..Default::default()
+        },
+        vec![],
+    )
+    .await;
+
+    let status = bob.status().unwrap();
+    assert_eq!(status.module, GossipModuleType::ShardedHistorical);
+    assert_eq!(status.current_rounds.len(), 1);
+    assert_eq!(status.current_rounds[0].peer, cert.as_str());
+    assert_eq!(status.current_rounds[0].op_blooms_sent, 1);
+    assert_eq!(status.completed_rounds, 0);
+
+    // - Bob receives the last two ops of the round.
+    let incoming = ShardedGossipWire::MissingOps(MissingOps {
+        ops: vec![KitsuneOpData::new(vec![1]), KitsuneOpData::new(vec![2])],
+        finished: MissingOpsStatus::AllComplete as u8,
+    });
+    bob.process_incoming(cert.clone(), incoming).await.unwrap();
+
+    let status = bob.status().unwrap();
+    assert!(status.current_rounds.is_empty());
+    assert_eq!(status.completed_rounds, 1);
+    assert_eq!(status.ops_received, 2);
+    assert_eq!(status.ops_sent, 0);
+}
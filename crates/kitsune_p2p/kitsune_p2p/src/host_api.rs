@@ -7,7 +7,8 @@ use kitsune_p2p_types::{
     dht_arc::DhtArcSet,
 };
 
-use crate::event::{GetAgentInfoSignedEvt, MetricRecord};
+use crate::block::{Block, BlockTarget};
+use crate::event::{GetAgentInfoSignedEvt, MetricQuery, MetricQueryAnswer, MetricRecord};
 
 /// A boxed future result with dynamic error type
 pub type KitsuneHostResult<'a, T> =
@@ -53,8 +54,28 @@ pub trait KitsuneHost: 'static + Send + Sync {
         records: Vec<MetricRecord>,
     ) -> KitsuneHostResult<()>;
 
+    /// Answer an aggregate query over the recorded metrics
+    fn query_metrics(
+        &self,
+        space: Arc<KitsuneSpace>,
+        query: MetricQuery,
+    ) -> KitsuneHostResult<MetricQueryAnswer>;
+
     /// Get the quantum Topology associated with this Space
     fn get_topology(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology>;
+
+    /// Get the blocks stored for this space, including expired ones.
+    fn get_blocks(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Vec<Block>>;
+
+    /// Store a block, replacing any existing block on the same target.
+    fn put_block(&self, space: Arc<KitsuneSpace>, block: Block) -> KitsuneHostResult<()>;
+
+    /// Remove the stored block on this target, if any.
+    fn delete_block(&self, space: Arc<KitsuneSpace>, target: BlockTarget) -> KitsuneHostResult<()>;
+
+    /// The space was offline and can reach the network again.
+    /// Anything the host held back while offline can be published now.
+    fn network_rejoined(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<()>;
 }
 
 /// Trait object for the host interface
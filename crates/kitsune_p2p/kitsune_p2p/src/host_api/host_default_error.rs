@@ -49,6 +49,19 @@ pub trait KitsuneHostDefaultError: KitsuneHost {
         .into()))
     }
 
+    fn query_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _query: MetricQuery,
+    ) -> KitsuneHostResult<MetricQueryAnswer> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "query_metrics",
+            Self::NAME
+        )
+        .into()))
+    }
+
     fn query_region_set(
         &self,
         _space: Arc<KitsuneSpace>,
@@ -87,6 +100,46 @@ pub trait KitsuneHostDefaultError: KitsuneHost {
         )
         .into()))
     }
+
+    fn get_blocks(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<Vec<Block>> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "get_blocks",
+            Self::NAME
+        )
+        .into()))
+    }
+
+    fn put_block(&self, _space: Arc<KitsuneSpace>, _block: Block) -> KitsuneHostResult<()> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "put_block",
+            Self::NAME
+        )
+        .into()))
+    }
+
+    fn delete_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _target: BlockTarget,
+    ) -> KitsuneHostResult<()> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "delete_block",
+            Self::NAME
+        )
+        .into()))
+    }
+
+    fn network_rejoined(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<()> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "network_rejoined",
+            Self::NAME
+        )
+        .into()))
+    }
 }
 
 impl<T: KitsuneHostDefaultError> KitsuneHost for T {
@@ -113,6 +166,14 @@ impl<T: KitsuneHostDefaultError> KitsuneHost for T {
         KitsuneHostDefaultError::record_metrics(self, space, records)
     }
 
+    fn query_metrics(
+        &self,
+        space: Arc<KitsuneSpace>,
+        query: MetricQuery,
+    ) -> KitsuneHostResult<MetricQueryAnswer> {
+        KitsuneHostDefaultError::query_metrics(self, space, query)
+    }
+
     fn query_size_limited_regions(
         &self,
         space: Arc<KitsuneSpace>,
@@ -133,4 +194,20 @@ impl<T: KitsuneHostDefaultError> KitsuneHost for T {
     fn get_topology(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology> {
         KitsuneHostDefaultError::get_topology(self, space)
     }
+
+    fn get_blocks(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Vec<Block>> {
+        KitsuneHostDefaultError::get_blocks(self, space)
+    }
+
+    fn put_block(&self, space: Arc<KitsuneSpace>, block: Block) -> KitsuneHostResult<()> {
+        KitsuneHostDefaultError::put_block(self, space, block)
+    }
+
+    fn delete_block(&self, space: Arc<KitsuneSpace>, target: BlockTarget) -> KitsuneHostResult<()> {
+        KitsuneHostDefaultError::delete_block(self, space, target)
+    }
+
+    fn network_rejoined(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<()> {
+        KitsuneHostDefaultError::network_rejoined(self, space)
+    }
 }
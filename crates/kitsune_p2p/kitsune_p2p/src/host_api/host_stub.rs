@@ -1,10 +1,37 @@
-use crate::KitsuneHostDefaultError;
+use crate::block::{Block, BlockTarget};
+use crate::{KitsuneHostDefaultError, KitsuneHostResult};
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+use kitsune_p2p_types::box_fut;
+use std::sync::Arc;
 
 /// Dummy host impl for plumbing
 pub struct HostStub;
 
 impl KitsuneHostDefaultError for HostStub {
     const NAME: &'static str = "HostStub";
+
+    // Blocks are held in memory by kitsune, so there is
+    // no need to store them to be able to test blocking.
+
+    fn get_blocks(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<Vec<Block>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn put_block(&self, _space: Arc<KitsuneSpace>, _block: Block) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn delete_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _target: BlockTarget,
+    ) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn network_rejoined(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
 }
 
 impl HostStub {
@@ -56,6 +56,8 @@ pub use spawn::*;
 mod host_api;
 pub use host_api::*;
 
+pub mod wire_capture;
+
 #[allow(missing_docs)]
 #[cfg(any(test, feature = "test_utils"))]
 pub mod test_util;
@@ -1,5 +1,6 @@
 //! metrics tracked by kitsune_p2p spaces
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -10,6 +11,9 @@ use crate::types::event::*;
 use crate::types::*;
 use kitsune_p2p_timestamp::Timestamp;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
+use kitsune_p2p_types::dht_arc::DhtArcRange;
+use kitsune_p2p_types::dht_arc::DhtArcSet;
+use kitsune_p2p_types::dht_arc::DhtLocation;
 
 use num_traits::*;
 
@@ -112,6 +116,11 @@ struct NodeInfo {
     /// Running average for latency microseconds for any direct
     /// request/response calls to remote agent.
     latency_micros: RunAvg,
+    /// Running average of the fraction of gossip rounds
+    /// with this node that ended in an error.
+    error_rate: RunAvg,
+    /// The last time we heard from this node.
+    last_seen: Option<Timestamp>,
     /// Times we recorded errors for this node.
     errors: VecDeque<Instant>,
     /// Times we recorded initiates to this node.
@@ -124,6 +133,16 @@ struct NodeInfo {
     current_round: bool,
 }
 
+#[derive(Debug, Clone)]
+/// A remote agent which holds the ops in part of our storage arc.
+struct Holder {
+    /// The part of the agent's storage arc that it has finished
+    /// a historical gossip round with us over.
+    synced: DhtArcSet,
+    /// The agent stops counting as a holder once its agent info expires.
+    expires_at_ms: u64,
+}
+
 #[derive(Debug, Default)]
 /// Metrics tracking for remote nodes to help
 /// choose which remote node to initiate the next round with.
@@ -134,6 +153,16 @@ pub struct Metrics {
     /// Aggregate Extrapolated Dht Coverage
     agg_extrap_cov: RunAvg,
 
+    /// Remote agents known to hold the ops in part of our storage arc.
+    holders: HashMap<Arc<KitsuneAgent>, Holder>,
+
+    /// The number of agents, including us, holding the ops
+    /// at each basis in our storage arc.
+    redundancy: Vec<(DhtArcRange, u32)>,
+
+    /// The part of our storage arc that is held by fewer agents than the target.
+    under_replicated: Option<DhtArcSet>,
+
     // Number of times we need to force initiate
     // the next round.
     force_initiates: u8,
@@ -197,13 +226,37 @@ impl Metrics {
                 data: serde_json::json!(*node.reachability_quotient),
             });
 
-            out.push(MetricRecord {
-                kind: MetricRecordKind::LatencyMicros,
-                agent: Some(agent.clone()),
-                recorded_at_utc: now,
-                expires_at_utc: expires_at,
-                data: serde_json::json!(*node.latency_micros),
-            });
+            // An empty average would read as a perfect score,
+            // so only record the averages we have samples for.
+            if node.latency_micros.1 > 0 {
+                out.push(MetricRecord {
+                    kind: MetricRecordKind::LatencyMicros,
+                    agent: Some(agent.clone()),
+                    recorded_at_utc: now,
+                    expires_at_utc: expires_at,
+                    data: serde_json::json!(*node.latency_micros),
+                });
+            }
+
+            if node.error_rate.1 > 0 {
+                out.push(MetricRecord {
+                    kind: MetricRecordKind::ErrorRate,
+                    agent: Some(agent.clone()),
+                    recorded_at_utc: now,
+                    expires_at_utc: expires_at,
+                    data: serde_json::json!(*node.error_rate),
+                });
+            }
+
+            if let Some(last_seen) = node.last_seen {
+                out.push(MetricRecord {
+                    kind: MetricRecordKind::LastSeen,
+                    agent: Some(agent.clone()),
+                    recorded_at_utc: now,
+                    expires_at_utc: expires_at,
+                    data: serde_json::json!(last_seen.as_micros()),
+                });
+            }
         }
 
         out.push(MetricRecord {
@@ -234,9 +287,116 @@ impl Metrics {
             .collect::<serde_json::map::Map<String, serde_json::Value>>()
             .into();
 
+        let redundancy: Vec<_> = self
+            .redundancy
+            .iter()
+            .map(|(interval, holders)| {
+                serde_json::json!({
+                    "interval": interval,
+                    "holders": holders,
+                })
+            })
+            .collect();
+
         serde_json::json!({
             "aggExtrapCov": *self.agg_extrap_cov,
             "agents": agents,
+            "redundancy": redundancy,
+        })
+    }
+
+    /// Record that a historical gossip round with the remote agents finished,
+    /// so they now hold the ops in the common arc set
+    /// that fall within their storage arcs.
+    pub fn record_synced(&mut self, remote_agent_list: &[AgentInfoSigned], common: &DhtArcSet) {
+        for info in remote_agent_list {
+            let storage = DhtArcSet::from_interval(info.storage_arc.inner());
+            let holder = self.holders.entry(info.agent.clone()).or_insert(Holder {
+                synced: DhtArcSet::new_empty(),
+                expires_at_ms: 0,
+            });
+            // Drop anything the agent stopped holding if its arc shrank.
+            holder.synced = holder.synced.union(common).intersection(&storage);
+            holder.expires_at_ms = info.expires_at_ms;
+        }
+    }
+
+    /// Count how many agents hold the ops at each basis in our storage arc,
+    /// from the historical gossip rounds that finished.
+    ///
+    /// If the lowest count is below the `target` redundancy and has dropped
+    /// since it was last counted, the next few gossip rounds are forced and
+    /// will prefer peers that don't hold the under-replicated ops yet,
+    /// so those ops get pushed to them.
+    /// Returns true if gossip was forced.
+    pub fn record_redundancy(&mut self, arc_set: &DhtArcSet, target: f64) -> bool {
+        let now_ms = std::time::UNIX_EPOCH
+            .elapsed()
+            .expect("Your system clock is set before UNIX epoch")
+            .as_millis() as u64;
+        self.holders.retain(|_, h| h.expires_at_ms > now_ms);
+
+        let last = self.min_redundancy();
+        let parts = count_holders(arc_set, self.holders.values().map(|h| &h.synced));
+        self.redundancy = parts
+            .iter()
+            .map(|&((start, end), holders)| {
+                // We hold our own arc too.
+                (DhtArcRange::from_bounds(start, end), holders + 1)
+            })
+            .collect();
+        let under_replicated: DhtArcSet = parts
+            .into_iter()
+            .filter(|(_, holders)| ((holders + 1) as f64) < target)
+            .map(|(bounds, _)| bounds)
+            .collect::<Vec<_>>()
+            .into();
+        self.under_replicated = if under_replicated.is_empty() {
+            None
+        } else {
+            Some(under_replicated)
+        };
+
+        let force = match (self.min_redundancy(), last) {
+            (Some(current), Some(last)) => (current as f64) < target && current < last,
+            (Some(current), None) => (current as f64) < target,
+            (None, _) => false,
+        };
+        if force {
+            self.record_force_initiate();
+        }
+        force
+    }
+
+    /// The lowest number of agents holding the ops at any basis in our storage arc.
+    pub fn min_redundancy(&self) -> Option<u32> {
+        self.redundancy.iter().map(|(_, holders)| *holders).min()
+    }
+
+    /// The number of agents holding the ops at this basis,
+    /// if it is in our storage arc.
+    pub fn basis_redundancy(&self, basis: DhtLocation) -> Option<u32> {
+        self.redundancy
+            .iter()
+            .find(|(interval, _)| interval.contains(basis))
+            .map(|(_, holders)| *holders)
+    }
+
+    /// Would gossiping with these agents push under-replicated ops
+    /// to an agent that doesn't hold them yet?
+    pub fn adds_redundancy(&self, remote_agent_list: &[AgentInfoSigned]) -> bool {
+        let under_replicated = match &self.under_replicated {
+            Some(under_replicated) => under_replicated,
+            None => return false,
+        };
+        remote_agent_list.iter().any(|info| {
+            let wanted =
+                DhtArcSet::from_interval(info.storage_arc.inner()).intersection(under_replicated);
+            let held = self
+                .holders
+                .get(&info.agent)
+                .map(|h| h.synced.intersection(&wanted));
+            !wanted.is_empty() && held.as_ref() != Some(&wanted)
         })
     }
 
@@ -286,6 +446,7 @@ impl Metrics {
                 .entry(agent_info.into().agent().clone())
                 .or_default();
             info.latency_micros.push(micros);
+            info.last_seen = Some(Timestamp::now());
         }
     }
 
@@ -317,6 +478,7 @@ impl Metrics {
                 .entry(agent_info.into().agent().clone())
                 .or_default();
             record_instant(&mut info.remote_rounds);
+            info.last_seen = Some(Timestamp::now());
             info.current_round = true;
         }
     }
@@ -335,6 +497,8 @@ impl Metrics {
                 .entry(agent_info.into().agent().clone())
                 .or_default();
             info.reachability_quotient.push(100);
+            info.error_rate.push(0);
+            info.last_seen = Some(Timestamp::now());
             record_instant(&mut info.complete_rounds);
             info.current_round = false;
             if info.is_initiate_round() {
@@ -359,6 +523,7 @@ impl Metrics {
                 .entry(agent_info.into().agent().clone())
                 .or_default();
             info.reachability_quotient.push_n(1, 5);
+            info.error_rate.push(1);
             record_instant(&mut info.errors);
             info.current_round = false;
         }
@@ -625,6 +790,52 @@ impl MetricsSync {
     }
 }
 
+/// The non-wrapping bounds of the intervals in an arc set.
+fn bounds(arc_set: &DhtArcSet) -> Vec<(u32, u32)> {
+    arc_set
+        .intervals()
+        .into_iter()
+        .flat_map(|interval| match interval {
+            DhtArcRange::Empty => vec![],
+            DhtArcRange::Full => vec![(u32::MIN, u32::MAX)],
+            DhtArcRange::Bounded(start, end) if start <= end => {
+                vec![(start.as_u32(), end.as_u32())]
+            }
+            DhtArcRange::Bounded(start, end) => {
+                vec![(u32::MIN, end.as_u32()), (start.as_u32(), u32::MAX)]
+            }
+        })
+        .collect()
+}
+
+/// Split an arc set into parts that are each covered by the
+/// same number of the holders' arc sets, with that number.
+fn count_holders<'a>(
+    arc_set: &DhtArcSet,
+    holders: impl Iterator<Item = &'a DhtArcSet>,
+) -> Vec<((u32, u32), u32)> {
+    // How the number of holders changes at each location.
+    let mut changes: BTreeMap<u64, i64> = BTreeMap::new();
+    for (start, end) in holders.flat_map(bounds) {
+        *changes.entry(start as u64).or_default() += 1;
+        *changes.entry(end as u64 + 1).or_default() -= 1;
+    }
+    let mut parts = Vec::new();
+    for (start, end) in bounds(arc_set) {
+        let mut count: i64 = changes.range(..=start as u64).map(|(_, c)| c).sum();
+        let mut part_start = start;
+        for (&loc, &change) in changes.range(start as u64 + 1..=end as u64) {
+            if change != 0 {
+                parts.push(((part_start, (loc - 1) as u32), count as u32));
+                part_start = loc as u32;
+                count += change;
+            }
+        }
+        parts.push(((part_start, end), count as u32));
+    }
+    parts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -658,4 +869,84 @@ mod tests {
         a5.push_n(1, 255);
         assert_eq!(1.0, *a5);
     }
+
+    fn agent_info(agent: u8, expires_at_ms: u64) -> AgentInfoSigned {
+        futures::executor::block_on(AgentInfoSigned::sign(
+            Arc::new(KitsuneSpace(vec![0; 36])),
+            Arc::new(KitsuneAgent(vec![agent; 36])),
+            u32::MAX,
+            Vec::new(),
+            0,
+            expires_at_ms,
+            |_| async move { Ok(Arc::new(vec![0; 64].into())) },
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_count_holders() {
+        let arc_set = DhtArcSet::from(vec![(0, 99)]);
+        let holders = [
+            DhtArcSet::from(vec![(0, 49)]),
+            DhtArcSet::from(vec![(20, 200)]),
+            DhtArcSet::from(vec![(300, 400)]),
+        ];
+        assert_eq!(
+            count_holders(&arc_set, holders.iter()),
+            vec![((0, 19), 1), ((20, 49), 2), ((50, 99), 1)]
+        );
+        assert_eq!(
+            count_holders(&DhtArcSet::Full, std::iter::empty()),
+            vec![((u32::MIN, u32::MAX), 0)]
+        );
+    }
+
+    #[test]
+    fn test_record_redundancy() {
+        let target = 3.0;
+        let arc_set = DhtArcSet::from(vec![(0, 99)]);
+        let expires_at_ms = u64::MAX;
+        let a = agent_info(1, expires_at_ms);
+        let b = agent_info(2, expires_at_ms);
+        let c = agent_info(3, expires_at_ms);
+        let mut metrics = Metrics::default();
+        assert_eq!(metrics.min_redundancy(), None);
+        assert!(!metrics.adds_redundancy(std::slice::from_ref(&c)));
+
+        // - Bob holds all of our arc but alice only holds the first half.
+        metrics.record_synced(std::slice::from_ref(&a), &DhtArcSet::from(vec![(0, 49)]));
+        metrics.record_synced(std::slice::from_ref(&b), &arc_set);
+
+        // - The second half is below target so gossip is forced.
+        assert!(metrics.record_redundancy(&arc_set, target));
+        assert!(metrics.forced_initiate());
+        assert_eq!(metrics.basis_redundancy(10.into()), Some(3));
+        assert_eq!(metrics.basis_redundancy(60.into()), Some(2));
+        assert_eq!(metrics.basis_redundancy(200.into()), None);
+        assert_eq!(metrics.min_redundancy(), Some(2));
+
+        // - Gossip prefers peers that don't hold the second half yet.
+        assert!(metrics.adds_redundancy(std::slice::from_ref(&a)));
+        assert!(!metrics.adds_redundancy(std::slice::from_ref(&b)));
+        assert!(metrics.adds_redundancy(std::slice::from_ref(&c)));
+        metrics.force_initiates = 0;
+
+        // - Staying at the same level doesn't force gossip again.
+        assert!(!metrics.record_redundancy(&arc_set, target));
+        assert!(!metrics.forced_initiate());
+
+        // - Once alice holds the second half the whole arc is at target.
+        metrics.record_synced(&[a], &DhtArcSet::from(vec![(50, 99)]));
+        assert!(!metrics.record_redundancy(&arc_set, target));
+        assert_eq!(metrics.min_redundancy(), Some(3));
+        assert!(!metrics.adds_redundancy(&[c]));
+
+        let dump = metrics.dump();
+        assert_eq!(dump["redundancy"][0]["holders"], 3);
+
+        // - Holders whose agent info expired no longer count.
+        metrics.record_synced(&[agent_info(2, 1)], &arc_set);
+        assert!(metrics.record_redundancy(&arc_set, target));
+        assert_eq!(metrics.min_redundancy(), Some(2));
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::actor;
 use crate::actor::*;
+use crate::block::*;
 use crate::event::*;
 use crate::gossip::sharded_gossip::BandwidthThrottles;
 use crate::types::gossip::GossipModuleType;
@@ -12,9 +13,11 @@ use futures::future::FutureExt;
 use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_transport_ws::tx2::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
 use kitsune_p2p_types::tx2::tx2_api::*;
+use kitsune_p2p_types::tx2::tx2_pool::Tx2Lane;
 use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 use kitsune_p2p_types::tx2::tx2_restart_adapter::*;
 use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
@@ -92,6 +95,7 @@ pub(crate) struct KitsuneP2pActor {
     config: Arc<KitsuneP2pConfig>,
     bandwidth_throttles: BandwidthThrottles,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
+    block_list: BlockList,
 }
 
 impl KitsuneP2pActor {
@@ -133,6 +137,15 @@ impl KitsuneP2pActor {
                     bind_to,
                 )
             }
+            KitsuneP2pTx2Backend::WebSocket { bind_to } => {
+                let mut conf = WsConfig::default();
+                conf.tls = Some(tls_config.clone());
+                conf.tuning_params = Some(config.tuning_params.clone());
+                (
+                    tx2_ws_adapter(conf).await.map_err(KitsuneP2pError::other)?,
+                    bind_to,
+                )
+            }
             KitsuneP2pTx2Backend::Mock { mock_network } => {
                 is_mock = true;
                 (mock_network, "none:".into())
@@ -207,6 +220,30 @@ impl KitsuneP2pActor {
             KitsuneMetrics::count(t, l);
         });
 
+        // keep small control messages from queueing behind gossip
+        let metrics = metrics.set_write_lane(|d| match d {
+            "Wire::Failure"
+            | "Wire::PeerGet"
+            | "Wire::PeerGetResp"
+            | "Wire::PeerQuery"
+            | "Wire::PeerQueryResp"
+            | "Wire::MetricExchange" => Tx2Lane::Control,
+            "Wire::Gossip" | "Wire::DelegateBroadcast" | "Wire::Broadcast" => Tx2Lane::Gossip,
+            _ => Tx2Lane::UserData,
+        });
+
+        // opt-in capture of every wire message for bug reports
+        let metrics = match config.tuning_params.wire_capture_path() {
+            Some(path) => {
+                let capture = crate::wire_capture::WireCapture::create(&path)
+                    .map_err(KitsuneP2pError::other)?;
+                metrics.set_capture(move |dir, peer, kind, data| {
+                    capture.capture(dir, peer, kind, data)
+                })
+            }
+            None => metrics,
+        };
+
         // wrap in api
         let f = tx2_api(f, metrics);
 
@@ -219,17 +256,21 @@ impl KitsuneP2pActor {
         // capture endpoint handle
         let ep_hnd = ep.handle().clone();
 
+        let block_list = BlockList::default();
+
         let i_s = internal_sender.clone();
         tokio::task::spawn({
             let evt_sender = evt_sender.clone();
             let host = host.clone();
             let tuning_params = config.tuning_params.clone();
+            let block_list = block_list.clone();
             async move {
                 ep.for_each_concurrent(tuning_params.concurrent_limit_per_thread, move |event| {
                     let evt_sender = evt_sender.clone();
                     let host = host.clone();
                     let tuning_params = tuning_params.clone();
                     let i_s = i_s.clone();
+                    let block_list = block_list.clone();
                     async move {
                         macro_rules! resp {
                             ($r:expr, $e:expr) => {
@@ -246,13 +287,16 @@ impl KitsuneP2pActor {
                             OutgoingConnection(Tx2EpConnection {
                                 con,
                                 url,
-                            }) => {
-                                let _ = i_s.new_con(url, con).await;
-                            }
-                            IncomingConnection(Tx2EpConnection {
+                            })
+                            | IncomingConnection(Tx2EpConnection {
                                 con,
                                 url,
                             }) => {
+                                if block_list.is_cert_blocked(&con.peer_cert()) {
+                                    tracing::debug!(%url, "closing connection to blocked node");
+                                    con.close(0, "blocked").await;
+                                    return;
+                                }
                                 let _ = i_s.new_con(url, con).await;
                             }
                             ConnectionClosed(Tx2EpConnectionClosed {
@@ -261,8 +305,23 @@ impl KitsuneP2pActor {
                             }) => {
                                 let _ = i_s.del_con(url).await;
                             }
-                            IncomingRequest(Tx2EpIncomingRequest { data, respond, .. }) => {
+                            IncomingRequest(Tx2EpIncomingRequest {
+                                con,
+                                data,
+                                respond,
+                                ..
+                            }) => {
+                                if block_list.is_cert_blocked(&con.peer_cert()) {
+                                    resp!(respond, wire::Wire::failure("blocked".into()));
+                                    return;
+                                }
                                 match data {
+                                    wire::Wire::Call(wire::Call { space, .. })
+                                        if block_list
+                                            .is_agent_node_blocked(&space, &con.peer_cert()) =>
+                                    {
+                                        resp!(respond, wire::Wire::failure("blocked".into()));
+                                    }
                                     wire::Wire::Call(wire::Call {
                                         space,
                                         to_agent,
@@ -324,6 +383,30 @@ impl KitsuneP2pActor {
                                 }
                             }
                             IncomingNotify(Tx2EpIncomingNotify { con, data, url, .. }) => {
+                                if block_list.is_cert_blocked(&con.peer_cert()) {
+                                    return;
+                                }
+                                // drop publishes and gossip from the nodes
+                                // of agents blocked in the space
+                                let space = match &data {
+                                    wire::Wire::DelegateBroadcast(wire::DelegateBroadcast {
+                                        space,
+                                        ..
+                                    })
+                                    | wire::Wire::Broadcast(wire::Broadcast { space, .. })
+                                    | wire::Wire::Gossip(wire::Gossip { space, .. })
+                                    | wire::Wire::MetricExchange(wire::MetricExchange {
+                                        space,
+                                        ..
+                                    }) => Some(space),
+                                    _ => None,
+                                };
+                                if let Some(space) = space {
+                                    if block_list.is_agent_node_blocked(space, &con.peer_cert()) {
+                                        tracing::debug!(%url, "dropping notify from blocked agent's node");
+                                        return;
+                                    }
+                                }
                                 match data {
                                     wire::Wire::DelegateBroadcast(wire::DelegateBroadcast {
                                         space,
@@ -385,6 +468,12 @@ impl KitsuneP2pActor {
                                             // TODO: Should we check if the basis is
                                             // held before calling put_agent_info_signed?
                                             match AgentInfoSigned::decode(&data[..]) {
+                                                Ok(info)
+                                                    if block_list
+                                                        .is_agent_blocked(&space, &info.agent) =>
+                                                {
+                                                    block_list.note_agent_info(&space, &info);
+                                                }
                                                 Ok(info) => {
                                                     if let Err(err) = evt_sender
                                                         .put_agent_info_signed(
@@ -459,11 +548,31 @@ impl KitsuneP2pActor {
             config: Arc::new(config),
             bandwidth_throttles,
             parallel_notify_permit,
+            block_list,
         })
     }
 }
 
 use ghost_actor::dependencies::must_future::MustBoxFuture;
+/// Remember the nodes a newly blocked agent can be reached at,
+/// so we can drop what they send us for the space.
+async fn note_blocked_agent(
+    host: &HostApi,
+    block_list: &BlockList,
+    space: &Arc<KitsuneSpace>,
+    agent: Arc<KitsuneAgent>,
+) {
+    let info = host
+        .get_agent_info_signed(GetAgentInfoSignedEvt {
+            space: space.clone(),
+            agent,
+        })
+        .await;
+    if let Ok(Some(info)) = info {
+        block_list.note_agent_info(space, &info);
+    }
+}
+
 impl ghost_actor::GhostControlHandler for KitsuneP2pActor {
     fn handle_ghost_actor_shutdown(mut self) -> MustBoxFuture<'static, ()> {
         use futures::sink::SinkExt;
@@ -621,8 +730,17 @@ impl ghost_actor::GhostHandler<KitsuneP2pEvent> for KitsuneP2pActor {}
 impl KitsuneP2pEventHandler for KitsuneP2pActor {
     fn handle_put_agent_info_signed(
         &mut self,
-        input: crate::event::PutAgentInfoSignedEvt,
+        mut input: crate::event::PutAgentInfoSignedEvt,
     ) -> KitsuneP2pEventHandlerResult<()> {
+        // Whether from gossip, bootstrap or mdns,
+        // we don't store the agent info of blocked agents,
+        // but we do remember which nodes they're on.
+        for info in input.peer_data.iter() {
+            self.block_list.note_agent_info(&input.space, info);
+        }
+        input
+            .peer_data
+            .retain(|info| !self.block_list.is_agent_blocked(&input.space, &info.agent));
         Ok(self.evt_sender.put_agent_info_signed(input))
     }
 
@@ -630,7 +748,22 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
         &mut self,
         input: crate::event::QueryAgentsEvt,
     ) -> KitsuneP2pEventHandlerResult<Vec<crate::types::agent_store::AgentInfoSigned>> {
-        Ok(self.evt_sender.query_agents(input))
+        // Agent info stored before a block was added is still in the
+        // host's store, so leave blocked agents out of the results to
+        // keep them from being called, published to or gossiped with.
+        let space = input.space.clone();
+        let block_list = self.block_list.clone();
+        let fut = self.evt_sender.query_agents(input);
+        Ok(async move {
+            let mut list = fut.await?;
+            for info in list.iter() {
+                block_list.note_agent_info(&space, info);
+            }
+            list.retain(|info| !block_list.is_agent_blocked(&space, &info.agent));
+            Ok(list)
+        }
+        .boxed()
+        .into())
     }
 
     fn handle_query_peer_density(
@@ -710,9 +843,23 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         let config = Arc::clone(&self.config);
         let bandwidth_throttles = self.bandwidth_throttles.clone();
         let parallel_notify_permit = self.parallel_notify_permit.clone();
+        let block_list = self.block_list.clone();
         let space_sender = match self.spaces.entry(space.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => entry.insert(AsyncLazy::new(async move {
+                match host.get_blocks(space2.clone()).await {
+                    Ok(blocks) => {
+                        let now = kitsune_p2p_timestamp::Timestamp::now();
+                        for block in blocks.into_iter().filter(|b| b.is_active(now)) {
+                            let target = block.target.clone();
+                            block_list.block(space2.clone(), block);
+                            if let BlockTarget::Agent(agent) = target {
+                                note_blocked_agent(&host, &block_list, &space2, agent).await;
+                            }
+                        }
+                    }
+                    Err(err) => tracing::error!(?err, "failed to load blocks for space"),
+                }
                 let (send, send_inner, evt_recv) = spawn_space(
                     space2,
                     ep_hnd,
@@ -764,6 +911,9 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         payload: Vec<u8>,
         timeout_ms: Option<u64>,
     ) -> KitsuneP2pHandlerResult<Vec<u8>> {
+        if self.block_list.is_agent_blocked(&space, &to_agent) {
+            return Err(KitsuneP2pError::Blocked(BlockTarget::Agent(to_agent)));
+        }
         let space_sender = match self.spaces.get_mut(&space) {
             None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
             Some(space) => space.get(),
@@ -820,11 +970,12 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
     fn handle_targeted_broadcast(
         &mut self,
         space: Arc<KitsuneSpace>,
-        agents: Vec<Arc<KitsuneAgent>>,
+        mut agents: Vec<Arc<KitsuneAgent>>,
         timeout: KitsuneTimeout,
         payload: Vec<u8>,
         drop_at_limit: bool,
     ) -> KitsuneP2pHandlerResult<()> {
+        agents.retain(|agent| !self.block_list.is_agent_blocked(&space, agent));
         let space_sender = match self.spaces.get_mut(&space) {
             None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
             Some(space) => space.get(),
@@ -873,6 +1024,78 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .into())
     }
 
+    fn handle_gossip_status(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<SpaceGossipStatus> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.gossip_status(space).await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_network_status(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<NetworkStatus> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.network_status(space).await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_block(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        block: Block,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let host = self.host.clone();
+        let block_list = self.block_list.clone();
+        Ok(async move {
+            host.put_block(space.clone(), block.clone())
+                .await
+                .map_err(KitsuneP2pError::other)?;
+            let target = block.target.clone();
+            block_list.block(space.clone(), block);
+            if let BlockTarget::Agent(agent) = target {
+                note_blocked_agent(&host, &block_list, &space, agent).await;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_unblock(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        target: BlockTarget,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let host = self.host.clone();
+        let block_list = self.block_list.clone();
+        Ok(async move {
+            host.delete_block(space.clone(), target.clone())
+                .await
+                .map_err(KitsuneP2pError::other)?;
+            block_list.unblock(&space, &target);
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_dump_network_metrics(
         &mut self,
         space: Option<Arc<KitsuneSpace>>,
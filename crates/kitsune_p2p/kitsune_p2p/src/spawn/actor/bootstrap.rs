@@ -3,8 +3,10 @@ use crate::types::agent_store::AgentInfoSigned;
 use kitsune_p2p_types::bootstrap::RandomQuery;
 use once_cell::sync::Lazy;
 use once_cell::sync::OnceCell;
+use rand::Rng;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::time::Duration;
 use url2::Url2;
 
 /// Reuse a single reqwest Client for efficiency as we likely need several connections.
@@ -177,6 +179,17 @@ pub async fn proxy_list(url: Url2) -> KitsuneP2pResult<Vec<Url2>> {
         .collect())
 }
 
+/// Randomly vary a delay by up to `jitter_percent` percent either way.
+pub fn jitter(delay: Duration, jitter_percent: u32) -> Duration {
+    let delay_ms = delay.as_millis() as u64;
+    let spread = delay_ms * jitter_percent.min(100) as u64 / 100;
+    if spread == 0 {
+        return delay;
+    }
+    let offset = rand::thread_rng().gen_range(0..=spread * 2);
+    Duration::from_millis(delay_ms - spread + offset)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +203,18 @@ mod tests {
     use std::convert::TryInto;
     use std::sync::Arc;
 
+    #[test]
+    fn test_jitter() {
+        let delay = Duration::from_secs(100);
+        for _ in 0..100 {
+            let jittered = jitter(delay, 10);
+            assert!(jittered >= Duration::from_secs(90));
+            assert!(jittered <= Duration::from_secs(110));
+        }
+        assert_eq!(jitter(delay, 0), delay);
+        assert!(jitter(delay, 1000) <= Duration::from_secs(200));
+    }
+
     // TODO - FIXME - davidb
     // I'm disabling all these tests that depend on outside systems
     // we need local testing to prove these out in a ci environment.
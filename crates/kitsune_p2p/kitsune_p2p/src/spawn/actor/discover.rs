@@ -119,7 +119,9 @@ pub(crate) fn peer_connect(
         }
 
         // attempt an outgoing connection
-        let con_hnd = inner.ep_hnd.get_connection(url.clone(), timeout).await?;
+        let con_hnd = inner.ep_hnd.get_connection(url.clone(), timeout).await;
+        inner.connectivity.record_peer_connect(con_hnd.is_ok());
+        let con_hnd = con_hnd?;
 
         // return the result
         Ok(PeerDiscoverResult::OkRemote {
@@ -2,9 +2,9 @@ use super::*;
 use crate::metrics::*;
 use crate::types::gossip::GossipModule;
 use ghost_actor::dependencies::tracing;
-use kitsune_p2p_mdns::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
 use kitsune_p2p_types::codec::{rmp_decode, rmp_encode};
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::dht_arc::{DhtArc, DhtArcRange, DhtArcSet};
 use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
 use std::collections::{HashMap, HashSet};
@@ -15,6 +15,12 @@ use url2::Url2;
 /// (currently once per hour)
 const HISTORICAL_METRIC_RECORD_FREQ_MS: u64 = 1000 * 60 * 60;
 
+mod connectivity;
+use connectivity::*;
+
+mod mdns;
+use mdns::*;
+
 mod metric_exchange;
 use metric_exchange::*;
 
@@ -145,7 +151,8 @@ impl SpaceInternalHandler for Space {
 
     fn handle_update_agent_info(&mut self) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
+        let mdns = self.mdns.clone();
+        let connectivity = self.ro_inner.connectivity.clone();
         let network_type = self.config.network_type.clone();
         let mut agent_list = Vec::with_capacity(self.local_joined_agents.len());
         for agent in self.local_joined_agents.iter().cloned() {
@@ -156,11 +163,8 @@ impl SpaceInternalHandler for Space {
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
-        let dynamic_arcs = self.config.tuning_params.gossip_dynamic_arcs;
-        let single_storage_arc_per_space = self
-            .config
-            .tuning_params
-            .gossip_single_storage_arc_per_space;
+        let tuning_params = self.config.tuning_params.clone();
+        let host = self.ro_inner.host_api.clone();
         let internal_sender = self.i_s.clone();
         Ok(async move {
             let urls = vec![ep_hnd.local_addr()?];
@@ -175,10 +179,11 @@ impl SpaceInternalHandler for Space {
                     evt_sender: &evt_sender,
                     internal_sender: &internal_sender,
                     network_type: network_type.clone(),
-                    mdns_handles: &mut mdns_handles,
+                    mdns: &mdns,
+                    connectivity: &connectivity,
                     bootstrap_service: &bootstrap_service,
-                    dynamic_arcs,
-                    single_storage_arc_per_space,
+                    host: &host,
+                    tuning_params: &tuning_params,
                 };
                 peer_data.push(update_single_agent_info(input).await?);
             }
@@ -199,18 +204,16 @@ impl SpaceInternalHandler for Space {
         agent: Arc<KitsuneAgent>,
     ) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
+        let mdns = self.mdns.clone();
+        let connectivity = self.ro_inner.connectivity.clone();
         let network_type = self.config.network_type.clone();
         let ep_hnd = self.ro_inner.ep_hnd.clone();
         let evt_sender = self.evt_sender.clone();
         let internal_sender = self.i_s.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
-        let dynamic_arcs = self.config.tuning_params.gossip_dynamic_arcs;
-        let single_storage_arc_per_space = self
-            .config
-            .tuning_params
-            .gossip_single_storage_arc_per_space;
+        let tuning_params = self.config.tuning_params.clone();
+        let host = self.ro_inner.host_api.clone();
         let arc = self.get_agent_arc(&agent);
 
         Ok(async move {
@@ -224,10 +227,11 @@ impl SpaceInternalHandler for Space {
                 evt_sender: &evt_sender,
                 internal_sender: &internal_sender,
                 network_type: network_type.clone(),
-                mdns_handles: &mut mdns_handles,
+                mdns: &mdns,
+                connectivity: &connectivity,
                 bootstrap_service: &bootstrap_service,
-                dynamic_arcs,
-                single_storage_arc_per_space,
+                host: &host,
+                tuning_params: &tuning_params,
             };
             let peer_data = vec![update_single_agent_info(input).await?];
             internal_sender
@@ -432,6 +436,7 @@ impl SpaceInternalHandler for Space {
         url: TxUrl,
         con: Tx2ConHnd<wire::Wire>,
     ) -> InternalHandlerResult<()> {
+        self.ro_inner.connectivity.record_peer_connect(true);
         self.ro_inner.metric_exchange.write().new_con(url, con);
         unit_ok_fut()
     }
@@ -451,25 +456,51 @@ struct UpdateAgentInfoInput<'borrow> {
     evt_sender: &'borrow futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     internal_sender: &'borrow ghost_actor::GhostSender<SpaceInternal>,
     network_type: NetworkType,
-    mdns_handles: &'borrow mut HashMap<Vec<u8>, Arc<AtomicBool>>,
+    mdns: &'borrow MdnsDiscovery,
+    connectivity: &'borrow Connectivity,
     bootstrap_service: &'borrow Option<Url2>,
-    dynamic_arcs: bool,
-    single_storage_arc_per_space: bool,
+    host: &'borrow HostApi,
+    tuning_params: &'borrow KitsuneP2pTuningParams,
 }
 
 async fn update_arc_length(
     evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    host: &HostApi,
+    tuning_params: &KitsuneP2pTuningParams,
     space: Arc<KitsuneSpace>,
     arc: &mut DhtArc,
 ) -> KitsuneP2pResult<()> {
     let view = evt_sender.query_peer_density(space.clone(), *arc).await?;
 
+    let before = *arc;
     let cov_before = arc.coverage() * 100.0;
     tracing::trace!("Updating arc for space {:?}:", space);
     tracing::trace!("Before: {:2.1}% |{}|", cov_before, arc.to_ascii(64));
 
     view.update_arc(arc);
 
+    // The peer view only counts the peers we know about, so check with the
+    // host that the ops in our arc are held by enough peers before shrinking.
+    if arc.length() < before.length() {
+        let target = tuning_params.gossip_redundancy_target;
+        match host
+            .peer_extrapolated_coverage(space.clone(), DhtArcSet::from_interval(before.inner()))
+            .await
+        {
+            Ok(coverage) if coverage.iter().any(|c| *c < target) => {
+                tracing::debug!(?coverage, target, "Not shrinking an under-replicated arc");
+                *arc = before;
+            }
+            Ok(_) => (),
+            Err(e) => tracing::debug!("Failed to get extrapolated coverage: {:?}", e),
+        }
+    }
+
+    arc.clamp_coverage(
+        tuning_params.gossip_dynamic_arc_min_coverage,
+        tuning_params.gossip_dynamic_arc_max_coverage,
+    );
+
     let cov_after = arc.coverage() * 100.0;
     tracing::trace!("After:  {:2.1}% |{}|", cov_after, arc.to_ascii(64));
     tracing::trace!("Diff: {:-2.2}%", cov_after - cov_before);
@@ -489,17 +520,19 @@ async fn update_single_agent_info(
         evt_sender,
         internal_sender,
         network_type,
-        mdns_handles,
+        mdns,
+        connectivity,
         bootstrap_service,
-        dynamic_arcs,
-        single_storage_arc_per_space,
+        host,
+        tuning_params,
     } = input;
 
     // If there is only a single agent per space don't update the empty arcs.
-    let should_not_update_arc_length = single_storage_arc_per_space && arc.is_empty();
+    let should_not_update_arc_length =
+        tuning_params.gossip_single_storage_arc_per_space && arc.is_empty();
 
-    if dynamic_arcs && !should_not_update_arc_length {
-        update_arc_length(evt_sender, space.clone(), &mut arc).await?;
+    if tuning_params.gossip_dynamic_arcs && !should_not_update_arc_length {
+        update_arc_length(evt_sender, host, tuning_params, space.clone(), &mut arc).await?;
     }
 
     // Update the agents arc through the internal sender.
@@ -540,31 +573,17 @@ async fn update_single_agent_info(
         NetworkType::QuicMdns => {
             // Broadcast only valid AgentInfo
             if !urls.is_empty() {
-                // Kill previous broadcast for this space + agent
-                let key = [space.get_bytes(), agent.get_bytes()].concat();
-                if let Some(current_handle) = mdns_handles.get(&key) {
-                    mdns_kill_thread(current_handle.to_owned());
-                }
-                // Broadcast by using Space as service type and Agent as service name
-                let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
-                let agent_b64 = base64::encode_config(&agent[..], base64::URL_SAFE_NO_PAD);
-                //println!("(MDNS) - Broadcasting of Agent {:?} ({}) in space {:?} ({} ; {})",
-                // agent, agent.get_bytes().len(), space, space.get_bytes().len(), space_b64.len());
-                // Broadcast rmp encoded agent_info_signed
-                let mut buffer = Vec::new();
-                rmp_encode(&mut buffer, &agent_info_signed)?;
-                tracing::trace!(?space_b64, ?agent_b64);
-                let handle = mdns_create_broadcast_thread(space_b64, agent_b64, &buffer);
-                // store handle in self
-                mdns_handles.insert(key, handle);
+                mdns.advertise(&agent_info_signed)?;
             }
         }
         NetworkType::QuicBootstrap => {
-            crate::spawn::actor::bootstrap::put(
+            let res = crate::spawn::actor::bootstrap::put(
                 bootstrap_service.clone(),
                 agent_info_signed.clone(),
             )
-            .await?;
+            .await;
+            connectivity.record_bootstrap(res.is_ok());
+            res?;
         }
     }
     Ok(agent_info_signed)
@@ -578,6 +597,7 @@ impl ghost_actor::GhostControlHandler for Space {
             // https://doc.rust-lang.org/edition-guide/rust-2021/disjoint-capture-in-closures.html#migration
             let _ = &self;
             self.ro_inner.metric_exchange.write().shutdown();
+            self.mdns.shutdown();
 
             use futures::sink::SinkExt;
             // this is a curtesy, ok if fails
@@ -600,6 +620,26 @@ impl KitsuneP2pHandler for Space {
         )
     }
 
+    fn handle_block(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _block: crate::block::Block,
+    ) -> KitsuneP2pHandlerResult<()> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
+
+    fn handle_unblock(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _target: crate::block::BlockTarget,
+    ) -> KitsuneP2pHandlerResult<()> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
+
     fn handle_join(
         &mut self,
         space: Arc<KitsuneSpace>,
@@ -614,47 +654,10 @@ impl KitsuneP2pHandler for Space {
             module.local_agent_join(agent.clone());
         }
         let fut = self.i_s.update_single_agent_info(agent);
-        let evt_sender = self.evt_sender.clone();
         match self.config.network_type {
             NetworkType::QuicMdns => {
                 // Listen to MDNS service that has that space as service type
-                let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
-                if !self.mdns_listened_spaces.contains(&space_b64) {
-                    self.mdns_listened_spaces.insert(space_b64.clone());
-                    tokio::task::spawn(async move {
-                        let stream = mdns_listen(space_b64);
-                        tokio::pin!(stream);
-                        while let Some(maybe_response) = stream.next().await {
-                            match maybe_response {
-                                Ok(response) => {
-                                    tracing::trace!(msg = "Peer found via MDNS", ?response);
-                                    // Decode response
-                                    let maybe_agent_info_signed =
-                                        rmp_decode(&mut &*response.buffer);
-                                    if let Err(e) = maybe_agent_info_signed {
-                                        tracing::error!(msg = "Failed to decode MDNS peer", ?e);
-                                        continue;
-                                    }
-                                    if let Ok(remote_agent_info_signed) = maybe_agent_info_signed {
-                                        // Add to local storage
-                                        if let Err(e) = evt_sender
-                                            .put_agent_info_signed(PutAgentInfoSignedEvt {
-                                                space: space.clone(),
-                                                peer_data: vec![remote_agent_info_signed],
-                                            })
-                                            .await
-                                        {
-                                            tracing::error!(msg = "Failed to store MDNS peer", ?e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    tracing::error!(msg = "Failed to get peers from MDNS", ?e);
-                                }
-                            }
-                        }
-                    });
-                }
+                self.mdns.listen(space, self.evt_sender.clone());
             }
             NetworkType::QuicBootstrap => {
                 // quic bootstrap is managed for the whole space
@@ -1062,6 +1065,77 @@ impl KitsuneP2pHandler for Space {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_status(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<SpaceGossipStatus> {
+        let modules = self
+            .gossip_mod
+            .values()
+            .filter_map(|module| module.status())
+            .collect();
+        let arc_set = self
+            .agent_arcs
+            .values()
+            .map(|a| DhtArcSet::from_interval(DhtArcRange::from(a)))
+            .fold(DhtArcSet::new_empty(), |a, i| a.union(&i));
+        let peers_fut = self
+            .evt_sender
+            .query_agents(QueryAgentsEvt::new(space).by_arc_set(Arc::new(arc_set)));
+        let local_agents = self.local_joined_agents.clone();
+        let metrics = self.ro_inner.metrics.clone();
+        Ok(async move {
+            let peers: Vec<_> = peers_fut
+                .await?
+                .into_iter()
+                .filter(|info| !local_agents.contains(&info.agent))
+                .collect();
+            Ok(gossip_status(modules, &peers, &metrics.read()))
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_network_status(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<NetworkStatus> {
+        let status = self.ro_inner.connectivity.status();
+        Ok(async move { Ok(status) }.boxed().into())
+    }
+}
+
+/// Summarize the gossip progress with the peers that overlap our arcs.
+fn gossip_status(
+    modules: Vec<GossipStatus>,
+    peers: &[AgentInfoSigned],
+    metrics: &Metrics,
+) -> SpaceGossipStatus {
+    let peers: Vec<_> = peers
+        .iter()
+        .map(|info| PeerGossipStatus {
+            agent: info.agent.clone(),
+            in_current_round: metrics.is_current_round([info]),
+            ms_since_last_completed_round: metrics
+                .last_success([info])
+                .map(|t| t.elapsed().as_millis() as u64),
+        })
+        .collect();
+    let estimated_sync = if peers.is_empty() {
+        None
+    } else {
+        let synced = peers
+            .iter()
+            .filter(|p| p.ms_since_last_completed_round.is_some())
+            .count();
+        Some(synced as f64 / peers.len() as f64)
+    };
+    SpaceGossipStatus {
+        modules,
+        peers,
+        estimated_sync,
+    }
 }
 
 pub(crate) struct SpaceReadOnlyInner {
@@ -1076,6 +1150,7 @@ pub(crate) struct SpaceReadOnlyInner {
     pub(crate) parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     pub(crate) metrics: MetricsSync,
     pub(crate) metric_exchange: MetricExchangeSync,
+    pub(crate) connectivity: Connectivity,
 }
 
 /// A Kitsune P2p Node can track multiple "spaces" -- Non-interacting namespaced
@@ -1089,8 +1164,7 @@ pub(crate) struct Space {
     pub(crate) local_joined_agents: HashSet<Arc<KitsuneAgent>>,
     pub(crate) agent_arcs: HashMap<Arc<KitsuneAgent>, DhtArc>,
     pub(crate) config: Arc<KitsuneP2pConfig>,
-    mdns_handles: HashMap<Vec<u8>, Arc<AtomicBool>>,
-    mdns_listened_spaces: HashSet<String>,
+    mdns: MdnsDiscovery,
     gossip_mod: HashMap<GossipModuleType, GossipModule>,
 }
 
@@ -1178,29 +1252,47 @@ impl Space {
             .collect();
 
         let i_s_c = i_s.clone();
-        let agent_info_update_interval_ms =
-            config.tuning_params.gossip_agent_info_update_interval_ms as u64;
+        let agent_info_update_interval = std::time::Duration::from_millis(
+            config.tuning_params.gossip_agent_info_update_interval_ms as u64,
+        );
+        let jitter_percent = config.tuning_params.bootstrap_jitter_percent;
         tokio::task::spawn(async move {
+            use ghost_actor::GhostControlSender;
             loop {
-                tokio::time::sleep(std::time::Duration::from_millis(
-                    agent_info_update_interval_ms,
+                tokio::time::sleep(super::bootstrap::jitter(
+                    agent_info_update_interval,
+                    jitter_percent,
                 ))
                 .await;
+                if !i_s_c.ghost_actor_is_active() {
+                    break;
+                }
+                // this also re-publishes our agent info to the bootstrap service
                 if let Err(e) = i_s_c.update_agent_info().await {
                     tracing::error!(failed_to_update_agent_info_for_space = ?e);
                 }
             }
         });
 
+        let connectivity = Connectivity::new(
+            space.clone(),
+            i_s.clone(),
+            host_api.clone(),
+            config.network_type == NetworkType::QuicBootstrap && config.bootstrap_service.is_some(),
+        );
+
         if let NetworkType::QuicBootstrap = &config.network_type {
             // spawn the periodic bootstrap pull
+            let connectivity = connectivity.clone();
             let i_s_c = i_s.clone();
             let evt_s_c = evt_sender.clone();
             let bootstrap_service = config.bootstrap_service.clone();
             let space_c = space.clone();
+            let max_delay = std::time::Duration::from_millis(
+                config.tuning_params.bootstrap_max_delay_ms as u64,
+            );
             tokio::task::spawn(async move {
                 const START_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
-                const MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60 * 60);
 
                 let mut delay_len = START_DELAY;
 
@@ -1210,10 +1302,8 @@ impl Space {
                         break;
                     }
 
-                    tokio::time::sleep(delay_len).await;
-                    if delay_len <= MAX_DELAY {
-                        delay_len *= 2;
-                    }
+                    tokio::time::sleep(super::bootstrap::jitter(delay_len, jitter_percent)).await;
+                    delay_len = std::cmp::min(delay_len * 2, max_delay);
 
                     match super::bootstrap::random(
                         bootstrap_service.clone(),
@@ -1225,9 +1315,11 @@ impl Space {
                     .await
                     {
                         Err(e) => {
+                            connectivity.record_bootstrap(false);
                             tracing::error!(msg = "Failed to get peers from bootstrap", ?e);
                         }
                         Ok(list) => {
+                            connectivity.record_bootstrap(true);
                             if !i_s_c.ghost_actor_is_active() {
                                 break;
                             }
@@ -1270,6 +1362,7 @@ impl Space {
             parallel_notify_permit,
             metrics,
             metric_exchange,
+            connectivity,
         });
 
         Self {
@@ -1281,8 +1374,7 @@ impl Space {
             local_joined_agents: HashSet::new(),
             agent_arcs: HashMap::new(),
             config,
-            mdns_handles: HashMap::new(),
-            mdns_listened_spaces: HashSet::new(),
+            mdns: MdnsDiscovery::default(),
             gossip_mod,
         }
     }
@@ -1305,6 +1397,10 @@ impl Space {
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
+        if let NetworkType::QuicMdns = network_type {
+            // Peers on the LAN forget the agent once its last broadcast expires.
+            self.mdns.stop_advertising(&agent);
+        }
         Ok(async move {
             let signed_at_ms = crate::spawn::actor::bootstrap::now_once(None).await?;
             let expires_at_ms = signed_at_ms + expires_after;
@@ -1344,7 +1440,7 @@ impl Space {
 
             // Push to the network as well
             match network_type {
-                NetworkType::QuicMdns => tracing::warn!("NOT publishing leaves to mdns"),
+                NetworkType::QuicMdns => (),
                 NetworkType::QuicBootstrap => {
                     crate::spawn::actor::bootstrap::put(
                         bootstrap_service.clone(),
@@ -0,0 +1,159 @@
+//! Tracks the [`NetworkStatus`] of a space, and rejoins the network when
+//! a space which was offline can reach it again.
+//!
+//! The status is worked out from whether remote peers could be connected to
+//! and, if the space uses a bootstrap service, whether the last request to it
+//! succeeded. Connections are opened on demand, so a space without open
+//! connections isn't offline until its attempts to reach peers start failing.
+//! When a space stops being offline, the local agents are put to the bootstrap
+//! service again and the host is told with [`KitsuneHost::network_rejoined`]
+//! so it can publish what was authored while offline.
+
+use super::*;
+
+/// Peers count as unreachable after this many connection attempts
+/// in a row have failed, so one peer which went away doesn't make
+/// the whole space look offline.
+const UNREACHABLE_AFTER_FAILED_CONNECTS: u32 = 3;
+
+/// The connectivity of a space.
+/// Clones share the same state.
+#[derive(Clone)]
+pub(crate) struct Connectivity(Arc<parking_lot::Mutex<ConnectivityInner>>);
+
+struct ConnectivityInner {
+    space: Arc<KitsuneSpace>,
+    i_s: ghost_actor::GhostSender<SpaceInternal>,
+    host_api: HostApi,
+    uses_bootstrap: bool,
+    failed_connects: u32,
+    /// Whether remote peers can be reached,
+    /// or `None` before any connection was made or attempted.
+    peers_reachable: Option<bool>,
+    /// Whether the last request to the bootstrap service succeeded,
+    /// or `None` before the first request or if there is no bootstrap service.
+    bootstrap_reachable: Option<bool>,
+    status: NetworkStatus,
+}
+
+impl Connectivity {
+    /// A space starts out online, until it fails to reach the network.
+    pub fn new(
+        space: Arc<KitsuneSpace>,
+        i_s: ghost_actor::GhostSender<SpaceInternal>,
+        host_api: HostApi,
+        uses_bootstrap: bool,
+    ) -> Self {
+        Self(Arc::new(parking_lot::Mutex::new(ConnectivityInner {
+            space,
+            i_s,
+            host_api,
+            uses_bootstrap,
+            failed_connects: 0,
+            peers_reachable: None,
+            bootstrap_reachable: None,
+            status: NetworkStatus::Online,
+        })))
+    }
+
+    pub fn status(&self) -> NetworkStatus {
+        self.0.lock().status
+    }
+
+    /// Record whether a connection to a remote peer could be made.
+    /// New incoming connections count as well.
+    pub fn record_peer_connect(&self, connected: bool) {
+        let mut inner = self.0.lock();
+        if connected {
+            inner.failed_connects = 0;
+            inner.peers_reachable = Some(true);
+        } else {
+            inner.failed_connects += 1;
+            if inner.failed_connects >= UNREACHABLE_AFTER_FAILED_CONNECTS {
+                inner.peers_reachable = Some(false);
+            }
+        }
+        inner.update();
+    }
+
+    /// Record whether a request to the bootstrap service succeeded.
+    pub fn record_bootstrap(&self, reachable: bool) {
+        let mut inner = self.0.lock();
+        if inner.uses_bootstrap {
+            inner.bootstrap_reachable = Some(reachable);
+            inner.update();
+        }
+    }
+}
+
+impl ConnectivityInner {
+    fn update(&mut self) {
+        let status = network_status(self.peers_reachable, self.bootstrap_reachable);
+        let previous = std::mem::replace(&mut self.status, status);
+        if status == previous {
+            return;
+        }
+        tracing::info!(space = ?self.space, ?previous, ?status, "Network status changed");
+        if previous == NetworkStatus::Offline {
+            self.rejoin();
+        }
+    }
+
+    fn rejoin(&self) {
+        let space = self.space.clone();
+        let i_s = self.i_s.clone();
+        let host_api = self.host_api.clone();
+        tokio::task::spawn(async move {
+            // this also re-publishes our agent info to the bootstrap service
+            if let Err(e) = i_s.update_agent_info().await {
+                tracing::warn!(msg = "Failed to update agent info on rejoining", ?e);
+            }
+            if let Err(e) = host_api.network_rejoined(space).await {
+                tracing::error!(msg = "Failed to tell the host the network was rejoined", ?e);
+            }
+        });
+    }
+}
+
+fn network_status(
+    peers_reachable: Option<bool>,
+    bootstrap_reachable: Option<bool>,
+) -> NetworkStatus {
+    match (peers_reachable, bootstrap_reachable) {
+        (Some(false), Some(false)) | (Some(false), None) | (None, Some(false)) => {
+            NetworkStatus::Offline
+        }
+        (Some(true), Some(false)) | (Some(false), Some(true)) => NetworkStatus::Partial,
+        _ => NetworkStatus::Online,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_status_from_peers_and_bootstrap() {
+        assert_eq!(network_status(None, None), NetworkStatus::Online);
+        assert_eq!(network_status(Some(true), None), NetworkStatus::Online);
+        assert_eq!(network_status(None, Some(true)), NetworkStatus::Online);
+        assert_eq!(
+            network_status(Some(true), Some(true)),
+            NetworkStatus::Online
+        );
+        assert_eq!(
+            network_status(Some(true), Some(false)),
+            NetworkStatus::Partial
+        );
+        assert_eq!(
+            network_status(Some(false), Some(true)),
+            NetworkStatus::Partial
+        );
+        assert_eq!(network_status(Some(false), None), NetworkStatus::Offline);
+        assert_eq!(network_status(None, Some(false)), NetworkStatus::Offline);
+        assert_eq!(
+            network_status(Some(false), Some(false)),
+            NetworkStatus::Offline
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Peer discovery on the local network over MDNS, used in place of a
+//! bootstrap service when the network type is [`NetworkType::QuicMdns`].
+//!
+//! Every local agent of a space is advertised as a service whose type is the
+//! space and whose name is the agent, with the signed agent info in its TXT
+//! record. Agent infos found by browsing for the service type of the space
+//! are added to the peer store.
+
+use super::*;
+use kitsune_p2p_mdns::*;
+
+/// The MDNS broadcasts and listener of a space.
+/// Clones share the same broadcasts.
+#[derive(Clone, Default)]
+pub(super) struct MdnsDiscovery(Arc<parking_lot::Mutex<MdnsDiscoveryInner>>);
+
+#[derive(Default)]
+struct MdnsDiscoveryInner {
+    broadcasts: HashMap<Arc<KitsuneAgent>, Arc<AtomicBool>>,
+    listener: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl MdnsDiscovery {
+    /// Advertise the agent info of a local agent,
+    /// replacing the previous broadcast for that agent.
+    pub fn advertise(&self, agent_info_signed: &AgentInfoSigned) -> KitsuneP2pResult<()> {
+        let space_b64 = service_type(&agent_info_signed.space);
+        let agent_b64 =
+            base64::encode_config(&agent_info_signed.agent[..], base64::URL_SAFE_NO_PAD);
+        tracing::trace!(?space_b64, ?agent_b64);
+        let mut buffer = Vec::new();
+        rmp_encode(&mut buffer, agent_info_signed)?;
+        let handle = mdns_create_broadcast_thread(space_b64, agent_b64, &buffer);
+        if let Some(previous) = self
+            .0
+            .lock()
+            .broadcasts
+            .insert(agent_info_signed.agent.clone(), handle)
+        {
+            mdns_kill_thread(previous);
+        }
+        Ok(())
+    }
+
+    /// Stop advertising an agent which has left the space.
+    pub fn stop_advertising(&self, agent: &KitsuneAgent) {
+        if let Some(handle) = self.0.lock().broadcasts.remove(agent) {
+            mdns_kill_thread(handle);
+        }
+    }
+
+    /// Start browsing for peers of the space, unless already browsing.
+    pub fn listen(
+        &self,
+        space: Arc<KitsuneSpace>,
+        evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    ) {
+        let mut inner = self.0.lock();
+        if inner.listener.is_none() {
+            inner.listener = Some(tokio::task::spawn(listen(space, evt_sender)));
+        }
+    }
+
+    /// Stop all broadcasts and browsing.
+    pub fn shutdown(&self) {
+        let mut inner = self.0.lock();
+        for (_, handle) in inner.broadcasts.drain() {
+            mdns_kill_thread(handle);
+        }
+        if let Some(listener) = inner.listener.take() {
+            listener.abort();
+        }
+    }
+}
+
+/// The MDNS service type of a space.
+fn service_type(space: &KitsuneSpace) -> String {
+    base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD)
+}
+
+/// Whether an agent info found over MDNS should be added to the peer store.
+/// Anyone on the LAN can respond to the query, so only accept
+/// unexpired infos for the space being browsed.
+fn should_store(space: &KitsuneSpace, agent_info_signed: &AgentInfoSigned, now_ms: u64) -> bool {
+    *agent_info_signed.space == *space && agent_info_signed.expires_at_ms > now_ms
+}
+
+async fn listen(
+    space: Arc<KitsuneSpace>,
+    evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+) {
+    let stream = mdns_listen(service_type(&space));
+    tokio::pin!(stream);
+    while let Some(maybe_response) = stream.next().await {
+        let response = match maybe_response {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::error!(msg = "Failed to get peers from MDNS", ?e);
+                continue;
+            }
+        };
+        tracing::trace!(msg = "Peer found via MDNS", ?response);
+        let remote_agent_info_signed: AgentInfoSigned = match rmp_decode(&mut &*response.buffer) {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::error!(msg = "Failed to decode MDNS peer", ?e);
+                continue;
+            }
+        };
+        let now_ms = match crate::spawn::actor::bootstrap::now_once(None).await {
+            Ok(now_ms) => now_ms,
+            Err(e) => {
+                tracing::error!(msg = "Failed to get the time", ?e);
+                continue;
+            }
+        };
+        if !should_store(&space, &remote_agent_info_signed, now_ms) {
+            tracing::debug!(msg = "Ignoring MDNS peer", ?remote_agent_info_signed);
+            continue;
+        }
+        if let Err(e) = evt_sender
+            .put_agent_info_signed(PutAgentInfoSignedEvt {
+                space: space.clone(),
+                peer_data: vec![remote_agent_info_signed],
+            })
+            .await
+        {
+            tracing::error!(msg = "Failed to store MDNS peer", ?e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent_info(space: u8, expires_at_ms: u64) -> AgentInfoSigned {
+        futures::executor::block_on(AgentInfoSigned::sign(
+            Arc::new(KitsuneSpace(vec![space; 36])),
+            Arc::new(KitsuneAgent(vec![0; 36])),
+            u32::MAX,
+            Vec::new(),
+            0,
+            expires_at_ms,
+            |_| async move { Ok(Arc::new(vec![0; 64].into())) },
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_unexpired_peers_of_the_space_are_stored() {
+        let space = KitsuneSpace(vec![1; 36]);
+
+        let info = agent_info(1, 2000);
+        assert!(should_store(&space, &info, 1000));
+        assert!(!should_store(&space, &info, 2000));
+
+        let info = agent_info(2, 2000);
+        assert!(!should_store(&space, &info, 1000));
+    }
+}
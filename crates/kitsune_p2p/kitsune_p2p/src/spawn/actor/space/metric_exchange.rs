@@ -174,6 +174,18 @@ impl MetricExchangeSync {
 
                     if last_extrap_cov.should_trigger() {
                         let arc_set = mx.read().arc_set.clone();
+
+                        // Count the holders of each basis in our arc
+                        // from the gossip rounds that finished.
+                        let target = mx.read().tuning_params.gossip_redundancy_target;
+                        if metrics.write().record_redundancy(&arc_set, target) {
+                            tracing::info!(
+                                min_redundancy = ?metrics.read().min_redundancy(),
+                                target,
+                                "Redundancy dropped below target, pushing ops to more peers"
+                            );
+                        }
+
                         if let Ok(res) = host
                             .peer_extrapolated_coverage(space.clone(), arc_set)
                             .await
@@ -18,13 +18,30 @@ pub(crate) async fn handle_rpc_multi(
 
     driver.await;
 
-    Ok(out.finish())
+    out.finish()
 }
 
 struct Inner {
     response: Vec<actor::RpcMultiResponse>,
     remain_remote_count: u8,
     already_tried: HashSet<Arc<KitsuneAgent>>,
+    /// remote searches and calls that have not finished yet
+    pending_remote_count: usize,
+    /// the max timeout elapsed while remote requests were pending
+    timed_out: bool,
+}
+
+fn pending_remote(inner: &Share<Inner>, pending: bool) {
+    inner
+        .share_mut(|i, _| {
+            if pending {
+                i.pending_remote_count += 1;
+            } else {
+                i.pending_remote_count -= 1;
+            }
+            Ok(())
+        })
+        .expect("we never close this share")
 }
 
 fn check_already_tried(inner: &mut Inner, agent: &Arc<KitsuneAgent>) -> bool {
@@ -119,6 +136,8 @@ impl Outer {
                 response: Vec::new(),
                 remain_remote_count: max_remote_agent_count,
                 already_tried: HashSet::new(),
+                pending_remote_count: 0,
+                timed_out: false,
             }),
             ro_inner,
             agg,
@@ -140,12 +159,22 @@ impl Outer {
         out
     }
 
-    /// consume this logic container, returning the results
-    fn finish(self) -> Vec<actor::RpcMultiResponse> {
+    /// consume this logic container, returning the results,
+    /// or a timeout error if there are none because remote requests
+    /// were still pending when the max timeout elapsed
+    fn finish(self) -> KitsuneP2pResult<Vec<actor::RpcMultiResponse>> {
         let Self { inner, .. } = self;
 
         inner
-            .share_mut(|i, _| Ok(i.response.drain(..).collect()))
+            .share_mut(|i, _| {
+                if i.response.is_empty() && i.timed_out {
+                    return Ok(Err(KitsuneError::from(
+                        kitsune_p2p_types::KitsuneErrorKind::TimedOut,
+                    )
+                    .into()));
+                }
+                Ok(Ok(i.response.drain(..).collect()))
+            })
             .expect("we never close this share")
     }
 
@@ -195,12 +224,20 @@ impl Outer {
 
     /// stop all processing if/when we reach our max timeout.
     fn add_max_timeout_task(&self, max_timeout: KitsuneTimeout) {
+        let inner = self.inner.clone();
         let kill = self.kill.clone();
 
         self.add_task(async move {
             // wait the max timeout
             tokio::time::sleep(max_timeout.time_remaining()).await;
 
+            inner
+                .share_mut(|i, _| {
+                    i.timed_out = i.pending_remote_count > 0;
+                    Ok(())
+                })
+                .expect("we never close this share");
+
             // end all processing
             kill.kill_all();
 
@@ -328,6 +365,7 @@ impl Outer {
         let add_tokio_task = self.gen_add_tokio_task_fn();
         let report_results = self.gen_report_results_fn();
 
+        let inner = self.inner.clone();
         let ro_inner = self.ro_inner.clone();
         let space = self.space.clone();
         let payload = self.payload.clone();
@@ -335,10 +373,13 @@ impl Outer {
 
         Arc::new(move |info, permit| {
             let report_results = report_results.clone();
+            let inner = inner.clone();
             let ro_inner = ro_inner.clone();
             let space = space.clone();
             let payload = payload.clone();
 
+            pending_remote(&inner, true);
+
             add_tokio_task(
                 async move {
                     use discover::PeerDiscoverResult;
@@ -347,11 +388,13 @@ impl Outer {
                         match discover::peer_connect(ro_inner.clone(), &info, max_timeout).await {
                             PeerDiscoverResult::OkShortcut => {
                                 tracing::trace!("remote peer is local");
+                                pending_remote(&inner, false);
                                 permit.close();
                                 return;
                             }
                             PeerDiscoverResult::Err(err) => {
                                 tracing::warn!(?err, "remote call error");
+                                pending_remote(&inner, false);
                                 permit.close();
                                 return;
                             }
@@ -392,6 +435,7 @@ impl Outer {
                         }
                     }
 
+                    pending_remote(&inner, false);
                     permit.close();
                 }
                 .boxed(),
@@ -440,6 +484,8 @@ impl Outer {
         let add_tokio_task = self.gen_add_tokio_task_fn();
         let remote_call = self.gen_remote_call_fn();
 
+        pending_remote(&inner, true);
+
         // see add_tokio_task vs add_task
         add_tokio_task(
             async move {
@@ -472,6 +518,7 @@ impl Outer {
                     }
                 }
 
+                pending_remote(&inner, false);
                 first_discover_permit.close();
 
                 tracing::trace!("(rpc_multi_logic) remote get done");
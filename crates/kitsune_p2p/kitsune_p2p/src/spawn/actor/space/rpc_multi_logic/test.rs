@@ -238,6 +238,8 @@ async fn test_rpc_multi_logic_mocked() {
         metrics.clone(),
     );
 
+    let connectivity = Connectivity::new(space.clone(), i_s.clone(), host_api.clone(), false);
+
     // build up the ro_inner that discover calls expect
     let ro_inner = Arc::new(SpaceReadOnlyInner {
         space: space.clone(),
@@ -251,6 +253,7 @@ async fn test_rpc_multi_logic_mocked() {
         config,
         metrics,
         metric_exchange,
+        connectivity,
     });
 
     let basis = Arc::new(KitsuneBasis(vec![0; 36]));
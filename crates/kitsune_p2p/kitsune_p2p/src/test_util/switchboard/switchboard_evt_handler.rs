@@ -76,6 +76,14 @@ impl KitsuneHost for SwitchboardEventHandler {
         box_fut(Ok(()))
     }
 
+    fn query_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        query: MetricQuery,
+    ) -> crate::KitsuneHostResult<MetricQueryAnswer> {
+        box_fut(Ok(query.empty_answer()))
+    }
+
     fn query_size_limited_regions(
         &self,
         _space: Arc<KitsuneSpace>,
@@ -165,6 +173,33 @@ impl KitsuneHost for SwitchboardEventHandler {
     ) -> crate::KitsuneHostResult<dht::spacetime::Topology> {
         box_fut(Ok(self.sb.topology.clone()))
     }
+
+    fn get_blocks(
+        &self,
+        _space: Arc<KitsuneSpace>,
+    ) -> crate::KitsuneHostResult<Vec<crate::block::Block>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn put_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _block: crate::block::Block,
+    ) -> crate::KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn delete_block(
+        &self,
+        _space: Arc<KitsuneSpace>,
+        _target: crate::block::BlockTarget,
+    ) -> crate::KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
+
+    fn network_rejoined(&self, _space: Arc<KitsuneSpace>) -> crate::KitsuneHostResult<()> {
+        box_fut(Ok(()))
+    }
 }
 
 #[allow(warnings)]
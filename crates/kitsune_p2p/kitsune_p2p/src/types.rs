@@ -44,6 +44,10 @@ pub enum KitsuneP2pError {
     #[error(transparent)]
     TryFromInt(#[from] std::num::TryFromIntError),
 
+    /// The peer is blocked.
+    #[error("Blocked: {0:?}")]
+    Blocked(crate::block::BlockTarget),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -61,6 +65,11 @@ impl KitsuneP2pError {
     pub fn decoding_error(s: String) -> Self {
         Self::DecodingError(s.into_boxed_str())
     }
+
+    /// Is this a timeout error?
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::KitsuneError(e) if *e.kind() == kitsune_p2p_types::KitsuneErrorKind::TimedOut)
+    }
 }
 
 impl From<String> for KitsuneP2pError {
@@ -91,12 +100,15 @@ pub mod agent_store {
 }
 
 pub mod actor;
+pub mod block;
 pub mod event;
 pub(crate) mod gossip;
 #[allow(missing_docs)]
 pub mod wire;
 
-pub use gossip::GossipModuleType;
+pub use gossip::{
+    GossipModuleType, GossipRoundStatus, GossipStatus, PeerGossipStatus, SpaceGossipStatus,
+};
 pub use kitsune_p2p_types::dht;
 pub use kitsune_p2p_types::dht_arc;
 
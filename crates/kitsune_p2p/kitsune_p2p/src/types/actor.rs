@@ -1,5 +1,6 @@
 //! Definitions related to the KitsuneP2p peer-to-peer / dht communications actor.
 
+use crate::types::gossip::SpaceGossipStatus;
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::KitsuneTimeout;
 use std::sync::Arc;
@@ -69,6 +70,22 @@ pub enum BroadcastTo {
     PublishAgentInfo,
 }
 
+/// How well a space is connected to the network.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkStatus {
+    /// There are no open connections, and the bootstrap service,
+    /// if there is one, can't be reached.
+    /// Data authored while offline is published once the space rejoins.
+    Offline,
+    /// There are open connections but the bootstrap service can't be reached,
+    /// or the bootstrap service can be reached but there are no open connections.
+    Partial,
+    /// There are open connections, and the bootstrap service,
+    /// if there is one, can be reached.
+    Online,
+}
+
 type KSpace = Arc<super::KitsuneSpace>;
 type KSpaceOpt = Option<Arc<super::KitsuneSpace>>;
 type KAgent = Arc<super::KitsuneAgent>;
@@ -77,6 +94,8 @@ type KBasis = Arc<super::KitsuneBasis>;
 type Payload = Vec<u8>;
 type OptU64 = Option<u64>;
 type OptArc = Option<crate::dht_arc::DhtArc>;
+type Block = crate::block::Block;
+type BlockTarget = crate::block::BlockTarget;
 
 ghost_actor::ghost_chan! {
     /// The KitsuneP2pSender allows async remote-control of the KitsuneP2p actor.
@@ -96,6 +115,8 @@ ghost_actor::ghost_chan! {
 
         /// Make a request to multiple destination agents - awaiting/aggregating the responses.
         /// The remote sides will see these messages as "Call" events.
+        /// Fails with a `TimedOut` error if there are no responses because
+        /// remote requests were still pending when the max timeout elapsed.
         fn rpc_multi(input: RpcMulti) -> Vec<RpcMultiResponse>;
 
         /// Publish data to a "neighborhood" of remote nodes surrounding the
@@ -141,5 +162,18 @@ ghost_actor::ghost_chan! {
         fn dump_network_metrics(
             space: KSpaceOpt,
         ) -> serde_json::Value;
+
+        /// Get the progress of gossip in a space.
+        fn gossip_status(space: KSpace) -> SpaceGossipStatus;
+
+        /// Get how well a space is connected to the network.
+        fn network_status(space: KSpace) -> NetworkStatus;
+
+        /// Block a peer in a space, replacing any existing block on it.
+        /// The block is stored by the host, so it outlives this actor.
+        fn block(space: KSpace, block: Block) -> ();
+
+        /// Remove the block on a peer in a space.
+        fn unblock(space: KSpace, target: BlockTarget) -> ();
     }
 }
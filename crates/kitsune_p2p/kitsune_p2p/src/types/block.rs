@@ -0,0 +1,241 @@
+//! Blocking misbehaving peers.
+
+use crate::types::agent_store::AgentInfoSigned;
+use crate::types::{KitsuneAgent, KitsuneSpace};
+use kitsune_p2p_timestamp::Timestamp;
+use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
+use kitsune_p2p_types::Tx2Cert;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// A peer that can be blocked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum BlockTarget {
+    /// An agent in a space.
+    /// We don't store its agent info, call it, or send it broadcasts,
+    /// and we drop calls, publishes and gossip for the space
+    /// from the nodes we've seen it on.
+    Agent(Arc<KitsuneAgent>),
+
+    /// A node, by the digest of its tls cert.
+    /// We close connections with it and drop anything it sends us.
+    /// Connections are shared by all spaces, so a cert blocked in
+    /// one space is blocked in all of them.
+    Cert(Tx2Cert),
+}
+
+/// A block on a peer in a space.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Block {
+    /// The blocked peer.
+    pub target: BlockTarget,
+
+    /// When the block ends, or `None` to block until unblocked.
+    pub until: Option<Timestamp>,
+}
+
+impl Block {
+    /// Is this block in effect at `now`?
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        is_active(self.until, now)
+    }
+}
+
+fn is_active(until: Option<Timestamp>, now: Timestamp) -> bool {
+    match until {
+        Some(until) => now < until,
+        None => true,
+    }
+}
+
+/// When the block on a target ends, by the space it was blocked in.
+type BlockedSpaces = HashMap<Arc<KitsuneSpace>, Option<Timestamp>>;
+
+#[derive(Default)]
+struct BlockListInner {
+    blocks: HashMap<BlockTarget, BlockedSpaces>,
+
+    /// The nodes each blocked agent has been seen on, by space.
+    agent_nodes: HashMap<(Arc<KitsuneSpace>, Arc<KitsuneAgent>), HashSet<Tx2Cert>>,
+}
+
+impl BlockListInner {
+    fn is_agent_blocked(&self, space: &Arc<KitsuneSpace>, agent: &Arc<KitsuneAgent>) -> bool {
+        match self
+            .blocks
+            .get(&BlockTarget::Agent(agent.clone()))
+            .and_then(|spaces| spaces.get(space))
+        {
+            Some(until) => is_active(*until, Timestamp::now()),
+            None => false,
+        }
+    }
+}
+
+/// The blocks in effect on this node, shared by the actor
+/// and the endpoint event loop.
+#[derive(Clone, Default)]
+pub(crate) struct BlockList(Arc<parking_lot::RwLock<BlockListInner>>);
+
+impl BlockList {
+    /// Add a block on a peer in a space, replacing any existing one.
+    pub fn block(&self, space: Arc<KitsuneSpace>, block: Block) {
+        self.0
+            .write()
+            .blocks
+            .entry(block.target)
+            .or_default()
+            .insert(space, block.until);
+    }
+
+    /// Remove the block on a peer in a space.
+    pub fn unblock(&self, space: &Arc<KitsuneSpace>, target: &BlockTarget) {
+        let mut inner = self.0.write();
+        if let Some(spaces) = inner.blocks.get_mut(target) {
+            spaces.remove(space);
+            if spaces.is_empty() {
+                inner.blocks.remove(target);
+            }
+        }
+        if let BlockTarget::Agent(agent) = target {
+            inner.agent_nodes.remove(&(space.clone(), agent.clone()));
+        }
+    }
+
+    /// Is this node blocked in any space?
+    pub fn is_cert_blocked(&self, cert: &Tx2Cert) -> bool {
+        let now = Timestamp::now();
+        match self.0.read().blocks.get(&BlockTarget::Cert(cert.clone())) {
+            Some(spaces) => spaces.values().any(|until| is_active(*until, now)),
+            None => false,
+        }
+    }
+
+    /// Is this agent blocked in this space?
+    pub fn is_agent_blocked(&self, space: &Arc<KitsuneSpace>, agent: &Arc<KitsuneAgent>) -> bool {
+        self.0.read().is_agent_blocked(space, agent)
+    }
+
+    /// Is this node hosting an agent that is blocked in this space?
+    pub fn is_agent_node_blocked(&self, space: &Arc<KitsuneSpace>, cert: &Tx2Cert) -> bool {
+        let inner = self.0.read();
+        inner.agent_nodes.iter().any(|((s, agent), certs)| {
+            s == space && certs.contains(cert) && inner.is_agent_blocked(space, agent)
+        })
+    }
+
+    /// If this agent info is for a blocked agent,
+    /// remember the nodes it can be reached at.
+    pub fn note_agent_info(&self, space: &Arc<KitsuneSpace>, info: &AgentInfoSigned) {
+        self.note_agent_urls(space, &info.agent, &info.url_list);
+    }
+
+    fn note_agent_urls(
+        &self,
+        space: &Arc<KitsuneSpace>,
+        agent: &Arc<KitsuneAgent>,
+        urls: &[TxUrl],
+    ) {
+        let mut inner = self.0.write();
+        if !inner.is_agent_blocked(space, agent) {
+            return;
+        }
+        let certs = urls.iter().filter_map(|url| {
+            let purl = kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str()).ok()?;
+            Some(Tx2Cert::from(purl.digest()))
+        });
+        inner
+            .agent_nodes
+            .entry((space.clone(), agent.clone()))
+            .or_default()
+            .extend(certs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::bin_types::KitsuneBinType;
+
+    #[test]
+    fn blocks_expire_and_are_scoped() {
+        let space_a = Arc::new(KitsuneSpace::new(vec![1; 36]));
+        let space_b = Arc::new(KitsuneSpace::new(vec![2; 36]));
+        let agent = Arc::new(KitsuneAgent::new(vec![3; 36]));
+        let cert = Tx2Cert::from(vec![4; 32]);
+        let list = BlockList::default();
+
+        list.block(
+            space_a.clone(),
+            Block {
+                target: BlockTarget::Agent(agent.clone()),
+                until: None,
+            },
+        );
+        list.block(
+            space_a.clone(),
+            Block {
+                target: BlockTarget::Cert(cert.clone()),
+                until: None,
+            },
+        );
+
+        // - Agent blocks only apply in their space,
+        //   cert blocks apply everywhere.
+        assert!(list.is_agent_blocked(&space_a, &agent));
+        assert!(!list.is_agent_blocked(&space_b, &agent));
+        assert!(list.is_cert_blocked(&cert));
+
+        list.unblock(&space_a, &BlockTarget::Agent(agent.clone()));
+        assert!(!list.is_agent_blocked(&space_a, &agent));
+
+        // - A block in another space keeps the cert blocked
+        //   until it expires.
+        list.block(
+            space_b.clone(),
+            Block {
+                target: BlockTarget::Cert(cert.clone()),
+                until: Some(Timestamp::from_micros(0)),
+            },
+        );
+        list.unblock(&space_a, &BlockTarget::Cert(cert.clone()));
+        assert!(!list.is_cert_blocked(&cert));
+    }
+
+    #[test]
+    fn blocked_agent_nodes_are_blocked_in_space() {
+        let space_a = Arc::new(KitsuneSpace::new(vec![1; 36]));
+        let space_b = Arc::new(KitsuneSpace::new(vec![2; 36]));
+        let agent = Arc::new(KitsuneAgent::new(vec![3; 36]));
+        let cert = Tx2Cert::from(vec![4; 32]);
+        let url: TxUrl = kitsune_p2p_proxy::ProxyUrl::new(
+            "kitsune-quic://127.0.0.1:5000",
+            cert.as_digest().clone(),
+        )
+        .unwrap()
+        .as_str()
+        .into();
+        let list = BlockList::default();
+
+        // - Urls of agents that aren't blocked aren't remembered.
+        list.note_agent_urls(&space_a, &agent, std::slice::from_ref(&url));
+        list.block(
+            space_a.clone(),
+            Block {
+                target: BlockTarget::Agent(agent.clone()),
+                until: None,
+            },
+        );
+        assert!(!list.is_agent_node_blocked(&space_a, &cert));
+
+        // - Once seen, the blocked agent's node is blocked
+        //   only in the space the agent is blocked in.
+        list.note_agent_urls(&space_a, &agent, &[url]);
+        assert!(list.is_agent_node_blocked(&space_a, &cert));
+        assert!(!list.is_agent_node_blocked(&space_b, &cert));
+        assert!(!list.is_cert_blocked(&cert));
+
+        list.unblock(&space_a, &BlockTarget::Agent(agent));
+        assert!(!list.is_agent_node_blocked(&space_a, &cert));
+    }
+}
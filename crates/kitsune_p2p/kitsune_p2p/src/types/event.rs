@@ -159,6 +159,8 @@ const METRIC_KIND_UNKNOWN: &str = "Unknown";
 const METRIC_KIND_REACHABILITY_QUOTIENT: &str = "ReachabilityQuotient";
 const METRIC_KIND_LATENCY_MICROS: &str = "LatencyMicros";
 const METRIC_KIND_AGG_EXTRAP_COV: &str = "AggExtrapCov";
+const METRIC_KIND_ERROR_RATE: &str = "ErrorRate";
+const METRIC_KIND_LAST_SEEN: &str = "LastSeen";
 
 /// The type of metric recorded
 pub enum MetricRecordKind {
@@ -173,6 +175,12 @@ pub enum MetricRecordKind {
 
     /// AggExtrapCov metric kind
     AggExtrapCov,
+
+    /// ErrorRate metric kind
+    ErrorRate,
+
+    /// LastSeen metric kind
+    LastSeen,
 }
 
 impl MetricRecordKind {
@@ -184,6 +192,8 @@ impl MetricRecordKind {
             ReachabilityQuotient => METRIC_KIND_REACHABILITY_QUOTIENT,
             LatencyMicros => METRIC_KIND_LATENCY_MICROS,
             AggExtrapCov => METRIC_KIND_AGG_EXTRAP_COV,
+            ErrorRate => METRIC_KIND_ERROR_RATE,
+            LastSeen => METRIC_KIND_LAST_SEEN,
         }
     }
 
@@ -196,6 +206,10 @@ impl MetricRecordKind {
             LatencyMicros
         } else if input == METRIC_KIND_AGG_EXTRAP_COV {
             AggExtrapCov
+        } else if input == METRIC_KIND_ERROR_RATE {
+            ErrorRate
+        } else if input == METRIC_KIND_LAST_SEEN {
+            LastSeen
         } else {
             Unknown
         }
@@ -220,6 +234,52 @@ pub struct MetricRecord {
     pub data: serde_json::Value,
 }
 
+/// An aggregate query over the historical metric records of remote agents
+#[derive(Debug, Clone)]
+pub enum MetricQuery {
+    /// Mean latency of direct calls to each agent,
+    /// over the records made since `since`
+    LatencyMicros {
+        /// only consider records made at or after this time
+        since: Timestamp,
+    },
+
+    /// Mean fraction of gossip rounds with each agent that ended
+    /// in an error, over the records made since `since`
+    ErrorRate {
+        /// only consider records made at or after this time
+        since: Timestamp,
+    },
+
+    /// The last time we heard from each agent
+    LastSeen,
+}
+
+impl MetricQuery {
+    /// The answer to this query when there are no records
+    pub fn empty_answer(&self) -> MetricQueryAnswer {
+        match self {
+            Self::LatencyMicros { .. } => MetricQueryAnswer::LatencyMicros(Vec::new()),
+            Self::ErrorRate { .. } => MetricQueryAnswer::ErrorRate(Vec::new()),
+            Self::LastSeen => MetricQueryAnswer::LastSeen(Vec::new()),
+        }
+    }
+}
+
+/// The answer to a [`MetricQuery`], with one entry per agent
+/// that has matching records
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricQueryAnswer {
+    /// Mean latency in microseconds
+    LatencyMicros(Vec<(KAgent, f64)>),
+
+    /// Mean error rate, between 0.0 and 1.0
+    ErrorRate(Vec<(KAgent, f64)>),
+
+    /// Last time we heard from the agent
+    LastSeen(Vec<(KAgent, Timestamp)>),
+}
+
 type KSpace = Arc<super::KitsuneSpace>;
 type KAgent = Arc<super::KitsuneAgent>;
 type KOpHash = Arc<super::KitsuneOpHash>;
@@ -18,6 +18,62 @@ pub enum GossipModuleType {
     ShardedHistorical,
 }
 
+/// What a gossip module has done since the space was joined.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct GossipStatus {
+    /// The module reporting this status.
+    pub module: GossipModuleType,
+    /// The rounds in progress.
+    pub current_rounds: Vec<GossipRoundStatus>,
+    /// The number of rounds that finished without error.
+    pub completed_rounds: u64,
+    /// The number of ops sent to remote nodes.
+    pub ops_sent: u64,
+    /// The number of ops received from remote nodes.
+    pub ops_received: u64,
+}
+
+/// A gossip round in progress with a remote node.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct GossipRoundStatus {
+    /// The base64 tls cert of the remote node.
+    pub peer: String,
+    /// The agents the remote node holds.
+    pub remote_agents: Vec<Arc<KitsuneAgent>>,
+    /// Milliseconds since this round last had any contact.
+    pub idle_ms: u64,
+    /// The number of op blooms sent, which is the number of
+    /// missing op sets still expected from the remote node.
+    pub op_blooms_sent: u8,
+    /// Whether all op blooms have been received from the remote node.
+    pub received_all_op_blooms: bool,
+}
+
+/// The gossip status of a space, answering "is this node synced yet?".
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct SpaceGossipStatus {
+    /// The status of each gossip module running in the space.
+    pub modules: Vec<GossipStatus>,
+    /// The remote agents whose storage arc overlaps a local agent's arc,
+    /// which are the agents this node needs to gossip with.
+    pub peers: Vec<PeerGossipStatus>,
+    /// The fraction of `peers` a gossip round has been completed with,
+    /// from 0.0 to 1.0. `None` if no peers are known.
+    pub estimated_sync: Option<f64>,
+}
+
+/// The gossip status of a single remote agent.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct PeerGossipStatus {
+    /// The remote agent.
+    pub agent: Arc<KitsuneAgent>,
+    /// Whether a round with this agent's node is in progress.
+    pub in_current_round: bool,
+    /// Milliseconds since the last round with this agent's node completed,
+    /// or `None` if no round has completed.
+    pub ms_since_last_completed_round: Option<u64>,
+}
+
 /// Represents an interchangeable gossip strategy module
 pub trait AsGossipModule: 'static + Send + Sync {
     fn close(&self);
@@ -30,6 +86,9 @@ pub trait AsGossipModule: 'static + Send + Sync {
     fn local_agent_join(&self, a: Arc<KitsuneAgent>);
     fn local_agent_leave(&self, a: Arc<KitsuneAgent>);
     fn new_integrated_data(&self) {}
+    fn status(&self) -> Option<GossipStatus> {
+        None
+    }
 }
 
 #[derive(Clone)]
@@ -61,6 +120,11 @@ impl GossipModule {
     pub fn new_integrated_data(&self) {
         self.0.new_integrated_data();
     }
+
+    /// What this module has done so far, if it keeps track.
+    pub fn status(&self) -> Option<GossipStatus> {
+        self.0.status()
+    }
 }
 
 impl std::fmt::Debug for GossipModule {
@@ -0,0 +1,275 @@
+//! Opt-in capture of the kitsune wire protocol for bug reports.
+//!
+//! When the `wire_capture_path` tuning param is set, every message this
+//! node sends or receives is appended to that file as a
+//! [`WireCaptureRecord`]. Records hold a hash of each message, not its
+//! contents. The exception is sharded gossip, which is kept with every op
+//! swapped for its hash, so a captured gossip round can be fed back into
+//! the gossip state machine with
+//! [`ShardedGossipLocal::replay`](crate::gossip::sharded_gossip::ShardedGossipLocal::replay).
+//! The `kitsune-wire-capture` binary prints and replays capture logs.
+//!
+//! The log is a sequence of records, each a little endian `u32` length
+//! followed by the msgpack encoded record.
+
+use crate::gossip::sharded_gossip::ShardedGossipWire;
+use crate::types::gossip::GossipModuleType;
+use crate::wire;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_timestamp::Timestamp;
+use kitsune_p2p_types::bin_types::KitsuneOpData;
+use kitsune_p2p_types::codec::*;
+use kitsune_p2p_types::tx2::tx2_api::Tx2Direction;
+use kitsune_p2p_types::Tx2Cert;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+/// One message in a wire capture log.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WireCaptureRecord {
+    /// When the message was captured.
+    pub timestamp: Timestamp,
+    /// Whether the message was sent or received.
+    pub direction: Tx2Direction,
+    /// The tls cert digest of the remote node.
+    #[serde(with = "serde_bytes")]
+    pub peer: Vec<u8>,
+    /// The wire message variant, e.g. `"Wire::Gossip"`.
+    pub kind: String,
+    /// The encoded size of the message in bytes.
+    pub len: u64,
+    /// The blake2b-256 hash of the encoded message.
+    #[serde(with = "serde_bytes")]
+    pub hash: Vec<u8>,
+    /// The sharded gossip carried by this message, if any.
+    pub gossip: Option<CapturedGossip>,
+}
+
+/// The sharded gossip in a [`WireCaptureRecord`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CapturedGossip {
+    /// The gossip module the message was for.
+    pub module: GossipModuleType,
+    /// The encoded [`ShardedGossipWire`] message,
+    /// with the data of each op replaced by the hash of that data.
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl WireCaptureRecord {
+    /// Make the record for an encoded [`wire::Wire`] message.
+    pub fn new(direction: Tx2Direction, peer: &Tx2Cert, kind: &str, data: &[u8]) -> Self {
+        Self {
+            timestamp: Timestamp::now(),
+            direction,
+            peer: peer.as_bytes().to_vec(),
+            kind: kind.to_string(),
+            len: data.len() as u64,
+            hash: hash(data),
+            gossip: CapturedGossip::from_wire(data),
+        }
+    }
+
+    /// The cert of the remote node.
+    pub fn peer_cert(&self) -> Tx2Cert {
+        self.peer.clone().into()
+    }
+}
+
+impl CapturedGossip {
+    fn from_wire(data: &[u8]) -> Option<Self> {
+        let (module, gossip) = match wire::Wire::decode_ref(data).ok()? {
+            (_, wire::Wire::Gossip(wire::Gossip { data, module, .. }))
+                if module != GossipModuleType::Simple =>
+            {
+                (module, data)
+            }
+            _ => return None,
+        };
+        let gossip = match ShardedGossipWire::decode_ref(&gossip.0).ok()?.1 {
+            ShardedGossipWire::MissingOps(crate::gossip::sharded_gossip::MissingOps {
+                ops,
+                finished,
+            }) => ShardedGossipWire::missing_ops(
+                ops.iter()
+                    .map(|op| KitsuneOpData::new(hash(&op.0)))
+                    .collect(),
+                finished,
+            ),
+            gossip => gossip,
+        };
+        Some(Self {
+            module,
+            data: gossip.encode_vec().ok()?,
+        })
+    }
+}
+
+fn hash(data: &[u8]) -> Vec<u8> {
+    blake2b_simd::Params::new()
+        .hash_length(32)
+        .hash(data)
+        .as_bytes()
+        .to_vec()
+}
+
+/// How many records may wait to be written before new ones are dropped.
+const CAPTURE_QUEUE_LEN: usize = 4096;
+
+/// Appends [`WireCaptureRecord`]s to a capture log.
+///
+/// Records are written by a dedicated thread so that capturing never
+/// blocks the network on disk io. The thread finishes writing and exits
+/// once the capture is dropped.
+pub struct WireCapture(tokio::sync::mpsc::Sender<WireCaptureRecord>);
+
+impl WireCapture {
+    /// Open the capture log at this path, adding to it if it exists.
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let (send, recv) = tokio::sync::mpsc::channel(CAPTURE_QUEUE_LEN);
+        std::thread::Builder::new()
+            .name("wire-capture".to_string())
+            .spawn(move || write_task(std::io::BufWriter::new(file), recv))?;
+        Ok(Self(send))
+    }
+
+    /// Capture an encoded [`wire::Wire`] message.
+    /// If the writer falls behind the record is dropped
+    /// rather than slowing down the network.
+    pub fn capture(&self, direction: Tx2Direction, peer: &Tx2Cert, kind: &str, data: &[u8]) {
+        let record = WireCaptureRecord::new(direction, peer, kind, data);
+        if self.0.try_send(record).is_err() {
+            tracing::warn!("wire capture is falling behind, dropping a record");
+        }
+    }
+}
+
+fn write_task<W: Write>(mut writer: W, mut recv: tokio::sync::mpsc::Receiver<WireCaptureRecord>) {
+    while let Some(record) = recv.blocking_recv() {
+        let mut result = write_record(&mut writer, &record);
+        while result.is_ok() {
+            match recv.try_recv() {
+                Ok(record) => result = write_record(&mut writer, &record),
+                Err(_) => break,
+            }
+        }
+        // Flush whenever the queue is empty so a crash still leaves a complete log.
+        if let Err(err) = result.and_then(|_| writer.flush()) {
+            tracing::warn!(?err, "failed to write wire capture");
+        }
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &WireCaptureRecord) -> std::io::Result<()> {
+    let mut bytes = Vec::new();
+    rmp_encode(&mut bytes, record)?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)
+}
+
+/// Read every record in a wire capture log.
+pub fn read_wire_capture<R: Read>(mut reader: R) -> std::io::Result<Vec<WireCaptureRecord>> {
+    let mut records = Vec::new();
+    loop {
+        let mut len = [0; 4];
+        match reader.read_exact(&mut len) {
+            Ok(()) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+        let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+        reader.read_exact(&mut bytes)?;
+        records.push(rmp_decode(&mut bytes.as_slice())?);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NOISE;
+    use arbitrary::Arbitrary;
+    use kitsune_p2p_types::bin_types::KitsuneSpace;
+    use std::sync::Arc;
+
+    fn gossip(msg: ShardedGossipWire) -> Vec<u8> {
+        let mut u = arbitrary::Unstructured::new(&NOISE);
+        let space = Arc::new(KitsuneSpace::arbitrary(&mut u).unwrap());
+        wire::Wire::gossip(
+            space,
+            msg.encode_vec().unwrap().into(),
+            GossipModuleType::ShardedHistorical,
+        )
+        .encode_vec()
+        .unwrap()
+    }
+
+    #[test]
+    fn records_round_trip_through_the_log() {
+        let peer = Tx2Cert::from(vec![1; 32]);
+
+        let busy = gossip(ShardedGossipWire::busy());
+        let failure = wire::Wire::failure("nope".into()).encode_vec().unwrap();
+        let mut log = Vec::new();
+        for record in [
+            WireCaptureRecord::new(Tx2Direction::Inbound, &peer, "Wire::Gossip", &busy),
+            WireCaptureRecord::new(Tx2Direction::Outbound, &peer, "Wire::Failure", &failure),
+        ] {
+            write_record(&mut log, &record).unwrap();
+        }
+
+        let records = read_wire_capture(log.as_slice()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].direction, Tx2Direction::Inbound);
+        assert_eq!(records[0].peer_cert(), peer);
+        assert_eq!(records[0].len, busy.len() as u64);
+        assert_eq!(records[0].hash, hash(&busy));
+        let captured = records[0].gossip.as_ref().unwrap();
+        assert_eq!(captured.module, GossipModuleType::ShardedHistorical);
+        assert_eq!(
+            ShardedGossipWire::decode_ref(&captured.data).unwrap().1,
+            ShardedGossipWire::busy()
+        );
+        assert_eq!(records[1].kind, "Wire::Failure");
+        assert!(records[1].gossip.is_none());
+    }
+
+    #[test]
+    fn queued_records_are_written_in_order() {
+        let peer = Tx2Cert::from(vec![1; 32]);
+        let (send, recv) = tokio::sync::mpsc::channel(CAPTURE_QUEUE_LEN);
+        let records: Vec<_> = (0..10u8)
+            .map(|i| WireCaptureRecord::new(Tx2Direction::Inbound, &peer, "Wire::Failure", &[i]))
+            .collect();
+        for record in records.clone() {
+            send.try_send(record).unwrap();
+        }
+        drop(send);
+
+        let mut log = Vec::new();
+        write_task(&mut log, recv);
+        assert_eq!(read_wire_capture(log.as_slice()).unwrap(), records);
+    }
+
+    #[test]
+    fn op_data_is_not_captured() {
+        let peer = Tx2Cert::from(vec![1; 32]);
+        let op = vec![7; 100];
+        let data = gossip(ShardedGossipWire::missing_ops(
+            vec![KitsuneOpData::new(op.clone())],
+            2,
+        ));
+
+        let record = WireCaptureRecord::new(Tx2Direction::Inbound, &peer, "Wire::Gossip", &data);
+        let captured = record.gossip.unwrap();
+        assert_eq!(
+            ShardedGossipWire::decode_ref(&captured.data).unwrap().1,
+            ShardedGossipWire::missing_ops(vec![KitsuneOpData::new(hash(&op))], 2)
+        );
+    }
+}
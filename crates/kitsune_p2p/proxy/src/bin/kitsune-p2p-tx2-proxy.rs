@@ -1,10 +1,13 @@
 use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
+use kitsune_p2p_proxy::ProxyUrl;
 use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_transport_ws::tx2::*;
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
 use kitsune_p2p_types::metrics::*;
 use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_multi_adapter::*;
 use kitsune_p2p_types::tx2::tx2_pool::*;
 use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 use kitsune_p2p_types::*;
@@ -24,8 +27,20 @@ pub struct Opt {
     pub danger_use_unenc_cert: Option<std::path::PathBuf>,
 
     /// To which network interface / port should we bind?
+    /// The proxy serves quic nodes at this address, and websocket nodes
+    /// at the same host and port over tcp, relaying between them.
     #[structopt(short = "b", long, default_value = "kitsune-quic://0.0.0.0:0")]
     pub bind_to: String,
+
+    /// Serve usage stats as json over http at this address,
+    /// e.g. "127.0.0.1:8080".
+    #[structopt(long)]
+    pub stats_bind_to: Option<std::net::SocketAddr>,
+
+    /// Refuse to forward more than this many bytes per second
+    /// from any one client.
+    #[structopt(long)]
+    pub client_bandwidth_limit: Option<u64>,
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -108,18 +123,31 @@ async fn inner() -> KitsuneResult<()> {
     let mut conf = QuicConfig::default();
     conf.tls = Some(tls_conf.clone());
     conf.tuning_params = Some(tuning_params.clone());
-
-    let f = QuicBackendAdapt::new(conf).await?;
+    let quic = QuicBackendAdapt::new(conf).await?;
+    let mut conf = WsConfig::default();
+    conf.tls = Some(tls_conf.clone());
+    conf.tuning_params = Some(tuning_params.clone());
+    let ws = WsBackendAdapt::new(conf).await?;
+    let f = tx2_multi_adapter(vec![("kitsune-quic", quic), ("kitsune-ws", ws)]);
     let f = tx2_pool_promote(f, tuning_params.clone());
     let mut conf = ProxyConfig::default();
     conf.tuning_params = Some(tuning_params.clone());
     conf.allow_proxy_fwd = true;
+    conf.client_bandwidth_limit = opt.client_bandwidth_limit;
+    conf.stats_bind_to = opt.stats_bind_to;
     let f = tx2_proxy(f, conf)?;
 
     let ep = f
         .bind(opt.bind_to.into(), KitsuneTimeout::from_millis(30 * 1000))
         .await?;
-    println!("{}", ep.handle().local_addr()?);
+    let local_addr = ep.handle().local_addr()?;
+    println!("{}", local_addr);
+    // websocket nodes reach this proxy at the same host and port
+    let ws_addr = ProxyUrl::from_full(local_addr.as_str())?;
+    let ws_base = ws_addr
+        .as_base_str()
+        .replacen("kitsune-quic", "kitsune-ws", 1);
+    println!("{}", ProxyUrl::new(&ws_base, ws_addr.digest())?);
 
     let ep_hnd = ep.handle().clone();
     let ep_hnd = &ep_hnd;
@@ -99,6 +99,15 @@ pub struct ProxyConfig {
 
     /// Logic for dynamically fetching a proxy url from the bootstrap service
     pub proxy_from_bootstrap_cb: ProxyFromBootstrapCb,
+
+    /// If Some(limit), refuse to forward more than this many bytes
+    /// per second from any one client.
+    /// Default: None.
+    pub client_bandwidth_limit: Option<u64>,
+
+    /// If Some(addr), serve [`ProxyStats`] as json over http at addr.
+    /// Default: None.
+    pub stats_bind_to: Option<std::net::SocketAddr>,
 }
 
 impl Default for ProxyConfig {
@@ -108,12 +117,15 @@ impl Default for ProxyConfig {
             allow_proxy_fwd: false,
             client_of_remote_proxy: ProxyRemoteType::default(),
             proxy_from_bootstrap_cb: Arc::new(stub_proxy_from_bootstrap_cb),
+            client_bandwidth_limit: None,
+            stats_bind_to: None,
         }
     }
 }
 
 impl ProxyConfig {
     /// into inner contents with default application
+    #[allow(clippy::type_complexity)]
     pub fn split(
         self,
     ) -> KitsuneResult<(
@@ -121,12 +133,16 @@ impl ProxyConfig {
         bool,
         ProxyRemoteType,
         ProxyFromBootstrapCb,
+        Option<u64>,
+        Option<std::net::SocketAddr>,
     )> {
         let ProxyConfig {
             tuning_params,
             allow_proxy_fwd,
             client_of_remote_proxy,
             proxy_from_bootstrap_cb,
+            client_bandwidth_limit,
+            stats_bind_to,
         } = self;
 
         let tuning_params = tuning_params.unwrap_or_default();
@@ -136,10 +152,43 @@ impl ProxyConfig {
             allow_proxy_fwd,
             client_of_remote_proxy,
             proxy_from_bootstrap_cb,
+            client_bandwidth_limit,
+            stats_bind_to,
         ))
     }
 }
 
+/// Usage statistics of a proxy endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProxyStats {
+    /// The number of clients connected to us.
+    pub client_count: usize,
+
+    /// The number of routes to final peers,
+    /// both through proxies and direct.
+    pub route_count: usize,
+
+    /// The bytes we have forwarded for each connected client,
+    /// keyed by the client's cert digest.
+    pub forwarded_bytes: HashMap<String, u64>,
+
+    /// The forward requests we have refused.
+    pub rejected: ProxyRejectStats,
+}
+
+/// Counts of the forward requests a proxy has refused, by reason.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProxyRejectStats {
+    /// Proxy forwarding is disabled on this node.
+    pub fwd_disallowed: u64,
+
+    /// The destination is not connected to us.
+    pub invalid_target: u64,
+
+    /// The client is over its `client_bandwidth_limit`.
+    pub bandwidth_limit: u64,
+}
+
 /// Wrap a tx2 transport pool adapter with proxy logic.
 pub fn tx2_proxy(sub_fact: EpFactory, config: ProxyConfig) -> KitsuneResult<EpFactory> {
     ProxyEpFactory::new(sub_fact, config)
@@ -153,6 +202,32 @@ const DIGEST_BYTES: usize = 32;
 const PROXY_FWD_MSG: u8 = 0x30;
 const PROXY_ROUTE_ERR: u8 = 0xc0;
 
+// hole punching:
+// a client sends the proxy `[PROXY_PUNCH_REQ][dest cert]`,
+// the proxy invites the dest with `[PROXY_PUNCH_REQ][src cert]`,
+// and the dest accepts by sending its own `[PROXY_PUNCH_REQ][src cert]`.
+// Once both have asked, the proxy sends `[PROXY_PUNCH_ADDR][peer cert][peer url]`
+// to each of them, so they can try to connect directly.
+// An empty peer url means the dest is not connected to the proxy.
+const PROXY_PUNCH_REQ: u8 = 0x40;
+const PROXY_PUNCH_ADDR: u8 = 0x41;
+
+// don't ask the proxy to punch to a peer more often than this
+// after a punch to that peer failed.
+const PUNCH_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 5);
+
+// how long a proxy remembers a punch request waiting on the other side.
+const PUNCH_REQ_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+// a hole punch we've asked a proxy for
+struct PunchPending {
+    // the proxy connection we expect the peer's address on
+    proxy: Uniq,
+    started_at: tokio::time::Instant,
+    // outgoing connections waiting on the punch
+    waiters: Vec<tokio::sync::oneshot::Sender<Option<ConHnd>>>,
+}
+
 struct ProxyConHnd {
     uniq: Uniq,
     dir: Tx2ConDir,
@@ -213,17 +288,18 @@ impl AsConHnd for ProxyConHnd {
         self.peer_cert.clone()
     }
 
-    fn write(
+    fn write_lane(
         &self,
         msg_id: MsgId,
         mut data: PoolBuf,
+        lane: Tx2Lane,
         timeout: KitsuneTimeout,
     ) -> BoxFuture<'static, KitsuneResult<()>> {
         data.reserve_front(PROXY_TYPE_BYTES + DIGEST_BYTES + DIGEST_BYTES);
         data.prepend_from_slice(&self.local_cert);
         data.prepend_from_slice(&self.peer_cert);
         data.prepend_from_slice(&[PROXY_FWD_MSG]);
-        self.sub_con.write(msg_id, data, timeout).boxed()
+        self.sub_con.write_lane(msg_id, data, lane, timeout).boxed()
     }
 }
 
@@ -299,9 +375,35 @@ struct ProxyEpInner {
     // these are both INCOMING and OUTGOING
     direct_to_final_peer_con_map: HashMap<Uniq, HashMap<Tx2Cert, ConHnd>>,
 
+    // direct connections established by hole punching,
+    // keyed by the cert of the peer we would otherwise proxy to.
+    // these are !SUB CONS! they should not be returned
+    punched_sub_con_map: HashMap<Tx2Cert, ConHnd>,
+
+    // hole punches to a peer we've asked a proxy for
+    punch_pending: HashMap<Tx2Cert, PunchPending>,
+
+    // as a proxy, when each (src, dest) punch request was made,
+    // addresses are only exchanged once both sides have asked
+    punch_requests: HashMap<(Tx2Cert, Tx2Cert), tokio::time::Instant>,
+
+    // when a hole punch to a peer last failed
+    punch_failures: HashMap<Tx2Cert, tokio::time::Instant>,
+
+    // forwarding done for each connected client
+    client_usage: HashMap<Tx2Cert, ClientUsage>,
+    client_bandwidth_limit: Option<u64>,
+    rejected: ProxyRejectStats,
+
     backoff: Backoff,
 }
 
+struct ClientUsage {
+    forwarded_bytes: u64,
+    window_start: tokio::time::Instant,
+    window_bytes: u64,
+}
+
 impl ProxyEpInner {
     pub fn get_con_hnd(
         &mut self,
@@ -327,6 +429,64 @@ impl ProxyEpInner {
         };
         Ok((did_insert, con))
     }
+
+    /// Find the sub con to forward `len` bytes from `src_cert` to `dest_cert`
+    /// and record the usage, or count why we won't.
+    pub fn route_fwd(
+        &mut self,
+        src_cert: &Tx2Cert,
+        dest_cert: &Tx2Cert,
+        len: u64,
+    ) -> KitsuneResult<ConHnd> {
+        let d_sub_con = match self.digest_to_sub_con_map.get(dest_cert) {
+            Some(d_sub_con) => d_sub_con.clone(),
+            None => {
+                self.rejected.invalid_target += 1;
+                return Err(format!("Invalid Proxy Target: {:?}", dest_cert).into());
+            }
+        };
+
+        let now = tokio::time::Instant::now();
+        let usage = self
+            .client_usage
+            .entry(src_cert.clone())
+            .or_insert_with(|| ClientUsage {
+                forwarded_bytes: 0,
+                window_start: now,
+                window_bytes: 0,
+            });
+        if now.duration_since(usage.window_start) >= std::time::Duration::from_secs(1) {
+            usage.window_start = now;
+            usage.window_bytes = 0;
+        }
+        if let Some(limit) = self.client_bandwidth_limit {
+            if usage.window_bytes + len > limit {
+                self.rejected.bandwidth_limit += 1;
+                return Err("Proxy bandwidth limit exceeded".into());
+            }
+        }
+        usage.window_bytes += len;
+        usage.forwarded_bytes += len;
+
+        Ok(d_sub_con)
+    }
+
+    pub fn stats(&self) -> ProxyStats {
+        ProxyStats {
+            client_count: self.digest_to_sub_con_map.len(),
+            route_count: self
+                .direct_to_final_peer_con_map
+                .values()
+                .map(|m| m.len())
+                .sum(),
+            forwarded_bytes: self
+                .client_usage
+                .iter()
+                .map(|(cert, usage)| (cert.as_str().to_string(), usage.forwarded_bytes))
+                .collect(),
+            rejected: self.rejected.clone(),
+        }
+    }
 }
 
 struct ProxyEpHnd {
@@ -335,6 +495,8 @@ struct ProxyEpHnd {
     logic_hnd: LogicChanHandle<EpEvent>,
     inner: Share<ProxyEpInner>,
     cur_proxy_url: Share<Option<ProxyUrl>>,
+    punch_timeout: Option<std::time::Duration>,
+    stats_addr: Option<std::net::SocketAddr>,
 }
 
 async fn get_con_hnd(
@@ -366,6 +528,9 @@ impl ProxyEpHnd {
         logic_hnd: LogicChanHandle<EpEvent>,
         backoff: Backoff,
         cur_proxy_url: Share<Option<ProxyUrl>>,
+        punch_timeout: Option<std::time::Duration>,
+        client_bandwidth_limit: Option<u64>,
+        stats_addr: Option<std::net::SocketAddr>,
     ) -> KitsuneResult<Arc<ProxyEpHnd>> {
         let local_cert = sub_ep_hnd.local_cert();
         Ok(Arc::new(ProxyEpHnd {
@@ -375,9 +540,18 @@ impl ProxyEpHnd {
             inner: Share::new(ProxyEpInner {
                 digest_to_sub_con_map: HashMap::new(),
                 direct_to_final_peer_con_map: HashMap::new(),
+                punched_sub_con_map: HashMap::new(),
+                punch_pending: HashMap::new(),
+                punch_requests: HashMap::new(),
+                punch_failures: HashMap::new(),
+                client_usage: HashMap::new(),
+                client_bandwidth_limit,
+                rejected: ProxyRejectStats::default(),
                 backoff,
             }),
             cur_proxy_url,
+            punch_timeout,
+            stats_addr,
         }))
     }
 }
@@ -397,6 +571,9 @@ impl AsEpHnd for ProxyEpHnd {
                 "addr": addr?,
                 "proxy_count": i.digest_to_sub_con_map.len(),
                 "proxy_list": proxy_list,
+                "punched_count": i.punched_sub_con_map.len(),
+                "stats": i.stats(),
+                "stats_addr": self.stats_addr,
                 "sub": self.sub_ep_hnd.debug(),
             }))
         }) {
@@ -478,14 +655,139 @@ impl AsEpHnd for ProxyEpHnd {
         let logic_hnd = self.logic_hnd.clone();
         let con_fut = self.sub_ep_hnd.get_connection(base_url, timeout);
         let inner = self.inner.clone();
+        let punch_timeout = self.punch_timeout;
         async move {
-            let sub_con = con_fut.await?;
+            // prefer a direct connection if we've punched one
+            let punched =
+                inner.share_mut(|i, _| Ok(i.punched_sub_con_map.get(&peer_cert).cloned()))?;
+            let sub_con = match punched {
+                Some(sub_con) if !sub_con.is_closed() => sub_con,
+                _ => {
+                    let sub_con = con_fut.await?;
+                    match punch_timeout {
+                        // the base url is a proxy, try to get around it
+                        Some(punch_timeout) if sub_con.peer_cert() != peer_cert => {
+                            hole_punch(&inner, &sub_con, &peer_cert, punch_timeout)
+                                .await
+                                .unwrap_or(sub_con)
+                        }
+                        _ => sub_con,
+                    }
+                }
+            };
             get_con_hnd(&inner, logic_hnd, sub_con, local_cert, peer_cert, true).await
         }
         .boxed()
     }
 }
 
+/// Ask the proxy at the other end of `proxy_sub_con` to help us
+/// connect directly to `peer_cert`. Resolves to the direct connection,
+/// or None if we should keep proxying.
+async fn hole_punch(
+    inner: &Share<ProxyEpInner>,
+    proxy_sub_con: &ConHnd,
+    peer_cert: &Tx2Cert,
+    punch_timeout: std::time::Duration,
+) -> Option<ConHnd> {
+    let (s, r) = tokio::sync::oneshot::channel();
+    let send_req = inner
+        .share_mut(|i, _| {
+            // join a punch already in progress
+            if let Some(pending) = i.punch_pending.get_mut(peer_cert) {
+                if pending.started_at.elapsed() < punch_timeout {
+                    pending.waiters.push(s);
+                    return Ok(Some(false));
+                }
+            }
+            if let Some(failed_at) = i.punch_failures.get(peer_cert) {
+                if failed_at.elapsed() < PUNCH_RETRY_INTERVAL {
+                    return Ok(None);
+                }
+            }
+            i.punch_pending.insert(
+                peer_cert.clone(),
+                PunchPending {
+                    proxy: proxy_sub_con.uniq(),
+                    started_at: tokio::time::Instant::now(),
+                    waiters: vec![s],
+                },
+            );
+            Ok(Some(true))
+        })
+        .ok()??;
+
+    if send_req {
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(peer_cert);
+        data.prepend_from_slice(&[PROXY_PUNCH_REQ]);
+        let t = KitsuneTimeout::new(punch_timeout);
+        if let Err(err) = proxy_sub_con.write(0.into(), data, t).await {
+            tracing::debug!(?err, "failed to request hole punch");
+        }
+    }
+
+    let direct = match tokio::time::timeout(punch_timeout, r).await {
+        Ok(Ok(direct)) => direct,
+        _ => None,
+    };
+    let _ = inner.share_mut(|i, _| {
+        i.punch_pending.remove(peer_cert);
+        if direct.is_none() {
+            i.punch_failures
+                .insert(peer_cert.clone(), tokio::time::Instant::now());
+        }
+        Ok(())
+    });
+    direct
+}
+
+/// We've been told the observed address of a peer we're punching to,
+/// try to connect to it directly.
+async fn punch_connect(
+    hnd: Arc<ProxyEpHnd>,
+    peer_cert: Tx2Cert,
+    peer_url: String,
+    punch_timeout: std::time::Duration,
+) {
+    let direct = if peer_url.is_empty() {
+        None
+    } else {
+        let t = KitsuneTimeout::new(punch_timeout);
+        match hnd.sub_ep_hnd.get_connection(peer_url.into(), t).await {
+            // make sure we reached the node we meant to
+            Ok(sub_con) if sub_con.peer_cert() == peer_cert => Some(sub_con),
+            Ok(_) => {
+                tracing::warn!(?peer_cert, "hole punch reached a different peer");
+                None
+            }
+            Err(err) => {
+                tracing::debug!(?err, ?peer_cert, "hole punch failed");
+                None
+            }
+        }
+    };
+
+    let waiters = hnd
+        .inner
+        .share_mut(|i, _| {
+            if let Some(sub_con) = &direct {
+                tracing::debug!(?peer_cert, "hole punch succeeded");
+                i.punched_sub_con_map
+                    .insert(peer_cert.clone(), sub_con.clone());
+                i.punch_failures.remove(&peer_cert);
+            }
+            Ok(i.punch_pending.remove(&peer_cert))
+        })
+        .ok()
+        .flatten()
+        .map(|pending| pending.waiters)
+        .unwrap_or_default();
+    for waiter in waiters {
+        let _ = waiter.send(direct.clone());
+    }
+}
+
 async fn close_ep(hnd: &Arc<ProxyEpHnd>, logic_hnd: &LogicChanHandle<EpEvent>) {
     let _ = hnd.inner.share_mut(|_, c| {
         *c = true;
@@ -656,14 +958,19 @@ async fn incoming_evt_handle(
                     } else {
                         let dest = if !allow_proxy_fwd {
                             tracing::error!("received fwd request on, but proxy fwd is disallowed");
+                            let _ = hnd.inner.share_mut(|i, _| {
+                                i.rejected.fwd_disallowed += 1;
+                                Ok(())
+                            });
                             Err("proxy fwd disallowed".into())
                         } else {
-                            hnd.inner.share_mut(|i, _| {
-                                Ok(i.digest_to_sub_con_map.get(&dest_cert).cloned())
-                            })
+                            let src_cert = sub_con.peer_cert();
+                            let len = data.len() as u64;
+                            hnd.inner
+                                .share_mut(|i, _| i.route_fwd(&src_cert, &dest_cert, len))
                         };
                         if let Err(e) = match dest {
-                            Ok(Some(d_sub_con)) => {
+                            Ok(d_sub_con) => {
                                 write_to_sub_con(
                                     tuning_params,
                                     &hnd.inner,
@@ -675,9 +982,6 @@ async fn incoming_evt_handle(
                                 )
                                 .await
                             }
-                            Ok(None) => {
-                                Err(format!("Invalid Proxy Target: {:?}", dest_cert).into())
-                            }
                             Err(e) => Err(e),
                         } {
                             tracing::warn!("Proxy Fwd Error: {:?}", e);
@@ -703,6 +1007,71 @@ async fn incoming_evt_handle(
                         }
                     }
                 }
+                PROXY_PUNCH_REQ => {
+                    const PEER_START: usize = PROXY_TYPE_BYTES;
+                    const PEER_END: usize = PEER_START + DIGEST_BYTES;
+                    if data.len() != PEER_END {
+                        tracing::warn!("Invalid hole punch request");
+                        return;
+                    }
+                    let peer_cert: Tx2Cert = data[PEER_START..PEER_END].to_vec().into();
+                    let from_our_proxy = cur_proxy_url
+                        .share_ref(|r| Ok(r.as_ref().map(|u| Tx2Cert::from(u.digest()))))
+                        .ok()
+                        .flatten()
+                        .map(|proxy_cert| proxy_cert == sub_con.peer_cert())
+                        .unwrap_or(false);
+                    if from_our_proxy {
+                        // our proxy is inviting us to punch to a peer
+                        accept_punch_invite(hnd, sub_con, peer_cert).await;
+                    } else {
+                        rendezvous_punch(
+                            tuning_params,
+                            hnd,
+                            logic_hnd,
+                            allow_proxy_fwd,
+                            sub_con,
+                            peer_cert,
+                            cur_proxy_url,
+                        )
+                        .await;
+                    }
+                }
+                PROXY_PUNCH_ADDR => {
+                    const PEER_START: usize = PROXY_TYPE_BYTES;
+                    const PEER_END: usize = PEER_START + DIGEST_BYTES;
+                    if data.len() < PEER_END {
+                        tracing::warn!("Invalid hole punch address");
+                        return;
+                    }
+                    let punch_timeout = match hnd.punch_timeout {
+                        Some(punch_timeout) => punch_timeout,
+                        None => return,
+                    };
+                    let peer_cert: Tx2Cert = data[PEER_START..PEER_END].to_vec().into();
+                    // only trust addresses from the proxy we asked
+                    let expected = hnd
+                        .inner
+                        .share_ref(|i| {
+                            Ok(i.punch_pending.get(&peer_cert).map(|p| {
+                                p.proxy == sub_con.uniq() && p.started_at.elapsed() < punch_timeout
+                            }))
+                        })
+                        .ok()
+                        .flatten()
+                        .unwrap_or(false);
+                    if !expected {
+                        tracing::warn!(?peer_cert, "Unsolicited hole punch address");
+                        return;
+                    }
+                    let peer_url = String::from_utf8_lossy(&data[PEER_END..]).to_string();
+                    tokio::task::spawn(punch_connect(
+                        hnd.clone(),
+                        peer_cert,
+                        peer_url,
+                        punch_timeout,
+                    ));
+                }
                 PROXY_ROUTE_ERR => {
                     const SRC_START: usize = PROXY_TYPE_BYTES;
                     const SRC_END: usize = SRC_START + DIGEST_BYTES;
@@ -755,6 +1124,155 @@ async fn incoming_evt_handle(
     }
 }
 
+/// Our proxy says `peer_cert` wants to punch to us,
+/// agree to it if we do hole punching at all.
+async fn accept_punch_invite(hnd: &Arc<ProxyEpHnd>, proxy_sub_con: ConHnd, peer_cert: Tx2Cert) {
+    let punch_timeout = match hnd.punch_timeout {
+        Some(punch_timeout) => punch_timeout,
+        None => return,
+    };
+    let send_req = hnd
+        .inner
+        .share_mut(|i, _| {
+            if let Some(pending) = i.punch_pending.get(&peer_cert) {
+                if pending.started_at.elapsed() < punch_timeout {
+                    // we're already punching to them
+                    return Ok(false);
+                }
+            }
+            i.punch_pending.insert(
+                peer_cert.clone(),
+                PunchPending {
+                    proxy: proxy_sub_con.uniq(),
+                    started_at: tokio::time::Instant::now(),
+                    waiters: Vec::new(),
+                },
+            );
+            Ok(true)
+        })
+        .unwrap_or(false);
+    if !send_req {
+        return;
+    }
+    let mut data = PoolBuf::new();
+    data.extend_from_slice(&peer_cert);
+    data.prepend_from_slice(&[PROXY_PUNCH_REQ]);
+    let t = KitsuneTimeout::new(punch_timeout);
+    if let Err(err) = proxy_sub_con.write(0.into(), data, t).await {
+        tracing::debug!(?err, "failed to accept hole punch");
+    }
+}
+
+/// Acting as a proxy, `sub_con` asked to punch to `dest_cert`.
+/// Invite the dest, or if it has already asked to punch to `sub_con`,
+/// send each of them the address we see the other connecting from.
+async fn rendezvous_punch(
+    tuning_params: &KitsuneP2pTuningParams,
+    hnd: &Arc<ProxyEpHnd>,
+    logic_hnd: &LogicChanHandle<EpEvent>,
+    allow_proxy_fwd: bool,
+    sub_con: ConHnd,
+    dest_cert: Tx2Cert,
+    cur_proxy_url: &Share<Option<ProxyUrl>>,
+) {
+    let src_cert = sub_con.peer_cert();
+    let dest = if allow_proxy_fwd {
+        hnd.inner
+            .share_mut(|i, _| {
+                let d_sub_con = match i.digest_to_sub_con_map.get(&dest_cert) {
+                    Some(d_sub_con) => d_sub_con.clone(),
+                    None => return Ok(None),
+                };
+                i.punch_requests
+                    .retain(|_, at| at.elapsed() < PUNCH_REQ_TTL);
+                let both_asked = i
+                    .punch_requests
+                    .remove(&(dest_cert.clone(), src_cert.clone()))
+                    .is_some();
+                if !both_asked {
+                    i.punch_requests.insert(
+                        (src_cert.clone(), dest_cert.clone()),
+                        tokio::time::Instant::now(),
+                    );
+                }
+                Ok(Some((d_sub_con, both_asked)))
+            })
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+
+    let (d_sub_con, data, dest_url) = match dest {
+        Some((d_sub_con, false)) => {
+            let mut data = PoolBuf::new();
+            data.extend_from_slice(&src_cert);
+            data.prepend_from_slice(&[PROXY_PUNCH_REQ]);
+            (d_sub_con, data, None)
+        }
+        Some((d_sub_con, true)) => {
+            // the addresses we see these nodes connecting from
+            // are the ones their NATs have mapped for them
+            match (sub_con.peer_addr(), d_sub_con.peer_addr()) {
+                (Ok(src_url), Ok(dest_url)) => {
+                    let data = punch_addr(&src_cert, src_url.as_str());
+                    (d_sub_con, data, Some(dest_url.as_str().to_string()))
+                }
+                _ => {
+                    let data = punch_addr(&src_cert, "");
+                    (d_sub_con, data, Some(String::new()))
+                }
+            }
+        }
+        None => {
+            let data = punch_addr(&dest_cert, "");
+            let _ = write_to_sub_con(
+                tuning_params,
+                &hnd.inner,
+                logic_hnd,
+                sub_con,
+                0.into(),
+                data,
+                cur_proxy_url,
+            )
+            .await;
+            return;
+        }
+    };
+
+    let _ = write_to_sub_con(
+        tuning_params,
+        &hnd.inner,
+        logic_hnd,
+        d_sub_con,
+        0.into(),
+        data,
+        cur_proxy_url,
+    )
+    .await;
+    if let Some(dest_url) = dest_url {
+        let data = punch_addr(&dest_cert, &dest_url);
+        let _ = write_to_sub_con(
+            tuning_params,
+            &hnd.inner,
+            logic_hnd,
+            sub_con,
+            0.into(),
+            data,
+            cur_proxy_url,
+        )
+        .await;
+    }
+}
+
+fn punch_addr(peer_cert: &Tx2Cert, peer_url: &str) -> PoolBuf {
+    let mut data = PoolBuf::new();
+    data.extend_from_slice(peer_url.as_bytes());
+    data.prepend_from_slice(peer_cert);
+    data.prepend_from_slice(&[PROXY_PUNCH_ADDR]);
+    data
+}
+
 async fn write_to_sub_con(
     tuning_params: &KitsuneP2pTuningParams,
     inner: &Share<ProxyEpInner>,
@@ -802,8 +1320,12 @@ async fn close_connection_inner(
         // if this is an INCOMING connection, remove it from our proxy list
         if let Tx2ConDir::Incoming = peer_dir {
             i.digest_to_sub_con_map.remove(&peer_cert);
+            i.client_usage.remove(&peer_cert);
         }
 
+        // go back to proxying if a punched connection closes
+        i.punched_sub_con_map.retain(|_, c| c.uniq() != direct_peer);
+
         // remove all out cons associated with this exact connection
         Ok((
             i.backoff.clone(),
@@ -845,6 +1367,34 @@ async fn close_connection_inner(
     }
 }
 
+/// Serve the [`ProxyStats`] of an endpoint as json to any http request.
+async fn serve_stats(incoming: hyper::server::conn::AddrIncoming, hnd: Arc<ProxyEpHnd>) {
+    let server =
+        hyper::Server::builder(incoming).serve(hyper::service::make_service_fn(move |_| {
+            let hnd = hnd.clone();
+            async move {
+                Ok::<_, std::convert::Infallible>(hyper::service::service_fn(move |_req| {
+                    let stats = hnd.inner.share_ref(|i| Ok(i.stats()));
+                    async move {
+                        match stats.and_then(|s| {
+                            serde_json::to_string_pretty(&s).map_err(KitsuneError::other)
+                        }) {
+                            Ok(body) => hyper::Response::builder()
+                                .header(hyper::header::CONTENT_TYPE, "application/json")
+                                .body(hyper::Body::from(body)),
+                            Err(_) => hyper::Response::builder()
+                                .status(hyper::StatusCode::SERVICE_UNAVAILABLE)
+                                .body(hyper::Body::empty()),
+                        }
+                    }
+                }))
+            }
+        }));
+    if let Err(err) = server.await {
+        tracing::error!(?err, "proxy stats server error");
+    }
+}
+
 struct ProxyEp {
     logic_chan: LogicChan<EpEvent>,
     hnd: EpHnd,
@@ -857,11 +1407,13 @@ impl ProxyEp {
         allow_proxy_fwd: bool,
         client_of_remote_proxy: ProxyRemoteType,
         proxy_from_bootstrap_cb: ProxyFromBootstrapCb,
+        client_bandwidth_limit: Option<u64>,
+        stats_bind_to: Option<std::net::SocketAddr>,
     ) -> KitsuneResult<Ep> {
         // this isn't something that needs to be configurable,
         // because it's entirely dependent on the code written here
-        // we only ever capture two logic closures
-        // so technically, it only really would need to be 2.
+        // we only ever capture three logic closures
+        // so technically, it only really would need to be 3.
         const LOGIC_CHAN_LIMIT: usize = 32;
 
         let cur_proxy_url = Share::new(None);
@@ -871,11 +1423,26 @@ impl ProxyEp {
 
         let backoff = Backoff::new(10, 5000);
 
+        let punch_timeout = match tuning_params.tx2_hole_punch_timeout_ms {
+            0 => None,
+            ms => Some(std::time::Duration::from_millis(ms as u64)),
+        };
+
+        let stats_incoming = match stats_bind_to {
+            Some(addr) => {
+                Some(hyper::server::conn::AddrIncoming::bind(&addr).map_err(KitsuneError::other)?)
+            }
+            None => None,
+        };
+
         let hnd = ProxyEpHnd::new(
             sub_ep.handle().clone(),
             logic_hnd.clone(),
             backoff.clone(),
             cur_proxy_url.clone(),
+            punch_timeout,
+            client_bandwidth_limit,
+            stats_incoming.as_ref().map(|i| i.local_addr()),
         )?;
 
         let logic = incoming_evt_logic(
@@ -890,6 +1457,12 @@ impl ProxyEp {
         let l_hnd = logic_chan.handle().clone();
         l_hnd.capture_logic(logic).await?;
 
+        if let Some(stats_incoming) = stats_incoming {
+            l_hnd
+                .capture_logic(serve_stats(stats_incoming, hnd.clone()))
+                .await?;
+        }
+
         {
             // try to get our proxy addy inline, but fail silently
             if let Some(proxy_url) = client_of_remote_proxy
@@ -961,18 +1534,28 @@ struct ProxyEpFactory {
     allow_proxy_fwd: bool,
     client_of_remote_proxy: ProxyRemoteType,
     proxy_from_bootstrap_cb: ProxyFromBootstrapCb,
+    client_bandwidth_limit: Option<u64>,
+    stats_bind_to: Option<std::net::SocketAddr>,
     sub_fact: EpFactory,
 }
 
 impl ProxyEpFactory {
     pub fn new(sub_fact: EpFactory, config: ProxyConfig) -> KitsuneResult<EpFactory> {
-        let (tuning_params, allow_proxy_fwd, client_of_remote_proxy, proxy_from_bootstrap_cb) =
-            config.split()?;
+        let (
+            tuning_params,
+            allow_proxy_fwd,
+            client_of_remote_proxy,
+            proxy_from_bootstrap_cb,
+            client_bandwidth_limit,
+            stats_bind_to,
+        ) = config.split()?;
         let fact: EpFactory = Arc::new(ProxyEpFactory {
             tuning_params,
             allow_proxy_fwd,
             client_of_remote_proxy,
             proxy_from_bootstrap_cb,
+            client_bandwidth_limit,
+            stats_bind_to,
             sub_fact,
         });
         Ok(fact)
@@ -990,6 +1573,8 @@ impl AsEpFactory for ProxyEpFactory {
         let allow_proxy_fwd = self.allow_proxy_fwd;
         let client_of_remote_proxy = self.client_of_remote_proxy.clone();
         let proxy_from_bootstrap_cb = self.proxy_from_bootstrap_cb.clone();
+        let client_bandwidth_limit = self.client_bandwidth_limit;
+        let stats_bind_to = self.stats_bind_to;
         async move {
             let sub_ep = fut.await?;
             ProxyEp::new(
@@ -998,6 +1583,8 @@ impl AsEpFactory for ProxyEpFactory {
                 allow_proxy_fwd,
                 client_of_remote_proxy,
                 proxy_from_bootstrap_cb,
+                client_bandwidth_limit,
+                stats_bind_to,
             )
             .await
         }
@@ -1011,16 +1598,34 @@ mod tests {
     use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 
     async fn build_node(
+        s_done: Option<tokio::sync::oneshot::Sender<()>>,
+        expect_err: bool,
+        hole_punch: bool,
+    ) -> (tokio::task::JoinHandle<KitsuneResult<()>>, TxUrl, EpHnd) {
+        let mut conf = super::ProxyConfig::default();
+        conf.allow_proxy_fwd = true;
+        if hole_punch {
+            conf.tuning_params = Some(hole_punch_tuning_params());
+        }
+        build_node_with_config(s_done, expect_err, conf).await
+    }
+
+    fn hole_punch_tuning_params() -> KitsuneP2pTuningParams {
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.tx2_hole_punch_timeout_ms = 5000;
+        Arc::new(tuning_params)
+    }
+
+    async fn build_node_with_config(
         mut s_done: Option<tokio::sync::oneshot::Sender<()>>,
         expect_err: bool,
+        conf: ProxyConfig,
     ) -> (tokio::task::JoinHandle<KitsuneResult<()>>, TxUrl, EpHnd) {
         let t = KitsuneTimeout::from_millis(5000);
 
         let f = tx2_mem_adapter(MemConfig::default()).await.unwrap();
         let f = tx2_pool_promote(f, Default::default());
-
-        let mut conf = super::ProxyConfig::default();
-        conf.allow_proxy_fwd = true;
         let f = tx2_proxy(f, conf).unwrap();
 
         let mut ep = f.bind("none:".into(), t).await.unwrap();
@@ -1084,7 +1689,7 @@ mod tests {
         let t = KitsuneTimeout::from_millis(5000);
         let mut all_tasks = Vec::new();
 
-        let (p_join, p_addr, p_ep) = build_node(None, true).await;
+        let (p_join, p_addr, p_ep) = build_node(None, true, false).await;
         all_tasks.push(p_join);
 
         let fake_tgt: Tx2Cert = vec![0xdb; 32].into();
@@ -1097,7 +1702,7 @@ mod tests {
         println!("Fake Tgt: {:?}", fake_tgt);
 
         let (s_done, r_done) = tokio::sync::oneshot::channel();
-        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), true).await;
+        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), true, false).await;
 
         let mut data = PoolBuf::new();
         data.extend_from_slice(b"hello");
@@ -1119,12 +1724,12 @@ mod tests {
 
         let mut all_tasks = Vec::new();
 
-        let (p_join, p_addr, p_ep) = build_node(None, false).await;
+        let (p_join, p_addr, p_ep) = build_node(None, false, false).await;
         all_tasks.push(p_join);
         //println!("PROXY ADDR = {}", p_addr);
         //println!("PROXY: {:?}", p_ep.local_cert().unwrap());
 
-        let (t_join, t_addr, t_ep) = build_node(None, false).await;
+        let (t_join, t_addr, t_ep) = build_node(None, false, false).await;
         all_tasks.push(t_join);
 
         //println!("TGT ADDR = {}", t_addr);
@@ -1141,7 +1746,7 @@ mod tests {
         let mut all_futs = Vec::new();
         for _ in 0..COUNT {
             let (s_done, r_done) = tokio::sync::oneshot::channel();
-            let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false).await;
+            let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false, false).await;
             //println!("N: {:?}", n_ep.local_cert().unwrap());
 
             let t_addr_proxy = t_addr_proxy.clone();
@@ -1165,4 +1770,162 @@ mod tests {
 
         futures::future::try_join_all(all_tasks).await.unwrap();
     }
+
+    /// a node with hole punching enabled that uses `proxy` as its proxy
+    async fn build_punch_target(
+        proxy: &TxUrl,
+        hole_punch: bool,
+    ) -> (tokio::task::JoinHandle<KitsuneResult<()>>, TxUrl, EpHnd) {
+        let mut conf = super::ProxyConfig::default();
+        conf.allow_proxy_fwd = true;
+        conf.client_of_remote_proxy = ProxyRemoteType::Specific(proxy.clone());
+        if hole_punch {
+            conf.tuning_params = Some(hole_punch_tuning_params());
+        }
+        build_node_with_config(None, false, conf).await
+    }
+
+    fn base_host(url: &TxUrl) -> Option<String> {
+        ProxyUrl::from(url.as_str())
+            .as_base()
+            .host_str()
+            .map(str::to_string)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_hole_punch() {
+        observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let (p_join, p_addr, p_ep) = build_node(None, false, false).await;
+        let (t_join, t_addr_proxy, t_ep) = build_punch_target(&p_addr, true).await;
+
+        // establish proxy connection
+        let _ = t_ep.get_connection(p_addr.clone(), t).await.unwrap();
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false, true).await;
+
+        // the connection should bypass the proxy
+        let con = n_ep.get_connection(t_addr_proxy, t).await.unwrap();
+        assert_ne!(base_host(&con.peer_addr().unwrap()), base_host(&p_addr));
+        assert_eq!(con.peer_cert(), t_ep.local_cert());
+
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        con.write(0.into(), data, t).await.unwrap();
+        r_done.await.unwrap();
+
+        assert_eq!(n_ep.debug()["punched_count"], 1);
+
+        n_ep.close(0, "").await;
+        n_join.await.unwrap().unwrap();
+        p_ep.close(0, "").await;
+        t_ep.close(0, "").await;
+        p_join.await.unwrap().unwrap();
+        t_join.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_hole_punch_needs_both_sides() {
+        observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let (p_join, p_addr, p_ep) = build_node(None, false, false).await;
+        // the target doesn't punch, so the proxy must not reveal its address
+        let (t_join, t_addr_proxy, t_ep) = build_punch_target(&p_addr, false).await;
+        let _ = t_ep.get_connection(p_addr.clone(), t).await.unwrap();
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false, true).await;
+
+        // falls back to proxying once the punch times out
+        let con = n_ep
+            .get_connection(t_addr_proxy, KitsuneTimeout::from_millis(10000))
+            .await
+            .unwrap();
+        assert_eq!(base_host(&con.peer_addr().unwrap()), base_host(&p_addr));
+
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        con.write(0.into(), data, t).await.unwrap();
+        r_done.await.unwrap();
+
+        assert_eq!(n_ep.debug()["punched_count"], 0);
+
+        n_ep.close(0, "").await;
+        n_join.await.unwrap().unwrap();
+        p_ep.close(0, "").await;
+        t_ep.close(0, "").await;
+        p_join.await.unwrap().unwrap();
+        t_join.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_stats() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let mut conf = super::ProxyConfig::default();
+        conf.allow_proxy_fwd = true;
+        conf.client_bandwidth_limit = Some(100);
+        conf.stats_bind_to = Some(([127, 0, 0, 1], 0).into());
+        let (p_join, p_addr, p_ep) = build_node_with_config(None, false, conf).await;
+
+        let (t_join, t_addr, t_ep) = build_node(None, false, false).await;
+        let _ = t_ep.get_connection(p_addr.clone(), t).await.unwrap();
+        let t_addr_proxy = proxify_addr(&p_addr, &t_addr);
+
+        // a small message is forwarded
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false, false).await;
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        n_ep.write(t_addr_proxy.clone(), 0.into(), data, t)
+            .await
+            .unwrap();
+        r_done.await.unwrap();
+
+        // a message over the bandwidth limit is refused
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+        let (b_join, _b_addr, b_ep) = build_node(Some(s_done), true, false).await;
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(&[0; 100]);
+        b_ep.write(t_addr_proxy, 0.into(), data, t).await.unwrap();
+        r_done.await.unwrap();
+
+        let stats_addr: std::net::SocketAddr =
+            serde_json::from_value(p_ep.debug()["stats_addr"].clone()).unwrap();
+        let mut stream = tokio::net::TcpStream::connect(stats_addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: stats\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut res = String::new();
+        stream.read_to_string(&mut res).await.unwrap();
+        let (head, body) = res.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200"));
+        let stats: ProxyStats = serde_json::from_str(body).unwrap();
+
+        assert_eq!(stats.client_count, 3);
+        // the message plus the proxy header
+        let n_cert = n_ep.local_cert();
+        assert_eq!(stats.forwarded_bytes[n_cert.as_str()], 5 + 65);
+        assert_eq!(stats.forwarded_bytes[b_ep.local_cert().as_str()], 0);
+        assert_eq!(stats.rejected.bandwidth_limit, 1);
+        assert_eq!(stats.rejected.invalid_target, 0);
+
+        n_ep.close(0, "").await;
+        b_ep.close(0, "").await;
+        p_ep.close(0, "").await;
+        t_ep.close(0, "").await;
+        futures::future::try_join_all(vec![n_join, b_join, p_join, t_join])
+            .await
+            .unwrap();
+    }
 }
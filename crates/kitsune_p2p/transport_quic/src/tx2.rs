@@ -3,7 +3,6 @@
 
 use futures::future::{BoxFuture, FutureExt};
 use futures::stream::{BoxStream, StreamExt};
-use kitsune_p2p_types::config::*;
 use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
 use kitsune_p2p_types::tls::*;
 use kitsune_p2p_types::tx2::tx2_adapter::*;
@@ -13,33 +12,7 @@ use kitsune_p2p_types::*;
 use std::sync::Arc;
 
 /// Configuration for QuicBackendAdapt
-#[non_exhaustive]
-#[derive(Default)]
-pub struct QuicConfig {
-    /// Tls config
-    /// Default: None = ephemeral.
-    pub tls: Option<TlsConfig>,
-
-    /// Tuning Params
-    /// Default: None = default.
-    pub tuning_params: Option<KitsuneP2pTuningParams>,
-}
-
-impl QuicConfig {
-    /// into inner contents with default application
-    pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
-        let QuicConfig { tls, tuning_params } = self;
-
-        let tls = match tls {
-            None => TlsConfig::new_ephemeral().await?,
-            Some(tls) => tls,
-        };
-
-        let tuning_params = tuning_params.unwrap_or_default();
-
-        Ok((tls, tuning_params))
-    }
-}
+pub type QuicConfig = TlsBackendConfig;
 
 /// Quic endpoint bind adapter for kitsune tx2
 pub async fn tx2_quic_adapter(config: QuicConfig) -> KitsuneResult<AdapterFactory> {
@@ -98,16 +71,6 @@ struct QuicConAdaptInner {
 
 struct QuicConAdapt(Share<QuicConAdaptInner>, Uniq, Tx2Cert, Tx2ConDir);
 
-pub(crate) fn blake2b_32(data: &[u8]) -> Vec<u8> {
-    blake2b_simd::Params::new()
-        .hash_length(32)
-        .to_state()
-        .update(data)
-        .finalize()
-        .as_bytes()
-        .to_vec()
-}
-
 impl QuicConAdapt {
     pub fn new(con: quinn::Connection, dir: Tx2ConDir) -> KitsuneResult<Self> {
         let peer_cert: Tx2Cert = match con.peer_identity() {
@@ -340,7 +303,7 @@ impl EndpointAdapt for QuicEndpointAdapt {
         timeout
             .mix(async move {
                 let (ep, local_cert) = maybe_ep?;
-                let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME)
+                let addr = url_to_addr(url.as_url2(), crate::SCHEME)
                     .await
                     .map_err(KitsuneError::other)?;
                 let con = ep.connect(addr, "stub.stub").map_err(KitsuneError::other);
@@ -437,7 +400,7 @@ impl BindAdapt for QuicBackendAdapt {
         let quic_cli = self.quic_cli.clone();
         timeout
             .mix(async move {
-                let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME)
+                let addr = url_to_addr(url.as_url2(), crate::SCHEME)
                     .await
                     .map_err(KitsuneError::other)?;
 
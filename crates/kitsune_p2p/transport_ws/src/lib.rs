@@ -0,0 +1,17 @@
+#![deny(missing_docs)]
+//! Websocket transport module for kitsune-p2p
+//!
+//! For nodes on networks that block UDP, and so cannot use QUIC.
+//! Each connection is a websocket over TLS over TCP. The TLS layer uses
+//! the same certificates as the QUIC transport, so peers are identified
+//! by the same cert digests.
+
+/// Re-exported dependencies.
+pub mod dependencies {
+    pub use ::kitsune_p2p_types;
+    pub use ::tokio_tungstenite;
+}
+
+const SCHEME: &str = "kitsune-ws";
+
+pub mod tx2;
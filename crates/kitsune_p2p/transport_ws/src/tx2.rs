@@ -0,0 +1,599 @@
+#![allow(clippy::new_ret_no_self)]
+//! kitsune tx2 websocket transport backend
+//!
+//! A websocket carries a single ordered stream of messages, so each
+//! connection has a single incoming channel. Every outgoing channel
+//! sends whole frames (msg_id + data) as binary websocket messages.
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::sink::SinkExt;
+use futures::stream::{BoxStream, SplitSink, SplitStream, StreamExt};
+use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
+use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_adapter::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::tx2::*;
+use kitsune_p2p_types::*;
+use std::convert::TryFrom;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// Configuration for WsBackendAdapt
+pub type WsConfig = TlsBackendConfig;
+
+/// Websocket endpoint bind adapter for kitsune tx2
+pub async fn tx2_ws_adapter(config: WsConfig) -> KitsuneResult<AdapterFactory> {
+    WsBackendAdapt::new(config).await
+}
+
+// -- private -- //
+
+/// Tls ALPN identifier for kitsune websocket handshaking
+const ALPN_KITSUNE_WS_0: &[u8] = b"kitsune-ws/0";
+
+/// The size of the msg_id prefix of each binary message.
+const MSG_ID_BYTES: usize = 8;
+
+type WsStream = WebSocketStream<TlsStream<TcpStream>>;
+
+struct WsInChan {
+    con: Share<WsConAdaptInner>,
+    stream: SplitStream<WsStream>,
+}
+
+impl AsFramedReader for WsInChan {
+    fn read(&mut self, timeout: KitsuneTimeout) -> BoxFuture<'_, KitsuneResult<(MsgId, PoolBuf)>> {
+        async move {
+            loop {
+                let msg = match timeout.mix(async { Ok(self.stream.next().await) }).await? {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(err)) => {
+                        self.con.close();
+                        return Err(KitsuneError::other(err));
+                    }
+                    None => {
+                        self.con.close();
+                        return Err(KitsuneErrorKind::Closed.into());
+                    }
+                };
+                match msg {
+                    Message::Binary(data) if data.len() >= MSG_ID_BYTES => {
+                        let mut msg_id = [0; MSG_ID_BYTES];
+                        msg_id.copy_from_slice(&data[..MSG_ID_BYTES]);
+                        let mut buf = PoolBuf::new();
+                        buf.extend_from_slice(&data[MSG_ID_BYTES..]);
+                        return Ok((u64::from_le_bytes(msg_id).into(), buf));
+                    }
+                    // pings are answered by tungstenite
+                    Message::Ping(_) | Message::Pong(_) => (),
+                    Message::Close(_) => {
+                        self.con.close();
+                        return Err(KitsuneErrorKind::Closed.into());
+                    }
+                    _ => return Err("invalid websocket message".into()),
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+struct WsOutChan(Share<WsConAdaptInner>);
+
+impl AsFramedWriter for WsOutChan {
+    fn write(
+        &mut self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'_, KitsuneResult<()>> {
+        async move {
+            let sink = self.0.share_mut(|i, _| Ok(i.sink.clone()))?;
+            let mut msg = Vec::with_capacity(MSG_ID_BYTES + data.len());
+            msg.extend_from_slice(&msg_id.inner().to_le_bytes());
+            msg.extend_from_slice(&data);
+            let r = timeout
+                .mix(async move {
+                    sink.lock()
+                        .await
+                        .send(Message::Binary(msg))
+                        .await
+                        .map_err(KitsuneError::other)
+                })
+                .await;
+            if r.is_err() {
+                self.0.close();
+            }
+            r
+        }
+        .boxed()
+    }
+}
+
+struct WsInChanRecvAdapt(BoxStream<'static, InChanFut>);
+
+impl WsInChanRecvAdapt {
+    pub fn new(in_chan: WsInChan) -> Self {
+        let in_chan: InChan = Box::new(in_chan);
+        // The one channel lasts as long as the connection.
+        let in_chan: InChanFut = async move { Ok(in_chan) }.boxed();
+        Self(futures::stream::iter(Some(in_chan)).boxed())
+    }
+}
+
+impl futures::stream::Stream for WsInChanRecvAdapt {
+    type Item = InChanFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl InChanRecvAdapt for WsInChanRecvAdapt {}
+
+struct WsConAdaptInner {
+    peer_cert: Tx2Cert,
+    peer_addr: SocketAddr,
+    sink: Arc<tokio::sync::Mutex<SplitSink<WsStream, Message>>>,
+}
+
+struct WsConAdapt(Share<WsConAdaptInner>, Uniq, Tx2Cert, Tx2ConDir);
+
+impl ConAdapt for WsConAdapt {
+    fn uniq(&self) -> Uniq {
+        self.1
+    }
+
+    fn dir(&self) -> Tx2ConDir {
+        self.3
+    }
+
+    fn peer_addr(&self) -> KitsuneResult<TxUrl> {
+        let addr = self.0.share_mut(|i, _| Ok(i.peer_addr))?;
+
+        use kitsune_p2p_types::dependencies::url2;
+        let url = url2::url2!("{}://{}", crate::SCHEME, addr);
+
+        Ok(url.into())
+    }
+
+    fn peer_cert(&self) -> Tx2Cert {
+        self.2.clone()
+    }
+
+    fn out_chan(&self, _timeout: KitsuneTimeout) -> OutChanFut {
+        let con = self.0.clone();
+        async move {
+            if con.is_closed() {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            let out: OutChan = Box::new(WsOutChan(con));
+            Ok(out)
+        }
+        .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        let sink = self.0.share_mut(|i, c| {
+            tracing::info!(
+                peer_cert=?i.peer_cert,
+                %code,
+                %reason,
+                "close connection (ws)",
+            );
+            *c = true;
+            Ok(i.sink.clone())
+        });
+        async move {
+            if let Ok(sink) = sink {
+                let _ = sink.lock().await.close().await;
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Run the websocket handshake over a new TLS connection.
+async fn connecting(
+    tls: TlsStream<TcpStream>,
+    local_cert: Tx2Cert,
+    dir: Tx2ConDir,
+) -> KitsuneResult<Con> {
+    let (tcp, session) = tls.get_ref();
+    let peer_addr = tcp.peer_addr().map_err(KitsuneError::other)?;
+    let peer_cert: Tx2Cert = match session.peer_certificates().and_then(|c| c.first()) {
+        None => return Err("invalid peer certificate (none)".into()),
+        Some(cert) => blake2b_32(cert.as_ref()).into(),
+    };
+    let ws = match dir {
+        Tx2ConDir::Outgoing => {
+            tokio_tungstenite::client_async("ws://stub.stub/", tls)
+                .await
+                .map_err(KitsuneError::other)?
+                .0
+        }
+        Tx2ConDir::Incoming => tokio_tungstenite::accept_async(tls)
+            .await
+            .map_err(KitsuneError::other)?,
+    };
+    let (sink, stream) = ws.split();
+
+    let inner = Share::new(WsConAdaptInner {
+        peer_cert: peer_cert.clone(),
+        peer_addr,
+        sink: Arc::new(tokio::sync::Mutex::new(sink)),
+    });
+    let chan_recv: Box<dyn InChanRecvAdapt> = Box::new(WsInChanRecvAdapt::new(WsInChan {
+        con: inner.clone(),
+        stream,
+    }));
+    let con: Arc<dyn ConAdapt> = Arc::new(WsConAdapt(inner, Uniq::default(), peer_cert, dir));
+
+    let peer_cert = con.peer_cert();
+    let url = con.peer_addr()?;
+    match dir {
+        Tx2ConDir::Outgoing => {
+            tracing::info!(?local_cert, ?peer_cert, %url, "established outgoing connection (ws)");
+        }
+        Tx2ConDir::Incoming => {
+            tracing::info!(?local_cert, ?peer_cert, %url, "established incoming connection (ws)");
+        }
+    }
+
+    Ok((con, chan_recv))
+}
+
+struct WsConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl WsConRecvAdapt {
+    pub fn new(
+        listener: TcpListener,
+        tls_srv: Arc<rustls::ServerConfig>,
+        local_cert: Tx2Cert,
+        closed: Arc<tokio::sync::Notify>,
+        ep: Arc<dyn EndpointAdapt>,
+    ) -> Self {
+        struct OnDrop(Arc<dyn EndpointAdapt>);
+
+        impl Drop for OnDrop {
+            fn drop(&mut self) {
+                let f = self.0.close(500, "listener closed");
+                tokio::task::spawn(async move {
+                    let _ = f.await;
+                });
+            }
+        }
+
+        let on_drop = OnDrop(ep);
+
+        Self(
+            futures::stream::unfold((listener, on_drop), move |(listener, on_drop)| {
+                let tls_srv = tls_srv.clone();
+                let local_cert = local_cert.clone();
+                let closed = closed.clone();
+                async move {
+                    let tcp = loop {
+                        tokio::select! {
+                            _ = closed.notified() => return None,
+                            r = listener.accept() => match r {
+                                Ok((tcp, _)) => break tcp,
+                                Err(err) => {
+                                    tracing::warn!(?err, "failed to accept connection (ws)");
+                                }
+                            },
+                        }
+                    };
+                    let con = async move {
+                        let tls = tokio_rustls::TlsAcceptor::from(tls_srv)
+                            .accept(tcp)
+                            .await
+                            .map_err(KitsuneError::other)?;
+                        connecting(tls.into(), local_cert, Tx2ConDir::Incoming).await
+                    }
+                    .boxed();
+                    Some((con, (listener, on_drop)))
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for WsConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for WsConRecvAdapt {}
+
+struct WsEndpointAdaptInner {
+    local_addr: SocketAddr,
+    local_cert: Tx2Cert,
+    tls_cli: Arc<rustls::ClientConfig>,
+    closed: Arc<tokio::sync::Notify>,
+}
+
+struct WsEndpointAdapt(Share<WsEndpointAdaptInner>, Uniq, Tx2Cert);
+
+impl WsEndpointAdapt {
+    pub fn new(
+        local_addr: SocketAddr,
+        local_cert: Tx2Cert,
+        tls_cli: Arc<rustls::ClientConfig>,
+        closed: Arc<tokio::sync::Notify>,
+    ) -> Self {
+        Self(
+            Share::new(WsEndpointAdaptInner {
+                local_addr,
+                local_cert: local_cert.clone(),
+                tls_cli,
+                closed,
+            }),
+            Uniq::default(),
+            local_cert,
+        )
+    }
+}
+
+impl EndpointAdapt for WsEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        match self.local_addr() {
+            Ok(addr) => serde_json::json!({
+                "type": "tx2_ws",
+                "state": "open",
+                "addr": addr,
+            }),
+            Err(_) => serde_json::json!({
+                "type": "tx2_ws",
+                "state": "closed",
+            }),
+        }
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.1
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        let addr = self.0.share_mut(|i, _| Ok(i.local_addr))?;
+
+        use kitsune_p2p_types::dependencies::url2;
+        let mut url = url2::url2!("{}://{}", crate::SCHEME, addr);
+
+        if let Some(host) = url.host_str() {
+            if host == "0.0.0.0" {
+                for iface in if_addrs::get_if_addrs().map_err(KitsuneError::other)? {
+                    // super naive - just picking the first v4 that is not 127.0.0.1
+                    let addr = iface.addr.ip();
+                    if let std::net::IpAddr::V4(addr) = addr {
+                        if addr != std::net::Ipv4Addr::from([127, 0, 0, 1]) {
+                            url.set_host(Some(&iface.addr.ip().to_string())).unwrap();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(url.into())
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.2.clone()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        let maybe_ep = self
+            .0
+            .share_mut(|i, _| Ok((i.tls_cli.clone(), i.local_cert.clone())));
+        timeout
+            .mix(async move {
+                let (tls_cli, local_cert) = maybe_ep?;
+                let addr = url_to_addr(url.as_url2(), crate::SCHEME)
+                    .await
+                    .map_err(KitsuneError::other)?;
+                let con = async {
+                    let tcp = TcpStream::connect(addr)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    let name = rustls::ServerName::try_from("stub.stub").unwrap();
+                    let tls = tokio_rustls::TlsConnector::from(tls_cli)
+                        .connect(name, tcp)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    connecting(tls.into(), local_cert, Tx2ConDir::Outgoing).await
+                };
+                match con.await {
+                    Ok(con) => Ok(con),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to establish outgoing connection (ws)");
+                        Err(err)
+                    }
+                }
+            })
+            .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    fn close(&self, _code: u32, _reason: &str) -> BoxFuture<'static, ()> {
+        let _ = self.0.share_mut(|i, c| {
+            tracing::warn!(
+                local_cert=?i.local_cert,
+                "CLOSING ENDPOINT"
+            );
+            *c = true;
+            // stops the listener, the pool closes the open connections
+            i.closed.notify_one();
+            Ok(())
+        });
+        async move {}.boxed()
+    }
+}
+
+/// Websocket endpoint backend bind adapter for kitsune tx2
+pub struct WsBackendAdapt {
+    local_cert: Tx2Cert,
+    tls_srv: Arc<rustls::ServerConfig>,
+    tls_cli: Arc<rustls::ClientConfig>,
+}
+
+impl WsBackendAdapt {
+    /// Construct a new websocket tx2 backend bind adapter
+    pub async fn new(config: WsConfig) -> KitsuneResult<AdapterFactory> {
+        let (tls, tuning_params) = config.split().await?;
+
+        let local_cert = tls.cert_digest.clone().into();
+
+        let (tls_srv, tls_cli) = gen_tls_configs(ALPN_KITSUNE_WS_0, &tls, tuning_params)?;
+
+        let out: AdapterFactory = Arc::new(Self {
+            local_cert,
+            tls_srv,
+            tls_cli,
+        });
+
+        Ok(out)
+    }
+}
+
+impl BindAdapt for WsBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let local_cert = self.local_cert.clone();
+        let tls_srv = self.tls_srv.clone();
+        let tls_cli = self.tls_cli.clone();
+        timeout
+            .mix(async move {
+                let addr = url_to_addr(url.as_url2(), crate::SCHEME)
+                    .await
+                    .map_err(KitsuneError::other)?;
+
+                let listener = TcpListener::bind(addr).await.map_err(KitsuneError::other)?;
+                let local_addr = listener.local_addr().map_err(KitsuneError::other)?;
+                let closed = Arc::new(tokio::sync::Notify::new());
+
+                let ep: Arc<dyn EndpointAdapt> = Arc::new(WsEndpointAdapt::new(
+                    local_addr,
+                    local_cert.clone(),
+                    tls_cli,
+                    closed.clone(),
+                ));
+                let con_recv: Box<dyn ConRecvAdapt> = Box::new(WsConRecvAdapt::new(
+                    listener,
+                    tls_srv,
+                    local_cert.clone(),
+                    closed,
+                    ep.clone(),
+                ));
+
+                let url = ep.local_addr()?;
+
+                tracing::info!(?local_cert, %url, "bound local endpoint (ws)");
+
+                Ok((ep, con_recv))
+            })
+            .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ws_tx2() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+
+        let config = WsConfig::default();
+        let factory = WsBackendAdapt::new(config).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-ws://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let config = WsConfig::default();
+        let factory = WsBackendAdapt::new(config).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-ws://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+        let cert1 = ep1.local_cert();
+        let cert2 = ep2.local_cert();
+
+        // - The second message is larger than a TLS record
+        let msgs = vec![b"hello".to_vec(), vec![7; 1024 * 1024]];
+        let expected = msgs.clone();
+
+        let rt = kitsune_p2p_types::metrics::metric_task(async move {
+            if let Some(mc) = con_recv2.next().await {
+                let (con, mut recv) = mc.await.unwrap();
+                assert_eq!(con.peer_cert(), cert1);
+                if let Some(mc) = recv.next().await {
+                    let mut c = mc.await.unwrap();
+                    let t = KitsuneTimeout::from_millis(5000);
+                    for expect in expected {
+                        let (msg_id, data) = c.read(t).await.unwrap();
+                        assert_eq!(msg_id.inner(), 42);
+                        assert_eq!(data.as_ref(), &expect[..]);
+                    }
+                    s_done.send(()).unwrap();
+                }
+            }
+            KitsuneResult::Ok(())
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+        assert_eq!(c.peer_cert(), cert2);
+
+        // - Two out channels on one connection share the websocket
+        for data in msgs {
+            let mut out = c.out_chan(t).await.unwrap();
+            let mut buf = PoolBuf::new();
+            buf.extend_from_slice(&data);
+            out.write(42.into(), buf, t).await.unwrap();
+        }
+
+        r_done.await.unwrap();
+
+        c.close(0, "").await;
+        assert!(c.is_closed());
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+
+        rt.await.unwrap().unwrap();
+    }
+}
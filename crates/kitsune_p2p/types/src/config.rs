@@ -104,6 +104,22 @@ pub mod tuning_params_struct {
         /// to this count mega bits per second. [Default: 0.1]
         gossip_historic_inbound_target_mbps: f64 = 0.1,
 
+        /// The gossip loop will attempt to rate-limit outbound
+        /// traffic for each gossip round (each remote node being
+        /// gossiped with) to this count mega bits per second.
+        /// This applies on top of the loop limits above.
+        /// 0.0 means rounds are only limited by the loop.
+        /// [Default: 0.0]
+        gossip_round_outbound_target_mbps: f64 = 0.0,
+
+        /// The gossip loop will attempt to rate-limit inbound
+        /// traffic for each gossip round (each remote node being
+        /// gossiped with) to this count mega bits per second.
+        /// This applies on top of the loop limits above.
+        /// 0.0 means rounds are only limited by the loop.
+        /// [Default: 0.0]
+        gossip_round_inbound_target_mbps: f64 = 0.0,
+
         /// How long should we hold off talking to a peer
         /// we've previously spoken successfully to.
         /// [Default: 1 minute]
@@ -134,6 +150,14 @@ pub mod tuning_params_struct {
         /// Should gossip dynamically resize storage arcs?
         gossip_dynamic_arcs: bool = true,
 
+        /// The smallest storage arc, as a fraction of the DHT,
+        /// that dynamic arc resizing will shrink to. [Default: 0.0]
+        gossip_dynamic_arc_min_coverage: f64 = 0.0,
+
+        /// The largest storage arc, as a fraction of the DHT,
+        /// that dynamic arc resizing will grow to. [Default: 1.0]
+        gossip_dynamic_arc_max_coverage: f64 = 1.0,
+
         /// Allow only the first agent to join the space to
         /// have a sized storage arc. [Default: false]
         /// This is an experimental feature that sets the first
@@ -158,6 +182,23 @@ pub mod tuning_params_struct {
         /// Default agent expires after milliseconds. [Default: 20 minutes]
         agent_info_expires_after_ms: u32 = 1000 * 60 * 20,
 
+        /// Refuse to store agent info that expires further in the
+        /// future than this, so a peer can't sign itself an agent info
+        /// that never expires. [Default: 1 hour]
+        agent_info_max_expires_after_ms: u32 = 1000 * 60 * 60,
+
+        /// How long should we wait between fetching random peers
+        /// from the bootstrap service, at most?
+        /// Fetches start a second apart when we join a space,
+        /// then back off to this. [Default: 5 minutes]
+        bootstrap_max_delay_ms: u32 = 1000 * 60 * 5,
+
+        /// Randomly vary the delays between publishing our agent info
+        /// and fetching peers from the bootstrap service by up to this
+        /// percent, so nodes started together don't all hit the service
+        /// at once. [Default: 10]
+        bootstrap_jitter_percent: u32 = 10,
+
         /// Tls in-memory session storage capacity. [Default: 512]
         tls_in_mem_session_storage: u32 = 512,
 
@@ -188,6 +229,23 @@ pub mod tuning_params_struct {
         /// [Default: 2]
         tx2_channel_count_per_connection: usize = 2,
 
+        /// tx2 weight of the control message priority lane.
+        /// While all channels of a connection are busy, free channels
+        /// are shared between waiting writes in proportion to the
+        /// weights of their lanes. Weights below 1 are treated as 1.
+        /// [Default: 8]
+        tx2_lane_weight_control: u32 = 8,
+
+        /// tx2 weight of the gossip priority lane.
+        /// See `tx2_lane_weight_control`.
+        /// [Default: 1]
+        tx2_lane_weight_gossip: u32 = 1,
+
+        /// tx2 weight of the user data priority lane.
+        /// See `tx2_lane_weight_control`.
+        /// [Default: 4]
+        tx2_lane_weight_user_data: u32 = 4,
+
         /// tx2 timeout used for passive background operations
         /// like reads / responds.
         /// [Default: 30 seconds]
@@ -199,6 +257,15 @@ pub mod tuning_params_struct {
         /// [Default: 200 ms]
         tx2_initial_connect_retry_delay_ms: usize = 200,
 
+        /// tx2 proxy hole punch timeout.
+        /// When set, a node that can only reach a peer through a proxy
+        /// asks the proxy to exchange their observed addresses, then
+        /// both nodes try to connect directly, waiting at most this long
+        /// before falling back to the proxy.
+        /// Both peers need this set for the punch to succeed.
+        /// [Default: 0 (disabled)]
+        tx2_hole_punch_timeout_ms: u32 = 0,
+
         /// if you would like to be able to use an external tool
         /// to debug the QUIC messages sent and received by kitsune
         /// you'll need the decryption keys.
@@ -217,6 +284,12 @@ pub mod tuning_params_struct {
 
         /// Don't publish ops, only rely on gossip. Useful for testing the efficacy of gossip.
         disable_publish: bool = false,
+
+        /// Write a log of every wire message sent and received to this file,
+        /// so networking bugs can be reported and replayed.
+        /// Payloads are logged as hashes, not contents.
+        /// The default of `""` does not capture anything.
+        wire_capture_path: String = "".to_string(),
     }
 
     impl KitsuneP2pTuningParams {
@@ -231,11 +304,29 @@ pub mod tuning_params_struct {
             std::time::Duration::from_secs(self.danger_gossip_recent_threshold_secs)
         }
 
+        /// The tx2 lane weights, indexed by `Tx2Lane::index()`.
+        pub fn tx2_lane_weights(&self) -> [u32; crate::tx2::tx2_pool::Tx2Lane::COUNT] {
+            [
+                self.tx2_lane_weight_control,
+                self.tx2_lane_weight_gossip,
+                self.tx2_lane_weight_user_data,
+            ]
+        }
+
         /// returns true if we should initialize a tls keylog
         /// based on the `SSLKEYLOGFILE` environment variable
         pub fn use_env_tls_keylog(&self) -> bool {
             self.danger_tls_keylog == "env_keylog"
         }
+
+        /// The file to capture wire messages to, if capturing is on.
+        pub fn wire_capture_path(&self) -> Option<std::path::PathBuf> {
+            if self.wire_capture_path.is_empty() {
+                None
+            } else {
+                Some(self.wire_capture_path.clone().into())
+            }
+        }
     }
 }
 
@@ -196,6 +196,32 @@ impl From<&Tx2Cert> for CertDigest {
     }
 }
 
+impl serde::Serialize for Tx2Cert {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(serde_bytes::Bytes::new(self.as_bytes()), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Tx2Cert {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes =
+            <serde_bytes::ByteBuf as serde::Deserialize>::deserialize(deserializer)?.into_vec();
+        if bytes.len() != 32 {
+            return Err(serde::de::Error::invalid_length(
+                bytes.len(),
+                &"a 32 byte cert digest",
+            ));
+        }
+        Ok(bytes.into())
+    }
+}
+
 use config::KitsuneP2pTuningParams;
 use std::sync::Arc;
 
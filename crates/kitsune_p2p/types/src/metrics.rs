@@ -16,6 +16,9 @@ static TX_BYTES_PER_SEC: AtomicU64 = AtomicU64::new(0);
 static RX_BYTES_PER_SEC: AtomicU64 = AtomicU64::new(0);
 static SENDS_PER_SEC: AtomicU64 = AtomicU64::new(0);
 static RECVS_PER_SEC: AtomicU64 = AtomicU64::new(0);
+static CONTROL_SENDS_PER_SEC: AtomicU64 = AtomicU64::new(0);
+static GOSSIP_SENDS_PER_SEC: AtomicU64 = AtomicU64::new(0);
+static USER_DATA_SENDS_PER_SEC: AtomicU64 = AtomicU64::new(0);
 
 macro_rules! _make_cntr {
     (
@@ -70,6 +73,24 @@ make_cntr!(
     metric_pull_raw_recv_count,
 );
 
+make_cntr!(
+    "count the raw number of control lane messages sent out by this process",
+    metric_push_raw_send_count_control,
+    metric_pull_raw_send_count_control,
+);
+
+make_cntr!(
+    "count the raw number of gossip lane messages sent out by this process",
+    metric_push_raw_send_count_gossip,
+    metric_pull_raw_send_count_gossip,
+);
+
+make_cntr!(
+    "count the raw number of user data lane messages sent out by this process",
+    metric_push_raw_send_count_user_data,
+    metric_pull_raw_send_count_user_data,
+);
+
 macro_rules! _make_avg {
     (
         $doc:literal,
@@ -133,6 +154,44 @@ make_avg!(
     metric_pull_api_req_res_elasped_ms,
 );
 
+make_avg!(
+    "time control lane messages waited for an outgoing channel",
+    metric_push_lane_wait_ms_control,
+    metric_pull_lane_wait_ms_control,
+);
+
+make_avg!(
+    "time gossip lane messages waited for an outgoing channel",
+    metric_push_lane_wait_ms_gossip,
+    metric_pull_lane_wait_ms_gossip,
+);
+
+make_avg!(
+    "time user data lane messages waited for an outgoing channel",
+    metric_push_lane_wait_ms_user_data,
+    metric_pull_lane_wait_ms_user_data,
+);
+
+/// Record a message sent in the given lane,
+/// and how long it waited for an outgoing channel.
+pub(crate) fn metric_push_lane_send(lane: crate::tx2::tx2_pool::Tx2Lane, wait_ms: u64) {
+    use crate::tx2::tx2_pool::Tx2Lane;
+    match lane {
+        Tx2Lane::Control => {
+            metric_push_raw_send_count_control(1);
+            metric_push_lane_wait_ms_control(wait_ms);
+        }
+        Tx2Lane::Gossip => {
+            metric_push_raw_send_count_gossip(1);
+            metric_push_lane_wait_ms_gossip(wait_ms);
+        }
+        Tx2Lane::UserData => {
+            metric_push_raw_send_count_user_data(1);
+            metric_push_lane_wait_ms_user_data(wait_ms);
+        }
+    }
+}
+
 /// Spawns a tokio task with given future/async block.
 /// Captures a new TaskCounter instance to track task count.
 pub fn metric_task<T, E, F>(f: F) -> tokio::task::JoinHandle<Result<T, E>>
@@ -173,6 +232,18 @@ pub struct MetricSysInfo {
     pub avg_pool_buf_release_size: u64,
     /// avg api req/res elapsed time (ms)
     pub avg_api_req_res_elapsed_ms: u64,
+    /// control lane message send count (5 sec avg).
+    pub control_sends_per_sec: u64,
+    /// gossip lane message send count (5 sec avg).
+    pub gossip_sends_per_sec: u64,
+    /// user data lane message send count (5 sec avg).
+    pub user_data_sends_per_sec: u64,
+    /// avg time control lane messages waited for a channel (ms)
+    pub avg_control_lane_wait_ms: u64,
+    /// avg time gossip lane messages waited for a channel (ms)
+    pub avg_gossip_lane_wait_ms: u64,
+    /// avg time user data lane messages waited for a channel (ms)
+    pub avg_user_data_lane_wait_ms: u64,
 }
 
 /// Initialize polling of system usage info
@@ -220,6 +291,9 @@ pub fn init_sys_info_poll() {
             let mut rx_avg = FiveAvg::new();
             let mut send_avg = FiveAvg::new();
             let mut recv_avg = FiveAvg::new();
+            let mut control_send_avg = FiveAvg::new();
+            let mut gossip_send_avg = FiveAvg::new();
+            let mut user_data_send_avg = FiveAvg::new();
 
             let mut p_count: usize = 0;
 
@@ -251,6 +325,13 @@ pub fn init_sys_info_poll() {
                 recv_avg.push(metric_pull_raw_recv_count());
                 RECVS_PER_SEC.store(recv_avg.avg(), Ordering::Relaxed);
 
+                control_send_avg.push(metric_pull_raw_send_count_control());
+                CONTROL_SENDS_PER_SEC.store(control_send_avg.avg(), Ordering::Relaxed);
+                gossip_send_avg.push(metric_pull_raw_send_count_gossip());
+                GOSSIP_SENDS_PER_SEC.store(gossip_send_avg.avg(), Ordering::Relaxed);
+                user_data_send_avg.push(metric_pull_raw_send_count_user_data());
+                USER_DATA_SENDS_PER_SEC.store(user_data_send_avg.avg(), Ordering::Relaxed);
+
                 p_count += 1;
                 if p_count == 15 {
                     p_count = 0;
@@ -280,6 +361,12 @@ pub fn get_sys_info() -> MetricSysInfo {
         tokio_task_count: TASK_COUNT.load(Ordering::Relaxed),
         avg_pool_buf_release_size: metric_pull_pool_buf_release_size(),
         avg_api_req_res_elapsed_ms: metric_pull_api_req_res_elasped_ms(),
+        control_sends_per_sec: CONTROL_SENDS_PER_SEC.load(Ordering::Relaxed),
+        gossip_sends_per_sec: GOSSIP_SENDS_PER_SEC.load(Ordering::Relaxed),
+        user_data_sends_per_sec: USER_DATA_SENDS_PER_SEC.load(Ordering::Relaxed),
+        avg_control_lane_wait_ms: metric_pull_lane_wait_ms_control(),
+        avg_gossip_lane_wait_ms: metric_pull_lane_wait_ms_gossip(),
+        avg_user_data_lane_wait_ms: metric_pull_lane_wait_ms_user_data(),
     }
 }
 
@@ -35,6 +35,47 @@ impl TlsConfig {
     }
 }
 
+/// Tls and tuning configuration shared by the tx2 network backends.
+#[non_exhaustive]
+#[derive(Default)]
+pub struct TlsBackendConfig {
+    /// Tls config
+    /// Default: None = ephemeral.
+    pub tls: Option<TlsConfig>,
+
+    /// Tuning Params
+    /// Default: None = default.
+    pub tuning_params: Option<KitsuneP2pTuningParams>,
+}
+
+impl TlsBackendConfig {
+    /// into inner contents with default application
+    pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
+        let TlsBackendConfig { tls, tuning_params } = self;
+
+        let tls = match tls {
+            None => TlsConfig::new_ephemeral().await?,
+            Some(tls) => tls,
+        };
+
+        let tuning_params = tuning_params.unwrap_or_default();
+
+        Ok((tls, tuning_params))
+    }
+}
+
+/// The 32 byte blake2b hash of a der encoded peer certificate,
+/// by which tx2 backends identify their peers.
+pub fn blake2b_32(data: &[u8]) -> Vec<u8> {
+    blake2b_simd::Params::new()
+        .hash_length(32)
+        .to_state()
+        .update(data)
+        .finalize()
+        .as_bytes()
+        .to_vec()
+}
+
 /// Allow only these cipher suites for kitsune Tls.
 static CIPHER_SUITES: &[rustls::SupportedCipherSuite] = &[
     rustls::cipher_suite::TLS13_CHACHA20_POLY1305_SHA256,
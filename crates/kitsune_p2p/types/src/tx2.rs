@@ -10,6 +10,8 @@ pub mod tx2_adapter;
 
 pub mod tx2_api;
 
+pub mod tx2_multi_adapter;
+
 pub mod tx2_pool;
 
 pub mod tx2_pool_promote;
@@ -19,6 +19,35 @@ impl Default for Uniq {
     }
 }
 
+/// Resolve a "scheme://host:port" url to a socket address for binding
+/// or connecting, preferring ipv4 addresses.
+pub async fn url_to_addr(url: &url2::Url2, scheme: &str) -> KitsuneResult<std::net::SocketAddr> {
+    if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
+        return Err(format!(
+            "invalid input. got: '{}', expected: '{}://host:port'",
+            url, scheme
+        )
+        .into());
+    }
+
+    let rendered = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap());
+
+    if let Ok(iter) = tokio::net::lookup_host(rendered.clone()).await {
+        let mut fallback = None;
+        for addr in iter {
+            if addr.is_ipv4() {
+                return Ok(addr);
+            }
+            fallback = fallback.or(Some(addr));
+        }
+        if let Some(addr) = fallback {
+            return Ok(addr);
+        }
+    }
+
+    Err(format!("could not parse '{}', as 'host:port'", rendered).into())
+}
+
 /// The directionality of this connection.
 /// Did we establish it? Was it an incoming connection?
 #[derive(Debug, Clone, Copy)]
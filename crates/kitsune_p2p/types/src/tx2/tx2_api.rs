@@ -292,7 +292,10 @@ impl<C: Codec + 'static + Send + Unpin> Tx2ConHnd<C> {
         async move {
             let msg_id = MsgId::new_notify();
             let len = data.len();
-            this.con.write(msg_id, data, timeout).await?;
+            this.metrics
+                .capture(Tx2Direction::Outbound, &this.peer_cert(), dbg_name, &data);
+            let lane = this.metrics.write_lane(dbg_name);
+            this.con.write_lane(msg_id, data, lane, timeout).await?;
             this.metrics.write_len(dbg_name, len);
 
             let peer_cert = this.peer_cert();
@@ -338,8 +341,11 @@ impl<C: Codec + 'static + Send + Unpin> Tx2ConHnd<C> {
                 peer_cert,
             )?;
 
+            this.metrics
+                .capture(Tx2Direction::Outbound, &this.peer_cert(), dbg_name, &data);
+            let lane = this.metrics.write_lane(dbg_name);
             this.con
-                .write(MsgId::new(msg_id).as_req(), data, timeout)
+                .write_lane(MsgId::new(msg_id).as_req(), data, lane, timeout)
                 .await?;
 
             this.metrics.write_len(dbg_name, len);
@@ -524,6 +530,8 @@ pub struct Tx2Respond<C: Codec + 'static + Send + Unpin> {
     req_byte_count: usize,
     con: ConHnd,
     msg_id: u64,
+    lane: Tx2Lane,
+    metrics: Arc<Tx2ApiMetrics>,
     _p: std::marker::PhantomData<C>,
 }
 
@@ -541,8 +549,10 @@ impl<C: Codec + 'static + Send + Unpin> Tx2Respond<C> {
         req_byte_count: usize,
         con: ConHnd,
         msg_id: u64,
+        metrics: Arc<Tx2ApiMetrics>,
     ) -> Self {
         let time = tokio::time::Instant::now();
+        let lane = metrics.write_lane(dbg_name);
         Self {
             local_cert,
             peer_cert,
@@ -551,6 +561,8 @@ impl<C: Codec + 'static + Send + Unpin> Tx2Respond<C> {
             req_byte_count,
             con,
             msg_id,
+            lane,
+            metrics,
             _p: std::marker::PhantomData,
         }
     }
@@ -569,6 +581,8 @@ impl<C: Codec + 'static + Send + Unpin> Tx2Respond<C> {
             req_byte_count,
             con,
             msg_id,
+            lane,
+            metrics,
             ..
         } = self;
         async move {
@@ -578,6 +592,7 @@ impl<C: Codec + 'static + Send + Unpin> Tx2Respond<C> {
             let elapsed_s = time.elapsed().as_secs_f64();
             let resp_dbg_name = data.variant_type();
             let resp_byte_count = buf.len();
+            metrics.capture(Tx2Direction::Outbound, &peer_cert, resp_dbg_name, &buf);
             tracing::trace!(
                 %dbg_name,
                 %req_byte_count,
@@ -589,7 +604,8 @@ impl<C: Codec + 'static + Send + Unpin> Tx2Respond<C> {
                 "(api) res",
             );
 
-            con.write(MsgId::new(msg_id).as_res(), buf, timeout).await
+            con.write_lane(MsgId::new(msg_id).as_res(), buf, lane, timeout)
+                .await
         }
     }
 }
@@ -728,6 +744,8 @@ impl<C: Codec + 'static + Send + Unpin> Stream for Tx2Ep<C> {
                             Ok(c) => c,
                         };
                         let dbg_name = c.variant_type();
+                        self.2
+                            .capture(Tx2Direction::Inbound, &peer_cert, dbg_name, &data);
                         match msg_id.get_type() {
                             MsgIdType::Notify => Tx2EpEvent::IncomingNotify(Tx2EpIncomingNotify {
                                 con: Tx2ConHnd::new(
@@ -757,6 +775,7 @@ impl<C: Codec + 'static + Send + Unpin> Stream for Tx2Ep<C> {
                                     len,
                                     con,
                                     msg_id.as_id(),
+                                    self.2.clone(),
                                 ),
                             }),
                             MsgIdType::Res => {
@@ -813,11 +832,25 @@ impl<C: Codec + 'static + Send + Unpin> Tx2Ep<C> {
     }
 }
 
+/// Which way a message passed to a [`Tx2ApiMetrics::set_capture`]
+/// callback was travelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Tx2Direction {
+    /// The message was received from the peer.
+    Inbound,
+    /// The message is being sent to the peer.
+    Outbound,
+}
+
 type WriteLenCb = Box<dyn Fn(&'static str, usize) + 'static + Send + Sync>;
+type WriteLaneCb = Box<dyn Fn(&'static str) -> Tx2Lane + 'static + Send + Sync>;
+type CaptureCb = Box<dyn Fn(Tx2Direction, &Tx2Cert, &'static str, &[u8]) + 'static + Send + Sync>;
 
 /// Metrics callback manager to be injected into the endpoint
 pub struct Tx2ApiMetrics {
     write_len: Option<WriteLenCb>,
+    write_lane: Option<WriteLaneCb>,
+    capture: Option<CaptureCb>,
 }
 
 impl Default for Tx2ApiMetrics {
@@ -829,7 +862,11 @@ impl Default for Tx2ApiMetrics {
 impl Tx2ApiMetrics {
     /// Construct a new default Tx2ApiMetrics with no set callbacks
     pub fn new() -> Self {
-        Self { write_len: None }
+        Self {
+            write_len: None,
+            write_lane: None,
+            capture: None,
+        }
     }
 
     /// This callback will be invoked when we successfully write data
@@ -843,11 +880,48 @@ impl Tx2ApiMetrics {
         self
     }
 
+    /// This callback picks the priority lane for outgoing messages
+    /// by their codec variant. Responses are sent in the lane of their request.
+    /// Without it all messages are sent in the default [`Tx2Lane`].
+    pub fn set_write_lane<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&'static str) -> Tx2Lane + 'static + Send + Sync,
+    {
+        let f: WriteLaneCb = Box::new(f);
+        self.write_lane = Some(f);
+        self
+    }
+
+    /// This callback is handed the encoded bytes of every message
+    /// received, and of every message just before it is written,
+    /// along with the peer and the codec variant.
+    pub fn set_capture<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Tx2Direction, &Tx2Cert, &'static str, &[u8]) + 'static + Send + Sync,
+    {
+        let f: CaptureCb = Box::new(f);
+        self.capture = Some(f);
+        self
+    }
+
     fn write_len(&self, d: &'static str, l: usize) {
         if let Some(cb) = &self.write_len {
             cb(d, l)
         }
     }
+
+    fn write_lane(&self, d: &'static str) -> Tx2Lane {
+        match &self.write_lane {
+            Some(cb) => cb(d),
+            None => Tx2Lane::default(),
+        }
+    }
+
+    fn capture(&self, dir: Tx2Direction, peer: &Tx2Cert, d: &'static str, data: &[u8]) {
+        if let Some(cb) = &self.capture {
+            cb(dir, peer, d, data)
+        }
+    }
 }
 
 /// Construct a new Tx2EpFactory instance from a pool EpFactory
@@ -0,0 +1,175 @@
+//! Combine tx2 backend adapters for different url schemes into one endpoint.
+
+use crate::tx2::tx2_adapter::*;
+use crate::tx2::tx2_utils::*;
+use crate::*;
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{BoxStream, StreamExt};
+
+/// Combine tx2 backend adapters, each paired with the url scheme it serves,
+/// into one adapter. Binding binds every sub adapter: the first at the given
+/// url, the rest at the same host and port with their own scheme.
+/// Connections are made by the sub adapter for the scheme of the url,
+/// and incoming connections from all of them arrive on one stream.
+/// All the sub adapters must use the same tls certificate.
+///
+/// Panics if there are no sub adapters.
+pub fn tx2_multi_adapter(sub_adapters: Vec<(&'static str, AdapterFactory)>) -> AdapterFactory {
+    let local_cert = sub_adapters
+        .first()
+        .expect("tx2_multi_adapter requires a sub adapter")
+        .1
+        .local_cert();
+    Arc::new(MultiBackendAdapt {
+        sub_adapters: Arc::new(sub_adapters),
+        local_cert,
+    })
+}
+
+// -- private -- //
+
+/// Swap the scheme of a "scheme://host:port" url.
+fn with_scheme(url: &TxUrl, scheme: &str, port: Option<u16>) -> KitsuneResult<TxUrl> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| KitsuneError::from(format!("invalid bind url: {}", url)))?;
+    let port = port
+        .or_else(|| url.port())
+        .ok_or_else(|| KitsuneError::from(format!("invalid bind url: {}", url)))?;
+    Ok(format!("{}://{}:{}", scheme, host, port).into())
+}
+
+struct MultiConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl futures::stream::Stream for MultiConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for MultiConRecvAdapt {}
+
+struct MultiEndpointAdapt {
+    sub_eps: Vec<(&'static str, Arc<dyn EndpointAdapt>)>,
+    uniq: Uniq,
+    local_cert: Tx2Cert,
+}
+
+impl EndpointAdapt for MultiEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "tx2_multi",
+            "sub_eps": self
+                .sub_eps
+                .iter()
+                .map(|(_, ep)| ep.debug())
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.uniq
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        // the first sub endpoint is the primary address
+        self.sub_eps[0].1.local_addr()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        match self.sub_eps.iter().find(|(s, _)| *s == url.scheme()) {
+            Some((_, ep)) => ep.connect(url, timeout),
+            None => async move { Err(format!("no transport for url: {}", url).into()) }.boxed(),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sub_eps.iter().all(|(_, ep)| ep.is_closed())
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        let all = self
+            .sub_eps
+            .iter()
+            .map(|(_, ep)| ep.close(code, reason))
+            .collect::<Vec<_>>();
+        async move {
+            futures::future::join_all(all).await;
+        }
+        .boxed()
+    }
+}
+
+struct MultiBackendAdapt {
+    sub_adapters: Arc<Vec<(&'static str, AdapterFactory)>>,
+    local_cert: Tx2Cert,
+}
+
+impl BindAdapt for MultiBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let sub_adapters = self.sub_adapters.clone();
+        let local_cert = self.local_cert.clone();
+        timeout
+            .mix(async move {
+                let mut sub_eps = Vec::new();
+                let mut con_recvs = Vec::new();
+                let mut port = None;
+                for (scheme, sub_adapter) in sub_adapters.iter() {
+                    let sub_url = with_scheme(&url, scheme, port)?;
+                    let (ep, con_recv) = sub_adapter.bind(sub_url, timeout).await?;
+                    // bind the rest on the port the first one was given
+                    if port.is_none() {
+                        port = ep.local_addr()?.port();
+                    }
+                    sub_eps.push((*scheme, ep));
+                    con_recvs.push(con_recv);
+                }
+                let ep: Arc<dyn EndpointAdapt> = Arc::new(MultiEndpointAdapt {
+                    sub_eps,
+                    uniq: Uniq::default(),
+                    local_cert,
+                });
+                let con_recv: Box<dyn ConRecvAdapt> = Box::new(MultiConRecvAdapt(
+                    futures::stream::select_all(con_recvs).boxed(),
+                ));
+                Ok((ep, con_recv))
+            })
+            .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_scheme() {
+        let url: TxUrl = "kitsune-quic://127.0.0.1:0".into();
+        assert_eq!(
+            "kitsune-ws://127.0.0.1:5778",
+            with_scheme(&url, "kitsune-ws", Some(5778))
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            "kitsune-ws://127.0.0.1:0",
+            with_scheme(&url, "kitsune-ws", None).unwrap().as_str()
+        );
+    }
+}
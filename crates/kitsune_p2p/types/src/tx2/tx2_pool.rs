@@ -7,6 +7,35 @@ use crate::*;
 use futures::future::BoxFuture;
 use futures::stream::Stream;
 
+/// Priority lane of an outgoing message.
+///
+/// A connection only has a few outgoing channels. When they are all busy,
+/// writes wait for the next free channel, and that channel goes to
+/// one of the lanes with waiting writes according to the lane weights
+/// (see the `tx2_lane_weight_*` tuning params). This keeps small control
+/// messages from getting stuck behind large gossip payloads on slow links.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tx2Lane {
+    /// Small messages that other work is waiting on,
+    /// e.g. peer queries and failure responses.
+    Control,
+    /// Gossip and op data.
+    Gossip,
+    /// Everything else, e.g. remote calls.
+    #[default]
+    UserData,
+}
+
+impl Tx2Lane {
+    /// The number of lanes.
+    pub const COUNT: usize = 3;
+
+    /// The index of this lane, in `0..Tx2Lane::COUNT`.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
 /// Trait representing a connection handle.
 pub trait AsConHnd: std::fmt::Debug + 'static + Send + Sync + Unpin {
     /// Get the opaque Uniq identifier for this connection.
@@ -27,13 +56,24 @@ pub trait AsConHnd: std::fmt::Debug + 'static + Send + Sync + Unpin {
     /// Close this connection.
     fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()>;
 
-    /// Write data to this connection.
-    fn write(
+    /// Write data to this connection in the given priority lane.
+    fn write_lane(
         &self,
         msg_id: MsgId,
         data: PoolBuf,
+        lane: Tx2Lane,
         timeout: KitsuneTimeout,
     ) -> BoxFuture<'static, KitsuneResult<()>>;
+
+    /// Write data to this connection in the default [`Tx2Lane`].
+    fn write(
+        &self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'static, KitsuneResult<()>> {
+        self.write_lane(msg_id, data, Tx2Lane::default(), timeout)
+    }
 }
 
 /// Trait object connection handle
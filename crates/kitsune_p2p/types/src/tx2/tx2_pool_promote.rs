@@ -40,7 +40,7 @@ struct ConItemInner {
     inner: Share<PromoteEpInner>,
     con: Arc<dyn ConAdapt>,
     url: TxUrl,
-    writer_bucket: ResourceBucket<WriteChan>,
+    writer_bucket: LaneBucket<WriteChan>,
     write_chan_limit: Arc<Semaphore>,
 }
 
@@ -78,7 +78,7 @@ async fn in_chan_recv_logic(
     tuning_params: KitsuneP2pTuningParams,
     url: TxUrl,
     con_item: ConItem,
-    writer_bucket: ResourceBucket<WriteChan>,
+    writer_bucket: LaneBucket<WriteChan>,
     write_chan_limit: Arc<Semaphore>,
     logic_hnd: LogicChanHandle<EpEvent>,
     in_chan_recv: Box<dyn InChanRecvAdapt>,
@@ -275,10 +275,11 @@ impl AsConHnd for ConItem {
         .boxed()
     }
 
-    fn write(
+    fn write_lane(
         &self,
         msg_id: MsgId,
         data: PoolBuf,
+        lane: Tx2Lane,
         timeout: KitsuneTimeout,
     ) -> BoxFuture<'static, KitsuneResult<()>> {
         let this = self.clone();
@@ -291,11 +292,13 @@ impl AsConHnd for ConItem {
                     Ok((
                         i.local_cert.clone(),
                         i.con.peer_cert(),
-                        i.writer_bucket.acquire(Some(timeout)),
+                        i.writer_bucket.acquire(lane, Some(timeout)),
                     ))
                 })?;
 
+                let wait_start = tokio::time::Instant::now();
                 let mut writer = writer_fut.await?;
+                let wait_ms = wait_start.elapsed().as_millis() as u64;
 
                 writer.writer.write(msg_id, data, timeout).await?;
 
@@ -308,10 +311,13 @@ impl AsConHnd for ConItem {
                     ?local_cert,
                     ?peer_cert,
                     byte_count = %len,
+                    ?lane,
+                    %wait_ms,
                     "transmitted bytes",
                 );
 
                 crate::metrics::metric_push_raw_send_count(1);
+                crate::metrics::metric_push_lane_send(lane, wait_ms);
 
                 res
             };
@@ -351,7 +357,7 @@ impl ConItem {
         let dir = con.dir();
         let peer_cert = con.peer_cert();
 
-        let writer_bucket = ResourceBucket::new();
+        let writer_bucket = LaneBucket::new(tuning_params.tx2_lane_weights());
         let write_chan_limit = Arc::new(Semaphore::new(
             tuning_params.tx2_channel_count_per_connection,
         ));
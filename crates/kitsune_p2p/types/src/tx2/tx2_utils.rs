@@ -4,6 +4,9 @@
 mod active;
 pub use active::*;
 
+mod lane_bucket;
+pub use lane_bucket::*;
+
 mod latency;
 pub use latency::*;
 
@@ -0,0 +1,194 @@
+use crate::tx2::tx2_pool::Tx2Lane;
+use crate::tx2::tx2_utils::*;
+use crate::*;
+use std::collections::VecDeque;
+
+type Waiter<T> = tokio::sync::oneshot::Sender<T>;
+
+struct Inner<T: 'static + Send> {
+    bucket: Vec<T>,
+    waiters: [VecDeque<Waiter<T>>; Tx2Lane::COUNT],
+    weights: [i64; Tx2Lane::COUNT],
+    credit: [i64; Tx2Lane::COUNT],
+}
+
+impl<T: 'static + Send> Inner<T> {
+    /// Smooth weighted round robin across the lanes that have waiters.
+    /// A lane with weight `w` out of a total of `W` is picked
+    /// `w` times out of every `W` while all lanes are busy,
+    /// so no lane is ever starved.
+    fn next_lane(&mut self) -> Option<usize> {
+        let mut total = 0;
+        let mut best: Option<usize> = None;
+        for lane in 0..Tx2Lane::COUNT {
+            let waiters = &mut self.waiters[lane];
+            while waiters.front().map(|w| w.is_closed()).unwrap_or(false) {
+                // this waiter timed out or was dropped
+                waiters.pop_front();
+            }
+            if waiters.is_empty() {
+                continue;
+            }
+            self.credit[lane] += self.weights[lane];
+            total += self.weights[lane];
+            match best {
+                Some(b) if self.credit[b] >= self.credit[lane] => (),
+                _ => best = Some(lane),
+            }
+        }
+        if let Some(b) = best {
+            self.credit[b] -= total;
+        }
+        best
+    }
+
+    fn release(&mut self, mut t: T) {
+        while let Some(lane) = self.next_lane() {
+            let waiter = self.waiters[lane].pop_front().unwrap();
+            match waiter.send(t) {
+                Ok(()) => return,
+                Err(back) => t = back,
+            }
+        }
+        self.bucket.push(t);
+    }
+}
+
+/// Like [`ResourceBucket`], but when there are more acquirers than
+/// resources the released resources are handed out to the waiting
+/// acquirers by [`Tx2Lane`], according to the weight of each lane.
+pub struct LaneBucket<T: 'static + Send>(Arc<Share<Inner<T>>>);
+
+impl<T: 'static + Send> Clone for LaneBucket<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: 'static + Send> LaneBucket<T> {
+    /// Create a new lane bucket with weights for
+    /// `[Control, Gossip, UserData]`. Weights below 1 are treated as 1.
+    pub fn new(weights: [u32; Tx2Lane::COUNT]) -> Self {
+        let mut w = [1; Tx2Lane::COUNT];
+        for (w, weight) in w.iter_mut().zip(weights.iter()) {
+            *w = std::cmp::max(1, *weight as i64);
+        }
+        Self(Arc::new(Share::new(Inner {
+            bucket: Vec::new(),
+            waiters: Default::default(),
+            weights: w,
+            credit: [0; Tx2Lane::COUNT],
+        })))
+    }
+
+    /// Add a resource to the bucket.
+    /// Could be a new resource, or a previously acquired resource.
+    pub fn release(&self, t: T) {
+        let _ = self.0.share_mut(move |i, _| {
+            i.release(t);
+            Ok(())
+        });
+    }
+
+    /// Acquire a resource from the bucket, waiting in the given lane
+    /// if none are available.
+    pub fn acquire(
+        &self,
+        lane: Tx2Lane,
+        timeout: Option<KitsuneTimeout>,
+    ) -> impl std::future::Future<Output = KitsuneResult<T>> + 'static + Send {
+        let this = self.clone();
+        async move {
+            let mut r = match this.0.share_mut(|i, _| {
+                if !i.bucket.is_empty() {
+                    return Ok(Err(i.bucket.remove(0)));
+                }
+                let (s, r) = tokio::sync::oneshot::channel();
+                i.waiters[lane.index()].push_back(s);
+                Ok(Ok(r))
+            })? {
+                Err(t) => return Ok(t),
+                Ok(r) => r,
+            };
+
+            let res = {
+                let recv = async {
+                    (&mut r)
+                        .await
+                        .map_err(|_| KitsuneError::from(KitsuneErrorKind::Closed))
+                };
+                match timeout {
+                    Some(timeout) => timeout.mix(recv).await,
+                    None => recv.await,
+                }
+            };
+
+            if res.is_err() {
+                // don't lose a resource that was sent just as we gave up
+                r.close();
+                if let Ok(t) = r.try_recv() {
+                    this.release(t);
+                }
+            }
+
+            res
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_lane_bucket_timeout() {
+        let t = Some(KitsuneTimeout::from_millis(10));
+        let bucket = <LaneBucket<&'static str>>::new([1, 1, 1]);
+        assert!(bucket.acquire(Tx2Lane::Gossip, t).await.is_err());
+        bucket.release("1");
+        assert_eq!("1", bucket.acquire(Tx2Lane::Gossip, t).await.unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_lane_bucket_weights() {
+        let bucket = <LaneBucket<usize>>::new([4, 1, 1]);
+
+        let mut waiting = Vec::new();
+        for lane in [Tx2Lane::Control, Tx2Lane::Gossip, Tx2Lane::UserData] {
+            for _ in 0..6 {
+                waiting.push((lane, metric_task(bucket.acquire(lane, None))));
+            }
+        }
+
+        // let all the acquirers queue up
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        for n in 0..6 {
+            bucket.release(n);
+        }
+
+        let mut got = Vec::new();
+        for (lane, task) in waiting {
+            let wait = std::time::Duration::from_millis(20);
+            if let Ok(res) = tokio::time::timeout(wait, task).await {
+                got.push((res.unwrap().unwrap(), lane));
+            }
+        }
+        got.sort_unstable();
+        let lanes = got.into_iter().map(|(_, l)| l).collect::<Vec<_>>();
+
+        // control gets 4 of every 6 while all lanes are waiting,
+        // but gossip and user data are not starved
+        assert_eq!(
+            vec![
+                Tx2Lane::Control,
+                Tx2Lane::Control,
+                Tx2Lane::Gossip,
+                Tx2Lane::Control,
+                Tx2Lane::UserData,
+                Tx2Lane::Control,
+            ],
+            lanes,
+        );
+    }
+}
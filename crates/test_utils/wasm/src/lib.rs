@@ -6,63 +6,110 @@ use strum_macros::EnumIter;
 
 const WASM_WORKSPACE_TARGET: &str = "wasm_workspace/target";
 
-#[derive(EnumIter, Clone, Copy)]
-pub enum TestIntegrityWasm {
-    IntegrityZome,
+/// Define an enum of test wasms from a list of variants and wasm names.
+///
+/// Each wasm is built from the `test_wasm_{name}` crate in the wasm workspace,
+/// so adding a test wasm is a single line here.
+macro_rules! test_wasms {
+    ($(#[$meta:meta])* pub enum $enum:ident { $($variant:ident => $name:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(EnumIter, Clone, Copy)]
+        pub enum $enum {
+            $($variant,)*
+        }
+
+        impl $enum {
+            /// The name of the wasm, which is also the name of its zome.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            /// Every test wasm.
+            pub fn all() -> Vec<$enum> {
+                <$enum as strum::IntoEnumIterator>::iter().collect()
+            }
+        }
+
+        impl From<$enum> for ZomeName {
+            fn from(test_wasm: $enum) -> ZomeName {
+                ZomeName::from(test_wasm.name())
+            }
+        }
+
+        impl From<$enum> for PathBuf {
+            fn from(test_wasm: $enum) -> Self {
+                PathBuf::from(format!(
+                    "wasm32-unknown-unknown/release/test_wasm_{}.wasm",
+                    test_wasm.name()
+                ))
+            }
+        }
+    };
+}
+
+test_wasms! {
+    pub enum TestIntegrityWasm {
+        IntegrityZome => "integrity_zome",
+    }
 }
 
-#[derive(EnumIter, Clone, Copy)]
-pub enum TestCoordinatorWasm {
-    CoordinatorZome,
-    CoordinatorZomeUpdate,
+test_wasms! {
+    pub enum TestCoordinatorWasm {
+        CoordinatorZome => "coordinator_zome",
+        CoordinatorZomeUpdate => "coordinator_zome_update",
+    }
 }
 
-#[derive(EnumIter, Clone, Copy)]
-pub enum TestWasm {
-    AgentInfo,
-    Anchor,
-    Bench,
-    Capability,
-    CounterSigning,
-    Create,
-    Crd,
-    Crud,
-    Debug,
-    EntryDefs,
-    EmitSignal,
-    HashEntry,
-    Foo,
-    GenesisSelfCheckInvalid,
-    GenesisSelfCheckValid,
-    HashPath,
-    HdkExtern,
-    InitFail,
-    InitPass,
-    Link,
-    MigrateAgentFail,
-    MigrateAgentPass,
-    MultipleCalls,
-    MustGet,
-    PostCommitSuccess,
-    PostCommitVolley,
-    Query,
-    RandomBytes,
-    Schedule,
-    XSalsa20Poly1305,
-    SerRegression,
-    Sign,
-    SysTime,
-    TheIncredibleHalt,
-    Update,
-    Validate,
-    ValidateLink,
-    ValidateInvalid,
-    ValidateCreateLinkInvalid,
-    ValidateValid,
-    ValidateCreateLinkValid,
-    WhoAmI,
-    ZomeInfo,
+test_wasms! {
+    pub enum TestWasm {
+        AgentInfo => "agent_info",
+        Anchor => "anchor",
+        Bench => "bench",
+        Capability => "capability",
+        CounterSigning => "countersigning",
+        Create => "create_entry",
+        Crd => "crd",
+        Crud => "crud",
+        Debug => "debug",
+        EntryDefs => "entry_defs",
+        EmitSignal => "emit_signal",
+        HashEntry => "hash_entry",
+        Foo => "foo",
+        GenesisSelfCheckInvalid => "genesis_self_check_invalid",
+        GenesisSelfCheckValid => "genesis_self_check_valid",
+        HashPath => "hash_path",
+        HdkExtern => "hdk_extern",
+        InitFail => "init_fail",
+        InitPass => "init_pass",
+        Link => "link",
+        MigrateAgentFail => "migrate_agent_fail",
+        MigrateAgentPass => "migrate_agent_pass",
+        MultipleCalls => "multiple_calls",
+        MustGet => "must_get",
+        PostCommitSuccess => "post_commit_success",
+        PostCommitVolley => "post_commit_volley",
+        Query => "query",
+        RandomBytes => "random_bytes",
+        Schedule => "schedule",
+        XSalsa20Poly1305 => "x_salsa20_poly1305",
+        SerRegression => "ser_regression",
+        Sign => "sign",
+        SysTime => "sys_time",
+        TheIncredibleHalt => "the_incredible_halt",
+        Update => "update_entry",
+        Validate => "validate",
+        ValidateLink => "validate_link",
+        ValidateInvalid => "validate_invalid",
+        ValidateCreateLinkInvalid => "validate_link_add_invalid",
+        ValidateValid => "validate_valid",
+        ValidateCreateLinkValid => "validate_link_add_valid",
+        WhoAmI => "whoami",
+        ZomeInfo => "zome_info",
+    }
 }
+
 /// Utility type for combining a test wasm's coordinator
 /// zome with it's integrity zome.
 pub struct TestWasmPair<I, C = I> {
@@ -72,6 +119,53 @@ pub struct TestWasmPair<I, C = I> {
 
 pub type TestZomes = TestWasmPair<IntegrityZome, CoordinatorZome>;
 
+/// The zomes and wasms of several test wasms, grouped to go into one test DNA.
+/// The zomes keep the order they were added in.
+#[derive(Default)]
+pub struct TestWasmGroup {
+    pub integrity: Vec<IntegrityZome>,
+    pub coordinator: Vec<CoordinatorZome>,
+    pub wasms: Vec<DnaWasm>,
+}
+
+impl TestWasmGroup {
+    /// Add the integrity and coordinator zomes of a test wasm.
+    pub fn with_wasm(mut self, test_wasm: TestWasm) -> Self {
+        let TestZomes {
+            integrity,
+            coordinator,
+        } = test_wasm.into();
+        self.integrity.push(integrity);
+        self.coordinator.push(coordinator);
+        self.wasms.extend(Vec::<DnaWasm>::from(test_wasm));
+        self
+    }
+
+    /// Add an integrity zome on its own.
+    pub fn with_integrity(mut self, test_wasm: TestIntegrityWasm) -> Self {
+        self.integrity.push(test_wasm.into());
+        self.wasms.push(test_wasm.into());
+        self
+    }
+
+    /// Add a coordinator zome on its own.
+    pub fn with_coordinator(mut self, test_wasm: TestCoordinatorWasm) -> Self {
+        self.coordinator.push(test_wasm.into());
+        self.wasms.push(test_wasm.into());
+        self
+    }
+}
+
+impl From<Vec<TestWasm>> for TestWasmGroup {
+    fn from(test_wasms: Vec<TestWasm>) -> Self {
+        test_wasms
+            .into_iter()
+            .fold(Self::default(), |group, test_wasm| {
+                group.with_wasm(test_wasm)
+            })
+    }
+}
+
 impl TestWasm {
     /// Get the [`ZomeName`] for the integrity zome.
     pub fn integrity_zome_name(self) -> ZomeName {
@@ -95,73 +189,6 @@ impl TestWasm {
     }
 }
 
-impl From<TestIntegrityWasm> for ZomeName {
-    fn from(test_wasm: TestIntegrityWasm) -> ZomeName {
-        ZomeName::from(match test_wasm {
-            TestIntegrityWasm::IntegrityZome => "integrity_zome",
-        })
-    }
-}
-
-impl From<TestCoordinatorWasm> for ZomeName {
-    fn from(test_wasm: TestCoordinatorWasm) -> ZomeName {
-        ZomeName::from(match test_wasm {
-            TestCoordinatorWasm::CoordinatorZome => "coordinator_zome",
-            TestCoordinatorWasm::CoordinatorZomeUpdate => "coordinator_zome_update",
-        })
-    }
-}
-
-impl From<TestWasm> for ZomeName {
-    fn from(test_wasm: TestWasm) -> ZomeName {
-        ZomeName::from(match test_wasm {
-            TestWasm::AgentInfo => "agent_info",
-            TestWasm::Anchor => "anchor",
-            TestWasm::Bench => "bench",
-            TestWasm::Capability => "capability",
-            TestWasm::CounterSigning => "countersigning",
-            TestWasm::Create => "create_entry",
-            TestWasm::Crd => "crd",
-            TestWasm::Crud => "crud",
-            TestWasm::Debug => "debug",
-            TestWasm::EntryDefs => "entry_defs",
-            TestWasm::EmitSignal => "emit_signal",
-            TestWasm::HashEntry => "hash_entry",
-            TestWasm::Foo => "foo",
-            TestWasm::GenesisSelfCheckInvalid => "genesis_self_check_invalid",
-            TestWasm::GenesisSelfCheckValid => "genesis_self_check_valid",
-            TestWasm::HashPath => "hash_path",
-            TestWasm::HdkExtern => "hdk_extern",
-            TestWasm::InitFail => "init_fail",
-            TestWasm::InitPass => "init_pass",
-            TestWasm::Link => "link",
-            TestWasm::MigrateAgentFail => "migrate_agent_fail",
-            TestWasm::MigrateAgentPass => "migrate_agent_pass",
-            TestWasm::MultipleCalls => "multiple_calls",
-            TestWasm::MustGet => "must_get",
-            TestWasm::PostCommitSuccess => "post_commit_success",
-            TestWasm::PostCommitVolley => "post_commit_volley",
-            TestWasm::Query => "query",
-            TestWasm::RandomBytes => "random_bytes",
-            TestWasm::Schedule => "schedule",
-            TestWasm::XSalsa20Poly1305 => "x_salsa20_poly1305",
-            TestWasm::SerRegression => "ser_regression",
-            TestWasm::Sign => "sign",
-            TestWasm::SysTime => "sys_time",
-            TestWasm::TheIncredibleHalt => "the_incredible_halt",
-            TestWasm::Update => "update_entry",
-            TestWasm::Validate => "validate",
-            TestWasm::ValidateLink => "validate_link",
-            TestWasm::ValidateInvalid => "validate_invalid",
-            TestWasm::ValidateCreateLinkInvalid => "validate_link_add_invalid",
-            TestWasm::ValidateValid => "validate_valid",
-            TestWasm::ValidateCreateLinkValid => "validate_link_add_valid",
-            TestWasm::WhoAmI => "whoami",
-            TestWasm::ZomeInfo => "zome_info",
-        })
-    }
-}
-
 impl From<TestWasm> for TestWasmPair<ZomeName> {
     fn from(test_wasm: TestWasm) -> Self {
         let coordinator: ZomeName = test_wasm.into();
@@ -173,86 +200,6 @@ impl From<TestWasm> for TestWasmPair<ZomeName> {
     }
 }
 
-impl From<TestWasm> for PathBuf {
-    fn from(test_wasm: TestWasm) -> Self {
-        PathBuf::from(match test_wasm {
-            TestWasm::AgentInfo => "wasm32-unknown-unknown/release/test_wasm_agent_info.wasm",
-            TestWasm::Anchor => "wasm32-unknown-unknown/release/test_wasm_anchor.wasm",
-            TestWasm::Bench => "wasm32-unknown-unknown/release/test_wasm_bench.wasm",
-            TestWasm::Capability => "wasm32-unknown-unknown/release/test_wasm_capability.wasm",
-            TestWasm::CounterSigning => {
-                "wasm32-unknown-unknown/release/test_wasm_countersigning.wasm"
-            }
-            TestWasm::Create => "wasm32-unknown-unknown/release/test_wasm_create_entry.wasm",
-            TestWasm::Crd => "wasm32-unknown-unknown/release/test_wasm_crd.wasm",
-            TestWasm::Crud => "wasm32-unknown-unknown/release/test_wasm_crud.wasm",
-            TestWasm::Debug => "wasm32-unknown-unknown/release/test_wasm_debug.wasm",
-            TestWasm::EntryDefs => "wasm32-unknown-unknown/release/test_wasm_entry_defs.wasm",
-            TestWasm::EmitSignal => "wasm32-unknown-unknown/release/test_wasm_emit_signal.wasm",
-            TestWasm::HashEntry => "wasm32-unknown-unknown/release/test_wasm_hash_entry.wasm",
-            TestWasm::Foo => "wasm32-unknown-unknown/release/test_wasm_foo.wasm",
-            TestWasm::GenesisSelfCheckInvalid => {
-                "wasm32-unknown-unknown/release/test_wasm_genesis_self_check_invalid.wasm"
-            }
-            TestWasm::GenesisSelfCheckValid => {
-                "wasm32-unknown-unknown/release/test_wasm_genesis_self_check_valid.wasm"
-            }
-            TestWasm::HashPath => "wasm32-unknown-unknown/release/test_wasm_hash_path.wasm",
-            TestWasm::HdkExtern => "wasm32-unknown-unknown/release/test_wasm_hdk_extern.wasm",
-            TestWasm::InitFail => "wasm32-unknown-unknown/release/test_wasm_init_fail.wasm",
-            TestWasm::InitPass => "wasm32-unknown-unknown/release/test_wasm_init_pass.wasm",
-            TestWasm::Link => "wasm32-unknown-unknown/release/test_wasm_link.wasm",
-            TestWasm::MigrateAgentFail => {
-                "wasm32-unknown-unknown/release/test_wasm_migrate_agent_fail.wasm"
-            }
-            TestWasm::MigrateAgentPass => {
-                "wasm32-unknown-unknown/release/test_wasm_migrate_agent_pass.wasm"
-            }
-            TestWasm::MultipleCalls => {
-                "wasm32-unknown-unknown/release/test_wasm_multiple_calls.wasm"
-            }
-            TestWasm::MustGet => "wasm32-unknown-unknown/release/test_wasm_must_get.wasm",
-            TestWasm::PostCommitSuccess => {
-                "wasm32-unknown-unknown/release/test_wasm_post_commit_success.wasm"
-            }
-            TestWasm::PostCommitVolley => {
-                "wasm32-unknown-unknown/release/test_wasm_post_commit_volley.wasm"
-            }
-            TestWasm::Query => "wasm32-unknown-unknown/release/test_wasm_query.wasm",
-            TestWasm::RandomBytes => "wasm32-unknown-unknown/release/test_wasm_random_bytes.wasm",
-            TestWasm::Schedule => "wasm32-unknown-unknown/release/test_wasm_schedule.wasm",
-            TestWasm::XSalsa20Poly1305 => {
-                "wasm32-unknown-unknown/release/test_wasm_x_salsa20_poly1305.wasm"
-            }
-            TestWasm::SerRegression => {
-                "wasm32-unknown-unknown/release/test_wasm_ser_regression.wasm"
-            }
-            TestWasm::Sign => "wasm32-unknown-unknown/release/test_wasm_sign.wasm",
-            TestWasm::SysTime => "wasm32-unknown-unknown/release/test_wasm_sys_time.wasm",
-            TestWasm::TheIncredibleHalt => {
-                "wasm32-unknown-unknown/release/test_wasm_the_incredible_halt.wasm"
-            }
-            TestWasm::Update => "wasm32-unknown-unknown/release/test_wasm_update_entry.wasm",
-            TestWasm::Validate => "wasm32-unknown-unknown/release/test_wasm_validate.wasm",
-            TestWasm::ValidateLink => "wasm32-unknown-unknown/release/test_wasm_validate_link.wasm",
-            TestWasm::ValidateInvalid => {
-                "wasm32-unknown-unknown/release/test_wasm_validate_invalid.wasm"
-            }
-            TestWasm::ValidateCreateLinkInvalid => {
-                "wasm32-unknown-unknown/release/test_wasm_validate_link_add_invalid.wasm"
-            }
-            TestWasm::ValidateValid => {
-                "wasm32-unknown-unknown/release/test_wasm_validate_valid.wasm"
-            }
-            TestWasm::ValidateCreateLinkValid => {
-                "wasm32-unknown-unknown/release/test_wasm_validate_link_add_valid.wasm"
-            }
-            TestWasm::WhoAmI => "wasm32-unknown-unknown/release/test_wasm_whoami.wasm",
-            TestWasm::ZomeInfo => "wasm32-unknown-unknown/release/test_wasm_zome_info.wasm",
-        })
-    }
-}
-
 impl From<TestWasm> for DnaWasm {
     fn from(t: TestWasm) -> Self {
         DnaWasm::from(get_code(PathBuf::from(t)))
@@ -317,29 +264,6 @@ impl From<TestWasmPair<PathBuf>> for TestWasmPair<DnaWasm> {
     }
 }
 
-impl From<TestIntegrityWasm> for PathBuf {
-    fn from(t: TestIntegrityWasm) -> Self {
-        PathBuf::from(match t {
-            TestIntegrityWasm::IntegrityZome => {
-                "wasm32-unknown-unknown/release/test_wasm_integrity_zome.wasm"
-            }
-        })
-    }
-}
-
-impl From<TestCoordinatorWasm> for PathBuf {
-    fn from(t: TestCoordinatorWasm) -> Self {
-        PathBuf::from(match t {
-            TestCoordinatorWasm::CoordinatorZome => {
-                "wasm32-unknown-unknown/release/test_wasm_coordinator_zome.wasm"
-            }
-            TestCoordinatorWasm::CoordinatorZomeUpdate => {
-                "wasm32-unknown-unknown/release/test_wasm_coordinator_zome_update.wasm"
-            }
-        })
-    }
-}
-
 fn get_code(path: PathBuf) -> Vec<u8> {
     let path = match option_env!("HC_TEST_WASM_DIR") {
         Some(dir) => PathBuf::from(dir).join(path),
@@ -448,5 +372,6 @@ async fn path_to_def(path: PathBuf, dependencies: Vec<ZomeName>) -> ZomeDef {
     ZomeDef::Wasm(WasmZome {
         wasm_hash,
         dependencies,
+        init_after: Default::default(),
     })
 }
@@ -0,0 +1,26 @@
+use hdk::prelude::*;
+
+#[hdk_entry_helper]
+pub struct Thing(String);
+
+#[hdk_extern]
+fn whoami(_: ()) -> ExternResult<AgentInfo> {
+    debug!("whoami called");
+    agent_info()
+}
+
+#[hdk_extern]
+fn hash_thing(thing: Thing) -> ExternResult<EntryHash> {
+    debug!(?thing, "hashing");
+    hash_entry(&thing)
+}
+
+#[hdk_extern]
+fn now(_: ()) -> ExternResult<Timestamp> {
+    sys_time()
+}
+
+#[hdk_extern]
+fn random(len: u32) -> ExternResult<Bytes> {
+    random_bytes(len)
+}
@@ -51,6 +51,11 @@ fn get_entry(_: ()) -> ExternResult<Option<Record>> {
     get(hash_entry(&post())?, GetOptions::content())
 }
 
+#[hdk_extern]
+fn get_entry_outcome(_: ()) -> ExternResult<GetOutcome<Record>> {
+    get_outcome(hash_entry(&post())?, GetOptions::content())
+}
+
 #[hdk_extern]
 fn get_entry_twice(_: ()) -> ExternResult<Vec<Option<Record>>> {
     HDK.with(|h| {
@@ -68,6 +68,11 @@ fn remote_remote_call_info(agent: AgentPubKey) -> ExternResult<CallInfo> {
     }
 }
 
+#[hdk_extern]
+fn app_info(_: ()) -> ExternResult<AppInfo> {
+    hdk::prelude::app_info()
+}
+
 #[hdk_extern]
 fn dna_info(_: ()) -> ExternResult<DnaInfo> {
     hdk::prelude::dna_info()